@@ -7,6 +7,7 @@ use rand::rngs::OsRng;
 use serde::{Deserialize, Serialize};
 use std::fs;
 use std::path::PathBuf;
+use crate::ui::say;
 
 #[derive(Debug, Serialize, Deserialize)]
 struct AccountFile {
@@ -61,12 +62,12 @@ impl AccountManager {
         let keypair = Keypair::from_bytes(&key_bytes)
             .context("Failed to create keypair from private key")?;
 
-        println!("✓ Loaded account: {}", self.username());
+        say!("✓ Loaded account: {}", self.username());
         Ok(keypair)
     }
 
     fn create(&mut self) -> Result<Keypair> {
-        println!("Creating new account...");
+        say!("Creating new account...");
 
         // Get username from filename
         let filename = self.path.file_stem()
@@ -99,8 +100,8 @@ impl AccountManager {
         fs::write(&self.path, json)
             .with_context(|| format!("Failed to write account file: {}", self.path.display()))?;
 
-        println!("✓ Created new account: {}", self.username());
-        println!("✓ Saved to: {}", self.path.display());
+        say!("✓ Created new account: {}", self.username());
+        say!("✓ Saved to: {}", self.path.display());
 
         Ok(keypair)
     }
@@ -2,11 +2,15 @@
 
 use anyhow::{Context, Result};
 use colored::Colorize;
-use spaceway_core::{Client, SpaceId, ChannelId, ThreadId, SpaceMembershipMode, SpaceVisibility};
+use spaceway_core::{Client, SpaceId, ChannelId, ThreadId, UserId, SpaceMembershipMode, SpaceVisibility};
+use spaceway_core::types::{InviteUri, INVITE_URI_PREFIX};
+use spaceway_core::export::ExportFormat;
+use spaceway_core::network::ConnectionType;
 use std::sync::Arc;
 use tokio::sync::Mutex;
 use tokio::task::JoinHandle;
-use crate::ui;
+use crate::ui::{self, say};
+use serde_json::{json, Map, Value};
 
 pub struct CommandHandler {
     client: Arc<Mutex<Client>>,
@@ -14,6 +18,10 @@ pub struct CommandHandler {
     current_space: Option<SpaceId>,
     current_channel: Option<ChannelId>,
     current_thread: Option<ThreadId>,
+    /// Lines of a message being composed with `msg`
+    draft: Option<Vec<String>>,
+    /// Fields of the current command's result, emitted in JSON mode
+    result: Map<String, Value>,
 }
 
 impl CommandHandler {
@@ -24,6 +32,8 @@ impl CommandHandler {
             current_space: None,
             current_channel: None,
             current_thread: None,
+            draft: None,
+            result: Map::new(),
         }
     }
 
@@ -43,7 +53,72 @@ impl CommandHandler {
         Ok(handle)
     }
 
+    /// Flush the client's storage to disk before exiting
+    pub async fn shutdown(&self) {
+        let client = self.client.lock().await;
+        if let Err(e) = client.flush_storage() {
+            eprintln!("Failed to flush storage: {}", e);
+        }
+    }
+
+    /// Whether a `msg` draft is collecting lines
+    pub fn is_composing(&self) -> bool {
+        self.draft.is_some()
+    }
+
+    /// Add a raw input line to the `msg` draft; a line with just `.` sends it
+    pub async fn compose_line(&mut self, line: &str) -> Result<()> {
+        if line.trim() == "." {
+            return self.handle_command("msg .").await;
+        }
+        if let Some(draft) = self.draft.as_mut() {
+            draft.push(line.to_string());
+        }
+        Ok(())
+    }
+
+    /// Drop the `msg` draft without sending it
+    pub fn cancel_compose(&mut self) {
+        if self.draft.take().is_some() {
+            ui::print_info("Message discarded");
+        }
+    }
+
     pub async fn handle_command(&mut self, input: &str) -> Result<()> {
+        if !ui::is_json() {
+            return self.dispatch(input).await;
+        }
+
+        // JSON mode: every command yields exactly one result object, and
+        // errors are reported in it rather than returned
+        self.result.clear();
+        ui::take_error();
+        let outcome = self.dispatch(input).await;
+        let error = match outcome {
+            Ok(()) => ui::take_error(),
+            Err(e) => Some(format!("{:#}", e)),
+        };
+
+        let command = input.split_whitespace().next().unwrap_or_default();
+        let mut output = json!({
+            "command": command,
+            "input": input,
+            "success": error.is_none(),
+        });
+        match error {
+            Some(error) => output["error"] = Value::String(error),
+            None => output["data"] = Value::Object(std::mem::take(&mut self.result)),
+        }
+        ui::print_json(&output);
+        Ok(())
+    }
+
+    /// Add a field to the current command's JSON result
+    fn record(&mut self, key: &str, value: impl Into<Value>) {
+        self.result.insert(key.to_string(), value.into());
+    }
+
+    async fn dispatch(&mut self, input: &str) -> Result<()> {
         let parts: Vec<&str> = input.split_whitespace().collect();
         if parts.is_empty() {
             return Ok(());
@@ -54,7 +129,12 @@ impl CommandHandler {
             "version" | "about" => self.cmd_version(),
             "context" => self.cmd_context(),
             "network" => self.cmd_network().await,
+            "status" => self.cmd_status().await,
+            "sync-state" => self.cmd_sync_state(&parts[1..]).await,
+            "peers" => self.cmd_peers().await,
             "connect" => self.cmd_connect(&parts[1..]).await,
+            "relays" => self.cmd_relays().await,
+            "relay" => self.cmd_relay(&parts[1..]).await,
             "spaces" => self.cmd_spaces().await,
             "space" => self.cmd_space(&parts[1..]).await,
             "channels" => self.cmd_channels().await,
@@ -63,6 +143,7 @@ impl CommandHandler {
             "thread" => self.cmd_thread(&parts[1..]).await,
             "messages" => self.cmd_messages().await,
             "send" => self.cmd_send(&parts[1..].join(" ")).await,
+            "msg" => self.cmd_msg(&parts[1..]).await,
             "invite" => self.cmd_invite(&parts[1..]).await,
             "join" => self.cmd_join(&parts[1..]).await,
             "members" => self.cmd_members().await,
@@ -70,8 +151,11 @@ impl CommandHandler {
             "kick" | "remove" => self.cmd_kick(&parts[1..]).await,
             "keypackage" => self.cmd_keypackage(&parts[1..]).await,
             "upload" => self.cmd_upload(&parts[1..]).await,
+            "download" => self.cmd_download(&parts[1..]).await,
+            "export" => self.cmd_export(&parts[1..]).await,
+            "import" => self.cmd_import(&parts[1..]).await,
             "refresh" => self.cmd_refresh().await,
-            "help" => self.cmd_help(),
+            "help" => self.cmd_help(&parts[1..]),
             _ => {
                 ui::print_error(&format!("Unknown command: {}", parts[0]));
                 ui::print_info("Type 'help' for available commands");
@@ -80,82 +164,142 @@ impl CommandHandler {
         }
     }
 
-    fn cmd_version(&self) -> Result<()> {
-        println!();
-        println!("{}", "=".repeat(60).bright_blue());
-        println!("{}", format!("  {}", spaceway_core::version_string()).bright_cyan().bold());
-        println!("{}", "  Privacy-First Decentralized Communication".bright_white());
-        println!("{}", "=".repeat(60).bright_blue());
-        println!();
-        println!("{} {}", "Protocol Version:".bright_green(), spaceway_core::PROTOCOL_VERSION);
-        println!("{} {}", "Build:".bright_green(), spaceway_core::version::BUILD_PROFILE);
-        println!();
-        println!("{}", "For more information:".bright_blue());
-        println!("  GitHub: {}", "https://github.com/descord/descord".bright_cyan());
-        println!("  Docs:   {}", "https://descord.org/docs".bright_cyan());
-        println!();
-        Ok(())
-    }
-
-    fn cmd_help(&self) -> Result<()> {
-        println!();
-        println!("{}", "Available Commands:".bright_cyan().bold());
-        println!();
-        println!("{}", "  Information:".bright_yellow());
-        println!("    {} - Show current user info", "whoami".bright_green());
-        println!("    {} - Show version and build info", "version".bright_green());
-        println!("    {} - Show current context (space/channel/thread)", "context".bright_green());
-        println!("    {} - Show help", "help".bright_green());
-        println!();
-        println!("{}", "  Network:".bright_yellow());
-        println!("    {} - Show network status and peer ID", "network".bright_green());
-        println!("    {} <multiaddr> - Connect to a peer", "connect".bright_green());
-        println!();
-        println!("{}", "  Spaces:".bright_yellow());
-        println!("    {} - List all spaces", "spaces".bright_green());
-        println!("    {} create <name> [--mode lightweight|mls] - Create a new space", "space".bright_green());
-        println!("    {} list - List all spaces (same as 'spaces')", "space".bright_green());
-        println!("    {} <id> - Switch to a space by ID", "space".bright_green());
-        println!("    {} <space_id> <code> - Join space with invite", "join".bright_green());
-        println!("    {} - Create invite for current space", "invite".bright_green());
-        println!("    {} - List members in current space", "members".bright_green());
-        println!("    {} <user_id> - Remove member from current space", "kick".bright_green());
-        println!();
-        println!("{}", "  MLS Encryption:".bright_yellow());
-        println!("    {} - Publish KeyPackages to DHT for MLS", "keypackage publish".bright_green());
-        println!("    {} <user_id> - Add member to MLS encryption group", "member add".bright_green());
-        println!();
-        println!("{}", "  Channels & Threads:".bright_yellow());
-        println!("    {} - List channels in current space", "channels".bright_green());
-        println!("    {} <name> - Create or switch to channel", "channel".bright_green());
-        println!("    {} - List threads in current channel", "threads".bright_green());
-        println!("    {} <title> - Create or switch to thread", "thread".bright_green());
-        println!();
-        println!("{}", "  Messages:".bright_yellow());
-        println!("    {} - Show messages in current thread", "messages".bright_green());
-        println!("    {} <text> - Send message to current thread", "send".bright_green());
-        println!();
-        println!("{}", "  Files:".bright_yellow());
-        println!("    {} <path> - Upload file to DHT", "upload".bright_green());
-        println!("    {} - Refresh local state from network", "refresh".bright_green());
-        println!();
-        Ok(())
-    }
-
-    async fn cmd_whoami(&self) -> Result<()> {
+    fn cmd_version(&mut self) -> Result<()> {
+        self.record("version", spaceway_core::version::VERSION);
+        self.record("protocol_version", spaceway_core::PROTOCOL_VERSION);
+        self.record("build", spaceway_core::version::BUILD_PROFILE);
+        say!();
+        say!("{}", "=".repeat(60).bright_blue());
+        say!("{}", format!("  {}", spaceway_core::version_string()).bright_cyan().bold());
+        say!("{}", "  Privacy-First Decentralized Communication".bright_white());
+        say!("{}", "=".repeat(60).bright_blue());
+        say!();
+        say!("{} {}", "Protocol Version:".bright_green(), spaceway_core::PROTOCOL_VERSION);
+        say!("{} {}", "Build:".bright_green(), spaceway_core::version::BUILD_PROFILE);
+        say!();
+        say!("{}", "For more information:".bright_blue());
+        say!("  GitHub: {}", "https://github.com/descord/descord".bright_cyan());
+        say!("  Docs:   {}", "https://descord.org/docs".bright_cyan());
+        say!();
+        Ok(())
+    }
+
+    fn cmd_help(&mut self, args: &[&str]) -> Result<()> {
+        if let Some(name) = args.first() {
+            return self.cmd_help_command(name);
+        }
+
+        say!();
+        say!("{}", "Available Commands:".bright_cyan().bold());
+        say!();
+        say!("{}", "  Information:".bright_yellow());
+        say!("    {} - Show current user info", "whoami".bright_green());
+        say!("    {} - Show version and build info", "version".bright_green());
+        say!("    {} - Show current context (space/channel/thread)", "context".bright_green());
+        say!("    {} - Show help", "help".bright_green());
+        say!("    {} <command> - Show usage of one command (Tab completes commands and IDs)", "help".bright_green());
+        say!();
+        say!("{}", "  Network:".bright_yellow());
+        say!("    {} - Show network status and peer ID", "network".bright_green());
+        say!("    {} <multiaddr> - Connect to a peer", "connect".bright_green());
+        say!("    {} - List connected peers (direct/relayed)", "peers".bright_green());
+        say!("    {} - Show MLS epoch, members and connection health", "status".bright_green());
+        say!("    {} <space_id> - Show a space's vector clock and how connected peers differ", "sync-state".bright_green());
+        say!("    {} - List relays discovered on the DHT", "relays".bright_green());
+        say!("    {} connect <multiaddr> - Reserve a slot on a relay", "relay".bright_green());
+        say!("    {} auto - Connect to the best discovered relay", "relay".bright_green());
+        say!("    {} rotate <secs>|stop - Start or stop relay rotation", "relay".bright_green());
+        say!("    {} status - Show the current relay and circuit addresses", "relay".bright_green());
+        say!();
+        say!("{}", "  Spaces:".bright_yellow());
+        say!("    {} - List all spaces", "spaces".bright_green());
+        say!("    {} create <name> [--mode lightweight|mls] - Create a new space", "space".bright_green());
+        say!("    {} list - List all spaces (same as 'spaces')", "space".bright_green());
+        say!("    {} <id> - Switch to a space by ID", "space".bright_green());
+        say!("    {} <space_id> <code> - Join space with invite", "join".bright_green());
+        say!("    {} <descord://join/...> - Join space with invite link", "join".bright_green());
+        say!("    {} - Create invite for current space", "invite".bright_green());
+        say!("    {} - List members in current space", "members".bright_green());
+        say!("    {} <user_id> - Remove member from current space", "kick".bright_green());
+        say!();
+        say!("{}", "  MLS Encryption:".bright_yellow());
+        say!("    {} - Publish KeyPackages to DHT for MLS", "keypackage publish".bright_green());
+        say!("    {} <user_id> - Add member to MLS encryption group", "member add".bright_green());
+        say!();
+        say!("{}", "  Channels & Threads:".bright_yellow());
+        say!("    {} - List channels in current space", "channels".bright_green());
+        say!("    {} <name> - Create or switch to channel", "channel".bright_green());
+        say!("    {} - List threads in current channel", "threads".bright_green());
+        say!("    {} <title> - Create or switch to thread", "thread".bright_green());
+        say!();
+        say!("{}", "  Messages:".bright_yellow());
+        say!("    {} - Show messages in current thread", "messages".bright_green());
+        say!("    {} <text> - Send message to current thread", "send".bright_green());
+        say!("    {} - Compose a multi-line message, ending with a line of just '.'", "msg".bright_green());
+        say!("    {} --editor - Compose a message in $EDITOR", "msg".bright_green());
+        say!();
+        say!("{}", "  Files:".bright_yellow());
+        say!("    {} <path> [--space <id>] - Upload file to DHT", "upload".bright_green());
+        say!("    {} <hash> <out_path> [--space <id>] - Download file by hash", "download".bright_green());
+        say!("    {} - Refresh local state from network", "refresh".bright_green());
+        say!();
+        say!("{}", "  Backup:".bright_yellow());
+        say!("    {} <space_id> <file> [--format json|markdown] - Export space transcript", "export".bright_green());
+        say!("    {} <file> - Import a JSON transcript as a new space", "import".bright_green());
+        say!();
+        Ok(())
+    }
+
+    /// Usage of a single command, for `help <command>`
+    fn cmd_help_command(&mut self, name: &str) -> Result<()> {
+        let command = crate::completion::usage(name)
+            .with_context(|| format!("Unknown command: {}. Type 'help' for available commands", name))?;
+
+        say!();
+        for (form, description) in command.forms {
+            say!("  {:<50} {}", form.bright_green(), description);
+        }
+        say!();
+
+        self.record("command", command.name);
+        self.record("usage", command.forms.iter().map(|(form, _)| *form).collect::<Vec<_>>());
+        Ok(())
+    }
+
+    /// Space IDs and the current space's channel IDs, for tab completion
+    pub async fn completion_ids(&self) -> crate::completion::CompletionIds {
+        let client = self.client.lock().await;
+        let spaces = client.list_spaces().await
+            .iter()
+            .map(|space| hex::encode(space.id.0))
+            .collect();
+        let channels = match self.current_space {
+            Some(space_id) => client.list_channels(&space_id).await
+                .iter()
+                .map(|channel| hex::encode(channel.id.0))
+                .collect(),
+            None => Vec::new(),
+        };
+        crate::completion::CompletionIds { spaces, channels }
+    }
+
+    async fn cmd_whoami(&mut self) -> Result<()> {
         let user_id = {
             let client = self.client.lock().await;
             client.user_id()
         };
-        println!();
-        println!("{} {}", "Username:".bright_green(), self.username.bright_cyan());
-        println!("{} {}", "User ID:".bright_green(), hex::encode(user_id.as_bytes()));
-        println!("{} {}", "User ID (short):".bright_green(), hex::encode(&user_id.as_bytes()[..8]));
-        println!();
+        say!();
+        say!("{} {}", "Username:".bright_green(), self.username.bright_cyan());
+        say!("{} {}", "User ID:".bright_green(), hex::encode(user_id.as_bytes()));
+        say!("{} {}", "User ID (short):".bright_green(), hex::encode(&user_id.as_bytes()[..8]));
+        say!();
+
+        self.record("username", self.username.clone());
+        self.record("user_id", hex::encode(user_id.as_bytes()));
         Ok(())
     }
 
-    async fn cmd_network(&self) -> Result<()> {
+    async fn cmd_network(&mut self) -> Result<()> {
         let (peer_id, listeners) = {
             let client = self.client.lock().await;
             let peer_id = client.network_peer_id().await;
@@ -163,39 +307,191 @@ impl CommandHandler {
             (peer_id, listeners)
         };
 
-        println!();
-        println!("{}", "Network Status:".bright_cyan().bold());
-        println!("  {}: {}", "Peer ID".bright_green(), peer_id.bright_yellow());
+        say!();
+        say!("{}", "Network Status:".bright_cyan().bold());
+        say!("  {}: {}", "Peer ID".bright_green(), peer_id.bright_yellow());
         
         if listeners.is_empty() {
-            println!("  {}: {}", "Listening".bright_green(), "Not listening (no incoming connections)".yellow());
+            say!("  {}: {}", "Listening".bright_green(), "Not listening (no incoming connections)".yellow());
         } else {
-            println!("  {}: {}", "Listening on".bright_green(), listeners.len());
+            say!("  {}: {}", "Listening on".bright_green(), listeners.len());
             for addr in &listeners {
-                println!("    {}", addr.bright_yellow());
+                say!("    {}", addr.bright_yellow());
             }
         }
 
+        self.record("peer_id", peer_id.clone());
+        self.record("listen_addrs", listeners.clone());
+
         if listeners.is_empty() {
-            println!();
-            println!("{}", "💡 Tip: To accept connections, restart with --port <PORT>".bright_blue());
-            println!("  {}", "Example: descord --account alice.key --port 9001".bright_black());
+            say!();
+            say!("{}", "💡 Tip: To accept connections, restart with --port <PORT>".bright_blue());
+            say!("  {}", "Example: descord --account alice.key --port 9001".bright_black());
         } else {
-            println!();
-            println!("{}", "📋 Share this multiaddr for others to connect:".bright_blue());
+            say!();
+            say!("{}", "📋 Share this multiaddr for others to connect:".bright_blue());
             if let Some(addr) = listeners.first() {
-                println!("  {}", format!("{}/p2p/{}", addr, peer_id).bright_yellow());
+                say!("  {}", format!("{}/p2p/{}", addr, peer_id).bright_yellow());
+            }
+        }
+
+        say!();
+        Ok(())
+    }
+
+    async fn cmd_status(&mut self) -> Result<()> {
+        let (spaces, peers, relay) = {
+            let client = self.client.lock().await;
+            let mut spaces = Vec::new();
+            for space in client.list_spaces().await {
+                let epoch = client.space_epoch(&space.id).await;
+                let mls_members = client.mls_members(&space.id).await.len();
+                spaces.push((space, epoch, mls_members));
+            }
+            (spaces, client.connected_peer_info().await, client.current_relay().await)
+        };
+
+        say!();
+        say!("{} ({}):", "Spaces".bright_cyan().bold(), spaces.len());
+        for (space, epoch, mls_members) in &spaces {
+            say!("  {} - {}", space.id.short().bright_yellow(), space.name);
+            match epoch {
+                Some(epoch) => say!("    {}: {}", "Epoch".bright_green(), epoch.0),
+                None => say!("    {}: {}", "Epoch".bright_green(), "none (no MLS group)".yellow()),
+            }
+            say!("    {}: {}", "MLS members".bright_green(), mls_members);
+        }
+        say!();
+
+        let relayed = peers.iter().filter(|peer| peer.relayed).count();
+        say!("{}: {} ({} direct, {} relayed)",
+            "Connected peers".bright_green(),
+            peers.len(),
+            peers.len() - relayed,
+            relayed
+        );
+        match &relay {
+            Some(relay) => say!("{}: {}", "Relay".bright_green(), relay.peer_id),
+            None => say!("{}: {}", "Relay".bright_green(), "none".yellow()),
+        }
+        say!();
+
+        let space_entries: Vec<Value> = spaces.iter()
+            .map(|(space, epoch, mls_members)| json!({
+                "space_id": hex::encode(space.id.0),
+                "name": space.name,
+                "epoch": epoch.map(|e| e.0),
+                "mls_members": mls_members,
+            }))
+            .collect();
+        self.record("spaces", space_entries);
+        self.record("connected_peers", peers.len());
+        self.record("relayed_peers", relayed);
+        self.record("relay", relay.map(|r| r.peer_id.to_string()));
+        Ok(())
+    }
+
+    async fn cmd_sync_state(&mut self, args: &[&str]) -> Result<()> {
+        let Some(prefix) = args.first() else {
+            ui::print_error("Usage: sync-state <space_id>");
+            return Ok(());
+        };
+        let space_id = self.resolve_space(prefix).await?;
+
+        let (clock, peer_clocks) = {
+            let client = self.client.lock().await;
+            let connected: Vec<String> = client.connected_peer_info().await
+                .iter()
+                .map(|peer| peer.peer_id.to_string())
+                .collect();
+            let mut peer_clocks: Vec<_> = client.peer_space_clocks(&space_id).await
+                .into_iter()
+                .filter(|(peer, _)| connected.contains(peer))
+                .collect();
+            peer_clocks.sort_by(|a, b| a.0.cmp(&b.0));
+            (client.space_vector_clock(&space_id)?, peer_clocks)
+        };
+
+        let mut entries: Vec<_> = clock.clocks.iter().collect();
+        entries.sort();
+
+        say!();
+        say!("{} {}", "Vector clock for".bright_cyan().bold(), space_id.short().bright_yellow());
+        for (author, count) in &entries {
+            say!("  {} {} ops", author[..16].bright_yellow(), count);
+        }
+
+        let mut peers = Map::new();
+        for (peer, theirs) in &peer_clocks {
+            let diff = clock.diff(theirs);
+            say!();
+            if diff.is_empty() {
+                say!("  {} {}", peer.bright_yellow(), "in sync".bright_green());
+            } else {
+                say!("  {} {}", peer.bright_yellow(), "differs:".yellow());
+                for (author, ours, theirs) in &diff {
+                    say!("    {} ours {} theirs {}", author[..16].bright_black(), ours, theirs);
+                }
             }
+            peers.insert(peer.clone(), json!(diff.iter()
+                .map(|(author, ours, theirs)| json!({ "author": author, "ours": ours, "theirs": theirs }))
+                .collect::<Vec<_>>()));
+        }
+        if peer_clocks.is_empty() {
+            say!();
+            ui::print_info("No clocks from connected peers yet");
         }
+        say!();
+
+        self.record("space_id", hex::encode(space_id.0));
+        self.record("clock", json!(clock.clocks));
+        self.record("peer_diffs", peers);
+        Ok(())
+    }
+
+    async fn cmd_peers(&mut self) -> Result<()> {
+        let peers = {
+            let client = self.client.lock().await;
+            client.peer_details().await
+        };
 
-        println!();
+        say!();
+        if peers.is_empty() {
+            ui::print_info("No connected peers. Connect with: connect <multiaddr>");
+        } else {
+            say!("{} ({}):", "Connected Peers".bright_cyan().bold(), peers.len());
+            for peer in &peers {
+                let kind = match peer.connection_type {
+                    ConnectionType::Direct => "direct".bright_green(),
+                    ConnectionType::Relayed => "relayed".yellow(),
+                };
+                say!("  {} [{}] up {}s", peer.peer_id.to_string().bright_yellow(), kind, peer.uptime().as_secs());
+                for addr in &peer.addrs {
+                    say!("    {}", addr.to_string().bright_black());
+                }
+            }
+        }
+        say!();
+
+        let entries: Vec<Value> = peers.iter()
+            .map(|peer| json!({
+                "peer_id": peer.peer_id.to_string(),
+                "addrs": peer.addrs.iter().map(|addr| addr.to_string()).collect::<Vec<_>>(),
+                "connection": match peer.connection_type {
+                    ConnectionType::Direct => "direct",
+                    ConnectionType::Relayed => "relayed",
+                },
+                "connected_secs": peer.uptime().as_secs(),
+            }))
+            .collect();
+        self.record("peers", entries);
         Ok(())
     }
 
     async fn cmd_connect(&mut self, args: &[&str]) -> Result<()> {
         if args.is_empty() {
             ui::print_error("Usage: connect <multiaddr>");
-            println!("  Example: connect /ip4/127.0.0.1/tcp/9001/p2p/12D3KooW...");
+            say!("  Example: connect /ip4/127.0.0.1/tcp/9001/p2p/12D3KooW...");
             return Ok(());
         }
 
@@ -208,58 +504,187 @@ impl CommandHandler {
         }
 
         ui::print_success("Connected to peer!");
-        println!();
+        say!();
+
+        self.record("address", addr);
+        Ok(())
+    }
+
+    async fn cmd_relays(&mut self) -> Result<()> {
+        ui::print_info("Discovering relays on the DHT...");
+        let relays = {
+            let client = self.client.lock().await;
+            client.discover_relays().await?
+        };
+
+        say!();
+        if relays.is_empty() {
+            ui::print_info("No relays discovered");
+        } else {
+            say!("{} ({}):", "Discovered Relays".bright_cyan().bold(), relays.len());
+            for relay in &relays {
+                let latency = relay.latency_ms
+                    .map(|ms| format!("{}ms", ms))
+                    .unwrap_or_else(|| "?".to_string());
+                say!(
+                    "  {} reputation {} latency {} free circuits {}",
+                    relay.peer_id.to_string().bright_yellow(),
+                    relay.reputation,
+                    latency,
+                    relay.free_circuits()
+                );
+            }
+        }
+        say!();
+
+        let entries: Vec<Value> = relays.iter().map(relay_json).collect();
+        self.record("relays", entries);
+        Ok(())
+    }
+
+    async fn cmd_relay(&mut self, args: &[&str]) -> Result<()> {
+        match args {
+            ["connect", addr] => {
+                ui::print_info(&format!("Connecting to relay: {}...", addr));
+                {
+                    let client = self.client.lock().await;
+                    client.connect_to_relay(addr).await?;
+                }
+                ui::print_success("Connected to relay");
+                self.record("address", *addr);
+                Ok(())
+            }
+            ["auto"] => {
+                let relay = {
+                    let client = self.client.lock().await;
+                    client.auto_connect_relay().await?
+                };
+                ui::print_success(&format!("Connected to relay {} (reputation {})", relay.peer_id, relay.reputation));
+                self.record("relay", relay_json(&relay));
+                Ok(())
+            }
+            ["rotate", "stop"] => {
+                {
+                    let client = self.client.lock().await;
+                    client.stop_relay_rotation().await;
+                }
+                ui::print_success("Relay rotation stopped");
+                self.record("rotating", false);
+                Ok(())
+            }
+            ["rotate", secs] => {
+                let secs: u64 = secs.parse()
+                    .with_context(|| format!("Invalid rotation interval: {}", secs))?;
+                anyhow::ensure!(secs > 0, "Rotation interval must be at least 1 second");
+                {
+                    let client = self.client.lock().await;
+                    client.start_relay_rotation(std::time::Duration::from_secs(secs)).await?;
+                }
+                ui::print_success(&format!("Rotating relays every {}s", secs));
+                self.record("rotating", true);
+                self.record("interval_secs", secs);
+                Ok(())
+            }
+            ["status"] => self.cmd_relay_status().await,
+            _ => {
+                ui::print_error("Usage: relay connect <multiaddr>  OR  relay auto  OR  relay rotate <secs>|stop  OR  relay status");
+                Ok(())
+            }
+        }
+    }
+
+    async fn cmd_relay_status(&mut self) -> Result<()> {
+        let (current, addresses) = {
+            let client = self.client.lock().await;
+            (client.current_relay().await, client.relay_addresses().await)
+        };
+        // Without a reservation the client only reports a bare `/p2p-circuit/...` placeholder
+        let circuits: Vec<String> = addresses.into_iter()
+            .filter(|addr| !addr.starts_with("/p2p-circuit"))
+            .collect();
+
+        say!();
+        match &current {
+            Some(relay) => say!(
+                "{} {} (reputation {})",
+                "Current relay:".bright_green(),
+                relay.peer_id.to_string().bright_yellow(),
+                relay.reputation
+            ),
+            None if circuits.is_empty() => ui::print_info("No relay connected. Use: relay connect <multiaddr>  OR  relay auto"),
+            None => {}
+        }
+        for circuit in &circuits {
+            say!("  {} {}", "Reachable at:".bright_green(), circuit.bright_yellow());
+        }
+        say!();
+
+        self.record("relay", current.as_ref().map(relay_json));
+        self.record("circuits", circuits);
         Ok(())
     }
 
-    fn cmd_context(&self) -> Result<()> {
-        println!();
-        println!("{}", "Current Context:".bright_cyan().bold());
+    fn cmd_context(&mut self) -> Result<()> {
+        self.record("space_id", self.current_space.map(|id| hex::encode(id.0)));
+        self.record("channel_id", self.current_channel.map(|id| hex::encode(id.0)));
+        self.record("thread_id", self.current_thread.map(|id| hex::encode(id.0)));
+
+        say!();
+        say!("{}", "Current Context:".bright_cyan().bold());
         
         if let Some(space_id) = &self.current_space {
-            println!("  {}: {}", "Space".bright_green(), hex::encode(space_id.0));
+            say!("  {}: {}", "Space".bright_green(), hex::encode(space_id.0));
         } else {
-            println!("  {}: {}", "Space".bright_green(), "none".yellow());
+            say!("  {}: {}", "Space".bright_green(), "none".yellow());
         }
 
         if let Some(channel_id) = &self.current_channel {
-            println!("  {}: {}", "Channel".bright_green(), hex::encode(channel_id.0));
+            say!("  {}: {}", "Channel".bright_green(), hex::encode(channel_id.0));
         } else {
-            println!("  {}: {}", "Channel".bright_green(), "none".yellow());
+            say!("  {}: {}", "Channel".bright_green(), "none".yellow());
         }
 
         if let Some(thread_id) = &self.current_thread {
-            println!("  {}: {}", "Thread".bright_green(), hex::encode(thread_id.0));
+            say!("  {}: {}", "Thread".bright_green(), hex::encode(thread_id.0));
         } else {
-            println!("  {}: {}", "Thread".bright_green(), "none".yellow());
+            say!("  {}: {}", "Thread".bright_green(), "none".yellow());
         }
 
-        println!();
+        say!();
         Ok(())
     }
 
-    async fn cmd_spaces(&self) -> Result<()> {
+    async fn cmd_spaces(&mut self) -> Result<()> {
         let spaces = {
             let client = self.client.lock().await;
             client.list_spaces().await
         };
+
+        let entries: Vec<Value> = spaces.iter()
+            .map(|space| json!({
+                "space_id": hex::encode(space.id.0),
+                "name": space.name,
+                "current": Some(space.id) == self.current_space,
+            }))
+            .collect();
+        self.record("spaces", entries);
         
-        println!();
+        say!();
         if spaces.is_empty() {
             ui::print_info("No spaces yet. Create one with: space create <name>");
         } else {
-            println!("{} ({}):", "Spaces".bright_cyan().bold(), spaces.len());
+            say!("{} ({}):", "Spaces".bright_cyan().bold(), spaces.len());
             for space in spaces {
-                let id_short = hex::encode(&space.id.0[..8]);
+                let id_short = space.id.short();
                 let marker = if Some(space.id) == self.current_space {
                     "→".bright_green()
                 } else {
                     " ".normal()
                 };
-                println!("  {} {} - {}", marker, id_short.bright_yellow(), space.name);
+                say!("  {} {} - {}", marker, id_short.bright_yellow(), space.name);
             }
         }
-        println!();
+        say!();
         Ok(())
     }
 
@@ -310,19 +735,19 @@ impl CommandHandler {
             };
 
             // Show mode description before creating
-            println!();
+            say!();
             if membership_mode.is_lightweight() {
                 ui::print_info("Creating LIGHTWEIGHT space:");
-                println!("  • No space-level encryption");
-                println!("  • Channels will provide E2EE");
-                println!("  • Suitable for large communities (100k+ users)");
+                say!("  • No space-level encryption");
+                say!("  • Channels will provide E2EE");
+                say!("  • Suitable for large communities (100k+ users)");
             } else {
                 ui::print_info("Creating MLS-ENCRYPTED space:");
-                println!("  • Space-level MLS encryption");
-                println!("  • All members share encryption keys");
-                println!("  • Best for small teams (<1000 users)");
+                say!("  • Space-level MLS encryption");
+                say!("  • All members share encryption keys");
+                say!("  • Best for small teams (<1000 users)");
             }
-            println!();
+            say!();
 
             let (space, _op, _privacy_info) = {
                 let client = self.client.lock().await;
@@ -340,9 +765,13 @@ impl CommandHandler {
 
             ui::print_success(&format!("Created space: {} ({}) [{}]", 
                 name, 
-                hex::encode(&space.id.0[..8]),
+                space.id.short(),
                 membership_mode.short_name()
             ));
+
+            self.record("space_id", hex::encode(space.id.0));
+            self.record("name", name);
+            self.record("mode", membership_mode.short_name());
         } else {
             // Switch to space by ID prefix
             let prefix = args[0];
@@ -362,11 +791,13 @@ impl CommandHandler {
                     self.current_channel = None;
                     self.current_thread = None;
                     ui::print_success(&format!("Switched to space: {}", matches[0].name));
+                    self.record("space_id", hex::encode(matches[0].id.0));
+                    self.record("name", matches[0].name.clone());
                 }
                 _ => {
                     ui::print_error("Multiple spaces match that prefix. Be more specific:");
                     for space in matches {
-                        println!("  {} - {}", hex::encode(&space.id.0[..8]), space.name);
+                        say!("  {} - {}", space.id.short(), space.name);
                     }
                 }
             }
@@ -375,30 +806,42 @@ impl CommandHandler {
         Ok(())
     }
 
-    async fn cmd_channels(&self) -> Result<()> {
+    async fn cmd_channels(&mut self) -> Result<()> {
         let space_id = self.current_space.context("No space selected. Use: space <id>")?;
         let channels = {
             let client = self.client.lock().await;
             client.list_channels(&space_id).await
         };
+
+        let entries: Vec<Value> = channels.iter()
+            .map(|channel| json!({
+                "channel_id": hex::encode(channel.id.0),
+                "name": channel.name,
+                "archived": channel.archived,
+                "nsfw": channel.nsfw,
+                "current": Some(channel.id) == self.current_channel,
+            }))
+            .collect();
+        self.record("channels", entries);
         
-        println!();
+        say!();
         if channels.is_empty() {
             ui::print_info("No channels yet. Create one with: channel create <name>");
         } else {
-            println!("{} ({}):", "Channels".bright_cyan().bold(), channels.len());
+            say!("{} ({}):", "Channels".bright_cyan().bold(), channels.len());
             for channel in channels {
-                let id_short = hex::encode(&channel.id.0[..8]);
+                let id_short = channel.id.short();
                 let marker = if Some(channel.id) == self.current_channel {
                     "→".bright_green()
                 } else {
                     " ".normal()
                 };
                 let status = if channel.archived { " [archived]".red() } else { "".normal() };
-                println!("  {} {} - {}{}", marker, id_short.bright_yellow(), channel.name, status);
+                let nsfw = if channel.nsfw { " [nsfw]".magenta() } else { "".normal() };
+                say!("  {} {} - {}{}{}", marker, id_short.bright_yellow(), channel.name, status, nsfw);
             }
         }
-        println!();
+        say!();
         Ok(())
     }
 
@@ -406,7 +849,7 @@ impl CommandHandler {
         let space_id = self.current_space.context("No space selected. Use: space <id>")?;
 
         if args.is_empty() {
-            ui::print_error("Usage: channel create <name>  OR  channel <id>");
+            ui::print_error("Usage: channel create <name>  OR  channel join <id>  OR  channel <id>");
             return Ok(());
         }
 
@@ -429,7 +872,33 @@ impl CommandHandler {
             self.current_channel = Some(channel.id);
             self.current_thread = None;
 
-            ui::print_success(&format!("Created channel: {} ({})", name, hex::encode(&channel.id.0[..8])));
+            ui::print_success(&format!("Created channel: {} ({})", name, channel.id.short()));
+            self.record("channel_id", hex::encode(channel.id.0));
+            self.record("name", name);
+        } else if args[0] == "join" {
+            let Some(prefix) = args.get(1) else {
+                ui::print_error("Usage: channel join <id>");
+                return Ok(());
+            };
+            let channel = {
+                let client = self.client.lock().await;
+                client.list_channels(&space_id).await
+                    .into_iter()
+                    .find(|c| hex::encode(c.id.0).starts_with(prefix))
+                    .context(format!("No channel found with ID prefix: {}", prefix))?
+            };
+
+            {
+                let client = self.client.lock().await;
+                client.join_channel(&channel.id).await?;
+            }
+
+            self.current_channel = Some(channel.id);
+            self.current_thread = None;
+
+            ui::print_success(&format!("Joined channel: {}", channel.name));
+            self.record("channel_id", hex::encode(channel.id.0));
+            self.record("name", channel.name);
         } else {
             let prefix = args[0];
             let channels = {
@@ -447,11 +916,13 @@ impl CommandHandler {
                     self.current_channel = Some(matches[0].id);
                     self.current_thread = None;
                     ui::print_success(&format!("Switched to channel: {}", matches[0].name));
+                    self.record("channel_id", hex::encode(matches[0].id.0));
+                    self.record("name", matches[0].name.clone());
                 }
                 _ => {
                     ui::print_error("Multiple channels match that prefix. Be more specific:");
                     for channel in matches {
-                        println!("  {} - {}", hex::encode(&channel.id.0[..8]), channel.name);
+                        say!("  {} - {}", channel.id.short(), channel.name);
                     }
                 }
             }
@@ -460,30 +931,39 @@ impl CommandHandler {
         Ok(())
     }
 
-    async fn cmd_threads(&self) -> Result<()> {
+    async fn cmd_threads(&mut self) -> Result<()> {
         let channel_id = self.current_channel.context("No channel selected. Use: channel <id>")?;
         let threads = {
             let client = self.client.lock().await;
             client.list_threads(&channel_id).await
         };
+
+        let entries: Vec<Value> = threads.iter()
+            .map(|thread| json!({
+                "thread_id": hex::encode(thread.id.0),
+                "title": thread.title,
+                "current": Some(thread.id) == self.current_thread,
+            }))
+            .collect();
+        self.record("threads", entries);
         
-        println!();
+        say!();
         if threads.is_empty() {
             ui::print_info("No threads yet. Create one with: thread create <title>");
         } else {
-            println!("{} ({}):", "Threads".bright_cyan().bold(), threads.len());
+            say!("{} ({}):", "Threads".bright_cyan().bold(), threads.len());
             for thread in threads {
-                let id_short = hex::encode(&thread.id.0[..8]);
+                let id_short = thread.id.short();
                 let marker = if Some(thread.id) == self.current_thread {
                     "→".bright_green()
                 } else {
                     " ".normal()
                 };
                 let title = thread.title.as_deref().unwrap_or("Untitled");
-                println!("  {} {} - {}", marker, id_short.bright_yellow(), title);
+                say!("  {} {} - {}", marker, id_short.bright_yellow(), title);
             }
         }
-        println!();
+        say!();
         Ok(())
     }
     
@@ -496,17 +976,27 @@ impl CommandHandler {
             }
         };
         
-        let client = self.client.lock().await;
-        let members = client.list_members(&space_id).await;
+        let members = {
+            let client = self.client.lock().await;
+            client.list_members(&space_id).await
+        };
+
+        let entries: Vec<Value> = members.iter()
+            .map(|(user_id, role)| json!({
+                "user_id": hex::encode(user_id.as_bytes()),
+                "role": format!("{:?}", role),
+            }))
+            .collect();
+        self.record("members", entries);
         
         if members.is_empty() {
             ui::print_info("No members in this space");
             return Ok(());
         }
         
-        println!();
-        println!("{}", format!("Members in Space ({}):", members.len()).bright_cyan().bold());
-        println!();
+        say!();
+        say!("{}", format!("Members in Space ({}):", members.len()).bright_cyan().bold());
+        say!();
         
         for (user_id, role) in members {
             let role_str = match role {
@@ -514,9 +1004,9 @@ impl CommandHandler {
                 spaceway_core::types::Role::Moderator => "Moderator".bright_yellow(),
                 spaceway_core::types::Role::Member => "Member".bright_green(),
             };
-            println!("  {} [{}]", format!("{:?}", user_id).bright_white(), role_str);
+            say!("  {} [{}]", format!("{:?}", user_id).bright_white(), role_str);
         }
-        println!();
+        say!();
         
         Ok(())
     }
@@ -540,15 +1030,7 @@ impl CommandHandler {
         let user_id_str = args[0];
         let user_id_hex = user_id_str.trim_start_matches("UserId(").trim_end_matches(')');
         
-        let decoded = hex::decode(user_id_hex)
-            .context("Invalid user ID hex")?;
-        if decoded.len() != 32 {
-            ui::print_error(&format!("User ID must be 32 bytes (64 hex chars), got {} bytes", decoded.len()));
-            return Ok(());
-        }
-        let mut user_id_bytes = [0u8; 32];
-        user_id_bytes.copy_from_slice(&decoded);
-        let user_id = spaceway_core::types::UserId(user_id_bytes);
+        let user_id: UserId = user_id_hex.parse()?;
         
         ui::print_info(&format!("Removing user {:?} from Space...", user_id));
         
@@ -556,6 +1038,7 @@ impl CommandHandler {
         match client.remove_member(space_id, user_id).await {
             Ok(_) => {
                 ui::print_success(&format!("Successfully removed user {:?}", user_id));
+                self.result.insert("user_id".to_string(), hex::encode(user_id.as_bytes()).into());
             }
             Err(e) => {
                 ui::print_error(&format!("Failed to remove member: {}", e));
@@ -593,7 +1076,9 @@ impl CommandHandler {
 
             self.current_thread = Some(thread.id);
 
-            ui::print_success(&format!("Created thread: {} ({})", title, hex::encode(&thread.id.0[..8])));
+            ui::print_success(&format!("Created thread: {} ({})", title, thread.id.short()));
+            self.record("thread_id", hex::encode(thread.id.0));
+            self.record("title", title);
         } else {
             let prefix = args[0];
             let threads = {
@@ -611,12 +1096,14 @@ impl CommandHandler {
                     self.current_thread = Some(matches[0].id);
                     let title = matches[0].title.as_deref().unwrap_or("Untitled");
                     ui::print_success(&format!("Switched to thread: {}", title));
+                    self.record("thread_id", hex::encode(matches[0].id.0));
+                    self.record("title", matches[0].title.clone());
                 }
                 _ => {
                     ui::print_error("Multiple threads match that prefix. Be more specific:");
                     for thread in matches {
                         let title = thread.title.as_deref().unwrap_or("Untitled");
-                        println!("  {} - {}", hex::encode(&thread.id.0[..8]), title);
+                        say!("  {} - {}", thread.id.short(), title);
                     }
                 }
             }
@@ -625,24 +1112,35 @@ impl CommandHandler {
         Ok(())
     }
 
-    async fn cmd_messages(&self) -> Result<()> {
+    async fn cmd_messages(&mut self) -> Result<()> {
         let thread_id = self.current_thread.context("No thread selected. Use: thread <id>")?;
         let messages = {
             let client = self.client.lock().await;
-            client.list_messages(&thread_id).await
+            client.list_messages_with_deleted(&thread_id, true).await
         };
+
+        let entries: Vec<Value> = messages.iter()
+            .map(|msg| json!({
+                "message_id": hex::encode(msg.id.0),
+                "author": hex::encode(msg.author.as_bytes()),
+                "created_at": msg.created_at,
+                "content": msg.content,
+                "deleted": msg.deleted,
+            }))
+            .collect();
+        self.record("messages", entries);
         
-        println!();
+        say!();
         if messages.is_empty() {
             ui::print_info("No messages yet. Send one with: send <text>");
         } else {
-            println!("{} ({}):", "Messages".bright_cyan().bold(), messages.len());
+            say!("{} ({}):", "Messages".bright_cyan().bold(), messages.len());
             for msg in messages {
                 let author_short = hex::encode(&msg.author.as_bytes()[..4]);
                 let time_secs = msg.created_at / 1000;
                 let deleted = if msg.deleted { " [deleted]".red() } else { "".normal() };
-                println!();
-                println!("  {} {} {} ({}){}",
+                say!();
+                say!("  {} {} {} ({}){}",
                     "│".bright_black(),
                     author_short.bright_yellow(),
                     chrono::DateTime::from_timestamp(time_secs as i64, 0)
@@ -652,10 +1150,12 @@ impl CommandHandler {
                     hex::encode(&msg.id.0[..4]).bright_black(),
                     deleted
                 );
-                println!("  {} {}", "│".bright_black(), msg.content);
+                for line in msg.content.lines() {
+                    say!("  {} {}", "│".bright_black(), line);
+                }
             }
         }
-        println!();
+        say!();
         Ok(())
     }
 
@@ -674,31 +1174,63 @@ impl CommandHandler {
         };
         
         ui::print_success(&format!("Message sent ({})", hex::encode(&msg.id.0[..4])));
+        self.record("message_id", hex::encode(msg.id.0));
         Ok(())
     }
 
-    async fn cmd_invite(&self, args: &[&str]) -> Result<()> {
-        println!("🎫 [CLI::INVITE] Command received with {} args", args.len());
+    async fn cmd_msg(&mut self, args: &[&str]) -> Result<()> {
+        match (self.draft.take(), args) {
+            (Some(lines), ["."]) => self.cmd_send(&lines.join("\n")).await,
+            (None, []) => {
+                self.current_thread.context("No thread selected. Use: thread <id>")?;
+                self.draft = Some(Vec::new());
+                ui::print_info("Composing message. End with a line containing only '.'");
+                self.record("composing", true);
+                Ok(())
+            }
+            (None, ["--editor"]) => {
+                self.current_thread.context("No thread selected. Use: thread <id>")?;
+                let content = compose_in_editor()?;
+                self.cmd_send(&content).await
+            }
+            _ => {
+                ui::print_error("Usage: msg  OR  msg --editor");
+                Ok(())
+            }
+        }
+    }
+
+    async fn cmd_invite(&mut self, args: &[&str]) -> Result<()> {
+        say!("🎫 [CLI::INVITE] Command received with {} args", args.len());
         if !args.is_empty() {
-            println!("   Args: {:?}", args);
+            say!("   Args: {:?}", args);
         }
         
         let space_id = self.current_space.context("No space selected. Use: space <id>")?;
-        println!("✓ [CLI::INVITE] Current space: {}", hex::encode(&space_id.0[..8]));
+        say!("✓ [CLI::INVITE] Current space: {}", space_id.short());
 
         if args.is_empty() {
-            println!("   Action: List invites");
+            say!("   Action: List invites");
             // List invites
             let invites = {
                 let client = self.client.lock().await;
                 client.list_invites(&space_id).await
             };
+
+            let entries: Vec<Value> = invites.iter()
+                .map(|invite| json!({
+                    "code": invite.code,
+                    "expires_at": invite.expires_at,
+                }))
+                .collect();
+            self.record("space_id", hex::encode(space_id.0));
+            self.record("invites", entries);
             
-            println!();
+            say!();
             if invites.is_empty() {
                 ui::print_info("No active invites. Create one with: invite create");
             } else {
-                println!("{} ({}):", "Active Invites".bright_cyan().bold(), invites.len());
+                say!("{} ({}):", "Active Invites".bright_cyan().bold(), invites.len());
                 for invite in invites {
                     let expires = if let Some(exp) = invite.expires_at {
                         let time_secs = exp / 1000;
@@ -708,24 +1240,25 @@ impl CommandHandler {
                     } else {
                         "Never".to_string()
                     };
-                    println!("  {} {} - Expires: {}", 
+                    say!("  {} {} - Expires: {}", 
                         "Code:".bright_green(),
                         invite.code.bright_yellow(), 
                         expires
                     );
                 }
             }
-            println!();
+            say!();
         } else if args[0] == "create" {
-            println!("   Action: Create invite");
+            say!("   Action: Create invite");
+            let custom_code = args.get(1).map(|code| code.to_string());
             // Create invite
             let _op = {
                 let client = self.client.lock().await;
-                println!("   Calling client.create_invite...");
-                client.create_invite(space_id, None, None).await?
+                say!("   Calling client.create_invite...");
+                client.create_invite(space_id, None, None, None, custom_code.clone()).await?
             };
             
-            println!("✓  [CLI::INVITE] Invite created, fetching details...");
+            say!("✓  [CLI::INVITE] Invite created, fetching details...");
             
             // Fetch the latest invites to get the code
             let invites = {
@@ -733,44 +1266,65 @@ impl CommandHandler {
                 client.list_invites(&space_id).await
             };
             
-            if let Some(invite) = invites.last() {
+            let created = match &custom_code {
+                Some(code) => invites.iter().find(|invite| &invite.code == code),
+                None => invites.last(),
+            };
+            
+            if let Some(invite) = created {
+                self.record("space_id", hex::encode(space_id.0));
+                self.record("code", invite.code.clone());
+                self.record("uri", invite.to_uri());
                 ui::print_success(&format!("Created invite code: {}", invite.code.bright_yellow()));
-                println!();
-                println!("  Share this code with others to invite them:");
-                println!("  {} join {} {}", 
+                say!();
+                say!("  Share this code with others to invite them:");
+                say!("  {} join {} {}", 
                     "$".bright_black(), 
                     hex::encode(&space_id.0).bright_yellow(),
                     invite.code.bright_yellow()
                 );
-                println!();
+                say!();
+                say!("  Or share the invite link:");
+                say!("  {}", invite.to_uri().bright_yellow());
+                say!();
             } else {
                 ui::print_success("Created invite");
             }
         } else {
-            ui::print_error("Usage: invite  OR  invite create");
+            ui::print_error("Usage: invite  OR  invite create [code]");
         }
 
         Ok(())
     }
 
     async fn cmd_join(&mut self, args: &[&str]) -> Result<()> {
+        if args.len() == 1 && args[0].starts_with(INVITE_URI_PREFIX) {
+            let invite_uri = InviteUri::parse(args[0])?;
+            ui::print_info(&format!("Joining Space with invite link: {}...", invite_uri.code));
+
+            let _op = {
+                let client = self.client.lock().await;
+                client.join_with_uri(args[0]).await?
+            };
+
+            self.current_space = Some(invite_uri.space_id);
+            self.current_channel = None;
+            self.current_thread = None;
+
+            ui::print_success("Successfully joined Space!");
+            self.record("space_id", hex::encode(invite_uri.space_id.0));
+            return Ok(());
+        }
+
         if args.len() < 2 {
-            ui::print_error("Usage: join <space_id> <invite_code>  OR  join dht <space_id>");
+            ui::print_error("Usage: join <space_id> <invite_code>  OR  join <invite_link>  OR  join dht <space_id>");
             return Ok(());
         }
 
         if args[0] == "dht" {
             // Join from DHT
             let space_id_hex = args[1];
-            let decoded = hex::decode(space_id_hex)
-                .context("Invalid space ID hex")?;
-            if decoded.len() != 32 {
-                ui::print_error(&format!("Space ID must be 32 bytes (64 hex chars), got {} bytes", decoded.len()));
-                return Ok(());
-            }
-            let mut space_id_bytes = [0u8; 32];
-            space_id_bytes.copy_from_slice(&decoded);
-            let space_id = SpaceId(space_id_bytes);
+            let space_id: SpaceId = space_id_hex.parse()?;
 
             ui::print_info(&format!("Joining Space from DHT: {}...", space_id_hex));
             
@@ -784,24 +1338,18 @@ impl CommandHandler {
             self.current_thread = None;
 
             ui::print_success(&format!("Joined Space from DHT: {}", space.name));
-            println!();
-            println!("  Members: {}", space.members.len());
-            println!("  Visibility: {:?}", space.visibility);
-            println!();
+            self.record("space_id", hex::encode(space.id.0));
+            self.record("name", space.name.clone());
+            say!();
+            say!("  Members: {}", space.member_count());
+            say!("  Visibility: {:?}", space.visibility);
+            say!();
         } else {
             // Join with invite code
             let space_id_hex = args[0];
             let invite_code = args[1];
 
-            let decoded = hex::decode(space_id_hex)
-                .context("Invalid space ID hex")?;
-            if decoded.len() != 32 {
-                ui::print_error(&format!("Space ID must be 32 bytes (64 hex chars), got {} bytes", decoded.len()));
-                return Ok(());
-            }
-            let mut space_id_bytes = [0u8; 32];
-            space_id_bytes.copy_from_slice(&decoded);
-            let space_id = SpaceId(space_id_bytes);
+            let space_id: SpaceId = space_id_hex.parse()?;
 
             ui::print_info(&format!("Joining Space with invite code: {}...", invite_code));
 
@@ -815,31 +1363,76 @@ impl CommandHandler {
             self.current_thread = None;
 
             ui::print_success("Successfully joined Space!");
-            println!();
-            println!("  Note: You'll receive MLS Welcome message when an admin adds you");
-            println!();
+            self.record("space_id", hex::encode(space_id.0));
+            say!();
+            say!("  Note: You'll receive MLS Welcome message when an admin adds you");
+            say!();
         }
 
         Ok(())
     }
 
-    async fn cmd_upload(&self, args: &[&str]) -> Result<()> {
-        let space_id = self.current_space.context("No space selected")?;
+    /// Split an optional `--space <id>` flag off the arguments
+    ///
+    /// Falls back to the current space when the flag is absent.
+    async fn take_space_flag<'a>(&self, args: &[&'a str]) -> Result<(SpaceId, Vec<&'a str>)> {
+        let mut space_prefix = None;
+        let mut rest = Vec::new();
+
+        let mut i = 0;
+        while i < args.len() {
+            if args[i] == "--space" && i + 1 < args.len() {
+                space_prefix = Some(args[i + 1]);
+                i += 2;
+            } else {
+                rest.push(args[i]);
+                i += 1;
+            }
+        }
 
-        if args.is_empty() {
-            ui::print_error("Usage: upload <file_path>");
+        let space_id = match space_prefix {
+            Some(prefix) => self.resolve_space(prefix).await?,
+            None => self.current_space.context("No space selected. Use: space <id> or --space <id>")?,
+        };
+
+        Ok((space_id, rest))
+    }
+
+    /// Find the one space whose hex ID starts with `prefix`
+    async fn resolve_space(&self, prefix: &str) -> Result<SpaceId> {
+        let spaces = {
+            let client = self.client.lock().await;
+            client.list_spaces().await
+        };
+        let matches: Vec<_> = spaces.into_iter()
+            .filter(|s| hex::encode(s.id.0).starts_with(prefix))
+            .collect();
+        match matches.len() {
+            0 => anyhow::bail!("No space found with ID prefix: {}", prefix),
+            1 => Ok(matches[0].id),
+            _ => anyhow::bail!("Multiple spaces match prefix {}. Be more specific", prefix),
+        }
+    }
+
+    async fn cmd_upload(&mut self, args: &[&str]) -> Result<()> {
+        let (space_id, rest) = self.take_space_flag(args).await?;
+
+        if rest.is_empty() {
+            ui::print_error("Usage: upload <file_path> [--space <id>]");
             return Ok(());
         }
 
-        let file_path = args.join(" ");
+        let file_path = rest.join(" ");
         let data = std::fs::read(&file_path)
             .context("Failed to read file")?;
 
-        let filename = std::path::Path::new(&file_path)
+        let path = std::path::Path::new(&file_path);
+        let filename = path
             .file_name()
             .and_then(|n| n.to_str())
             .unwrap_or("unknown")
             .to_string();
+        let mime_type = mime_from_extension(path);
 
         ui::print_info(&format!("Uploading {} ({} bytes)...", filename, data.len()));
 
@@ -848,20 +1441,136 @@ impl CommandHandler {
             client.store_blob_for_space(
                 &space_id,
                 &data,
-                None, // Auto-detect MIME type would go here
+                mime_type.map(str::to_string),
                 Some(filename.clone()),
             ).await?
         };
 
-        ui::print_success(&format!("Uploaded: {} (hash: {})", 
+        ui::print_success(&format!("Uploaded: {} ({} bytes, {})",
             filename,
-            hex::encode(&metadata.hash.as_bytes()[..8])
+            data.len(),
+            mime_type.unwrap_or("unknown type")
         ));
+        say!("  {} {}", "Hash:".bright_green(), metadata.hash.to_hex().bright_yellow());
+        say!("  {} download {} <out_path>", "$".bright_black(), metadata.hash.to_hex());
+
+        self.record("space_id", hex::encode(space_id.0));
+        self.record("hash", metadata.hash.to_hex());
+        self.record("filename", filename);
+        self.record("mime_type", mime_type);
+        self.record("size", data.len());
+
+        Ok(())
+    }
+
+    async fn cmd_download(&mut self, args: &[&str]) -> Result<()> {
+        let (space_id, rest) = self.take_space_flag(args).await?;
+
+        if rest.len() < 2 {
+            ui::print_error("Usage: download <hash> <out_path> [--space <id>]");
+            return Ok(());
+        }
+
+        let hash = spaceway_core::storage::BlobHash::from_hex(rest[0])
+            .context("Invalid blob hash (expected 64 hex chars)")?;
+        let out_path = rest[1..].join(" ");
+
+        ui::print_info(&format!("Downloading {}...", &rest[0][..rest[0].len().min(16)]));
+
+        let data = {
+            let client = self.client.lock().await;
+            client.retrieve_blob_for_space(&space_id, &hash).await?
+        };
+
+        std::fs::write(&out_path, &data)
+            .with_context(|| format!("Failed to write {}", out_path))?;
+
+        ui::print_success(&format!("Downloaded {} bytes to {}", data.len(), out_path));
+
+        self.record("space_id", hex::encode(space_id.0));
+        self.record("hash", hash.to_hex());
+        self.record("path", out_path);
+        self.record("size", data.len());
+
+        Ok(())
+    }
+
+    async fn cmd_export(&mut self, args: &[&str]) -> Result<()> {
+        let mut format = None;
+        let mut rest = Vec::new();
+
+        let mut i = 0;
+        while i < args.len() {
+            if args[i] == "--format" && i + 1 < args.len() {
+                match ExportFormat::from_str(args[i + 1]) {
+                    Some(f) => format = Some(f),
+                    None => {
+                        ui::print_error(&format!("Invalid format: '{}'. Use 'json' or 'markdown'", args[i + 1]));
+                        return Ok(());
+                    }
+                }
+                i += 2;
+            } else {
+                rest.push(args[i]);
+                i += 1;
+            }
+        }
+
+        if rest.len() < 2 {
+            ui::print_error("Usage: export <space_id> <file> [--format json|markdown]");
+            return Ok(());
+        }
+
+        let space_id = self.resolve_space(rest[0]).await?;
+        let out_path = rest[1..].join(" ");
+        let format = format.unwrap_or_else(|| ExportFormat::from_path(std::path::Path::new(&out_path)));
+
+        let data = {
+            let client = self.client.lock().await;
+            client.export_space(&space_id, format).await?
+        };
+
+        std::fs::write(&out_path, &data)
+            .with_context(|| format!("Failed to write {}", out_path))?;
+
+        ui::print_success(&format!("Exported space to {} ({} bytes)", out_path, data.len()));
+
+        self.record("space_id", hex::encode(space_id.0));
+        self.record("path", out_path);
+        self.record("format", if format == ExportFormat::Json { "json" } else { "markdown" });
+        self.record("size", data.len());
+
+        Ok(())
+    }
+
+    async fn cmd_import(&mut self, args: &[&str]) -> Result<()> {
+        if args.is_empty() {
+            ui::print_error("Usage: import <file>");
+            return Ok(());
+        }
+
+        let path = args.join(" ");
+        let data = std::fs::read(&path)
+            .with_context(|| format!("Failed to read {}", path))?;
+
+        ui::print_info(&format!("Importing transcript from {}...", path));
+
+        let space_id = {
+            let client = self.client.lock().await;
+            client.import_transcript(&data).await?
+        };
+
+        self.current_space = Some(space_id);
+        self.current_channel = None;
+        self.current_thread = None;
+
+        ui::print_success(&format!("Imported as space {}", space_id.short()));
+        self.record("space_id", hex::encode(space_id.0));
 
         Ok(())
     }
 
-    async fn cmd_refresh(&self) -> Result<()> {
+    async fn cmd_refresh(&mut self) -> Result<()> {
         ui::print_info("Refreshing network state...");
         
         // Just a simple status check
@@ -871,6 +1580,7 @@ impl CommandHandler {
         };
 
         ui::print_success(&format!("Connected as peer: {}", &peer_id[..16]));
+        self.record("peer_id", peer_id);
         Ok(())
     }
     
@@ -886,10 +1596,10 @@ impl CommandHandler {
         if args.is_empty() {
             ui::print_error("Usage: member add <user_id>");
             ui::print_info("This adds the user to the MLS encryption group");
-            println!();
-            println!("  Get user IDs with: members");
-            println!("  Example: member add 1a2b3c4d5e6f...");
-            println!();
+            say!();
+            say!("  Get user IDs with: members");
+            say!("  Example: member add 1a2b3c4d5e6f...");
+            say!();
             return Ok(());
         }
         
@@ -904,25 +1614,17 @@ impl CommandHandler {
             let user_id_str = args[1];
             let user_id_hex = user_id_str.trim_start_matches("UserId(").trim_end_matches(')');
             
-            let decoded = hex::decode(user_id_hex)
-                .context("Invalid user ID hex")?;
-            if decoded.len() != 32 {
-                ui::print_error(&format!("User ID must be 32 bytes (64 hex chars), got {} bytes", decoded.len()));
-                return Ok(());
-            }
-            let mut user_id_bytes = [0u8; 32];
-            user_id_bytes.copy_from_slice(&decoded);
-            let user_id = spaceway_core::types::UserId(user_id_bytes);
+            let user_id: UserId = user_id_hex.parse()?;
             
-            println!();
-            ui::print_info(&format!("Adding {} to MLS encryption group...", hex::encode(&user_id.0[..8])));
-            println!();
-            println!("  This will:");
-            println!("  1. Fetch their KeyPackage from DHT");
-            println!("  2. Add them to the MLS group");
-            println!("  3. Send them a Welcome message");
-            println!("  4. Rotate encryption keys (new epoch)");
-            println!();
+            say!();
+            ui::print_info(&format!("Adding {} to MLS encryption group...", user_id.short()));
+            say!();
+            say!("  This will:");
+            say!("  1. Fetch their KeyPackage from DHT");
+            say!("  2. Add them to the MLS group");
+            say!("  3. Send them a Welcome message");
+            say!("  4. Rotate encryption keys (new epoch)");
+            say!();
             
             let client = self.client.lock().await;
             match client.add_member_with_mls(
@@ -931,42 +1633,43 @@ impl CommandHandler {
                 spaceway_core::types::Role::Member
             ).await {
                 Ok(_) => {
-                    ui::print_success(&format!("User {} added to MLS group!", hex::encode(&user_id.0[..8])));
-                    println!();
-                    println!("  ✓ User can now decrypt messages in this space");
-                    println!();
+                    ui::print_success(&format!("User {} added to MLS group!", user_id.short()));
+                    self.result.insert("user_id".to_string(), hex::encode(user_id.0).into());
+                    say!();
+                    say!("  ✓ User can now decrypt messages in this space");
+                    say!();
                 }
                 Err(e) => {
                     let error_msg = format!("{}", e);
                     
                     if error_msg.contains("DuplicateSignatureKey") {
                         ui::print_error("User is already in the MLS encryption group!");
-                        println!();
-                        println!("  This user has already been added to the MLS group for this space.");
-                        println!("  Each user can only be added once.");
-                        println!();
-                        println!("  Note: Space creators are automatically added to their MLS group.");
-                        println!();
+                        say!();
+                        say!("  This user has already been added to the MLS group for this space.");
+                        say!("  Each user can only be added once.");
+                        say!();
+                        say!("  Note: Space creators are automatically added to their MLS group.");
+                        say!();
                     } else if error_msg.contains("NotFound") || error_msg.contains("No KeyPackages") {
                         ui::print_error("User hasn't published KeyPackages yet");
-                        println!();
-                        println!("  Tell the user to run:");
-                        println!("  > keypackage publish");
-                        println!();
+                        say!();
+                        say!("  Tell the user to run:");
+                        say!("  > keypackage publish");
+                        say!();
                     } else if error_msg.contains("quorum") || error_msg.contains("DHT") {
                         ui::print_error("DHT quorum not reached");
-                        println!();
-                        println!("  Need more peers in the network to fetch KeyPackages from DHT.");
-                        println!("  For 2-peer setup, consider direct KeyPackage exchange.");
-                        println!();
+                        say!();
+                        say!("  Need more peers in the network to fetch KeyPackages from DHT.");
+                        say!("  For 2-peer setup, consider direct KeyPackage exchange.");
+                        say!();
                     } else {
                         ui::print_error(&format!("Failed to add member to MLS: {}", e));
-                        println!();
-                        println!("  Possible reasons:");
-                        println!("  • User hasn't published KeyPackages (tell them: keypackage publish)");
-                        println!("  • DHT quorum not reached (need more peers)");
-                        println!("  • User is already in the MLS group");
-                        println!();
+                        say!();
+                        say!("  Possible reasons:");
+                        say!("  • User hasn't published KeyPackages (tell them: keypackage publish)");
+                        say!("  • DHT quorum not reached (need more peers)");
+                        say!("  • User is already in the MLS group");
+                        say!();
                     }
                 }
             }
@@ -981,36 +1684,37 @@ impl CommandHandler {
         if args.is_empty() {
             ui::print_error("Usage: keypackage publish");
             ui::print_info("Publishes your KeyPackages to DHT for MLS encryption");
-            println!();
-            println!("  This allows others to add you to encrypted spaces");
-            println!();
+            say!();
+            say!("  This allows others to add you to encrypted spaces");
+            say!();
             return Ok(());
         }
         
         if args[0] == "publish" {
-            println!();
+            say!();
             ui::print_info("Publishing KeyPackages to DHT...");
-            println!();
-            println!("  Generating 10 KeyPackages...");
+            say!();
+            say!("  Generating 10 KeyPackages...");
             
             let client = self.client.lock().await;
             match client.publish_key_packages_to_dht().await {
                 Ok(_) => {
                     ui::print_success("Published 10 KeyPackages to DHT");
-                    println!();
-                    println!("  ✓ Others can now add you to MLS encryption groups");
-                    println!("  ✓ KeyPackages will be consumed as you join groups");
-                    println!();
-                    println!("  Tip: Re-run this command periodically to refresh expired packages");
-                    println!();
+                    self.result.insert("published".to_string(), 10.into());
+                    say!();
+                    say!("  ✓ Others can now add you to MLS encryption groups");
+                    say!("  ✓ KeyPackages will be consumed as you join groups");
+                    say!();
+                    say!("  Tip: Re-run this command periodically to refresh expired packages");
+                    say!();
                 }
                 Err(e) => {
                     ui::print_error(&format!("Failed to publish KeyPackages: {}", e));
-                    println!();
-                    println!("  Possible reasons:");
-                    println!("  • DHT quorum not reached (need more peers)");
-                    println!("  • Network connectivity issues");
-                    println!();
+                    say!();
+                    say!("  Possible reasons:");
+                    say!("  • DHT quorum not reached (need more peers)");
+                    say!("  • Network connectivity issues");
+                    say!();
                 }
             }
         } else {
@@ -1021,3 +1725,76 @@ impl CommandHandler {
     }
 }
 
+/// Guess a MIME type from a file extension
+fn mime_from_extension(path: &std::path::Path) -> Option<&'static str> {
+    let ext = path.extension()?.to_str()?.to_ascii_lowercase();
+    let mime = match ext.as_str() {
+        "txt" | "log" => "text/plain",
+        "md" => "text/markdown",
+        "html" | "htm" => "text/html",
+        "css" => "text/css",
+        "csv" => "text/csv",
+        "json" => "application/json",
+        "pdf" => "application/pdf",
+        "zip" => "application/zip",
+        "gz" => "application/gzip",
+        "png" => "image/png",
+        "jpg" | "jpeg" => "image/jpeg",
+        "gif" => "image/gif",
+        "webp" => "image/webp",
+        "svg" => "image/svg+xml",
+        "mp3" => "audio/mpeg",
+        "ogg" => "audio/ogg",
+        "wav" => "audio/wav",
+        "mp4" => "video/mp4",
+        "webm" => "video/webm",
+        _ => return None,
+    };
+    Some(mime)
+}
+
+/// Open `$VISUAL` or `$EDITOR` on an empty file and return what was written
+fn compose_in_editor() -> Result<String> {
+    let editor = std::env::var("VISUAL")
+        .or_else(|_| std::env::var("EDITOR"))
+        .context("Set $EDITOR to compose messages in an editor")?;
+    let mut words = editor.split_whitespace();
+    let program = words.next().context("$EDITOR is empty")?;
+
+    let path = std::env::temp_dir().join(format!("spaceway-msg-{}.txt", std::process::id()));
+    std::fs::write(&path, "")?;
+    let status = std::process::Command::new(program)
+        .args(words)
+        .arg(&path)
+        .status()
+        .with_context(|| format!("Failed to run editor: {}", editor));
+    let content = std::fs::read_to_string(&path);
+    let _ = std::fs::remove_file(&path);
+
+    anyhow::ensure!(status?.success(), "Editor exited with an error; message not sent");
+    Ok(content?.trim_end_matches('\n').to_string())
+}
+
+fn relay_json(relay: &spaceway_core::network::relay::RelayInfo) -> Value {
+    json!({
+        "peer_id": relay.peer_id.to_string(),
+        "addresses": relay.addresses.iter().map(|addr| addr.to_string()).collect::<Vec<_>>(),
+        "reputation": relay.reputation,
+        "latency_ms": relay.latency_ms,
+        "free_circuits": relay.free_circuits(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::Path;
+
+    #[test]
+    fn test_mime_from_extension() {
+        assert_eq!(mime_from_extension(Path::new("notes.txt")), Some("text/plain"));
+        assert_eq!(mime_from_extension(Path::new("photo.JPG")), Some("image/jpeg"));
+        assert_eq!(mime_from_extension(Path::new("archive.unknown")), None);
+        assert_eq!(mime_from_extension(Path::new("Makefile")), None);
+    }
+}
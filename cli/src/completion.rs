@@ -0,0 +1,238 @@
+//! Tab completion and per-command usage for the interactive REPL
+//!
+//! Command names complete from [`COMMANDS`]; arguments that take a space or
+//! channel ID complete from the IDs the handler last reported via
+//! [`ReplHelper::set_ids`].
+
+use rustyline::completion::Completer;
+use rustyline::highlight::Highlighter;
+use rustyline::hint::Hinter;
+use rustyline::validate::Validator;
+use rustyline::{Context, Helper};
+use std::sync::{Arc, RwLock};
+
+/// A REPL command and the forms it accepts
+pub struct CommandUsage {
+    pub name: &'static str,
+    /// Each accepted form with what it does
+    pub forms: &'static [(&'static str, &'static str)],
+}
+
+/// Every command the REPL dispatches (aliases included), for completion and `help <command>`
+pub const COMMANDS: &[CommandUsage] = &[
+    CommandUsage { name: "whoami", forms: &[("whoami", "Show current user info")] },
+    CommandUsage { name: "version", forms: &[("version", "Show version and build info")] },
+    CommandUsage { name: "about", forms: &[("about", "Same as 'version'")] },
+    CommandUsage { name: "context", forms: &[("context", "Show current space/channel/thread")] },
+    CommandUsage { name: "network", forms: &[("network", "Show network status and peer ID")] },
+    CommandUsage { name: "status", forms: &[("status", "Show MLS epoch, members and connection health")] },
+    CommandUsage {
+        name: "sync-state",
+        forms: &[("sync-state <space_id>", "Show a space's vector clock and how connected peers differ")],
+    },
+    CommandUsage { name: "peers", forms: &[("peers", "List connected peers (direct/relayed)")] },
+    CommandUsage { name: "connect", forms: &[("connect <multiaddr>", "Connect to a peer")] },
+    CommandUsage { name: "relays", forms: &[("relays", "List relays discovered on the DHT")] },
+    CommandUsage {
+        name: "relay",
+        forms: &[
+            ("relay connect <multiaddr>", "Reserve a slot on a relay"),
+            ("relay auto", "Connect to the best discovered relay"),
+            ("relay rotate <secs>", "Switch relays every <secs> seconds"),
+            ("relay rotate stop", "Stop relay rotation"),
+            ("relay status", "Show the current relay and circuit addresses"),
+        ],
+    },
+    CommandUsage { name: "spaces", forms: &[("spaces", "List all spaces")] },
+    CommandUsage {
+        name: "space",
+        forms: &[
+            ("space create <name> [--mode lightweight|mls]", "Create a new space"),
+            ("space list", "List all spaces"),
+            ("space <id>", "Switch to a space by ID prefix"),
+        ],
+    },
+    CommandUsage { name: "channels", forms: &[("channels", "List channels in current space")] },
+    CommandUsage {
+        name: "channel",
+        forms: &[
+            ("channel create <name>", "Create a channel in current space"),
+            ("channel join <id>", "Join a channel's encryption group so you can post in it"),
+            ("channel <id>", "Switch to a channel by ID prefix"),
+        ],
+    },
+    CommandUsage { name: "threads", forms: &[("threads", "List threads in current channel")] },
+    CommandUsage {
+        name: "thread",
+        forms: &[
+            ("thread create <title>", "Create a thread in current channel"),
+            ("thread <id>", "Switch to a thread by ID prefix"),
+        ],
+    },
+    CommandUsage { name: "messages", forms: &[("messages", "Show messages in current thread")] },
+    CommandUsage { name: "send", forms: &[("send <text>", "Send a message to current thread")] },
+    CommandUsage {
+        name: "msg",
+        forms: &[
+            ("msg", "Compose a multi-line message, ending with a line of just '.'"),
+            ("msg --editor", "Compose a message in $EDITOR"),
+        ],
+    },
+    CommandUsage {
+        name: "invite",
+        forms: &[
+            ("invite", "List active invites for current space"),
+            ("invite create [code]", "Create an invite code (optionally a custom one)"),
+        ],
+    },
+    CommandUsage {
+        name: "join",
+        forms: &[
+            ("join <space_id> <invite_code>", "Join a space with an invite code"),
+            ("join <descord://join/...>", "Join a space with an invite link"),
+            ("join dht <space_id>", "Join a space from the DHT"),
+        ],
+    },
+    CommandUsage { name: "members", forms: &[("members", "List members in current space")] },
+    CommandUsage { name: "member", forms: &[("member add <user_id>", "Add a member to the MLS group")] },
+    CommandUsage { name: "kick", forms: &[("kick <user_id>", "Remove a member from current space")] },
+    CommandUsage { name: "remove", forms: &[("remove <user_id>", "Same as 'kick'")] },
+    CommandUsage { name: "keypackage", forms: &[("keypackage publish", "Publish KeyPackages to the DHT for MLS")] },
+    CommandUsage { name: "upload", forms: &[("upload <file_path> [--space <id>]", "Upload a file to a space")] },
+    CommandUsage { name: "download", forms: &[("download <hash> <out_path> [--space <id>]", "Download a file by hash")] },
+    CommandUsage {
+        name: "export",
+        forms: &[("export <space_id> <file> [--format json|markdown]", "Export a space transcript")],
+    },
+    CommandUsage { name: "import", forms: &[("import <file>", "Import a JSON transcript as a new space")] },
+    CommandUsage { name: "refresh", forms: &[("refresh", "Refresh local state from network")] },
+    CommandUsage {
+        name: "help",
+        forms: &[("help", "List all commands"), ("help <command>", "Show usage of one command")],
+    },
+    CommandUsage { name: "quit", forms: &[("quit", "Exit the application")] },
+    CommandUsage { name: "exit", forms: &[("exit", "Exit the application")] },
+];
+
+/// Usage of one command, if it exists
+pub fn usage(name: &str) -> Option<&'static CommandUsage> {
+    COMMANDS.iter().find(|command| command.name == name)
+}
+
+/// IDs offered when completing arguments
+#[derive(Debug, Clone, Default)]
+pub struct CompletionIds {
+    /// Hex IDs of the spaces the user is in
+    pub spaces: Vec<String>,
+    /// Hex IDs of the channels in the current space
+    pub channels: Vec<String>,
+}
+
+/// Completion candidates for `line` with the cursor at `pos`
+///
+/// Returns where the word being completed starts and the candidates for it.
+pub fn complete(line: &str, pos: usize, ids: &CompletionIds) -> (usize, Vec<String>) {
+    let before = &line[..pos];
+    let start = before.rfind(char::is_whitespace).map(|i| i + 1).unwrap_or(0);
+    let word = &before[start..];
+    let previous: Vec<&str> = before[..start].split_whitespace().collect();
+
+    let options: Vec<&str> = match previous.as_slice() {
+        [] => COMMANDS.iter().map(|command| command.name).collect(),
+        [.., "--space"] => ids.spaces.iter().map(String::as_str).collect(),
+        ["help"] => COMMANDS.iter().map(|command| command.name).collect(),
+        ["space"] => ["create", "list"].into_iter().chain(ids.spaces.iter().map(String::as_str)).collect(),
+        ["channel"] => ["create", "join"].into_iter().chain(ids.channels.iter().map(String::as_str)).collect(),
+        ["channel", "join"] => ids.channels.iter().map(String::as_str).collect(),
+        ["join"] => std::iter::once("dht").chain(ids.spaces.iter().map(String::as_str)).collect(),
+        ["export"] | ["sync-state"] => ids.spaces.iter().map(String::as_str).collect(),
+        ["thread"] | ["invite"] => vec!["create"],
+        ["member"] => vec!["add"],
+        ["relay"] => vec!["auto", "connect", "rotate", "status"],
+        ["relay", "rotate"] => vec!["stop"],
+        ["keypackage"] => vec!["publish"],
+        ["msg"] => vec!["--editor"],
+        _ => Vec::new(),
+    };
+
+    let mut candidates: Vec<String> = options.into_iter()
+        .filter(|option| option.starts_with(word))
+        .map(str::to_string)
+        .collect();
+    candidates.sort();
+    candidates.dedup();
+    (start, candidates)
+}
+
+/// rustyline helper completing REPL commands and IDs
+#[derive(Default)]
+pub struct ReplHelper {
+    ids: Arc<RwLock<CompletionIds>>,
+}
+
+impl ReplHelper {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Replace the IDs offered for completion
+    pub fn set_ids(&self, ids: CompletionIds) {
+        *self.ids.write().unwrap() = ids;
+    }
+}
+
+impl Completer for ReplHelper {
+    type Candidate = String;
+
+    fn complete(&self, line: &str, pos: usize, _ctx: &Context<'_>) -> rustyline::Result<(usize, Vec<String>)> {
+        Ok(complete(line, pos, &self.ids.read().unwrap()))
+    }
+}
+
+impl Hinter for ReplHelper {
+    type Hint = String;
+}
+
+impl Highlighter for ReplHelper {}
+
+impl Validator for ReplHelper {}
+
+impl Helper for ReplHelper {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ids() -> CompletionIds {
+        CompletionIds {
+            spaces: vec!["ab12".to_string(), "ab34".to_string(), "cd56".to_string()],
+            channels: vec!["ef78".to_string()],
+        }
+    }
+
+    #[test]
+    fn test_completes_command_names() {
+        assert_eq!(complete("me", 2, &ids()), (0, vec!["member".to_string(), "members".to_string(), "messages".to_string()]));
+        assert_eq!(complete("spa", 3, &ids()), (0, vec!["space".to_string(), "spaces".to_string()]));
+        assert_eq!(complete("help ki", 7, &ids()), (5, vec!["kick".to_string()]));
+        assert!(complete("zz", 2, &ids()).1.is_empty());
+    }
+
+    #[test]
+    fn test_completes_ids_for_arguments() {
+        assert_eq!(complete("space ab", 8, &ids()), (6, vec!["ab12".to_string(), "ab34".to_string()]));
+        assert_eq!(complete("space ", 6, &ids()).1, vec!["ab12", "ab34", "cd56", "create", "list"]);
+        assert_eq!(complete("channel e", 9, &ids()), (8, vec!["ef78".to_string()]));
+        assert_eq!(complete("upload notes.txt --space c", 26, &ids()), (25, vec!["cd56".to_string()]));
+        // Free text is never completed
+        assert!(complete("send ab", 7, &ids()).1.is_empty());
+    }
+
+    #[test]
+    fn test_every_dispatched_command_has_usage() {
+        for name in ["whoami", "space", "join", "kick", "remove", "export", "help"] {
+            assert!(usage(name).is_some_and(|command| !command.forms.is_empty()), "{}", name);
+        }
+        assert!(usage("nope").is_none());
+    }
+}
@@ -0,0 +1,189 @@
+//! Config file support - named profiles merged with command-line flags
+//!
+//! Example `~/.config/descord/config.toml`:
+//!
+//! ```toml
+//! default_profile = "local"
+//!
+//! [profiles.local]
+//! account = "alice.key"
+//! relay = "/ip4/127.0.0.1/tcp/9000"
+//! bootstrap = ["/ip4/127.0.0.1/tcp/9001/p2p/12D3..."]
+//! port = 9002
+//! ```
+
+use anyhow::{Context, Result};
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::Args;
+
+/// Relay used when neither the flags nor the config file name one
+pub const DEFAULT_RELAY: &str = "/ip4/127.0.0.1/tcp/9000";
+
+/// Profile used when none is selected explicitly
+const DEFAULT_PROFILE: &str = "default";
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ConfigFile {
+    /// Profile to use when `--profile` is not given
+    default_profile: Option<String>,
+    #[serde(default)]
+    profiles: HashMap<String, Profile>,
+}
+
+/// One named setup in the config file; every field is optional
+#[derive(Debug, Default, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct Profile {
+    account: Option<PathBuf>,
+    relay: Option<String>,
+    #[serde(default)]
+    bootstrap: Vec<String>,
+    port: Option<u16>,
+    data_dir: Option<PathBuf>,
+}
+
+/// Effective settings after merging flags over the selected profile
+#[derive(Debug, Clone, PartialEq)]
+pub struct Settings {
+    pub account: PathBuf,
+    pub relay: String,
+    pub data_dir: Option<PathBuf>,
+    pub port: Option<u16>,
+    pub bootstrap_peers: Vec<String>,
+}
+
+impl Settings {
+    /// Resolve settings from parsed flags and the config file they point to
+    ///
+    /// An explicit `--config` must exist; the default location is optional.
+    pub fn resolve(args: &Args) -> Result<Self> {
+        let (config, base_dir) = match &args.config {
+            Some(path) => (load(path)?, path.parent().map(Path::to_path_buf)),
+            None => match default_config_path().filter(|p| p.exists()) {
+                Some(path) => (load(&path)?, path.parent().map(Path::to_path_buf)),
+                None => (ConfigFile::default(), None),
+            },
+        };
+
+        let profile = match &args.profile {
+            Some(name) => config.profiles.get(name).cloned()
+                .with_context(|| format!("Profile '{}' not found in config file", name))?,
+            None => {
+                let name = config.default_profile.as_deref().unwrap_or(DEFAULT_PROFILE);
+                config.profiles.get(name).cloned().unwrap_or_default()
+            }
+        };
+
+        // Relative paths in the config file are relative to the file itself
+        let from_config = |path: PathBuf| match &base_dir {
+            Some(dir) if path.is_relative() => dir.join(path),
+            _ => path,
+        };
+
+        let account = args.account.clone()
+            .or_else(|| profile.account.map(from_config))
+            .context("No account given. Use --account or set `account` in a config profile")?;
+
+        let bootstrap_peers = match &args.bootstrap {
+            Some(peer) => vec![peer.clone()],
+            None => profile.bootstrap,
+        };
+
+        Ok(Self {
+            account,
+            relay: args.relay.clone()
+                .or(profile.relay)
+                .unwrap_or_else(|| DEFAULT_RELAY.to_string()),
+            data_dir: args.data_dir.clone().or_else(|| profile.data_dir.map(from_config)),
+            port: args.port.or(profile.port),
+            bootstrap_peers,
+        })
+    }
+}
+
+/// `$XDG_CONFIG_HOME/descord/config.toml`, falling back to `~/.config`
+pub fn default_config_path() -> Option<PathBuf> {
+    let config_home = std::env::var_os("XDG_CONFIG_HOME")
+        .map(PathBuf::from)
+        .or_else(|| std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".config")))?;
+    Some(config_home.join("descord").join("config.toml"))
+}
+
+fn load(path: &Path) -> Result<ConfigFile> {
+    let data = fs::read_to_string(path)
+        .with_context(|| format!("Failed to read config file: {}", path.display()))?;
+    toml::from_str(&data)
+        .with_context(|| format!("Failed to parse config file: {}", path.display()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap::Parser;
+    use tempfile::TempDir;
+
+    const CONFIG: &str = r#"
+default_profile = "local"
+
+[profiles.local]
+account = "alice.key"
+relay = "/ip4/10.0.0.1/tcp/9000"
+bootstrap = ["/ip4/10.0.0.2/tcp/9001"]
+port = 9002
+
+[profiles.remote]
+account = "/keys/bob.key"
+relay = "/dns4/relay.example.org/tcp/9000"
+"#;
+
+    fn resolve(dir: &TempDir, flags: &[&str]) -> Result<Settings> {
+        let config = dir.path().join("config.toml");
+        fs::write(&config, CONFIG).unwrap();
+
+        let mut argv = vec!["spaceway", "--config", config.to_str().unwrap()];
+        argv.extend_from_slice(flags);
+        Settings::resolve(&Args::try_parse_from(argv).unwrap())
+    }
+
+    #[test]
+    fn test_config_relay_used_without_flag() {
+        let dir = TempDir::new().unwrap();
+        let settings = resolve(&dir, &[]).unwrap();
+
+        assert_eq!(settings.relay, "/ip4/10.0.0.1/tcp/9000");
+        assert_eq!(settings.account, dir.path().join("alice.key"));
+        assert_eq!(settings.bootstrap_peers, vec!["/ip4/10.0.0.2/tcp/9001".to_string()]);
+        assert_eq!(settings.port, Some(9002));
+    }
+
+    #[test]
+    fn test_flags_override_config() {
+        let dir = TempDir::new().unwrap();
+        let settings = resolve(&dir, &[
+            "--relay", "/ip4/127.0.0.1/tcp/7000",
+            "--account", "carol.key",
+            "--port", "0",
+        ]).unwrap();
+
+        assert_eq!(settings.relay, "/ip4/127.0.0.1/tcp/7000");
+        assert_eq!(settings.account, PathBuf::from("carol.key"));
+        assert_eq!(settings.port, Some(0));
+    }
+
+    #[test]
+    fn test_named_profile_selection() {
+        let dir = TempDir::new().unwrap();
+        let settings = resolve(&dir, &["--profile", "remote"]).unwrap();
+
+        assert_eq!(settings.relay, "/dns4/relay.example.org/tcp/9000");
+        assert_eq!(settings.account, PathBuf::from("/keys/bob.key"));
+        assert!(settings.bootstrap_peers.is_empty());
+
+        assert!(resolve(&dir, &["--profile", "missing"]).is_err());
+    }
+}
@@ -3,34 +3,42 @@
 //! Usage:
 //!   descord --account alice.key
 //!   descord --account bob.key --relay /ip4/127.0.0.1/tcp/9000
+//!   echo "space create Test" | descord --account alice.key --output json
+//!   descord --profile local   (settings from ~/.config/descord/config.toml)
 
 use anyhow::Result;
 use clap::Parser;
 use colored::Colorize;
 use spaceway_core::{Client, ClientConfig};
 use rustyline::error::ReadlineError;
-use rustyline::DefaultEditor;
+use rustyline::history::DefaultHistory;
+use rustyline::Editor;
 use std::path::PathBuf;
 use std::io::{self, BufRead};
 use tracing::info;
 
 mod account;
 mod commands;
+mod completion;
+mod config;
 mod ui;
 
 use account::AccountManager;
 use commands::CommandHandler;
+use completion::ReplHelper;
+use config::Settings;
+use ui::{say, OutputFormat};
 
 #[derive(Parser, Debug)]
 #[command(author, version, about, long_about = None)]
 struct Args {
     /// Path to account keypair file (will be created if it doesn't exist)
     #[arg(short, long)]
-    account: PathBuf,
+    account: Option<PathBuf>,
 
-    /// Relay node address
-    #[arg(short, long, default_value = "/ip4/127.0.0.1/tcp/9000")]
-    relay: String,
+    /// Relay node address [default: /ip4/127.0.0.1/tcp/9000]
+    #[arg(short, long)]
+    relay: Option<String>,
 
     /// Data directory
     #[arg(short, long)]
@@ -43,12 +51,25 @@ struct Args {
     /// Bootstrap peer multiaddr to connect to (e.g., /ip4/127.0.0.1/tcp/9001/p2p/12D3...)
     #[arg(short = 'b', long)]
     bootstrap: Option<String>,
+
+    /// Output format: human-readable text, or one JSON object per command
+    #[arg(short, long, value_enum, default_value_t = OutputFormat::Text)]
+    output: OutputFormat,
+
+    /// Config file [default: ~/.config/descord/config.toml]
+    #[arg(long)]
+    config: Option<PathBuf>,
+
+    /// Named profile from the config file; flags override its values
+    #[arg(long)]
+    profile: Option<String>,
 }
 
 #[tokio::main]
 async fn main() -> Result<()> {
-    // Initialize tracing
+    // Initialize tracing (on stderr, so stdout stays parseable in JSON mode)
     tracing_subscriber::fmt()
+        .with_writer(io::stderr)
         .with_env_filter(
             tracing_subscriber::EnvFilter::from_default_env()
                 .add_directive(tracing::Level::WARN.into()),
@@ -56,54 +77,51 @@ async fn main() -> Result<()> {
         .init();
 
     let args = Args::parse();
+    ui::set_output_format(args.output);
+    let settings = Settings::resolve(&args)?;
 
     // Print banner with version
-    println!("{}", "=".repeat(60).bright_blue());
-    println!("{}", format!("  {}", spaceway_core::version_string()).bright_cyan().bold());
-    println!("{}", "  Privacy-First Decentralized Communication".bright_white());
-    println!("{}", "=".repeat(60).bright_blue());
-    println!();
+    say!("{}", "=".repeat(60).bright_blue());
+    say!("{}", format!("  {}", spaceway_core::version_string()).bright_cyan().bold());
+    say!("{}", "  Privacy-First Decentralized Communication".bright_white());
+    say!("{}", "=".repeat(60).bright_blue());
+    say!();
 
     // Load or create account
-    let mut account_mgr = AccountManager::new(args.account.clone())?;
+    let mut account_mgr = AccountManager::new(settings.account.clone())?;
     let keypair = account_mgr.load_or_create()?;
     let user_id = keypair.user_id();
 
-    println!("{}", "=".repeat(60).bright_blue());
-    println!("{}", "Descord - Privacy-Preserving Decentralized Forum".bright_cyan().bold());
-    println!("{}", "=".repeat(60).bright_blue());
-    println!();
-    println!("{} {}", "Account:".bright_green(), account_mgr.username());
-    println!("{} {}", "User ID:".bright_green(), hex::encode(&user_id.as_bytes()[..8]));
-    println!("{} {}", "Relay:".bright_green(), args.relay);
-    println!();
+    say!("{}", "=".repeat(60).bright_blue());
+    say!("{}", "Descord - Privacy-Preserving Decentralized Forum".bright_cyan().bold());
+    say!("{}", "=".repeat(60).bright_blue());
+    say!();
+    say!("{} {}", "Account:".bright_green(), account_mgr.username());
+    say!("{} {}", "User ID:".bright_green(), hex::encode(&user_id.as_bytes()[..8]));
+    say!("{} {}", "Relay:".bright_green(), settings.relay);
+    say!();
 
     // Create client with per-user data directory
-    let data_dir = args.data_dir.unwrap_or_else(|| {
+    let data_dir = settings.data_dir.clone().unwrap_or_else(|| {
         // Use account filename (without .key) as the data dir name
-        let account_name = args.account
+        let account_name = settings.account
             .file_stem()
             .and_then(|s| s.to_str())
             .unwrap_or("data");
         PathBuf::from(format!("{}-data", account_name))
     });
 
-    let listen_addrs = if let Some(port) = args.port {
+    let listen_addrs = if let Some(port) = settings.port {
         vec![format!("/ip4/0.0.0.0/tcp/{}", port)]
     } else {
         vec![]
     };
 
-    let bootstrap_peers = if let Some(peer) = args.bootstrap {
-        vec![peer]
-    } else {
-        vec![]
-    };
-
     let config = ClientConfig {
         storage_path: data_dir,
         listen_addrs,
-        bootstrap_peers,
+        bootstrap_peers: settings.bootstrap_peers,
+        ..Default::default()
     };
 
     info!("Creating client with config: {:?}", config);
@@ -120,15 +138,16 @@ async fn main() -> Result<()> {
 
     if is_terminal {
         // Interactive mode with rustyline (history, editing, etc.)
-        run_interactive_mode(&mut handler, &mut account_mgr, args.account).await?;
+        run_interactive_mode(&mut handler, &mut account_mgr, settings.account).await?;
     } else {
         // Piped/non-interactive mode - simple line reading
-        println!("{}", "Running in non-interactive mode (piped input)".bright_yellow());
+        say!("{}", "Running in non-interactive mode (piped input)".bright_yellow());
         run_piped_mode(&mut handler).await?;
     }
 
     // Stop client
     client_handle.abort();
+    handler.shutdown().await;
 
     Ok(())
 }
@@ -140,7 +159,8 @@ async fn run_interactive_mode(
     account_path: PathBuf,
 ) -> Result<()> {
     // Interactive REPL
-    let mut rl = DefaultEditor::new()?;
+    let mut rl: Editor<ReplHelper, DefaultHistory> = Editor::new()?;
+    rl.set_helper(Some(ReplHelper::new()));
     let history_file = account_path.with_extension("history");
     let _ = rl.load_history(&history_file);
 
@@ -151,8 +171,21 @@ async fn run_interactive_mode(
     println!();
 
     loop {
-        let prompt = format!("{}> ", account_mgr.username().bright_cyan());
+        if let Some(helper) = rl.helper() {
+            helper.set_ids(handler.completion_ids().await);
+        }
+
+        let prompt = if handler.is_composing() {
+            format!("{} ", "...".bright_black())
+        } else {
+            format!("{}> ", account_mgr.username().bright_cyan())
+        };
         match rl.readline(&prompt) {
+            Ok(line) if handler.is_composing() => {
+                if let Err(e) = handler.compose_line(&line).await {
+                    ui::print_error(&format!("{}", e));
+                }
+            }
             Ok(line) => {
                 let line = line.trim();
                 if line.is_empty() {
@@ -163,10 +196,10 @@ async fn run_interactive_mode(
 
                 match line {
                     "quit" | "exit" => {
-                        println!("{}", "Goodbye!".bright_green());
+                        say!("{}", "Goodbye!".bright_green());
                         break;
                     }
-                    "help" => {
+                    "help" if !ui::is_json() => {
                         ui::print_help();
                     }
                     _ => {
@@ -178,10 +211,11 @@ async fn run_interactive_mode(
             }
             Err(ReadlineError::Interrupted) => {
                 println!("{}", "^C".yellow());
+                handler.cancel_compose();
                 continue;
             }
             Err(ReadlineError::Eof) => {
-                println!("{}", "Goodbye!".bright_green());
+                say!("{}", "Goodbye!".bright_green());
                 break;
             }
             Err(err) => {
@@ -210,6 +244,14 @@ async fn run_piped_mode(handler: &mut CommandHandler) -> Result<()> {
             }
         };
 
+        // Lines of a `msg` draft are kept verbatim, blank ones included
+        if handler.is_composing() {
+            if let Err(e) = handler.compose_line(&line).await {
+                ui::print_error(&format!("{}", e));
+            }
+            continue;
+        }
+
         let line = line.trim();
         if line.is_empty() {
             continue;
@@ -217,10 +259,10 @@ async fn run_piped_mode(handler: &mut CommandHandler) -> Result<()> {
 
         match line {
             "quit" | "exit" => {
-                println!("{}", "Goodbye!".bright_green());
+                say!("{}", "Goodbye!".bright_green());
                 break;
             }
-            "help" => {
+            "help" if !ui::is_json() => {
                 ui::print_help();
             }
             _ => {
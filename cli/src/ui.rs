@@ -1,12 +1,67 @@
 //! UI utilities for pretty printing
 
 use colored::Colorize;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+
+/// How command results are written to stdout
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, clap::ValueEnum)]
+pub enum OutputFormat {
+    /// Colored, human-readable prose
+    #[default]
+    Text,
+    /// One JSON object per command result, for scripting
+    Json,
+}
+
+static JSON_OUTPUT: AtomicBool = AtomicBool::new(false);
+
+/// First error reported by the running command while in JSON mode
+static PENDING_ERROR: Mutex<Option<String>> = Mutex::new(None);
+
+/// Select the output format for the rest of the session
+pub fn set_output_format(format: OutputFormat) {
+    let json = format == OutputFormat::Json;
+    JSON_OUTPUT.store(json, Ordering::Relaxed);
+    if json {
+        colored::control::set_override(false);
+    }
+}
+
+/// Check if results are being emitted as JSON
+pub fn is_json() -> bool {
+    JSON_OUTPUT.load(Ordering::Relaxed)
+}
+
+/// Take the error reported via `print_error` since the last call, if any
+pub fn take_error() -> Option<String> {
+    PENDING_ERROR.lock().unwrap().take()
+}
+
+/// Print a JSON value as a single line
+pub fn print_json(value: &serde_json::Value) {
+    println!("{}", value);
+}
+
+/// `println!` for human-readable prose; suppressed in JSON mode
+macro_rules! say {
+    ($($arg:tt)*) => {
+        if !$crate::ui::is_json() {
+            println!($($arg)*);
+        }
+    };
+}
+pub(crate) use say;
 
 pub fn print_help() {
+    if is_json() {
+        return;
+    }
     println!();
     println!("{}", "Available Commands:".bright_cyan().bold());
     println!();
     println!("  {:<30} {}", "help".bright_green(), "Show this help message");
+    println!("  {:<30} {}", "help <command>".bright_green(), "Show usage of one command");
     println!("  {:<30} {}", "quit, exit".bright_green(), "Exit the application");
     println!();
     println!("  {}", "Spaces:".bright_yellow().bold());
@@ -17,6 +72,7 @@ pub fn print_help() {
     println!("  {}", "Channels:".bright_yellow().bold());
     println!("  {:<30} {}", "channels".bright_green(), "List channels in current space");
     println!("  {:<30} {}", "channel create <name>".bright_green(), "Create a channel");
+    println!("  {:<30} {}", "channel join <id>".bright_green(), "Join a channel to post in it");
     println!("  {:<30} {}", "channel <id>".bright_green(), "Switch to a channel");
     println!();
     println!("  {}", "Threads:".bright_yellow().bold());
@@ -27,37 +83,55 @@ pub fn print_help() {
     println!("  {}", "Messages:".bright_yellow().bold());
     println!("  {:<30} {}", "messages".bright_green(), "Show messages in current thread");
     println!("  {:<30} {}", "send <text>".bright_green(), "Send a message");
+    println!("  {:<30} {}", "msg".bright_green(), "Compose a multi-line message (end with '.')");
+    println!("  {:<30} {}", "msg --editor".bright_green(), "Compose a message in $EDITOR");
     println!();
     println!("  {}", "Invites:".bright_yellow().bold());
     println!("  {:<30} {}", "invite".bright_green(), "List active invites");
-    println!("  {:<30} {}", "invite create".bright_green(), "Create an invite code");
+    println!("  {:<30} {}", "invite create [code]".bright_green(), "Create an invite code (optionally a custom one)");
     println!("  {:<30} {}", "join <space_id> <code>".bright_green(), "Join with invite code");
+    println!("  {:<30} {}", "join <descord://join/...>".bright_green(), "Join with invite link");
     println!("  {:<30} {}", "join dht <space_id>".bright_green(), "Join from DHT (offline)");
     println!();
     println!("  {}", "Files:".bright_yellow().bold());
-    println!("  {:<30} {}", "upload <file>".bright_green(), "Upload file to current space");
+    println!("  {:<30} {}", "upload <file> [--space <id>]".bright_green(), "Upload file to a space");
+    println!("  {:<30} {}", "download <hash> <out>".bright_green(), "Download file by hash");
+    println!("  {:<30} {}", "export <space_id> <file>".bright_green(), "Export space transcript (.json/.md)");
+    println!("  {:<30} {}", "import <file>".bright_green(), "Import a JSON transcript");
     println!();
     println!("  {}", "Info:".bright_yellow().bold());
     println!("  {:<30} {}", "whoami".bright_green(), "Show current user info");
     println!("  {:<30} {}", "context".bright_green(), "Show current context");
     println!("  {:<30} {}", "network".bright_green(), "Show network status & peer ID");
     println!("  {:<30} {}", "connect <multiaddr>".bright_green(), "Connect to a peer");
+    println!("  {:<30} {}", "peers".bright_green(), "List connected peers");
+    println!("  {:<30} {}", "status".bright_green(), "Show MLS epochs & connection health");
+    println!("  {:<30} {}", "sync-state <space_id>".bright_green(), "Show a space's vector clock vs peers");
+    println!("  {:<30} {}", "relays".bright_green(), "List discovered relays");
+    println!("  {:<30} {}", "relay connect <multiaddr>".bright_green(), "Reserve a slot on a relay");
+    println!("  {:<30} {}", "relay auto".bright_green(), "Connect to the best discovered relay");
+    println!("  {:<30} {}", "relay rotate <secs>|stop".bright_green(), "Start or stop relay rotation");
+    println!("  {:<30} {}", "relay status".bright_green(), "Show the current relay");
     println!("  {:<30} {}", "refresh".bright_green(), "Refresh network status");
     println!();
 }
 
 pub fn print_error(msg: &str) {
+    if is_json() {
+        PENDING_ERROR.lock().unwrap().get_or_insert_with(|| msg.to_string());
+        return;
+    }
     println!("{} {}", "✗".bright_red(), msg.red());
 }
 
 pub fn print_success(msg: &str) {
-    println!("{} {}", "✓".bright_green(), msg.bright_green());
+    say!("{} {}", "✓".bright_green(), msg.bright_green());
 }
 
 pub fn print_info(msg: &str) {
-    println!("{} {}", "ℹ".bright_blue(), msg);
+    say!("{} {}", "ℹ".bright_blue(), msg);
 }
 
 pub fn print_warning(msg: &str) {
-    println!("{} {}", "⚠".bright_yellow(), msg.yellow());
+    say!("{} {}", "⚠".bright_yellow(), msg.yellow());
 }
@@ -0,0 +1,36 @@
+//! Uploading and downloading blobs through the CLI
+
+mod common;
+
+use common::run_json_session;
+use spaceway_core::storage::BlobHash;
+use tempfile::TempDir;
+
+#[test]
+fn test_upload_download_round_trip() {
+    let dir = TempDir::new().unwrap();
+    let contents = b"hello from an attachment\n";
+    let source = dir.path().join("hello.txt");
+    let target = dir.path().join("hello-copy.txt");
+    std::fs::write(&source, contents).unwrap();
+
+    // Blobs are content-addressed, so the hash is known up front
+    let hash = BlobHash::hash(contents).to_hex();
+    let input = format!(
+        "space create Files\nupload {}\ndownload {} {}\nquit\n",
+        source.display(),
+        hash,
+        target.display(),
+    );
+    let results = run_json_session(&dir, &input);
+    assert_eq!(results.len(), 3, "expected one result per command: {:?}", results);
+
+    let uploaded = &results[1];
+    assert_eq!(uploaded["success"], true, "{:?}", uploaded);
+    assert_eq!(uploaded["data"]["hash"], hash.as_str());
+    assert_eq!(uploaded["data"]["mime_type"], "text/plain");
+
+    let downloaded = &results[2];
+    assert_eq!(downloaded["success"], true, "{:?}", downloaded);
+    assert_eq!(std::fs::read(&target).unwrap(), contents);
+}
@@ -0,0 +1,43 @@
+//! Helpers for driving the CLI binary in piped mode
+
+#![allow(dead_code)]
+
+use serde_json::Value;
+use std::io::Write;
+use std::process::{Command, Stdio};
+use tempfile::TempDir;
+
+/// Run the CLI for a fresh account in `dir`, feeding `input` on stdin
+///
+/// Returns everything written to stdout.
+pub fn run_session(dir: &TempDir, extra_args: &[&str], input: &str) -> String {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_spaceway"))
+        .arg("--account").arg(dir.path().join("alice.key"))
+        .arg("--data-dir").arg(dir.path().join("alice-data"))
+        .args(extra_args)
+        .env("XDG_CONFIG_HOME", dir.path())
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .spawn()
+        .expect("failed to start spaceway");
+
+    child.stdin.take().unwrap().write_all(input.as_bytes()).unwrap();
+    let output = child.wait_with_output().unwrap();
+    assert!(output.status.success());
+
+    String::from_utf8_lossy(&output.stdout).into_owned()
+}
+
+/// Run a session with `--output json` and return every result object
+///
+/// Logs go to stderr, so every stdout line must be a JSON result.
+pub fn run_json_session(dir: &TempDir, input: &str) -> Vec<Value> {
+    run_session(dir, &["--output", "json"], input)
+        .lines()
+        .map(|line| {
+            serde_json::from_str(line)
+                .unwrap_or_else(|e| panic!("stdout line is not JSON ({}): {:?}", e, line))
+        })
+        .collect()
+}
@@ -0,0 +1,28 @@
+//! Multi-line messages composed with `msg`
+
+mod common;
+
+use common::run_json_session;
+use tempfile::TempDir;
+
+#[test]
+fn test_msg_keeps_every_line() {
+    let dir = TempDir::new().unwrap();
+    let input = "space create Letters\nchannel create general\nthread create Drafts\n\
+                 msg\nDear all,\n\n  the second paragraph.\n.\nmessages\nquit\n";
+    let results = run_json_session(&dir, input);
+
+    let composing = results.iter().find(|r| r["command"] == "msg" && r["data"]["composing"] == true);
+    assert!(composing.is_some(), "{:?}", results);
+    let sent = results.iter()
+        .find(|r| r["command"] == "msg" && r["data"]["message_id"].is_string())
+        .expect("the draft should be sent on '.'");
+    assert_eq!(sent["success"], true);
+
+    let messages = results.iter().rev().find(|r| r["command"] == "messages").unwrap();
+    let stored = messages["data"]["messages"].as_array().unwrap()
+        .iter()
+        .find(|m| m["message_id"] == sent["data"]["message_id"])
+        .expect("the composed message should be listed");
+    assert_eq!(stored["content"], "Dear all,\n\n  the second paragraph.");
+}
@@ -0,0 +1,37 @@
+//! `--output json` should make piped sessions machine-readable
+
+mod common;
+
+use common::run_json_session;
+use tempfile::TempDir;
+
+#[test]
+fn test_space_create_emits_json_with_space_id() {
+    let dir = TempDir::new().unwrap();
+    let results = run_json_session(&dir, "space create Test\ncontext\nquit\n");
+
+    assert_eq!(results.len(), 2, "expected one result per command: {:?}", results);
+
+    let created = &results[0];
+    assert_eq!(created["command"], "space");
+    assert_eq!(created["success"], true);
+    assert_eq!(created["data"]["name"], "Test");
+    let space_id = created["data"]["space_id"].as_str().unwrap();
+    assert_eq!(space_id.len(), 64);
+    assert!(hex::decode(space_id).is_ok());
+
+    // The new space becomes the current context
+    assert_eq!(results[1]["data"]["space_id"], space_id);
+}
+
+#[test]
+fn test_errors_are_reported_as_json() {
+    let dir = TempDir::new().unwrap();
+    let results = run_json_session(&dir, "channels\nbogus\nquit\n");
+
+    assert_eq!(results.len(), 2);
+    for result in &results {
+        assert_eq!(result["success"], false);
+        assert!(result["error"].is_string());
+    }
+}
@@ -0,0 +1,19 @@
+//! The `relay` management commands
+
+mod common;
+
+use common::{run_json_session, run_session};
+use tempfile::TempDir;
+
+#[test]
+fn test_relay_status_without_relay() {
+    let dir = TempDir::new().unwrap();
+    let stdout = run_session(&dir, &[], "relay status\nquit\n");
+    assert!(stdout.to_lowercase().contains("no relay"), "{}", stdout);
+
+    let dir = TempDir::new().unwrap();
+    let results = run_json_session(&dir, "relay status\nquit\n");
+    assert_eq!(results[0]["success"], true);
+    assert!(results[0]["data"]["relay"].is_null());
+    assert_eq!(results[0]["data"]["circuits"], serde_json::json!([]));
+}
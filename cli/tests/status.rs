@@ -0,0 +1,20 @@
+//! The `status` introspection command
+
+mod common;
+
+use common::run_session;
+use tempfile::TempDir;
+
+#[test]
+fn test_status_shows_space_epoch() {
+    let dir = TempDir::new().unwrap();
+    let stdout = run_session(&dir, &[], "space create Status Check\nstatus\nquit\n");
+
+    // Fresh MLS space: the creator is at epoch 0
+    assert!(stdout.contains("Status Check"), "{}", stdout);
+    assert!(
+        stdout.lines().any(|line| line.trim() == "Epoch: 0"),
+        "status output should contain an epoch line:\n{}", stdout
+    );
+    assert!(stdout.contains("Connected peers: 0"), "{}", stdout);
+}
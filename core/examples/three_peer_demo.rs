@@ -21,6 +21,7 @@ async fn create_peer(name: &str, port: u16) -> (Client, String) {
         listen_port: Some(port),
         relay_address: None,
         bootstrap_peers: vec![],
+        ..Default::default()
     };
     
     let client = Client::new(config).await.unwrap();
@@ -0,0 +1,140 @@
+//! Headless bot accounts
+//!
+//! [`BotClient`] wraps a [`Client`] for automation: handlers registered with
+//! [`BotClient::on_message`] and [`BotClient::on_mention`] run for every
+//! message from another user, driven by the client's event stream, and get a
+//! [`BotContext`] to reply with. Ids stay typed throughout, so a welcome or
+//! moderation bot never has to parse hex.
+
+use crate::client::{Client, ClientEvent};
+use crate::forum::Message;
+use crate::types::*;
+use crate::Result;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+use tokio::sync::broadcast::error::RecvError;
+use tokio::task::JoinHandle;
+
+/// Future returned by a bot handler
+pub type HandlerFuture = Pin<Box<dyn Future<Output = Result<()>> + Send>>;
+
+type Handler = Box<dyn Fn(BotContext, Message) -> HandlerFuture + Send + Sync>;
+
+/// What a handler can act on: the client and the Space the message is in
+#[derive(Clone)]
+pub struct BotContext {
+    /// The bot's client
+    pub client: Arc<Client>,
+    /// Space of the message being handled
+    pub space_id: SpaceId,
+}
+
+impl BotContext {
+    /// Post a Message in the same Thread as `message`
+    pub async fn reply(&self, message: &Message, content: impl Into<String>) -> Result<Message> {
+        let (reply, _) = self.client.post_message(self.space_id, message.thread_id, content.into()).await?;
+        Ok(reply)
+    }
+}
+
+/// Client wrapper that dispatches incoming messages to handlers
+pub struct BotClient {
+    client: Arc<Client>,
+    message_handlers: Vec<Handler>,
+    mention_handlers: Vec<Handler>,
+}
+
+impl BotClient {
+    /// Wrap a client; handlers run once [`BotClient::run`] is called
+    pub fn new(client: Arc<Client>) -> Self {
+        Self {
+            client,
+            message_handlers: Vec::new(),
+            mention_handlers: Vec::new(),
+        }
+    }
+
+    /// The wrapped client
+    pub fn client(&self) -> &Arc<Client> {
+        &self.client
+    }
+
+    /// The bot's user ID
+    pub fn user_id(&self) -> UserId {
+        self.client.user_id()
+    }
+
+    /// Text users write to mention the bot
+    pub fn mention(&self) -> String {
+        format!("@{}", self.user_id().short())
+    }
+
+    /// Run `handler` for every message posted by someone else
+    pub fn on_message<F, Fut>(&mut self, handler: F) -> &mut Self
+    where
+        F: Fn(BotContext, Message) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = Result<()>> + Send + 'static,
+    {
+        self.message_handlers.push(Box::new(move |ctx, message| Box::pin(handler(ctx, message))));
+        self
+    }
+
+    /// Run `handler` for every message from someone else that mentions the bot
+    pub fn on_mention<F, Fut>(&mut self, handler: F) -> &mut Self
+    where
+        F: Fn(BotContext, Message) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = Result<()>> + Send + 'static,
+    {
+        self.mention_handlers.push(Box::new(move |ctx, message| Box::pin(handler(ctx, message))));
+        self
+    }
+
+    /// Dispatch one event to the matching handlers
+    ///
+    /// The bot's own messages are ignored so replies cannot loop.
+    pub async fn handle_event(&self, event: ClientEvent) -> Result<()> {
+        let ClientEvent::MessagePosted { space_id, message } = event else {
+            return Ok(());
+        };
+        let user_id = self.user_id();
+        if message.author == user_id || message.deleted {
+            return Ok(());
+        }
+
+        let ctx = BotContext { client: Arc::clone(&self.client), space_id };
+        for handler in &self.message_handlers {
+            handler(ctx.clone(), message.clone()).await?;
+        }
+        if message.mentions(&user_id) {
+            for handler in &self.mention_handlers {
+                handler(ctx.clone(), message.clone()).await?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Start dispatching events in the background
+    ///
+    /// Subscribes before returning, so every message posted after this call is
+    /// seen. Handler errors are logged and do not stop the bot.
+    pub fn run(self) -> JoinHandle<()> {
+        let mut events = self.client.subscribe_events();
+
+        tokio::spawn(async move {
+            loop {
+                match events.recv().await {
+                    Ok(event) => {
+                        if let Err(e) = self.handle_event(event).await {
+                            tracing::warn!(error = %e, "Bot handler failed");
+                        }
+                    }
+                    Err(RecvError::Lagged(skipped)) => {
+                        tracing::warn!(skipped, "Bot fell behind the event stream");
+                    }
+                    Err(RecvError::Closed) => break,
+                }
+            }
+        })
+    }
+}
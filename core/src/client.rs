@@ -4,14 +4,15 @@
 //! Integrates CRDT operations, MLS encryption, and P2P networking.
 
 use crate::crdt::CrdtOp;
-use crate::crypto::signing::Keypair;
+use crate::crypto::signing::{Keypair, Signer};
 use crate::forum::{Space, SpaceManager, Channel, ChannelManager, Thread, ThreadManager, Message};
 use crate::mls::provider::{create_provider, DescordProvider};
+use crate::mls::{SealedKeyHistory, SpaceKeys};
 use crate::network::{NetworkNode, NetworkEvent};
 use anyhow::Context;
 use crate::storage::Store;
 use crate::types::*;
-use crate::{Error, Result};
+use crate::{Error, JoinError, Result};
 
 use std::path::PathBuf;
 use tokio::sync::{mpsc, RwLock};
@@ -20,6 +21,13 @@ use std::time::{Duration, Instant};
 use tokio::task::JoinHandle;
 use std::collections::VecDeque;
 
+/// Link preview fetched in the background, waiting to be attached
+struct PendingLinkPreview {
+    space_id: SpaceId,
+    message_id: MessageId,
+    preview: crate::forum::LinkPreview,
+}
+
 /// Queued MLS message that failed to decrypt (e.g., due to epoch mismatch)
 #[derive(Debug, Clone)]
 struct PendingMlsMessage {
@@ -33,6 +41,71 @@ struct PendingMlsMessage {
     queued_at: Instant,
 }
 
+/// A DHT record this client has written (value itself is not kept)
+#[derive(Debug, Clone)]
+struct DhtWriteRecord {
+    /// Full DHT key
+    key: Vec<u8>,
+    /// Space the record belongs to (None for user-scoped records)
+    space_id: Option<SpaceId>,
+    /// Kind of value stored
+    value_type: String,
+    /// Size of the stored value in bytes
+    size_bytes: usize,
+}
+
+/// DHT records this client has written, one per key
+///
+/// Every operation batch gets its own key, so an active Space keeps adding
+/// records; past [`MAX_DHT_WRITES`] the least recently written is forgotten.
+#[derive(Debug, Default)]
+struct DhtWrites {
+    /// Records and the sequence number of their last write, by key
+    records: std::collections::HashMap<Vec<u8>, (u64, DhtWriteRecord)>,
+    /// Keys by the sequence number of their last write, oldest first
+    order: std::collections::BTreeMap<u64, Vec<u8>>,
+    next_seq: u64,
+}
+
+impl DhtWrites {
+    /// Remember a write, replacing any earlier write to the same key
+    fn record(&mut self, record: DhtWriteRecord) {
+        let seq = self.next_seq;
+        self.next_seq += 1;
+        self.order.insert(seq, record.key.clone());
+        if let Some((previous, _)) = self.records.insert(record.key.clone(), (seq, record)) {
+            self.order.remove(&previous);
+        }
+        
+        while self.records.len() > MAX_DHT_WRITES {
+            let Some((_, key)) = self.order.pop_first() else { break };
+            self.records.remove(&key);
+        }
+    }
+    
+    /// Records in the order they were last written
+    fn iter(&self) -> impl Iterator<Item = &DhtWriteRecord> {
+        self.order.values().filter_map(|key| self.records.get(key).map(|(_, record)| record))
+    }
+}
+
+/// A stretch of time this client spent removed from a Space's MLS group
+#[derive(Debug, Clone, Copy)]
+struct Absence {
+    /// When the Commit removing us was processed (Unix seconds, like op timestamps)
+    left_at: u64,
+    /// When a Welcome re-added us; `u64::MAX` while still removed
+    rejoined_at: u64,
+}
+
+impl Absence {
+    /// Whether `op` is content posted while we were out, which stays unread after a rejoin
+    fn hides(&self, op: &CrdtOp) -> bool {
+        matches!(op.op_type, crate::crdt::OpType::PostMessage(_) | crate::crdt::OpType::EditMessage(_))
+            && (self.left_at..self.rejoined_at).contains(&op.timestamp)
+    }
+}
+
 /// Information about a peer discovered in a space
 #[derive(Debug, Clone)]
 pub struct SpacePeerInfo {
@@ -43,6 +116,772 @@ pub struct SpacePeerInfo {
     pub relay_address: String,
 }
 
+/// Something that happened in a Space this client follows
+#[derive(Debug, Clone)]
+pub enum ClientEvent {
+    /// A message was posted, locally or by a peer
+    MessagePosted {
+        /// Space the message belongs to
+        space_id: SpaceId,
+        /// The message as applied locally
+        message: Message,
+    },
+    /// A message was edited, redacted, or gained a link preview or reaction
+    MessageUpdated {
+        /// Space the message belongs to
+        space_id: SpaceId,
+        /// The message after the change
+        message: Message,
+    },
+    /// An MLS-encrypted message in a Space could not be decrypted
+    DecryptionFailed {
+        /// Space the message was published in
+        space_id: SpaceId,
+        /// Why it failed, and whether it will be retried
+        reason: DecryptFailReason,
+    },
+    /// A Welcome re-added this client to a Space it had been removed from
+    /// 
+    /// Connected peers are asked for missed ops right away; call
+    /// [`Client::sync_space_from_dht`] to also recover history from the DHT.
+    /// Messages posted while this client was out stay hidden.
+    Rejoined {
+        /// Space rejoined
+        space_id: SpaceId,
+        /// Last epoch of the group this client was removed from
+        previous_epoch: EpochId,
+        /// Epoch the Welcome joined at
+        epoch: EpochId,
+    },
+    /// A Space was announced on the discovery topic
+    /// 
+    /// Unless `ClientConfig::auto_subscribe_discovered` is set, its topic is
+    /// not joined; call [`Client::subscribe_to_space`] to follow it.
+    SpaceDiscovered {
+        /// Space announced
+        space_id: SpaceId,
+        /// Its name at creation
+        name: String,
+    },
+    /// An op has waited on missing dependencies longer than
+    /// `ClientConfig::op_stuck_after`
+    /// 
+    /// Connected peers are asked for the Space's missing ops once per stuck op.
+    OpStuck(crate::crdt::StuckOp),
+}
+
+/// Why an MLS-encrypted message could not be decrypted
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DecryptFailReason {
+    /// Sent in an epoch this client hasn't reached yet; Space messages are
+    /// queued and retried once the Commit or Welcome arrives
+    WrongEpoch,
+    /// This client is no longer (or never was) in the group
+    NotAMember,
+    /// The message is malformed or fails authentication
+    Corrupt,
+}
+
+impl DecryptFailReason {
+    /// Whether the failure may resolve on its own once this client catches up
+    pub fn is_transient(&self) -> bool {
+        matches!(self, Self::WrongEpoch)
+    }
+    
+    /// What a UI can offer the user to recover, if anything
+    pub fn suggestion(&self) -> Option<&'static str> {
+        match self {
+            Self::NotAMember => Some("Re-sync with sync_space_from_dht, or ask for a new invite to rejoin the Space"),
+            Self::WrongEpoch | Self::Corrupt => None,
+        }
+    }
+}
+
+/// Classify a failed decryption by `group`
+/// 
+/// Membership wins over the error itself: a removed member that missed the
+/// Commit only sees messages from a future epoch, which would otherwise be
+/// queued forever.
+fn decrypt_fail_reason(group: &crate::mls::MlsGroup, is_member: bool, error: &Error) -> DecryptFailReason {
+    if !is_member || !group.is_active() {
+        DecryptFailReason::NotAMember
+    } else if format!("{:?}", error).contains("WrongEpoch") {
+        DecryptFailReason::WrongEpoch
+    } else {
+        DecryptFailReason::Corrupt
+    }
+}
+
+/// Events kept for subscribers that fall behind
+const EVENT_CHANNEL_CAPACITY: usize = 256;
+
+/// KeyPackages published to the DHT per call
+const DHT_KEY_PACKAGES: usize = 5;
+
+/// DHT writes remembered for the dashboard
+const MAX_DHT_WRITES: usize = 4096;
+
+/// How often ops whose DHT upload failed are retried (also retried on connect)
+const DHT_RETRY_INTERVAL: Duration = Duration::from_secs(5);
+
+/// How often unsent ops are republished while any are queued (also on connect)
+const OUTBOX_RETRY_INTERVAL: Duration = Duration::from_secs(2);
+
+/// How often the holdback queues are checked for stuck ops
+const HOLDBACK_CHECK_INTERVAL: Duration = Duration::from_secs(5);
+
+/// How long to wait for a circuit to an invite's creator before the next hint
+const INVITE_HINT_DIAL_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Event for the message an applied op created or changed, if any
+fn message_event(manager: &ThreadManager, op: &CrdtOp) -> Option<ClientEvent> {
+    use crate::crdt::{OpPayload, OpType};
+    let (message_id, posted) = match &op.op_type {
+        OpType::PostMessage(OpPayload::PostMessage { message_id, .. }) => (*message_id, true),
+        OpType::CreateThread(OpPayload::CreateThread { first_message_id, .. }) => (*first_message_id, true),
+        OpType::ForwardMessage(OpPayload::ForwardMessage { message_id, .. }) => (*message_id, true),
+        OpType::EditMessage(OpPayload::EditMessage { message_id, .. })
+        | OpType::RedactMessage(OpPayload::RedactMessage { message_id })
+        | OpType::AttachLinkPreview(OpPayload::AttachLinkPreview { message_id, .. })
+        | OpType::AddReaction(OpPayload::AddReaction { message_id, .. }) => (*message_id, false),
+        _ => return None,
+    };
+    let message = manager.get_message(&message_id)?.clone();
+    let space_id = op.space_id;
+    Some(if posted {
+        ClientEvent::MessagePosted { space_id, message }
+    } else {
+        ClientEvent::MessageUpdated { space_id, message }
+    })
+}
+
+/// Message, thread and preview image to purge once a redaction is applied
+type RedactionTarget = (MessageId, ThreadId, Option<crate::storage::BlobHash>);
+
+/// Capture what a redaction must purge, before the content is dropped
+fn redaction_target(manager: &ThreadManager, message_id: &MessageId) -> Option<RedactionTarget> {
+    manager.get_message(message_id).map(|message| (
+        *message_id,
+        message.thread_id,
+        message.link_preview.as_ref().and_then(|preview| preview.image_blob),
+    ))
+}
+
+/// Finish a redaction locally: persist the scrubbed ops and drop the blobs
+fn purge_redacted(
+    store: &Store,
+    storage: &crate::storage::Storage,
+    manager: &ThreadManager,
+    (message_id, thread_id, preview_image): RedactionTarget,
+) -> Result<()> {
+    for op in manager.message_ops(&message_id) {
+        store.put_op(op)?;
+    }
+    storage.purge_message(&thread_id, &message_id)?;
+    storage.add_tombstone(&thread_id, &message_id)?;
+    if let Some(hash) = preview_image {
+        storage.purge_blob(&hash)?;
+    }
+    Ok(())
+}
+
+/// Delete everything stored locally for one Space
+///
+/// Shared by [`Client::purge_space`] and received `DeleteSpace` ops.
+async fn purge_space_data(
+    store: &Store,
+    storage: &crate::storage::Storage,
+    space_manager: &RwLock<SpaceManager>,
+    channel_manager: &RwLock<ChannelManager>,
+    thread_manager: &RwLock<ThreadManager>,
+    mls_provider: &RwLock<DescordProvider>,
+    space_id: SpaceId,
+) -> Result<()> {
+    let stored_ops = store.get_space_ops(&space_id)?;
+    
+    let (mut thread_ids, mut message_ids) = {
+        let mut manager = thread_manager.write().await;
+        manager.remove_space(&space_id)
+    };
+    
+    // Stored ops may reference threads and messages that were never loaded
+    for op in &stored_ops {
+        thread_ids.extend(op.thread_id);
+        match &op.op_type {
+            crate::crdt::OpType::CreateThread(crate::crdt::OpPayload::CreateThread { first_message_id, .. }) => {
+                message_ids.push(*first_message_id);
+            }
+            crate::crdt::OpType::PostMessage(crate::crdt::OpPayload::PostMessage { message_id, .. }) => {
+                message_ids.push(*message_id);
+            }
+            _ => {}
+        }
+    }
+    thread_ids.sort_by_key(|id| id.0);
+    thread_ids.dedup();
+    message_ids.sort_by_key(|id| id.0);
+    message_ids.dedup();
+    
+    storage.purge_space(&space_id, &thread_ids, &message_ids)?;
+    let removed_ops = store.purge_space(&space_id)?;
+    
+    {
+        let provider = mls_provider.read().await;
+        channel_manager.write().await.remove_space(&space_id, &provider)?;
+        space_manager.write().await.remove_space(&space_id, &provider)?;
+    }
+    
+    tracing::info!(
+        space_id = %space_id.short(),
+        ops = removed_ops,
+        threads = thread_ids.len(),
+        messages = message_ids.len(),
+        "Purged Space"
+    );
+    
+    Ok(())
+}
+
+/// Apply a received `DeleteSpace` op: tombstone the Space and purge it
+///
+/// The op itself is stored again after the purge, so this replica keeps a
+/// record of the deletion to pass on when peers sync from it.
+async fn apply_space_deletion(
+    store: &Store,
+    storage: &crate::storage::Storage,
+    space_manager: &RwLock<SpaceManager>,
+    channel_manager: &RwLock<ChannelManager>,
+    thread_manager: &RwLock<ThreadManager>,
+    mls_provider: &RwLock<DescordProvider>,
+    op: &CrdtOp,
+) -> Result<()> {
+    space_manager.write().await.process_delete_space(op)?;
+    purge_space_data(store, storage, space_manager, channel_manager, thread_manager, mls_provider, op.space_id).await?;
+    store.put_op(op)?;
+    Ok(())
+}
+
+/// Fetch a Space's operation index, or `None` if none is stored
+async fn dht_get_index(network: &mut NetworkNode, space_id: &SpaceId) -> Result<Option<crate::crdt::OperationBatchIndex>> {
+    let index_key = crate::crdt::OperationBatchIndex::compute_dht_key(space_id);
+    match network.dht_get(index_key).await {
+        Ok(values) if !values.is_empty() => Ok(Some(crate::crdt::OperationBatchIndex::from_bytes(&values[0])?)),
+        _ => Ok(None),
+    }
+}
+
+/// Fetch and decrypt one operation batch, or `None` if it is not found
+async fn dht_get_batch(
+    network: &mut NetworkNode,
+    keys: &SpaceKeys,
+    space_id: &SpaceId,
+    sequence: u32,
+) -> Result<Option<crate::crdt::OperationBatch>> {
+    let batch_key = crate::crdt::EncryptedOperationBatch::compute_dht_key(space_id, sequence);
+    let values = match network.dht_get(batch_key).await {
+        Ok(values) if !values.is_empty() => values,
+        _ => return Ok(None),
+    };
+    let batch = crate::crdt::EncryptedOperationBatch::from_bytes(&values[0])?.open(keys)?;
+    if batch.space_id != *space_id {
+        return Err(Error::InvalidOperation("Space ID mismatch in batch".to_string()));
+    }
+    Ok(Some(batch))
+}
+
+/// Remember a DHT record a client wrote, replacing any earlier write to the same key
+async fn record_dht_write(
+    writes: &RwLock<DhtWrites>,
+    key: &[u8],
+    space_id: Option<SpaceId>,
+    value_type: &str,
+    size_bytes: usize,
+) {
+    writes.write().await.record(DhtWriteRecord {
+        key: key.to_vec(),
+        space_id,
+        value_type: value_type.to_string(),
+        size_bytes,
+    });
+}
+
+/// Seal a batch for the Space's members and store it under its sequence's key
+async fn dht_put_batch(
+    network: &mut NetworkNode,
+    writes: &RwLock<DhtWrites>,
+    keys: &SpaceKeys,
+    batch: &crate::crdt::OperationBatch,
+) -> Result<()> {
+    let encrypted = crate::crdt::EncryptedOperationBatch::seal(batch, keys)?;
+    let batch_key = encrypted.dht_key();
+    let batch_bytes = encrypted.to_bytes()?;
+    record_dht_write(writes, &batch_key, Some(batch.space_id), &format!("operation_batch #{}", batch.sequence), batch_bytes.len()).await;
+    network.dht_put(batch_key, batch_bytes).await
+}
+
+/// Append operations to a Space's DHT log (see [`Client::dht_put_operations`])
+async fn dht_append_operations(
+    network: &mut NetworkNode,
+    writes: &RwLock<DhtWrites>,
+    keys: &SpaceKeys,
+    space_id: &SpaceId,
+    ops: Vec<CrdtOp>,
+) -> Result<()> {
+    use crate::crdt::OperationBatchIndex;
+    
+    tracing::debug!("Storing operations in DHT");
+    
+    if ops.is_empty() {
+        tracing::debug!("Empty ops, returning early");
+        return Ok(());
+    }
+    
+    let index_key = OperationBatchIndex::compute_dht_key(space_id);
+    let mut index = match dht_get_index(network, space_id).await? {
+        Some(index) => index,
+        None => {
+            tracing::debug!("Creating new operation index");
+            OperationBatchIndex::new(*space_id)
+        }
+    };
+    
+    // Merge into the tail batch if it has room
+    let tail = match index.batch_sequences.last() {
+        Some(sequence) => dht_get_batch(network, keys, space_id, *sequence).await?,
+        None => None,
+    };
+    let batch = index.append(tail, ops);
+    dht_put_batch(network, writes, keys, &batch).await?;
+    
+    // Only compact when every batch is reachable, or their ops would be lost
+    if index.needs_compaction() {
+        let mut batches = Vec::with_capacity(index.batch_sequences.len());
+        for sequence in index.batch_sequences.clone() {
+            match dht_get_batch(network, keys, space_id, sequence).await? {
+                Some(batch) => batches.push(batch),
+                None => break,
+            }
+        }
+        if batches.len() == index.batch_sequences.len() {
+            let before = batches.len();
+            for batch in index.compact(batches) {
+                dht_put_batch(network, writes, keys, &batch).await?;
+            }
+            tracing::debug!(before, after = index.batch_sequences.len(), "Compacted operation index");
+        }
+    }
+    
+    let index_bytes = index.to_bytes()?;
+    record_dht_write(writes, &index_key, Some(*space_id), "operation_batch_index", index_bytes.len()).await;
+    network.dht_put(index_key, index_bytes).await?;
+    
+    tracing::debug!(sequence = batch.sequence, count = batch.count, "Stored operation batch in DHT");
+    
+    Ok(())
+}
+
+/// DHT key a user's KeyPackages are published under: SHA256("keypackage:" + user_id_hex)
+fn key_package_dht_key(user_id: &UserId) -> Vec<u8> {
+    use sha2::{Sha256, Digest};
+
+    let mut hasher = Sha256::new();
+    hasher.update(b"keypackage:");
+    hasher.update(hex::encode(&user_id.0).as_bytes());
+    hasher.finalize().to_vec()
+}
+
+/// DHT key a channel MLS group's GroupInfo is published under: SHA256("channel-group-info:" + channel_id_hex)
+fn channel_group_info_dht_key(channel_id: &ChannelId) -> Vec<u8> {
+    use sha2::{Sha256, Digest};
+
+    let mut hasher = Sha256::new();
+    hasher.update(b"channel-group-info:");
+    hasher.update(hex::encode(channel_id.0).as_bytes());
+    hasher.finalize().to_vec()
+}
+
+/// Publish the current GroupInfo of a channel's MLS group to the DHT
+///
+/// Members republish it whenever the group changes epoch, since an
+/// external commit against a stale GroupInfo is rejected.
+async fn dht_put_channel_group_info(
+    mls_provider: &RwLock<DescordProvider>,
+    channel_manager: &RwLock<ChannelManager>,
+    network: &RwLock<NetworkNode>,
+    writes: &RwLock<DhtWrites>,
+    channel_id: &ChannelId,
+) -> Result<()> {
+    let (space_id, value) = {
+        let provider = mls_provider.read().await;
+        let manager = channel_manager.read().await;
+        let space_id = manager.get_channel(channel_id)
+            .ok_or_else(|| Error::NotFound(format!("Channel {:?} not found", channel_id)))?
+            .space_id;
+        let group = manager.get_mls_group(channel_id)
+            .filter(|group| group.is_active())
+            .ok_or_else(|| Error::NotFound(format!("Not in the MLS group of channel {}", channel_id.short())))?;
+        (space_id, group.export_group_info(&provider)?)
+    };
+
+    let key = channel_group_info_dht_key(channel_id);
+    record_dht_write(writes, &key, Some(space_id), "channel_group_info", value.len()).await;
+    network.write().await.dht_put(key, value).await
+}
+
+/// Seal every epoch key a Space's MLS group knows under its current epoch and store it
+///
+/// Called after this client commits, so the members it admitted can open
+/// records sealed before their Welcome.
+async fn dht_put_key_history(
+    space_manager: &RwLock<SpaceManager>,
+    network: &RwLock<NetworkNode>,
+    writes: &RwLock<DhtWrites>,
+    space_id: &SpaceId,
+) -> Result<()> {
+    let value = {
+        let manager = space_manager.read().await;
+        let group = manager.get_mls_group(space_id)
+            .ok_or_else(|| Error::NotFound(format!("No MLS group for Space {}", space_id.short())))?;
+        SealedKeyHistory::seal(*space_id, group)?.to_bytes()?
+    };
+    
+    let key = SealedKeyHistory::dht_key(space_id);
+    record_dht_write(writes, &key, Some(*space_id), "key_history", value.len()).await;
+    network.write().await.dht_put(key, value).await
+}
+
+/// A Space's keys for `label`, importing past epochs' keys from the DHT if some are missing
+///
+/// The DHT is only asked when `dht_enabled`. Lightweight Spaces get
+/// `SpaceKeys::Public`. Any other Space we hold no MLS group for is an
+/// error, so nothing meant for members is sealed with the public key;
+/// readers fall back to `Public`, which still opens records sealed that way.
+async fn dht_space_keys(
+    space_manager: &RwLock<SpaceManager>,
+    network: &RwLock<NetworkNode>,
+    dht_enabled: bool,
+    space_id: &SpaceId,
+    label: &str,
+) -> Result<SpaceKeys> {
+    let missing_epochs = {
+        let manager = space_manager.read().await;
+        if manager.get_space(space_id).is_some_and(|space| space.membership_mode.is_lightweight()) {
+            return Ok(SpaceKeys::Public);
+        }
+        manager.get_mls_group(space_id)
+            .ok_or_else(|| Error::NotFound(format!("No MLS group for Space {}", space_id.short())))?
+            .is_missing_epochs()
+    };
+    
+    if missing_epochs && dht_enabled {
+        let values = network.write().await
+            .dht_get(SealedKeyHistory::dht_key(space_id)).await
+            .unwrap_or_default();
+        let mut manager = space_manager.write().await;
+        if let Some(group) = manager.get_mls_group_mut(space_id) {
+            for value in values {
+                let opened = SealedKeyHistory::from_bytes(&value).and_then(|history| history.open(group));
+                match opened {
+                    Ok(keys) => group.import_epoch_keys(keys),
+                    Err(e) => tracing::debug!(space_id = %space_id.short(), error = %e, "Could not open key history"),
+                }
+            }
+        }
+    }
+    
+    let manager = space_manager.read().await;
+    manager.get_mls_group(space_id)
+        .map(|group| group.space_keys(label))
+        .ok_or_else(|| Error::NotFound(format!("No MLS group for Space {}", space_id.short())))
+}
+
+/// Error for DHT calls made while `ClientConfig::dht_enabled` is off
+fn dht_disabled() -> Error {
+    Error::Network("DHT is disabled (ClientConfig::dht_enabled)".to_string())
+}
+
+/// Add a member who joined through an invite to the Space's MLS group
+///
+/// Only the Space owner admits, so two admins never race conflicting
+/// Commits for the same joiner. Returns `false` when there is nothing for
+/// this replica to do (not the owner, no MLS group, or already a member).
+/// The joiner's KeyPackage is fetched from the DHT; the Commit goes to the
+/// Space topic and the Welcome to the joiner's welcome topic.
+async fn admit_to_space_mls(
+    space_manager: &RwLock<SpaceManager>,
+    network: &RwLock<NetworkNode>,
+    dht_writes: &RwLock<DhtWrites>,
+    mls_provider: &RwLock<DescordProvider>,
+    admin: UserId,
+    space_id: SpaceId,
+    joiner: UserId,
+) -> Result<bool> {
+    let role = {
+        let manager = space_manager.read().await;
+        let Some(space) = manager.get_space(&space_id) else {
+            return Ok(false);
+        };
+        let Some(group) = manager.get_mls_group(&space_id) else {
+            return Ok(false);
+        };
+        if space.owner != admin || joiner == admin || group.members_with_roles().contains_key(&joiner) {
+            return Ok(false);
+        }
+        space.get_role(&joiner).unwrap_or(Role::Member)
+    };
+
+    let values = network.write().await.dht_get(key_package_dht_key(&joiner)).await?;
+    let bundles: Vec<crate::mls::KeyPackageBundle> = match values.first() {
+        Some(bytes) => serde_json::from_slice(bytes)
+            .map_err(|e| Error::Serialization(format!("Failed to deserialize KeyPackages: {}", e)))?,
+        None => Vec::new(),
+    };
+    let bundle = bundles.into_iter().next()
+        .ok_or_else(|| Error::NotFound(format!("No KeyPackages found for user {}", joiner)))?;
+
+    let (commit_msg, welcome_msg) = {
+        let provider = mls_provider.read().await;
+        let key_package = crate::mls::KeyPackageStore::deserialize_key_package(&bundle, &provider)?;
+        let mut manager = space_manager.write().await;
+        manager.add_member_with_mls(&space_id, joiner, role, key_package, &admin, &provider)?
+    };
+
+    let commit_bytes = commit_msg.to_bytes()
+        .map_err(|e| Error::Serialization(format!("Failed to serialize Commit: {:?}", e)))?;
+    let welcome_bytes = welcome_msg.to_bytes()
+        .map_err(|e| Error::Serialization(format!("Failed to serialize Welcome: {:?}", e)))?;
+
+    {
+        let mut network = network.write().await;
+        let space_topic = format!("space/{}", space_id.short());
+        if let Err(e) = network.publish(&space_topic, commit_bytes).await {
+            tracing::warn!(error = %e, "Could not publish Commit for invited member");
+        }
+        let welcome_topic = format!("user/{}/welcome", joiner.short());
+        network.publish(&welcome_topic, welcome_bytes).await?;
+    }
+    
+    if let Err(e) = dht_put_key_history(space_manager, network, dht_writes, &space_id).await {
+        tracing::warn!(space_id = %space_id.short(), error = %e, "Could not store key history for invited member");
+    }
+
+    Ok(true)
+}
+
+/// Apply a received `DeleteChannel` op
+///
+/// The author must hold DELETE_CHANNELS in the Space. The Channel is
+/// tombstoned and its MLS group torn down, then its threads and messages
+/// are dropped.
+async fn apply_channel_deletion(
+    space_manager: &RwLock<SpaceManager>,
+    channel_manager: &RwLock<ChannelManager>,
+    thread_manager: &RwLock<ThreadManager>,
+    mls_provider: &RwLock<DescordProvider>,
+    op: &CrdtOp,
+) -> Result<()> {
+    let channel_id = op.channel_id
+        .ok_or_else(|| Error::InvalidOperation("Missing channel_id".to_string()))?;
+    {
+        let manager = space_manager.read().await;
+        let space = manager.get_space(&op.space_id)
+            .ok_or_else(|| Error::NotFound(format!("Space {:?} not found", op.space_id)))?;
+        if !space.can_delete_channels(&op.author) {
+            return Err(Error::Permission(format!(
+                "User {} lacks DELETE_CHANNELS in space {}", op.author, op.space_id
+            )));
+        }
+    }
+    
+    {
+        let provider = mls_provider.read().await;
+        channel_manager.write().await.process_delete_channel(op, &provider)?;
+    }
+    thread_manager.write().await.remove_channel(&channel_id);
+    Ok(())
+}
+
+/// Ops held back in any manager's holdback queue, oldest first
+async fn holdback_status(
+    space_manager: &RwLock<SpaceManager>,
+    channel_manager: &RwLock<ChannelManager>,
+    thread_manager: &RwLock<ThreadManager>,
+) -> Vec<crate::crdt::StuckOp> {
+    let now = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap()
+        .as_secs();
+    let mut stuck = space_manager.read().await.stuck_ops(now);
+    stuck.extend(channel_manager.read().await.stuck_ops(now));
+    stuck.extend(thread_manager.read().await.stuck_ops(now));
+    stuck.sort_by(|a, b| b.age.cmp(&a.age).then(a.op_id.0.cmp(&b.op_id.0)));
+    stuck
+}
+
+/// Marks an op re-sent by someone other than its author: `[RELAYED_MARKER][sealed op]`
+/// 
+/// The inner frame is what [`seal_op`] produces, so the op stays sealed for
+/// its group. Receivers skip the MLS-sender-is-author check on these and
+/// rely on the op's own signature instead.
+const RELAYED_MARKER: u8 = 0x03;
+
+/// Re-publish stored ops in a [`RELAYED_MARKER`] frame
+async fn republish_ops(
+    network: &RwLock<NetworkNode>,
+    mls_provider: &RwLock<DescordProvider>,
+    channel_manager: &RwLock<ChannelManager>,
+    space_manager: &RwLock<SpaceManager>,
+    topic: &str,
+    ops: Vec<CrdtOp>,
+) {
+    for op in ops {
+        let data = match seal_op(mls_provider, channel_manager, space_manager, &op, &op.to_canonical_bytes()).await {
+            Ok(sealed) => {
+                let mut data = vec![RELAYED_MARKER];
+                data.extend_from_slice(&sealed);
+                data
+            }
+            Err(e) => {
+                tracing::warn!(op_id = ?op.op_id, error = %e, "Failed to seal op for sync answer");
+                continue;
+            }
+        };
+        let _ = network.write().await.publish(topic, data).await;
+        tokio::time::sleep(Duration::from_millis(10)).await;
+    }
+}
+
+/// Apply a forward of `message_id` into `target_thread` by `user_id`
+///
+/// Checks the target is writable and visible and that `user_id` is a
+/// member of both Spaces. Returns the target Space, the copy and its op,
+/// which the caller stores and sends. Shared by [`Client::forward_message`]
+/// and the announcement relay.
+#[allow(clippy::too_many_arguments)]
+async fn forward_to_thread(
+    space_manager: &RwLock<SpaceManager>,
+    channel_manager: &RwLock<ChannelManager>,
+    thread_manager: &RwLock<ThreadManager>,
+    signer: &dyn Signer,
+    user_id: UserId,
+    show_nsfw: bool,
+    message_id: MessageId,
+    target_thread: ThreadId,
+) -> Result<(SpaceId, Message, CrdtOp)> {
+    let (source_space, target_space, target_channel) = {
+        let manager = thread_manager.read().await;
+        let original = manager.get_message(&message_id)
+            .ok_or_else(|| Error::NotFound(format!("Message {:?} not found", message_id)))?;
+        let thread_of = |thread_id: &ThreadId| manager.get_thread(thread_id)
+            .ok_or_else(|| Error::NotFound(format!("Thread {:?} not found", thread_id)));
+        let target = thread_of(&target_thread)?;
+        (thread_of(&original.thread_id)?.space_id, target.space_id, target.channel_id)
+    };
+    space_manager.read().await.check_writable(&target_space)?;
+    
+    if !show_nsfw && channel_manager.read().await.get_channel(&target_channel).is_some_and(|channel| channel.nsfw) {
+        return Err(Error::Permission(
+            "Channel is marked NSFW; enable show_nsfw to post here".to_string()
+        ));
+    }
+    
+    let epoch = {
+        let space_manager = space_manager.read().await;
+        for space_id in [source_space, target_space] {
+            let space = space_manager.get_space(&space_id)
+                .ok_or_else(|| Error::NotFound(format!("Space {:?} not found", space_id)))?;
+            if !space.is_member(&user_id) {
+                return Err(Error::Permission(
+                    "Forwarding requires membership in both Spaces".to_string()
+                ));
+            }
+        }
+        space_manager.get_space(&target_space)
+            .map(|space| space.epoch)
+            .ok_or_else(|| Error::NotFound(format!("Space {:?} not found", target_space)))?
+    };
+    
+    let timestamp = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap()
+        .as_secs();
+    let new_id = MessageId::from_content(&user_id, &target_thread, &message_id.0, timestamp, None);
+    
+    let mut manager = thread_manager.write().await;
+    let op = manager.forward_message(new_id, message_id, target_thread, user_id, signer, epoch)?;
+    let message = manager.get_message(&new_id)
+        .ok_or_else(|| Error::NotFound(format!("Message {:?} not found", new_id)))?
+        .clone();
+    Ok((target_space, message, op))
+}
+
+/// Error for MLS work on a client whose identity signer can't sign for MLS
+fn no_mls_signer() -> Error {
+    Error::Crypto("Identity signer can't export an MLS signature key".to_string())
+}
+
+/// A connected peer picked at random to answer a sync request
+async fn pick_responder(network: &RwLock<NetworkNode>) -> Option<libp2p::PeerId> {
+    use rand::seq::SliceRandom;
+    let peers = network.read().await.connected_peers().await;
+    peers.choose(&mut rand::thread_rng()).copied()
+}
+
+/// Frame a serialized op for GossipSub, MLS-encrypting it when possible
+/// 
+/// Channel-level encryption wins over Space-level; without either group
+/// the op is sent as plaintext. The provider lock is taken before a
+/// manager lock and encryption itself is synchronous, so no manager lock
+/// is ever held across an `.await`.
+async fn seal_op(
+    mls_provider: &RwLock<DescordProvider>,
+    channel_manager: &RwLock<ChannelManager>,
+    space_manager: &RwLock<SpaceManager>,
+    op: &CrdtOp,
+    op_bytes: &[u8],
+) -> Result<Vec<u8>> {
+    let provider = mls_provider.read().await;
+    
+    if let Some(channel_id) = &op.channel_id {
+        let mut channel_manager = channel_manager.write().await;
+        if let Some(mls_group) = channel_manager.get_mls_group_mut(channel_id) {
+            tracing::debug!("Encrypting with channel MLS group");
+            // 0x02 indicates channel-level encryption
+            return frame_encrypted(0x02, &channel_id.0, mls_group, op_bytes, &provider);
+        }
+    }
+    
+    let mut space_manager = space_manager.write().await;
+    if let Some(mls_group) = space_manager.get_mls_group_mut(&op.space_id) {
+        tracing::debug!("Encrypting with Space MLS group");
+        // The space_id is needed for decryption on the receive side
+        return frame_encrypted(0x01, &op.space_id.0, mls_group, op_bytes, &provider);
+    }
+    
+    tracing::debug!("No MLS group, using plaintext");
+    let mut data = vec![0x00];
+    data.extend_from_slice(op_bytes);
+    Ok(data)
+}
+
+/// Encrypt op bytes for an MLS group as `[marker][group id (32 bytes)][MLS message]`
+fn frame_encrypted(
+    marker: u8,
+    group_id: &[u8; 32],
+    mls_group: &mut crate::mls::MlsGroup,
+    op_bytes: &[u8],
+    provider: &DescordProvider,
+) -> Result<Vec<u8>> {
+    let encrypted_bytes = mls_group.encrypt_application_message(op_bytes, provider)?
+        .to_bytes()
+        .map_err(|e| Error::Serialization(format!("Failed to serialize MLS message: {}", e)))?;
+    
+    let mut data = vec![marker];
+    data.extend_from_slice(group_id);
+    data.extend_from_slice(&encrypted_bytes);
+    Ok(data)
+}
+
 /// Client configuration
 #[derive(Debug, Clone)]
 pub struct ClientConfig {
@@ -54,6 +893,58 @@ pub struct ClientConfig {
     
     /// Bootstrap peers for DHT
     pub bootstrap_peers: Vec<String>,
+    
+    /// Read and post in channels flagged NSFW / age-restricted
+    pub show_nsfw: bool,
+    
+    /// Blob layout and quota (per-Space directories, max size, eviction policy)
+    pub storage: crate::storage::StorageConfig,
+    
+    /// Maximum op size, and per-author rate of ops accepted from gossip
+    pub op_limits: crate::crdt::OpLimits,
+    
+    /// Acknowledge received ops and collect acks for sent ones (`delivery_status`)
+    pub delivery_acks: bool,
+    
+    /// Largest message content accepted, in bytes; bigger content belongs in an attachment
+    pub max_message_bytes: usize,
+    
+    /// GossipSub heartbeat and mesh parameters
+    pub gossip: crate::network::GossipConfig,
+    
+    /// Idle connection timeout and keepalive pings
+    pub connection: crate::network::ConnectionConfig,
+    
+    /// Exchange known peers with other members of shared Spaces and dial some (`known_peers`)
+    pub peer_exchange: bool,
+    
+    /// Announce ourselves in subscribed Spaces and track who else is online (`last_seen`)
+    pub presence: bool,
+    
+    /// How often subscribed Spaces are reconciled with a random connected peer (`None` disables)
+    pub anti_entropy_interval: Option<Duration>,
+    
+    /// Unused KeyPackages generated at startup, and refilled to once fewer
+    /// than half remain (0 generates them only when one is needed)
+    pub key_package_pool: usize,
+    
+    /// Recently received op IDs remembered in memory so duplicates skip the
+    /// store lookup (0 checks every received op against the store)
+    pub dedup_cache_capacity: usize,
+    
+    /// Subscribe to every Space announced on the discovery topic; when off,
+    /// each is only reported as `ClientEvent::SpaceDiscovered`
+    pub auto_subscribe_discovered: bool,
+    
+    /// How long an op may wait in a holdback queue before it is reported as
+    /// `ClientEvent::OpStuck`
+    pub op_stuck_after: Duration,
+    
+    /// Store and look up Spaces, ops, blobs and KeyPackages in the DHT; when
+    /// off, everything goes over gossip and peer sync, `dht_*` methods fail
+    /// with a "DHT is disabled" error, and members are added with
+    /// [`Client::add_member_with_key_package_bundle`]
+    pub dht_enabled: bool,
 }
 
 impl Default for ClientConfig {
@@ -62,16 +953,75 @@ impl Default for ClientConfig {
             storage_path: PathBuf::from("./descord-data"),
             listen_addrs: vec!["/ip4/0.0.0.0/tcp/0".to_string()],
             bootstrap_peers: vec![],
+            show_nsfw: false,
+            storage: crate::storage::StorageConfig::default(),
+            op_limits: crate::crdt::OpLimits::default(),
+            delivery_acks: false,
+            max_message_bytes: 16 * 1024,
+            gossip: crate::network::GossipConfig::default(),
+            connection: crate::network::ConnectionConfig::default(),
+            peer_exchange: false,
+            presence: false,
+            anti_entropy_interval: Some(crate::network::anti_entropy::ANTI_ENTROPY_INTERVAL),
+            key_package_pool: 10,
+            dedup_cache_capacity: crate::network::dedup::DEFAULT_DEDUP_CACHE_CAPACITY,
+            auto_subscribe_discovered: false,
+            op_stuck_after: crate::crdt::holdback::OP_STUCK_AFTER,
+            dht_enabled: true,
         }
     }
 }
 
+/// Builds a [`Client`] with injected components
+/// 
+/// [`Client::new`] covers the common case; the builder swaps in pluggable
+/// parts such as the MLS crypto backend or an identity key held in hardware.
+pub struct ClientBuilder {
+    signer: Arc<dyn Signer>,
+    config: ClientConfig,
+    mls_backend: Option<Arc<dyn crate::mls::MlsBackend>>,
+}
+
+impl ClientBuilder {
+    /// Start building a client with the given keypair and configuration
+    pub fn new(keypair: Keypair, config: ClientConfig) -> Self {
+        Self::with_signer(Arc::new(keypair), config)
+    }
+    
+    /// Start building a client whose identity key is held by `signer`
+    /// 
+    /// Use this with an [`crate::crypto::signing::ExternalSigner`] to keep
+    /// the secret key on a PKCS#11 token or platform keystore.
+    pub fn with_signer(signer: Arc<dyn Signer>, config: ClientConfig) -> Self {
+        Self {
+            signer,
+            config,
+            mls_backend: None,
+        }
+    }
+    
+    /// Run MLS on `backend` instead of the default rust-crypto backend
+    pub fn mls_backend(mut self, backend: Arc<dyn crate::mls::MlsBackend>) -> Self {
+        self.mls_backend = Some(backend);
+        self
+    }
+    
+    /// Create the client
+    pub fn build(self) -> Result<Client> {
+        let provider = match self.mls_backend {
+            Some(backend) => DescordProvider::with_backend(backend),
+            None => create_provider(),
+        };
+        Client::with_provider(self.signer, self.config, provider)
+    }
+}
+
 /// Main client for interacting with Descord
 pub struct Client {
-    /// User's keypair
-    keypair: Keypair,
+    /// Signs ops with the user's identity key
+    signer: Arc<dyn Signer>,
     
-    /// User ID (derived from keypair)
+    /// User ID (derived from the signer's public key)
     user_id: UserId,
     
     /// Space manager
@@ -99,11 +1049,17 @@ pub struct Client {
     mls_provider: Arc<RwLock<DescordProvider>>,
     
     /// KeyPackage store for MLS member addition
-    keypackage_store: Arc<RwLock<crate::mls::KeyPackageStore>>,
+    /// 
+    /// `None` when the identity signer can't sign for MLS; such a client
+    /// can't create or join MLS groups.
+    keypackage_store: Arc<RwLock<Option<crate::mls::KeyPackageStore>>>,
     
     /// Current relay information
     current_relay: Arc<RwLock<Option<crate::network::relay::RelayInfo>>>,
     
+    /// Circuit addresses we are reachable at through our relay reservation
+    relay_circuits: Arc<RwLock<Vec<String>>>,
+    
     /// Relay rotation task handle
     rotation_task: Arc<RwLock<Option<JoinHandle<()>>>>,
     
@@ -112,61 +1068,190 @@ pub struct Client {
     
     /// Queue for MLS messages that failed to decrypt (waiting for epoch update)
     pending_mls_messages: Arc<RwLock<VecDeque<PendingMlsMessage>>>,
+    
+    /// DHT records written by this client (for dashboard inspection)
+    dht_writes: Arc<RwLock<DhtWrites>>,
+    
+    /// Counters behind `metrics_snapshot()`
+    counters: Arc<crate::metrics::ClientCounters>,
+    
+    /// Whether NSFW channels are readable and postable (`ClientConfig::show_nsfw`)
+    show_nsfw: bool,
+    
+    /// Optional source of link previews for posted messages
+    link_preview_provider: Arc<RwLock<Option<Arc<dyn crate::forum::LinkPreviewProvider>>>>,
+    
+    /// Previews fetched in the background, sent by the fetch tasks
+    link_preview_tx: mpsc::UnboundedSender<PendingLinkPreview>,
+    
+    /// Previews waiting for `attach_pending_link_previews`
+    link_preview_rx: Arc<RwLock<mpsc::UnboundedReceiver<PendingLinkPreview>>>,
+    
+    /// Whether delivery acks are sent and collected (`ClientConfig::delivery_acks`)
+    delivery_acks: bool,
+    
+    /// Whether Spaces announced on the discovery topic are subscribed to (`ClientConfig::auto_subscribe_discovered`)
+    auto_subscribe_discovered: bool,
+    
+    /// Wait after which a held-back op is reported stuck (`ClientConfig::op_stuck_after`)
+    op_stuck_after: Duration,
+    
+    /// Whether the DHT is used at all (`ClientConfig::dht_enabled`)
+    dht_enabled: bool,
+    
+    /// Acks received for ops this client broadcast
+    delivery: Arc<RwLock<crate::network::DeliveryTracker>>,
+    
+    /// Received ops waiting to be acknowledged
+    ack_batcher: Arc<RwLock<crate::network::AckBatcher>>,
+    
+    /// Publisher behind `subscribe_events()`
+    events: tokio::sync::broadcast::Sender<ClientEvent>,
+    
+    /// Publisher behind `subscribe_ops()`
+    ops: tokio::sync::broadcast::Sender<(CrdtOp, SpaceId)>,
+    
+    /// Live webhook token IDs and the channel each may post into
+    webhooks: Arc<RwLock<std::collections::HashMap<[u8; 16], ChannelId>>>,
+    
+    /// Task re-posting messages from channels this user follows
+    announcement_relay: Arc<RwLock<Option<JoinHandle<()>>>>,
+    
+    /// Largest message content accepted (`ClientConfig::max_message_bytes`)
+    max_message_bytes: usize,
+    
+    /// Whether peers are exchanged with other Space members (`ClientConfig::peer_exchange`)
+    peer_exchange: bool,
+    
+    /// Peers learned through exchange, per Space
+    peer_book: Arc<RwLock<crate::network::PeerBook>>,
+    
+    /// Spaces we announce ourselves in
+    pex_spaces: Arc<RwLock<std::collections::HashSet<SpaceId>>>,
+    
+    /// Set when a connection or subscription means our announcements are stale
+    pex_pending: Arc<std::sync::atomic::AtomicBool>,
+    
+    /// Whether presence heartbeats are sent and tracked (`ClientConfig::presence`)
+    presence: bool,
+    
+    /// Last heartbeat accepted from each user, per Space
+    presence_book: Arc<RwLock<crate::network::PresenceBook>>,
+    
+    /// Spaces we send heartbeats in
+    presence_spaces: Arc<RwLock<std::collections::HashSet<SpaceId>>>,
+    
+    /// Time between anti-entropy rounds (`ClientConfig::anti_entropy_interval`)
+    anti_entropy_interval: Option<Duration>,
+    
+    /// Spaces reconciled by anti-entropy
+    sync_spaces: Arc<RwLock<std::collections::HashSet<SpaceId>>>,
+    
+    /// Size the unused KeyPackage pool is kept at (`ClientConfig::key_package_pool`)
+    key_package_pool: usize,
+    
+    /// Recently received op IDs, checked before the store
+    dedup_cache: Arc<RwLock<crate::network::DedupCache>>,
+    /// Per-author throttle on ops received over gossip
+    op_throttle: Arc<RwLock<crate::network::OpThrottle>>,
+    
+    /// Last clock each peer sent with a sync request or answer, per Space
+    peer_clocks: Arc<RwLock<std::collections::HashMap<SpaceId, std::collections::HashMap<String, crate::storage::VectorClock>>>>,
+    
+    /// When this client was removed from (and maybe re-added to) each Space's MLS group
+    absences: Arc<RwLock<std::collections::HashMap<SpaceId, Absence>>>,
+    
+    /// Wakes the pending DHT upload retrier early (on new connections)
+    dht_retry: Arc<tokio::sync::Notify>,
+    
+    /// Wakes the unsent op flusher early (on new connections)
+    outbox_retry: Arc<tokio::sync::Notify>,
 }
 
 impl Client {
     /// Create a new client with the given keypair and configuration
     pub fn new(keypair: Keypair, config: ClientConfig) -> Result<Self> {
-        let user_id = keypair.user_id();
+        ClientBuilder::new(keypair, config).build()
+    }
+    
+    /// Start building a client with non-default components
+    pub fn builder(keypair: Keypair, config: ClientConfig) -> ClientBuilder {
+        ClientBuilder::new(keypair, config)
+    }
+    
+    fn with_provider(signer: Arc<dyn Signer>, config: ClientConfig, provider: DescordProvider) -> Result<Self> {
+        let user_id = signer.user_id();
         
         // Create storage backends
         let store = Arc::new(Store::open(&config.storage_path)?);
         
         // Create managers
-        let space_manager = Arc::new(RwLock::new(SpaceManager::new()));
-        let channel_manager = Arc::new(RwLock::new(ChannelManager::new()));
-        let thread_manager = Arc::new(RwLock::new(ThreadManager::new()));
+        let mut space_manager = SpaceManager::new();
+        let mut channel_manager = ChannelManager::new();
+        let mut thread_manager = ThreadManager::new();
+        let op_limits = crate::crdt::OpLimits {
+            max_message_bytes: config.max_message_bytes,
+            ..config.op_limits
+        };
+        space_manager.set_op_limits(op_limits);
+        channel_manager.set_op_limits(op_limits);
+        thread_manager.set_op_limits(op_limits);
+        let space_manager = Arc::new(RwLock::new(space_manager));
+        let channel_manager = Arc::new(RwLock::new(channel_manager));
+        let thread_manager = Arc::new(RwLock::new(thread_manager));
         
         // Initialize blob storage
-        let storage = Arc::new(crate::storage::Storage::open(&config.storage_path)?);
+        let storage = Arc::new(crate::storage::Storage::open_with_config(
+            &config.storage_path,
+            config.storage.clone(),
+        )?);
         
         // Create network with bootstrap peers and listen addresses
-        let (network_node, network_rx) = NetworkNode::new_with_config(
+        let (network_node, network_rx) = NetworkNode::new_with_network_config(
             config.bootstrap_peers.clone(),
-            config.listen_addrs.clone()
+            config.listen_addrs.clone(),
+            config.gossip,
+            config.connection,
         )?;
         let network = Arc::new(RwLock::new(network_node));
         let network_rx = Arc::new(RwLock::new(network_rx));
         
         // Create MLS provider (wrapped in Arc<RwLock> for shared mutable access)
-        let mls_provider = Arc::new(RwLock::new(create_provider()));
+        let mls_provider = Arc::new(RwLock::new(provider));
         
         // Create MLS signer and KeyPackage store
         use openmls::prelude::*;
-        use openmls_basic_credential::SignatureKeyPair;
         let ciphersuite = Ciphersuite::MLS_128_DHKEMX25519_AES128GCM_SHA256_Ed25519;
-        let mls_signer = SignatureKeyPair::new(ciphersuite.signature_algorithm())
-            .map_err(|e| crate::Error::Crypto(format!("Failed to create MLS signer: {:?}", e)))?;
-        let mls_signer = Arc::new(mls_signer); // Wrap in Arc for sharing
-        
-        let mut kp_store = crate::mls::KeyPackageStore::new(user_id, mls_signer, ciphersuite);
-        
-        // Generate initial batch of KeyPackages (10 packages)
-        // Using try_read() since this is not an async context
-        {
-            let provider_lock = mls_provider.try_read()
-                .map_err(|e| crate::Error::Crypto(format!("Failed to acquire provider lock: {}", e)))?;
-            let _key_packages = kp_store.generate_key_packages(10, &provider_lock)?;
-            println!("✓ Generated {} KeyPackages for user {}", 10, user_id);
-        }
+        let kp_store = match crate::mls::identity_signer(&*signer) {
+            Ok(mls_signer) => {
+                let mut kp_store = crate::mls::KeyPackageStore::new(user_id, mls_signer, ciphersuite);
+                
+                // Generate the initial batch of KeyPackages
+                // Using try_read() since this is not an async context
+                if config.key_package_pool > 0 {
+                    let provider_lock = mls_provider.try_read()
+                        .map_err(|e| crate::Error::Crypto(format!("Failed to acquire provider lock: {}", e)))?;
+                    kp_store.generate_key_packages(config.key_package_pool, &provider_lock)?;
+                    tracing::info!(count = config.key_package_pool, %user_id, "Generated KeyPackages");
+                }
+                Some(kp_store)
+            }
+            Err(e) => {
+                tracing::warn!(%user_id, error = %e, "MLS disabled for this client");
+                None
+            }
+        };
         
         let keypackage_store = Arc::new(RwLock::new(kp_store));
         
         // Create GossipSub metrics
         let gossip_metrics = Arc::new(crate::network::GossipMetrics::new());
         
+        let (link_preview_tx, link_preview_rx) = mpsc::unbounded_channel();
+        let events = tokio::sync::broadcast::channel(EVENT_CHANNEL_CAPACITY).0;
+        
         Ok(Self {
-            keypair,
+            signer,
             user_id,
             space_manager,
             channel_manager,
@@ -178,9 +1263,43 @@ impl Client {
             mls_provider,
             keypackage_store,
             current_relay: Arc::new(RwLock::new(None)),
+            relay_circuits: Arc::new(RwLock::new(Vec::new())),
             rotation_task: Arc::new(RwLock::new(None)),
             gossip_metrics,
             pending_mls_messages: Arc::new(RwLock::new(VecDeque::new())),
+            dht_writes: Arc::new(RwLock::new(DhtWrites::default())),
+            counters: Arc::new(crate::metrics::ClientCounters::default()),
+            show_nsfw: config.show_nsfw,
+            link_preview_provider: Arc::new(RwLock::new(None)),
+            link_preview_tx,
+            link_preview_rx: Arc::new(RwLock::new(link_preview_rx)),
+            delivery_acks: config.delivery_acks,
+            auto_subscribe_discovered: config.auto_subscribe_discovered,
+            op_stuck_after: config.op_stuck_after,
+            dht_enabled: config.dht_enabled,
+            delivery: Arc::new(RwLock::new(crate::network::DeliveryTracker::default())),
+            ack_batcher: Arc::new(RwLock::new(crate::network::AckBatcher::default())),
+            events,
+            ops: tokio::sync::broadcast::channel(EVENT_CHANNEL_CAPACITY).0,
+            webhooks: Arc::new(RwLock::new(std::collections::HashMap::new())),
+            announcement_relay: Arc::new(RwLock::new(None)),
+            max_message_bytes: config.max_message_bytes,
+            peer_exchange: config.peer_exchange,
+            peer_book: Arc::new(RwLock::new(crate::network::PeerBook::default())),
+            pex_spaces: Arc::new(RwLock::new(std::collections::HashSet::new())),
+            pex_pending: Arc::new(std::sync::atomic::AtomicBool::new(false)),
+            presence: config.presence,
+            presence_book: Arc::new(RwLock::new(crate::network::PresenceBook::default())),
+            presence_spaces: Arc::new(RwLock::new(std::collections::HashSet::new())),
+            anti_entropy_interval: config.anti_entropy_interval,
+            sync_spaces: Arc::new(RwLock::new(std::collections::HashSet::new())),
+            key_package_pool: config.key_package_pool,
+            dedup_cache: Arc::new(RwLock::new(crate::network::DedupCache::new(config.dedup_cache_capacity))),
+            op_throttle: Arc::new(RwLock::new(crate::network::OpThrottle::new(config.op_limits.rate_limit))),
+            peer_clocks: Arc::new(RwLock::new(std::collections::HashMap::new())),
+            absences: Arc::new(RwLock::new(std::collections::HashMap::new())),
+            dht_retry: Arc::new(tokio::sync::Notify::new()),
+            outbox_retry: Arc::new(tokio::sync::Notify::new()),
         })
     }
     
@@ -192,22 +1311,67 @@ impl Client {
             let _ = network.subscribe("descord/space-discovery").await;
             
             // Subscribe to user's personal Welcome message topic for MLS group invitations
-            let welcome_topic = format!("user/{}/welcome", hex::encode(&self.user_id.0[..8]));
+            let welcome_topic = format!("user/{}/welcome", self.user_id.short());
             let _ = network.subscribe(&welcome_topic).await;
-            println!("✓ Subscribed to Welcome message topic: {}", welcome_topic);
+            tracing::info!("Subscribed to Welcome message topic: {}", welcome_topic);
+            
+            // Answers to our own sync requests
+            let catch_up_topic = crate::network::anti_entropy::catch_up_topic(&network.local_peer_id().to_string());
+            let _ = network.subscribe(&catch_up_topic).await;
+        }
+        
+        if self.delivery_acks {
+            self.spawn_ack_flusher();
+        }
+        if self.peer_exchange {
+            self.spawn_peer_announcer();
+        }
+        if self.presence {
+            self.spawn_presence_heartbeat();
+        }
+        if let Some(period) = self.anti_entropy_interval {
+            self.spawn_anti_entropy(period);
+        }
+        if self.dht_enabled {
+            self.spawn_dht_upload_retrier();
         }
+        self.spawn_outbox_flusher();
+        self.spawn_holdback_watch();
+        self.ensure_announcement_relay().await;
         
         // Spawn event processing task
         let space_manager = Arc::clone(&self.space_manager);
         let channel_manager = Arc::clone(&self.channel_manager);
         let thread_manager = Arc::clone(&self.thread_manager);
         let store = Arc::clone(&self.store);
+        let storage = Arc::clone(&self.storage);
         let network_rx = Arc::clone(&self.network_rx);
         let network = Arc::clone(&self.network);
         let gossip_metrics = Arc::clone(&self.gossip_metrics);
         let mls_provider = Arc::clone(&self.mls_provider); // Clone Arc<RwLock> to share provider
         let keypackage_store = Arc::clone(&self.keypackage_store); // Clone for Welcome processing
         let pending_mls_messages = Arc::clone(&self.pending_mls_messages); // Clone for queued message processing
+        let counters = Arc::clone(&self.counters);
+        let delivery_acks = self.delivery_acks;
+        let auto_subscribe_discovered = self.auto_subscribe_discovered;
+        let delivery = Arc::clone(&self.delivery);
+        let ack_batcher = Arc::clone(&self.ack_batcher);
+        let events = self.events.clone();
+        let op_stream = self.ops.clone();
+        let dedup_cache = Arc::clone(&self.dedup_cache);
+        let op_throttle = Arc::clone(&self.op_throttle);
+        let peer_clocks = Arc::clone(&self.peer_clocks);
+        let absences = Arc::clone(&self.absences);
+        let dht_writes = Arc::clone(&self.dht_writes);
+        let dht_enabled = self.dht_enabled;
+        let peer_exchange = self.peer_exchange;
+        let peer_book = Arc::clone(&self.peer_book);
+        let pex_pending = Arc::clone(&self.pex_pending);
+        let presence = self.presence;
+        let presence_book = Arc::clone(&self.presence_book);
+        let dht_retry = Arc::clone(&self.dht_retry);
+        let outbox_retry = Arc::clone(&self.outbox_retry);
+        let local_peer_id = self.peer_id().await;
         let user_id = self.user_id; // Clone user_id for the async task
         
         tokio::spawn(async move {
@@ -220,63 +1384,150 @@ impl Client {
                 if let Some(event) = event_opt {
                     match event {
                         NetworkEvent::MessageReceived { topic, data, source } => {
-                            println!("📬 Client received network message on topic: {}", topic);
+                            let span = tracing::debug_span!(
+                                "handle_incoming_op",
+                                topic = %topic,
+                                op_id = tracing::field::Empty,
+                                space_id = tracing::field::Empty,
+                            );
+                            tracing::debug!(parent: &span, "Client received network message");
+                            
+                            // Delivery acks are ephemeral and never decoded as ops
+                            if topic.ends_with("/acks") {
+                                if delivery_acks {
+                                    match crate::network::Ack::from_bytes(&data) {
+                                        Ok(ack) if ack.verify() => {
+                                            if !delivery.write().await.record(&ack) {
+                                                tracing::debug!(parent: &span, "Ignored replayed ack");
+                                            }
+                                        }
+                                        Ok(_) => tracing::warn!(parent: &span, "Rejected ack with invalid signature"),
+                                        Err(e) => tracing::warn!(parent: &span, "Failed to decode ack: {}", e),
+                                    }
+                                }
+                                continue;
+                            }
+                            
+                            // Peer announcements are ephemeral too
+                            if topic.ends_with("/peers") {
+                                if peer_exchange {
+                                    match crate::network::PeerExchange::from_bytes(&data) {
+                                        Ok(exchange) => {
+                                            let dials = peer_book.write().await.learn(&local_peer_id, &exchange);
+                                            for addr in dials {
+                                                tracing::debug!(parent: &span, %addr, "Dialing peer learned through exchange");
+                                                let network = Arc::clone(&network);
+                                                tokio::spawn(async move {
+                                                    let _ = network.write().await.dial(addr).await;
+                                                });
+                                            }
+                                        }
+                                        Err(e) => tracing::warn!(parent: &span, "Failed to decode peer exchange: {}", e),
+                                    }
+                                }
+                                continue;
+                            }
+                            
+                            // So are presence heartbeats; replays and forgeries are dropped
+                            if topic.ends_with("/presence") {
+                                if presence {
+                                    match crate::network::Presence::from_bytes(&data) {
+                                        Ok(heartbeat) => {
+                                            if !presence_book.write().await.record(&heartbeat) {
+                                                tracing::debug!(parent: &span, "Ignored forged or replayed presence");
+                                            }
+                                        }
+                                        Err(e) => tracing::warn!(parent: &span, "Failed to decode presence: {}", e),
+                                    }
+                                }
+                                continue;
+                            }
+                            
+                            // Sync digests are answered by the one peer they name
+                            if topic.ends_with("/sync") {
+                                match crate::network::SyncDigest::from_bytes(&data) {
+                                    Ok(digest) if digest.responder == local_peer_id.to_string() => {
+                                        match store.get_space_ops(&digest.space_id) {
+                                            Ok(ops) => {
+                                                let missing = digest.clock.missing_ops(&ops);
+                                                tracing::debug!(parent: &span, count = missing.len(), "Answering sync digest");
+                                                republish_ops(&network, &mls_provider, &channel_manager, &space_manager, &format!("space/{}", digest.space_id.short()), missing).await;
+                                            }
+                                            Err(e) => tracing::warn!(parent: &span, "Failed to read ops for sync digest: {}", e),
+                                        }
+                                    }
+                                    Ok(_) => {}
+                                    Err(e) => tracing::warn!(parent: &span, "Failed to decode sync digest: {}", e),
+                                }
+                                continue;
+                            }
                             
-                            // Check if this is a sync request (starts with "SYNC_REQUEST:")
-                            if let Ok(text) = String::from_utf8(data.clone()) {
-                                if text.starts_with("SYNC_REQUEST:") {
-                                    println!("  🔄 Received sync request from peer");
-                                    if let Some(space_id_hex) = text.strip_prefix("SYNC_REQUEST:") {
-                                        println!("    Space ID hex: {}", space_id_hex);
-                                        if let Ok(space_id_bytes) = hex::decode(space_id_hex) {
-                                            println!("    Decoded {} bytes", space_id_bytes.len());
-                                            if space_id_bytes.len() == 32 {
-                                                let mut space_id_arr = [0u8; 32];
-                                                space_id_arr.copy_from_slice(&space_id_bytes);
-                                                let space_id = SpaceId(space_id_arr);
+                            // Sync requests are answered by the one peer they name, with
+                            // only the ops the requester lacks, on a topic only the
+                            // requester subscribes to. A request relayed by someone else
+                            // may name a requester that never sent it, so only those
+                            // received straight from the requester are answered.
+                            if data.starts_with(crate::network::anti_entropy::SYNC_REQUEST_PREFIX) {
+                                match crate::network::CatchUpRequest::from_bytes(&data) {
+                                    Ok(request) if request.responder != local_peer_id.to_string() => {}
+                                    Ok(request) if request.requester != source.to_string() => {
+                                        tracing::debug!(parent: &span, requester = %request.requester, %source, "Ignoring sync request not sent by its requester");
+                                    }
+                                    Ok(request) => {
+                                        peer_clocks.write().await
+                                            .entry(request.space_id)
+                                            .or_default()
+                                            .insert(request.requester.clone(), request.clock.to_vector_clock());
+                                        match store.get_space_ops(&request.space_id) {
+                                            Ok(ops) => {
+                                                let missing = request.clock.missing_ops(&ops);
+                                                tracing::debug!(parent: &span, count = missing.len(), requester = %request.requester, "Answering sync request");
+                                                let topic = crate::network::anti_entropy::catch_up_topic(&request.requester);
+                                                republish_ops(&network, &mls_provider, &channel_manager, &space_manager, &topic, missing).await;
                                                 
-                                                // Handle sync request inline (we're already in async context)
-                                                match store.get_space_ops(&space_id) {
-                                                    Ok(ops) => {
-                                                        println!("    Found {} operations in storage", ops.len());
-                                                        if !ops.is_empty() {
-                                                            println!("  📤 Re-broadcasting {} operations for Space", ops.len());
-                                                            let space_topic = format!("space/{}", hex::encode(&space_id.0[..8]));
-                                                            for op in ops {
-                                                                // Broadcast each operation
-                                                                if let Ok(data) = minicbor::to_vec(&op) {
-                                                                    let mut net = network.write().await;
-                                                                    let _ = net.publish(&space_topic, data).await;
-                                                                    drop(net);
-                                                                    tokio::time::sleep(Duration::from_millis(10)).await;
-                                                                }
-                                                            }
-                                                            println!("  ✓ Sync complete");
-                                                        } else {
-                                                            println!("    ⚠️ No operations to send");
-                                                        }
-                                                    }
-                                                    Err(e) => {
-                                                        println!("    ⚠️ Error getting operations: {}", e);
-                                                    }
+                                                // Then our clock, so the requester knows when it has caught up
+                                                let answer = crate::network::ResponderClock {
+                                                    space_id: request.space_id,
+                                                    responder: local_peer_id.to_string(),
+                                                    clock: crate::network::SpaceClock::of(&ops),
+                                                };
+                                                if let Ok(bytes) = answer.to_bytes() {
+                                                    let _ = network.write().await.publish(&topic, bytes).await;
                                                 }
                                             }
+                                            Err(e) => tracing::warn!(parent: &span, "Failed to read ops for sync request: {}", e),
                                         }
                                     }
-                                    continue; // Don't try to decode as CrdtOp
+                                    Err(e) => tracing::debug!(parent: &span, "Ignoring sync request in an older format: {}", e),
+                                }
+                                continue; // Don't try to decode as CrdtOp
+                            }
+                            
+                            // A peer finished answering our sync request
+                            if data.starts_with(crate::network::anti_entropy::SYNC_CLOCK_PREFIX) {
+                                match crate::network::ResponderClock::from_bytes(&data) {
+                                    Ok(answer) => {
+                                        peer_clocks.write().await
+                                            .entry(answer.space_id)
+                                            .or_default()
+                                            .insert(answer.responder, answer.clock.to_vector_clock());
+                                    }
+                                    Err(e) => tracing::debug!(parent: &span, "Failed to decode responder clock: {}", e),
                                 }
+                                continue;
                             }
                             
                             // Check if this is a Welcome message (on user/{id}/welcome topic)
                             if topic.starts_with("user/") && topic.ends_with("/welcome") {
-                                println!("  🎉 Received MLS Welcome message");
+                                tracing::debug!(parent: &span, "Received MLS Welcome message");
                                 
                                 // Get the signer from our KeyPackageStore
                                 // This is the SAME signer used when generating KeyPackages
                                 // Critical: must use the same keypair that Alice expects!
-                                let kp_store = keypackage_store.read().await;
-                                let signer_arc = kp_store.signer();
-                                drop(kp_store);
+                                let Some(signer_arc) = keypackage_store.read().await.as_ref().map(|store| store.signer()) else {
+                                    tracing::warn!(parent: &span, "Ignoring Welcome: this client can't sign for MLS");
+                                    continue;
+                                };
                                 
                                 // Use the shared provider that has the KeyPackage private keys
                                 let provider = mls_provider.read().await;
@@ -288,7 +1539,7 @@ impl Client {
                                 ) {
                                 Ok(mls_group) => {
                                     let epoch = mls_group.current_epoch().0;
-                                    println!("  ✓ Successfully joined MLS group (epoch {})", epoch);
+                                    tracing::debug!(parent: &span, epoch, "Joined MLS group from Welcome");
                                     
                                     // Wrap in Option so we can move it conditionally
                                     let mut mls_group_opt = Some(mls_group);
@@ -302,7 +1553,10 @@ impl Client {
                                             let mut spaces = space_mgr.list_spaces();
                                             
                                             for space in spaces.iter() {
-                                                if space_mgr.get_mls_group(&space.id).is_none() {
+                                                // A group we were removed from can be replaced by a rejoin
+                                                let removed_from = space_mgr.get_mls_group(&space.id)
+                                                    .map(|group| (!group.is_active()).then(|| group.current_epoch()));
+                                                if let None | Some(Some(_)) = removed_from {
                                                     // This must be the space for this Welcome!
                                                     let space_id = space.id;
                                                     let space_name = space.name.clone();
@@ -312,15 +1566,45 @@ impl Client {
                                                     space_mgr_mut.store_mls_group(space_id, mls_group_opt.take().unwrap());
                                                     drop(space_mgr_mut);
                                                     
-                                                    println!("  ✓ MLS group stored for space {} ({})", 
-                                                        space_name, hex::encode(&space_id.0[..8]));
-                                                    println!("  ✓ Can now decrypt messages in this space!");
+                                                    tracing::info!(parent: &span, space_id = %space_id.short(), space = %space_name, "Joined Space MLS group");
+                                                    
+                                                    if let Some(Some(previous_epoch)) = removed_from {
+                                                        tracing::info!(space_id = %space_id.short(), previous_epoch = previous_epoch.0, epoch, "Rejoined Space");
+                                                        if let Some(absence) = absences.write().await.get_mut(&space_id) {
+                                                            absence.rejoined_at = std::time::SystemTime::now()
+                                                                .duration_since(std::time::UNIX_EPOCH)
+                                                                .unwrap()
+                                                                .as_secs();
+                                                        }
+                                                        let _ = events.send(ClientEvent::Rejoined {
+                                                            space_id,
+                                                            previous_epoch,
+                                                            epoch: EpochId(epoch),
+                                                        });
+                                                        
+                                                        // Ask connected peers for what we missed
+                                                        match store.get_space_ops(&space_id) {
+                                                            Ok(ops) => {
+                                                                let request = pick_responder(&network).await.map(|responder| crate::network::CatchUpRequest {
+                                                                    space_id,
+                                                                    requester: local_peer_id.to_string(),
+                                                                    clock: crate::network::SpaceClock::of(&ops),
+                                                                    responder: responder.to_string(),
+                                                                });
+                                                                if let Some(Ok(bytes)) = request.map(|request| request.to_bytes()) {
+                                                                    let topic = format!("space/{}", space_id.short());
+                                                                    let _ = network.write().await.publish(&topic, bytes).await;
+                                                                }
+                                                            }
+                                                            Err(e) => tracing::warn!("Failed to read ops for rejoin sync: {}", e),
+                                                        }
+                                                    }
                                                     
                                                     // Process queued messages for this space
                                                     let mut pending_queue = pending_mls_messages.write().await;
                                                     let queue_len = pending_queue.len();
                                                     if queue_len > 0 {
-                                                        println!("  📬 Processing {} queued messages...", queue_len);
+                                                        tracing::debug!(parent: &span, "Processing {} queued messages...", queue_len);
                                                         
                                                         // Drain messages for this space and try to decrypt them
                                                         let mut remaining = VecDeque::new();
@@ -335,21 +1619,21 @@ impl Client {
                                                                 if let Some(mls_group) = space_mgr_mut.get_mls_group_mut(&space_id) {
                                                                     match mls_group.decrypt_application_message(&pending_msg.encrypted_data, &provider) {
                                                                         Ok(decrypted_bytes) => {
-                                                                            println!("    ✓ Decrypted queued message ({} bytes)", decrypted_bytes.len());
+                                                                            tracing::debug!(parent: &span, "Decrypted queued message ({} bytes)", decrypted_bytes.len());
                                                                             processed += 1;
                                                                             
                                                                             // Decode and process the operation
-                                                                            if let Ok(op) = minicbor::decode::<CrdtOp>(&decrypted_bytes) {
+                                                                            if let Ok(op) = CrdtOp::from_canonical_bytes(&decrypted_bytes) {
                                                                                 // Store and process the operation (same logic as regular messages)
                                                                                 if op.verify_signature() {
                                                                                     if let Err(e) = store.put_op(&op) {
-                                                                                        eprintln!("      ⚠️ Failed to store queued operation: {}", e);
+                                                                                        tracing::warn!(parent: &span, "Failed to store queued operation: {}", e);
                                                                                     }
                                                                                 }
                                                                             }
                                                                         }
                                                                         Err(e) => {
-                                                                            eprintln!("    ⚠️ Still can't decrypt queued message: {}", e);
+                                                                            tracing::warn!(parent: &span, "Still can't decrypt queued message: {}", e);
                                                                             // Re-queue if still can't decrypt
                                                                             remaining.push_back(pending_msg);
                                                                         }
@@ -365,7 +1649,7 @@ impl Client {
                                                         
                                                         // Put back messages we couldn't process
                                                         *pending_queue = remaining;
-                                                        println!("    ✓ Processed {}/{} queued messages", processed, queue_len);
+                                                        tracing::debug!(parent: &span, "Processed {}/{} queued messages", processed, queue_len);
                                                     }
                                                     drop(pending_queue);
                                                     
@@ -377,7 +1661,7 @@ impl Client {
                                         
                                         // If not a space Welcome, check if it's a channel Welcome
                                         if !found {
-                                            println!("  🔍 Not a space Welcome, checking channels...");
+                                            tracing::debug!(parent: &span, "Not a space Welcome, checking channels...");
                                             let mut target_channel_id: Option<(ChannelId, String)> = None;
                                             
                                             {
@@ -406,16 +1690,14 @@ impl Client {
                                                 channel_mgr_mut.store_mls_group(channel_id, mls_group_opt.take().unwrap());
                                                 drop(channel_mgr_mut);
                                                 
-                                                println!("  ✅ MLS group stored for channel {} ({})", 
-                                                    channel_name, hex::encode(&channel_id.0[..8]));
-                                                println!("  ✅ Can now participate in this channel!");
+                                                tracing::info!(parent: &span, channel_id = %channel_id.short(), channel = %channel_name, "Joined channel MLS group");
                                             } else {
-                                                println!("  ⚠️ Couldn't find space or channel for this MLS group");
+                                                tracing::warn!(parent: &span, "Couldn't find space or channel for this MLS group");
                                             }
                                         }
                                     }
                                     Err(e) => {
-                                        eprintln!("  ⚠️ Failed to process Welcome message: {}", e);
+                                        tracing::warn!(parent: &span, "Failed to process Welcome message: {}", e);
                                     }
                                 }
                                 
@@ -442,7 +1724,7 @@ impl Client {
                             };
                             
                             if is_commit_message {
-                                println!("  🔄 MLS Commit message detected - processing epoch update...");
+                                tracing::debug!(parent: &span, "MLS Commit message detected - processing epoch update...");
                                 
                                 // We need to find which space this Commit is for
                                 // The Commit itself doesn't contain the space_id, but we can try all our spaces
@@ -464,7 +1746,14 @@ impl Client {
                                     if let Some(mls_group) = space_mgr.get_mls_group_mut(&space_id) {
                                         match mls_group.process_commit_message(&data, &provider) {
                                             Ok(()) => {
-                                                println!("  ✓ Commit processed for space {}", hex::encode(&space_id.0[..8]));
+                                                tracing::debug!(parent: &span, "Commit processed for space {}", space_id.short());
+                                                if !mls_group.is_active() {
+                                                    let left_at = std::time::SystemTime::now()
+                                                        .duration_since(std::time::UNIX_EPOCH)
+                                                        .unwrap()
+                                                        .as_secs();
+                                                    absences.write().await.insert(space_id, Absence { left_at, rejoined_at: u64::MAX });
+                                                }
                                                 processed = true;
                                                 processed_space_id = Some(space_id);
                                                 drop(provider);
@@ -483,14 +1772,14 @@ impl Client {
                                 
                                 // If we processed a Commit, try to decrypt queued messages for that space
                                 if let Some(space_id) = processed_space_id {
-                                    println!("  📬 Checking for queued messages to process...");
+                                    tracing::debug!(parent: &span, "Checking for queued messages to process...");
                                     let queued: Vec<PendingMlsMessage> = {
                                         let mut pending_queue = pending_mls_messages.write().await;
                                         pending_queue.drain(..).collect()
                                     };
                                     
                                     if !queued.is_empty() {
-                                        println!("  📬 Processing {} queued messages...", queued.len());
+                                        tracing::debug!(parent: &span, "Processing {} queued messages...", queued.len());
                                         
                                         for queued_msg in queued {
                                             if queued_msg.space_id == space_id {
@@ -501,7 +1790,7 @@ impl Client {
                                                 if let Some(mls_group) = space_mgr.get_mls_group_mut(&space_id) {
                                                     match mls_group.decrypt_application_message(&queued_msg.encrypted_data, &provider) {
                                                         Ok(plaintext) => {
-                                                            println!("  ✓ Decrypted queued message ({} bytes)", plaintext.len());
+                                                            tracing::debug!(parent: &span, "Decrypted queued message ({} bytes)", plaintext.len());
                                                             
                                                             // Decode the CrdtOp from the decrypted plaintext
                                                             if let Ok(op) = bincode::deserialize::<CrdtOp>(&plaintext) {
@@ -511,12 +1800,12 @@ impl Client {
                                                                 // Process the operation
                                                                 // TODO: Can't call self.handle_incoming_op from spawned task
                                                                 // Need to send op to a channel for processing
-                                                                eprintln!("  ✓ Queued operation decoded, but can't process in spawned task");
+                                                                tracing::debug!(parent: &span, "Queued operation decoded, but can't process in spawned task");
                                                             }
                                                         }
                                                         Err(e) => {
                                                             // Still can't decrypt - re-queue
-                                                            eprintln!("  ⚠️ Still can't decrypt queued message: {}", e);
+                                                            tracing::warn!(parent: &span, "Still can't decrypt queued message: {}", e);
                                                             let mut pending_queue = pending_mls_messages.write().await;
                                                             pending_queue.push_back(queued_msg);
                                                             drop(pending_queue);
@@ -538,21 +1827,63 @@ impl Client {
                                     }
                                 }
                                 
+                                // Otherwise it may be a channel Commit, e.g. someone joining by external commit
+                                if !processed {
+                                    let channel_ids: Vec<ChannelId> = {
+                                        let mut channel_mgr = channel_manager.write().await;
+                                        channel_mgr.mls_groups_mut().map(|(id, _)| *id).collect()
+                                    };
+                                    for channel_id in channel_ids {
+                                        let provider = mls_provider.read().await;
+                                        let mut channel_mgr = channel_manager.write().await;
+                                        let Some(mls_group) = channel_mgr.get_mls_group_mut(&channel_id) else {
+                                            continue;
+                                        };
+                                        if mls_group.process_commit_message(&data, &provider).is_ok() {
+                                            tracing::debug!(parent: &span, "Commit processed for channel {}", channel_id.short());
+                                            processed = true;
+                                            drop(channel_mgr);
+                                            drop(provider);
+                                            
+                                            // Keep the published GroupInfo at the new epoch
+                                            if dht_enabled {
+                                                let (mls_provider, channel_manager, network, dht_writes) = (
+                                                    Arc::clone(&mls_provider),
+                                                    Arc::clone(&channel_manager),
+                                                    Arc::clone(&network),
+                                                    Arc::clone(&dht_writes),
+                                                );
+                                                tokio::spawn(async move {
+                                                    if let Err(e) = dht_put_channel_group_info(&mls_provider, &channel_manager, &network, &dht_writes, &channel_id).await {
+                                                        tracing::debug!(error = %e, "Failed to republish channel GroupInfo");
+                                                    }
+                                                });
+                                            }
+                                            break;
+                                        }
+                                    }
+                                }
+                                
                                 if !processed {
-                                    eprintln!("  ⚠️ Could not process Commit (no matching MLS group)");
+                                    tracing::warn!(parent: &span, "Could not process Commit (no matching MLS group)");
                                 }
                                 
                                 continue; // Don't try to decode as CrdtOp
                             }
                             
+                            // Ops re-sent by anti-entropy or a sync answer were sealed by
+                            // whoever holds them, not necessarily their author
+                            let relayed = data.first() == Some(&RELAYED_MARKER);
+                            let data = if relayed { data[1..].to_vec() } else { data };
+                            
                             // Check for MLS encryption marker and decode the operation
                             let op = if data.first() == Some(&0x01) {
                                 // Space-level MLS encryption
-                                println!("  🔒 Space MLS-encrypted message detected");
+                                tracing::debug!(parent: &span, "Space MLS-encrypted message detected");
                                 
                                 // Message format: [0x01][space_id (32 bytes)][encrypted_data]
                                 if data.len() < 33 {
-                                    eprintln!("  ⚠️ MLS message too short (need at least 33 bytes)");
+                                    tracing::warn!(parent: &span, "MLS message too short (need at least 33 bytes)");
                                     continue;
                                 }
                                 
@@ -560,7 +1891,7 @@ impl Client {
                                 let space_id_bytes: [u8; 32] = match data[1..33].try_into() {
                                     Ok(bytes) => bytes,
                                     Err(_) => {
-                                        eprintln!("  ⚠️ Invalid space_id in MLS message");
+                                        tracing::warn!(parent: &span, "Invalid space_id in MLS message");
                                         continue;
                                     }
                                 };
@@ -570,22 +1901,23 @@ impl Client {
                                 let encrypted_data = &data[33..];
                                 
                                 // Decrypt using the space's MLS group
-                                let decrypted_bytes = {
+                                let (decrypted_bytes, mls_sender) = {
                                     let mut space_mgr = space_manager.write().await;
                                     let provider = mls_provider.read().await;
                                     
                                     match space_mgr.get_mls_group_mut(&space_id) {
                                         Some(mls_group) => {
-                                            match mls_group.decrypt_application_message(encrypted_data, &provider) {
-                                                Ok(plaintext) => {
-                                                    println!("  ✓ Decrypted Space MLS message ({} bytes)", plaintext.len());
-                                                    plaintext
+                                            match mls_group.decrypt_application_message_with_sender(encrypted_data, &provider) {
+                                                Ok((plaintext, sender)) => {
+                                                    tracing::debug!(parent: &span, "Decrypted Space MLS message ({} bytes)", plaintext.len());
+                                                    (plaintext, sender)
                                                 }
                                                 Err(e) => {
-                                                    let error_str = format!("{:?}", e);
-                                                    if error_str.contains("WrongEpoch") {
+                                                    let is_member = space_mgr.get_space(&space_id)
+                                                        .is_none_or(|space| space.is_member(&user_id));
+                                                    let reason = decrypt_fail_reason(mls_group, is_member, &e);
+                                                    if reason.is_transient() {
                                                         // Epoch mismatch - queue for retry after Welcome
-                                                        eprintln!("  ⏸️  Message from future epoch - queuing for retry");
                                                         let mut pending_queue = pending_mls_messages.write().await;
                                                         pending_queue.push_back(PendingMlsMessage {
                                                             space_id,
@@ -593,40 +1925,50 @@ impl Client {
                                                             topic: topic.clone(),
                                                             queued_at: Instant::now(),
                                                         });
-                                                        eprintln!("     (Queued: {} pending messages)", pending_queue.len());
+                                                        tracing::warn!(parent: &span, pending = pending_queue.len(), "Message from future epoch - queued for retry");
                                                         drop(pending_queue);
-                                                        continue;
                                                     } else {
-                                                        eprintln!("  ⚠️ Failed to decrypt MLS message: {}", e);
-                                                        eprintln!("     (You may have been removed from this Space)");
-                                                        continue;
+                                                        counters.record_decrypt_failure();
+                                                        tracing::warn!(parent: &span, ?reason, "Failed to decrypt MLS message: {}", e);
                                                     }
+                                                    let _ = events.send(ClientEvent::DecryptionFailed { space_id, reason });
+                                                    continue;
                                                 }
                                             }
                                         }
                                         None => {
-                                            eprintln!("  ⚠️ No MLS group found for space_id {}", hex::encode(&space_id.0[..8]));
-                                            eprintln!("     (You may not be a member of this Space)");
+                                            tracing::warn!(parent: &span, "No MLS group found for space_id {} (you may not be a member of this Space)", space_id.short());
+                                            let _ = events.send(ClientEvent::DecryptionFailed {
+                                                space_id,
+                                                reason: DecryptFailReason::NotAMember,
+                                            });
                                             continue;
                                         }
                                     }
                                 };
                                 
                                 // Decode the decrypted operation
-                                match minicbor::decode::<CrdtOp>(&decrypted_bytes) {
+                                match CrdtOp::from_canonical_bytes(&decrypted_bytes) {
+                                    // The identity key that signed for MLS must be the author of
+                                    // an op sent first-hand; an unbound leaf can't vouch for any
+                                    // author. Relayed ops are vouched for by their signature alone
+                                    Ok(op) if !relayed && mls_sender != Some(op.author) => {
+                                        tracing::warn!(parent: &span, author = %op.author, sender = ?mls_sender, "Rejecting operation: author is not the MLS sender");
+                                        continue;
+                                    }
                                     Ok(op) => op,
                                     Err(e) => {
-                                        eprintln!("  ⚠️ Failed to decode decrypted operation: {}", e);
+                                        tracing::warn!(parent: &span, "Failed to decode decrypted operation: {}", e);
                                         continue;
                                     }
                                 }
                             } else if data.first() == Some(&0x02) {
                                 // Channel-level MLS encryption
-                                println!("  🔒 Channel MLS-encrypted message detected");
+                                tracing::debug!(parent: &span, "Channel MLS-encrypted message detected");
                                 
                                 // Message format: [0x02][channel_id (32 bytes)][encrypted_data]
                                 if data.len() < 33 {
-                                    eprintln!("  ⚠️ Channel MLS message too short (need at least 33 bytes)");
+                                    tracing::warn!(parent: &span, "Channel MLS message too short (need at least 33 bytes)");
                                     continue;
                                 }
                                 
@@ -634,7 +1976,7 @@ impl Client {
                                 let channel_id_bytes: [u8; 32] = match data[1..33].try_into() {
                                     Ok(bytes) => bytes,
                                     Err(_) => {
-                                        eprintln!("  ⚠️ Invalid channel_id in MLS message");
+                                        tracing::warn!(parent: &span, "Invalid channel_id in MLS message");
                                         continue;
                                     }
                                 };
@@ -644,74 +1986,89 @@ impl Client {
                                 let encrypted_data = &data[33..];
                                 
                                 // Decrypt using the channel's MLS group
-                                let decrypted_bytes = {
+                                let (decrypted_bytes, mls_sender) = {
                                     let mut channel_mgr = channel_manager.write().await;
                                     let provider = mls_provider.read().await;
                                     
+                                    let space_id = channel_mgr.get_channel(&channel_id).map(|channel| channel.space_id);
                                     match channel_mgr.get_mls_group_mut(&channel_id) {
                                         Some(mls_group) => {
-                                            match mls_group.decrypt_application_message(encrypted_data, &provider) {
-                                                Ok(plaintext) => {
-                                                    println!("  ✓ Decrypted Channel MLS message ({} bytes)", plaintext.len());
-                                                    plaintext
+                                            match mls_group.decrypt_application_message_with_sender(encrypted_data, &provider) {
+                                                Ok((plaintext, sender)) => {
+                                                    tracing::debug!(parent: &span, "Decrypted Channel MLS message ({} bytes)", plaintext.len());
+                                                    (plaintext, sender)
                                                 }
                                                 Err(e) => {
-                                                    eprintln!("  ⚠️ Failed to decrypt Channel MLS message: {}", e);
-                                                    eprintln!("     (You may have been removed from this Channel)");
+                                                    counters.record_decrypt_failure();
+                                                    let reason = decrypt_fail_reason(mls_group, true, &e);
+                                                    tracing::warn!(parent: &span, ?reason, "Failed to decrypt Channel MLS message: {}", e);
+                                                    if let Some(space_id) = space_id {
+                                                        let _ = events.send(ClientEvent::DecryptionFailed { space_id, reason });
+                                                    }
                                                     continue;
                                                 }
                                             }
                                         }
                                         None => {
-                                            eprintln!("  ⚠️ No MLS group found for channel_id {}", hex::encode(&channel_id.0[..8]));
-                                            eprintln!("     (You may not be a member of this Channel)");
+                                            tracing::warn!(parent: &span, "No MLS group found for channel_id {} (you may not be a member of this Channel)", channel_id.short());
                                             continue;
                                         }
                                     }
                                 };
                                 
                                 // Decode the decrypted operation
-                                match minicbor::decode::<CrdtOp>(&decrypted_bytes) {
+                                match CrdtOp::from_canonical_bytes(&decrypted_bytes) {
+                                    // The identity key that signed for MLS must be the author of
+                                    // an op sent first-hand; an unbound leaf can't vouch for any
+                                    // author. Relayed ops are vouched for by their signature alone
+                                    Ok(op) if !relayed && mls_sender != Some(op.author) => {
+                                        tracing::warn!(parent: &span, author = %op.author, sender = ?mls_sender, "Rejecting operation: author is not the MLS sender");
+                                        continue;
+                                    }
                                     Ok(op) => op,
                                     Err(e) => {
-                                        eprintln!("  ⚠️ Failed to decode decrypted operation: {}", e);
+                                        tracing::warn!(parent: &span, "Failed to decode decrypted operation: {}", e);
                                         continue;
                                     }
                                 }
                             } else if data.first() == Some(&0x00) {
                                 // Plaintext - strip marker and decode
-                                match minicbor::decode::<CrdtOp>(&data[1..]) {
+                                match CrdtOp::from_canonical_bytes(&data[1..]) {
                                     Ok(op) => op,
                                     Err(e) => {
-                                        eprintln!("  ⚠️ Failed to decode operation: {}", e);
+                                        tracing::warn!(parent: &span, "Failed to decode operation: {}", e);
                                         continue;
                                     }
                                 }
                             } else {
                                 // Legacy format (no marker) - assume plaintext
-                                match minicbor::decode::<CrdtOp>(&data[..]) {
+                                match CrdtOp::from_canonical_bytes(&data[..]) {
                                     Ok(op) => op,
                                     Err(e) => {
-                                        eprintln!("  ⚠️ Failed to decode operation: {}", e);
+                                        tracing::warn!(parent: &span, "Failed to decode operation: {}", e);
                                         continue;
                                     }
                                 }
                             };
                             
                             // Process the decoded operation
-                            println!("  ✓ Decoded operation: {:?}", op.op_type);
+                            span.record("op_id", op.op_id.0.to_string().as_str());
+                            span.record("space_id", op.space_id.short().as_str());
+                            tracing::debug!(parent: &span, "Decoded operation: {:?}", op.op_type);
                             // Verify signature before processing
                             if !op.verify_signature() {
-                                eprintln!("⚠️ Rejected message with invalid signature from {:?}", source);
+                                tracing::warn!(parent: &span, "Rejected message with invalid signature from {:?}", source);
                                 continue;
                             }
-                            println!("  ✓ Signature verified");
+                            tracing::debug!(parent: &span, "Signature verified");
                             
                             // Check if we've already processed this operation (deduplication)
-                            let is_duplicate = if let Ok(Some(_)) = store.get_op(&op.op_id) {
+                            let seen = dedup_cache.write().await
+                                .is_duplicate(&op.op_id, || matches!(store.get_op(&op.op_id), Ok(Some(_))));
+                            let is_duplicate = if seen {
                                 // Already seen this op, skip processing
                                 gossip_metrics.record_receive(&topic, true).await;
-                                println!("  ⚠️ Duplicate operation, skipping");
+                                tracing::debug!(parent: &span, "Duplicate operation, skipping");
                                 true
                             } else {
                                 gossip_metrics.record_receive(&topic, false).await;
@@ -721,9 +2078,23 @@ impl Client {
                             if is_duplicate {
                                 continue;
                             }
-                            println!("  ✓ Not a duplicate, processing...");
+                            
+                            // Drop a flood from one author without storing it, so
+                            // anti-entropy can fetch the ops once it has passed
+                            if op.author != user_id
+                                && !op_throttle.write().await.admit(op.author, std::time::Instant::now())
+                            {
+                                tracing::debug!(parent: &span, author = ?op.author, "Throttled operation from a flooding author");
+                                continue;
+                            }
+                            counters.record_op_received();
+                            if delivery_acks && op.author != user_id {
+                                ack_batcher.write().await.queue(op.space_id, op.op_id);
+                            }
+                            tracing::debug!(parent: &span, "Not a duplicate, processing...");
                             
                             tracing::debug!(
+                                parent: &span,
                                 op_id = ?op.op_id,
                                 op_type = ?op.op_type,
                                 topic = %topic,
@@ -731,28 +2102,47 @@ impl Client {
                                 "Received and validated CRDT operation"
                             );
                             
-                            // If this is a CreateSpace on discovery topic, auto-subscribe to the space
+                            // A CreateSpace on the discovery topic announces a Space; only
+                            // follow it if configured to, so we don't leak interest in it
                             if topic == "descord/space-discovery" {
                                 if let crate::crdt::OpType::CreateSpace(payload) = &op.op_type {
                                     if let crate::crdt::OpPayload::CreateSpace { name, .. } = payload {
-                                                println!("📢 Discovered space: {} (space_{})", name, ::hex::encode(&op.space_id.0[..4]));
+                                                tracing::info!(parent: &span, space = %name, "Discovered Space");
                                                 
-                                                // Auto-subscribe to the space topic
-                                                let space_topic = format!("space/{}", ::hex::encode(&op.space_id.0[..8]));
-                                                let mut net = network.write().await;
-                                                if let Ok(_) = net.subscribe(&space_topic).await {
-                                                    println!("  → Auto-subscribed to {}", space_topic);
+                                                if auto_subscribe_discovered {
+                                                    let space_topic = format!("space/{}", op.space_id.short());
+                                                    let mut net = network.write().await;
+                                                    if let Ok(_) = net.subscribe(&space_topic).await {
+                                                        tracing::info!(parent: &span, topic = %space_topic, "Auto-subscribed to discovered Space");
+                                                    }
+                                                    drop(net);
                                                 }
-                                                drop(net);
+                                                let _ = events.send(ClientEvent::SpaceDiscovered {
+                                                    space_id: op.space_id,
+                                                    name: name.clone(),
+                                                });
                                             }
                                         }
                                     }
                                     
+                                    // Archived and deleted Spaces take no new ops
+                                    if let Err(e) = space_manager.read().await.check_incoming(&op) {
+                                        tracing::debug!(parent: &span, "Dropped operation: {}", e);
+                                        continue;
+                                    }
+                                    
+                                    // Nor do we read what was posted while we were removed
+                                    if absences.read().await.get(&op.space_id).is_some_and(|absence| absence.hides(&op)) {
+                                        tracing::debug!(parent: &span, "Dropped message posted while removed from the Space");
+                                        continue;
+                                    }
+                                    
                                     // Store the operation (persistence + deduplication)
                                     if let Err(e) = store.put_op(&op) {
-                                        eprintln!("⚠️ Failed to store operation: {}", e);
+                                        tracing::warn!(parent: &span, "Failed to store operation: {}", e);
                                         continue;
                                     }
+                                    dedup_cache.write().await.insert(op.op_id);
                                     
                                     // Process based on operation type
                                     match &op.op_type {
@@ -761,31 +2151,70 @@ impl Client {
                                                 let mut manager = space_manager.write().await;
                                                 let _ = manager.process_create_space(&op);
                                                 
-                                                println!("✓ Processed CreateSpace: {} ({})", name, op.space_id);
+                                                tracing::info!(parent: &span, space = %name, "Processed CreateSpace");
                                             }
                                         }
                                         crate::crdt::OpType::UpdateSpaceVisibility(_) => {
                                             let mut manager = space_manager.write().await;
                                             let _ = manager.process_update_space_visibility(&op);
                                         }
+                                        crate::crdt::OpType::UpdateSpaceMetadata(_) => {
+                                            let mut manager = space_manager.write().await;
+                                            if let Err(e) = manager.process_update_space_metadata(&op) {
+                                                tracing::warn!(parent: &span, "Failed to process UpdateSpaceMetadata: {}", e);
+                                            }
+                                        }
+                                        crate::crdt::OpType::ArchiveSpace => {
+                                            let mut manager = space_manager.write().await;
+                                            if let Err(e) = manager.process_archive_space(&op) {
+                                                tracing::warn!(parent: &span, "Failed to process ArchiveSpace: {}", e);
+                                            }
+                                        }
+                                        crate::crdt::OpType::DeleteSpace => {
+                                            match apply_space_deletion(&store, &storage, &space_manager, &channel_manager, &thread_manager, &mls_provider, &op).await {
+                                                Ok(()) => pending_mls_messages.write().await.retain(|pending| pending.space_id != op.space_id),
+                                                Err(e) => tracing::warn!(parent: &span, "Failed to process DeleteSpace: {}", e),
+                                            }
+                                        }
+                                        crate::crdt::OpType::UpdateRole(_) => {
+                                            let mut manager = space_manager.write().await;
+                                            if let Err(e) = manager.process_update_role(&op) {
+                                                tracing::warn!(parent: &span, "Failed to process UpdateRole: {}", e);
+                                            }
+                                        }
                                         crate::crdt::OpType::CreateInvite(_) => {
                                             let mut manager = space_manager.write().await;
                                             if let Err(e) = manager.process_create_invite(&op) {
-                                                eprintln!("⚠️ Failed to process CreateInvite: {}", e);
+                                                tracing::warn!(parent: &span, "Failed to process CreateInvite: {}", e);
                                             }
                                         }
                                         crate::crdt::OpType::RevokeInvite(_) => {
                                             let mut manager = space_manager.write().await;
                                             if let Err(e) = manager.process_revoke_invite(&op) {
-                                                eprintln!("⚠️ Failed to process RevokeInvite: {}", e);
+                                                tracing::warn!(parent: &span, "Failed to process RevokeInvite: {}", e);
                                             }
                                         }
                                         crate::crdt::OpType::UseInvite(_) => {
                                             let mut manager = space_manager.write().await;
                                             if let Err(e) = manager.process_use_invite(&op) {
-                                                eprintln!("⚠️ Failed to process UseInvite: {}", e);
-                                            } else {
-                                                println!("✓ Processed UseInvite: user joined space {}", op.space_id);
+                                                tracing::warn!(parent: &span, "Failed to process UseInvite: {}", e);
+                                            } else if dht_enabled {
+                                                tracing::info!(parent: &span, joiner = %op.author, "Processed UseInvite");
+                                                drop(manager);
+                                                
+                                                // Admission waits on the DHT, so keep it off the event loop
+                                                let space_manager = Arc::clone(&space_manager);
+                                                let network = Arc::clone(&network);
+                                                let dht_writes = Arc::clone(&dht_writes);
+                                                let mls_provider = Arc::clone(&mls_provider);
+                                                let (space_id, joiner) = (op.space_id, op.author);
+                                                tokio::spawn(async move {
+                                                    match admit_to_space_mls(&space_manager, &network, &dht_writes, &mls_provider, user_id, space_id, joiner).await {
+                                                        Ok(true) => tracing::info!(space_id = %space_id.short(), %joiner, "Added invitee to Space MLS group"),
+                                                        Ok(false) => {}
+                                                        Err(e) => tracing::warn!(space_id = %space_id.short(), %joiner, error = %e, "Could not add invitee to Space MLS group"),
+                                                    }
+                                                });
                                             }
                                         }
                                         crate::crdt::OpType::AddMember(_) => {
@@ -794,41 +2223,139 @@ impl Client {
                                                 let mut manager = space_manager.write().await;
                                                 // Access spaces HashMap directly (SpaceManager::spaces is private, so use process_use_invite pattern)
                                                 // For now, just log - AddMember is handled by MLS flow or use_invite
-                                                println!("ℹ AddMember operation received for user {} on space {}", user_id, op.space_id);
-                                                println!("  (Members are added via invite or MLS Welcome message)");
+                                                tracing::debug!(parent: &span, %user_id, "AddMember received; members are added via invite or MLS Welcome");
                                             }
                                         }
                                         crate::crdt::OpType::RemoveMember(_) => {
                                             let mut manager = space_manager.write().await;
                                             if let Err(e) = manager.process_remove_member(&op) {
-                                                eprintln!("⚠️ Failed to process RemoveMember: {}", e);
+                                                tracing::warn!(parent: &span, "Failed to process RemoveMember: {}", e);
                                             }
                                         }
                                         crate::crdt::OpType::CreateChannel(_) => {
                                             let mut manager = channel_manager.write().await;
                                             let _ = manager.process_create_channel(&op);
                                         }
+                                        crate::crdt::OpType::UpdateChannel(_) => {
+                                            let mut manager = channel_manager.write().await;
+                                            if let Err(e) = manager.process_update_channel(&op) {
+                                                tracing::warn!(parent: &span, "Failed to process UpdateChannel: {}", e);
+                                            }
+                                        }
+                                        crate::crdt::OpType::DeleteChannel => {
+                                            if let Err(e) = apply_channel_deletion(&space_manager, &channel_manager, &thread_manager, &mls_provider, &op).await {
+                                                tracing::warn!(parent: &span, "Failed to process DeleteChannel: {}", e);
+                                            }
+                                        }
+                                        crate::crdt::OpType::FollowChannel(_) => {
+                                            let mut manager = channel_manager.write().await;
+                                            if let Err(e) = manager.process_follow_channel(&op) {
+                                                tracing::warn!(parent: &span, "Failed to process FollowChannel: {}", e);
+                                            }
+                                        }
+                                        crate::crdt::OpType::UnfollowChannel(_) => {
+                                            let mut manager = channel_manager.write().await;
+                                            if let Err(e) = manager.process_unfollow_channel(&op) {
+                                                tracing::warn!(parent: &span, "Failed to process UnfollowChannel: {}", e);
+                                            }
+                                        }
                                         crate::crdt::OpType::CreateThread(_) => {
                                             let mut manager = thread_manager.write().await;
-                                            let _ = manager.process_create_thread(&op);
+                                            if manager.process_create_thread(&op).is_ok() {
+                                                if let Some(event) = message_event(&manager, &op) {
+                                                    let _ = events.send(event);
+                                                }
+                                                for released in manager.take_released() {
+                                                    if let Some(event) = message_event(&manager, &released) {
+                                                        let _ = events.send(event);
+                                                    }
+                                                }
+                                            }
                                         }
                                         crate::crdt::OpType::PostMessage(_) => {
                                             let mut manager = thread_manager.write().await;
-                                            let _ = manager.process_post_message(&op);
+                                            if manager.process_post_message(&op).is_ok() {
+                                                if let Some(event) = message_event(&manager, &op) {
+                                                    let _ = events.send(event);
+                                                }
+                                            }
+                                        }
+                                        crate::crdt::OpType::ForwardMessage(_) => {
+                                            let mut manager = thread_manager.write().await;
+                                            if manager.process_forward_message(&op).is_ok() {
+                                                if let Some(event) = message_event(&manager, &op) {
+                                                    let _ = events.send(event);
+                                                }
+                                            }
                                         }
                                         crate::crdt::OpType::EditMessage(_) => {
                                             let mut manager = thread_manager.write().await;
-                                            let _ = manager.process_edit_message(&op);
+                                            if manager.process_edit_message(&op).is_ok() {
+                                                if let Some(event) = message_event(&manager, &op) {
+                                                    let _ = events.send(event);
+                                                }
+                                            }
+                                        }
+                                        crate::crdt::OpType::RedactMessage(crate::crdt::OpPayload::RedactMessage { message_id }) => {
+                                            let mut manager = thread_manager.write().await;
+                                            let target = redaction_target(&manager, message_id);
+                                            match manager.process_redact_message(&op) {
+                                                Ok(()) => {
+                                                    if let Some(target) = target {
+                                                        if let Err(e) = purge_redacted(&store, &storage, &manager, target) {
+                                                            tracing::warn!(parent: &span, "Failed to purge redacted message: {}", e);
+                                                        }
+                                                    }
+                                                    if let Some(event) = message_event(&manager, &op) {
+                                                        let _ = events.send(event);
+                                                    }
+                                                }
+                                                Err(e) => tracing::warn!(parent: &span, "Failed to process RedactMessage: {}", e),
+                                            }
+                                        }
+                                        crate::crdt::OpType::AttachLinkPreview(_) => {
+                                            let mut manager = thread_manager.write().await;
+                                            if manager.process_attach_link_preview(&op).is_ok() {
+                                                if let Some(event) = message_event(&manager, &op) {
+                                                    let _ = events.send(event);
+                                                }
+                                            }
+                                        }
+                                        crate::crdt::OpType::UpdateSpaceReactions(_) => {
+                                            let mut manager = space_manager.write().await;
+                                            match manager.process_update_space_reactions(&op) {
+                                                Ok(()) => {
+                                                    drop(manager);
+                                                    thread_manager.write().await.set_allowed_reactions(&op);
+                                                }
+                                                Err(e) => tracing::warn!(parent: &span, "Failed to process UpdateSpaceReactions: {}", e),
+                                            }
+                                        }
+                                        crate::crdt::OpType::AddReaction(_) => {
+                                            let mut manager = thread_manager.write().await;
+                                            match manager.process_add_reaction(&op) {
+                                                Ok(()) => if let Some(event) = message_event(&manager, &op) {
+                                                    let _ = events.send(event);
+                                                },
+                                                Err(e) => tracing::warn!(parent: &span, "Failed to process AddReaction: {}", e),
+                                            }
                                         }
                                         _ => {}
                                     }
+                                    let space_id = op.space_id;
+                                    let _ = op_stream.send((op, space_id));
                         }
                         NetworkEvent::PeerConnected(peer_id) => {
-                            println!("Peer connected: {}", peer_id);
+                            tracing::debug!("Peer connected: {}", peer_id);
                             // Note: Space discovery subscription happens in start() before event loop
+                            peer_book.write().await.connected(peer_id);
+                            pex_pending.store(true, std::sync::atomic::Ordering::Relaxed);
+                            dht_retry.notify_one();
+                            outbox_retry.notify_one();
                         }
                         NetworkEvent::PeerDisconnected(peer_id) => {
-                            println!("Peer disconnected: {}", peer_id);
+                            tracing::debug!("Peer disconnected: {}", peer_id);
+                            peer_book.write().await.disconnected(&peer_id);
                         }
                         _ => {}
                     }
@@ -881,6 +2408,11 @@ impl Client {
     /// 
     /// Privacy Warning: This function returns privacy information that MUST be shown to the user
     /// before the space is created.
+    #[tracing::instrument(name = "create_space", skip_all, fields(
+        space_id = tracing::field::Empty,
+        op_id = tracing::field::Empty,
+        topic = tracing::field::Empty,
+    ))]
     pub async fn create_space_with_mode(
         &self,
         name: String,
@@ -893,6 +2425,9 @@ impl Client {
             .unwrap()
             .as_secs();
         let space_id = SpaceId::from_content(&self.user_id, &name, timestamp);
+        let span = tracing::Span::current();
+        span.record("space_id", space_id.short().as_str());
+        span.record("topic", format!("space/{}", space_id.short()).as_str());
         
         // Generate privacy information for user consent
         let privacy_info = PrivacyInfo::from_visibility(visibility);
@@ -909,10 +2444,11 @@ impl Client {
             visibility,
             membership_mode,
             self.user_id,
-            &self.keypair,
+            &*self.signer,
             &provider,
         )?;
         drop(provider);
+        span.record("op_id", op.op_id.0.to_string().as_str());
         
         // Get the space before dropping the lock
         let space = manager.get_space(&space_id)
@@ -938,17 +2474,24 @@ impl Client {
         
         // Store Space metadata in DHT for offline discovery
         // (space_manager lock already dropped above)
-        if let Err(e) = self.dht_put_space(&space_id).await {
-            eprintln!("⚠️  Failed to store Space in DHT: {}", e);
-            // Non-fatal - space still created locally
+        if self.dht_enabled {
+            if let Err(e) = self.dht_put_space(&space_id).await {
+                tracing::warn!(space_id = %space_id.short(), error = %e, "Failed to store Space in DHT");
+                // Non-fatal - space still created locally
+            }
+        }
+        
+        // Public spaces are also listed in the DHT directory
+        if self.dht_enabled && visibility.is_discoverable() {
+            if let Err(e) = self.dht_put_directory_entry(&space_id).await {
+                tracing::warn!(space_id = %space_id.short(), error = %e, "Failed to list Space in public directory");
+            }
         }
         
-        // Print mode information
         if membership_mode.is_lightweight() {
-            println!("ℹ️  Created LIGHTWEIGHT space - no space-level MLS group");
-            println!("   Channels will provide E2EE when you create them.");
+            tracing::info!(space_id = %space.id.short(), "Created Lightweight Space; channels provide E2EE");
         } else {
-            println!("ℹ️  Created MLS-encrypted space - space-level encryption enabled");
+            tracing::info!(space_id = %space.id.short(), "Created MLS-encrypted Space");
         }
         
         Ok((space, op, privacy_info))
@@ -971,7 +2514,7 @@ impl Client {
                 space_id,
                 visibility,
                 self.user_id,
-                &self.keypair,
+                &*self.signer,
             )?
         }; // Lock dropped here
         
@@ -984,37 +2527,109 @@ impl Client {
         Ok(op)
     }
     
+    /// Replace a Space's tags and category (requires MANAGE_SPACE)
+    /// 
+    /// Public Spaces are re-listed in the directory so the new tags are searchable.
+    pub async fn update_space_tags(
+        &self,
+        space_id: SpaceId,
+        tags: Vec<String>,
+        category: Option<String>,
+    ) -> Result<CrdtOp> {
+        let (op, discoverable) = {
+            let mut manager = self.space_manager.write().await;
+            let op = manager.update_space_metadata(space_id, tags, category, self.user_id, &*self.signer)?;
+            let discoverable = manager.get_space(&space_id)
+                .is_some_and(|space| space.visibility.is_discoverable());
+            (op, discoverable)
+        };
+        
+        self.store.put_op(&op)?;
+        self.broadcast_op(&op).await?;
+        
+        if discoverable && self.dht_enabled {
+            if let Err(e) = self.dht_put_directory_entry(&space_id).await {
+                tracing::warn!(error = %e, "Failed to update public directory entry");
+            }
+        }
+        
+        Ok(op)
+    }
+    
+    /// Restrict the reactions members may use in a Space (requires MANAGE_SPACE)
+    /// 
+    /// Unicode and custom `:name:` emoji are both accepted; `None` lets
+    /// members use any reaction again.
+    pub async fn set_allowed_reactions(
+        &self,
+        space_id: SpaceId,
+        allowed: Option<Vec<String>>,
+    ) -> Result<CrdtOp> {
+        let op = self.space_manager.write().await
+            .update_space_reactions(space_id, allowed, self.user_id, &*self.signer)?;
+        self.thread_manager.write().await.set_allowed_reactions(&op);
+        
+        self.store.put_op(&op)?;
+        self.broadcast_op(&op).await?;
+        
+        Ok(op)
+    }
+    
+    /// Set a role's display color (RGB) and whether it is hoisted
+    pub async fn update_role(
+        &self,
+        space_id: SpaceId,
+        role_id: RoleId,
+        color: Option<u32>,
+        hoisted: bool,
+    ) -> Result<CrdtOp> {
+        let op = {
+            let mut manager = self.space_manager.write().await;
+            manager.update_role(space_id, role_id, color, hoisted, self.user_id, &*self.signer)?
+        };
+        
+        self.store.put_op(&op)?;
+        self.broadcast_op(&op).await?;
+        
+        Ok(op)
+    }
+    
     /// Create an invite for a space
+    /// 
+    /// With `role`, whoever joins through the invite is assigned that role.
+    /// The role must be below the creator's own in the hierarchy.
+    /// With `custom_code` (e.g. `welcome`), that code is used instead of a
+    /// random one; it must be unique among the Space's active invites.
     pub async fn create_invite(
         &self,
         space_id: SpaceId,
         max_uses: Option<u32>,
         max_age_hours: Option<u32>,
+        role: Option<RoleId>,
+        custom_code: Option<String>,
     ) -> Result<CrdtOp> {
-        println!("🎫 [CLIENT::CREATE_INVITE] Called");
-        println!("   Space: {}", hex::encode(&space_id.0[..8]));
-        println!("   User: {}", hex::encode(&self.user_id.as_bytes()[..8]));
+        self.check_writable(&space_id).await?;
         
         let op = {
             let mut manager = self.space_manager.write().await;
             manager.create_invite(
                 space_id,
                 self.user_id,
-                &self.keypair,
+                &*self.signer,
                 max_uses,
                 max_age_hours,
+                role,
+                custom_code,
             )?
         }; // Lock dropped here
         
-        println!("✓ [CLIENT::CREATE_INVITE] Operation created, broadcasting...");
-        
         // Store operation
         self.store.put_op(&op)?;
         
         // Broadcast operation
         self.broadcast_op(&op).await?;
         
-        println!("✓ [CLIENT::CREATE_INVITE] Complete");
+        tracing::info!(space_id = %space_id.short(), op_id = %op.op_id.0, "Created invite");
         
         Ok(op)
     }
@@ -1031,7 +2646,7 @@ impl Client {
                 space_id,
                 invite_id,
                 self.user_id,
-                &self.keypair,
+                &*self.signer,
             )?
         }; // Lock dropped here
         
@@ -1053,7 +2668,7 @@ impl Client {
         code: String,
     ) -> Result<CrdtOp> {
         // Subscribe to space topic FIRST so we can receive operations via GossipSub
-        println!("ℹ Subscribing to Space topic...");
+        tracing::info!("Subscribing to Space topic...");
         self.subscribe_to_space(&space_id).await?;
         
         // First check if we have the Space locally
@@ -1064,12 +2679,17 @@ impl Client {
         
         // If Space doesn't exist locally, try fetching from DHT or create placeholder
         if !has_space {
-            println!("⚠️  Space not found locally, will sync via GossipSub from connected peers...");
+            tracing::warn!("Space not found locally, will sync via GossipSub from connected peers...");
             
             // Try DHT as a fallback
-            match self.dht_get_space(&space_id).await {
+            let from_dht = if self.dht_enabled {
+                self.dht_get_space(&space_id).await
+            } else {
+                Err(dht_disabled())
+            };
+            match from_dht {
                 Ok(space) => {
-                    println!("✓ Retrieved Space '{}' from DHT", space.name);
+                    tracing::info!("Retrieved Space '{}' from DHT", space.name);
                     
                     // Store space metadata locally
                     let mut manager = self.space_manager.write().await;
@@ -1080,47 +2700,43 @@ impl Client {
                     match self.dht_get_operations(&space_id).await {
                         Ok(ops) => {
                             if !ops.is_empty() {
-                                println!("✓ Fetched {} operations from DHT", ops.len());
+                                tracing::info!("Fetched {} operations from DHT", ops.len());
                                 for op in ops {
                                     if let Err(e) = self.handle_incoming_op(op).await {
-                                        eprintln!("⚠ Failed to apply operation: {}", e);
+                                        tracing::warn!("Failed to apply operation: {}", e);
                                     }
                                 }
-                                println!("✓ Applied operations to rebuild Space state");
+                                tracing::info!("Applied operations to rebuild Space state");
                             }
                         }
                         Err(e) => {
-                            eprintln!("⚠ Failed to fetch operations from DHT: {}", e);
+                            tracing::warn!("Failed to fetch operations from DHT: {}", e);
                         }
                     }
                 }
                 Err(e) => {
-                    eprintln!("⚠ DHT fetch failed: {}", e);
-                    println!("  Requesting sync from connected peers via GossipSub...");
+                    if self.dht_enabled {
+                        tracing::warn!("DHT fetch failed: {}", e);
+                    }
+                    tracing::debug!("Requesting sync from connected peers via GossipSub...");
                     
                     // Broadcast a sync request on the Space topic
-                    let space_topic = format!("space/{}", hex::encode(&space_id.0[..8]));
-                    let sync_request = format!("SYNC_REQUEST:{}", hex::encode(&space_id.0));
-                    if let Err(e) = self.broadcast_raw(&space_topic, sync_request.as_bytes().to_vec()).await {
-                        eprintln!("⚠ Failed to send sync request: {}", e);
+                    if let Err(e) = self.request_space_sync(&space_id).await {
+                        tracing::warn!("Failed to send sync request: {}", e);
                     }
                     
                     // Wait for peers to respond with operations
-                    println!("  Waiting 3 seconds for peers to resend Space operations...");
+                    tracing::debug!("Waiting 3 seconds for peers to resend Space operations...");
                     tokio::time::sleep(Duration::from_secs(3)).await;
                     
                     // Check if we received the Space
                     let manager = self.space_manager.read().await;
                     if manager.get_space(&space_id).is_none() {
                         drop(manager);
-                        println!("  Tip: Make sure you're connected to the Space creator");
-                        println!("  Use 'network' to check connections, 'connect <multiaddr>' to connect");
-                        return Err(Error::NotFound(format!(
-                            "Space not found. Connect to the Space creator first, then try again."
-                        )));
+                        return Err(self.space_lookup_error(space_id, e).await);
                     }
                     drop(manager);
-                    println!("✓ Received Space data from peer");
+                    tracing::info!("Received Space data from peer");
                 }
             }
         }
@@ -1131,7 +2747,7 @@ impl Client {
                 space_id,
                 code,
                 self.user_id,
-                &self.keypair,
+                &*self.signer,
             )?
         }; // Lock dropped here
         
@@ -1147,10 +2763,112 @@ impl Client {
         Ok(op)
     }
     
+    /// Join a Space from an invite link (`descord://join/...`)
+    /// 
+    /// If the link carries a relay hint, the client connects to that relay
+    /// first; a relay that cannot be reached is reported but does not abort
+    /// the join, since the Space may still be reachable through other peers.
+    pub async fn join_with_uri(&self, uri: &str) -> Result<CrdtOp> {
+        let invite_uri = InviteUri::parse(uri)?;
+        
+        if let Some(relay) = &invite_uri.relay {
+            tracing::info!(%relay, "Connecting to relay from invite link");
+            if let Err(e) = self.connect_to_relay(relay).await {
+                tracing::warn!(%relay, error = %e, "Failed to connect to relay");
+            }
+        }
+        self.dial_invite_hints(&invite_uri.hints).await;
+        
+        self.join_with_invite(invite_uri.space_id, invite_uri.code).await
+    }
+    
+    /// Reach an invite's creator through its relay hints
+    /// 
+    /// Tries the hints in order until one connects, so the Space state and
+    /// the MLS add don't depend on the creator being findable in the DHT.
+    /// Failures are only reported: the creator may be reachable otherwise.
+    async fn dial_invite_hints(&self, hints: &[String]) {
+        for hint in hints {
+            let Ok(addr) = hint.parse::<libp2p::Multiaddr>() else {
+                tracing::warn!(%hint, "Ignoring malformed relay hint");
+                continue;
+            };
+            let Some(libp2p::multiaddr::Protocol::P2p(creator)) = addr.iter().last() else {
+                tracing::warn!(%hint, "Relay hint does not name the creator");
+                continue;
+            };
+            
+            tracing::info!(%hint, "Dialing invite creator through relay");
+            if let Err(e) = self.network.write().await.dial(addr).await {
+                tracing::warn!(%hint, error = %e, "Failed to dial invite creator");
+                continue;
+            }
+            
+            let deadline = tokio::time::Instant::now() + INVITE_HINT_DIAL_TIMEOUT;
+            while tokio::time::Instant::now() < deadline {
+                if self.network.read().await.connected_peers().await.contains(&creator) {
+                    tracing::info!(%creator, "Connected to invite creator via relay");
+                    return;
+                }
+                tokio::time::sleep(Duration::from_millis(200)).await;
+            }
+            tracing::warn!(%hint, "Timed out reaching invite creator");
+        }
+    }
+
+    /// Join a Space with an invite code, including MLS membership and history
+    ///
+    /// Publishes this user's KeyPackages so the Space owner can add them to
+    /// the MLS group, uses the invite (fetching the Space from the DHT or
+    /// connected peers if it isn't known locally), and catches up on the
+    /// Space's operations. The owner answers the UseInvite with a Welcome,
+    /// which is applied when it arrives; [`Client::space_epoch`] turns `Some`
+    /// once it has.
+    pub async fn join_space(&self, space_id: SpaceId, invite_code: &str) -> Result<Space> {
+        if self.dht_enabled {
+            if let Err(e) = self.publish_key_packages_to_dht().await {
+                tracing::warn!(error = %e, "Could not publish KeyPackages; the owner can't add us to MLS yet");
+            }
+        }
+
+        let had_space = self.get_space(&space_id).await.is_some();
+        self.join_with_invite(space_id, invite_code.to_string()).await?;
+
+        // join_with_invite only pulls history for Spaces it had to fetch
+        if had_space {
+            if let Err(e) = self.sync_space_from_dht(space_id).await {
+                tracing::warn!(error = %e, "Could not sync Space history from DHT");
+            }
+        }
+
+        self.get_space(&space_id).await
+            .ok_or_else(|| Error::NotFound(format!("Space {:?} not found after joining", space_id)))
+    }
+    
     /// List all invites for a space
+    /// 
+    /// Invites this user created carry our current relay circuit addresses
+    /// as relay hints, so links made from them reach us after a rotation.
     pub async fn list_invites(&self, space_id: &SpaceId) -> Vec<Invite> {
+        let relay_hints = self.relay_circuits.read().await.clone();
+        let manager = self.space_manager.read().await;
+        manager.list_invites(space_id).into_iter()
+            .cloned()
+            .map(|mut invite| {
+                if invite.creator == self.user_id {
+                    invite.relay_hints = relay_hints.clone();
+                }
+                invite
+            })
+            .collect()
+    }
+    
+    /// Who joined through an invite and when (unix seconds)
+    /// 
+    /// Only admins and the invite's creator may read this.
+    pub async fn invite_usage(&self, space_id: SpaceId, invite_id: InviteId) -> Result<Vec<(UserId, u64)>> {
         let manager = self.space_manager.read().await;
-        manager.list_invites(space_id).into_iter().cloned().collect()
+        manager.invite_usage(&space_id, &invite_id, &self.user_id)
     }
     
     /// Sync a Space from DHT (useful after being added as a member via GossipSub)
@@ -1158,23 +2876,31 @@ impl Client {
     /// When you're added to a Space via GossipSub, you receive the AddMember operation
     /// but not the historical operations (CreateSpace, CreateChannel, messages, etc.).
     /// This method fetches all historical operations from DHT and applies them.
+    /// 
+    /// With the DHT disabled, connected peers are asked for the ops instead;
+    /// they arrive over gossip.
     pub async fn sync_space_from_dht(&self, space_id: SpaceId) -> Result<()> {
-        println!("🔄 Syncing Space {} from DHT...", space_id);
+        if !self.dht_enabled {
+            self.subscribe_to_space(&space_id).await?;
+            return self.request_space_sync(&space_id).await;
+        }
+        
+        tracing::debug!("Syncing Space {} from DHT...", space_id);
         
         // Fetch CRDT operations from DHT
         let ops = self.dht_get_operations(&space_id).await?;
         
-        println!("  → Fetched {} operations from DHT", ops.len());
+        tracing::debug!("Fetched {} operations from DHT", ops.len());
         
         // Apply operations to rebuild state
         if !ops.is_empty() {
             for op in &ops {
                 // Apply each operation (this rebuilds channels, threads, messages, etc.)
                 if let Err(e) = self.handle_incoming_op(op.clone()).await {
-                    eprintln!("⚠ Failed to apply operation: {}", e);
+                    tracing::warn!("Failed to apply operation: {}", e);
                 }
             }
-            println!("✓ Synced Space state from {} operations", ops.len());
+            tracing::info!("Synced Space state from {} operations", ops.len());
         }
         
         // Subscribe to space topic for future updates
@@ -1183,20 +2909,47 @@ impl Client {
         Ok(())
     }
     
+    /// Fail DHT calls when `ClientConfig::dht_enabled` is off
+    fn require_dht(&self) -> Result<()> {
+        if self.dht_enabled {
+            Ok(())
+        } else {
+            Err(dht_disabled())
+        }
+    }
+    
+    /// Why a Space couldn't be found for joining, given the lookup's error
+    /// 
+    /// DHT misses and timeouts look alike, so without any connected peer
+    /// the network is reported unreachable rather than the Space missing.
+    async fn space_lookup_error(&self, space_id: SpaceId, error: Error) -> Error {
+        match error {
+            Error::Join(JoinError::SpaceIdMismatch { .. }) | Error::InvalidSignature => error,
+            _ if self.network.read().await.connected_peers().await.is_empty() => {
+                JoinError::Unreachable("no peers connected".to_string()).into()
+            }
+            _ => JoinError::SpaceNotFound(space_id).into(),
+        }
+    }
+    
     /// Join a space by fetching metadata from DHT (works when creator is offline)
     /// 
     /// This is the primary way to join a space when you have the Space ID but
     /// the creator is not online. The Space metadata is retrieved from the DHT.
     pub async fn join_space_from_dht(&self, space_id: SpaceId) -> Result<crate::forum::Space> {
+        self.require_dht()?;
         // First, try to get the space from DHT
-        let space = self.dht_get_space(&space_id).await?;
+        let space = match self.dht_get_space(&space_id).await {
+            Ok(space) => space,
+            Err(e) => return Err(self.space_lookup_error(space_id, e).await),
+        };
         
         // Add space to local manager
         let mut manager = self.space_manager.write().await;
         
         // Check if we already have this space
         if manager.get_space(&space_id).is_some() {
-            println!("ℹ️  Space already exists locally: {}", space.name);
+            tracing::info!("Space already exists locally: {}", space.name);
             return Ok(space);
         }
         
@@ -1204,21 +2957,23 @@ impl Client {
         drop(manager); // Release lock for async operation
         let ops = self.dht_get_operations(&space_id).await?;
         
-        println!("✓ Joined Space from DHT: {}", space.name);
-        println!("  Space ID: {}", space_id);
-        println!("  Owner: {}", space.owner);
-        println!("  Members: {}", space.members.len());
-        println!("  Operations fetched: {}", ops.len());
+        tracing::info!(
+            space_id = %space_id.short(),
+            owner = %space.owner,
+            members = space.member_count(),
+            ops = ops.len(),
+            "Joined Space from DHT"
+        );
         
         // Apply operations to rebuild state
         if !ops.is_empty() {
             for op in ops {
                 // Apply each operation (this rebuilds channels, threads, messages, etc.)
                 if let Err(e) = self.handle_incoming_op(op).await {
-                    eprintln!("⚠ Failed to apply operation: {}", e);
+                    tracing::warn!("Failed to apply operation: {}", e);
                 }
             }
-            println!("✓ Applied operations to rebuild Space state");
+            tracing::info!("Applied operations to rebuild Space state");
         }
         
         // Subscribe to space topic for future updates
@@ -1233,9 +2988,11 @@ impl Client {
     
     /// Store Space metadata in the DHT for offline discovery
     /// 
-    /// This allows other users to join the Space even when the creator is offline.
-    /// The metadata is encrypted and can only be decrypted by those who know the Space ID.
+    /// This lets members load the Space even when the creator is offline.
+    /// MLS Spaces seal the metadata for their members under the current
+    /// epoch's key; Lightweight Spaces' can be read by anyone with the Space ID.
     pub async fn dht_put_space(&self, space_id: &SpaceId) -> Result<()> {
+        self.require_dht()?;
         use crate::forum::{SpaceMetadata, EncryptedSpaceMetadata};
         
         // Get the space
@@ -1243,50 +3000,117 @@ impl Client {
         let space = manager.get_space(space_id)
             .ok_or_else(|| Error::NotFound(format!("Space {:?} not found", space_id)))?;
         
-        // Create metadata (convert Keypair to ed25519_dalek::SigningKey)
-        let signing_key = ed25519_dalek::SigningKey::from_bytes(&self.keypair.to_bytes());
-        let metadata = SpaceMetadata::from_space(space, &signing_key);
+        // Create metadata
+        let metadata = SpaceMetadata::from_space(space, &*self.signer)?;
+        drop(manager);
         
-        // Encrypt metadata
-        let encrypted = EncryptedSpaceMetadata::encrypt(&metadata)?;
+        // Seal metadata for the Space's members
+        let keys = self.dht_keys(space_id, crate::mls::key_labels::SPACE_METADATA).await?;
+        let encrypted = EncryptedSpaceMetadata::seal(&metadata, &keys)?;
         
         // Serialize for DHT
         let value = encrypted.to_bytes()?;
         
         // Compute DHT key
         let key = EncryptedSpaceMetadata::dht_key(space_id);
+        self.record_dht_write(&key, Some(*space_id), "space_metadata", value.len()).await;
         
         // Store in DHT
         let mut network = self.network.write().await;
         network.dht_put(key, value).await?;
         
-        println!("✓ Stored Space metadata in DHT: {}", space.name);
+        tracing::info!(space_id = %space_id.short(), "Stored Space metadata in DHT");
+        
+        Ok(())
+    }
+    
+    /// List a public Space in the DHT directory
+    /// 
+    /// Fetches the current directory, inserts or refreshes this Space's entry
+    /// and stores it back. Spaces that are not `SpaceVisibility::Public` are
+    /// rejected so they never leak into the directory.
+    pub async fn dht_put_directory_entry(&self, space_id: &SpaceId) -> Result<()> {
+        self.require_dht()?;
+        use crate::forum::{DirectoryEntry, SpaceDirectory};
+        
+        let entry = {
+            let manager = self.space_manager.read().await;
+            let space = manager.get_space(space_id)
+                .ok_or_else(|| Error::NotFound(format!("Space {:?} not found", space_id)))?;
+            DirectoryEntry::from_space(space)
+                .ok_or_else(|| Error::InvalidOperation("Only public spaces can be listed in the directory".to_string()))?
+        };
+        
+        let key = SpaceDirectory::dht_key();
+        let mut network = self.network.write().await;
+        let mut directory = match network.dht_get(key.clone()).await {
+            Ok(values) => Self::merge_directory_records(&values),
+            Err(_) => SpaceDirectory::default(),
+        };
+        directory.upsert(entry);
+        
+        let value = directory.to_bytes()?;
+        self.record_dht_write(&key, Some(*space_id), "space_directory", value.len()).await;
+        network.dht_put(key, value).await?;
+        
+        tracing::info!(space_id = %space_id.short(), "Listed Space in public directory");
         
         Ok(())
     }
     
+    /// Search the public Space directory by name and, optionally, tag
+    /// 
+    /// Matches are case-insensitive name substrings; an empty query lists
+    /// every public Space. A directory that was never written yields no results.
+    pub async fn search_public_spaces(&self, query: &str, tag: Option<&str>) -> Vec<crate::forum::DirectoryEntry> {
+        use crate::forum::SpaceDirectory;
+        
+        if !self.dht_enabled {
+            return Vec::new();
+        }
+        let mut network = self.network.write().await;
+        // A failed lookup means no peer holds a directory yet
+        let values = network.dht_get(SpaceDirectory::dht_key()).await.unwrap_or_default();
+        
+        Self::merge_directory_records(&values).search(query, tag)
+    }
+    
+    /// Merge every directory record returned by the DHT, skipping corrupt ones
+    fn merge_directory_records(values: &[Vec<u8>]) -> crate::forum::SpaceDirectory {
+        let mut directory = crate::forum::SpaceDirectory::default();
+        for value in values {
+            if let Ok(record) = crate::forum::SpaceDirectory::from_bytes(value) {
+                directory.merge(record);
+            }
+        }
+        directory
+    }
+    
     /// Retrieve Space metadata from the DHT
     /// 
     /// This allows joining a Space even when the creator is offline.
     pub async fn dht_get_space(&self, space_id: &SpaceId) -> Result<crate::forum::Space> {
+        self.require_dht()?;
         use crate::forum::{SpaceMetadata, EncryptedSpaceMetadata};
         
         // Compute DHT key
         let key = EncryptedSpaceMetadata::dht_key(space_id);
+        let keys = self.dht_keys(space_id, crate::mls::key_labels::SPACE_METADATA).await
+            .unwrap_or(SpaceKeys::Public);
         
         // Query DHT
         let mut network = self.network.write().await;
         let values = network.dht_get(key).await?;
         
         if values.is_empty() {
-            return Err(Error::NotFound(format!("Space {:?} not found in DHT", space_id)));
+            return Err(JoinError::SpaceNotFound(*space_id).into());
         }
         
         // Deserialize first value
         let encrypted = EncryptedSpaceMetadata::from_bytes(&values[0])?;
         
         // Decrypt metadata
-        let metadata = encrypted.decrypt()?;
+        let metadata = encrypted.open(&keys)?;
         
         // Verify signature
         if !metadata.verify_signature() {
@@ -1295,7 +3119,7 @@ impl Client {
         
         // Verify Space ID matches
         if metadata.id != *space_id {
-            return Err(Error::InvalidOperation("Space ID mismatch".to_string()));
+            return Err(JoinError::SpaceIdMismatch { expected: *space_id, actual: metadata.id }.into());
         }
         
         // Convert metadata to Space - use Space::new_with_mode to properly initialize roles
@@ -1310,155 +3134,118 @@ impl Client {
         );
         
         // Update fields that aren't set by constructor
-        space.members = metadata.initial_members.clone();
+        space.member_roles.clear();
+        for (user_id, role) in &metadata.initial_members {
+            space.add_member(*user_id, *role);
+        }
         space.invites = std::collections::HashMap::new();
         space.invite_permissions = metadata.invite_permissions.clone();
         space.epoch = metadata.epoch;
+        space.set_tags(&metadata.tags, metadata.category.clone());
         
-        println!("✓ Retrieved Space from DHT: {}", space.name);
+        tracing::info!("Retrieved Space from DHT: {}", space.name);
         
         Ok(space)
     }
     
     /// Store CRDT operations in the DHT
     /// 
-    /// Batches operations and stores them encrypted for later retrieval.
+    /// Operations are merged into the tail batch until it holds
+    /// `BATCH_TARGET_OPS`, so per-op puts don't add a sequence each. An index
+    /// bloated by older one-op batches is compacted on the way.
     /// This enables offline message history sync.
+    #[tracing::instrument(skip_all, fields(space_id = %space_id.short(), ops = ops.len()))]
     pub async fn dht_put_operations(
         &self,
         space_id: &SpaceId,
         ops: Vec<CrdtOp>,
     ) -> Result<()> {
-        use crate::crdt::{OperationBatch, EncryptedOperationBatch, OperationBatchIndex};
-        
-        eprintln!("🔷 [DHT_PUT_OPS] START: Storing {} operations for space {}", 
-                 ops.len(), hex::encode(&space_id.0[..8]));
-        
-        if ops.is_empty() {
-            eprintln!("🔷 [DHT_PUT_OPS] Empty ops, returning early");
-            return Ok(());
-        }
-        
-        // First, fetch or create the index
-        eprintln!("🔷 [DHT_PUT_OPS] Step 1: Acquiring network lock...");
+        self.require_dht()?;
+        let keys = self.dht_keys(space_id, crate::mls::key_labels::OP_BATCH).await?;
         let mut network = self.network.write().await;
-        eprintln!("🔷 [DHT_PUT_OPS] Step 1: ✓ Network lock acquired");
-        
-        let index_key = OperationBatchIndex::compute_dht_key(space_id);
-        eprintln!("🔷 [DHT_PUT_OPS] Step 2: Fetching DHT index for key {}...", hex::encode(&index_key[..8]));
-        
-        let mut index = match network.dht_get(index_key.clone()).await {
-            Ok(values) if !values.is_empty() => {
-                eprintln!("🔷 [DHT_PUT_OPS] Step 2: ✓ Found existing index with {} values", values.len());
-                OperationBatchIndex::from_bytes(&values[0])?
-            }
-            Ok(_) => {
-                eprintln!("🔷 [DHT_PUT_OPS] Step 2: Creating new index (no values found)");
-                OperationBatchIndex::new(*space_id)
-            }
-            Err(e) => {
-                eprintln!("🔷 [DHT_PUT_OPS] Step 2: Creating new index (error: {})", e);
-                // Create new index
-                OperationBatchIndex::new(*space_id)
-            }
-        };
-        
-        // Get next sequence number
-        let sequence = index.batch_sequences.last().copied().unwrap_or(0) + 1;
-        eprintln!("🔷 [DHT_PUT_OPS] Step 3: Using sequence number {}", sequence);
-        
-        // Create operation batch
-        eprintln!("🔷 [DHT_PUT_OPS] Step 4: Creating operation batch...");
-        let batch = OperationBatch::new(*space_id, ops.clone(), sequence);
-        
-        // Encrypt batch
-        eprintln!("🔷 [DHT_PUT_OPS] Step 5: Encrypting batch...");
-        let encrypted = EncryptedOperationBatch::encrypt(&batch)?;
-        eprintln!("🔷 [DHT_PUT_OPS] Step 5: ✓ Batch encrypted");
-        
-        // Store batch in DHT
-        let batch_key = encrypted.dht_key();
-        let batch_bytes = encrypted.to_bytes()?;
-        eprintln!("🔷 [DHT_PUT_OPS] Step 6: Storing batch in DHT (key: {}, size: {} bytes)...", 
-                 hex::encode(&batch_key[..8]), batch_bytes.len());
-        network.dht_put(batch_key, batch_bytes).await?;
-        eprintln!("🔷 [DHT_PUT_OPS] Step 6: ✓ Batch stored in DHT");
-        
-        // Update index
-        eprintln!("🔷 [DHT_PUT_OPS] Step 7: Updating index...");
-        index.add_batch(sequence, ops.len() as u32);
-        
-        // Store updated index
-        let index_bytes = index.to_bytes()?;
-        eprintln!("🔷 [DHT_PUT_OPS] Step 8: Storing updated index in DHT (size: {} bytes)...", index_bytes.len());
-        network.dht_put(index_key, index_bytes).await?;
-        eprintln!("🔷 [DHT_PUT_OPS] Step 8: ✓ Index stored in DHT");
-        
-        eprintln!("🔷 [DHT_PUT_OPS] END: ✓ Successfully stored {} operations in DHT (batch {})", ops.len(), sequence);
-        
-        Ok(())
+        dht_append_operations(&mut network, &self.dht_writes, &keys, space_id, ops).await
     }
     
     /// Retrieve CRDT operations from the DHT
     /// 
     /// Fetches all operation batches for a Space and returns them in order.
     pub async fn dht_get_operations(&self, space_id: &SpaceId) -> Result<Vec<CrdtOp>> {
-        use crate::crdt::{EncryptedOperationBatch, OperationBatchIndex};
-        
-        // Fetch index
+        self.dht_get_operations_range(space_id, 0..usize::MAX).await
+    }
+    
+    /// Fetch a Space's operation index from the DHT, if one was stored
+    /// 
+    /// Its `batch_sequences` tell a reader how many batches there are, so
+    /// history can be paged with [`Client::dht_get_operations_range`].
+    pub async fn dht_get_operation_index(&self, space_id: &SpaceId) -> Result<Option<crate::crdt::OperationBatchIndex>> {
+        self.require_dht()?;
         let mut network = self.network.write().await;
-        let index_key = OperationBatchIndex::compute_dht_key(space_id);
-        
-        let index = match network.dht_get(index_key).await {
-            Ok(values) if !values.is_empty() => {
-                OperationBatchIndex::from_bytes(&values[0])?
-            }
-            _ => {
-                // No operations stored yet
-                return Ok(Vec::new());
-            }
+        dht_get_index(&mut network, space_id).await
+    }
+    
+    /// Retrieve the operations in batches at index positions `batches`
+    /// 
+    /// Positions past the end of the index are ignored. Batches that are not
+    /// found (still propagating) are skipped.
+    pub async fn dht_get_operations_range(
+        &self,
+        space_id: &SpaceId,
+        batches: std::ops::Range<usize>,
+    ) -> Result<Vec<CrdtOp>> {
+        self.require_dht()?;
+        let keys = self.dht_keys(space_id, crate::mls::key_labels::OP_BATCH).await
+            .unwrap_or(SpaceKeys::Public);
+        let mut network = self.network.write().await;
+        let Some(index) = dht_get_index(&mut network, space_id).await? else {
+            // No operations stored yet
+            return Ok(Vec::new());
         };
         
-        // Fetch all batches
         let mut all_ops = Vec::new();
-        
-        for sequence in &index.batch_sequences {
-            // Compute batch key
-            let batch_key = EncryptedOperationBatch::compute_dht_key(space_id, *sequence);
-            
-            // Fetch batch
-            match network.dht_get(batch_key).await {
-                Ok(values) if !values.is_empty() => {
-                    let encrypted = EncryptedOperationBatch::from_bytes(&values[0])?;
-                    let batch = encrypted.decrypt()?;
-                    
-                    // Verify Space ID matches
-                    if batch.space_id != *space_id {
-                        return Err(Error::InvalidOperation("Space ID mismatch in batch".to_string()));
-                    }
-                    
-                    all_ops.extend(batch.operations);
-                }
-                _ => {
-                    // Batch not found, skip (might be still propagating)
-                    println!("⚠ Batch {} not found in DHT", sequence);
-                }
+        for sequence in index.sequences_in(batches) {
+            match dht_get_batch(&mut network, &keys, space_id, *sequence).await? {
+                Some(batch) => all_ops.extend(batch.operations),
+                None => tracing::warn!("Batch {} not found in DHT", sequence),
             }
         }
         
-        println!("✓ Retrieved {} operations from DHT", all_ops.len());
+        tracing::info!("Retrieved {} operations from DHT", all_ops.len());
         
         Ok(all_ops)
     }
     
+    /// A Space's keys for its `label` records (see [`dht_space_keys`])
+    async fn dht_keys(&self, space_id: &SpaceId, label: &str) -> Result<SpaceKeys> {
+        dht_space_keys(&self.space_manager, &self.network, self.dht_enabled, space_id, label).await
+    }
+    
+    /// Store the Space's key history after this client committed, so the
+    /// members it admitted can open older records
+    async fn publish_key_history(&self, space_id: &SpaceId) {
+        if !self.dht_enabled {
+            return;
+        }
+        if let Err(e) = dht_put_key_history(&self.space_manager, &self.network, &self.dht_writes, space_id).await {
+            tracing::warn!(space_id = %space_id.short(), error = %e, "Could not store key history");
+        }
+    }
+    
+    /// Remember a DHT record this client wrote, replacing any earlier write to the same key
+    /// 
+    /// Recorded before the PUT is issued: the record is always kept in the local
+    /// Kademlia store, even if replication to peers later fails.
+    async fn record_dht_write(&self, key: &[u8], space_id: Option<SpaceId>, value_type: &str, size_bytes: usize) {
+        record_dht_write(&self.dht_writes, key, space_id, value_type, size_bytes).await;
+    }
+    
     // ========================================================================
     // DHT Blob Storage (Phase 4: Encrypted Blob Replication)
     // ========================================================================
     
     /// Store an encrypted blob in the DHT for offline availability
     /// 
-    /// Takes a locally-encrypted blob and encrypts it again with the Space-derived
-    /// key before storing in the DHT. This allows Space members to discover and
+    /// Takes a locally-encrypted blob and seals it again for the Space's members
+    /// before storing in the DHT. This allows Space members to discover and
     /// fetch blobs even when the original author is offline.
     pub async fn dht_put_blob(
         &self,
@@ -1466,10 +3253,12 @@ impl Client {
         blob_hash: &crate::storage::BlobHash,
         local_blob: &crate::storage::EncryptedBlob,
     ) -> Result<()> {
+        self.require_dht()?;
         use crate::storage::{DhtBlob, BlobIndex};
         
-        // Encrypt blob for DHT storage
-        let dht_blob = DhtBlob::encrypt(space_id, blob_hash, local_blob)?;
+        // Seal blob for the Space's members
+        let keys = self.dht_keys(space_id, crate::mls::key_labels::BLOB).await?;
+        let dht_blob = DhtBlob::seal(space_id, blob_hash, local_blob, &keys)?;
         
         // Serialize for DHT
         let blob_bytes = dht_blob.to_bytes()?;
@@ -1492,6 +3281,7 @@ impl Client {
         };
         
         // Store blob in DHT
+        self.record_dht_write(&blob_key, Some(*space_id), "blob", blob_bytes.len()).await;
         network.dht_put(blob_key, blob_bytes).await?;
         
         // Update index (approximate size - we don't track exact size here)
@@ -1499,9 +3289,10 @@ impl Client {
         
         // Store updated index
         let index_bytes = index.to_bytes()?;
+        self.record_dht_write(&index_key, Some(*space_id), "blob_index", index_bytes.len()).await;
         network.dht_put(index_key, index_bytes).await?;
         
-        println!("✓ Stored blob in DHT: {} bytes", dht_blob.ciphertext.len());
+        tracing::info!("Stored blob in DHT: {} bytes", dht_blob.ciphertext.len());
         
         Ok(())
     }
@@ -1515,10 +3306,13 @@ impl Client {
         space_id: &SpaceId,
         blob_hash: &crate::storage::BlobHash,
     ) -> Result<crate::storage::EncryptedBlob> {
+        self.require_dht()?;
         use crate::storage::DhtBlob;
         
         // Compute DHT key
         let blob_key = DhtBlob::compute_dht_key(space_id, blob_hash);
+        let keys = self.dht_keys(space_id, crate::mls::key_labels::BLOB).await
+            .unwrap_or(SpaceKeys::Public);
         
         // Fetch from DHT
         let mut network = self.network.write().await;
@@ -1540,9 +3334,9 @@ impl Client {
         }
         
         // Decrypt DHT layer to get locally-encrypted blob
-        let local_blob = dht_blob.decrypt()?;
+        let local_blob = dht_blob.open(&keys)?;
         
-        println!("✓ Retrieved blob from DHT: {} bytes", dht_blob.ciphertext.len());
+        tracing::info!("Retrieved blob from DHT: {} bytes", dht_blob.ciphertext.len());
         
         Ok(local_blob)
     }
@@ -1551,6 +3345,7 @@ impl Client {
     /// 
     /// Useful for discovering what blobs can be fetched.
     pub async fn dht_list_blobs(&self, space_id: &SpaceId) -> Result<Vec<crate::storage::BlobHash>> {
+        self.require_dht()?;
         use crate::storage::BlobIndex;
         
         // Fetch index
@@ -1567,11 +3362,26 @@ impl Client {
             }
         };
         
-        println!("✓ Found {} blobs in DHT for Space", index.blob_hashes.len());
+        tracing::info!("Found {} blobs in DHT for Space", index.blob_hashes.len());
         
         Ok(index.blob_hashes)
     }
     
+    /// Check where a blob can be loaded from, without downloading it
+    /// 
+    /// Looks for the file locally and for the hash in the Space's DHT blob
+    /// index, so a UI can flag a missing attachment before fetching it. An
+    /// unreachable DHT counts as not available there.
+    pub async fn blob_available(
+        &self,
+        space_id: &SpaceId,
+        hash: &crate::storage::BlobHash,
+    ) -> Result<crate::storage::BlobAvailability> {
+        let local = self.storage.has_blob(hash)?;
+        let dht = self.dht_enabled && self.dht_list_blobs(space_id).await?.contains(hash);
+        Ok(crate::storage::BlobAvailability { local, dht })
+    }
+    
     // ============ MLS KeyPackage Management ============
     
     /// Get a KeyPackage bundle for this user (for direct P2P exchange)
@@ -1579,45 +3389,63 @@ impl Client {
     /// This allows direct KeyPackage exchange between connected peers without using DHT.
     /// Useful for 2-peer scenarios where DHT quorum cannot be achieved.
     pub async fn get_key_package_bundle(&self) -> Result<crate::mls::KeyPackageBundle> {
+        Ok(self.take_key_packages(1).await?.remove(0))
+    }
+    
+    /// Generate KeyPackages until at least `min` are unused, returning how
+    /// many were generated
+    pub async fn ensure_keypackages(&self, min: usize) -> Result<usize> {
+        let mut kp_store = self.keypackage_store.write().await;
+        let kp_store = kp_store.as_mut().ok_or_else(no_mls_signer)?;
+        let provider = self.mls_provider.read().await;
+        kp_store.ensure_available(min, &provider)
+    }
+    
+    /// Number of unused KeyPackages in the local pool
+    pub async fn available_keypackages(&self) -> usize {
+        self.keypackage_store.read().await.as_ref().map_or(0, |store| store.available_count())
+    }
+    
+    /// Hand out `count` one-time KeyPackages, generating them if the pool
+    /// is short and refilling it once it runs low
+    async fn take_key_packages(&self, count: usize) -> Result<Vec<crate::mls::KeyPackageBundle>> {
         let mut kp_store = self.keypackage_store.write().await;
-        kp_store.get_key_package_bundle()
+        let kp_store = kp_store.as_mut().ok_or_else(no_mls_signer)?;
+        let provider = self.mls_provider.read().await;
+        kp_store.ensure_available(count, &provider)?;
+        let bundles = kp_store.take_key_package_bundles(count)?;
+        if kp_store.available_count() * 2 < self.key_package_pool {
+            let generated = kp_store.ensure_available(self.key_package_pool, &provider)?;
+            tracing::debug!(generated, "Refilled KeyPackage pool");
+        }
+        Ok(bundles)
     }
     
     /// Publish this user's KeyPackages to the DHT
     /// 
     /// Other users can fetch these KeyPackages to add this user to their MLS groups.
+    /// Published packages leave the local pool so they are never also handed
+    /// out directly.
     pub async fn publish_key_packages_to_dht(&self) -> Result<()> {
-        use sha2::{Sha256, Digest};
-        
-        // Get KeyPackages from store
-        let mut kp_store = self.keypackage_store.write().await;
-        let provider = self.mls_provider.read().await;
-        let bundles = kp_store.generate_key_packages(5, &provider)?;
-        drop(provider);
-        drop(kp_store);
+        self.require_dht()?;
+        let bundles = self.take_key_packages(DHT_KEY_PACKAGES).await?;
         
         if bundles.is_empty() {
             return Ok(());
         }
         
-        // Compute DHT key: SHA256("keypackage:" + user_id_hex)
-        let user_id_hex = hex::encode(&self.user_id.0);
-        let mut hasher = Sha256::new();
-        hasher.update(b"keypackage:");
-        hasher.update(user_id_hex.as_bytes());
-        let dht_key_hash = hasher.finalize();
-        let mut dht_key = Vec::new();
-        dht_key.extend_from_slice(&dht_key_hash[..32]);
+        let dht_key = key_package_dht_key(&self.user_id);
         
         // Serialize all bundles
         let bundles_bytes = serde_json::to_vec(&bundles)
             .map_err(|e| Error::Serialization(format!("Failed to serialize KeyPackages: {}", e)))?;
         
         // Store in DHT
+        self.record_dht_write(&dht_key, None, "key_packages", bundles_bytes.len()).await;
         let mut network = self.network.write().await;
         network.dht_put(dht_key, bundles_bytes).await?;
         
-        println!("✓ Published {} KeyPackages to DHT for user {}", bundles.len(), self.user_id);
+        tracing::info!("Published {} KeyPackages to DHT for user {}", bundles.len(), self.user_id);
         
         Ok(())
     }
@@ -1626,16 +3454,8 @@ impl Client {
     /// 
     /// Returns one KeyPackageBundle that can be used to add the user to an MLS group.
     pub async fn fetch_key_package_from_dht(&self, user_id: &UserId) -> Result<crate::mls::KeyPackageBundle> {
-        use sha2::{Sha256, Digest};
-        
-        // Compute DHT key
-        let user_id_hex = hex::encode(&user_id.0);
-        let mut hasher = Sha256::new();
-        hasher.update(b"keypackage:");
-        hasher.update(user_id_hex.as_bytes());
-        let dht_key_hash = hasher.finalize();
-        let mut dht_key = Vec::new();
-        dht_key.extend_from_slice(&dht_key_hash[..32]);
+        self.require_dht()?;
+        let dht_key = key_package_dht_key(user_id);
         
         // Fetch from DHT
         let mut network = self.network.write().await;
@@ -1654,7 +3474,7 @@ impl Client {
         }
         
         // Return the first bundle (in production, we'd consume it)
-        println!("✓ Fetched KeyPackage for user {} from DHT", user_id);
+        tracing::info!("Fetched KeyPackage for user {} from DHT", user_id);
         Ok(bundles[0].clone())
     }
     
@@ -1676,6 +3496,65 @@ impl Client {
         manager.list_spaces().into_iter().cloned().collect()
     }
     
+    /// Delete everything stored locally for one Space
+    /// 
+    /// Removes the Space's operations, blobs, message indices and MLS group
+    /// state, and forgets its Space, Channels, Threads and Messages. Other
+    /// Spaces are untouched. Nothing is broadcast: peers and DHT replicas keep
+    /// their copies, so re-joining the Space would sync it again.
+    pub async fn purge_space(&self, space_id: SpaceId) -> Result<()> {
+        purge_space_data(
+            &self.store,
+            &self.storage,
+            &self.space_manager,
+            &self.channel_manager,
+            &self.thread_manager,
+            &self.mls_provider,
+            space_id,
+        ).await?;
+        self.pending_mls_messages.write().await.retain(|pending| pending.space_id != space_id);
+        Ok(())
+    }
+    
+    /// Archive a Space, making it read-only for every member
+    /// 
+    /// Only the owner can archive. Replicas that apply the op reject any
+    /// further ops for the Space, except its deletion.
+    pub async fn archive_space(&self, space_id: SpaceId) -> Result<CrdtOp> {
+        let op = {
+            let mut manager = self.space_manager.write().await;
+            manager.archive_space(space_id, self.user_id, &*self.signer)?
+        };
+        
+        self.store.put_op(&op)?;
+        self.broadcast_op(&op).await?;
+        
+        Ok(op)
+    }
+    
+    /// Delete a Space and purge its local data
+    /// 
+    /// Only the owner can delete. The op is broadcast before the purge, while
+    /// the Space's MLS group still exists to encrypt it; replicas that apply
+    /// it purge the Space too and refuse to re-create it.
+    pub async fn delete_space(&self, space_id: SpaceId) -> Result<CrdtOp> {
+        let op = {
+            let mut manager = self.space_manager.write().await;
+            manager.delete_space(space_id, self.user_id, &*self.signer)?
+        };
+        
+        self.broadcast_op(&op).await?;
+        self.purge_space(space_id).await?;
+        self.store.put_op(&op)?;
+        
+        Ok(op)
+    }
+    
+    /// Refuse local writes to an archived or deleted Space
+    async fn check_writable(&self, space_id: &SpaceId) -> Result<()> {
+        self.space_manager.read().await.check_writable(space_id)
+    }
+    
     /// Add a member to a Space
     pub async fn add_member(
         &self,
@@ -1689,7 +3568,7 @@ impl Client {
             user_id,
             role,
             self.user_id,
-            &self.keypair,
+            &*self.signer,
         )?;
         
         // Store operation
@@ -1712,7 +3591,7 @@ impl Client {
         role: Role,
         key_package_bundle: crate::mls::KeyPackageBundle,
     ) -> Result<CrdtOp> {
-        println!("🔑 Adding member {} with provided KeyPackage...", user_id);
+        tracing::debug!("Adding member {} with provided KeyPackage...", user_id);
         
         // Step 1: Deserialize the KeyPackage
         let provider = self.mls_provider.read().await;
@@ -1734,7 +3613,7 @@ impl Client {
         drop(provider);
         drop(manager);
         
-        println!("  ✓ Added to MLS group, epoch rotated");
+        tracing::debug!("Added to MLS group, epoch rotated");
         
         // Step 3: Serialize messages
         let commit_bytes = commit_msg.to_bytes()
@@ -1743,20 +3622,21 @@ impl Client {
             .map_err(|e| Error::Serialization(format!("Failed to serialize Welcome: {}", e)))?;
         
         // Step 4: Publish Commit to existing members via GossipSub
-        let space_topic = format!("space/{}", hex::encode(&space_id.0[..8]));
+        let space_topic = format!("space/{}", space_id.short());
         {
             let mut network = self.network.write().await;
             network.publish(&space_topic, commit_bytes).await?;
         }
-        println!("  ✓ Published Commit to existing members on {}", space_topic);
+        tracing::debug!("Published Commit to existing members on {}", space_topic);
         
         // Step 5: Send Welcome message to new member via their user topic
-        let user_topic = format!("user/{}/welcome", user_id);
+        let user_topic = format!("user/{}/welcome", user_id.short());
         {
             let mut network = self.network.write().await;
             network.publish(&user_topic, welcome_bytes).await?;
         }
-        println!("  ✓ Sent Welcome message to {} on {}", user_id, user_topic);
+        tracing::debug!("Sent Welcome message to {} on {}", user_id, user_topic);
+        self.publish_key_history(&space_id).await;
         
         // Step 6: Create and broadcast the CRDT AddMember operation
         let mut manager = self.space_manager.write().await;
@@ -1765,7 +3645,7 @@ impl Client {
             user_id,
             role,
             self.user_id,
-            &self.keypair,
+            &*self.signer,
         )?;
         drop(manager);
         
@@ -1775,7 +3655,7 @@ impl Client {
         // Broadcast operation
         self.broadcast_op(&op).await?;
         
-        println!("✅ Member {} added with MLS (P2P KeyPackage)", user_id);
+        tracing::info!(space_id = %space_id.short(), %user_id, "Added member with MLS (P2P KeyPackage)");
         
         Ok(op)
     }
@@ -1795,7 +3675,7 @@ impl Client {
         role: Role,
     ) -> Result<CrdtOp> {
         // Step 1: Fetch the user's KeyPackage from DHT
-        println!("🔑 Fetching KeyPackage for user {} from DHT...", user_id);
+        tracing::debug!("Fetching KeyPackage for user {} from DHT...", user_id);
         let key_package_bundle = self.fetch_key_package_from_dht(&user_id).await?;
         
         // Step 2: Deserialize the KeyPackage
@@ -1822,7 +3702,7 @@ impl Client {
             user_id,
             role,
             self.user_id,
-            &self.keypair,
+            &*self.signer,
         )?;
         drop(manager);
         
@@ -1834,7 +3714,7 @@ impl Client {
         
         // Step 7: Distribute MLS messages via GossipSub
         // Use the same topic that members subscribe to: "space/{space_id}"
-        let space_topic = format!("space/{}", hex::encode(&space_id.0[..8]));
+        let space_topic = format!("space/{}", space_id.short());
         
         // Convert MLS messages to bytes - OpenMLS MlsMessageOut has to_bytes() method
         let commit_bytes = commit_msg.to_bytes()
@@ -1843,26 +3723,26 @@ impl Client {
         
         // Attempt to send Commit (may fail if no peers subscribed to /mls topic - that's OK)
         match network.publish(&space_topic, commit_bytes).await {
-            Ok(_) => println!("✓ Sent Commit message to existing members on {}", space_topic),
-            Err(e) => println!("⚠️ Could not send Commit (no peers on {} topic): {}", space_topic, e),
+            Ok(_) => tracing::info!("Sent Commit message to existing members on {}", space_topic),
+            Err(e) => tracing::warn!("Could not send Commit (no peers on {} topic): {}", space_topic, e),
         }
         
         // Serialize and send Welcome to new member (via direct topic)
-        let welcome_topic = format!("user/{}/welcome", hex::encode(&user_id.0[..8]));
+        let welcome_topic = format!("user/{}/welcome", user_id.short());
         let welcome_bytes = welcome_msg.to_bytes()
             .map_err(|e| crate::Error::Serialization(format!("Failed to serialize Welcome: {:?}", e)))?;
         
         match network.publish(&welcome_topic, welcome_bytes).await {
-            Ok(_) => println!("✓ Sent Welcome message to {} on {}", hex::encode(&user_id.0[..8]), welcome_topic),
+            Ok(_) => tracing::info!("Sent Welcome message to {} on {}", user_id.short(), welcome_topic),
             Err(e) => {
-                eprintln!("✗ Failed to send Welcome message to {}: {}", welcome_topic, e);
-                eprintln!("  This means the new member won't be able to decrypt messages!");
+                tracing::warn!("Failed to send Welcome message to {}: {}", welcome_topic, e);
             }
         }
         
         drop(network);
+        self.publish_key_history(&space_id).await;
         
-        println!("✅ Successfully added member {} to Space with MLS", user_id);
+        tracing::info!(space_id = %space_id.short(), %user_id, "Added member to Space with MLS");
         
         Ok(op)
     }
@@ -1882,7 +3762,7 @@ impl Client {
             space_id,
             user_id,
             self.user_id,
-            &self.keypair,
+            &*self.signer,
             &provider,
         )?;
         drop(provider);
@@ -1896,26 +3776,36 @@ impl Client {
         
         // If we got a Commit message, broadcast it to remaining members
         if let Some(commit_msg) = commit_msg_opt {
-            println!("  📡 Broadcasting Commit to remaining members...");
-            let space_topic = format!("space/{}", hex::encode(&space_id.0[..8]));
+            tracing::debug!("Broadcasting Commit to remaining members...");
+            let space_topic = format!("space/{}", space_id.short());
             let commit_bytes = commit_msg.to_bytes()
                 .map_err(|e| Error::Serialization(format!("Failed to serialize Commit: {:?}", e)))?;
             
             let mut network = self.network.write().await;
             match network.publish(&space_topic, commit_bytes).await {
-                Ok(_) => println!("  ✓ Commit broadcast - remaining members will update to new epoch"),
-                Err(e) => eprintln!("  ⚠️ Could not broadcast Commit: {}", e),
+                Ok(_) => tracing::debug!("Commit broadcast - remaining members will update to new epoch"),
+                Err(e) => tracing::warn!("Could not broadcast Commit: {}", e),
             }
+            drop(network);
+            self.publish_key_history(&space_id).await;
         }
         
         Ok(op)
     }
     
+    /// Number of members in a Space (0 if the Space is unknown)
+    /// 
+    /// Reads the materialized membership map in place, without cloning the Space.
+    pub async fn member_count(&self, space_id: &SpaceId) -> usize {
+        let manager = self.space_manager.read().await;
+        manager.get_space(space_id).map_or(0, Space::member_count)
+    }
+    
     /// List all members of a Space
     pub async fn list_members(&self, space_id: &SpaceId) -> Vec<(UserId, Role)> {
         let manager = self.space_manager.read().await;
         if let Some(space) = manager.get_space(space_id) {
-            space.members.iter().map(|(uid, role)| (*uid, *role)).collect()
+            space.members().into_iter().collect()
         } else {
             vec![]
         }
@@ -1928,6 +3818,7 @@ impl Client {
         name: String,
         description: Option<String>,
     ) -> Result<(Channel, CrdtOp)> {
+        self.check_writable(&space_id).await?;
         let channel_id = ChannelId::from_content(&space_id, &name, &self.user_id);
         
         // Check permissions
@@ -1965,7 +3856,7 @@ impl Client {
             name,
             description,
             self.user_id,
-            &self.keypair,
+            &*self.signer,
             epoch,
             true, // Always create channel-level MLS group
             Some(&provider),
@@ -1973,29 +3864,347 @@ impl Client {
         
         drop(provider); // Release MLS provider lock
         
+        let channel = manager.get_channel(&channel_id)
+            .ok_or_else(|| Error::NotFound(format!("Channel {:?} not found", channel_id)))?
+            .clone();
+        
+        // Release before broadcasting - broadcast_op locks channel_manager
+        // to encrypt with the new channel's MLS group
+        drop(manager);
+        
         // Store operation
         self.store.put_op(&op)?;
         
         // Broadcast operation
         self.broadcast_op(&op).await?;
         
-        let channel = manager.get_channel(&channel_id)
+        // Let other Space members join the channel's MLS group
+        if self.dht_enabled {
+            if let Err(e) = self.dht_put_channel_group_info(&channel_id).await {
+                tracing::warn!("Failed to store channel GroupInfo in DHT: {}", e);
+            }
+        }
+        
+        Ok((channel, op))
+    }
+    
+    /// Get a Channel by ID
+    pub async fn get_channel(&self, channel_id: &ChannelId) -> Option<Channel> {
+        let manager = self.channel_manager.read().await;
+        manager.get_channel(channel_id).cloned()
+    }
+    
+    /// List the members of a Channel and their channel roles
+    /// 
+    /// Empty if the Channel is unknown.
+    pub async fn list_channel_members(&self, channel_id: &ChannelId) -> Vec<(UserId, Role)> {
+        let manager = self.channel_manager.read().await;
+        manager.get_channel(channel_id).map_or_else(Vec::new, Channel::list_members)
+    }
+    
+    /// List Channels in a Space
+    pub async fn list_channels(&self, space_id: &SpaceId) -> Vec<Channel> {
+        self.list_channels_with_deleted(space_id, false).await
+    }
+    
+    /// List Channels in a Space, with deleted ones flagged `deleted` if `include_deleted`
+    /// 
+    /// Deleted channels appear as they were when deleted, for moderation and
+    /// audit tooling.
+    pub async fn list_channels_with_deleted(&self, space_id: &SpaceId, include_deleted: bool) -> Vec<Channel> {
+        let manager = self.channel_manager.read().await;
+        manager.list_channels_with_deleted(space_id, include_deleted).into_iter().cloned().collect()
+    }
+    
+    /// Flag or unflag a Channel as NSFW / age-restricted
+    /// 
+    /// Requires MANAGE_CHANNELS in the Channel's Space.
+    pub async fn set_channel_nsfw(&self, channel_id: ChannelId, nsfw: bool) -> Result<CrdtOp> {
+        let space_id = self.get_channel(&channel_id).await
             .ok_or_else(|| Error::NotFound(format!("Channel {:?} not found", channel_id)))?
-            .clone();
+            .space_id;
+        
+        let epoch = {
+            let space_manager = self.space_manager.read().await;
+            let space = space_manager.get_space(&space_id)
+                .ok_or_else(|| Error::NotFound(format!("Space {:?} not found", space_id)))?;
+            if !space.can_manage_channels(&self.user_id) {
+                return Err(Error::Rejected(
+                    "Permission denied: You don't have MANAGE_CHANNELS permission".to_string()
+                ));
+            }
+            space.epoch
+        };
+        
+        let op = {
+            let mut manager = self.channel_manager.write().await;
+            manager.set_nsfw(channel_id, nsfw, self.user_id, &*self.signer, epoch)?
+        };
+        
+        self.store.put_op(&op)?;
+        self.broadcast_op(&op).await?;
+        
+        Ok(op)
+    }
+    
+    /// Delete a Channel, its threads and messages, and its MLS group
+    /// 
+    /// Requires DELETE_CHANNELS in the Channel's Space. The Channel ID is
+    /// tombstoned, so late ops can't bring it back.
+    pub async fn delete_channel(&self, channel_id: ChannelId) -> Result<CrdtOp> {
+        let space_id = self.get_channel(&channel_id).await
+            .ok_or_else(|| Error::NotFound(format!("Channel {:?} not found", channel_id)))?
+            .space_id;
+        
+        let epoch = {
+            let space_manager = self.space_manager.read().await;
+            let space = space_manager.get_space(&space_id)
+                .ok_or_else(|| Error::NotFound(format!("Space {:?} not found", space_id)))?;
+            if !space.can_delete_channels(&self.user_id) {
+                return Err(Error::Rejected(
+                    "Permission denied: You don't have DELETE_CHANNELS permission".to_string()
+                ));
+            }
+            space.epoch
+        };
+        
+        let op = {
+            let provider = self.mls_provider.read().await;
+            let mut manager = self.channel_manager.write().await;
+            manager.delete_channel(channel_id, self.user_id, &*self.signer, epoch, &provider)?
+        };
+        self.thread_manager.write().await.remove_channel(&channel_id);
+        
+        self.store.put_op(&op)?;
+        self.broadcast_op(&op).await?;
+        
+        Ok(op)
+    }
+    
+    /// Follow another channel's announcements into `target_channel_id`
+    /// 
+    /// Starts a thread in the target channel that this client forwards the
+    /// source's messages into as they are posted. The follow itself is a replicated op in the target Space, so every member
+    /// sees it. Requires membership in the source Space and MANAGE_CHANNELS
+    /// in the target's.
+    pub async fn follow_channel(
+        &self,
+        source_channel_id: ChannelId,
+        target_channel_id: ChannelId,
+    ) -> Result<(crate::forum::ChannelFollow, CrdtOp)> {
+        let (source, target) = {
+            let manager = self.channel_manager.read().await;
+            let get = |channel_id: &ChannelId| manager.get_channel(channel_id)
+                .cloned()
+                .ok_or_else(|| Error::NotFound(format!("Channel {:?} not found", channel_id)));
+            (get(&source_channel_id)?, get(&target_channel_id)?)
+        };
+        
+        let epoch = {
+            let space_manager = self.space_manager.read().await;
+            let source_space = space_manager.get_space(&source.space_id)
+                .ok_or_else(|| Error::NotFound(format!("Space {:?} not found", source.space_id)))?;
+            if !source_space.is_member(&self.user_id) {
+                return Err(Error::Permission("Following requires membership in the source Space".to_string()));
+            }
+            let target_space = space_manager.get_space(&target.space_id)
+                .ok_or_else(|| Error::NotFound(format!("Space {:?} not found", target.space_id)))?;
+            if !target_space.can_manage_channels(&self.user_id) {
+                return Err(Error::Rejected(
+                    "Permission denied: You don't have MANAGE_CHANNELS permission".to_string()
+                ));
+            }
+            target_space.epoch
+        };
+        
+        if self.channel_manager.read().await.follows_into(&target_channel_id).iter()
+            .any(|follow| follow.source_channel == source_channel_id)
+        {
+            return Err(Error::AlreadyExists("Channel is already followed".to_string()));
+        }
+        
+        let (thread, _) = self.create_thread(
+            target.space_id,
+            target_channel_id,
+            Some(format!("Announcements from #{}", source.name)),
+            format!("Following #{}", source.name),
+        ).await?;
+        
+        let op = {
+            let mut manager = self.channel_manager.write().await;
+            manager.follow_channel(source_channel_id, target_channel_id, thread.id, self.user_id, &*self.signer, epoch)?
+        };
+        
+        self.store.put_op(&op)?;
+        self.broadcast_op(&op).await?;
+        
+        let follow = crate::forum::ChannelFollow {
+            source_channel: source_channel_id,
+            target_channel: target_channel_id,
+            target_thread: thread.id,
+            follower: self.user_id,
+        };
+        self.ensure_announcement_relay().await;
+        Ok((follow, op))
+    }
+    
+    /// Stop `target_channel_id` following `source_channel_id`
+    /// 
+    /// Requires MANAGE_CHANNELS in the target's Space.
+    pub async fn unfollow_channel(
+        &self,
+        source_channel_id: ChannelId,
+        target_channel_id: ChannelId,
+    ) -> Result<CrdtOp> {
+        let space_id = self.get_channel(&target_channel_id).await
+            .ok_or_else(|| Error::NotFound(format!("Channel {:?} not found", target_channel_id)))?
+            .space_id;
+        
+        let epoch = {
+            let space_manager = self.space_manager.read().await;
+            let space = space_manager.get_space(&space_id)
+                .ok_or_else(|| Error::NotFound(format!("Space {:?} not found", space_id)))?;
+            if !space.can_manage_channels(&self.user_id) {
+                return Err(Error::Rejected(
+                    "Permission denied: You don't have MANAGE_CHANNELS permission".to_string()
+                ));
+            }
+            space.epoch
+        };
+        
+        let op = {
+            let mut manager = self.channel_manager.write().await;
+            manager.unfollow_channel(source_channel_id, target_channel_id, self.user_id, &*self.signer, epoch)?
+        };
+        
+        self.store.put_op(&op)?;
+        self.broadcast_op(&op).await?;
+        
+        Ok(op)
+    }
+    
+    /// Channels announcing into `channel_id`
+    pub async fn list_channel_follows(&self, channel_id: &ChannelId) -> Vec<crate::forum::ChannelFollow> {
+        let manager = self.channel_manager.read().await;
+        manager.follows_into(channel_id).into_iter().copied().collect()
+    }
+    
+    /// Spawn the announcement relay unless it is running or there is nothing to relay
+    /// 
+    /// The relay forwards each message posted in a channel this user follows
+    /// into the follow's thread. Only follows this user set up are relayed,
+    /// so each announcement is re-posted once rather than by every member of
+    /// the target. Messages that are themselves forwards are never
+    /// re-announced, which breaks follow loops. The task ends once the
+    /// user's last follow is gone; the next follow starts it again.
+    async fn ensure_announcement_relay(&self) {
+        let mut task = self.announcement_relay.write().await;
+        if task.as_ref().is_some_and(|task| !task.is_finished())
+            || self.channel_manager.read().await.follows_by(&self.user_id).is_empty()
+        {
+            return;
+        }
+        
+        let mut posted = self.events.subscribe();
+        let signer = Arc::clone(&self.signer);
+        let user_id = self.user_id;
+        let show_nsfw = self.show_nsfw;
+        let space_manager = Arc::clone(&self.space_manager);
+        let channel_manager = Arc::clone(&self.channel_manager);
+        let thread_manager = Arc::clone(&self.thread_manager);
+        let store = Arc::clone(&self.store);
+        let storage = Arc::clone(&self.storage);
+        let events = self.events.clone();
+        let outbox_retry = Arc::clone(&self.outbox_retry);
+        let dht_retry = self.dht_enabled.then(|| Arc::clone(&self.dht_retry));
         
-        Ok((channel, op))
+        *task = Some(tokio::spawn(async move {
+            use tokio::sync::broadcast::error::RecvError;
+            
+            loop {
+                let message = match posted.recv().await {
+                    Ok(ClientEvent::MessagePosted { message, .. }) => message,
+                    Ok(_) => continue,
+                    Err(RecvError::Lagged(skipped)) => {
+                        tracing::warn!(skipped, "Announcement relay fell behind the event stream");
+                        continue;
+                    }
+                    Err(RecvError::Closed) => break,
+                };
+                if channel_manager.read().await.follows_by(&user_id).is_empty() {
+                    break;
+                }
+                if message.forwarded_from.is_some() || message.deleted {
+                    continue;
+                }
+                let Some(channel_id) = thread_manager.read().await.get_thread(&message.thread_id).map(|thread| thread.channel_id) else {
+                    continue;
+                };
+                let targets: Vec<ThreadId> = channel_manager.read().await
+                    .followers_of(&channel_id).into_iter()
+                    .filter(|follow| follow.follower == user_id)
+                    .map(|follow| follow.target_thread)
+                    .collect();
+                
+                for target_thread in targets {
+                    let forwarded = forward_to_thread(
+                        &space_manager, &channel_manager, &thread_manager,
+                        &*signer, user_id, show_nsfw, message.id, target_thread,
+                    ).await;
+                    let (space_id, copy, op) = match forwarded {
+                        Ok(forwarded) => forwarded,
+                        Err(e) => {
+                            tracing::warn!(message_id = ?message.id, error = %e, "Failed to relay announcement");
+                            continue;
+                        }
+                    };
+                    
+                    // Sent through the outbox, like ops posted while offline
+                    if let Err(e) = store.put_op(&op) {
+                        tracing::warn!(op_id = %op.op_id.0, error = %e, "Failed to store relayed announcement");
+                        continue;
+                    }
+                    if let Err(e) = storage.queue_unsent_op(&op) {
+                        tracing::warn!(op_id = %op.op_id.0, error = %e, "Failed to queue relayed announcement");
+                    }
+                    outbox_retry.notify_one();
+                    if let Some(dht_retry) = &dht_retry {
+                        if let Err(e) = storage.queue_pending_upload(&op) {
+                            tracing::warn!(op_id = %op.op_id.0, error = %e, "Failed to queue relayed announcement for DHT upload");
+                        }
+                        dht_retry.notify_one();
+                    }
+                    let _ = events.send(ClientEvent::MessagePosted { space_id, message: copy });
+                }
+            }
+            tracing::debug!("Announcement relay stopped, no follows left");
+        }));
     }
     
-    /// Get a Channel by ID
-    pub async fn get_channel(&self, channel_id: &ChannelId) -> Option<Channel> {
-        let manager = self.channel_manager.read().await;
-        manager.get_channel(channel_id).cloned()
+    /// Refuse content over `ClientConfig::max_message_bytes`
+    fn check_message_size(&self, content: &str) -> Result<()> {
+        if content.len() > self.max_message_bytes {
+            return Err(Error::InvalidOperation(format!(
+                "Message is {} bytes, over the {} byte limit; send large content as an attachment",
+                content.len(),
+                self.max_message_bytes
+            )));
+        }
+        Ok(())
     }
     
-    /// List Channels in a Space
-    pub async fn list_channels(&self, space_id: &SpaceId) -> Vec<Channel> {
-        let manager = self.channel_manager.read().await;
-        manager.list_channels(space_id).into_iter().cloned().collect()
+    /// Whether a thread's channel is NSFW and this client has not opted in
+    async fn nsfw_hidden(&self, thread_id: &ThreadId) -> bool {
+        if self.show_nsfw {
+            return false;
+        }
+        let channel_id = match self.thread_manager.read().await.get_thread(thread_id) {
+            Some(thread) => thread.channel_id,
+            None => return false,
+        };
+        self.channel_manager.read().await
+            .get_channel(&channel_id)
+            .is_some_and(|channel| channel.nsfw)
     }
     
     /// Add a user to a Channel (with channel-level MLS encryption)
@@ -2032,7 +4241,13 @@ impl Client {
             let mut network = self.network.write().await;
             network.publish(&user_topic, welcome_bytes).await?;
         }
-        println!("  ✅ Sent channel Welcome message to {} on {}", hex::encode(&user_id.0[..8]), user_topic);
+        tracing::debug!("Sent channel Welcome message to {} on {}", user_id.short(), user_topic);
+        
+        if self.dht_enabled {
+            if let Err(e) = self.dht_put_channel_group_info(channel_id).await {
+                tracing::warn!("Failed to store channel GroupInfo in DHT: {}", e);
+            }
+        }
         
         Ok(())
     }
@@ -2053,12 +4268,121 @@ impl Client {
             &self.user_id,
             &provider,
         ).map_err(|e| Error::Mls(format!("Failed to remove member from channel: {}", e)))?;
+        drop(provider);
+        drop(manager);
         
         // TODO: Broadcast Commit message to channel members via DHT
         
+        if self.dht_enabled {
+            if let Err(e) = self.dht_put_channel_group_info(channel_id).await {
+                tracing::warn!("Failed to store channel GroupInfo in DHT: {}", e);
+            }
+        }
+        
+        Ok(())
+    }
+    
+    /// Join a Channel's MLS group, which is required before posting in it
+    /// 
+    /// Does nothing if this user is already in the group. Otherwise the
+    /// channel's GroupInfo is fetched from the DHT (members keep it current)
+    /// and we join by external commit, so no member needs to be online to
+    /// Welcome us. Only members of the Channel's Space may join.
+    pub async fn join_channel(&self, channel_id: &ChannelId) -> Result<()> {
+        if self.in_channel_group(channel_id).await {
+            return Ok(());
+        }
+        self.require_dht()?;
+        
+        let values = self.network.write().await
+            .dht_get(channel_group_info_dht_key(channel_id)).await?;
+        if values.is_empty() {
+            return Err(Error::NotFound(format!(
+                "No GroupInfo published for channel {}; ask a member to add you with add_to_channel",
+                channel_id.short()
+            )));
+        }
+        
+        self.join_channel_with_group_info(channel_id, &values[0]).await
+    }
+    
+    /// Join a Channel's MLS group by external commit, from a GroupInfo a
+    /// member handed over directly (see [`Client::channel_group_info`])
+    /// 
+    /// Useful for 2-peer scenarios where DHT quorum cannot be achieved.
+    pub async fn join_channel_with_group_info(&self, channel_id: &ChannelId, group_info: &[u8]) -> Result<()> {
+        if self.in_channel_group(channel_id).await {
+            return Ok(());
+        }
+        
+        let space_id = self.get_channel(channel_id).await
+            .ok_or_else(|| Error::NotFound(format!("Channel {:?} not found", channel_id)))?
+            .space_id;
+        self.check_writable(&space_id).await?;
+        {
+            let space_manager = self.space_manager.read().await;
+            let space = space_manager.get_space(&space_id)
+                .ok_or_else(|| Error::NotFound(format!("Space {:?} not found", space_id)))?;
+            if !space.is_member(&self.user_id) {
+                return Err(Error::Permission("Only Space members can join its channels".to_string()));
+            }
+        }
+        
+        let commit_bytes = {
+            let provider = self.mls_provider.read().await;
+            let (group, commit_bytes) = crate::mls::MlsGroup::join_by_external_commit(
+                group_info,
+                SpaceId(channel_id.0),
+                self.user_id,
+                crate::mls::identity_signer(&*self.signer)?,
+                &provider,
+            )?;
+            self.channel_manager.write().await.store_joined_mls_group(*channel_id, self.user_id, group)?;
+            commit_bytes
+        };
+        tracing::info!("Joined MLS group of channel {}", channel_id.short());
+        
+        // Existing members process the Commit like any other on the Space topic
+        let space_topic = format!("space/{}", space_id.short());
+        match self.network.write().await.publish(&space_topic, commit_bytes).await {
+            Ok(_) => tracing::info!("Sent external Commit to channel members on {}", space_topic),
+            Err(e) => tracing::warn!("Could not send external Commit (no peers on {} topic): {}", space_topic, e),
+        }
+        
+        if self.dht_enabled {
+            if let Err(e) = self.dht_put_channel_group_info(channel_id).await {
+                tracing::warn!("Failed to store channel GroupInfo in DHT: {}", e);
+            }
+        }
+        
         Ok(())
     }
     
+    /// Export the GroupInfo of a Channel's MLS group, for handing to a Space
+    /// member who wants to join directly (see [`Client::join_channel_with_group_info`])
+    pub async fn channel_group_info(&self, channel_id: &ChannelId) -> Result<Vec<u8>> {
+        let provider = self.mls_provider.read().await;
+        let manager = self.channel_manager.read().await;
+        manager.get_mls_group(channel_id)
+            .filter(|group| group.is_active())
+            .ok_or_else(|| Error::NotFound(format!("Not in the MLS group of channel {}", channel_id.short())))?
+            .export_group_info(&provider)
+    }
+    
+    /// Publish a Channel's current GroupInfo to the DHT so Space members can
+    /// [`Client::join_channel`] while no member is online
+    pub async fn dht_put_channel_group_info(&self, channel_id: &ChannelId) -> Result<()> {
+        self.require_dht()?;
+        dht_put_channel_group_info(&self.mls_provider, &self.channel_manager, &self.network, &self.dht_writes, channel_id).await
+    }
+    
+    /// Whether this client is in a Channel's MLS group
+    async fn in_channel_group(&self, channel_id: &ChannelId) -> bool {
+        self.channel_manager.read().await
+            .get_mls_group(channel_id)
+            .is_some_and(|group| group.is_active())
+    }
+    
     /// Create a Thread in a Channel
     pub async fn create_thread(
         &self,
@@ -2067,6 +4391,9 @@ impl Client {
         title: Option<String>,
         first_message: String,
     ) -> Result<(Thread, CrdtOp)> {
+        self.check_writable(&space_id).await?;
+        self.check_message_size(&first_message)?;
+        
         // Hash the first message content
         use sha2::{Sha256, Digest};
         let mut hasher = Sha256::new();
@@ -2101,7 +4428,7 @@ impl Client {
             title,
             first_message,
             self.user_id,
-            &self.keypair,
+            &*self.signer,
             epoch,
         )?;
         
@@ -2114,6 +4441,9 @@ impl Client {
         let thread = manager.get_thread(&thread_id)
             .ok_or_else(|| Error::NotFound(format!("Thread {:?} not found", thread_id)))?
             .clone();
+        if let Some(event) = message_event(&manager, &op) {
+            let _ = self.events.send(event);
+        }
         
         Ok((thread, op))
     }
@@ -2126,8 +4456,13 @@ impl Client {
     
     /// List Threads in a Channel
     pub async fn list_threads(&self, channel_id: &ChannelId) -> Vec<Thread> {
+        self.list_threads_with_deleted(channel_id, false).await
+    }
+    
+    /// List Threads in a Channel, with ones deleted along with the Channel if `include_deleted`
+    pub async fn list_threads_with_deleted(&self, channel_id: &ChannelId, include_deleted: bool) -> Vec<Thread> {
         let manager = self.thread_manager.read().await;
-        manager.list_threads(channel_id).into_iter().cloned().collect()
+        manager.list_threads_with_deleted(channel_id, include_deleted).into_iter().cloned().collect()
     }
     
     /// Post a Message to a Thread
@@ -2137,61 +4472,58 @@ impl Client {
         thread_id: ThreadId,
         content: String,
     ) -> Result<(Message, CrdtOp)> {
-        // Auto-join channel MLS group if needed (Phase 2: Per-channel encryption)
-        {
-            let thread_manager = self.thread_manager.read().await;
-            if let Some(thread) = thread_manager.get_thread(&thread_id) {
-                let channel_id = thread.channel_id;
-                drop(thread_manager);
-                
-                // Check if user is in this channel's MLS group
-                let channel_manager = self.channel_manager.read().await;
-                if let Some(channel) = channel_manager.get_channel(&channel_id) {
-                    let is_member = channel.is_member(&self.user_id);
-                    let has_mls_group = channel_manager.get_mls_group(&channel_id).is_some();
-                    
-                    println!("  🔍 Channel auto-join check: is_member={}, has_mls_group={}", is_member, has_mls_group);
-                    
-                    drop(channel_manager);
-                    
-                    // Auto-add to channel MLS group if:
-                    // 1. User is not yet a member of the channel
-                    // 2. Channel has an MLS group (it should, they're always created)
-                    if !is_member && has_mls_group {
-                        println!("  🔐 Auto-joining channel MLS group...");
-                        // Get user's key package from DHT
-                        match self.fetch_key_package_from_dht(&self.user_id).await {
-                            Ok(key_package_bundle) => {
-                                let key_package_bytes = &key_package_bundle.key_package_bytes;
-                                let provider = self.mls_provider.read().await;
-                                let mut channel_mgr = self.channel_manager.write().await;
-                                
-                                // Add user to channel's MLS group
-                                match channel_mgr.add_member_with_mls(
-                                    &channel_id,
-                                    self.user_id,
-                                    Role::Member,
-                                    key_package_bytes,
-                                    &self.user_id,  // User is adding themselves (self-join)
-                                    &provider,
-                                ) {
-                                    Ok(_welcome_bytes) => {
-                                        println!("  ✓ Auto-joined channel MLS group");
-                                        // TODO: Store Welcome message for offline sync
-                                    }
-                                    Err(e) => {
-                                        eprintln!("  ⚠️ Failed to auto-join channel MLS: {}", e);
-                                        // Continue anyway - user can still post to channel
-                                    }
-                                }
-                            }
-                            Err(e) => {
-                                eprintln!("  ⚠️ No key package found for auto-join: {}", e);
-                                // Continue anyway
-                            }
-                        }
-                    }
-                }
+        self.post_message_with_quote(space_id, thread_id, content, None).await
+    }
+    
+    /// Reply in a Thread, quoting another Message inline
+    /// 
+    /// The start of the quoted message is copied into the reply (see
+    /// [`crate::forum::QuotedMessage`]), so the quote still reads the same
+    /// after the original is edited or deleted.
+    pub async fn quote_reply(
+        &self,
+        thread_id: ThreadId,
+        quoted_id: MessageId,
+        content: String,
+    ) -> Result<(Message, CrdtOp)> {
+        let (space_id, quote) = {
+            let manager = self.thread_manager.read().await;
+            let space_id = manager.get_thread(&thread_id)
+                .ok_or_else(|| Error::NotFound(format!("Thread {:?} not found", thread_id)))?
+                .space_id;
+            let quoted = manager.get_message(&quoted_id)
+                .ok_or_else(|| Error::NotFound(format!("Message {:?} not found", quoted_id)))?;
+            if quoted.deleted {
+                return Err(Error::InvalidOperation("Cannot quote a deleted message".to_string()));
+            }
+            (space_id, crate::forum::QuotedMessage::of(quoted))
+        };
+        
+        self.post_message_with_quote(space_id, thread_id, content, Some(quote)).await
+    }
+    
+    async fn post_message_with_quote(
+        &self,
+        space_id: SpaceId,
+        thread_id: ThreadId,
+        content: String,
+        quote: Option<crate::forum::QuotedMessage>,
+    ) -> Result<(Message, CrdtOp)> {
+        self.check_writable(&space_id).await?;
+        self.check_message_size(&content)?;
+        if self.nsfw_hidden(&thread_id).await {
+            return Err(Error::Permission(
+                "Channel is marked NSFW; enable show_nsfw to post here".to_string()
+            ));
+        }
+        
+        // Posts are sealed with the channel's MLS group, so the caller must be in it
+        if let Some(thread) = self.get_thread(&thread_id).await {
+            if !self.in_channel_group(&thread.channel_id).await {
+                return Err(Error::Permission(format!(
+                    "Not in the MLS group of channel {}; call join_channel first",
+                    thread.channel_id.short()
+                )));
             }
         }
         
@@ -2222,15 +4554,24 @@ impl Client {
             space.epoch
         };
         
-        let mut manager = self.thread_manager.write().await;
-        let op = manager.post_message(
-            message_id,
-            thread_id,
-            content,
-            self.user_id,
-            &self.keypair,
-            epoch,
-        )?;
+        // Release the thread manager before broadcasting, so the receive path
+        // is not blocked on the network
+        let (op, message) = {
+            let mut manager = self.thread_manager.write().await;
+            let op = manager.post_message(
+                message_id,
+                thread_id,
+                content,
+                quote,
+                self.user_id,
+                &*self.signer,
+                epoch,
+            )?;
+            let message = manager.get_message(&message_id)
+                .ok_or_else(|| Error::NotFound(format!("Message {:?} not found", message_id)))?
+                .clone();
+            (op, message)
+        };
         
         // Store operation
         self.store.put_op(&op)?;
@@ -2238,21 +4579,245 @@ impl Client {
         // Broadcast operation
         self.broadcast_op(&op).await?;
         
-        let message = manager.get_message(&message_id)
-            .ok_or_else(|| Error::NotFound(format!("Message {:?} not found", message_id)))?
-            .clone();
+        let _ = self.events.send(ClientEvent::MessagePosted { space_id, message: message.clone() });
+        self.spawn_link_preview(space_id, &message).await;
+        
+        Ok((message, op))
+    }
+    
+    /// Forward a Message into another Thread, possibly in another Space
+    /// 
+    /// The copy keeps the original's author and thread in
+    /// [`Message::forward_source`] and its ID in [`Message::forwarded_from`].
+    /// The user must be a member of both the source and the target Space.
+    pub async fn forward_message(
+        &self,
+        message_id: MessageId,
+        target_thread_id: ThreadId,
+    ) -> Result<(Message, CrdtOp)> {
+        let (target_space, message, op) = forward_to_thread(
+            &self.space_manager,
+            &self.channel_manager,
+            &self.thread_manager,
+            &*self.signer,
+            self.user_id,
+            self.show_nsfw,
+            message_id,
+            target_thread_id,
+        ).await?;
+        
+        self.store.put_op(&op)?;
+        self.broadcast_op(&op).await?;
+        
+        let _ = self.events.send(ClientEvent::MessagePosted { space_id: target_space, message: message.clone() });
         
         Ok((message, op))
     }
     
+    /// Subscribe to events for messages posted locally or received from peers
+    /// 
+    /// Only events sent after subscribing are delivered; a subscriber that falls
+    /// more than a few hundred events behind skips the oldest.
+    pub fn subscribe_events(&self) -> tokio::sync::broadcast::Receiver<ClientEvent> {
+        self.events.subscribe()
+    }
+    
+    /// Follow one Thread live
+    /// 
+    /// Each new message is sent once posted, then again whenever it is
+    /// edited, redacted, or gains a link preview or reaction, so a UI can
+    /// replace it by ID. Backed by `subscribe_events()`, so the same lag
+    /// rules apply; the stream stops when the receiver is dropped.
+    pub fn subscribe_thread(&self, thread_id: ThreadId) -> mpsc::Receiver<Message> {
+        use tokio::sync::broadcast::error::RecvError;
+        
+        let mut events = self.events.subscribe();
+        let (tx, rx) = mpsc::channel(EVENT_CHANNEL_CAPACITY);
+        tokio::spawn(async move {
+            loop {
+                let event = tokio::select! {
+                    _ = tx.closed() => break,
+                    event = events.recv() => event,
+                };
+                let message = match event {
+                    Ok(ClientEvent::MessagePosted { message, .. } | ClientEvent::MessageUpdated { message, .. }) => message,
+                    Ok(_) | Err(RecvError::Lagged(_)) => continue,
+                    Err(RecvError::Closed) => break,
+                };
+                if message.thread_id == thread_id && tx.send(message).await.is_err() {
+                    break;
+                }
+            }
+        });
+        rx
+    }
+    
+    /// Tell subscribers about the message a local op changed
+    async fn send_message_event(&self, op: &CrdtOp) {
+        if let Some(event) = message_event(&*self.thread_manager.read().await, op) {
+            let _ = self.events.send(event);
+        }
+    }
+    
+    /// Subscribe to every op received from peers once it has been verified,
+    /// decrypted and applied
+    /// 
+    /// Lower level than `subscribe_events()`, for indexers and analytics that
+    /// want the raw op stream. Ops created locally are not echoed, and the
+    /// same lag rules apply.
+    pub fn subscribe_ops(&self) -> tokio::sync::broadcast::Receiver<(CrdtOp, SpaceId)> {
+        self.ops.subscribe()
+    }
+    
+    /// Install a provider that fetches link previews for posted messages
+    /// 
+    /// Previews are fetched in the background and attached later by
+    /// [`Client::attach_pending_link_previews`], so posting never waits on them.
+    pub async fn set_link_preview_provider(&self, provider: Arc<dyn crate::forum::LinkPreviewProvider>) {
+        *self.link_preview_provider.write().await = Some(provider);
+    }
+    
+    /// Fetch a preview for the message's first link on a background task
+    async fn spawn_link_preview(&self, space_id: SpaceId, message: &Message) {
+        let Some(provider) = self.link_preview_provider.read().await.clone() else {
+            return;
+        };
+        let Some(url) = crate::forum::link_preview::first_url(&message.content) else {
+            return;
+        };
+        
+        let url = url.to_string();
+        let message_id = message.id;
+        let tx = self.link_preview_tx.clone();
+        tokio::spawn(async move {
+            if let Some(preview) = provider.preview(&url).await {
+                let _ = tx.send(PendingLinkPreview { space_id, message_id, preview });
+            }
+        });
+    }
+    
+    /// Attach every link preview fetched so far, returning the new ops
+    pub async fn attach_pending_link_previews(&self) -> Result<Vec<CrdtOp>> {
+        let pending: Vec<PendingLinkPreview> = {
+            let mut rx = self.link_preview_rx.write().await;
+            std::iter::from_fn(|| rx.try_recv().ok()).collect()
+        };
+        
+        let mut ops = Vec::with_capacity(pending.len());
+        for PendingLinkPreview { space_id, message_id, preview } in pending {
+            ops.push(self.attach_link_preview(space_id, message_id, preview).await?);
+        }
+        Ok(ops)
+    }
+    
+    /// Attach a link preview to one of this user's messages
+    pub async fn attach_link_preview(
+        &self,
+        space_id: SpaceId,
+        message_id: MessageId,
+        preview: crate::forum::LinkPreview,
+    ) -> Result<CrdtOp> {
+        self.check_writable(&space_id).await?;
+        
+        let epoch = {
+            let space_manager = self.space_manager.read().await;
+            let space = space_manager.get_space(&space_id)
+                .ok_or_else(|| Error::NotFound(format!("Space {:?} not found", space_id)))?;
+            space.epoch
+        };
+        
+        let op = {
+            let mut manager = self.thread_manager.write().await;
+            manager.attach_link_preview(message_id, preview, self.user_id, &*self.signer, epoch)?
+        };
+        
+        self.store.put_op(&op)?;
+        self.broadcast_op(&op).await?;
+        self.send_message_event(&op).await;
+        
+        Ok(op)
+    }
+    
+    /// React to a Message
+    /// 
+    /// Fails if the Space restricts reactions and `emoji` isn't allowed.
+    pub async fn add_reaction(
+        &self,
+        space_id: SpaceId,
+        message_id: MessageId,
+        emoji: String,
+    ) -> Result<CrdtOp> {
+        self.check_writable(&space_id).await?;
+        
+        let epoch = {
+            let space_manager = self.space_manager.read().await;
+            let space = space_manager.get_space(&space_id)
+                .ok_or_else(|| Error::NotFound(format!("Space {:?} not found", space_id)))?;
+            space.epoch
+        };
+        
+        let op = {
+            let mut manager = self.thread_manager.write().await;
+            manager.add_reaction(message_id, emoji, self.user_id, &*self.signer, epoch)?
+        };
+        
+        self.store.put_op(&op)?;
+        self.broadcast_op(&op).await?;
+        self.send_message_event(&op).await;
+        
+        Ok(op)
+    }
+    
     /// Edit a Message
     pub async fn edit_message(
         &self,
         space_id: SpaceId,
         message_id: MessageId,
-        new_content: String,
+        new_content: String,
+    ) -> Result<CrdtOp> {
+        self.check_writable(&space_id).await?;
+        self.check_message_size(&new_content)?;
+        
+        // Get current epoch from Space
+        let epoch = {
+            let space_manager = self.space_manager.read().await;
+            let space = space_manager.get_space(&space_id)
+                .ok_or_else(|| Error::NotFound(format!("Space {:?} not found", space_id)))?;
+            space.epoch
+        };
+        
+        let op = self.thread_manager.write().await.edit_message(
+            message_id,
+            new_content,
+            self.user_id,
+            &*self.signer,
+            epoch,
+        )?;
+        
+        // Store operation
+        self.store.put_op(&op)?;
+        
+        // Broadcast operation
+        self.broadcast_op(&op).await?;
+        self.send_message_event(&op).await;
+        
+        Ok(op)
+    }
+    
+    /// Redact one of this user's Messages (GDPR-style erasure)
+    /// 
+    /// Unlike a delete, which only hides the message, this purges its content
+    /// from memory, from the local op log and from blob storage, and publishes
+    /// a `RedactMessage` op telling every replica to do the same. The local
+    /// purge is complete; copies already cached in the DHT or on peers that
+    /// never receive the op are purged on a best-effort basis only.
+    pub async fn redact_message(
+        &self,
+        space_id: SpaceId,
+        message_id: MessageId,
     ) -> Result<CrdtOp> {
-        // Get current epoch from Space
+        self.check_writable(&space_id).await?;
+        
         let epoch = {
             let space_manager = self.space_manager.read().await;
             let space = space_manager.get_space(&space_id)
@@ -2261,19 +4826,16 @@ impl Client {
         };
         
         let mut manager = self.thread_manager.write().await;
-        let op = manager.edit_message(
-            message_id,
-            new_content,
-            self.user_id,
-            &self.keypair,
-            epoch,
-        )?;
+        let target = redaction_target(&manager, &message_id)
+            .ok_or_else(|| Error::NotFound(format!("Message {:?} not found", message_id)))?;
+        let op = manager.redact_message(message_id, self.user_id, &*self.signer, epoch)?;
         
-        // Store operation
-        self.store.put_op(&op)?;
+        purge_redacted(&self.store, &self.storage, &manager, target)?;
+        drop(manager);
         
-        // Broadcast operation
+        self.store.put_op(&op)?;
         self.broadcast_op(&op).await?;
+        self.send_message_event(&op).await;
         
         Ok(op)
     }
@@ -2285,9 +4847,206 @@ impl Client {
     }
     
     /// List Messages in a Thread
+    /// 
+    /// Deleted messages are left out. Empty for threads in NSFW channels
+    /// unless `show_nsfw` is enabled.
     pub async fn list_messages(&self, thread_id: &ThreadId) -> Vec<Message> {
+        self.list_messages_with_deleted(thread_id, false).await
+    }
+    
+    /// List Messages in a Thread, with deleted ones (flagged `deleted`) if `include_deleted`
+    /// 
+    /// A message counts as deleted once it is redacted or its ID is in the
+    /// Thread's stored `TombstoneSet`.
+    pub async fn list_messages_with_deleted(&self, thread_id: &ThreadId, include_deleted: bool) -> Vec<Message> {
+        if self.nsfw_hidden(thread_id).await {
+            return Vec::new();
+        }
+        let tombstones = self.storage.get_tombstones(thread_id).unwrap_or_default();
         let manager = self.thread_manager.read().await;
-        manager.list_messages(thread_id).into_iter().cloned().collect()
+        manager.list_messages(thread_id)
+            .into_iter()
+            .cloned()
+            .map(|mut message| {
+                message.deleted |= tombstones.contains(&message.id);
+                message
+            })
+            .filter(|message| include_deleted || !message.deleted)
+            .collect()
+    }
+    
+    /// Hash of a Space's materialized state, for checking convergence
+    /// 
+    /// Covers members and roles, channels, threads, and each message's ID,
+    /// content and deletion flag, visited in sorted ID order so replicas that
+    /// applied the same ops in any order get the same hash. Unknown Spaces
+    /// hash as empty.
+    pub async fn space_state_hash(&self, space_id: &SpaceId) -> [u8; 32] {
+        use sha2::{Sha256, Digest};
+        let mut hasher = Sha256::new();
+        hasher.update(b"SPACE_STATE_V1:");
+        hasher.update(space_id.0);
+        
+        let mut members: Vec<(UserId, Role)> = self.list_members(space_id).await;
+        members.sort_by_key(|(user_id, _)| user_id.0);
+        hasher.update((members.len() as u64).to_le_bytes());
+        for (user_id, role) in &members {
+            hasher.update(user_id.0);
+            hasher.update([*role as u8]);
+        }
+        
+        let channel_manager = self.channel_manager.read().await;
+        let thread_manager = self.thread_manager.read().await;
+        let mut channels = channel_manager.list_channels(space_id);
+        channels.sort_by_key(|channel| channel.id.0);
+        hasher.update((channels.len() as u64).to_le_bytes());
+        for channel in channels {
+            hasher.update(channel.id.0);
+            
+            let mut threads = thread_manager.list_threads(&channel.id);
+            threads.sort_by_key(|thread| thread.id.0);
+            hasher.update((threads.len() as u64).to_le_bytes());
+            for thread in threads {
+                hasher.update(thread.id.0);
+                
+                let mut messages = thread_manager.list_messages(&thread.id);
+                messages.sort_by_key(|message| message.id.0);
+                hasher.update((messages.len() as u64).to_le_bytes());
+                for message in messages {
+                    hasher.update(message.id.0);
+                    hasher.update((message.content.len() as u64).to_le_bytes());
+                    hasher.update(message.content.as_bytes());
+                    hasher.update([message.deleted as u8]);
+                }
+            }
+        }
+        
+        hasher.finalize().into()
+    }
+    
+    /// Export a Space's channels, threads and messages as a transcript
+    /// 
+    /// Output is sorted, so exporting unchanged state is deterministic.
+    /// Attachments are referenced by blob hash, not embedded.
+    pub async fn export_space(
+        &self,
+        space_id: &SpaceId,
+        format: crate::export::ExportFormat,
+    ) -> Result<Vec<u8>> {
+        use crate::export::*;
+        
+        let space = self.get_space(space_id).await
+            .ok_or_else(|| Error::NotFound(format!("Space {} not found", space_id.short())))?;
+        
+        let mut transcript = SpaceTranscript::new(&space);
+        for channel in self.list_channels(space_id).await {
+            let mut channel_transcript = ChannelTranscript::new(&channel);
+            for thread in self.list_threads(&channel.id).await {
+                let mut thread_transcript = ThreadTranscript::new(&thread);
+                for message in self.list_messages(&thread.id).await {
+                    let attachment = self.storage.get_message_blob(&message.id).ok().flatten();
+                    let mut message_transcript = MessageTranscript::new(&message, attachment);
+                    message_transcript.origin = self.storage.get_message_origin(&message.id).ok().flatten();
+                    thread_transcript.messages.push(message_transcript);
+                }
+                channel_transcript.threads.push(thread_transcript);
+            }
+            transcript.channels.push(channel_transcript);
+        }
+        transcript.sort();
+        
+        transcript.render(format)
+    }
+    
+    /// Replay a JSON transcript (from `export_space`) into a new Space
+    /// 
+    /// Everything is re-created as fresh CRDT ops authored by this client,
+    /// in the transcript's order. Each message's original author and
+    /// timestamp are kept locally (see `message_origin`). Deleted messages
+    /// are skipped. The transcript is validated before anything is created.
+    pub async fn import_transcript(&self, bytes: &[u8]) -> Result<SpaceId> {
+        use crate::export::SpaceTranscript;
+        
+        let mut transcript = SpaceTranscript::from_json(bytes)?;
+        transcript.sort();
+        
+        let membership_mode = SpaceMembershipMode::from_str(&transcript.membership_mode)
+            .unwrap_or_default();
+        let (space, _, _) = self.create_space_with_mode(
+            transcript.name.clone(),
+            transcript.description.clone(),
+            SpaceVisibility::default(),
+            membership_mode,
+        ).await?;
+        
+        for channel in &transcript.channels {
+            let (new_channel, _) = self.create_channel(
+                space.id,
+                channel.name.clone(),
+                channel.description.clone(),
+            ).await?;
+            
+            for thread in &channel.threads {
+                let mut messages = thread.messages.iter().filter(|m| !m.deleted);
+                
+                // The first message becomes the thread starter
+                let first = messages.next();
+                let (new_thread, _) = self.create_thread(
+                    space.id,
+                    new_channel.id,
+                    thread.title.clone(),
+                    first.map(|m| m.content.clone()).unwrap_or_default(),
+                ).await?;
+                if let Some(first) = first {
+                    self.storage.store_message_origin(&new_thread.first_message_id, &first.origin())?;
+                }
+                
+                for message in messages {
+                    let (new_message, _) = self.post_message(
+                        space.id,
+                        new_thread.id,
+                        message.content.clone(),
+                    ).await?;
+                    self.storage.store_message_origin(&new_message.id, &message.origin())?;
+                }
+            }
+        }
+        
+        tracing::info!(space_id = %space.id.short(), transcript = %transcript.name, "Imported transcript");
+        Ok(space.id)
+    }
+    
+    /// Original attribution of a message created by `import_transcript`
+    pub async fn message_origin(&self, message_id: &MessageId) -> Option<crate::export::MessageOrigin> {
+        self.storage.get_message_origin(message_id).ok().flatten()
+    }
+    
+    /// Key for blobs only this user can read
+    fn user_blob_key(&self) -> [u8; 32] {
+        use sha2::{Sha256, Digest};
+        let mut hasher = Sha256::new();
+        hasher.update(b"descord-user-blob-key-v1");
+        hasher.update(&self.user_id.0);
+        hasher.finalize().into()
+    }
+    
+    /// Keys for blobs shared with a Space, newest first
+    /// 
+    /// Exported from the Space's MLS group at each epoch we have keys for, so
+    /// only members can derive them and a removed member can't derive the
+    /// next one; the first seals new blobs. Lightweight Spaces have no group;
+    /// their blobs are keyed by the public Space ID and are not secret.
+    async fn space_blob_keys(&self, space_id: &SpaceId) -> Result<Vec<[u8; 32]>> {
+        match self.dht_keys(space_id, crate::mls::key_labels::BLOB).await? {
+            SpaceKeys::Epochs(keys) => Ok(keys.into_values().rev().collect()),
+            SpaceKeys::Public => {
+                use sha2::{Sha256, Digest};
+                let mut hasher = Sha256::new();
+                hasher.update(b"descord-space-blob-key-v1");
+                hasher.update(&space_id.0);
+                Ok(vec![hasher.finalize().into()])
+            }
+        }
     }
     
     /// Store a blob (attachment, media)
@@ -2302,13 +5061,10 @@ impl Client {
         mime_type: Option<String>,
         filename: Option<String>,
     ) -> Result<crate::storage::indices::BlobMetadata> {
-        // Derive encryption key from user's keypair
-        // For user-specific blobs (attachments), we use a user-derived key
-        use sha2::{Sha256, Digest};
-        let mut hasher = Sha256::new();
-        hasher.update(b"descord-user-blob-key-v1");
-        hasher.update(&self.user_id.0);
-        let key_bytes: [u8; 32] = hasher.finalize().into();
+        // Private blobs use the user-derived key
+        let key_bytes = self.user_blob_key();
+        
+        self.storage.check_content_type(data, mime_type.as_deref())?;
         
         // Store encrypted blob
         let hash = self.storage.store_blob(data, &key_bytes)?;
@@ -2342,7 +5098,9 @@ impl Client {
     /// Store a blob with DHT replication for a specific Space
     /// 
     /// This is used for Space-related content (messages, attachments) that should
-    /// be available even when the uploader is offline.
+    /// be available even when the uploader is offline. The blob is encrypted
+    /// with the Space's key rather than the uploader's, so other members can
+    /// read it.
     pub async fn store_blob_for_space(
         &self,
         space_id: &SpaceId,
@@ -2350,31 +5108,44 @@ impl Client {
         mime_type: Option<String>,
         filename: Option<String>,
     ) -> Result<crate::storage::indices::BlobMetadata> {
-        // Store locally first
-        let metadata = self.store_blob(data, mime_type, filename).await?;
+        // Space blobs use the Space's members-only key so every member can decrypt them
+        let key_bytes = self.space_blob_keys(space_id).await?
+            .into_iter()
+            .next()
+            .ok_or_else(|| Error::Crypto("No key to seal Space blobs with".to_string()))?;
         
-        // Derive encryption key for local blob
-        use sha2::{Sha256, Digest};
-        let mut hasher = Sha256::new();
-        hasher.update(b"descord-user-blob-key-v1");
-        hasher.update(&self.user_id.0);
-        let key_bytes: [u8; 32] = hasher.finalize().into();
+        self.storage.check_content_type(data, mime_type.as_deref())?;
+        
+        // Store locally first, in the Space's own blob directory
+        let hash = self.storage.store_space_blob(space_id, data, &key_bytes)?;
+        let metadata = crate::storage::indices::BlobMetadata::new(
+            hash,
+            data.len() as u64,
+            mime_type,
+            filename,
+            self.user_id,
+            None,
+        );
+        self.storage.store_blob_metadata(&hash, &metadata)?;
         
         // Load the locally-encrypted blob
-        let blob_path = self.storage.blob_dir().join(metadata.hash.to_hex());
+        let blob_path = self.storage.blob_path(&metadata.hash)?;
         let blob_bytes = std::fs::read(&blob_path)
             .context("Failed to read blob for DHT upload")?;
         let local_blob = crate::storage::blob::EncryptedBlob::from_bytes(&blob_bytes)?;
         
         // Upload to DHT (non-blocking, best effort)
+        if !self.dht_enabled {
+            return Ok(metadata);
+        }
         let result = self.dht_put_blob(space_id, &metadata.hash, &local_blob).await;
         if let Err(e) = result {
             // Don't fail if DHT upload fails (degraded mode)
-            eprintln!("⚠ Failed to upload blob to DHT: {}", e);
+            tracing::warn!("Failed to upload blob to DHT: {}", e);
         } else {
             tracing::info!(
                 hash = %metadata.hash.to_hex(),
-                space_id = %hex::encode(&space_id.0[..8]),
+                space_id = %space_id.short(),
                 "Uploaded blob to DHT"
             );
         }
@@ -2386,12 +5157,7 @@ impl Client {
     /// 
     /// Decrypts and returns the blob data. Verifies content integrity.
     pub async fn retrieve_blob(&self, hash: &crate::storage::BlobHash) -> Result<Vec<u8>> {
-        // Derive the same encryption key
-        use sha2::{Sha256, Digest};
-        let mut hasher = Sha256::new();
-        hasher.update(b"descord-user-blob-key-v1");
-        hasher.update(&self.user_id.0);
-        let key_bytes: [u8; 32] = hasher.finalize().into();
+        let key_bytes = self.user_blob_key();
         
         // Try local storage first
         match self.storage.load_blob(hash, &key_bytes) {
@@ -2419,15 +5185,15 @@ impl Client {
         space_id: &SpaceId,
         hash: &crate::storage::BlobHash,
     ) -> Result<Vec<u8>> {
-        // Derive encryption key
-        use sha2::{Sha256, Digest};
-        let mut hasher = Sha256::new();
-        hasher.update(b"descord-user-blob-key-v1");
-        hasher.update(&self.user_id.0);
-        let key_bytes: [u8; 32] = hasher.finalize().into();
+        // Any epoch we held may have sealed it; blobs stored before Space keys used the user key
+        let mut keys = self.space_blob_keys(space_id).await.unwrap_or_default();
+        keys.push(self.user_blob_key());
         
         // Try local storage first
-        match self.storage.load_blob(hash, &key_bytes) {
+        let local = keys.iter()
+            .find_map(|key| self.storage.load_blob(hash, key).ok())
+            .ok_or(());
+        match local {
             Ok(plaintext) => {
                 tracing::debug!(
                     hash = %hash.to_hex(),
@@ -2439,19 +5205,19 @@ impl Client {
                 // Not found locally - try DHT
                 tracing::info!(
                     hash = %hash.to_hex(),
-                    space_id = %hex::encode(&space_id.0[..8]),
+                    space_id = %space_id.short(),
                     "Blob not found locally, fetching from DHT"
                 );
                 
                 match self.dht_get_blob(space_id, hash).await {
                     Ok(local_blob) => {
                         // Got it from DHT! Decrypt and store locally
-                        let plaintext = local_blob.decrypt(&key_bytes)?;
+                        let plaintext = keys.iter()
+                            .find_map(|key| local_blob.decrypt(key).ok())
+                            .ok_or_else(|| Error::Crypto(format!("No Space key opens blob {}", hash.to_hex())))?;
                         
-                        // Store locally for future access
-                        let blob_bytes = local_blob.to_bytes()?;
-                        let blob_path = self.storage.blob_dir().join(hash.to_hex());
-                        std::fs::write(&blob_path, &blob_bytes)
+                        // Cache locally for future access (evictable under the storage quota)
+                        self.storage.cache_space_blob(space_id, hash, &local_blob)
                             .context("Failed to cache blob from DHT")?;
                         
                         tracing::info!(
@@ -2470,158 +5236,565 @@ impl Client {
                     }
                 }
             }
-        }
+        }
+    }
+    
+    /// Broadcast a CRDT operation to the network
+    #[tracing::instrument(skip_all, fields(
+        op_id = %op.op_id.0,
+        space_id = %op.space_id.short(),
+        topic = tracing::field::Empty,
+    ))]
+    async fn broadcast_op(&self, op: &CrdtOp) -> Result<()> {
+        let topic = format!("space/{}", op.space_id.short());
+        tracing::Span::current().record("topic", topic.as_str());
+        self.counters.record_op_sent();
+        if self.delivery_acks {
+            self.delivery.write().await.track(op.op_id);
+        }
+        
+        tracing::debug!(op_type = ?op.op_type, "Broadcasting operation");
+        
+        // Broadcast via GossipSub
+        tracing::debug!("Step 1: Calling broadcast_op_on_topic (GossipSub)...");
+        if !self.broadcast_op_on_topic(op, &topic).await? {
+            // Nobody to send to yet; the outbox flusher resends it once a peer connects
+            tracing::debug!("No peers to publish to, queued op for sending");
+            if let Err(e) = self.storage.queue_unsent_op(op) {
+                tracing::warn!(error = %e, "Failed to queue unsent operation");
+            }
+        }
+        tracing::debug!("Step 1: GossipSub broadcast completed");
+        
+        // Store in DHT for offline sync
+        // Note: We store each operation individually for now
+        // TODO: Batch operations for efficiency
+        if !self.dht_enabled {
+            return Ok(());
+        }
+        tracing::debug!("Step 2: Calling dht_put_operations (DHT storage)...");
+        let result = self.dht_put_operations(&op.space_id, vec![op.clone()]).await;
+        if let Err(e) = result {
+            // Don't fail if DHT storage fails (degraded mode); queue the op
+            // so the retrier uploads it once peers are reachable
+            tracing::warn!(error = %e, "Failed to store operation in DHT, queued for retry");
+            if let Err(e) = self.storage.queue_pending_upload(op) {
+                tracing::warn!(error = %e, "Failed to queue operation for DHT retry");
+            }
+        }
+        
+        tracing::debug!("Broadcast operation completed");
+        Ok(())
+    }
+    
+    /// Broadcast a CRDT operation to a specific topic
+    /// 
+    /// Returns whether the op was published; having no peers to publish to
+    /// is expected (single-node use, offline) and not an error.
+    async fn broadcast_op_on_topic(&self, op: &CrdtOp, topic: &str) -> Result<bool> {
+        tracing::debug!(topic, "Publishing operation via GossipSub");
+        
+        // Serialize the operation
+        tracing::debug!("Step A: Serializing operation...");
+        let op_bytes = op.to_canonical_bytes();
+        tracing::debug!("Step A: Serialized {} bytes", op_bytes.len());
+        
+        // Encrypt for the channel or Space MLS group, if there is one. All
+        // manager locks are released before publishing
+        let data = seal_op(&self.mls_provider, &self.channel_manager, &self.space_manager, op, &op_bytes).await?;
+        tracing::debug!("Step E: Data prepared ({} bytes), acquiring network lock...", data.len());
+        
+        let mut network = self.network.write().await;
+        tracing::debug!("Step E: Network lock acquired");
+        
+        // Attempt to publish, but don't fail if no peers are connected
+        // This is expected in single-node scenarios and tests
+        tracing::debug!("Step F: Calling network.publish...");
+        let result = network.publish(topic, data).await;
+        tracing::debug!("Step F: Publish returned: {:?}", result.is_ok());
+        
+        // Record metrics
+        tracing::debug!("Step G: Recording metrics...");
+        if result.is_ok() {
+            self.gossip_metrics.record_publish(topic).await;
+        }
+        tracing::debug!("Step G: Metrics recorded");
+        
+        tracing::debug!("GossipSub publish completed");
+        Ok(result.is_ok())
+    }
+    
+    /// Broadcast raw data on a topic (for sync requests, etc.)
+    async fn broadcast_raw(&self, topic: &str, data: Vec<u8>) -> Result<()> {
+        let mut network = self.network.write().await;
+        network.publish(topic, data).await
+    }
+    
+    /// Ask connected peers for the operations of a Space we don't hold yet
+    ///
+    /// The request carries a clock of our ops and names one connected peer,
+    /// picked at random, to answer it by sending only the ops the clock shows
+    /// to be missing, on a topic only this client subscribes to, so other
+    /// members of the Space aren't sent anything. Repeating the request
+    /// recovers ops lost in transit. Fails when no peer is connected.
+    pub async fn request_space_sync(&self, space_id: &SpaceId) -> Result<()> {
+        let responder = pick_responder(&self.network).await
+            .ok_or_else(|| Error::Network("No connected peer to sync from".to_string()))?;
+        self.request_space_sync_from(space_id, &responder.to_string()).await
+    }
+    
+    /// Ask the peer `responder` for the ops of a Space we lack
+    async fn request_space_sync_from(&self, space_id: &SpaceId, responder: &str) -> Result<()> {
+        let space_topic = format!("space/{}", space_id.short());
+        let request = crate::network::CatchUpRequest {
+            space_id: *space_id,
+            requester: self.peer_id().await.to_string(),
+            clock: crate::network::SpaceClock::of(&self.store.get_space_ops(space_id)?),
+            responder: responder.to_string(),
+        };
+        self.broadcast_raw(&space_topic, request.to_bytes()?).await
+    }
+    
+    /// Our causal frontier for a Space: how many ops of each author we hold
+    ///
+    /// Keys are full hex user IDs. Compare with [`Client::peer_space_clocks`]
+    /// to see whether we are caught up with a peer.
+    pub fn space_vector_clock(&self, space_id: &SpaceId) -> Result<crate::storage::VectorClock> {
+        let ops = self.store.get_space_ops(space_id)?;
+        Ok(crate::network::SpaceClock::of(&ops).to_vector_clock())
+    }
+    
+    /// Clocks peers last sent with a sync request or answer for a Space, by peer ID
+    pub async fn peer_space_clocks(&self, space_id: &SpaceId) -> std::collections::HashMap<String, crate::storage::VectorClock> {
+        self.peer_clocks.read().await
+            .get(space_id)
+            .cloned()
+            .unwrap_or_default()
+    }
+    
+    /// Dial a peer, join a Space's topic and sync until we hold all it has
+    ///
+    /// `peer_addr` must end in `/p2p/{peer_id}` so the peer's answer can be
+    /// told apart from others. Sync requests are repeated until that peer's
+    /// clock shows nothing we lack, or `timeout` passes with
+    /// [`Error::TimedOut`].
+    pub async fn connect_and_sync(&self, peer_addr: &str, space_id: &SpaceId, timeout: std::time::Duration) -> Result<()> {
+        let multiaddr: libp2p::Multiaddr = peer_addr.parse()
+            .map_err(|e| Error::Network(format!("Invalid peer address: {}", e)))?;
+        let peer_id = match multiaddr.iter().last() {
+            Some(libp2p::multiaddr::Protocol::P2p(peer_id)) => peer_id.to_string(),
+            _ => return Err(Error::Network(format!("Peer address has no /p2p/ peer ID: {}", peer_addr))),
+        };
+        let deadline = tokio::time::Instant::now() + timeout;
+        
+        // A clock from an earlier sync says nothing about this one
+        if let Some(clocks) = self.peer_clocks.write().await.get_mut(space_id) {
+            clocks.remove(&peer_id);
+        }
+        self.network_dial(peer_addr).await?;
+        self.subscribe_to_space(space_id).await?;
+        
+        let mut next_request = tokio::time::Instant::now();
+        loop {
+            if let Some(theirs) = self.peer_space_clocks(space_id).await.remove(&peer_id) {
+                let ours = self.space_vector_clock(space_id)?;
+                if theirs.clocks.iter().all(|(user, &count)| ours.clocks.get(user).copied().unwrap_or(0) >= count) {
+                    return Ok(());
+                }
+            }
+            let now = tokio::time::Instant::now();
+            if now >= deadline {
+                return Err(Error::TimedOut(format!("sync of Space {} with {}", space_id.short(), peer_id)));
+            }
+            if now >= next_request {
+                // Fails until the gossip mesh with the peer has formed
+                if let Err(e) = self.request_space_sync_from(space_id, &peer_id).await {
+                    tracing::debug!("Sync request not sent yet: {}", e);
+                }
+                next_request = now + std::time::Duration::from_secs(2);
+            }
+            tokio::time::sleep(std::time::Duration::from_millis(200)).await;
+        }
+    }
+    
+    /// Ops held back because something they depend on hasn't arrived, oldest first
+    /// 
+    /// A message delivered before its thread waits here until the thread
+    /// does; ops that stay are usually a sign of a missed sync.
+    pub async fn holdback_status(&self) -> Vec<crate::crdt::StuckOp> {
+        holdback_status(&self.space_manager, &self.channel_manager, &self.thread_manager).await
+    }
+    
+    /// Subscribe to a Space's operation stream
+    pub async fn subscribe_to_space(&self, space_id: &SpaceId) -> Result<()> {
+        let topic = format!("space/{}", space_id.short());
+        tracing::debug!(%topic, "Subscribing to topic");
+        let mut network = self.network.write().await;
+        network.subscribe(&topic).await?;
+        tracing::info!(%topic, "Subscribed to topic");
+        
+        if self.delivery_acks {
+            network.subscribe(&crate::network::ack::ack_topic(space_id)).await?;
+        }
+        if self.peer_exchange {
+            network.subscribe(&crate::network::peer_exchange::peer_exchange_topic(space_id)).await?;
+            self.pex_spaces.write().await.insert(*space_id);
+            self.pex_pending.store(true, std::sync::atomic::Ordering::Relaxed);
+        }
+        if self.anti_entropy_interval.is_some() {
+            network.subscribe(&crate::network::anti_entropy::sync_topic(space_id)).await?;
+            self.sync_spaces.write().await.insert(*space_id);
+        }
+        if self.presence {
+            network.subscribe(&crate::network::presence::presence_topic(space_id)).await?;
+            self.presence_spaces.write().await.insert(*space_id);
+        }
+        
+        Ok(())
+    }
+    
+    /// Peers in a Space learned through peer exchange that we have connected to
+    ///
+    /// Empty unless `ClientConfig::peer_exchange` is enabled.
+    pub async fn known_peers(&self, space_id: &SpaceId) -> Vec<crate::network::PeerRecord> {
+        self.peer_book.read().await.known_peers(space_id)
+    }
+    
+    /// When each user was last seen online in a Space, in milliseconds since the epoch
+    ///
+    /// Empty unless `ClientConfig::presence` is enabled on both ends.
+    pub async fn last_seen(&self, space_id: &SpaceId) -> std::collections::HashMap<UserId, u64> {
+        self.presence_book.read().await.last_seen(space_id)
+    }
+    
+    /// Users that acknowledged receiving an op this client broadcast
+    ///
+    /// Empty unless `ClientConfig::delivery_acks` is enabled on both ends.
+    pub async fn delivery_status(&self, op_id: &OpId) -> Vec<UserId> {
+        self.delivery.read().await.status(op_id)
+    }
+    
+    /// Publish batched acks for received ops every `ACK_FLUSH_INTERVAL`
+    fn spawn_ack_flusher(&self) {
+        let signer = Arc::clone(&self.signer);
+        let network = Arc::clone(&self.network);
+        let ack_batcher = Arc::clone(&self.ack_batcher);
+        
+        tokio::spawn(async move {
+            let mut stamper = crate::network::Stamper::default();
+            let mut interval = tokio::time::interval(crate::network::ack::ACK_FLUSH_INTERVAL);
+            loop {
+                interval.tick().await;
+                let batches = ack_batcher.write().await.drain();
+                for (space_id, op_ids) in batches {
+                    let ack = crate::network::Ack::new(&*signer, space_id, op_ids, stamper.next());
+                    let Ok(bytes) = ack.and_then(|ack| ack.to_bytes()) else { continue };
+                    let mut network = network.write().await;
+                    if let Err(e) = network.publish(&crate::network::ack::ack_topic(&space_id), bytes).await {
+                        tracing::debug!(error = %e, "Failed to publish ack");
+                    }
+                }
+            }
+        });
+    }
+    
+    /// Publish a signed heartbeat in each of our Spaces every `PRESENCE_INTERVAL`
+    fn spawn_presence_heartbeat(&self) {
+        let signer = Arc::clone(&self.signer);
+        let network = Arc::clone(&self.network);
+        let presence_spaces = Arc::clone(&self.presence_spaces);
+        
+        tokio::spawn(async move {
+            let mut stamper = crate::network::Stamper::default();
+            let mut interval = tokio::time::interval(crate::network::presence::PRESENCE_INTERVAL);
+            loop {
+                interval.tick().await;
+                let spaces: Vec<SpaceId> = presence_spaces.read().await.iter().copied().collect();
+                for space_id in spaces {
+                    let heartbeat = crate::network::Presence::new(&*signer, space_id, stamper.next());
+                    let Ok(bytes) = heartbeat.and_then(|heartbeat| heartbeat.to_bytes()) else { continue };
+                    let mut network = network.write().await;
+                    if let Err(e) = network.publish(&crate::network::presence::presence_topic(&space_id), bytes).await {
+                        tracing::debug!(error = %e, "Failed to publish presence");
+                    }
+                }
+            }
+        });
+    }
+    
+    /// Publish peer announcements for our Spaces whenever they went stale
+    ///
+    /// A failed publish (e.g. before the topic mesh has formed) leaves the
+    /// announcements pending, so they are retried on the next tick.
+    fn spawn_peer_announcer(&self) {
+        let network = Arc::clone(&self.network);
+        let peer_book = Arc::clone(&self.peer_book);
+        let pex_spaces = Arc::clone(&self.pex_spaces);
+        let pex_pending = Arc::clone(&self.pex_pending);
+        
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(crate::network::peer_exchange::PEX_INTERVAL);
+            loop {
+                interval.tick().await;
+                if !pex_pending.swap(false, std::sync::atomic::Ordering::Relaxed) {
+                    continue;
+                }
+                
+                let spaces: Vec<SpaceId> = pex_spaces.read().await.iter().copied().collect();
+                let (local, listen_addrs) = {
+                    let network = network.read().await;
+                    (*network.local_peer_id(), network.listeners().await)
+                };
+                for space_id in spaces {
+                    let announcement = peer_book.read().await.announcement(space_id, &local, &listen_addrs);
+                    let Ok(bytes) = announcement.to_bytes() else { continue };
+                    let topic = crate::network::peer_exchange::peer_exchange_topic(&space_id);
+                    if let Err(e) = network.write().await.publish(&topic, bytes).await {
+                        tracing::debug!(error = %e, "Failed to publish peer announcement");
+                        pex_pending.store(true, std::sync::atomic::Ordering::Relaxed);
+                    }
+                }
+            }
+        });
+    }
+    
+    /// Report ops held back longer than `op_stuck_after` as `ClientEvent::OpStuck`
+    ///
+    /// Each stuck op is reported once. Its Space's connected peers are asked
+    /// for the ops our clock lacks, which usually include what it waits on.
+    fn spawn_holdback_watch(&self) {
+        let space_manager = Arc::clone(&self.space_manager);
+        let channel_manager = Arc::clone(&self.channel_manager);
+        let thread_manager = Arc::clone(&self.thread_manager);
+        let store = Arc::clone(&self.store);
+        let network = Arc::clone(&self.network);
+        let events = self.events.clone();
+        let stuck_after = self.op_stuck_after;
+        
+        tokio::spawn(async move {
+            let mut reported = std::collections::HashSet::new();
+            let mut interval = tokio::time::interval(HOLDBACK_CHECK_INTERVAL);
+            loop {
+                interval.tick().await;
+                let stuck: Vec<crate::crdt::StuckOp> = holdback_status(&space_manager, &channel_manager, &thread_manager).await
+                    .into_iter()
+                    .filter(|stuck| stuck.age >= stuck_after)
+                    .collect();
+                reported.retain(|op_id| stuck.iter().any(|stuck| stuck.op_id == *op_id));
+                
+                let mut synced = std::collections::HashSet::new();
+                for stuck in stuck {
+                    if !reported.insert(stuck.op_id) {
+                        continue;
+                    }
+                    tracing::warn!(op_id = ?stuck.op_id, waiting_on = ?stuck.waiting_on, age = ?stuck.age, "Operation stuck in holdback");
+                    
+                    if synced.insert(stuck.space_id) {
+                        match (store.get_space_ops(&stuck.space_id), pick_responder(&network).await) {
+                            (Ok(ops), Some(responder)) => {
+                                let mut network = network.write().await;
+                                let request = crate::network::CatchUpRequest {
+                                    space_id: stuck.space_id,
+                                    requester: network.local_peer_id().to_string(),
+                                    clock: crate::network::SpaceClock::of(&ops),
+                                    responder: responder.to_string(),
+                                };
+                                if let Ok(bytes) = request.to_bytes() {
+                                    let topic = format!("space/{}", stuck.space_id.short());
+                                    if let Err(e) = network.publish(&topic, bytes).await {
+                                        tracing::debug!(error = %e, "Failed to request sync for stuck op");
+                                    }
+                                }
+                            }
+                            (Ok(_), None) => tracing::debug!("No peer to request sync for stuck op from"),
+                            (Err(e), _) => tracing::warn!(error = %e, "Failed to read ops for stuck-op sync"),
+                        }
+                    }
+                    let _ = events.send(ClientEvent::OpStuck(stuck));
+                }
+            }
+        });
+    }
+    
+    /// Send a sync digest for each subscribed Space every `period`
+    ///
+    /// Each digest names one random connected peer, which re-publishes the
+    /// ops our clock lacks. Rounds without a connected peer are skipped.
+    fn spawn_anti_entropy(&self, period: Duration) {
+        let store = Arc::clone(&self.store);
+        let network = Arc::clone(&self.network);
+        let sync_spaces = Arc::clone(&self.sync_spaces);
+        
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval_at(tokio::time::Instant::now() + period, period);
+            loop {
+                interval.tick().await;
+                let peers = network.read().await.connected_peers().await;
+                if peers.is_empty() {
+                    continue;
+                }
+                
+                let spaces: Vec<SpaceId> = sync_spaces.read().await.iter().copied().collect();
+                for space_id in spaces {
+                    let ops = match store.get_space_ops(&space_id) {
+                        Ok(ops) => ops,
+                        Err(e) => {
+                            tracing::warn!(error = %e, "Failed to read ops for anti-entropy");
+                            continue;
+                        }
+                    };
+                    let responder = {
+                        use rand::seq::SliceRandom;
+                        peers.choose(&mut rand::thread_rng()).copied()
+                    };
+                    let Some(responder) = responder else { continue };
+                    let digest = crate::network::SyncDigest {
+                        space_id,
+                        responder: responder.to_string(),
+                        clock: crate::network::SpaceClock::of(&ops),
+                    };
+                    let Ok(bytes) = digest.to_bytes() else { continue };
+                    let topic = crate::network::anti_entropy::sync_topic(&space_id);
+                    if let Err(e) = network.write().await.publish(&topic, bytes).await {
+                        tracing::debug!(error = %e, "Failed to publish sync digest");
+                    }
+                }
+            }
+        });
     }
     
-    /// Broadcast a CRDT operation to the network
-    async fn broadcast_op(&self, op: &CrdtOp) -> Result<()> {
-        let topic = format!("space/{}", ::hex::encode(&op.space_id.0[..8]));
-        
-        eprintln!("📢 [BROADCAST START] Broadcasting operation on topic: {}", topic);
-        eprintln!("📢 [BROADCAST] Operation type: {:?}, space_id: {}", 
-                 std::any::type_name_of_val(&op.op_type), hex::encode(&op.space_id.0[..8]));
-        
-        // Broadcast via GossipSub
-        eprintln!("📢 [BROADCAST] Step 1: Calling broadcast_op_on_topic (GossipSub)...");
-        self.broadcast_op_on_topic(op, &topic).await?;
-        eprintln!("📢 [BROADCAST] Step 1: ✓ GossipSub broadcast completed");
-        
-        // Store in DHT for offline sync
-        // Note: We store each operation individually for now
-        // TODO: Batch operations for efficiency
-        eprintln!("📢 [BROADCAST] Step 2: Calling dht_put_operations (DHT storage)...");
-        let result = self.dht_put_operations(&op.space_id, vec![op.clone()]).await;
-        match &result {
-            Ok(_) => eprintln!("📢 [BROADCAST] Step 2: ✓ DHT storage completed"),
-            Err(e) => eprintln!("📢 [BROADCAST] Step 2: ✗ DHT storage failed: {}", e),
-        }
-        if let Err(e) = result {
-            // Don't fail if DHT storage fails (degraded mode)
-            eprintln!("⚠ Failed to store operation in DHT: {}", e);
-        }
-        
-        eprintln!("📢 [BROADCAST END] Broadcast operation completed");
-        Ok(())
+    /// Operations whose DHT upload failed and are waiting to be retried
+    pub async fn pending_dht_uploads(&self) -> Result<Vec<CrdtOp>> {
+        Ok(self.storage.pending_uploads()?)
     }
     
-    /// Broadcast a CRDT operation to a specific topic
-    async fn broadcast_op_on_topic(&self, op: &CrdtOp, topic: &str) -> Result<()> {
-        eprintln!("🔵 [GOSSIPSUB] START: Broadcasting to topic {}", topic);
+    /// Retry queued DHT uploads every `DHT_RETRY_INTERVAL` and on new connections
+    ///
+    /// Nothing is attempted without a connected peer. Each Space's queued ops
+    /// go up as one append and leave the queue only once it succeeded, so a
+    /// failed retry keeps them for the next round (and across restarts).
+    fn spawn_dht_upload_retrier(&self) {
+        let storage = Arc::clone(&self.storage);
+        let network = Arc::clone(&self.network);
+        let space_manager = Arc::clone(&self.space_manager);
+        let dht_writes = Arc::clone(&self.dht_writes);
+        let dht_retry = Arc::clone(&self.dht_retry);
         
-        // Serialize the operation
-        eprintln!("🔵 [GOSSIPSUB] Step A: Serializing operation...");
-        let op_bytes = minicbor::to_vec(op)
-            .map_err(|e| Error::Serialization(format!("Failed to encode operation: {}", e)))?;
-        eprintln!("🔵 [GOSSIPSUB] Step A: ✓ Serialized {} bytes", op_bytes.len());
-        
-        // Check if this Space has an MLS group - if so, encrypt the operation
-        eprintln!("🔵 [GOSSIPSUB] Step B: Acquiring space_manager lock...");
-        let data = {
-            // First check for channel-level MLS group (for operations in channels)
-            let mut channel_encrypted = false;
-            let mut data = Vec::new();
-            
-            // Check if this operation is for a specific channel
-            if let Some(channel_id) = &op.channel_id {
-                let mut channel_manager = self.channel_manager.write().await;
-                if let Some(mls_group) = channel_manager.get_mls_group_mut(channel_id) {
-                    eprintln!("🔵 [GOSSIPSUB] Step C: Channel MLS group found, encrypting...");
-                    // Encrypt the operation as MLS application data using channel's group
-                    let provider = self.mls_provider.read().await;
-                    let encrypted_msg = mls_group.encrypt_application_message(&op_bytes, &provider)?;
-                    drop(provider);
-                    drop(channel_manager);
-                    eprintln!("🔵 [GOSSIPSUB] Step C: ✓ Encrypted with channel MLS");
-                    
-                    // Serialize the encrypted MLS message
-                    eprintln!("🔵 [GOSSIPSUB] Step D: Serializing encrypted message...");
-                    let encrypted_bytes = encrypted_msg.to_bytes()
-                        .map_err(|e| Error::Serialization(format!("Failed to serialize MLS message: {}", e)))?;
-                    eprintln!("🔵 [GOSSIPSUB] Step D: ✓ Serialized {} bytes", encrypted_bytes.len());
-                    
-                    // Format: [0x02][channel_id (32 bytes)][encrypted_data]
-                    // 0x02 indicates channel-level encryption
-                    data = vec![0x02];
-                    data.extend_from_slice(&channel_id.0);
-                    data.extend_from_slice(&encrypted_bytes);
-                    channel_encrypted = true;
-                } else {
-                    drop(channel_manager);
+        tokio::spawn(async move {
+            loop {
+                tokio::select! {
+                    _ = tokio::time::sleep(DHT_RETRY_INTERVAL) => {}
+                    _ = dht_retry.notified() => {}
                 }
-            }
-            
-            // If not encrypted at channel level, check for space-level MLS
-            if !channel_encrypted {
-                let mut space_manager = self.space_manager.write().await;
-                eprintln!("🔵 [GOSSIPSUB] Step B: ✓ Lock acquired, checking for MLS group...");
                 
-                if let Some(mls_group) = space_manager.get_mls_group_mut(&op.space_id) {
-                    eprintln!("🔵 [GOSSIPSUB] Step C: Space MLS group found, encrypting...");
-                    // Encrypt the operation as MLS application data
-                    let provider = self.mls_provider.read().await;
-                    let encrypted_msg = mls_group.encrypt_application_message(&op_bytes, &provider)?;
-                    drop(provider);
-                    eprintln!("🔵 [GOSSIPSUB] Step C: ✓ Encrypted");
-                    
-                    // Serialize the encrypted MLS message
-                    eprintln!("🔵 [GOSSIPSUB] Step D: Serializing encrypted message...");
-                    let encrypted_bytes = encrypted_msg.to_bytes()
-                        .map_err(|e| Error::Serialization(format!("Failed to serialize MLS message: {}", e)))?;
-                    eprintln!("🔵 [GOSSIPSUB] Step D: ✓ Serialized {} bytes", encrypted_bytes.len());
-                    
-                    // Format: [0x01][space_id (32 bytes)][encrypted_data]
-                    // The space_id is needed for decryption on the receive side
-                    data = vec![0x01];
-                    data.extend_from_slice(&op.space_id.0);
-                    data.extend_from_slice(&encrypted_bytes);
-                } else {
-                    eprintln!("🔵 [GOSSIPSUB] Step C: No MLS group, using plaintext");
-                    // No MLS group - send plaintext with marker (0x00)
-                    data = vec![0x00];
-                    data.extend_from_slice(&op_bytes);
+                let pending = match storage.pending_uploads() {
+                    Ok(pending) if !pending.is_empty() => pending,
+                    Ok(_) => continue,
+                    Err(e) => {
+                        tracing::warn!(error = %e, "Failed to read pending DHT uploads");
+                        continue;
+                    }
+                };
+                
+                if network.read().await.connected_peers().await.is_empty() {
+                    continue;
+                }
+                
+                let mut by_space: std::collections::HashMap<SpaceId, Vec<CrdtOp>> = std::collections::HashMap::new();
+                for op in pending {
+                    by_space.entry(op.space_id).or_default().push(op);
+                }
+                for (space_id, ops) in by_space {
+                    let op_ids: Vec<OpId> = ops.iter().map(|op| op.op_id).collect();
+                    // Stay queued until we can seal for the Space's members
+                    let keys = match dht_space_keys(&space_manager, &network, true, &space_id, crate::mls::key_labels::OP_BATCH).await {
+                        Ok(keys) => keys,
+                        Err(e) => {
+                            tracing::debug!(space_id = %space_id.short(), error = %e, "No keys to seal queued operations");
+                            continue;
+                        }
+                    };
+                    let mut network = network.write().await;
+                    match dht_append_operations(&mut network, &dht_writes, &keys, &space_id, ops).await {
+                        Ok(()) => {
+                            tracing::info!(count = op_ids.len(), "Uploaded queued operations to DHT");
+                            for op_id in &op_ids {
+                                if let Err(e) = storage.remove_pending_upload(op_id) {
+                                    tracing::warn!(error = %e, "Failed to dequeue uploaded operation");
+                                }
+                            }
+                        }
+                        Err(e) => tracing::debug!(error = %e, "DHT upload retry failed"),
+                    }
                 }
             }
-            
-            data
-        };
-        eprintln!("🔵 [GOSSIPSUB] Step E: Data prepared ({} bytes), acquiring network lock...", data.len());
-        
-        let mut network = self.network.write().await;
-        eprintln!("🔵 [GOSSIPSUB] Step E: ✓ Network lock acquired");
-        
-        // Attempt to publish, but don't fail if no peers are connected
-        // This is expected in single-node scenarios and tests
-        eprintln!("🔵 [GOSSIPSUB] Step F: Calling network.publish...");
-        let result = network.publish(topic, data).await;
-        eprintln!("🔵 [GOSSIPSUB] Step F: ✓ Publish returned: {:?}", result.is_ok());
-        
-        // Record metrics
-        eprintln!("🔵 [GOSSIPSUB] Step G: Recording metrics...");
-        if result.is_ok() {
-            self.gossip_metrics.record_publish(topic).await;
-        }
-        eprintln!("🔵 [GOSSIPSUB] Step G: ✓ Metrics recorded");
-        
-        eprintln!("🔵 [GOSSIPSUB] END: Completed");
-        result.or(Ok(()))
+        });
     }
     
-    /// Broadcast raw data on a topic (for sync requests, etc.)
-    async fn broadcast_raw(&self, topic: &str, data: Vec<u8>) -> Result<()> {
-        let mut network = self.network.write().await;
-        network.publish(topic, data).await
+    /// Operations that were created while no peer could receive them and
+    /// are waiting to be published
+    pub async fn unsent_ops(&self) -> Result<Vec<CrdtOp>> {
+        Ok(self.storage.unsent_ops()?)
     }
     
-    /// Handle a sync request from a peer by re-broadcasting all Space operations
-    /// Subscribe to a Space's operation stream
-    pub async fn subscribe_to_space(&self, space_id: &SpaceId) -> Result<()> {
-        let topic = format!("space/{}", ::hex::encode(&space_id.0[..8]));
-        println!("🔔 Subscribing to topic: {}", topic);
-        let mut network = self.network.write().await;
-        network.subscribe(&topic).await?;
-        println!("✓ Subscribed to topic: {}", topic);
+    /// Republish queued unsent ops every `OUTBOX_RETRY_INTERVAL` and on new connections
+    ///
+    /// Ops are sent oldest first and sealed again at send time, so they use
+    /// the current MLS epoch. The first failed publish (e.g. the topic mesh
+    /// hasn't formed yet) ends the round; the queue is kept on disk, so ops
+    /// also survive a restart.
+    fn spawn_outbox_flusher(&self) {
+        let storage = Arc::clone(&self.storage);
+        let network = Arc::clone(&self.network);
+        let mls_provider = Arc::clone(&self.mls_provider);
+        let channel_manager = Arc::clone(&self.channel_manager);
+        let space_manager = Arc::clone(&self.space_manager);
+        let gossip_metrics = Arc::clone(&self.gossip_metrics);
+        let outbox_retry = Arc::clone(&self.outbox_retry);
         
-        Ok(())
+        tokio::spawn(async move {
+            loop {
+                tokio::select! {
+                    _ = tokio::time::sleep(OUTBOX_RETRY_INTERVAL) => {}
+                    _ = outbox_retry.notified() => {}
+                }
+                
+                let unsent = match storage.unsent_ops() {
+                    Ok(unsent) => unsent,
+                    Err(e) => {
+                        tracing::warn!(error = %e, "Failed to read unsent operations");
+                        continue;
+                    }
+                };
+                
+                for op in unsent {
+                    let topic = format!("space/{}", op.space_id.short());
+                    let op_bytes = op.to_canonical_bytes();
+                    let data = match seal_op(&mls_provider, &channel_manager, &space_manager, &op, &op_bytes).await {
+                        Ok(data) => data,
+                        Err(e) => {
+                            tracing::warn!(error = %e, "Failed to seal unsent operation");
+                            continue;
+                        }
+                    };
+                    if network.write().await.publish(&topic, data).await.is_err() {
+                        break;
+                    }
+                    gossip_metrics.record_publish(&topic).await;
+                    tracing::debug!(op_id = %op.op_id.0, "Sent queued operation");
+                    if let Err(e) = storage.remove_unsent_op(&op.op_id) {
+                        tracing::warn!(error = %e, "Failed to dequeue sent operation");
+                    }
+                }
+            }
+        });
+    }
+    
+    /// Simulate latency and message loss on inbound GossipSub traffic
+    #[cfg(any(test, feature = "test-utils"))]
+    pub async fn set_network_conditions(&self, conditions: crate::network::NetworkConditions) -> Result<()> {
+        let mut network = self.network.write().await;
+        network.set_conditions(conditions).await
     }
     
     /// Get network peer ID
@@ -2630,12 +5803,28 @@ impl Client {
         *network.local_peer_id()
     }
     
+    /// Idle connection timeout and keepalive in effect for the network
+    pub async fn connection_config(&self) -> crate::network::ConnectionConfig {
+        self.network.read().await.connection_config()
+    }
+    
     /// Get listening addresses
     pub async fn listening_addrs(&self) -> Vec<libp2p::Multiaddr> {
         let network = self.network.read().await;
         network.listeners().await
     }
     
+    /// GossipSub topics this client is subscribed to, sorted
+    ///
+    /// Read from the network node, so it shows what the swarm actually
+    /// receives rather than what the client meant to subscribe to.
+    pub async fn subscribed_topics(&self) -> Vec<String> {
+        let network = self.network.read().await;
+        let mut topics = network.subscribed_topics().await;
+        topics.sort();
+        topics
+    }
+    
     /// Dial a peer directly
     pub async fn dial(&self, addr: libp2p::Multiaddr) -> Result<()> {
         let mut network = self.network.write().await;
@@ -2650,15 +5839,15 @@ impl Client {
             match event {
                 NetworkEvent::MessageReceived { topic: _, data, source: _ } => {
                     // Decode CRDT operation
-                    if let Ok(op) = minicbor::decode::<CrdtOp>(&data) {
+                    if let Ok(op) = CrdtOp::from_canonical_bytes(&data) {
                         self.handle_incoming_op(op).await?;
                     }
                 }
                 NetworkEvent::PeerConnected(peer_id) => {
-                    println!("Peer connected: {}", peer_id);
+                    tracing::debug!("Peer connected: {}", peer_id);
                 }
                 NetworkEvent::PeerDisconnected(peer_id) => {
-                    println!("Peer disconnected: {}", peer_id);
+                    tracing::debug!("Peer disconnected: {}", peer_id);
                 }
                 _ => {}
             }
@@ -2668,9 +5857,27 @@ impl Client {
     }
     
     /// Handle an incoming CRDT operation
+    #[tracing::instrument(skip_all, fields(
+        op_id = %op.op_id.0,
+        space_id = %op.space_id.short(),
+        topic = %format!("space/{}", op.space_id.short()),
+    ))]
     pub async fn handle_incoming_op(&self, op: CrdtOp) -> Result<()> {
+        // Ops from the DHT and catch-up reach us here without passing the
+        // gossip handler's check, and must not impersonate another author
+        if !op.verify_signature() {
+            return Err(Error::InvalidSignature);
+        }
+        
+        // Archived and deleted Spaces take no new ops
+        self.space_manager.read().await.check_incoming(&op)?;
+        if self.absences.read().await.get(&op.space_id).is_some_and(|absence| absence.hides(&op)) {
+            return Err(Error::Rejected("Message was posted while this client was removed from the Space".to_string()));
+        }
+        
         // Store the operation
         self.store.put_op(&op)?;
+        self.dedup_cache.write().await.insert(op.op_id);
         
         // Process based on operation type
         match &op.op_type {
@@ -2682,6 +5889,30 @@ impl Client {
                 let mut manager = self.space_manager.write().await;
                 manager.process_update_space_visibility(&op)?;
             }
+            crate::crdt::OpType::UpdateSpaceMetadata(_) => {
+                let mut manager = self.space_manager.write().await;
+                manager.process_update_space_metadata(&op)?;
+            }
+            crate::crdt::OpType::ArchiveSpace => {
+                let mut manager = self.space_manager.write().await;
+                manager.process_archive_space(&op)?;
+            }
+            crate::crdt::OpType::DeleteSpace => {
+                apply_space_deletion(
+                    &self.store,
+                    &self.storage,
+                    &self.space_manager,
+                    &self.channel_manager,
+                    &self.thread_manager,
+                    &self.mls_provider,
+                    &op,
+                ).await?;
+                self.pending_mls_messages.write().await.retain(|pending| pending.space_id != op.space_id);
+            }
+            crate::crdt::OpType::UpdateRole(_) => {
+                let mut manager = self.space_manager.write().await;
+                manager.process_update_role(&op)?;
+            }
             crate::crdt::OpType::CreateInvite(_) => {
                 let mut manager = self.space_manager.write().await;
                 manager.process_create_invite(&op)?;
@@ -2693,6 +5924,17 @@ impl Client {
             crate::crdt::OpType::UseInvite(_) => {
                 let mut manager = self.space_manager.write().await;
                 manager.process_use_invite(&op)?;
+                drop(manager);
+                
+                // The joiner can't decrypt anything until the owner adds them to
+                // MLS, which needs their KeyPackage from the DHT
+                if self.dht_enabled {
+                    match admit_to_space_mls(&self.space_manager, &self.network, &self.dht_writes, &self.mls_provider, self.user_id, op.space_id, op.author).await {
+                        Ok(true) => tracing::info!(joiner = %op.author, "Added invited member to Space MLS group"),
+                        Ok(false) => {}
+                        Err(e) => tracing::warn!(joiner = %op.author, error = %e, "Could not add invited member to Space MLS group"),
+                    }
+                }
             }
             crate::crdt::OpType::RemoveMember(_) => {
                 let mut manager = self.space_manager.write().await;
@@ -2702,23 +5944,97 @@ impl Client {
                 let mut manager = self.channel_manager.write().await;
                 manager.process_create_channel(&op)?;
             }
+            crate::crdt::OpType::UpdateChannel(_) => {
+                let mut manager = self.channel_manager.write().await;
+                manager.process_update_channel(&op)?;
+            }
+            crate::crdt::OpType::DeleteChannel => {
+                apply_channel_deletion(
+                    &self.space_manager,
+                    &self.channel_manager,
+                    &self.thread_manager,
+                    &self.mls_provider,
+                    &op,
+                ).await?;
+            }
+            crate::crdt::OpType::FollowChannel(_) => {
+                let mut manager = self.channel_manager.write().await;
+                manager.process_follow_channel(&op)?;
+            }
+            crate::crdt::OpType::UnfollowChannel(_) => {
+                let mut manager = self.channel_manager.write().await;
+                manager.process_unfollow_channel(&op)?;
+            }
             crate::crdt::OpType::CreateThread(_) => {
                 let mut manager = self.thread_manager.write().await;
                 manager.process_create_thread(&op)?;
+                if let Some(event) = message_event(&manager, &op) {
+                    let _ = self.events.send(event);
+                }
+                // Messages that arrived before their thread
+                for released in manager.take_released() {
+                    if let Some(event) = message_event(&manager, &released) {
+                        let _ = self.events.send(event);
+                    }
+                }
             }
             crate::crdt::OpType::PostMessage(_) => {
                 let mut manager = self.thread_manager.write().await;
                 manager.process_post_message(&op)?;
+                if let Some(event) = message_event(&manager, &op) {
+                    let _ = self.events.send(event);
+                }
+            }
+            crate::crdt::OpType::ForwardMessage(_) => {
+                let mut manager = self.thread_manager.write().await;
+                manager.process_forward_message(&op)?;
+                if let Some(event) = message_event(&manager, &op) {
+                    let _ = self.events.send(event);
+                }
             }
             crate::crdt::OpType::EditMessage(_) => {
                 let mut manager = self.thread_manager.write().await;
                 manager.process_edit_message(&op)?;
+                if let Some(event) = message_event(&manager, &op) {
+                    let _ = self.events.send(event);
+                }
+            }
+            crate::crdt::OpType::RedactMessage(crate::crdt::OpPayload::RedactMessage { message_id }) => {
+                let mut manager = self.thread_manager.write().await;
+                let target = redaction_target(&manager, message_id);
+                manager.process_redact_message(&op)?;
+                if let Some(target) = target {
+                    purge_redacted(&self.store, &self.storage, &manager, target)?;
+                }
+                if let Some(event) = message_event(&manager, &op) {
+                    let _ = self.events.send(event);
+                }
+            }
+            crate::crdt::OpType::AttachLinkPreview(_) => {
+                let mut manager = self.thread_manager.write().await;
+                manager.process_attach_link_preview(&op)?;
+                if let Some(event) = message_event(&manager, &op) {
+                    let _ = self.events.send(event);
+                }
+            }
+            crate::crdt::OpType::UpdateSpaceReactions(_) => {
+                self.space_manager.write().await.process_update_space_reactions(&op)?;
+                self.thread_manager.write().await.set_allowed_reactions(&op);
+            }
+            crate::crdt::OpType::AddReaction(_) => {
+                let mut manager = self.thread_manager.write().await;
+                manager.process_add_reaction(&op)?;
+                if let Some(event) = message_event(&manager, &op) {
+                    let _ = self.events.send(event);
+                }
             }
             _ => {
                 // Other operations can be added as needed
             }
         }
         
+        let space_id = op.space_id;
+        let _ = self.ops.send((op, space_id));
         Ok(())
     }
     
@@ -2749,16 +6065,21 @@ impl Client {
     
     /// Discover available relay servers from DHT
     pub async fn discover_relays(&self) -> Result<Vec<crate::network::relay::RelayInfo>> {
+        self.require_dht()?;
         let mut network = self.network.write().await;
         network.discover_relays().await
     }
     
     /// Connect to a relay server and reserve a relay slot
+    /// 
+    /// A slot is only reserved when the address ends in the relay's
+    /// `/p2p/{peer_id}`; the resulting circuit address replaces any earlier
+    /// one in [`Client::relay_addresses`] and in our invites' relay hints.
     pub async fn connect_to_relay(&self, relay_addr: &str) -> Result<()> {
-        let multiaddr = relay_addr.parse()
-            .map_err(|e| Error::Network(format!("Invalid relay address {}: {}", relay_addr, e)))?;
-        let mut network = self.network.write().await;
-        network.dial(multiaddr).await
+        if let Some(circuit) = reserve_relay_circuit(&self.network, relay_addr).await? {
+            *self.relay_circuits.write().await = vec![circuit];
+        }
+        Ok(())
     }
     
     /// Dial a peer through a relay (for IP privacy)
@@ -2787,6 +6108,11 @@ impl Client {
     /// 
     /// Returns only /p2p-circuit addresses for privacy
     pub async fn relay_addresses(&self) -> Vec<String> {
+        let circuits = self.relay_circuits.read().await.clone();
+        if !circuits.is_empty() {
+            return circuits;
+        }
+        
         let network = self.network.read().await;
         let peer_id = network.local_peer_id();
         
@@ -2797,7 +6123,8 @@ impl Client {
     
     /// Auto-discover and connect to best available relay
     /// 
-    /// Discovers relays from DHT and connects to the one with best reputation
+    /// Discovers relays from DHT and connects to the one with the best
+    /// reputation and the most free circuits, avoiding relays at capacity
     pub async fn auto_connect_relay(&self) -> Result<crate::network::relay::RelayInfo> {
         // Discover relays from DHT
         let relays = self.discover_relays().await?;
@@ -2806,21 +6133,22 @@ impl Client {
             return Err(Error::Network("No relays discovered".to_string()));
         }
         
-        // Sort by reputation (highest first)
-        let mut sorted_relays = relays;
-        sorted_relays.sort_by(|a, b| {
-            b.reputation.partial_cmp(&a.reputation).unwrap_or(std::cmp::Ordering::Equal)
-        });
+        // Best first (saturated relays last)
+        let sorted_relays = crate::network::relay::rank_relays(relays);
         
         // Connect to best relay
         let best_relay = &sorted_relays[0];
         
         // Pick first available address
-        if let Some(addr) = best_relay.addresses.first() {
+        if let Some(addr) = best_relay.dial_address() {
             let addr_str = addr.to_string();
             self.connect_to_relay(&addr_str).await?;
-            println!("✓ Connected to relay: {} (reputation: {:.2})", 
-                best_relay.peer_id, best_relay.reputation);
+            tracing::info!(
+                peer_id = %best_relay.peer_id,
+                reputation = best_relay.reputation,
+                free_circuits = best_relay.free_circuits(),
+                "Connected to relay"
+            );
             
             // Store current relay
             *self.current_relay.write().await = Some(best_relay.clone());
@@ -2849,7 +6177,7 @@ impl Client {
             loop {
                 interval.tick().await;
                 
-                println!("🔄 Relay rotation triggered");
+                tracing::debug!("Relay rotation triggered");
                 
                 // Discover available relays
                 match client_clone.discover_relays().await {
@@ -2860,50 +6188,47 @@ impl Client {
                             current.as_ref().map(|r| r.peer_id.to_string())
                         };
                         
-                        let mut available_relays: Vec<_> = relays.into_iter()
+                        let available_relays: Vec<_> = relays.into_iter()
                             .filter(|r| Some(r.peer_id.to_string()) != current_peer_id)
                             .collect();
                         
                         if available_relays.is_empty() {
-                            println!("⚠️ No alternative relays available for rotation");
+                            tracing::warn!("No alternative relays available for rotation");
                             continue;
                         }
                         
-                        // Sort by reputation
-                        available_relays.sort_by(|a, b| {
-                            b.reputation.partial_cmp(&a.reputation).unwrap_or(std::cmp::Ordering::Equal)
-                        });
+                        // Best first (saturated relays last)
+                        let available_relays = crate::network::relay::rank_relays(available_relays);
                         
                         // Connect to new best relay
                         let new_relay = &available_relays[0];
-                        if let Some(addr) = new_relay.addresses.first() {
+                        if let Some(addr) = new_relay.dial_address() {
                             let addr_str = addr.to_string();
                             match client_clone.connect_to_relay(&addr_str).await {
                                 Ok(_) => {
-                                    println!("✓ Rotated to relay: {} (reputation: {:.2})", 
-                                        new_relay.peer_id, new_relay.reputation);
+                                    tracing::info!(peer_id = %new_relay.peer_id, reputation = new_relay.reputation, "Rotated to relay");
                                     
                                     // Update current relay
                                     *client_clone.current_relay.write().await = Some(new_relay.clone());
                                 }
                                 Err(e) => {
-                                    println!("❌ Relay rotation failed: {}", e);
+                                    tracing::warn!(error = %e, "Relay rotation failed");
                                 }
                             }
                         }
                     }
                     Ok(_) => {
-                        println!("⚠️ No relays discovered during rotation");
+                        tracing::warn!("No relays discovered during rotation");
                     }
                     Err(e) => {
-                        println!("❌ Relay discovery failed during rotation: {}", e);
+                        tracing::warn!(error = %e, "Relay discovery failed during rotation");
                     }
                 }
             }
         });
         
         *self.rotation_task.write().await = Some(task);
-        println!("🔄 Relay rotation started (interval: {:?})", rotation_interval);
+        tracing::debug!("Relay rotation started (interval: {:?})", rotation_interval);
         
         Ok(())
     }
@@ -2913,7 +6238,7 @@ impl Client {
         let mut task = self.rotation_task.write().await;
         if let Some(handle) = task.take() {
             handle.abort();
-            println!("🛑 Relay rotation stopped");
+            tracing::debug!("Relay rotation stopped");
         }
     }
     
@@ -2932,11 +6257,19 @@ impl Client {
         self.gossip_metrics.print_summary().await;
     }
     
+    /// Size and hit/miss counts of the received-op dedup cache
+    /// 
+    /// Every miss cost one store lookup.
+    pub async fn dedup_cache_stats(&self) -> crate::network::DedupCacheStats {
+        self.dedup_cache.read().await.stats()
+    }
+    
     /// Helper to clone necessary fields for rotation task
     fn clone_for_rotation(&self) -> ClientForRotation {
         ClientForRotation {
             network: Arc::clone(&self.network),
             current_relay: Arc::clone(&self.current_relay),
+            relay_circuits: Arc::clone(&self.relay_circuits),
         }
     }
     
@@ -2948,6 +6281,7 @@ impl Client {
     /// Key format: /descord/space/{space_id}/peers
     /// Value: JSON with peer_id and relay_address (no IP exposed)
     pub async fn advertise_space_presence(&self, space_id: SpaceId) -> Result<()> {
+        self.require_dht()?;
         let relay_addrs = self.relay_addresses().await;
         if relay_addrs.is_empty() {
             return Err(Error::Network("No relay address available for advertisement".to_string()));
@@ -2975,7 +6309,7 @@ impl Client {
         let mut network = self.network.write().await;
         network.dht_put(space_key.as_bytes().to_vec(), value_bytes).await?;
         
-        println!("📢 Advertised presence in space {} via DHT", hex::encode(&space_id.0[..8]));
+        tracing::info!(space_id = %space_id.short(), "Advertised presence via DHT");
         Ok(())
     }
     
@@ -2984,6 +6318,7 @@ impl Client {
     /// Queries DHT for other peers advertising themselves in this space
     /// Returns list of (peer_id, relay_address) tuples
     pub async fn discover_space_peers(&self, space_id: SpaceId) -> Result<Vec<SpacePeerInfo>> {
+        self.require_dht()?;
         let space_key = format!("/descord/space/{}/peers", hex::encode(&space_id.0));
         
         let mut network = self.network.write().await;
@@ -3007,7 +6342,7 @@ impl Client {
             }
         }
         
-        println!("🔍 Discovered {} peers in space {}", peers.len(), hex::encode(&space_id.0[..8]));
+        tracing::debug!(space_id = %space_id.short(), peers = peers.len(), "Discovered peers in Space");
         Ok(peers)
     }
     
@@ -3019,13 +6354,13 @@ impl Client {
         let peers = self.discover_space_peers(space_id).await?;
         
         if peers.is_empty() {
-            println!("ℹ️ No peers found in space {}", hex::encode(&space_id.0[..8]));
+            tracing::info!(space_id = %space_id.short(), "No peers found in Space");
             return Ok(0);
         }
         
         let mut connected = 0;
         for peer in &peers {
-            println!("📞 Dialing peer {} via relay...", &peer.peer_id[..16]);
+            tracing::debug!(peer_id = %&peer.peer_id[..16], "Dialing peer via relay");
             
             // Parse relay address to extract relay peer ID
             // Format: /ip4/x.x.x.x/tcp/xxxx/p2p/{relay_id}/p2p-circuit/p2p/{peer_id}
@@ -3039,22 +6374,22 @@ impl Client {
                     
                     match self.dial_peer_via_relay(relay_addr, relay_id, &peer.peer_id).await {
                         Ok(_) => {
-                            println!("✓ Connected to peer {} via relay", &peer.peer_id[..16]);
+                            tracing::info!("Connected to peer {} via relay", &peer.peer_id[..16]);
                             connected += 1;
                         }
                         Err(e) => {
-                            println!("⚠️ Failed to connect to peer {}: {}", &peer.peer_id[..16], e);
+                            tracing::warn!("Failed to connect to peer {}: {}", &peer.peer_id[..16], e);
                         }
                     }
                 } else {
-                    println!("⚠️ Invalid relay address format for peer {}", &peer.peer_id[..16]);
+                    tracing::warn!("Invalid relay address format for peer {}", &peer.peer_id[..16]);
                 }
             } else {
-                println!("⚠️ Cannot parse relay address for peer {}", &peer.peer_id[..16]);
+                tracing::warn!("Cannot parse relay address for peer {}", &peer.peer_id[..16]);
             }
         }
         
-        println!("🌐 Connected to {}/{} peers in space", connected, peers.len());
+        tracing::debug!("Connected to {}/{} peers in space", connected, peers.len());
         Ok(connected)
     }
     
@@ -3112,12 +6447,7 @@ impl Client {
         for space in &mut spaces {
             for channel in &mut space.channels {
                 for thread in &mut channel.threads {
-                    let thread_id_bytes = hex::decode(&thread.id).unwrap_or_default();
-                    if thread_id_bytes.len() == 32 {
-                        let mut id_arr = [0u8; 32];
-                        id_arr.copy_from_slice(&thread_id_bytes);
-                        let thread_id = crate::ThreadId(id_arr);
-                        
+                    if let Ok(thread_id) = crate::ThreadId::from_hex(&thread.id) {
                         let messages = self.list_messages(&thread_id).await;
                         thread.messages = messages
                             .iter()
@@ -3129,10 +6459,17 @@ impl Client {
         }
         
         // Get connected peers
-        let connected_peers = self.get_connected_peers().await;
-        
-        // Mock DHT storage (TODO: implement real DHT query)
-        let dht_storage = vec![];
+        let peers: Vec<crate::dashboard::PeerSnapshot> = self.peer_details().await
+            .iter()
+            .map(crate::dashboard::PeerSnapshot::from_detail)
+            .collect();
+        let connected_peers = peers.iter().map(|peer| peer.peer_id.clone()).collect();
+        
+        // DHT records this client has written
+        let dht_storage = {
+            let writes = self.dht_writes.read().await;
+            writes.iter().map(Self::dht_entry_snapshot).collect()
+        };
         
         // Mock MLS groups (TODO: query actual MLS group state)
         let mls_groups = vec![];
@@ -3144,6 +6481,93 @@ impl Client {
             dht_storage,
             mls_groups,
             connected_peers,
+            peers,
+        }
+    }
+    
+    /// Get the most recent CRDT operations this client has stored
+    /// 
+    /// Returns up to `limit` operations across all spaces, sorted by HLC
+    /// (oldest first), for the dashboard's operation timeline. Only the
+    /// latest `limit` ops of each space are read from storage.
+    pub fn recent_ops(&self, limit: usize) -> Result<Vec<crate::dashboard::CrdtOperationSnapshot>> {
+        let mut ops = self.store.latest_ops_per_space(limit)?;
+        ops.sort_by(|a, b| a.hlc.cmp(&b.hlc).then(a.op_id.0.cmp(&b.op_id.0)));
+        
+        let skip = ops.len().saturating_sub(limit);
+        Ok(ops[skip..]
+            .iter()
+            .map(crate::dashboard::CrdtOperationSnapshot::from_crdt_op)
+            .collect())
+    }
+    
+    /// Unread mentions of and replies to this user, newest first
+    /// 
+    /// Derived from the stored ops across all Spaces and the read markers
+    /// set by [`Client::mark_notification_read`], so the inbox survives a
+    /// restart.
+    pub fn notifications(&self) -> Result<Vec<crate::notification::Notification>> {
+        let ops = self.store.get_all_ops()?;
+        let read = self.store.read_markers()?;
+        Ok(crate::notification::derive_notifications(&ops, &self.user_id, &read))
+    }
+    
+    /// Clear the notification for a message
+    pub fn mark_notification_read(&self, message_id: &MessageId) -> Result<()> {
+        self.store.put_read_marker(message_id)
+    }
+    
+    /// Every indexed message by `user_id` across all threads, newest first
+    /// 
+    /// Reads the storage's per-user message index, e.g. to review a user's
+    /// posts for moderation or to show them on a profile.
+    pub fn user_timeline(&self, user_id: &UserId) -> Result<Vec<crate::storage::MessageIndex>> {
+        Ok(self.storage.get_user_messages(user_id, usize::MAX, None)?)
+    }
+    
+    /// Flush the op store and blob storage to disk
+    /// 
+    /// Run before shutting down, or before inspecting on-disk state.
+    pub fn flush_storage(&self) -> Result<()> {
+        self.store.flush()?;
+        self.storage.flush()?;
+        Ok(())
+    }
+    
+    /// Compact the op store and blob storage, reclaiming space from deleted data
+    pub fn compact_storage(&self) -> Result<()> {
+        self.store.compact();
+        self.storage.compact()?;
+        Ok(())
+    }
+    
+    /// Get local disk usage: blobs, stored operations and tombstones
+    pub fn storage_stats(&self) -> Result<crate::storage::StorageStats> {
+        let mut stats = self.storage.stats()?;
+        for (space_id, (count, bytes)) in self.store.space_op_usage()? {
+            stats.add_ops(space_id, count, bytes);
+        }
+        Ok(stats)
+    }
+    
+    /// Get the DHT entries this client has written for a Space
+    /// 
+    /// Lists the Space metadata key, operation batch index and batches, blob
+    /// index and blobs, plus this user's KeyPackage record, with value sizes.
+    /// Only writes made by this client are known; the DHT itself is not queried.
+    pub async fn dht_storage_snapshot(&self, space_id: &SpaceId) -> Vec<crate::dashboard::DhtEntry> {
+        let writes = self.dht_writes.read().await;
+        writes.iter()
+            .filter(|record| record.space_id.is_none() || record.space_id == Some(*space_id))
+            .map(Self::dht_entry_snapshot)
+            .collect()
+    }
+    
+    fn dht_entry_snapshot(record: &DhtWriteRecord) -> crate::dashboard::DhtEntry {
+        crate::dashboard::DhtEntry {
+            key: hex::encode(&record.key[..record.key.len().min(16)]),
+            value_type: record.value_type.clone(),
+            size_bytes: record.size_bytes,
         }
     }
     
@@ -3177,12 +6601,155 @@ impl Client {
             .map(|peer_id| peer_id.to_string())
             .collect()
     }
+    
+    /// Get connected peers with their connection type (direct or relayed)
+    pub async fn connected_peer_info(&self) -> Vec<crate::network::ConnectedPeer> {
+        let network = self.network.read().await;
+        network.connected_peer_info().await
+    }
+    
+    /// Get connected peers with every open connection's address, whether
+    /// they're reached directly or through a relay, and since when
+    pub async fn peer_details(&self) -> Vec<crate::network::PeerDetail> {
+        let network = self.network.read().await;
+        network.peer_details().await
+    }
+    
+    /// Get the current MLS epoch of a Space
+    /// 
+    /// Returns `None` if this client has no MLS group for the Space
+    /// (lightweight Spaces, or before the Welcome message arrives).
+    pub async fn space_epoch(&self, space_id: &SpaceId) -> Option<EpochId> {
+        let space_manager = self.space_manager.read().await;
+        space_manager.get_mls_group(space_id).map(|group| group.current_epoch())
+    }
+    
+    /// Derive a members-only key for a Space from its MLS exporter secret
+    /// 
+    /// The key belongs to the current epoch: derive it again after an epoch
+    /// change, since members removed by that Commit can no longer compute it.
+    /// Fails for lightweight Spaces and before the Welcome message arrives.
+    pub async fn space_key(&self, space_id: &SpaceId, label: &str) -> Result<[u8; 32]> {
+        let space_manager = self.space_manager.read().await;
+        let group = space_manager.get_mls_group(space_id)
+            .ok_or_else(|| Error::NotFound(format!("No MLS group for Space {}", space_id.short())))?;
+        let provider = self.mls_provider.read().await;
+        group.export_key(label, &provider)
+    }
+    
+    /// Derive a members-only key for a Channel from its MLS exporter secret
+    pub async fn channel_key(&self, channel_id: &ChannelId, label: &str) -> Result<[u8; 32]> {
+        let channel_manager = self.channel_manager.read().await;
+        let group = channel_manager.get_mls_group(channel_id)
+            .ok_or_else(|| Error::NotFound(format!("No MLS group for Channel {}", channel_id.short())))?;
+        let provider = self.mls_provider.read().await;
+        group.export_key(label, &provider)
+    }
+    
+    /// Get the members of a Space's MLS group, as known locally
+    pub async fn mls_members(&self, space_id: &SpaceId) -> Vec<UserId> {
+        let space_manager = self.space_manager.read().await;
+        let mut members: Vec<UserId> = space_manager.get_mls_group(space_id)
+            .map(|group| group.members_with_roles().keys().copied().collect())
+            .unwrap_or_default();
+        members.sort_by_key(|user_id| user_id.0);
+        members
+    }
+    
+    /// Take a snapshot of this client's health metrics
+    ///
+    /// Render it with `ClientMetrics::to_prometheus()` for scraping.
+    pub async fn metrics_snapshot(&self) -> crate::metrics::ClientMetrics {
+        let (dht_puts, dht_gets, connected_peers) = {
+            let network = self.network.read().await;
+            let (puts, gets) = network.dht_request_counts();
+            (puts, gets, network.connected_peers().await.len())
+        };
+        
+        let space_epochs = {
+            let space_manager = self.space_manager.read().await;
+            space_manager.list_spaces().iter()
+                .filter_map(|space| {
+                    space_manager.get_mls_group(&space.id)
+                        .map(|group| (hex::encode(space.id.0), group.current_epoch().0))
+                })
+                .collect()
+        };
+        
+        crate::metrics::ClientMetrics {
+            ops_sent: self.counters.ops_sent(),
+            ops_received: self.counters.ops_received(),
+            decrypt_failures: self.counters.decrypt_failures(),
+            pending_mls_messages: self.pending_mls_messages.read().await.len(),
+            dht_puts,
+            dht_gets,
+            connected_peers,
+            space_epochs,
+        }
+    }
+    
+    /// Issue a webhook token that lets external integrations post into a channel
+    pub async fn create_webhook(&self, channel_id: ChannelId) -> Result<crate::webhook::WebhookToken> {
+        if self.get_channel(&channel_id).await.is_none() {
+            return Err(Error::NotFound(format!("Channel {:?} not found", channel_id)));
+        }
+        
+        let id: [u8; 16] = rand::random();
+        let signature = self.signer.sign(&crate::webhook::WebhookToken::signing_bytes(&id, &channel_id))?;
+        self.webhooks.write().await.insert(id, channel_id);
+        
+        Ok(crate::webhook::WebhookToken { id, channel_id, signature })
+    }
+    
+    /// Revoke a webhook token; returns false if it was not live
+    pub async fn revoke_webhook(&self, token: &crate::webhook::WebhookToken) -> bool {
+        self.webhooks.write().await.remove(&token.id).is_some()
+    }
+    
+    /// Post on behalf of a webhook token
+    /// 
+    /// Posts into the given thread of the token's channel, or starts a new
+    /// thread with the content as its first message.
+    pub async fn post_via_webhook(
+        &self,
+        token: &crate::webhook::WebhookToken,
+        post: crate::webhook::WebhookPost,
+    ) -> Result<Message> {
+        let signing_bytes = crate::webhook::WebhookToken::signing_bytes(&token.id, &token.channel_id);
+        if self.signer.public_key().verify(&signing_bytes, &token.signature).is_err() {
+            return Err(Error::Permission("Invalid webhook token".to_string()));
+        }
+        if self.webhooks.read().await.get(&token.id) != Some(&token.channel_id) {
+            return Err(Error::Permission("Webhook token has been revoked".to_string()));
+        }
+        
+        let channel = self.get_channel(&token.channel_id).await
+            .ok_or_else(|| Error::NotFound(format!("Channel {:?} not found", token.channel_id)))?;
+        
+        match post.parse_thread_id()? {
+            Some(thread_id) => {
+                let in_channel = self.get_thread(&thread_id).await
+                    .is_some_and(|thread| thread.channel_id == channel.id);
+                if !in_channel {
+                    return Err(Error::NotFound(format!("Thread {:?} not found in channel", thread_id)));
+                }
+                let (message, _) = self.post_message(channel.space_id, thread_id, post.content).await?;
+                Ok(message)
+            }
+            None => {
+                let (thread, _) = self.create_thread(channel.space_id, channel.id, post.title, post.content).await?;
+                self.list_messages(&thread.id).await.into_iter().next()
+                    .ok_or_else(|| Error::NotFound(format!("Thread {:?} has no messages", thread.id)))
+            }
+        }
+    }
 }
 
 /// Minimal client clone for rotation background task
 struct ClientForRotation {
     network: Arc<RwLock<NetworkNode>>,
     current_relay: Arc<RwLock<Option<crate::network::relay::RelayInfo>>>,
+    relay_circuits: Arc<RwLock<Vec<String>>>,
 }
 
 impl ClientForRotation {
@@ -3192,11 +6759,28 @@ impl ClientForRotation {
     }
     
     async fn connect_to_relay(&self, relay_addr: &str) -> Result<()> {
-        let multiaddr = relay_addr.parse()
-            .map_err(|e| Error::Network(format!("Invalid relay address {}: {}", relay_addr, e)))?;
-        let mut network = self.network.write().await;
-        network.dial(multiaddr).await
+        // Invite hints follow the new relay from here on
+        if let Some(circuit) = reserve_relay_circuit(&self.network, relay_addr).await? {
+            *self.relay_circuits.write().await = vec![circuit];
+        }
+        Ok(())
+    }
+}
+
+/// Dial a relay and reserve a slot on it if the address names its peer ID
+/// 
+/// Returns the circuit address we can be reached at through the relay.
+async fn reserve_relay_circuit(network: &RwLock<NetworkNode>, relay_addr: &str) -> Result<Option<String>> {
+    let multiaddr: libp2p::Multiaddr = relay_addr.parse()
+        .map_err(|e| Error::Network(format!("Invalid relay address {}: {}", relay_addr, e)))?;
+    let mut network = network.write().await;
+    network.dial(multiaddr.clone()).await?;
+    
+    if !matches!(multiaddr.iter().last(), Some(libp2p::multiaddr::Protocol::P2p(_))) {
+        return Ok(None);
     }
+    network.listen_via_relay(multiaddr).await?;
+    Ok(Some(format!("{}/p2p-circuit/p2p/{}", relay_addr, network.local_peer_id())))
 }
 
 #[cfg(test)]
@@ -3213,6 +6797,7 @@ mod tests {
             storage_path: temp_dir.path().to_path_buf(),
             listen_addrs: vec![],
             bootstrap_peers: vec![],
+            ..Default::default()
         };
         
         let client = Client::new(keypair, config);
@@ -3228,6 +6813,7 @@ mod tests {
             storage_path: temp_dir.path().to_path_buf(),
             listen_addrs: vec![],
             bootstrap_peers: vec![],
+            ..Default::default()
         };
         
         let client = Client::new(keypair, config).unwrap();
@@ -3250,6 +6836,7 @@ mod tests {
             storage_path: temp_dir.path().to_path_buf(),
             listen_addrs: vec![],
             bootstrap_peers: vec![],
+            ..Default::default()
         };
         
         let client = Client::new(keypair, config).unwrap();
@@ -3275,6 +6862,7 @@ mod tests {
             storage_path: temp_dir.path().to_path_buf(),
             listen_addrs: vec![],
             bootstrap_peers: vec![],
+            ..Default::default()
         };
         
         let client = Client::new(keypair, config).unwrap();
@@ -3305,6 +6893,7 @@ mod tests {
             storage_path: temp_dir.path().to_path_buf(),
             listen_addrs: vec![],
             bootstrap_peers: vec![],
+            ..Default::default()
         };
         
         let client = Client::new(keypair, config).unwrap();
@@ -3326,4 +6915,29 @@ mod tests {
         let retrieved = client.retrieve_blob(&metadata.hash).await.unwrap();
         assert_eq!(&retrieved[..], &data[..]);
     }
+    
+    #[test]
+    fn test_dht_writes_keep_one_record_per_key_up_to_the_cap() {
+        let write = |key: u32, size_bytes| DhtWriteRecord {
+            key: key.to_be_bytes().to_vec(),
+            space_id: None,
+            value_type: "operation_batch".to_string(),
+            size_bytes,
+        };
+        let mut writes = DhtWrites::default();
+        for key in 0..MAX_DHT_WRITES as u32 {
+            writes.record(write(key, 1));
+        }
+        
+        // Rewriting a key replaces its record and makes it the newest
+        writes.record(write(0, 2));
+        assert_eq!(writes.iter().count(), MAX_DHT_WRITES);
+        assert_eq!(writes.iter().last().map(|record| record.size_bytes), Some(2));
+        
+        // Past the cap the least recently written key goes
+        writes.record(write(MAX_DHT_WRITES as u32, 1));
+        assert_eq!(writes.iter().count(), MAX_DHT_WRITES);
+        assert!(writes.iter().all(|record| record.key != 1u32.to_be_bytes()));
+        assert!(writes.iter().any(|record| record.key == 0u32.to_be_bytes()));
+    }
 }
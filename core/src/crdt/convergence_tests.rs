@@ -31,6 +31,7 @@ fn arb_crdt_op() -> impl Strategy<Value = CrdtOp> {
                 op_type: OpType::PostMessage(OpPayload::PostMessage {
                     message_id: MessageId::new(),
                     content,
+                    quote: None,
                 }),
                 prev_ops: vec![],
                 author: user_id,
@@ -44,8 +45,7 @@ fn arb_crdt_op() -> impl Strategy<Value = CrdtOp> {
             };
             
             // Sign the operation
-            let signing_bytes = op.signing_bytes();
-            op.signature = Signature(keypair.sign(&signing_bytes).0);
+            op.sign(&keypair).unwrap();
             
             op
         })
@@ -214,6 +214,7 @@ mod unit_tests {
             op_type: OpType::PostMessage(OpPayload::PostMessage {
                 message_id: MessageId::new(),
                 content: "Test".to_string(),
+                quote: None,
             }),
             prev_ops: vec![],
             author: keypair.user_id(),
@@ -224,8 +225,7 @@ mod unit_tests {
         };
         
         // Sign the operation
-        let signing_bytes = op.signing_bytes();
-        op.signature = Signature(keypair.sign(&signing_bytes).0);
+        op.sign(&keypair).unwrap();
         
         assert!(replica.apply(op));
         assert_eq!(replica.message_count, 1);
@@ -244,6 +244,7 @@ mod unit_tests {
             op_type: OpType::PostMessage(OpPayload::PostMessage {
                 message_id: MessageId::new(),
                 content: "Test".to_string(),
+                quote: None,
             }),
             prev_ops: vec![],
             author: keypair.user_id(),
@@ -254,8 +255,7 @@ mod unit_tests {
         };
         
         // Sign the operation
-        let signing_bytes = op.signing_bytes();
-        op.signature = Signature(keypair.sign(&signing_bytes).0);
+        op.sign(&keypair).unwrap();
         
         assert!(replica.apply(op.clone()));
         assert!(!replica.apply(op)); // Duplicate should be rejected
@@ -4,7 +4,8 @@
 //! Operations are batched by Space and encrypted before storage.
 
 use crate::crdt::CrdtOp;
-use crate::types::SpaceId;
+use crate::mls::SpaceKeys;
+use crate::types::{EpochId, SpaceId};
 use crate::{Error, Result};
 use minicbor::{Decode, Encode};
 use serde::{Deserialize, Serialize};
@@ -14,6 +15,15 @@ use aes_gcm::{
     Aes256Gcm, Nonce,
 };
 
+/// Number of operations a batch is filled to before a new one is started
+///
+/// Small puts are merged into the tail batch until it reaches this size, so
+/// the index grows by one sequence per `BATCH_TARGET_OPS` operations.
+pub const BATCH_TARGET_OPS: usize = 64;
+
+/// Sequence count above which a sparsely filled index is compacted
+pub const MAX_INDEX_BATCHES: usize = 32;
+
 /// A batch of CRDT operations for a Space
 /// 
 /// Operations are batched to reduce DHT storage overhead.
@@ -80,7 +90,9 @@ impl OperationBatch {
 /// Encrypted operation batch for DHT storage
 /// 
 /// Operations contain sensitive metadata (who did what, when).
-/// We encrypt them with a key derived from the Space ID.
+/// MLS Spaces seal them under the epoch's exporter key ([`Self::seal`]) so
+/// only members can read them; Lightweight Spaces fall back to a key
+/// derived from the Space ID.
 #[derive(Clone, Debug)]
 pub struct EncryptedOperationBatch {
     /// Space ID (used to derive decryption key)
@@ -94,17 +106,48 @@ pub struct EncryptedOperationBatch {
     
     /// Encrypted batch data
     pub ciphertext: Vec<u8>,
+    
+    /// MLS epoch whose key sealed the batch, or `None` for the Space ID key
+    pub key_epoch: Option<EpochId>,
 }
 
 impl EncryptedOperationBatch {
+    /// Seal a batch for a Space's members under its current epoch
+    /// 
+    /// Lightweight Spaces (`SpaceKeys::Public`) use the Space ID key.
+    pub fn seal(batch: &OperationBatch, keys: &SpaceKeys) -> Result<Self> {
+        match keys.sealing()? {
+            Some((epoch, key)) => {
+                let mut sealed = Self::encrypt_with_key(batch, &key)?;
+                sealed.key_epoch = Some(epoch);
+                Ok(sealed)
+            }
+            None => Self::encrypt(batch),
+        }
+    }
+    
+    /// Open a batch with the key of the epoch it names
+    pub fn open(&self, keys: &SpaceKeys) -> Result<OperationBatch> {
+        match self.key_epoch {
+            Some(epoch) => self.decrypt_with_key(&keys.opening(epoch)?),
+            None => self.decrypt(),
+        }
+    }
+    
     /// Encrypt an operation batch
     pub fn encrypt(batch: &OperationBatch) -> Result<Self> {
+        // Derive encryption key from Space ID
+        Self::encrypt_with_key(batch, &Self::derive_key(&batch.space_id))
+    }
+    
+    /// Encrypt an operation batch under a caller-supplied key
+    /// 
+    /// Used with a key from the Space's MLS exporter
+    /// ([`crate::mls::MlsGroup::export_key`]) so only members can decrypt it.
+    pub fn encrypt_with_key(batch: &OperationBatch, key: &[u8; 32]) -> Result<Self> {
         // Serialize batch
         let plaintext = batch.to_bytes()?;
         
-        // Derive encryption key from Space ID
-        let key = Self::derive_key(&batch.space_id);
-        
         // Generate random nonce
         let mut nonce_bytes = [0u8; 12];
         use rand::RngCore;
@@ -112,7 +155,7 @@ impl EncryptedOperationBatch {
         let nonce = Nonce::from_slice(&nonce_bytes);
         
         // Encrypt
-        let cipher = Aes256Gcm::new_from_slice(&key)
+        let cipher = Aes256Gcm::new_from_slice(key)
             .map_err(|e| Error::Crypto(format!("Failed to create cipher: {}", e)))?;
         
         let ciphertext = cipher.encrypt(nonce, plaintext.as_ref())
@@ -123,16 +166,20 @@ impl EncryptedOperationBatch {
             sequence: batch.sequence,
             nonce: nonce_bytes,
             ciphertext,
+            key_epoch: None,
         })
     }
     
     /// Decrypt an operation batch
     pub fn decrypt(&self) -> Result<OperationBatch> {
         // Derive decryption key from Space ID
-        let key = Self::derive_key(&self.space_id);
-        
+        self.decrypt_with_key(&Self::derive_key(&self.space_id))
+    }
+    
+    /// Decrypt an operation batch sealed with [`Self::encrypt_with_key`]
+    pub fn decrypt_with_key(&self, key: &[u8; 32]) -> Result<OperationBatch> {
         // Decrypt
-        let cipher = Aes256Gcm::new_from_slice(&key)
+        let cipher = Aes256Gcm::new_from_slice(key)
             .map_err(|e| Error::Crypto(format!("Failed to create cipher: {}", e)))?;
         
         let nonce = Nonce::from_slice(&self.nonce);
@@ -189,6 +236,11 @@ impl EncryptedOperationBatch {
         buf.extend_from_slice(&(self.ciphertext.len() as u32).to_le_bytes());
         buf.extend_from_slice(&self.ciphertext);
         
+        // Write the sealing epoch (8 bytes), absent for Space ID keyed batches
+        if let Some(epoch) = self.key_epoch {
+            buf.extend_from_slice(&epoch.0.to_le_bytes());
+        }
+        
         Ok(buf)
     }
     
@@ -219,11 +271,22 @@ impl EncryptedOperationBatch {
         }
         let ciphertext = bytes[52..52 + ciphertext_len].to_vec();
         
+        // Read the sealing epoch, if any (8 bytes)
+        let key_epoch = match &bytes[52 + ciphertext_len..] {
+            [] => None,
+            epoch => {
+                let epoch: [u8; 8] = epoch.try_into()
+                    .map_err(|_| Error::Serialization("Malformed batch epoch".to_string()))?;
+                Some(EpochId(u64::from_le_bytes(epoch)))
+            }
+        };
+        
         Ok(Self {
             space_id,
             sequence,
             nonce,
             ciphertext,
+            key_epoch,
         })
     }
 }
@@ -277,6 +340,76 @@ impl OperationBatchIndex {
             .as_secs();
     }
     
+    /// Fold new operations into the log, returning the batch to (re)write
+    ///
+    /// `tail` is the batch at the last indexed sequence, as fetched from the
+    /// DHT. If it has room the operations are merged into it and the same
+    /// sequence is rewritten; otherwise (or if the tail could not be fetched)
+    /// a new sequence is started. The index is updated either way.
+    pub fn append(&mut self, tail: Option<OperationBatch>, operations: Vec<CrdtOp>) -> OperationBatch {
+        let op_count = operations.len() as u32;
+        let last = self.batch_sequences.last().copied();
+
+        let batch = match tail {
+            Some(tail) if Some(tail.sequence) == last
+                && tail.operations.len() + operations.len() <= BATCH_TARGET_OPS =>
+            {
+                let mut merged = tail.operations;
+                merged.extend(operations);
+                OperationBatch::new(self.space_id, merged, tail.sequence)
+            }
+            _ => OperationBatch::new(self.space_id, operations, last.unwrap_or(0) + 1),
+        };
+
+        self.add_batch(batch.sequence, op_count);
+        batch
+    }
+
+    /// Whether the index holds many batches that are mostly empty
+    ///
+    /// True for indices written one operation per batch, before tail merging.
+    pub fn needs_compaction(&self) -> bool {
+        let batches = self.batch_sequences.len();
+        batches > MAX_INDEX_BATCHES
+            && (self.total_operations as usize) < batches * BATCH_TARGET_OPS / 2
+    }
+
+    /// Re-chunk every batch into full batches under fresh sequences
+    ///
+    /// `batches` must be all batches of the index, in index order; the caller
+    /// should not compact when any of them could not be fetched, or their
+    /// operations would be dropped from the log. New sequences start after the
+    /// current last one, so readers of the old index still find the old
+    /// batches until the new index replaces it.
+    pub fn compact(&mut self, batches: Vec<OperationBatch>) -> Vec<OperationBatch> {
+        let mut next = self.batch_sequences.last().copied().unwrap_or(0) + 1;
+        let operations: Vec<CrdtOp> = batches.into_iter().flat_map(|batch| batch.operations).collect();
+
+        let compacted: Vec<OperationBatch> = operations
+            .chunks(BATCH_TARGET_OPS)
+            .map(|chunk| {
+                let batch = OperationBatch::new(self.space_id, chunk.to_vec(), next);
+                next += 1;
+                batch
+            })
+            .collect();
+
+        self.batch_sequences = compacted.iter().map(|batch| batch.sequence).collect();
+        self.total_operations = operations.len() as u64;
+        self.last_updated = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_secs();
+        compacted
+    }
+
+    /// Sequences at index positions `range`, clamped to the batches present
+    pub fn sequences_in(&self, range: std::ops::Range<usize>) -> &[u32] {
+        let end = range.end.min(self.batch_sequences.len());
+        let start = range.start.min(end);
+        &self.batch_sequences[start..end]
+    }
+
     /// Get DHT storage key for this index
     pub fn dht_key(&self) -> Vec<u8> {
         Self::compute_dht_key(&self.space_id)
@@ -372,6 +505,26 @@ mod tests {
         assert_eq!(decoded.sequence, encrypted.sequence);
         assert_eq!(decoded.nonce, encrypted.nonce);
         assert_eq!(decoded.ciphertext, encrypted.ciphertext);
+        assert_eq!(decoded.key_epoch, None);
+    }
+    
+    #[test]
+    fn test_sealed_batch_names_its_epoch() {
+        let space_id = SpaceId::new();
+        let batch = OperationBatch::new(space_id, vec![create_test_op(1000)], 0);
+        let keys = SpaceKeys::Epochs([(EpochId(0), [1u8; 32]), (EpochId(1), [2u8; 32])].into());
+        
+        let sealed = EncryptedOperationBatch::seal(&batch, &keys).unwrap();
+        assert_eq!(sealed.key_epoch, Some(EpochId(1)));
+        let decoded = EncryptedOperationBatch::from_bytes(&sealed.to_bytes().unwrap()).unwrap();
+        assert_eq!(decoded.key_epoch, Some(EpochId(1)));
+        assert_eq!(decoded.open(&keys).unwrap().operations.len(), 1);
+        
+        // Neither an older epoch's key nor the Space ID opens it
+        let stale = SpaceKeys::Epochs([(EpochId(0), [1u8; 32])].into());
+        assert!(decoded.open(&stale).is_err());
+        assert!(decoded.decrypt().is_err());
+        assert!(decoded.open(&SpaceKeys::Public).is_err());
     }
     
     #[test]
@@ -11,6 +11,7 @@
 use crate::crdt::CrdtOp;
 use crate::types::*;
 use std::collections::{HashMap, HashSet, VecDeque};
+use std::time::Duration;
 
 /// Maximum number of operations to buffer before rejecting new ones
 const MAX_BUFFERED_OPS: usize = 10000;
@@ -18,6 +19,22 @@ const MAX_BUFFERED_OPS: usize = 10000;
 /// Maximum time (in seconds) an operation can stay buffered
 const MAX_BUFFER_TIME_SECS: u64 = 300; // 5 minutes
 
+/// How long an operation may wait on its dependencies before it counts as stuck
+pub const OP_STUCK_AFTER: Duration = Duration::from_secs(30);
+
+/// An operation held back because something it depends on hasn't arrived
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StuckOp {
+    /// The buffered operation
+    pub op_id: OpId,
+    /// Space the operation belongs to
+    pub space_id: SpaceId,
+    /// Operations it still waits on; empty when waiting for an MLS epoch
+    pub waiting_on: Vec<OpId>,
+    /// Time since the operation was buffered
+    pub age: Duration,
+}
+
 /// Holdback queue for operations awaiting dependencies
 pub struct HoldbackQueue {
     /// Operations indexed by op_id for quick lookup
@@ -263,6 +280,24 @@ impl HoldbackQueue {
         self.buffered_ops.is_empty()
     }
 
+    /// Every buffered operation with what it waits on, oldest first
+    pub fn stuck_ops(&self, current_time: u64) -> Vec<StuckOp> {
+        let mut stuck: Vec<StuckOp> = self.buffered_ops.values()
+            .map(|buffered| {
+                let mut waiting_on: Vec<OpId> = buffered.missing_deps.iter().copied().collect();
+                waiting_on.sort_by_key(|op_id| op_id.0);
+                StuckOp {
+                    op_id: buffered.op.op_id,
+                    space_id: buffered.op.space_id,
+                    waiting_on,
+                    age: Duration::from_secs(current_time.saturating_sub(buffered.buffered_at)),
+                }
+            })
+            .collect();
+        stuck.sort_by(|a, b| b.age.cmp(&a.age).then(a.op_id.0.cmp(&b.op_id.0)));
+        stuck
+    }
+
     /// Get all buffered operations (for debugging)
     pub fn buffered_ops(&self) -> Vec<&CrdtOp> {
         self.buffered_ops.values().map(|b| &b.op).collect()
@@ -290,6 +325,7 @@ mod tests {
             op_type: OpType::PostMessage(OpPayload::PostMessage {
                 message_id: MessageId::new(),
                 content: "Test message".to_string(),
+                quote: None,
             }),
             prev_ops,
             author: UserId([1u8; 32]),
@@ -364,6 +400,27 @@ mod tests {
         assert_eq!(queue.len(), 0);
     }
 
+    #[test]
+    fn test_stuck_ops() {
+        let mut queue = HoldbackQueue::new();
+        
+        let dep_id = OpId(Uuid::new_v4());
+        let op_id = OpId(Uuid::new_v4());
+        let space_id = SpaceId::new();
+        
+        queue.buffer(create_test_op(op_id, space_id, vec![dep_id]), vec![dep_id], 1000).unwrap();
+        
+        let stuck = queue.stuck_ops(1042);
+        assert_eq!(stuck.len(), 1);
+        assert_eq!(stuck[0].op_id, op_id);
+        assert_eq!(stuck[0].space_id, space_id);
+        assert_eq!(stuck[0].waiting_on, vec![dep_id]);
+        assert_eq!(stuck[0].age, Duration::from_secs(42));
+        
+        queue.on_op_accepted(dep_id);
+        assert!(queue.stuck_ops(1042).is_empty());
+    }
+
     #[test]
     fn test_multiple_dependencies() {
         let mut queue = HoldbackQueue::new();
@@ -18,6 +18,6 @@ mod convergence_tests;
 
 pub use hlc::Hlc;
 pub use ops::{CrdtOp, OpPayload, OpType};
-pub use validator::{OpValidator, ValidationResult, RejectionReason};
-pub use holdback::HoldbackQueue;
+pub use validator::{OpValidator, ValidationResult, RejectionReason, OpLimits, RateLimit};
+pub use holdback::{HoldbackQueue, StuckOp};
 pub use dht_storage::{OperationBatch, EncryptedOperationBatch, OperationBatchIndex};
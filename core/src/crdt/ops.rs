@@ -4,8 +4,11 @@
 
 use crate::types::*;
 use crate::crdt::Hlc;
+use crate::forum::link_preview::LinkPreview;
+use crate::forum::thread::QuotedMessage;
 use minicbor::{Decode, Encode};
 use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
 use std::collections::HashSet;
 
 /// CRDT operation envelope
@@ -62,10 +65,26 @@ pub struct CrdtOp {
     pub signature: Signature,
 }
 
+/// Every signed field of a [`CrdtOp`] except the id, hashed into the id
+#[derive(Encode)]
+struct ContentData<'a> {
+    #[n(0)] space_id: &'a SpaceId,
+    #[n(1)] channel_id: &'a Option<ChannelId>,
+    #[n(2)] thread_id: &'a Option<ThreadId>,
+    #[n(3)] op_type: &'a OpType,
+    #[n(4)] prev_ops: &'a Vec<OpId>,
+    #[n(5)] author: &'a UserId,
+    #[n(6)] epoch: &'a EpochId,
+    #[n(7)] hlc: &'a Hlc,
+    #[n(8)] timestamp: u64,
+}
+
 impl CrdtOp {
     /// Get the canonical bytes for signing
     ///
-    /// This serializes all fields except the signature itself
+    /// This serializes all fields except the signature itself. The bytes
+    /// are always produced by re-encoding the decoded fields, never taken
+    /// from the wire, so every encoding of the same op signs the same bytes.
     pub fn signing_bytes(&self) -> Vec<u8> {
         // Create a temporary struct without signature for encoding
         #[derive(Encode)]
@@ -98,9 +117,81 @@ impl CrdtOp {
         minicbor::to_vec(&data).expect("CBOR encoding should not fail")
     }
 
+    /// Derive the op id from the op's content
+    ///
+    /// Hashes the canonical encoding of every signed field except the id
+    /// itself, so two ops with the same id always carry the same content.
+    pub fn content_id(&self) -> OpId {
+        let content = minicbor::to_vec(self.content_data()).expect("CBOR encoding should not fail");
+
+        let mut hasher = Sha256::new();
+        hasher.update(b"OP_V1:");
+        hasher.update(&content);
+
+        let hash = hasher.finalize();
+        let mut bytes = [0u8; 16];
+        bytes.copy_from_slice(&hash[..16]);
+        OpId(uuid::Uuid::from_bytes(bytes))
+    }
+
+    /// Set the content-derived op id and sign the op with `signer`
+    ///
+    /// Call this once all other fields are final; changing any of them
+    /// afterwards invalidates both the id and the signature. Fails if
+    /// `signer` is not the op's author, since peers would reject the op.
+    pub fn sign(&mut self, signer: &dyn crate::crypto::signing::Signer) -> crate::Result<()> {
+        if signer.user_id() != self.author {
+            return Err(crate::Error::InvalidOperation("Op author does not match the signing key".to_string()));
+        }
+        self.op_id = self.content_id();
+        let signing_bytes = self.signing_bytes();
+        self.signature = Signature(signer.sign(&signing_bytes)?.0);
+        Ok(())
+    }
+
+    fn content_data(&self) -> ContentData<'_> {
+        ContentData {
+            space_id: &self.space_id,
+            channel_id: &self.channel_id,
+            thread_id: &self.thread_id,
+            op_type: &self.op_type,
+            prev_ops: &self.prev_ops,
+            author: &self.author,
+            epoch: &self.epoch,
+            hlc: &self.hlc,
+            timestamp: self.timestamp,
+        }
+    }
+
+    /// Canonical CBOR encoding of the whole op, for the wire and the DHT
+    ///
+    /// minicbor's derived encoding is deterministic for ops: fields in index
+    /// order, definite lengths and shortest-form integers, and no maps whose
+    /// order could vary. So there is exactly one valid encoding per op.
+    pub fn to_canonical_bytes(&self) -> Vec<u8> {
+        minicbor::to_vec(self).expect("CBOR encoding should not fail")
+    }
+
+    /// Decode an op received from a peer, rejecting non-canonical encodings
+    ///
+    /// CBOR allows several encodings of the same value (indefinite lengths,
+    /// over-long integers). They would all decode and verify, yet hash and
+    /// dedup differently, so only the canonical encoding is accepted.
+    pub fn from_canonical_bytes(bytes: &[u8]) -> crate::Result<Self> {
+        let op: Self = minicbor::decode(bytes)
+            .map_err(|e| crate::Error::Serialization(format!("Failed to decode operation: {}", e)))?;
+        if op.to_canonical_bytes() != bytes {
+            return Err(crate::Error::Serialization("Operation is not canonically encoded".to_string()));
+        }
+        Ok(op)
+    }
+
     /// Verify the cryptographic signature on this operation
     /// 
-    /// Validates that the operation was signed by the claimed author
+    /// Validates that the operation was signed by the claimed author, over
+    /// the canonical encoding from [`CrdtOp::signing_bytes`]. The author's
+    /// `UserId` is their public key, so an op signed with one key while
+    /// claiming another user's id fails here.
     pub fn verify_signature(&self) -> bool {
         use ed25519_dalek::Verifier;
         
@@ -216,6 +307,54 @@ pub enum OpType {
     /// Use an invite (join via invite)
     #[n(17)]
     UseInvite(#[n(0)] OpPayload),
+
+    /// Update space tags and category
+    #[n(18)]
+    UpdateSpaceMetadata(#[n(0)] OpPayload),
+
+    /// Update a role's display settings
+    #[n(19)]
+    UpdateRole(#[n(0)] OpPayload),
+
+    /// Attach a link preview to a message
+    #[n(20)]
+    AttachLinkPreview(#[n(0)] OpPayload),
+
+    /// Forward a message into another thread
+    #[n(21)]
+    ForwardMessage(#[n(0)] OpPayload),
+
+    /// Redact a message: purge its content everywhere, not just hide it
+    #[n(22)]
+    RedactMessage(#[n(0)] OpPayload),
+
+    /// Follow another channel's announcements into this one
+    #[n(23)]
+    FollowChannel(#[n(0)] OpPayload),
+
+    /// Stop following another channel
+    #[n(24)]
+    UnfollowChannel(#[n(0)] OpPayload),
+
+    /// Delete a channel along with its threads
+    #[n(25)]
+    DeleteChannel,
+
+    /// Make a space read-only
+    #[n(26)]
+    ArchiveSpace,
+
+    /// Delete a space, tombstoning its ID
+    #[n(27)]
+    DeleteSpace,
+
+    /// Restrict which reactions may be used in a space
+    #[n(28)]
+    UpdateSpaceReactions(#[n(0)] OpPayload),
+
+    /// React to a message
+    #[n(29)]
+    AddReaction(#[n(0)] OpPayload),
 }
 
 /// Operation payload (type-specific data)
@@ -253,6 +392,9 @@ pub enum OpPayload {
         name: Option<String>,
         #[n(1)]
         description: Option<String>,
+        /// Mark the channel NSFW / age-restricted (None = unchanged)
+        #[n(2)]
+        nsfw: Option<bool>,
     },
 
     /// Create thread payload
@@ -273,6 +415,9 @@ pub enum OpPayload {
         message_id: MessageId,
         #[n(1)]
         content: String,
+        /// Message quoted inline, if any
+        #[n(2)]
+        quote: Option<QuotedMessage>,
     },
 
     /// Edit message payload
@@ -372,6 +517,92 @@ pub enum OpPayload {
         invite_id: InviteId,
         #[n(1)]
         code: String,
+        /// Role granted by the invite, applied together with the join
+        #[n(2)]
+        role: Option<RoleId>,
+    },
+
+    /// Update space metadata payload (replaces tags and category)
+    #[n(17)]
+    UpdateSpaceMetadata {
+        #[n(0)]
+        tags: Vec<String>,
+        #[n(1)]
+        category: Option<String>,
+    },
+
+    /// Update role payload (replaces color and hoist flag)
+    #[n(18)]
+    UpdateRole {
+        #[n(0)]
+        role_id: RoleId,
+        #[n(1)]
+        color: Option<u32>,
+        #[n(2)]
+        hoisted: bool,
+    },
+
+    /// Attach link preview payload
+    #[n(19)]
+    AttachLinkPreview {
+        #[n(0)]
+        message_id: MessageId,
+        #[n(1)]
+        preview: LinkPreview,
+    },
+
+    /// Forward message payload (copy of the original plus its attribution)
+    #[n(20)]
+    ForwardMessage {
+        #[n(0)]
+        message_id: MessageId,
+        #[n(1)]
+        content: String,
+        #[n(2)]
+        forwarded_from: MessageId,
+        #[n(3)]
+        original_author: UserId,
+        #[n(4)]
+        source_thread: ThreadId,
+    },
+
+    /// Redact message payload
+    #[n(21)]
+    RedactMessage {
+        #[n(0)]
+        message_id: MessageId,
+    },
+
+    /// Follow channel payload (the op's channel is the follower)
+    #[n(22)]
+    FollowChannel {
+        #[n(0)]
+        source_channel: ChannelId,
+        #[n(1)]
+        target_thread: ThreadId,
+    },
+
+    /// Unfollow channel payload
+    #[n(23)]
+    UnfollowChannel {
+        #[n(0)]
+        source_channel: ChannelId,
+    },
+
+    /// Space reaction allowlist payload (None lifts the restriction)
+    #[n(24)]
+    UpdateSpaceReactions {
+        #[n(0)]
+        allowed: Option<Vec<String>>,
+    },
+
+    /// Add reaction payload (a Unicode emoji or a custom `:name:`)
+    #[n(25)]
+    AddReaction {
+        #[n(0)]
+        message_id: MessageId,
+        #[n(1)]
+        emoji: String,
     },
 }
 
@@ -413,6 +644,7 @@ mod tests {
             op_type: OpType::PostMessage(OpPayload::PostMessage {
                 message_id: MessageId::new(),
                 content: "Hello world".to_string(),
+                quote: None,
             }),
             prev_ops: vec![],
             author: UserId([1u8; 32]),
@@ -428,4 +660,46 @@ mod tests {
         let decoded: CrdtOp = minicbor::decode(&buf).expect("decode failed");
         assert_eq!(op, decoded);
     }
+
+    #[test]
+    fn test_non_canonical_encoding_is_rejected() {
+        let keypair = crate::crypto::signing::Keypair::generate();
+        let mut op = CrdtOp {
+            op_id: OpId(Uuid::new_v4()),
+            space_id: SpaceId::new(),
+            channel_id: None,
+            thread_id: None,
+            op_type: OpType::PostMessage(OpPayload::PostMessage {
+                message_id: MessageId::new(),
+                content: "Hello world".to_string(),
+                quote: None,
+            }),
+            prev_ops: vec![],
+            author: keypair.user_id(),
+            epoch: EpochId(5),
+            hlc: Hlc { wall_time: 2000, logical: 3 },
+            timestamp: 2000,
+            signature: Signature([0u8; 64]),
+        };
+        op.signature = Signature(keypair.sign(&op.signing_bytes()).0);
+
+        let canonical = op.to_canonical_bytes();
+        assert_eq!(CrdtOp::from_canonical_bytes(&canonical).unwrap(), op);
+
+        // Same op with the outer array re-encoded as indefinite-length
+        assert_eq!(canonical[0] & 0xe0, 0x80, "op encodes as a definite array");
+        let mut reencoded = vec![0x9f];
+        reencoded.extend_from_slice(&canonical[1..]);
+        reencoded.push(0xff);
+        assert_ne!(reencoded, canonical);
+
+        // It decodes to the same op, with the same op_id and a valid signature...
+        let lenient: CrdtOp = minicbor::decode(&reencoded).unwrap();
+        assert_eq!(lenient, op);
+        assert!(lenient.verify_signature());
+        assert_eq!(lenient.signing_bytes(), op.signing_bytes());
+
+        // ...but is refused off the wire
+        assert!(CrdtOp::from_canonical_bytes(&reencoded).is_err());
+    }
 }
@@ -4,11 +4,13 @@
 //! - Signature verification
 //! - Causality checking (prev_ops dependencies)
 //! - Membership/epoch validation
+//! - Size limits
 //! - Deterministic conflict resolution
 
-use crate::crdt::{CrdtOp, OpType, OpPayload};
+use crate::crdt::{CrdtOp, OpType, OpPayload, Hlc};
 use crate::types::*;
-use std::collections::{HashMap, HashSet};
+use crate::{Error, Result};
+use std::collections::{BTreeMap, HashMap, HashSet};
 
 /// Validation result for a CRDT operation
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -36,6 +38,47 @@ pub enum RejectionReason {
     Duplicate,
     /// Invalid operation content
     InvalidContent(String),
+    /// Encoded operation exceeds the maximum size
+    TooLarge,
+    /// Message content exceeds the maximum message size
+    MessageTooLarge,
+    /// `op_id` doesn't match the id derived from the op's content
+    IdMismatch,
+    /// Reaction is not in the Space's allowlist
+    ReactionNotAllowed,
+}
+
+/// Per-author sliding-window rate limit
+///
+/// Enforced on operations received over gossip, by local receive time (see
+/// [`crate::network::OpThrottle`]); it never decides whether an op is valid.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RateLimit {
+    /// Most operations one author may send us within the window
+    pub max_ops: usize,
+    /// Window length in milliseconds
+    pub window_ms: u64,
+}
+
+/// Size limits enforced on every operation, and the receive rate limit
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct OpLimits {
+    /// Maximum CBOR-encoded size of an operation, in bytes
+    pub max_op_bytes: usize,
+    /// Per-author limit on received ops (None disables it); local ops are exempt
+    pub rate_limit: Option<RateLimit>,
+    /// Maximum message content size, in bytes (`ClientConfig::max_message_bytes`)
+    pub max_message_bytes: usize,
+}
+
+impl Default for OpLimits {
+    fn default() -> Self {
+        Self {
+            max_op_bytes: 256 * 1024,
+            rate_limit: Some(RateLimit { max_ops: 1000, window_ms: 10_000 }),
+            max_message_bytes: 16 * 1024,
+        }
+    }
 }
 
 /// CRDT operation validator
@@ -46,50 +89,178 @@ pub struct OpValidator {
     /// Current MLS epoch for each space
     space_epochs: HashMap<SpaceId, EpochId>,
     
-    /// Membership state: Space -> User -> (Epoch joined, Epoch removed)
-    /// Epoch removed is None if still a member
+    /// Membership history: Space -> User -> periods of membership
     memberships: HashMap<SpaceId, HashMap<UserId, MembershipRecord>>,
     
     /// Operations we've already seen (for deduplication)
     seen_ops: HashSet<OpId>,
+
+    /// Size limits
+    limits: OpLimits,
+
+    /// Reaction allowlist history per Space, by the position of the op that
+    /// set it (None lifts the restriction)
+    allowed_reactions: HashMap<SpaceId, BTreeMap<OpPoint, Option<Vec<String>>>>,
+
+    /// The winning `CreateSpace` claim of each Space
+    origins: HashMap<SpaceId, OriginClaim>,
 }
 
-/// Membership record for epoch-based validation
+/// A `CreateSpace` claim; the lowest HLC (then op ID) owns the Space
+#[derive(Debug, Clone, Copy)]
+struct OriginClaim {
+    hlc: Hlc,
+    op_id: OpId,
+    author: UserId,
+}
+
+impl OriginClaim {
+    fn of(op: &CrdtOp) -> Self {
+        Self { hlc: op.hlc, op_id: op.op_id, author: op.author }
+    }
+
+    fn beats(&self, other: &OriginClaim) -> bool {
+        (self.hlc, self.op_id.0) < (other.hlc, other.op_id.0)
+    }
+}
+
+/// Position of an operation in a Space's history: its epoch, then its HLC
+/// to order operations within the same epoch
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+struct OpPoint {
+    epoch: EpochId,
+    hlc: Hlc,
+}
+
+impl OpPoint {
+    fn of(op: &CrdtOp) -> Self {
+        Self { epoch: op.epoch, hlc: op.hlc }
+    }
+}
+
+/// One continuous membership, from joining until removal
 #[derive(Debug, Clone)]
-struct MembershipRecord {
-    /// Epoch when user joined
+struct MembershipPeriod {
+    /// Epoch when the user joined
     joined_at: EpochId,
-    /// Epoch when user was removed (None if still member)
-    removed_at: Option<EpochId>,
+    /// Operation that removed the user (None if still a member)
+    removed_at: Option<OpPoint>,
+}
+
+impl MembershipPeriod {
+    /// Whether an operation at `point` falls inside this membership
+    fn contains(&self, point: OpPoint) -> bool {
+        self.joined_at <= point.epoch && self.removed_at.map_or(true, |removed| point < removed)
+    }
+}
+
+/// Membership history for epoch-based validation
+///
+/// Every join and removal is kept, so historical operations are checked
+/// against the membership at their own epoch rather than the current one.
+#[derive(Debug, Clone)]
+struct MembershipRecord {
+    /// Membership periods, oldest first; only the last can be open
+    periods: Vec<MembershipPeriod>,
     /// Current role
     role: Role,
 }
 
+impl MembershipRecord {
+    fn joined(epoch: EpochId, role: Role) -> Self {
+        Self {
+            periods: vec![MembershipPeriod { joined_at: epoch, removed_at: None }],
+            role,
+        }
+    }
+
+    fn is_member(&self) -> bool {
+        self.periods.last().is_some_and(|period| period.removed_at.is_none())
+    }
+
+    /// Start a new membership period unless one is already open
+    fn rejoin(&mut self, epoch: EpochId) {
+        if !self.is_member() {
+            self.periods.push(MembershipPeriod { joined_at: epoch, removed_at: None });
+        }
+    }
+
+    /// Close the open membership period at the removing operation
+    fn remove(&mut self, at: OpPoint) {
+        if let Some(period) = self.periods.last_mut().filter(|period| period.removed_at.is_none()) {
+            period.removed_at = Some(at);
+        }
+    }
+}
+
 impl OpValidator {
     pub fn new() -> Self {
         Self {
             space_epochs: HashMap::new(),
             memberships: HashMap::new(),
             seen_ops: HashSet::new(),
+            limits: OpLimits::default(),
+            allowed_reactions: HashMap::new(),
+            origins: HashMap::new(),
         }
     }
 
+    /// Create a validator with custom size limits
+    pub fn with_limits(limits: OpLimits) -> Self {
+        Self { limits, ..Self::new() }
+    }
+
+    /// Replace the size limits
+    pub fn set_limits(&mut self, limits: OpLimits) {
+        self.limits = limits;
+    }
+
+    /// Current size limits
+    pub fn limits(&self) -> OpLimits {
+        self.limits
+    }
+
+    /// Restrict a Space's reactions to `allowed` from the position of the
+    /// `UpdateSpaceReactions` op `op` on, or lift the restriction
+    ///
+    /// Managers that don't see the Space's own ops are told here.
+    pub fn set_allowed_reactions(&mut self, op: &CrdtOp, allowed: Option<Vec<String>>) {
+        self.allowed_reactions.entry(op.space_id)
+            .or_default()
+            .insert(OpPoint::of(op), allowed);
+    }
+
     /// Validate a CRDT operation according to the formal specification
     ///
     /// This implements the `accept_op(op)` pseudocode from project_desc.md:
-    /// 1. Verify signature
+    /// 1. Verify signature and that the op id matches its content
     /// 2. Verify causality (check prev_ops)
     /// 3. Verify membership/epoch constraints
     /// 4. Check for duplicates
+    ///
+    /// Oversized operations and messages are rejected before any of these steps.
     pub fn validate(
         &self,
         op: &CrdtOp,
         known_ops: &HashMap<OpId, CrdtOp>,
     ) -> ValidationResult {
+        if !self.within_size_limit(op) {
+            return ValidationResult::Reject(RejectionReason::TooLarge);
+        }
+        if !self.within_message_limit(op) {
+            return ValidationResult::Reject(RejectionReason::MessageTooLarge);
+        }
+        if !self.reaction_allowed(op) {
+            return ValidationResult::Reject(RejectionReason::ReactionNotAllowed);
+        }
+
         // Step 1: Verify signature
         if !self.verify_signature(op) {
             return ValidationResult::Reject(RejectionReason::InvalidSignature);
         }
+        if op.op_id != op.content_id() {
+            return ValidationResult::Reject(RejectionReason::IdMismatch);
+        }
 
         // Step 2: Verify causality - check all prev_ops are known
         let missing_deps: Vec<OpId> = op.prev_ops
@@ -111,7 +282,7 @@ impl OpValidator {
         }
 
         // Check author membership at op.epoch
-        if let Some(rejection) = self.check_membership(&op.author, &op.space_id, &op.epoch, local_epoch) {
+        if let Some(rejection) = self.check_membership(op) {
             return ValidationResult::Reject(rejection);
         }
 
@@ -123,6 +294,62 @@ impl OpValidator {
         ValidationResult::Accept
     }
 
+    /// Check a locally created operation against the size limits
+    ///
+    /// Local operations skip signature and causality checks, but must not
+    /// exceed the limits peers will enforce when they receive them, nor use
+    /// a reaction they would refuse.
+    pub fn check_local(&self, op: &CrdtOp) -> Result<()> {
+        if !self.within_size_limit(op) {
+            return Err(Error::Rejected(format!(
+                "Operation exceeds the maximum size of {} bytes",
+                self.limits.max_op_bytes
+            )));
+        }
+        if !self.within_message_limit(op) {
+            return Err(Error::Rejected(format!(
+                "Message exceeds the maximum size of {} bytes",
+                self.limits.max_message_bytes
+            )));
+        }
+        if !self.reaction_allowed(op) {
+            return Err(Error::Rejected("Reaction is not allowed in this Space".to_string()));
+        }
+        Ok(())
+    }
+
+    fn within_size_limit(&self, op: &CrdtOp) -> bool {
+        minicbor::to_vec(op).map_or(false, |bytes| bytes.len() <= self.limits.max_op_bytes)
+    }
+
+    /// Whether any message content the op carries fits the message limit
+    fn within_message_limit(&self, op: &CrdtOp) -> bool {
+        let content = match &op.op_type {
+            OpType::CreateThread(OpPayload::CreateThread { first_message: content, .. })
+            | OpType::PostMessage(OpPayload::PostMessage { content, .. })
+            | OpType::EditMessage(OpPayload::EditMessage { new_content: content, .. })
+            | OpType::ForwardMessage(OpPayload::ForwardMessage { content, .. }) => content,
+            _ => return true,
+        };
+        content.len() <= self.limits.max_message_bytes
+    }
+
+    /// Whether a reaction the op adds is in the allowlist its Space had at
+    /// the op's position, if any
+    ///
+    /// Like membership, the allowlist is looked up at the op's (epoch, HLC),
+    /// so a reaction added before a restriction stays valid when it arrives
+    /// after it.
+    fn reaction_allowed(&self, op: &CrdtOp) -> bool {
+        let OpType::AddReaction(OpPayload::AddReaction { emoji, .. }) = &op.op_type else {
+            return true;
+        };
+        self.allowed_reactions.get(&op.space_id)
+            .and_then(|history| history.range(..OpPoint::of(op)).next_back())
+            .and_then(|(_, allowed)| allowed.as_ref())
+            .map_or(true, |allowed| allowed.contains(emoji))
+    }
+
     /// Verify the cryptographic signature on an operation
     fn verify_signature(&self, op: &CrdtOp) -> bool {
         let signing_bytes = op.signing_bytes();
@@ -144,29 +371,27 @@ impl OpValidator {
     }
 
     /// Check if author was a member at the operation's epoch
-    fn check_membership(
-        &self,
-        author: &UserId,
-        space_id: &SpaceId,
-        op_epoch: &EpochId,
-        _local_epoch: EpochId,
-    ) -> Option<RejectionReason> {
-        let space_members = self.memberships.get(space_id)?;
-        let member_record = space_members.get(author)?;
-
-        // Check if author had joined by op_epoch
-        if member_record.joined_at.0 > op_epoch.0 {
-            return Some(RejectionReason::InvalidMembership);
+    ///
+    /// Uses the membership history reconstructed at the op's position
+    /// (epoch, then HLC), so an op written before its author was removed is
+    /// still accepted when replayed after the removal.
+    fn check_membership(&self, op: &CrdtOp) -> Option<RejectionReason> {
+        let space_members = self.memberships.get(&op.space_id)?;
+        let member_record = space_members.get(&op.author)?;
+
+        let point = OpPoint::of(op);
+        if member_record.periods.iter().any(|period| period.contains(point)) {
+            return None;
         }
 
-        // Check if author was removed before op_epoch
-        if let Some(removed_at) = member_record.removed_at {
-            if removed_at.0 <= op_epoch.0 {
-                return Some(RejectionReason::AuthorRemoved);
-            }
+        // Not a member here: either removed before the op, or not yet joined
+        let removed_before = member_record.periods.iter()
+            .any(|period| period.joined_at <= point.epoch && period.removed_at.is_some_and(|removed| removed <= point));
+        if removed_before {
+            Some(RejectionReason::AuthorRemoved)
+        } else {
+            Some(RejectionReason::InvalidMembership)
         }
-
-        None
     }
 
     /// Update validator state after accepting an operation
@@ -176,35 +401,45 @@ impl OpValidator {
         // Update membership state based on operation type
         match &op.op_type {
             OpType::CreateSpace(_) => {
-                // Creator becomes first admin
-                self.space_epochs.insert(op.space_id, EpochId(0));
-                let mut members = HashMap::new();
-                members.insert(op.author, MembershipRecord {
-                    joined_at: EpochId(0),
-                    removed_at: None,
-                    role: Role::Admin,
-                });
-                self.memberships.insert(op.space_id, members);
+                // Creator becomes first admin; an epoch already learned (e.g.
+                // from the DHT) is never rolled back
+                let claim = OriginClaim::of(op);
+                let previous = self.origins.get(&op.space_id).copied();
+                if previous.is_some_and(|previous| !claim.beats(&previous)) {
+                    return;
+                }
+                self.origins.insert(op.space_id, claim);
+                self.space_epochs.entry(op.space_id).or_insert(EpochId(0));
+                let space_members = self.memberships.entry(op.space_id).or_insert_with(HashMap::new);
+                // A claim that lost never created the Space, so its author
+                // holds nothing in it
+                if let Some(previous) = previous.filter(|previous| previous.author != op.author) {
+                    space_members.remove(&previous.author);
+                }
+                space_members.insert(op.author, MembershipRecord::joined(EpochId(0), Role::Admin));
             }
             
             OpType::RemoveMember(payload) => {
                 if let OpPayload::RemoveMember { user_id, .. } = payload {
                     if let Some(space_members) = self.memberships.get_mut(&op.space_id) {
                         if let Some(record) = space_members.get_mut(user_id) {
-                            record.removed_at = Some(op.epoch);
+                            record.remove(OpPoint::of(op));
                         }
                     }
                 }
             }
 
+            OpType::UpdateSpaceReactions(OpPayload::UpdateSpaceReactions { allowed }) => {
+                self.set_allowed_reactions(op, allowed.clone());
+            }
+
             OpType::AssignRole(payload) => {
                 if let OpPayload::AssignRole { user_id, role, .. } = payload {
                     if let Some(space_members) = self.memberships.get_mut(&op.space_id) {
-                        space_members.entry(*user_id).or_insert(MembershipRecord {
-                            joined_at: op.epoch,
-                            removed_at: None,
-                            role: Role::Member,
-                        }).role = *role;
+                        let record = space_members.entry(*user_id)
+                            .or_insert_with(|| MembershipRecord::joined(op.epoch, Role::Member));
+                        record.rejoin(op.epoch);
+                        record.role = *role;
                     }
                 }
             }
@@ -218,14 +453,24 @@ impl OpValidator {
         self.space_epochs.insert(space_id, epoch);
     }
 
+    /// A current member's role in a space
+    pub fn member_role(&self, space_id: &SpaceId, user_id: &UserId) -> Option<Role> {
+        self.memberships.get(space_id)?
+            .get(user_id)
+            .filter(|record| record.is_member())
+            .map(|record| record.role)
+    }
+
     /// Add a member to a space at a specific epoch
+    ///
+    /// A previously removed member starts a new membership period; their
+    /// earlier history is kept.
     pub fn add_member(&mut self, space_id: SpaceId, user_id: UserId, epoch: EpochId, role: Role) {
         let space_members = self.memberships.entry(space_id).or_insert_with(HashMap::new);
-        space_members.insert(user_id, MembershipRecord {
-            joined_at: epoch,
-            removed_at: None,
-            role,
-        });
+        let record = space_members.entry(user_id)
+            .or_insert_with(|| MembershipRecord::joined(epoch, role));
+        record.rejoin(epoch);
+        record.role = role;
     }
 }
 
@@ -261,6 +506,7 @@ mod tests {
             op_type: OpType::PostMessage(OpPayload::PostMessage {
                 message_id: MessageId::new(),
                 content: "Test message".to_string(),
+                quote: None,
             }),
             prev_ops,
             author: author_with_pubkey,
@@ -271,8 +517,7 @@ mod tests {
         };
         
         // Sign the operation properly
-        let signing_bytes = op.signing_bytes();
-        op.signature = Signature(keypair.sign(&signing_bytes).0);
+        op.sign(&keypair).unwrap();
         
         op
     }
@@ -327,8 +572,6 @@ mod tests {
         use crate::crypto::signing::Keypair;
         
         let mut validator = OpValidator::new();
-        let op_id = OpId(Uuid::new_v4());
-        validator.seen_ops.insert(op_id);
 
         // Create a keypair first
         let keypair = Keypair::generate();
@@ -340,13 +583,10 @@ mod tests {
             vec![],
         );
         
-        // Change op_id and author to match our test setup
-        op.op_id = op_id;
+        // Change author to match our test setup and re-sign
         op.author = keypair.user_id();
-        
-        // Re-sign with the new op_id
-        let signing_bytes = op.signing_bytes();
-        op.signature = Signature(keypair.sign(&signing_bytes).0);
+        op.sign(&keypair).unwrap();
+        validator.seen_ops.insert(op.op_id);
 
         let known_ops = HashMap::new();
         match validator.validate(&op, &known_ops) {
@@ -354,4 +594,27 @@ mod tests {
             _ => panic!("Expected duplicate rejection"),
         }
     }
+
+    #[test]
+    fn test_validate_tampered_op_id() {
+        use crate::crypto::signing::Keypair;
+
+        let validator = OpValidator::new();
+        let keypair = Keypair::generate();
+
+        let mut op = create_test_op(UserId([1u8; 32]), SpaceId::new(), EpochId(0), vec![]);
+        op.author = keypair.user_id();
+        op.sign(&keypair).unwrap();
+
+        // A validly signed op whose id wasn't derived from its content
+        op.op_id = OpId(Uuid::new_v4());
+        op.signature = Signature(keypair.sign(&op.signing_bytes()).0);
+        assert!(op.verify_signature());
+
+        let known_ops = HashMap::new();
+        assert_eq!(
+            validator.validate(&op, &known_ops),
+            ValidationResult::Reject(RejectionReason::IdMismatch)
+        );
+    }
 }
@@ -7,4 +7,4 @@
 
 pub mod signing;
 
-pub use signing::{Keypair, PublicKey, SecretKey};
+pub use signing::{ExternalSigner, Keypair, PublicKey, SecretKey, Signer};
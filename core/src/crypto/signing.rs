@@ -2,7 +2,7 @@
 
 use crate::{Error, Result};
 use crate::types::{Signature, UserId};
-use ed25519_dalek::{Signer, Verifier};
+use ed25519_dalek::{Signer as _, Verifier};
 use rand::rngs::OsRng;
 
 /// Ed25519 keypair
@@ -19,6 +19,16 @@ impl Keypair {
         Self { inner }
     }
 
+    /// Generate a keypair deterministically from a 32-byte seed
+    ///
+    /// The seed is used as the secret key itself, so the same seed always
+    /// yields the same keypair across dependency upgrades, which makes test
+    /// identities (and tie-breaks that order by `UserId`) reproducible.
+    pub fn generate_seeded(seed: [u8; 32]) -> Self {
+        let inner = ed25519_dalek::SigningKey::from_bytes(&seed);
+        Self { inner }
+    }
+
     /// Create keypair from secret key bytes
     pub fn from_bytes(bytes: &[u8; 32]) -> Result<Self> {
         let inner = ed25519_dalek::SigningKey::from_bytes(bytes);
@@ -49,6 +59,92 @@ impl Keypair {
     }
 }
 
+/// Signs with a user's Ed25519 identity key
+///
+/// Ops, acks and webhook tokens are signed through this trait, so the
+/// secret can stay in an HSM, TPM or security key. [`Keypair`] is the
+/// in-memory implementation; [`ExternalSigner`] delegates to a device.
+pub trait Signer: Send + Sync {
+    /// The public half of the identity key
+    fn public_key(&self) -> PublicKey;
+
+    /// Sign a message
+    fn sign(&self, message: &[u8]) -> Result<Signature>;
+
+    /// Get the user ID (public key bytes)
+    fn user_id(&self) -> UserId {
+        self.public_key().user_id()
+    }
+
+    /// The identity key as an MLS signature key
+    ///
+    /// MLS leaves signed with this key carry the same public key as the
+    /// user ID, so peers can tell an MLS sender and an op author are the
+    /// same user. `None` when the secret can't leave the signer.
+    fn mls_signature_key(&self) -> Option<openmls_basic_credential::SignatureKeyPair> {
+        None
+    }
+}
+
+impl Signer for Keypair {
+    fn public_key(&self) -> PublicKey {
+        Keypair::public_key(self)
+    }
+
+    fn sign(&self, message: &[u8]) -> Result<Signature> {
+        Ok(Keypair::sign(self, message))
+    }
+
+    fn mls_signature_key(&self) -> Option<openmls_basic_credential::SignatureKeyPair> {
+        Some(openmls_basic_credential::SignatureKeyPair::from_raw(
+            openmls::prelude::SignatureScheme::ED25519,
+            self.to_bytes().to_vec(),
+            self.public_key().to_bytes().to_vec(),
+        ))
+    }
+}
+
+/// Signature callback of an [`ExternalSigner`]
+pub type SignFn = dyn Fn(&[u8]) -> Result<[u8; 64]> + Send + Sync;
+
+/// Signs with a key held outside the process
+///
+/// The callback hands the message to the device (e.g. `C_Sign` on a
+/// PKCS#11 session, or a platform keystore) and returns the raw Ed25519
+/// signature. Each signature is checked against the public key, so a token
+/// holding the wrong key fails here instead of producing ops every peer
+/// rejects.
+pub struct ExternalSigner {
+    public_key: PublicKey,
+    sign_fn: Box<SignFn>,
+}
+
+impl ExternalSigner {
+    /// Create a signer for the device key with the given public key
+    pub fn new(
+        public_key: PublicKey,
+        sign_fn: impl Fn(&[u8]) -> Result<[u8; 64]> + Send + Sync + 'static,
+    ) -> Self {
+        Self {
+            public_key,
+            sign_fn: Box::new(sign_fn),
+        }
+    }
+}
+
+impl Signer for ExternalSigner {
+    fn public_key(&self) -> PublicKey {
+        self.public_key
+    }
+
+    fn sign(&self, message: &[u8]) -> Result<Signature> {
+        let signature = Signature((self.sign_fn)(message)?);
+        self.public_key.verify(message, &signature)
+            .map_err(|_| Error::Crypto("External signer produced a signature for another key".to_string()))?;
+        Ok(signature)
+    }
+}
+
 /// Ed25519 public key
 #[derive(Clone, Copy, PartialEq, Eq)]
 pub struct PublicKey {
@@ -133,6 +229,32 @@ mod tests {
         assert_eq!(user_id.0, public_key.to_bytes());
     }
 
+    #[test]
+    fn test_seeded_keypair_is_deterministic() {
+        let seed = [7u8; 32];
+        let keypair1 = Keypair::generate_seeded(seed);
+        let keypair2 = Keypair::generate_seeded(seed);
+        
+        assert_eq!(keypair1.user_id(), keypair2.user_id());
+        assert_eq!(keypair1.to_bytes(), keypair2.to_bytes());
+        
+        let message = b"Reproducible signature";
+        assert_eq!(keypair1.sign(message), keypair2.sign(message));
+        
+        let other = Keypair::generate_seeded([8u8; 32]);
+        assert_ne!(keypair1.user_id(), other.user_id());
+    }
+
+    #[test]
+    fn test_seeded_keypair_vector() {
+        // Pinned so a dependency bump can't silently change seeded identities
+        let keypair = Keypair::generate_seeded([7u8; 32]);
+        assert_eq!(
+            hex::encode(keypair.user_id().0),
+            "ea4a6c63e29c520abef5507b132ec5f9954776aebebe7b92421eea691446d22c"
+        );
+    }
+
     #[test]
     fn test_sign_verify() {
         let keypair = Keypair::generate();
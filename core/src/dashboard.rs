@@ -5,7 +5,7 @@
 //! sensitive cryptographic material.
 
 use crate::types::*;
-use crate::crdt::{CrdtOp, OpType};
+use crate::crdt::{CrdtOp, Hlc, OpType};
 use crate::forum::{Space, Channel};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
@@ -38,6 +38,23 @@ pub struct ClientSnapshot {
     pub mls_groups: Vec<MlsGroupInfo>,
     /// Connected peer IDs
     pub connected_peers: Vec<String>,
+    /// Connected peers with addresses and connection type
+    #[serde(default)]
+    pub peers: Vec<PeerSnapshot>,
+}
+
+/// Connected peer information
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub struct PeerSnapshot {
+    /// Peer ID
+    pub peer_id: String,
+    /// Remote addresses of the open connections
+    pub addrs: Vec<String>,
+    /// "direct" or "relayed"
+    pub connection_type: String,
+    /// Seconds the peer has been connected
+    pub connected_secs: u64,
 }
 
 /// Space information snapshot
@@ -58,10 +75,31 @@ pub struct SpaceSnapshot {
     pub channels: Vec<ChannelSnapshot>,
     /// Number of custom roles
     pub role_count: usize,
+    /// Roles in display order (highest position first)
+    pub roles: Vec<RoleSnapshot>,
     /// Space creation timestamp
     pub created_at: u64,
     /// Current epoch
     pub epoch: u64,
+    /// Whether the Space is archived (read-only)
+    #[serde(default)]
+    pub archived: bool,
+}
+
+/// Role display information
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub struct RoleSnapshot {
+    /// Role ID
+    pub id: String,
+    /// Role name
+    pub name: String,
+    /// Position in hierarchy (higher = more powerful)
+    pub position: u32,
+    /// Display color (RGB)
+    pub color: Option<u32>,
+    /// Whether members are listed separately
+    pub hoisted: bool,
 }
 
 /// Member information
@@ -94,6 +132,11 @@ pub struct ChannelSnapshot {
     pub created_at: u64,
     /// Whether archived
     pub archived: bool,
+    /// Whether flagged NSFW / age-restricted
+    pub nsfw: bool,
+    /// Channel members, ordered by user ID
+    #[serde(default)]
+    pub members: Vec<MemberInfo>,
     /// Threads in this channel
     pub threads: Vec<ThreadSnapshot>,
 }
@@ -110,8 +153,11 @@ pub struct ThreadSnapshot {
     pub creator: String,
     /// Creation timestamp
     pub created_at: u64,
-    /// Message count
+    /// Message count (non-deleted messages)
     pub message_count: usize,
+    /// Timestamp of the most recent message
+    #[serde(default)]
+    pub last_activity: u64,
     /// Messages in this thread
     pub messages: Vec<MessageSnapshot>,
 }
@@ -128,6 +174,19 @@ pub struct MessageSnapshot {
     pub content: String,
     /// Creation timestamp
     pub created_at: u64,
+    /// Message quoted inline, if any
+    #[serde(default)]
+    pub quote: Option<QuoteSnapshot>,
+}
+
+/// Inline quote display information
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub struct QuoteSnapshot {
+    /// Quoted message ID (hex-encoded)
+    pub message_id: String,
+    /// Quoted content as it read when the reply was sent
+    pub snippet: String,
 }
 
 /// DHT storage entry (metadata only, no actual data)
@@ -194,6 +253,8 @@ pub struct NetworkEdge {
 pub struct CrdtOperationSnapshot {
     /// Unix timestamp
     pub timestamp: u64,
+    /// Hybrid logical clock (used to order the timeline)
+    pub hlc: Hlc,
     /// Operation ID (hex-encoded)
     pub op_id: String,
     /// Operation type string
@@ -210,48 +271,64 @@ pub struct CrdtOperationSnapshot {
 // Conversion helpers (From traits)
 // ============================================================================
 
+impl MemberInfo {
+    /// Describe a member and the permissions their role grants
+    pub fn new(user_id: &UserId, role: Role) -> Self {
+        let permissions = match role {
+            Role::Admin => vec![
+                "CREATE_CHANNELS".to_string(),
+                "INVITE_MEMBERS".to_string(),
+                "KICK_MEMBERS".to_string(),
+                "MANAGE_ROLES".to_string(),
+                "SEND_MESSAGES".to_string(),
+            ],
+            Role::Moderator => vec![
+                "CREATE_CHANNELS".to_string(),
+                "KICK_MEMBERS".to_string(),
+                "SEND_MESSAGES".to_string(),
+            ],
+            Role::Member => vec![
+                "INVITE_MEMBERS".to_string(),
+                "SEND_MESSAGES".to_string(),
+            ],
+        };
+        
+        Self {
+            user_id: hex::encode(&user_id.0),
+            role: format!("{:?}", role),
+            permissions,
+        }
+    }
+}
+
 impl SpaceSnapshot {
     /// Create a snapshot from a Space
     pub fn from_space(space: &Space) -> Self {
-        #[allow(deprecated)]
-        let members: Vec<MemberInfo> = space.members.iter().map(|(user_id, role)| {
-            // Get permissions for this role
-            let permissions = match role {
-                Role::Admin => vec![
-                    "CREATE_CHANNELS".to_string(),
-                    "INVITE_MEMBERS".to_string(),
-                    "KICK_MEMBERS".to_string(),
-                    "MANAGE_ROLES".to_string(),
-                    "SEND_MESSAGES".to_string(),
-                ],
-                Role::Moderator => vec![
-                    "CREATE_CHANNELS".to_string(),
-                    "KICK_MEMBERS".to_string(),
-                    "SEND_MESSAGES".to_string(),
-                ],
-                Role::Member => vec![
-                    "INVITE_MEMBERS".to_string(),
-                    "SEND_MESSAGES".to_string(),
-                ],
-            };
-            
-            MemberInfo {
-                user_id: hex::encode(&user_id.0),
-                role: format!("{:?}", role),
-                permissions,
-            }
+        let members: Vec<MemberInfo> = space.members().iter()
+            .map(|(user_id, role)| MemberInfo::new(user_id, *role))
+            .collect();
+        
+        let mut roles: Vec<RoleSnapshot> = space.roles.values().map(|role| RoleSnapshot {
+            id: role.id.0.to_string(),
+            name: role.name.clone(),
+            position: role.position,
+            color: role.color,
+            hoisted: role.hoisted,
         }).collect();
+        roles.sort_by(|a, b| b.position.cmp(&a.position).then_with(|| a.name.cmp(&b.name)));
         
         Self {
             id: hex::encode(&space.id.0),
             name: space.name.clone(),
             owner: hex::encode(&space.owner.0),
-            member_count: members.len(),
+            member_count: space.member_count(),
             members,
             channels: Vec::new(), // Will be populated separately
             role_count: space.roles.len(),
+            roles,
             created_at: space.created_at,
             epoch: space.epoch.0,
+            archived: space.archived,
         }
     }
 }
@@ -267,6 +344,10 @@ impl ChannelSnapshot {
             creator: hex::encode(&channel.creator.0),
             created_at: channel.created_at,
             archived: channel.archived,
+            nsfw: channel.nsfw,
+            members: channel.list_members().iter()
+                .map(|(user_id, role)| MemberInfo::new(user_id, *role))
+                .collect(),
             threads: Vec::new(), // Will be filled in by the caller
         }
     }
@@ -281,6 +362,7 @@ impl ThreadSnapshot {
             creator: hex::encode(&thread.creator.0),
             created_at: thread.created_at,
             message_count: thread.message_count as usize,
+            last_activity: thread.last_activity,
             messages,
         }
     }
@@ -294,6 +376,25 @@ impl MessageSnapshot {
             author: hex::encode(&message.author.0),
             content: message.content.clone(),
             created_at: message.created_at,
+            quote: message.quote.as_ref().map(|quote| QuoteSnapshot {
+                message_id: quote.message_id.to_string(),
+                snippet: quote.snippet.clone(),
+            }),
+        }
+    }
+}
+
+impl PeerSnapshot {
+    /// Create a snapshot from a connected peer's details
+    pub fn from_detail(detail: &crate::network::PeerDetail) -> Self {
+        Self {
+            peer_id: detail.peer_id.to_string(),
+            addrs: detail.addrs.iter().map(|addr| addr.to_string()).collect(),
+            connection_type: match detail.connection_type {
+                crate::network::ConnectionType::Direct => "direct".to_string(),
+                crate::network::ConnectionType::Relayed => "relayed".to_string(),
+            },
+            connected_secs: detail.uptime().as_secs(),
         }
     }
 }
@@ -314,12 +415,25 @@ impl CrdtOperationSnapshot {
             OpType::EditMessage(_) => "EditMessage",
             OpType::DeleteMessage(_) => "DeleteMessage",
             OpType::UpdateSpaceVisibility(_) => "UpdateSpaceVisibility",
+            OpType::UpdateSpaceMetadata(_) => "UpdateSpaceMetadata",
+            OpType::UpdateRole(_) => "UpdateRole",
+            OpType::AttachLinkPreview(_) => "AttachLinkPreview",
+            OpType::ForwardMessage(_) => "ForwardMessage",
+            OpType::RedactMessage(_) => "RedactMessage",
+            OpType::FollowChannel(_) => "FollowChannel",
+            OpType::UnfollowChannel(_) => "UnfollowChannel",
+            OpType::DeleteChannel => "DeleteChannel",
+            OpType::ArchiveSpace => "ArchiveSpace",
+            OpType::DeleteSpace => "DeleteSpace",
+            OpType::UpdateSpaceReactions(_) => "UpdateSpaceReactions",
+            OpType::AddReaction(_) => "AddReaction",
             OpType::RemoveRole(_) => "RemoveRole",
             _ => "Other", // For other operation types
         };
 
         Self {
             timestamp: op.timestamp,
+            hlc: op.hlc,
             op_id: hex::encode(op.op_id.0.as_bytes()),
             op_type: op_type_str.to_string(),
             author: hex::encode(&op.author.0),
@@ -329,6 +443,26 @@ impl CrdtOperationSnapshot {
     }
 }
 
+/// Merge several clients' operation lists into one timeline
+///
+/// Operations seen by more than one client appear once. The result is
+/// sorted by HLC (oldest first) and keeps only the newest `limit` entries.
+pub fn merge_timelines(
+    timelines: impl IntoIterator<Item = Vec<CrdtOperationSnapshot>>,
+    limit: usize,
+) -> Vec<CrdtOperationSnapshot> {
+    let mut by_id: HashMap<String, CrdtOperationSnapshot> = HashMap::new();
+    for op in timelines.into_iter().flatten() {
+        by_id.entry(op.op_id.clone()).or_insert(op);
+    }
+    
+    let mut merged: Vec<CrdtOperationSnapshot> = by_id.into_values().collect();
+    merged.sort_by(|a, b| a.hlc.cmp(&b.hlc).then_with(|| a.op_id.cmp(&b.op_id)));
+    
+    let skip = merged.len().saturating_sub(limit);
+    merged.split_off(skip)
+}
+
 impl NetworkGraph {
     /// Create an empty network graph
     pub fn new() -> Self {
@@ -385,6 +519,7 @@ mod tests {
             dht_storage: vec![],
             mls_groups: vec![],
             connected_peers: vec![],
+            peers: vec![],
         };
 
         let json = serde_json::to_string(&snapshot).unwrap();
@@ -392,6 +527,30 @@ mod tests {
         assert_eq!(snapshot.name, deserialized.name);
     }
 
+    #[test]
+    fn test_merge_timelines_dedups_and_sorts_by_hlc() {
+        let op = |id: &str, wall_time: u64| CrdtOperationSnapshot {
+            timestamp: wall_time / 1000,
+            hlc: Hlc { wall_time, logical: 0 },
+            op_id: id.to_string(),
+            op_type: "CreateSpace".to_string(),
+            author: "alice".to_string(),
+            space_id: "space".to_string(),
+            channel_id: None,
+        };
+
+        let alice = vec![op("a", 3000), op("b", 1000)];
+        let bob = vec![op("b", 1000), op("c", 2000)];
+
+        let merged = merge_timelines(vec![alice.clone(), bob.clone()], 10);
+        let ids: Vec<&str> = merged.iter().map(|op| op.op_id.as_str()).collect();
+        assert_eq!(ids, vec!["b", "c", "a"]);
+
+        let newest = merge_timelines(vec![alice, bob], 2);
+        let ids: Vec<&str> = newest.iter().map(|op| op.op_id.as_str()).collect();
+        assert_eq!(ids, vec!["c", "a"]);
+    }
+
     #[test]
     fn test_network_graph_builder() {
         let mut graph = NetworkGraph::new();
@@ -0,0 +1,401 @@
+//! Space transcript export and import
+//!
+//! Serializable snapshot of a Space's channels, threads and messages, for
+//! backups and sharing. Output is sorted by creation time so that exporting
+//! the same state twice yields byte-identical files. JSON
+//! transcripts can be replayed into a new Space with
+//! [`crate::Client::import_transcript`].
+
+use crate::forum::{Channel, Message, Space, Thread};
+use crate::storage::BlobHash;
+use crate::{Error, Result};
+use serde::{Deserialize, Serialize};
+use std::fmt::Write as _;
+
+/// Transcript schema version written into every JSON export
+pub const TRANSCRIPT_VERSION: u32 = 1;
+
+/// Output format for [`crate::Client::export_space`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExportFormat {
+    /// Structured JSON (can be re-imported)
+    Json,
+    /// Human-readable Markdown
+    Markdown,
+}
+
+impl ExportFormat {
+    /// Parse from string (for CLI input)
+    pub fn from_str(s: &str) -> Option<Self> {
+        match s.to_lowercase().as_str() {
+            "json" => Some(ExportFormat::Json),
+            "markdown" | "md" => Some(ExportFormat::Markdown),
+            _ => None,
+        }
+    }
+
+    /// Pick a format from a file extension, defaulting to JSON
+    pub fn from_path(path: &std::path::Path) -> Self {
+        match path.extension().and_then(|ext| ext.to_str()) {
+            Some(ext) if ext.eq_ignore_ascii_case("md") => ExportFormat::Markdown,
+            _ => ExportFormat::Json,
+        }
+    }
+}
+
+/// Exported Space with all of its channels
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SpaceTranscript {
+    pub version: u32,
+    pub space_id: String,
+    pub name: String,
+    pub description: Option<String>,
+    pub owner: String,
+    pub membership_mode: String,
+    pub created_at: u64,
+    pub channels: Vec<ChannelTranscript>,
+}
+
+/// Exported Channel with its threads
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ChannelTranscript {
+    pub channel_id: String,
+    pub name: String,
+    pub description: Option<String>,
+    pub creator: String,
+    pub created_at: u64,
+    pub archived: bool,
+    pub threads: Vec<ThreadTranscript>,
+}
+
+/// Exported Thread with its messages
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ThreadTranscript {
+    pub thread_id: String,
+    pub title: Option<String>,
+    pub creator: String,
+    pub created_at: u64,
+    pub messages: Vec<MessageTranscript>,
+}
+
+/// Exported Message; attachments are referenced by blob hash only
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MessageTranscript {
+    pub message_id: String,
+    pub author: String,
+    pub content: String,
+    pub created_at: u64,
+    pub edited_at: Option<u64>,
+    pub deleted: bool,
+    pub attachment: Option<String>,
+    /// Original author/time, for messages that were themselves imported
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub origin: Option<MessageOrigin>,
+}
+
+/// Where an imported message originally came from
+///
+/// Imported messages are re-authored by the importing user, so the original
+/// attribution is kept alongside them in local storage.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MessageOrigin {
+    /// Original message ID (hex)
+    pub message_id: String,
+    /// Original author (hex user ID)
+    pub author: String,
+    /// Original creation timestamp (milliseconds)
+    pub created_at: u64,
+}
+
+impl SpaceTranscript {
+    /// Start a transcript for a Space (channels are added by the caller)
+    pub fn new(space: &Space) -> Self {
+        Self {
+            version: TRANSCRIPT_VERSION,
+            space_id: hex::encode(space.id.0),
+            name: space.name.clone(),
+            description: space.description.clone(),
+            owner: hex::encode(space.owner.0),
+            membership_mode: space.membership_mode.short_name().to_string(),
+            created_at: space.created_at,
+            channels: Vec::new(),
+        }
+    }
+
+    /// Sort channels and threads by creation time, then id; messages by time
+    ///
+    /// The message sort is stable, so messages sharing a timestamp keep their
+    /// thread order (which is causal) instead of being reordered by id.
+    pub fn sort(&mut self) {
+        self.channels.sort_by(|a, b| (a.created_at, &a.channel_id).cmp(&(b.created_at, &b.channel_id)));
+        for channel in &mut self.channels {
+            channel.threads.sort_by(|a, b| (a.created_at, &a.thread_id).cmp(&(b.created_at, &b.thread_id)));
+            for thread in &mut channel.threads {
+                thread.messages.sort_by_key(|message| message.created_at);
+            }
+        }
+    }
+
+    /// Parse a JSON transcript, rejecting unsupported or malformed input
+    pub fn from_json(bytes: &[u8]) -> Result<Self> {
+        let transcript: Self = serde_json::from_slice(bytes)
+            .map_err(|e| Error::Serialization(format!("Invalid transcript: {}", e)))?;
+        transcript.validate()?;
+        Ok(transcript)
+    }
+
+    /// Check that a transcript can be replayed
+    pub fn validate(&self) -> Result<()> {
+        if self.version != TRANSCRIPT_VERSION {
+            return Err(Error::InvalidOperation(format!(
+                "Unsupported transcript version {} (expected {})",
+                self.version, TRANSCRIPT_VERSION
+            )));
+        }
+        if self.name.trim().is_empty() {
+            return Err(Error::InvalidOperation("Transcript has an empty space name".to_string()));
+        }
+
+        let mut channel_names = std::collections::HashSet::new();
+        for channel in &self.channels {
+            if channel.name.trim().is_empty() {
+                return Err(Error::InvalidOperation(format!(
+                    "Channel {} has an empty name", short_id(&channel.channel_id)
+                )));
+            }
+            if !channel_names.insert(channel.name.as_str()) {
+                return Err(Error::InvalidOperation(format!(
+                    "Duplicate channel name '{}'", channel.name
+                )));
+            }
+            for message in channel.threads.iter().flat_map(|t| &t.messages) {
+                let author_ok = hex::decode(&message.author).map(|b| b.len() == 32).unwrap_or(false);
+                if !author_ok {
+                    return Err(Error::InvalidOperation(format!(
+                        "Message {} has an invalid author id", short_id(&message.message_id)
+                    )));
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Encode in the requested format
+    pub fn render(&self, format: ExportFormat) -> Result<Vec<u8>> {
+        match format {
+            ExportFormat::Json => serde_json::to_vec_pretty(self)
+                .map_err(|e| Error::Serialization(format!("Failed to encode transcript: {}", e))),
+            ExportFormat::Markdown => Ok(self.to_markdown().into_bytes()),
+        }
+    }
+
+    /// Render as Markdown
+    pub fn to_markdown(&self) -> String {
+        let mut out = String::new();
+        let _ = writeln!(out, "# {}", self.name);
+        out.push('\n');
+        if let Some(description) = &self.description {
+            let _ = writeln!(out, "> {}", description);
+            out.push('\n');
+        }
+        let _ = writeln!(out, "- Space ID: `{}`", self.space_id);
+        let _ = writeln!(out, "- Owner: `{}`", short_id(&self.owner));
+        let _ = writeln!(out, "- Created: {}", format_timestamp(self.created_at));
+
+        for channel in &self.channels {
+            out.push('\n');
+            let archived = if channel.archived { " (archived)" } else { "" };
+            let _ = writeln!(out, "## #{}{}", channel.name, archived);
+            if let Some(description) = &channel.description {
+                out.push('\n');
+                let _ = writeln!(out, "> {}", description);
+            }
+
+            for thread in &channel.threads {
+                out.push('\n');
+                let _ = writeln!(out, "### {}", thread.title.as_deref().unwrap_or("Untitled"));
+
+                for message in &thread.messages {
+                    out.push('\n');
+                    let edited = if message.edited_at.is_some() { " (edited)" } else { "" };
+                    let origin = message.origin();
+                    let _ = writeln!(out, "**{}** · {}{}",
+                        short_id(&origin.author),
+                        format_timestamp(origin.created_at),
+                        edited
+                    );
+                    out.push('\n');
+                    if message.deleted {
+                        out.push_str("*[deleted]*\n");
+                    } else {
+                        for line in message.content.lines() {
+                            let _ = writeln!(out, "{}", line);
+                        }
+                    }
+                    if let Some(hash) = &message.attachment {
+                        out.push('\n');
+                        let _ = writeln!(out, "*Attachment: `{}`*", hash);
+                    }
+                }
+            }
+        }
+
+        out
+    }
+}
+
+impl ChannelTranscript {
+    pub fn new(channel: &Channel) -> Self {
+        Self {
+            channel_id: hex::encode(channel.id.0),
+            name: channel.name.clone(),
+            description: channel.description.clone(),
+            creator: hex::encode(channel.creator.0),
+            created_at: channel.created_at,
+            archived: channel.archived,
+            threads: Vec::new(),
+        }
+    }
+}
+
+impl ThreadTranscript {
+    pub fn new(thread: &Thread) -> Self {
+        Self {
+            thread_id: hex::encode(thread.id.0),
+            title: thread.title.clone(),
+            creator: hex::encode(thread.creator.0),
+            created_at: thread.created_at,
+            messages: Vec::new(),
+        }
+    }
+}
+
+impl MessageTranscript {
+    pub fn new(message: &Message, attachment: Option<BlobHash>) -> Self {
+        Self {
+            message_id: hex::encode(message.id.0),
+            author: hex::encode(message.author.0),
+            content: message.content.clone(),
+            created_at: message.created_at,
+            edited_at: message.edited_at,
+            deleted: message.deleted,
+            attachment: attachment.map(|hash| hash.to_hex()),
+            origin: None,
+        }
+    }
+
+    /// Original attribution: an earlier origin if re-imported, else this message
+    pub fn origin(&self) -> MessageOrigin {
+        self.origin.clone().unwrap_or_else(|| MessageOrigin {
+            message_id: self.message_id.clone(),
+            author: self.author.clone(),
+            created_at: self.created_at,
+        })
+    }
+}
+
+/// First 8 bytes of a hex id, for display
+fn short_id(hex_id: &str) -> &str {
+    &hex_id[..hex_id.len().min(16)]
+}
+
+/// Format a millisecond timestamp as UTC
+fn format_timestamp(millis: u64) -> String {
+    chrono::DateTime::from_timestamp_millis(millis as i64)
+        .map(|dt| dt.format("%Y-%m-%d %H:%M:%S UTC").to_string())
+        .unwrap_or_else(|| millis.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn message(id: &str, created_at: u64, content: &str) -> MessageTranscript {
+        MessageTranscript {
+            message_id: id.to_string(),
+            author: "ab".repeat(32),
+            content: content.to_string(),
+            created_at,
+            edited_at: None,
+            deleted: false,
+            attachment: None,
+            origin: None,
+        }
+    }
+
+    fn transcript() -> SpaceTranscript {
+        SpaceTranscript {
+            version: TRANSCRIPT_VERSION,
+            space_id: "00".repeat(32),
+            name: "Book Club".to_string(),
+            description: Some("Monthly reads".to_string()),
+            owner: "ab".repeat(32),
+            membership_mode: "mls".to_string(),
+            created_at: 1_700_000_000_000,
+            channels: vec![ChannelTranscript {
+                channel_id: "01".repeat(32),
+                name: "general".to_string(),
+                description: None,
+                creator: "ab".repeat(32),
+                created_at: 1_700_000_001_000,
+                archived: false,
+                threads: vec![ThreadTranscript {
+                    thread_id: "02".repeat(32),
+                    title: Some("Dune".to_string()),
+                    creator: "ab".repeat(32),
+                    created_at: 1_700_000_002_000,
+                    messages: vec![
+                        message("04", 1_700_000_004_000, "Second"),
+                        message("03", 1_700_000_003_000, "First"),
+                    ],
+                }],
+            }],
+        }
+    }
+
+    #[test]
+    fn test_sort_orders_messages_by_time() {
+        let mut transcript = transcript();
+        transcript.sort();
+
+        let messages = &transcript.channels[0].threads[0].messages;
+        assert_eq!(messages[0].content, "First");
+        assert_eq!(messages[1].content, "Second");
+    }
+
+    #[test]
+    fn test_markdown_rendering() {
+        let mut transcript = transcript();
+        transcript.sort();
+        let markdown = transcript.to_markdown();
+
+        assert!(markdown.starts_with("# Book Club\n"));
+        assert!(markdown.contains("## #general"));
+        assert!(markdown.contains("### Dune"));
+        assert!(markdown.find("First").unwrap() < markdown.find("Second").unwrap());
+        assert!(markdown.contains("2023-11-14 22:13:23 UTC"));
+    }
+
+    #[test]
+    fn test_from_json_rejects_bad_input() {
+        assert!(SpaceTranscript::from_json(b"{not json").is_err());
+
+        let mut transcript = transcript();
+        transcript.version = TRANSCRIPT_VERSION + 1;
+        let bytes = transcript.render(ExportFormat::Json).unwrap();
+        assert!(SpaceTranscript::from_json(&bytes).is_err());
+
+        let mut transcript = self::transcript();
+        transcript.channels[0].threads[0].messages[0].author = "zz".to_string();
+        let bytes = transcript.render(ExportFormat::Json).unwrap();
+        assert!(SpaceTranscript::from_json(&bytes).is_err());
+    }
+
+    #[test]
+    fn test_format_from_path() {
+        assert_eq!(ExportFormat::from_path("out.md".as_ref()), ExportFormat::Markdown);
+        assert_eq!(ExportFormat::from_path("out.json".as_ref()), ExportFormat::Json);
+        assert_eq!(ExportFormat::from_path("out".as_ref()), ExportFormat::Json);
+    }
+}
@@ -4,11 +4,11 @@
 //! Channels can have Threads (multi-message discussions).
 
 use crate::types::*;
-use crate::crdt::{CrdtOp, OpType, OpPayload, Hlc, HoldbackQueue, OpValidator, ValidationResult};
+use crate::crdt::{CrdtOp, OpType, OpPayload, Hlc, HoldbackQueue, OpLimits, OpValidator, StuckOp, ValidationResult};
 use crate::mls::{MlsGroup, MlsGroupConfig};
 use crate::mls::provider::DescordProvider;
 use crate::{Error, Result};
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use openmls::prelude::OpenMlsProvider;
 
 /// A Channel (text communication container)
@@ -44,6 +44,12 @@ pub struct Channel {
     
     /// Whether the channel is archived
     pub archived: bool,
+    
+    /// Whether the channel is NSFW / age-restricted
+    pub nsfw: bool,
+    
+    /// Whether the channel was deleted (only seen when listing tombstones)
+    pub deleted: bool,
 }
 
 impl Channel {
@@ -70,6 +76,8 @@ impl Channel {
             members,
             created_at,
             archived: false,
+            nsfw: false,
+            deleted: false,
         }
     }
     
@@ -93,6 +101,15 @@ impl Channel {
         self.members.get(user_id)
     }
     
+    /// Members and their channel roles, ordered by user ID
+    pub fn list_members(&self) -> Vec<(UserId, Role)> {
+        let mut members: Vec<(UserId, Role)> = self.members.iter()
+            .map(|(user_id, role)| (*user_id, *role))
+            .collect();
+        members.sort_by_key(|(user_id, _)| *user_id);
+        members
+    }
+    
     /// Advance to next epoch (for MLS key rotation)
     pub fn advance_epoch(&mut self) {
         self.epoch.0 += 1;
@@ -117,6 +134,24 @@ impl Channel {
     pub fn set_description(&mut self, description: Option<String>) {
         self.description = description;
     }
+    
+    /// Flag or unflag the channel as NSFW
+    pub fn set_nsfw(&mut self, nsfw: bool) {
+        self.nsfw = nsfw;
+    }
+}
+
+/// A channel re-posting another channel's messages (announcement follow)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ChannelFollow {
+    /// Channel whose messages are announced
+    pub source_channel: ChannelId,
+    /// Channel receiving them
+    pub target_channel: ChannelId,
+    /// Thread in the target channel the messages are forwarded into
+    pub target_thread: ThreadId,
+    /// User who set up the follow; only their client re-posts
+    pub follower: UserId,
 }
 
 /// Manages Channel state and operations
@@ -141,6 +176,15 @@ pub struct ChannelManager {
     
     /// All operations (for persistence)
     operations: HashMap<OpId, CrdtOp>,
+    
+    /// Announcement follows by (source, target) channel
+    follows: HashMap<(ChannelId, ChannelId), ChannelFollow>,
+    
+    /// Tombstones for deleted channels, so late ops can't resurrect them
+    deleted: HashSet<ChannelId>,
+    
+    /// Last known state of deleted channels, for audit listings
+    tombstoned: HashMap<ChannelId, Channel>,
 }
 
 impl ChannelManager {
@@ -153,8 +197,21 @@ impl ChannelManager {
             holdback: HoldbackQueue::new(),
             hlc: Hlc::now(),
             operations: HashMap::new(),
+            follows: HashMap::new(),
+            deleted: HashSet::new(),
+            tombstoned: HashMap::new(),
         }
     }
+
+    /// Set the size limits applied to local and received operations
+    pub fn set_op_limits(&mut self, limits: OpLimits) {
+        self.validator.set_limits(limits);
+    }
+    
+    /// Operations held back waiting on ops we don't have yet
+    pub fn stuck_ops(&self, current_time: u64) -> Vec<StuckOp> {
+        self.holdback.stuck_ops(current_time)
+    }
     
     /// Create a new Channel
     pub fn create_channel(
@@ -164,7 +221,7 @@ impl ChannelManager {
         name: String,
         description: Option<String>,
         creator: UserId,
-        creator_keypair: &crate::crypto::signing::Keypair,
+        creator_keypair: &dyn crate::crypto::signing::Signer,
         epoch: EpochId,
     ) -> Result<CrdtOp> {
         // Channels always use MLS for E2EE
@@ -192,7 +249,7 @@ impl ChannelManager {
         name: String,
         description: Option<String>,
         creator: UserId,
-        creator_keypair: &crate::crypto::signing::Keypair,
+        creator_keypair: &dyn crate::crypto::signing::Signer,
         epoch: EpochId,
         create_mls_group: bool,
         provider: Option<&DescordProvider>,
@@ -201,15 +258,15 @@ impl ChannelManager {
         if self.channels.contains_key(&channel_id) {
             return Err(Error::AlreadyExists(format!("Channel {:?} already exists", channel_id)));
         }
+        if self.deleted.contains(&channel_id) {
+            return Err(Error::Rejected(format!("Channel {:?} was deleted", channel_id)));
+        }
         
         // Create MLS group for this channel if requested
         let mls_group = if create_mls_group {
             if let Some(prov) = provider {
                 let mls_config = MlsGroupConfig::default();
-                let signer = openmls_basic_credential::SignatureKeyPair::new(
-                    mls_config.ciphersuite.signature_algorithm()
-                ).map_err(|e| Error::Crypto(format!("Failed to create signer: {:?}", e)))?;
-                let signer = std::sync::Arc::new(signer);
+                let signer = crate::mls::identity_signer(creator_keypair)?;
                 
                 // Use channel_id as the group identifier
                 Some(MlsGroup::create(
@@ -243,7 +300,7 @@ impl ChannelManager {
         
         // Create CRDT operation
         let mut op = CrdtOp {
-            op_id: OpId(uuid::Uuid::new_v4()),
+            op_id: OpId(uuid::Uuid::nil()), // set by sign()
             space_id,
             channel_id: Some(channel_id),
             thread_id: None,
@@ -260,8 +317,8 @@ impl ChannelManager {
         };
         
         // Sign the operation
-        let signing_bytes = op.signing_bytes();
-        op.signature = Signature(creator_keypair.sign(&signing_bytes).0);
+        op.sign(creator_keypair)?;
+        self.validator.check_local(&op)?;
         
         // Apply locally
         self.channels.insert(channel_id, channel);
@@ -273,7 +330,7 @@ impl ChannelManager {
         // Store MLS group if created
         if let Some(group) = mls_group {
             self.mls_groups.insert(channel_id, group);
-            println!("ℹ️  Created channel-level MLS group for channel: {}", hex::encode(&channel_id.0[..8]));
+            tracing::info!("Created channel-level MLS group for channel: {}", channel_id.short());
         }
         
         self.operations.insert(op.op_id, op.clone());
@@ -291,6 +348,9 @@ impl ChannelManager {
                 if let OpType::CreateChannel(OpPayload::CreateChannel { name, description }) = &op.op_type {
                     let channel_id = op.channel_id
                         .ok_or_else(|| Error::InvalidOperation("Missing channel_id".to_string()))?;
+                    if self.deleted.contains(&channel_id) {
+                        return Err(Error::Rejected(format!("Channel {:?} was deleted", channel_id)));
+                    }
                     
                     let channel = Channel::new(
                         channel_id,
@@ -334,7 +394,7 @@ impl ChannelManager {
         channel_id: ChannelId,
         new_name: String,
         author: UserId,
-        author_keypair: &crate::crypto::signing::Keypair,
+        author_keypair: &dyn crate::crypto::signing::Signer,
         epoch: EpochId,
     ) -> Result<CrdtOp> {
         // Check channel exists
@@ -350,13 +410,14 @@ impl ChannelManager {
             .as_secs();
         
         let mut op = CrdtOp {
-            op_id: OpId(uuid::Uuid::new_v4()),
+            op_id: OpId(uuid::Uuid::nil()), // set by sign()
             space_id,
             channel_id: Some(channel_id),
             thread_id: None,
             op_type: OpType::UpdateChannel(OpPayload::UpdateChannel {
                 name: Some(new_name.clone()),
                 description: None,
+                nsfw: None,
             }),
             prev_ops: vec![],
             author,
@@ -367,8 +428,8 @@ impl ChannelManager {
         };
         
         // Sign the operation
-        let signing_bytes = op.signing_bytes();
-        op.signature = Signature(author_keypair.sign(&signing_bytes).0);
+        op.sign(author_keypair)?;
+        self.validator.check_local(&op)?;
         
         // Apply locally
         channel.set_name(new_name);
@@ -378,12 +439,99 @@ impl ChannelManager {
         Ok(op)
     }
     
+    /// Flag or unflag a channel as NSFW / age-restricted
+    pub fn set_nsfw(
+        &mut self,
+        channel_id: ChannelId,
+        nsfw: bool,
+        author: UserId,
+        author_keypair: &dyn crate::crypto::signing::Signer,
+        epoch: EpochId,
+    ) -> Result<CrdtOp> {
+        let channel = self.channels.get_mut(&channel_id)
+            .ok_or_else(|| Error::NotFound(format!("Channel {:?} not found", channel_id)))?;
+        
+        let space_id = channel.space_id;
+        
+        let current_time = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_secs();
+        
+        let mut op = CrdtOp {
+            op_id: OpId(uuid::Uuid::nil()), // set by sign()
+            space_id,
+            channel_id: Some(channel_id),
+            thread_id: None,
+            op_type: OpType::UpdateChannel(OpPayload::UpdateChannel {
+                name: None,
+                description: None,
+                nsfw: Some(nsfw),
+            }),
+            prev_ops: vec![],
+            author,
+            epoch,
+            hlc: self.hlc.tick(),
+            timestamp: current_time,
+            signature: Signature([0u8; 64]),
+        };
+        
+        op.sign(author_keypair)?;
+        self.validator.check_local(&op)?;
+        
+        channel.set_nsfw(nsfw);
+        self.operations.insert(op.op_id, op.clone());
+        self.validator.apply_op(&op);
+        
+        Ok(op)
+    }
+    
+    /// Process an UpdateChannel operation from the network
+    pub fn process_update_channel(&mut self, op: &CrdtOp) -> Result<()> {
+        match self.validator.validate(op, &self.operations) {
+            ValidationResult::Accept => {
+                if let OpType::UpdateChannel(OpPayload::UpdateChannel { name, description, nsfw }) = &op.op_type {
+                    let channel_id = op.channel_id
+                        .ok_or_else(|| Error::InvalidOperation("Missing channel_id".to_string()))?;
+                    let channel = self.channels.get_mut(&channel_id)
+                        .ok_or_else(|| Error::NotFound(format!("Channel {:?} not found", channel_id)))?;
+                    
+                    if let Some(name) = name {
+                        channel.set_name(name.clone());
+                    }
+                    if let Some(description) = description {
+                        channel.set_description(Some(description.clone()));
+                    }
+                    if let Some(nsfw) = nsfw {
+                        channel.set_nsfw(*nsfw);
+                    }
+                    
+                    self.operations.insert(op.op_id, op.clone());
+                    self.validator.apply_op(op);
+                    self.hlc.update(op.hlc);
+                    
+                    Ok(())
+                } else {
+                    Err(Error::InvalidOperation("Expected UpdateChannel operation".to_string()))
+                }
+            }
+            ValidationResult::Buffered(deps) => {
+                self.holdback.buffer(op.clone(), deps, op.timestamp)
+                    .map_err(|e| Error::Storage(e))?;
+                Ok(())
+            }
+            ValidationResult::Reject(reason) => {
+                Err(Error::InvalidOperation(format!("Operation rejected: {:?}", reason)))
+            }
+        }
+    }
+    
     /// Archive a channel
     pub fn archive_channel(
         &mut self,
         channel_id: ChannelId,
         author: UserId,
-        author_keypair: &crate::crypto::signing::Keypair,
+        author_keypair: &dyn crate::crypto::signing::Signer,
         epoch: EpochId,
     ) -> Result<CrdtOp> {
         let channel = self.channels.get_mut(&channel_id)
@@ -397,7 +545,7 @@ impl ChannelManager {
             .as_secs();
         
         let mut op = CrdtOp {
-            op_id: OpId(uuid::Uuid::new_v4()),
+            op_id: OpId(uuid::Uuid::nil()), // set by sign()
             space_id,
             channel_id: Some(channel_id),
             thread_id: None,
@@ -410,8 +558,8 @@ impl ChannelManager {
             signature: Signature([0u8; 64]),
         };
         
-        let signing_bytes = op.signing_bytes();
-        op.signature = Signature(author_keypair.sign(&signing_bytes).0);
+        op.sign(author_keypair)?;
+        self.validator.check_local(&op)?;
         
         channel.archive();
         self.operations.insert(op.op_id, op.clone());
@@ -420,6 +568,292 @@ impl ChannelManager {
         Ok(op)
     }
     
+    /// Delete a channel, tearing down its MLS group
+    /// 
+    /// The channel ID is tombstoned so later ops for it are refused.
+    pub fn delete_channel(
+        &mut self,
+        channel_id: ChannelId,
+        author: UserId,
+        author_keypair: &dyn crate::crypto::signing::Signer,
+        epoch: EpochId,
+        provider: &DescordProvider,
+    ) -> Result<CrdtOp> {
+        let channel = self.channels.get(&channel_id)
+            .ok_or_else(|| Error::NotFound(format!("Channel {:?} not found", channel_id)))?;
+        
+        let current_time = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_secs();
+        
+        let mut op = CrdtOp {
+            op_id: OpId(uuid::Uuid::nil()), // set by sign()
+            space_id: channel.space_id,
+            channel_id: Some(channel_id),
+            thread_id: None,
+            op_type: OpType::DeleteChannel,
+            prev_ops: vec![],
+            author,
+            epoch,
+            hlc: self.hlc.tick(),
+            timestamp: current_time,
+            signature: Signature([0u8; 64]),
+        };
+        
+        op.sign(author_keypair)?;
+        self.validator.check_local(&op)?;
+        
+        self.remove_channel(&channel_id, provider)?;
+        self.operations.insert(op.op_id, op.clone());
+        self.validator.apply_op(&op);
+        
+        Ok(op)
+    }
+    
+    /// Process a DeleteChannel operation from the network
+    /// 
+    /// The caller checks that the author may delete channels in the Space.
+    pub fn process_delete_channel(&mut self, op: &CrdtOp, provider: &DescordProvider) -> Result<()> {
+        match self.validator.validate(op, &self.operations) {
+            ValidationResult::Accept => {
+                if let OpType::DeleteChannel = &op.op_type {
+                    let channel_id = op.channel_id
+                        .ok_or_else(|| Error::InvalidOperation("Missing channel_id".to_string()))?;
+                    
+                    // Tombstone even if the channel never arrived here
+                    self.remove_channel(&channel_id, provider)?;
+                    
+                    self.operations.insert(op.op_id, op.clone());
+                    self.validator.apply_op(op);
+                    self.hlc.update(op.hlc);
+                    
+                    Ok(())
+                } else {
+                    Err(Error::InvalidOperation("Expected DeleteChannel operation".to_string()))
+                }
+            }
+            ValidationResult::Buffered(deps) => {
+                self.holdback.buffer(op.clone(), deps, op.timestamp)
+                    .map_err(|e| Error::Storage(e))?;
+                Ok(())
+            }
+            ValidationResult::Reject(reason) => {
+                Err(Error::InvalidOperation(format!("Operation rejected: {:?}", reason)))
+            }
+        }
+    }
+    
+    /// Whether a channel has been deleted
+    pub fn is_deleted(&self, channel_id: &ChannelId) -> bool {
+        self.deleted.contains(channel_id)
+    }
+    
+    /// Drop a channel, its follows and MLS group, and tombstone its ID
+    fn remove_channel(&mut self, channel_id: &ChannelId, provider: &DescordProvider) -> Result<()> {
+        self.deleted.insert(*channel_id);
+        if let Some(mut channel) = self.channels.remove(channel_id) {
+            if let Some(ids) = self.space_channels.get_mut(&channel.space_id) {
+                ids.retain(|id| id != channel_id);
+            }
+            channel.deleted = true;
+            self.tombstoned.insert(*channel_id, channel);
+        }
+        self.follows.retain(|(source, target), _| source != channel_id && target != channel_id);
+        if let Some(group) = self.mls_groups.remove(channel_id) {
+            group.delete(provider)?;
+        }
+        Ok(())
+    }
+    
+    /// Follow `source_channel`, forwarding its messages into `target_thread`
+    pub fn follow_channel(
+        &mut self,
+        source_channel: ChannelId,
+        target_channel: ChannelId,
+        target_thread: ThreadId,
+        author: UserId,
+        author_keypair: &dyn crate::crypto::signing::Signer,
+        epoch: EpochId,
+    ) -> Result<CrdtOp> {
+        if source_channel == target_channel {
+            return Err(Error::InvalidOperation("A channel cannot follow itself".to_string()));
+        }
+        if self.follows.contains_key(&(source_channel, target_channel)) {
+            return Err(Error::AlreadyExists("Channel is already followed".to_string()));
+        }
+        let channel = self.channels.get(&target_channel)
+            .ok_or_else(|| Error::NotFound(format!("Channel {:?} not found", target_channel)))?;
+        
+        let current_time = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_secs();
+        
+        let mut op = CrdtOp {
+            op_id: OpId(uuid::Uuid::nil()), // set by sign()
+            space_id: channel.space_id,
+            channel_id: Some(target_channel),
+            thread_id: None,
+            op_type: OpType::FollowChannel(OpPayload::FollowChannel {
+                source_channel,
+                target_thread,
+            }),
+            prev_ops: vec![],
+            author,
+            epoch,
+            hlc: self.hlc.tick(),
+            timestamp: current_time,
+            signature: Signature([0u8; 64]),
+        };
+        
+        op.sign(author_keypair)?;
+        self.validator.check_local(&op)?;
+        
+        self.follows.insert((source_channel, target_channel), ChannelFollow {
+            source_channel,
+            target_channel,
+            target_thread,
+            follower: author,
+        });
+        self.operations.insert(op.op_id, op.clone());
+        self.validator.apply_op(&op);
+        
+        Ok(op)
+    }
+    
+    /// Stop `target_channel` following `source_channel`
+    pub fn unfollow_channel(
+        &mut self,
+        source_channel: ChannelId,
+        target_channel: ChannelId,
+        author: UserId,
+        author_keypair: &dyn crate::crypto::signing::Signer,
+        epoch: EpochId,
+    ) -> Result<CrdtOp> {
+        if !self.follows.contains_key(&(source_channel, target_channel)) {
+            return Err(Error::NotFound("Channel is not followed".to_string()));
+        }
+        let channel = self.channels.get(&target_channel)
+            .ok_or_else(|| Error::NotFound(format!("Channel {:?} not found", target_channel)))?;
+        
+        let current_time = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_secs();
+        
+        let mut op = CrdtOp {
+            op_id: OpId(uuid::Uuid::nil()), // set by sign()
+            space_id: channel.space_id,
+            channel_id: Some(target_channel),
+            thread_id: None,
+            op_type: OpType::UnfollowChannel(OpPayload::UnfollowChannel { source_channel }),
+            prev_ops: vec![],
+            author,
+            epoch,
+            hlc: self.hlc.tick(),
+            timestamp: current_time,
+            signature: Signature([0u8; 64]),
+        };
+        
+        op.sign(author_keypair)?;
+        self.validator.check_local(&op)?;
+        
+        self.follows.remove(&(source_channel, target_channel));
+        self.operations.insert(op.op_id, op.clone());
+        self.validator.apply_op(&op);
+        
+        Ok(op)
+    }
+    
+    /// Process a FollowChannel operation from the network
+    pub fn process_follow_channel(&mut self, op: &CrdtOp) -> Result<()> {
+        match self.validator.validate(op, &self.operations) {
+            ValidationResult::Accept => {
+                if let OpType::FollowChannel(OpPayload::FollowChannel { source_channel, target_thread }) = &op.op_type {
+                    let target_channel = op.channel_id
+                        .ok_or_else(|| Error::InvalidOperation("Missing channel_id".to_string()))?;
+                    if *source_channel == target_channel {
+                        return Err(Error::InvalidOperation("A channel cannot follow itself".to_string()));
+                    }
+                    
+                    self.follows.insert((*source_channel, target_channel), ChannelFollow {
+                        source_channel: *source_channel,
+                        target_channel,
+                        target_thread: *target_thread,
+                        follower: op.author,
+                    });
+                    
+                    self.operations.insert(op.op_id, op.clone());
+                    self.validator.apply_op(op);
+                    self.hlc.update(op.hlc);
+                    
+                    Ok(())
+                } else {
+                    Err(Error::InvalidOperation("Expected FollowChannel operation".to_string()))
+                }
+            }
+            ValidationResult::Buffered(deps) => {
+                self.holdback.buffer(op.clone(), deps, op.timestamp)
+                    .map_err(|e| Error::Storage(e))?;
+                Ok(())
+            }
+            ValidationResult::Reject(reason) => {
+                Err(Error::InvalidOperation(format!("Operation rejected: {:?}", reason)))
+            }
+        }
+    }
+    
+    /// Process an UnfollowChannel operation from the network
+    pub fn process_unfollow_channel(&mut self, op: &CrdtOp) -> Result<()> {
+        match self.validator.validate(op, &self.operations) {
+            ValidationResult::Accept => {
+                if let OpType::UnfollowChannel(OpPayload::UnfollowChannel { source_channel }) = &op.op_type {
+                    let target_channel = op.channel_id
+                        .ok_or_else(|| Error::InvalidOperation("Missing channel_id".to_string()))?;
+                    self.follows.remove(&(*source_channel, target_channel));
+                    
+                    self.operations.insert(op.op_id, op.clone());
+                    self.validator.apply_op(op);
+                    self.hlc.update(op.hlc);
+                    
+                    Ok(())
+                } else {
+                    Err(Error::InvalidOperation("Expected UnfollowChannel operation".to_string()))
+                }
+            }
+            ValidationResult::Buffered(deps) => {
+                self.holdback.buffer(op.clone(), deps, op.timestamp)
+                    .map_err(|e| Error::Storage(e))?;
+                Ok(())
+            }
+            ValidationResult::Reject(reason) => {
+                Err(Error::InvalidOperation(format!("Operation rejected: {:?}", reason)))
+            }
+        }
+    }
+    
+    /// Follows announcing `source_channel` elsewhere
+    pub fn followers_of(&self, source_channel: &ChannelId) -> Vec<&ChannelFollow> {
+        self.follows.values()
+            .filter(|follow| follow.source_channel == *source_channel)
+            .collect()
+    }
+    
+    /// Follows set up by `follower`
+    pub fn follows_by(&self, follower: &UserId) -> Vec<&ChannelFollow> {
+        self.follows.values()
+            .filter(|follow| follow.follower == *follower)
+            .collect()
+    }
+    
+    /// Follows announcing into `target_channel`
+    pub fn follows_into(&self, target_channel: &ChannelId) -> Vec<&ChannelFollow> {
+        self.follows.values()
+            .filter(|follow| follow.target_channel == *target_channel)
+            .collect()
+    }
+    
     /// Get a Channel by ID
     pub fn get_channel(&self, channel_id: &ChannelId) -> Option<&Channel> {
         self.channels.get(channel_id)
@@ -433,6 +867,29 @@ impl ChannelManager {
             .unwrap_or_default()
     }
     
+    /// Get all Channels in a Space, with deleted ones (flagged `deleted`) if asked
+    pub fn list_channels_with_deleted(&self, space_id: &SpaceId, include_deleted: bool) -> Vec<&Channel> {
+        let mut channels = self.list_channels(space_id);
+        if include_deleted {
+            channels.extend(self.tombstoned.values().filter(|channel| channel.space_id == *space_id));
+        }
+        channels
+    }
+    
+    /// Forget every Channel in a Space, including operations and MLS groups
+    pub fn remove_space(&mut self, space_id: &SpaceId, provider: &DescordProvider) -> Result<Vec<ChannelId>> {
+        self.tombstoned.retain(|_, channel| channel.space_id != *space_id);
+        let channel_ids = self.space_channels.remove(space_id).unwrap_or_default();
+        for channel_id in &channel_ids {
+            self.channels.remove(channel_id);
+            if let Some(group) = self.mls_groups.remove(channel_id) {
+                group.delete(provider)?;
+            }
+        }
+        self.operations.retain(|_, op| op.space_id != *space_id);
+        Ok(channel_ids)
+    }
+    
     /// Get MLS group for a Channel
     pub fn get_mls_group(&self, channel_id: &ChannelId) -> Option<&MlsGroup> {
         self.mls_groups.get(channel_id)
@@ -448,6 +905,15 @@ impl ChannelManager {
         self.mls_groups.insert(channel_id, mls_group);
     }
     
+    /// Store a channel MLS group `user_id` joined by themselves (by external commit)
+    pub fn store_joined_mls_group(&mut self, channel_id: ChannelId, user_id: UserId, mls_group: MlsGroup) -> Result<()> {
+        let channel = self.channels.get_mut(&channel_id)
+            .ok_or_else(|| Error::NotFound(format!("Channel {:?} not found", channel_id)))?;
+        channel.add_member(user_id, Role::Member);
+        self.mls_groups.insert(channel_id, mls_group);
+        Ok(())
+    }
+
     /// Get mutable iterator over all channel MLS groups (for processing Commits)
     pub fn mls_groups_mut(&mut self) -> impl Iterator<Item = (&ChannelId, &mut MlsGroup)> {
         self.mls_groups.iter_mut()
@@ -0,0 +1,183 @@
+//! Public Space directory stored in the DHT
+//!
+//! Public Spaces advertise a small [`DirectoryEntry`] under a single
+//! well-known DHT key, so peers can browse and search them by name without
+//! having heard the original `CreateSpace` op. Private and Hidden Spaces
+//! never appear here.
+
+use crate::types::*;
+use crate::{Error, Result};
+use minicbor::{Decode, Encode};
+use serde::{Deserialize, Serialize};
+use sha2::{Sha256, Digest};
+
+/// Listing for one public Space
+#[derive(Clone, Debug, PartialEq, Eq, Encode, Decode, Serialize, Deserialize)]
+pub struct DirectoryEntry {
+    /// Space ID (used to join)
+    #[n(0)]
+    pub space_id: SpaceId,
+
+    /// Display name
+    #[n(1)]
+    pub name: String,
+
+    /// Optional description
+    #[n(2)]
+    pub description: Option<String>,
+
+    /// Member count at the time the entry was published
+    #[n(3)]
+    pub member_count: u32,
+
+    /// Topic tags (normalized)
+    #[n(4)]
+    pub tags: Vec<String>,
+
+    /// Optional category
+    #[n(5)]
+    pub category: Option<String>,
+}
+
+impl DirectoryEntry {
+    /// Build an entry for a Space, or `None` if it is not publicly discoverable
+    pub fn from_space(space: &crate::forum::Space) -> Option<Self> {
+        if !space.visibility.is_discoverable() {
+            return None;
+        }
+
+        Some(Self {
+            space_id: space.id,
+            name: space.name.clone(),
+            description: space.description.clone(),
+            member_count: space.member_count() as u32,
+            tags: space.tags.clone(),
+            category: space.category.clone(),
+        })
+    }
+
+    /// Case-insensitive name substring match (an empty query matches everything)
+    pub fn matches(&self, query: &str) -> bool {
+        self.name.to_lowercase().contains(&query.to_lowercase())
+    }
+
+    /// Whether the entry carries `tag` (compared after normalization)
+    pub fn has_tag(&self, tag: &str) -> bool {
+        let tag = tag.trim().to_lowercase();
+        self.tags.iter().any(|t| *t == tag)
+    }
+}
+
+/// All directory entries stored under the directory DHT key
+#[derive(Clone, Debug, Default, PartialEq, Eq, Encode, Decode)]
+pub struct SpaceDirectory {
+    /// Entries, at most one per Space
+    #[n(0)]
+    pub entries: Vec<DirectoryEntry>,
+}
+
+impl SpaceDirectory {
+    /// Insert an entry, replacing any previous entry for the same Space
+    pub fn upsert(&mut self, entry: DirectoryEntry) {
+        match self.entries.iter_mut().find(|e| e.space_id == entry.space_id) {
+            Some(existing) => *existing = entry,
+            None => self.entries.push(entry),
+        }
+    }
+
+    /// Merge another copy of the directory (e.g. a record from another peer)
+    pub fn merge(&mut self, other: SpaceDirectory) {
+        for entry in other.entries {
+            if !self.entries.iter().any(|e| e.space_id == entry.space_id) {
+                self.entries.push(entry);
+            }
+        }
+    }
+
+    /// Entries whose name contains `query` (and carry `tag`, if given), sorted by name
+    pub fn search(&self, query: &str, tag: Option<&str>) -> Vec<DirectoryEntry> {
+        let mut results: Vec<DirectoryEntry> = self.entries.iter()
+            .filter(|entry| entry.matches(query))
+            .filter(|entry| tag.map_or(true, |tag| entry.has_tag(tag)))
+            .cloned()
+            .collect();
+        results.sort_by(|a, b| a.name.cmp(&b.name).then(a.space_id.0.cmp(&b.space_id.0)));
+        results
+    }
+
+    /// Serialize to CBOR bytes for DHT storage
+    pub fn to_bytes(&self) -> Result<Vec<u8>> {
+        minicbor::to_vec(self)
+            .map_err(|e| Error::Serialization(format!("Failed to encode SpaceDirectory: {}", e)))
+    }
+
+    /// Deserialize from CBOR bytes
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self> {
+        minicbor::decode(bytes)
+            .map_err(|e| Error::Serialization(format!("Failed to decode SpaceDirectory: {}", e)))
+    }
+
+    /// DHT key shared by every node's copy of the directory
+    pub fn dht_key() -> Vec<u8> {
+        let mut hasher = Sha256::new();
+        hasher.update(b"DESCORD_SPACE_DIRECTORY_V1");
+        hasher.finalize().to_vec()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(name: &str) -> DirectoryEntry {
+        DirectoryEntry {
+            space_id: SpaceId::from_content(&UserId([1u8; 32]), name, 0),
+            name: name.to_string(),
+            description: None,
+            member_count: 1,
+            tags: vec![],
+            category: None,
+        }
+    }
+
+    #[test]
+    fn test_search_by_name_substring() {
+        let mut directory = SpaceDirectory::default();
+        directory.upsert(entry("Rust Gardeners"));
+        directory.upsert(entry("Book Club"));
+
+        let results = directory.search("garden", None);
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].name, "Rust Gardeners");
+        assert_eq!(directory.search("", None).len(), 2);
+    }
+
+    #[test]
+    fn test_search_filters_by_tag() {
+        let mut directory = SpaceDirectory::default();
+        let mut tagged = entry("Rust Gardeners");
+        tagged.tags = vec!["gardening".to_string(), "rust".to_string()];
+        directory.upsert(tagged);
+        directory.upsert(entry("Book Club"));
+
+        let results = directory.search("", Some(" Rust "));
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].name, "Rust Gardeners");
+        assert!(directory.search("book", Some("rust")).is_empty());
+    }
+
+    #[test]
+    fn test_upsert_replaces_and_round_trips() {
+        let mut directory = SpaceDirectory::default();
+        directory.upsert(entry("Book Club"));
+        let mut updated = entry("Book Club");
+        updated.member_count = 5;
+        directory.upsert(updated);
+
+        assert_eq!(directory.entries.len(), 1);
+        assert_eq!(directory.entries[0].member_count, 5);
+
+        let decoded = SpaceDirectory::from_bytes(&directory.to_bytes().unwrap()).unwrap();
+        assert_eq!(decoded, directory);
+    }
+}
@@ -0,0 +1,63 @@
+//! Link previews for messages
+//!
+//! Previews are derived data: a pluggable [`LinkPreviewProvider`] fetches
+//! them after a message is posted, and the result is attached with a
+//! separate `AttachLinkPreview` op that references the message. Nothing is
+//! fetched unless a provider has been installed on the client.
+
+use crate::storage::BlobHash;
+use minicbor::{Decode, Encode};
+use serde::{Deserialize, Serialize};
+use std::future::Future;
+use std::pin::Pin;
+
+/// Preview metadata for a URL in a message
+#[derive(Clone, Debug, PartialEq, Eq, Encode, Decode, Serialize, Deserialize)]
+pub struct LinkPreview {
+    /// URL the preview describes
+    #[n(0)]
+    pub url: String,
+
+    /// Page title
+    #[n(1)]
+    pub title: Option<String>,
+
+    /// Page description
+    #[n(2)]
+    pub description: Option<String>,
+
+    /// Preview image, stored as a blob
+    #[n(3)]
+    pub image_blob: Option<BlobHash>,
+}
+
+/// Future returned by [`LinkPreviewProvider::preview`]
+pub type PreviewFuture<'a> = Pin<Box<dyn Future<Output = Option<LinkPreview>> + Send + 'a>>;
+
+/// Source of link previews (e.g. an HTTP fetcher reading OpenGraph tags)
+pub trait LinkPreviewProvider: Send + Sync {
+    /// Build a preview for `url`, or `None` if there is nothing to show
+    fn preview<'a>(&'a self, url: &'a str) -> PreviewFuture<'a>;
+}
+
+/// First `http://` or `https://` URL in a message, without trailing punctuation
+pub fn first_url(content: &str) -> Option<&str> {
+    content.split_whitespace()
+        .map(|word| word.trim_start_matches(['(', '<', '"', '\'']))
+        .find(|word| word.starts_with("https://") || word.starts_with("http://"))
+        .map(|word| word.trim_end_matches(['.', ',', ';', ':', '!', '?', ')', '>', '"', '\'']))
+        .filter(|url| !url.ends_with("://"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_first_url() {
+        assert_eq!(first_url("see https://example.com/a."), Some("https://example.com/a"));
+        assert_eq!(first_url("(http://example.com) and https://other.org"), Some("http://example.com"));
+        assert_eq!(first_url("no links here, ftp://nope"), None);
+        assert_eq!(first_url("just https://"), None);
+    }
+}
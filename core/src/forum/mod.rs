@@ -7,10 +7,14 @@
 
 pub mod space;
 pub mod space_metadata;
+pub mod directory;
 pub mod channel;
 pub mod thread;
+pub mod link_preview;
 
 pub use space::{Space, SpaceManager};
 pub use space_metadata::{SpaceMetadata, EncryptedSpaceMetadata};
-pub use channel::{Channel, ChannelManager};
-pub use thread::{Thread, Message, ThreadManager};
+pub use directory::{DirectoryEntry, SpaceDirectory};
+pub use channel::{Channel, ChannelFollow, ChannelManager};
+pub use thread::{Thread, Message, ForwardSource, QuotedMessage, ThreadManager};
+pub use link_preview::{LinkPreview, LinkPreviewProvider, PreviewFuture};
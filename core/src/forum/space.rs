@@ -4,11 +4,11 @@
 //! Each Space has its own MLS group for E2E encryption.
 
 use crate::types::*;
-use crate::crdt::{CrdtOp, OpType, OpPayload, Hlc, HoldbackQueue, OpValidator, ValidationResult};
+use crate::crdt::{CrdtOp, OpType, OpPayload, Hlc, HoldbackQueue, OpLimits, OpValidator, StuckOp, ValidationResult};
 use crate::mls::{MlsGroup, MlsGroupConfig};
 use crate::mls::provider::DescordProvider;
-use crate::{Error, Result};
-use std::collections::HashMap;
+use crate::{Error, JoinError, Result};
+use std::collections::{HashMap, HashSet};
 
 /// A Space (top-level forum container)
 #[derive(Debug, Clone)]
@@ -34,14 +34,15 @@ pub struct Space {
     /// Default role for new members (like Discord's @everyone)
     pub default_role: RoleId,
     
-    /// DEPRECATED: Old members HashMap (kept for backward compatibility)
-    /// TODO: Remove after migration
-    #[deprecated(note = "Use member_roles instead")]
-    pub members: HashMap<UserId, Role>,
-    
     /// Visibility and discoverability settings
     pub visibility: SpaceVisibility,
     
+    /// Topic tags for discovery (normalized: lowercase, sorted, unique)
+    pub tags: Vec<String>,
+    
+    /// Optional category (e.g. "gaming")
+    pub category: Option<String>,
+    
     /// Membership mode (lightweight vs MLS encrypted)
     pub membership_mode: SpaceMembershipMode,
     
@@ -56,14 +57,26 @@ pub struct Space {
     
     /// Creation timestamp
     pub created_at: u64,
+    
+    /// Whether the owner archived the Space, making it read-only
+    pub archived: bool,
+    
+    /// Reactions members may use (None = any)
+    pub allowed_reactions: Option<Vec<String>>,
 }
 
 impl Space {
     /// Create default roles for a new space (Admin, Moderator, Member)
-    fn create_default_roles(owner: UserId) -> (HashMap<RoleId, SpaceRole>, HashMap<UserId, RoleId>, RoleId) {
-        let admin_role = SpaceRole::admin();
-        let mod_role = SpaceRole::moderator();
-        let member_role = SpaceRole::member();
+    ///
+    /// Role IDs are derived from the Space ID so that ops referencing a role
+    /// (e.g. role-scoped invites) resolve identically on every replica.
+    fn create_default_roles(space_id: SpaceId, owner: UserId) -> (HashMap<RoleId, SpaceRole>, HashMap<UserId, RoleId>, RoleId) {
+        let mut admin_role = SpaceRole::admin();
+        let mut mod_role = SpaceRole::moderator();
+        let mut member_role = SpaceRole::member();
+        for role in [&mut admin_role, &mut mod_role, &mut member_role] {
+            role.id = RoleId::derived(&space_id, &role.name);
+        }
         
         let admin_role_id = admin_role.id;
         let mod_role_id = mod_role.id;
@@ -81,6 +94,16 @@ impl Space {
         (roles, member_roles, member_role_id)
     }
     
+    /// Hand ownership to `owner`, moving the Admin role a losing claim granted
+    fn reassign_owner(&mut self, owner: UserId) {
+        let admin_role_id = RoleId::derived(&self.id, &SpaceRole::admin().name);
+        if self.member_roles.get(&self.owner) == Some(&admin_role_id) {
+            self.member_roles.remove(&self.owner);
+        }
+        self.member_roles.insert(owner, admin_role_id);
+        self.owner = owner;
+    }
+    
     /// Create a new Space
     pub fn new(
         id: SpaceId,
@@ -89,11 +112,7 @@ impl Space {
         owner: UserId,
         created_at: u64,
     ) -> Self {
-        let (roles, member_roles, default_role) = Self::create_default_roles(owner);
-        
-        // Create deprecated members HashMap for backward compatibility
-        let mut members = HashMap::new();
-        members.insert(owner, Role::Admin);
+        let (roles, member_roles, default_role) = Self::create_default_roles(id, owner);
         
         Self {
             id,
@@ -103,13 +122,16 @@ impl Space {
             roles,
             member_roles,
             default_role,
-            members,
             visibility: SpaceVisibility::default(),
+            tags: Vec::new(),
+            category: None,
             membership_mode: SpaceMembershipMode::default(),
             invites: HashMap::new(),
             invite_permissions: InvitePermissions::default(),
             epoch: EpochId(0),
             created_at,
+            archived: false,
+            allowed_reactions: None,
         }
     }
     
@@ -122,10 +144,7 @@ impl Space {
         visibility: SpaceVisibility,
         created_at: u64,
     ) -> Self {
-        let (roles, member_roles, default_role) = Self::create_default_roles(owner);
-        
-        let mut members = HashMap::new();
-        members.insert(owner, Role::Admin);
+        let (roles, member_roles, default_role) = Self::create_default_roles(id, owner);
         
         Self {
             id,
@@ -135,13 +154,16 @@ impl Space {
             roles,
             member_roles,
             default_role,
-            members,
             visibility,
+            tags: Vec::new(),
+            category: None,
             membership_mode: SpaceMembershipMode::default(),
             invites: HashMap::new(),
             invite_permissions: InvitePermissions::default(),
             epoch: EpochId(0),
             created_at,
+            archived: false,
+            allowed_reactions: None,
         }
     }
     
@@ -155,10 +177,7 @@ impl Space {
         membership_mode: SpaceMembershipMode,
         created_at: u64,
     ) -> Self {
-        let (roles, member_roles, default_role) = Self::create_default_roles(owner);
-        
-        let mut members = HashMap::new();
-        members.insert(owner, Role::Admin);
+        let (roles, member_roles, default_role) = Self::create_default_roles(id, owner);
         
         Self {
             id,
@@ -168,13 +187,16 @@ impl Space {
             roles,
             member_roles,
             default_role,
-            members,
             visibility,
+            tags: Vec::new(),
+            category: None,
             membership_mode,
             invites: HashMap::new(),
             invite_permissions: InvitePermissions::default(),
             epoch: EpochId(0),
             created_at,
+            archived: false,
+            allowed_reactions: None,
         }
     }
     
@@ -183,34 +205,108 @@ impl Space {
         self.visibility = visibility;
     }
     
+    /// Replace the Space's tags and category
+    /// 
+    /// Tags are normalized so every replica stores the same list for the same op.
+    pub fn set_tags(&mut self, tags: &[String], category: Option<String>) {
+        self.tags = Self::normalize_tags(tags);
+        self.category = category
+            .map(|c| c.trim().to_lowercase())
+            .filter(|c| !c.is_empty());
+    }
+    
+    /// Trim and lowercase tags, drop empty ones, then sort and dedupe
+    pub fn normalize_tags(tags: &[String]) -> Vec<String> {
+        let mut normalized: Vec<String> = tags.iter()
+            .map(|tag| tag.trim().to_lowercase())
+            .filter(|tag| !tag.is_empty())
+            .collect();
+        normalized.sort();
+        normalized.dedup();
+        normalized
+    }
+    
+    /// Trim reactions, drop empty ones, then sort and dedupe
+    /// 
+    /// Unlike tags they keep their case, since custom `:name:` emoji may
+    /// differ only in case.
+    pub fn normalize_reactions(reactions: &[String]) -> Vec<String> {
+        let mut normalized: Vec<String> = reactions.iter()
+            .map(|reaction| reaction.trim().to_string())
+            .filter(|reaction| !reaction.is_empty())
+            .collect();
+        normalized.sort();
+        normalized.dedup();
+        normalized
+    }
+    
+    /// Whether members may react with `emoji`
+    pub fn allows_reaction(&self, emoji: &str) -> bool {
+        self.allowed_reactions.as_ref()
+            .map_or(true, |allowed| allowed.iter().any(|reaction| reaction == emoji))
+    }
+    
     /// Add a member to the Space
+    /// 
+    /// The legacy role maps onto the matching default role; `Member` (or a
+    /// default role that was removed) maps onto `default_role`.
     pub fn add_member(&mut self, user_id: UserId, role: Role) {
-        self.members.insert(user_id, role);
+        let role_id = self.legacy_role_id(role);
+        self.member_roles.insert(user_id, role_id);
     }
     
     /// Remove a member from the Space
     pub fn remove_member(&mut self, user_id: &UserId) -> Option<Role> {
-        self.members.remove(user_id)
+        let role = self.get_role(user_id);
+        self.member_roles.remove(user_id);
+        role
     }
     
     /// Update a member's role
     pub fn update_role(&mut self, user_id: &UserId, new_role: Role) -> Result<()> {
-        if let Some(role) = self.members.get_mut(user_id) {
-            *role = new_role;
-            Ok(())
-        } else {
-            Err(Error::NotFound(format!("User {:?} not in Space", user_id)))
+        if !self.is_member(user_id) {
+            return Err(Error::NotFound(format!("User {:?} not in Space", user_id)));
         }
+        self.add_member(*user_id, new_role);
+        Ok(())
     }
     
     /// Check if a user is a member
     pub fn is_member(&self, user_id: &UserId) -> bool {
-        self.members.contains_key(user_id)
+        self.member_roles.contains_key(user_id)
     }
     
-    /// Get a user's role
-    pub fn get_role(&self, user_id: &UserId) -> Option<&Role> {
-        self.members.get(user_id)
+    /// Number of members
+    pub fn member_count(&self) -> usize {
+        self.member_roles.len()
+    }
+    
+    /// Get a user's role, as the legacy `Role` of their assigned role
+    pub fn get_role(&self, user_id: &UserId) -> Option<Role> {
+        let role_id = self.member_roles.get(user_id)?;
+        Some(self.roles.get(role_id).map(SpaceRole::legacy_role).unwrap_or(Role::Member))
+    }
+    
+    /// All members with their legacy `Role`, computed from `member_roles`
+    pub fn members(&self) -> HashMap<UserId, Role> {
+        self.member_roles.keys()
+            .filter_map(|user_id| self.get_role(user_id).map(|role| (*user_id, role)))
+            .collect()
+    }
+    
+    /// Role ID a legacy `Role` is stored as
+    fn legacy_role_id(&self, role: Role) -> RoleId {
+        let name = match role {
+            Role::Admin => "Admin",
+            Role::Moderator => "Moderator",
+            Role::Member => return self.default_role,
+        };
+        let role_id = RoleId::derived(&self.id, name);
+        if self.roles.contains_key(&role_id) {
+            role_id
+        } else {
+            self.default_role
+        }
     }
     
     /// Advance to next epoch
@@ -306,6 +402,20 @@ impl Space {
         assigner_position > target_position
     }
     
+    /// The non-revoked invite using `code`, if any
+    /// 
+    /// Codes are unique among non-revoked invites, so a revoked code can be reused.
+    pub fn active_invite_with_code(&self, code: &str) -> Option<&Invite> {
+        self.invites.values().find(|invite| invite.code == code && !invite.revoked)
+    }
+    
+    /// Role an invite grants on join, if its creator may still assign it
+    pub fn invite_role(&self, invite: &Invite) -> Option<RoleId> {
+        invite.role.filter(|role_id| {
+            self.roles.contains_key(role_id) && self.can_assign_role(&invite.creator, role_id)
+        })
+    }
+    
     /// Assign a role to a user
     pub fn assign_role(&mut self, user_id: UserId, role_id: RoleId) -> Result<()> {
         // Check role exists
@@ -316,20 +426,6 @@ impl Space {
         // Update role assignment
         self.member_roles.insert(user_id, role_id);
         
-        // Also update deprecated members HashMap for backward compatibility
-        let role = if let Some(r) = self.roles.get(&role_id) {
-            if r.permissions.is_admin() {
-                Role::Admin
-            } else if r.permissions.has(SpacePermissions::KICK_MEMBERS) {
-                Role::Moderator
-            } else {
-                Role::Member
-            }
-        } else {
-            Role::Member
-        };
-        self.members.insert(user_id, role);
-        
         Ok(())
     }
     
@@ -359,6 +455,9 @@ pub struct SpaceManager {
     
     /// All operations we've seen (for persistence)
     operations: HashMap<OpId, CrdtOp>,
+    
+    /// Tombstones for deleted spaces, so a replayed CreateSpace can't revive them
+    deleted: HashSet<SpaceId>,
 }
 
 impl SpaceManager {
@@ -370,8 +469,19 @@ impl SpaceManager {
             holdback: HoldbackQueue::new(),
             hlc: Hlc::now(),
             operations: HashMap::new(),
+            deleted: HashSet::new(),
         }
     }
+
+    /// Set the size limits applied to local and received operations
+    pub fn set_op_limits(&mut self, limits: OpLimits) {
+        self.validator.set_limits(limits);
+    }
+    
+    /// Operations held back waiting on ops we don't have yet
+    pub fn stuck_ops(&self, current_time: u64) -> Vec<StuckOp> {
+        self.holdback.stuck_ops(current_time)
+    }
     
     /// Create a new Space (as founder)
     pub fn create_space(
@@ -380,7 +490,7 @@ impl SpaceManager {
         name: String,
         description: Option<String>,
         creator: UserId,
-        creator_keypair: &crate::crypto::signing::Keypair,
+        creator_keypair: &dyn crate::crypto::signing::Signer,
         provider: &DescordProvider,
     ) -> Result<CrdtOp> {
         // Check if space already exists
@@ -390,10 +500,7 @@ impl SpaceManager {
         
         // Create MLS group for this space
         let mls_config = MlsGroupConfig::default();
-        let signer = openmls_basic_credential::SignatureKeyPair::new(
-            mls_config.ciphersuite.signature_algorithm()
-        ).map_err(|e| Error::Crypto(format!("Failed to create signer: {:?}", e)))?;
-        let signer = std::sync::Arc::new(signer); // Wrap in Arc
+        let signer = crate::mls::identity_signer(creator_keypair)?;
         
         let mls_group = MlsGroup::create(
             space_id,
@@ -419,7 +526,7 @@ impl SpaceManager {
         
         // Create CRDT operation
         let mut op = CrdtOp {
-            op_id: OpId(uuid::Uuid::new_v4()),
+            op_id: OpId(uuid::Uuid::nil()), // set by sign()
             space_id,
             channel_id: None,
             thread_id: None,
@@ -436,8 +543,8 @@ impl SpaceManager {
         };
         
         // Sign the operation
-        let signing_bytes = op.signing_bytes();
-        op.signature = Signature(creator_keypair.sign(&signing_bytes).0);
+        op.sign(creator_keypair)?;
+        self.validator.check_local(&op)?;
         
         // Apply locally
         self.spaces.insert(space_id, space);
@@ -456,7 +563,7 @@ impl SpaceManager {
         description: Option<String>,
         visibility: SpaceVisibility,
         creator: UserId,
-        creator_keypair: &crate::crypto::signing::Keypair,
+        creator_keypair: &dyn crate::crypto::signing::Signer,
         provider: &DescordProvider,
     ) -> Result<CrdtOp> {
         // Use default membership mode (MLS) for backwards compatibility
@@ -481,21 +588,21 @@ impl SpaceManager {
         visibility: SpaceVisibility,
         membership_mode: SpaceMembershipMode,
         creator: UserId,
-        creator_keypair: &crate::crypto::signing::Keypair,
+        creator_keypair: &dyn crate::crypto::signing::Signer,
         provider: &DescordProvider,
     ) -> Result<CrdtOp> {
         // Check if space already exists
         if self.spaces.contains_key(&space_id) {
             return Err(Error::AlreadyExists(format!("Space {:?} already exists", space_id)));
         }
+        if self.deleted.contains(&space_id) {
+            return Err(Error::Rejected(format!("Space {:?} was deleted", space_id)));
+        }
         
         // Conditionally create MLS group based on membership mode
         let mls_group = if membership_mode.uses_space_mls() {
             let mls_config = MlsGroupConfig::default();
-            let signer = openmls_basic_credential::SignatureKeyPair::new(
-                mls_config.ciphersuite.signature_algorithm()
-            ).map_err(|e| Error::Crypto(format!("Failed to create signer: {:?}", e)))?;
-            let signer = std::sync::Arc::new(signer);
+            let signer = crate::mls::identity_signer(creator_keypair)?;
             
             Some(MlsGroup::create(
                 space_id,
@@ -526,7 +633,7 @@ impl SpaceManager {
         
         // Create CRDT operation
         let mut op = CrdtOp {
-            op_id: OpId(uuid::Uuid::new_v4()),
+            op_id: OpId(uuid::Uuid::nil()), // set by sign()
             space_id,
             channel_id: None,
             thread_id: None,
@@ -543,8 +650,8 @@ impl SpaceManager {
         };
         
         // Sign the operation
-        let signing_bytes = op.signing_bytes();
-        op.signature = Signature(creator_keypair.sign(&signing_bytes).0);
+        op.sign(creator_keypair)?;
+        self.validator.check_local(&op)?;
         
         // Apply locally
         self.spaces.insert(space_id, space);
@@ -566,7 +673,7 @@ impl SpaceManager {
         space_id: SpaceId,
         visibility: SpaceVisibility,
         author: UserId,
-        author_keypair: &crate::crypto::signing::Keypair,
+        author_keypair: &dyn crate::crypto::signing::Signer,
     ) -> Result<CrdtOp> {
         // Check space exists
         let space = self.spaces.get_mut(&space_id)
@@ -587,7 +694,7 @@ impl SpaceManager {
             .as_secs();
         
         let mut op = CrdtOp {
-            op_id: OpId(uuid::Uuid::new_v4()),
+            op_id: OpId(uuid::Uuid::nil()), // set by sign()
             space_id,
             channel_id: None,
             thread_id: None,
@@ -603,8 +710,8 @@ impl SpaceManager {
         };
         
         // Sign the operation
-        let signing_bytes = op.signing_bytes();
-        op.signature = Signature(author_keypair.sign(&signing_bytes).0);
+        op.sign(author_keypair)?;
+        self.validator.check_local(&op)?;
         
         // Apply locally
         space.set_visibility(visibility);
@@ -649,22 +756,295 @@ impl SpaceManager {
         }
     }
     
+    /// Update a Space's tags and category (requires MANAGE_SPACE)
+    pub fn update_space_metadata(
+        &mut self,
+        space_id: SpaceId,
+        tags: Vec<String>,
+        category: Option<String>,
+        author: UserId,
+        author_keypair: &dyn crate::crypto::signing::Signer,
+    ) -> Result<CrdtOp> {
+        let space = self.spaces.get_mut(&space_id)
+            .ok_or_else(|| Error::NotFound(format!("Space {:?} not found", space_id)))?;
+        
+        if !space.has_permission(&author, |p| p.has(SpacePermissions::MANAGE_SPACE)) {
+            return Err(Error::Permission("MANAGE_SPACE permission required to edit space metadata".to_string()));
+        }
+        
+        let current_time = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_secs();
+        
+        let mut op = CrdtOp {
+            op_id: OpId(uuid::Uuid::nil()), // set by sign()
+            space_id,
+            channel_id: None,
+            thread_id: None,
+            op_type: OpType::UpdateSpaceMetadata(OpPayload::UpdateSpaceMetadata {
+                tags: tags.clone(),
+                category: category.clone(),
+            }),
+            prev_ops: vec![],
+            author,
+            epoch: space.epoch,
+            hlc: self.hlc.tick(),
+            timestamp: current_time,
+            signature: Signature([0u8; 64]),
+        };
+        
+        op.sign(author_keypair)?;
+        self.validator.check_local(&op)?;
+        
+        space.set_tags(&tags, category);
+        self.operations.insert(op.op_id, op.clone());
+        self.validator.apply_op(&op);
+        
+        Ok(op)
+    }
+    
+    /// Process an incoming UpdateSpaceMetadata operation
+    pub fn process_update_space_metadata(&mut self, op: &CrdtOp) -> Result<()> {
+        match self.validator.validate(op, &self.operations) {
+            ValidationResult::Accept => {
+                if let OpType::UpdateSpaceMetadata(OpPayload::UpdateSpaceMetadata { tags, category }) = &op.op_type {
+                    let space = self.spaces.get_mut(&op.space_id)
+                        .ok_or_else(|| Error::NotFound(format!("Space {:?} not found", op.space_id)))?;
+                    if !space.has_permission(&op.author, |p| p.has(SpacePermissions::MANAGE_SPACE)) {
+                        return Err(Error::Permission("MANAGE_SPACE permission required to edit space metadata".to_string()));
+                    }
+                    
+                    space.set_tags(tags, category.clone());
+                    self.operations.insert(op.op_id, op.clone());
+                    self.validator.apply_op(op);
+                    self.hlc.update(op.hlc);
+                    Ok(())
+                } else {
+                    Err(Error::InvalidOperation("Expected UpdateSpaceMetadata operation".to_string()))
+                }
+            }
+            ValidationResult::Buffered(deps) => {
+                self.holdback.buffer(op.clone(), deps, op.timestamp)
+                    .map_err(|e| Error::Storage(e))?;
+                Ok(())
+            }
+            ValidationResult::Reject(reason) => {
+                Err(Error::InvalidOperation(format!("Operation rejected: {:?}", reason)))
+            }
+        }
+    }
+    
+    /// Restrict the reactions members may use, or lift the restriction with `None`
+    pub fn update_space_reactions(
+        &mut self,
+        space_id: SpaceId,
+        allowed: Option<Vec<String>>,
+        author: UserId,
+        author_keypair: &dyn crate::crypto::signing::Signer,
+    ) -> Result<CrdtOp> {
+        let space = self.spaces.get_mut(&space_id)
+            .ok_or_else(|| Error::NotFound(format!("Space {:?} not found", space_id)))?;
+        
+        if !space.has_permission(&author, |p| p.has(SpacePermissions::MANAGE_SPACE)) {
+            return Err(Error::Permission("MANAGE_SPACE permission required to restrict reactions".to_string()));
+        }
+        let allowed = allowed.map(|reactions| Space::normalize_reactions(&reactions));
+        
+        let current_time = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_secs();
+        
+        let mut op = CrdtOp {
+            op_id: OpId(uuid::Uuid::nil()), // set by sign()
+            space_id,
+            channel_id: None,
+            thread_id: None,
+            op_type: OpType::UpdateSpaceReactions(OpPayload::UpdateSpaceReactions {
+                allowed: allowed.clone(),
+            }),
+            prev_ops: vec![],
+            author,
+            epoch: space.epoch,
+            hlc: self.hlc.tick(),
+            timestamp: current_time,
+            signature: Signature([0u8; 64]),
+        };
+        
+        op.sign(author_keypair)?;
+        self.validator.check_local(&op)?;
+        
+        space.allowed_reactions = allowed;
+        self.operations.insert(op.op_id, op.clone());
+        self.validator.apply_op(&op);
+        
+        Ok(op)
+    }
+    
+    /// Process an incoming UpdateSpaceReactions operation
+    pub fn process_update_space_reactions(&mut self, op: &CrdtOp) -> Result<()> {
+        match self.validator.validate(op, &self.operations) {
+            ValidationResult::Accept => {
+                if let OpType::UpdateSpaceReactions(OpPayload::UpdateSpaceReactions { allowed }) = &op.op_type {
+                    let space = self.spaces.get_mut(&op.space_id)
+                        .ok_or_else(|| Error::NotFound(format!("Space {:?} not found", op.space_id)))?;
+                    if !space.has_permission(&op.author, |p| p.has(SpacePermissions::MANAGE_SPACE)) {
+                        return Err(Error::Permission("MANAGE_SPACE permission required to restrict reactions".to_string()));
+                    }
+                    
+                    space.allowed_reactions = allowed.as_ref().map(|reactions| Space::normalize_reactions(reactions));
+                    self.operations.insert(op.op_id, op.clone());
+                    self.validator.apply_op(op);
+                    self.hlc.update(op.hlc);
+                    Ok(())
+                } else {
+                    Err(Error::InvalidOperation("Expected UpdateSpaceReactions operation".to_string()))
+                }
+            }
+            ValidationResult::Buffered(deps) => {
+                self.holdback.buffer(op.clone(), deps, op.timestamp)
+                    .map_err(|e| Error::Storage(e))?;
+                Ok(())
+            }
+            ValidationResult::Reject(reason) => {
+                Err(Error::InvalidOperation(format!("Operation rejected: {:?}", reason)))
+            }
+        }
+    }
+    
+    /// Update a role's color and hoist flag
+    /// 
+    /// Requires MANAGE_ROLES, and (except for the owner) the role must sit
+    /// below the author's own role.
+    pub fn update_role(
+        &mut self,
+        space_id: SpaceId,
+        role_id: RoleId,
+        color: Option<u32>,
+        hoisted: bool,
+        author: UserId,
+        author_keypair: &dyn crate::crypto::signing::Signer,
+    ) -> Result<CrdtOp> {
+        let space = self.spaces.get_mut(&space_id)
+            .ok_or_else(|| Error::NotFound(format!("Space {:?} not found", space_id)))?;
+        
+        if !space.roles.contains_key(&role_id) {
+            return Err(Error::NotFound(format!("Role {:?} not found", role_id)));
+        }
+        if !space.can_assign_role(&author, &role_id) {
+            return Err(Error::Permission("Cannot edit a role at or above your own".to_string()));
+        }
+        
+        let current_time = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_secs();
+        
+        let mut op = CrdtOp {
+            op_id: OpId(uuid::Uuid::nil()), // set by sign()
+            space_id,
+            channel_id: None,
+            thread_id: None,
+            op_type: OpType::UpdateRole(OpPayload::UpdateRole {
+                role_id,
+                color,
+                hoisted,
+            }),
+            prev_ops: vec![],
+            author,
+            epoch: space.epoch,
+            hlc: self.hlc.tick(),
+            timestamp: current_time,
+            signature: Signature([0u8; 64]),
+        };
+        
+        op.sign(author_keypair)?;
+        self.validator.check_local(&op)?;
+        
+        if let Some(role) = space.roles.get_mut(&role_id) {
+            role.color = color;
+            role.hoisted = hoisted;
+        }
+        self.operations.insert(op.op_id, op.clone());
+        self.validator.apply_op(&op);
+        
+        Ok(op)
+    }
+    
+    /// Process an incoming UpdateRole operation
+    pub fn process_update_role(&mut self, op: &CrdtOp) -> Result<()> {
+        match self.validator.validate(op, &self.operations) {
+            ValidationResult::Accept => {
+                if let OpType::UpdateRole(OpPayload::UpdateRole { role_id, color, hoisted }) = &op.op_type {
+                    let space = self.spaces.get_mut(&op.space_id)
+                        .ok_or_else(|| Error::NotFound(format!("Space {:?} not found", op.space_id)))?;
+                    if !space.can_assign_role(&op.author, role_id) {
+                        return Err(Error::Permission("Cannot edit a role at or above your own".to_string()));
+                    }
+                    let role = space.roles.get_mut(role_id)
+                        .ok_or_else(|| Error::NotFound(format!("Role {:?} not found", role_id)))?;
+                    
+                    role.color = *color;
+                    role.hoisted = *hoisted;
+                    self.operations.insert(op.op_id, op.clone());
+                    self.validator.apply_op(op);
+                    self.hlc.update(op.hlc);
+                    Ok(())
+                } else {
+                    Err(Error::InvalidOperation("Expected UpdateRole operation".to_string()))
+                }
+            }
+            ValidationResult::Buffered(deps) => {
+                self.holdback.buffer(op.clone(), deps, op.timestamp)
+                    .map_err(|e| Error::Storage(e))?;
+                Ok(())
+            }
+            ValidationResult::Reject(reason) => {
+                Err(Error::InvalidOperation(format!("Operation rejected: {:?}", reason)))
+            }
+        }
+    }
+    
     /// Process an incoming CreateSpace operation
     pub fn process_create_space(&mut self, op: &CrdtOp) -> Result<()> {
         // Validate the operation
         match self.validator.validate(op, &self.operations) {
             ValidationResult::Accept => {
+                if self.deleted.contains(&op.space_id) {
+                    return Err(Error::Rejected(format!("Space {:?} was deleted", op.space_id)));
+                }
+                
                 // Extract space details
                 if let OpType::CreateSpace(OpPayload::CreateSpace { name, description }) = &op.op_type {
-                    let space = Space::new(
-                        op.space_id,
-                        name.clone(),
-                        description.clone(),
-                        op.author,
-                        op.timestamp,
-                    );
+                    let origin = self.origin_op(&op.space_id)
+                        .map(|origin| (origin.hlc, origin.op_id.0));
+                    let wins = origin.map_or(true, |origin| (op.hlc, op.op_id.0) < origin);
+                    
+                    match self.spaces.get_mut(&op.space_id) {
+                        None => {
+                            let space = Space::new(
+                                op.space_id,
+                                name.clone(),
+                                description.clone(),
+                                op.author,
+                                op.timestamp,
+                            );
+                            self.spaces.insert(op.space_id, space);
+                        }
+                        // Known from the DHT or an earlier claim: keep its state
+                        // (including the epoch), but the lowest-HLC claim owns it
+                        Some(space) if wins => {
+                            space.name = name.clone();
+                            space.description = description.clone();
+                            space.reassign_owner(op.author);
+                            space.created_at = op.timestamp;
+                        }
+                        Some(_) => {}
+                    }
                     
-                    self.spaces.insert(op.space_id, space);
+                    // The validator keeps the winning claim's author as the
+                    // only creator, whichever claim arrived first
                     self.operations.insert(op.op_id, op.clone());
                     self.validator.apply_op(op);
                     self.hlc.update(op.hlc);
@@ -686,6 +1066,182 @@ impl SpaceManager {
         }
     }
     
+    /// Archive a Space (owner only), making it read-only
+    pub fn archive_space(
+        &mut self,
+        space_id: SpaceId,
+        author: UserId,
+        author_keypair: &dyn crate::crypto::signing::Signer,
+    ) -> Result<CrdtOp> {
+        self.check_writable(&space_id)?;
+        let space = self.spaces.get_mut(&space_id)
+            .ok_or_else(|| Error::NotFound(format!("Space {:?} not found", space_id)))?;
+        if space.owner != author {
+            return Err(Error::Permission("Only the owner can archive a space".to_string()));
+        }
+        
+        let current_time = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_secs();
+        
+        let mut op = CrdtOp {
+            op_id: OpId(uuid::Uuid::nil()), // set by sign()
+            space_id,
+            channel_id: None,
+            thread_id: None,
+            op_type: OpType::ArchiveSpace,
+            prev_ops: vec![],
+            author,
+            epoch: space.epoch,
+            hlc: self.hlc.tick(),
+            timestamp: current_time,
+            signature: Signature([0u8; 64]),
+        };
+        
+        op.sign(author_keypair)?;
+        self.validator.check_local(&op)?;
+        
+        space.archived = true;
+        self.operations.insert(op.op_id, op.clone());
+        self.validator.apply_op(&op);
+        
+        Ok(op)
+    }
+    
+    /// Process an incoming ArchiveSpace operation
+    pub fn process_archive_space(&mut self, op: &CrdtOp) -> Result<()> {
+        match self.validator.validate(op, &self.operations) {
+            ValidationResult::Accept => {
+                if let OpType::ArchiveSpace = &op.op_type {
+                    let space = self.spaces.get_mut(&op.space_id)
+                        .ok_or_else(|| Error::NotFound(format!("Space {:?} not found", op.space_id)))?;
+                    if space.owner != op.author {
+                        return Err(Error::Permission("Only the owner can archive a space".to_string()));
+                    }
+                    
+                    space.archived = true;
+                    self.operations.insert(op.op_id, op.clone());
+                    self.validator.apply_op(op);
+                    self.hlc.update(op.hlc);
+                    Ok(())
+                } else {
+                    Err(Error::InvalidOperation("Expected ArchiveSpace operation".to_string()))
+                }
+            }
+            ValidationResult::Buffered(deps) => {
+                self.holdback.buffer(op.clone(), deps, op.timestamp)
+                    .map_err(|e| Error::Storage(e))?;
+                Ok(())
+            }
+            ValidationResult::Reject(reason) => {
+                Err(Error::InvalidOperation(format!("Operation rejected: {:?}", reason)))
+            }
+        }
+    }
+    
+    /// Delete a Space (owner only)
+    /// 
+    /// Tombstones the Space ID; the caller purges its local data with
+    /// [`SpaceManager::remove_space`] and the other managers.
+    pub fn delete_space(
+        &mut self,
+        space_id: SpaceId,
+        author: UserId,
+        author_keypair: &dyn crate::crypto::signing::Signer,
+    ) -> Result<CrdtOp> {
+        let space = self.spaces.get(&space_id)
+            .ok_or_else(|| Error::NotFound(format!("Space {:?} not found", space_id)))?;
+        if space.owner != author {
+            return Err(Error::Permission("Only the owner can delete a space".to_string()));
+        }
+        
+        let current_time = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_secs();
+        
+        let mut op = CrdtOp {
+            op_id: OpId(uuid::Uuid::nil()), // set by sign()
+            space_id,
+            channel_id: None,
+            thread_id: None,
+            op_type: OpType::DeleteSpace,
+            prev_ops: vec![],
+            author,
+            epoch: space.epoch,
+            hlc: self.hlc.tick(),
+            timestamp: current_time,
+            signature: Signature([0u8; 64]),
+        };
+        
+        op.sign(author_keypair)?;
+        self.validator.check_local(&op)?;
+        
+        self.deleted.insert(space_id);
+        self.validator.apply_op(&op);
+        
+        Ok(op)
+    }
+    
+    /// Process an incoming DeleteSpace operation
+    /// 
+    /// As with [`SpaceManager::delete_space`], purging is left to the caller.
+    pub fn process_delete_space(&mut self, op: &CrdtOp) -> Result<()> {
+        match self.validator.validate(op, &self.operations) {
+            ValidationResult::Accept => {
+                if let OpType::DeleteSpace = &op.op_type {
+                    let space = self.spaces.get(&op.space_id)
+                        .ok_or_else(|| Error::NotFound(format!("Space {:?} not found", op.space_id)))?;
+                    if space.owner != op.author {
+                        return Err(Error::Permission("Only the owner can delete a space".to_string()));
+                    }
+                    
+                    self.deleted.insert(op.space_id);
+                    self.validator.apply_op(op);
+                    self.hlc.update(op.hlc);
+                    Ok(())
+                } else {
+                    Err(Error::InvalidOperation("Expected DeleteSpace operation".to_string()))
+                }
+            }
+            ValidationResult::Buffered(deps) => {
+                self.holdback.buffer(op.clone(), deps, op.timestamp)
+                    .map_err(|e| Error::Storage(e))?;
+                Ok(())
+            }
+            ValidationResult::Reject(reason) => {
+                Err(Error::InvalidOperation(format!("Operation rejected: {:?}", reason)))
+            }
+        }
+    }
+    
+    /// Whether a Space has been deleted
+    pub fn is_deleted(&self, space_id: &SpaceId) -> bool {
+        self.deleted.contains(space_id)
+    }
+    
+    /// Refuse writes to an archived or deleted Space
+    pub fn check_writable(&self, space_id: &SpaceId) -> Result<()> {
+        if self.deleted.contains(space_id) {
+            return Err(Error::Rejected(format!("Space {:?} was deleted", space_id)));
+        }
+        if self.spaces.get(space_id).is_some_and(|space| space.archived) {
+            return Err(Error::Rejected(format!("Space {:?} is archived", space_id)));
+        }
+        Ok(())
+    }
+    
+    /// Refuse a received op for an archived or deleted Space
+    /// 
+    /// An archived Space still accepts its deletion.
+    pub fn check_incoming(&self, op: &CrdtOp) -> Result<()> {
+        match op.op_type {
+            OpType::DeleteSpace if !self.deleted.contains(&op.space_id) => Ok(()),
+            _ => self.check_writable(&op.space_id),
+        }
+    }
+    
     /// Add a member to a Space
     pub fn add_member(
         &mut self,
@@ -693,7 +1249,7 @@ impl SpaceManager {
         user_id: UserId,
         role: Role,
         author: UserId,
-        author_keypair: &crate::crypto::signing::Keypair,
+        author_keypair: &dyn crate::crypto::signing::Signer,
     ) -> Result<CrdtOp> {
         // Check space exists
         let space = self.spaces.get_mut(&space_id)
@@ -714,7 +1270,7 @@ impl SpaceManager {
             .as_secs();
         
         let mut op = CrdtOp {
-            op_id: OpId(uuid::Uuid::new_v4()),
+            op_id: OpId(uuid::Uuid::nil()), // set by sign()
             space_id,
             channel_id: None,
             thread_id: None,
@@ -731,8 +1287,8 @@ impl SpaceManager {
         };
         
         // Sign the operation
-        let signing_bytes = op.signing_bytes();
-        op.signature = Signature(author_keypair.sign(&signing_bytes).0);
+        op.sign(author_keypair)?;
+        self.validator.check_local(&op)?;
         
         // Apply locally
         space.add_member(user_id, role);
@@ -768,7 +1324,7 @@ impl SpaceManager {
             provider,
         )?;
         
-        println!("✓ MLS group updated - new epoch: {}", mls_group.epoch().0);
+        tracing::info!("MLS group updated - new epoch: {}", mls_group.epoch().0);
         
         Ok((commit_msg, welcome_msg))
     }
@@ -781,7 +1337,7 @@ impl SpaceManager {
         space_id: SpaceId,
         user_id: UserId,
         author: UserId,
-        author_keypair: &crate::crypto::signing::Keypair,
+        author_keypair: &dyn crate::crypto::signing::Signer,
         provider: &DescordProvider,
     ) -> Result<(CrdtOp, Option<openmls::framing::MlsMessageOut>)> {
         // Check space exists
@@ -802,7 +1358,7 @@ impl SpaceManager {
         }
         
         // Check target user is actually a member
-        if !space.members.contains_key(&user_id) {
+        if !space.is_member(&user_id) {
             return Err(Error::NotFound(format!("User {:?} not a member of Space", user_id)));
         }
         
@@ -813,7 +1369,7 @@ impl SpaceManager {
             .as_secs();
         
         let mut op = CrdtOp {
-            op_id: OpId(uuid::Uuid::new_v4()),
+            op_id: OpId(uuid::Uuid::nil()), // set by sign()
             space_id,
             channel_id: None,
             thread_id: None,
@@ -830,8 +1386,8 @@ impl SpaceManager {
         };
         
         // Sign the operation
-        let signing_bytes = op.signing_bytes();
-        op.signature = Signature(author_keypair.sign(&signing_bytes).0);
+        op.sign(author_keypair)?;
+        self.validator.check_local(&op)?;
         
         // Apply locally - remove from Space
         space.remove_member(&user_id);
@@ -841,19 +1397,18 @@ impl SpaceManager {
             // Remove member and rotate keys
             match mls_group.remove_member_with_key_rotation(&user_id, &author, provider) {
                 Ok(commit) => {
-                    println!("✓ MLS keys rotated - removed member can't decrypt future messages");
+                    tracing::info!("MLS keys rotated - removed member can't decrypt future messages");
                     Some(commit)
                 }
                 Err(e) => {
-                    eprintln!("⚠ Warning: MLS key rotation failed: {}", e);
-                    eprintln!("  Removed member may still be able to decrypt new messages");
+                    // The removed member may still be able to decrypt new messages
+                    tracing::warn!(space_id = %space_id.short(), error = %e, "MLS key rotation failed");
                     // Continue anyway - the member is still removed from the Space
                     None
                 }
             }
         } else {
-            eprintln!("⚠ Warning: No MLS group found for Space");
-            eprintln!("  This Space may not have E2E encryption enabled");
+            tracing::debug!(space_id = %space_id.short(), "No MLS group for Space, skipping key rotation");
             None
         };
         
@@ -873,16 +1428,38 @@ impl SpaceManager {
     /// This is used when joining a Space while the creator is offline.
     /// The Space metadata is fetched from DHT, but we don't have MLS keys yet.
     /// The user won't be able to decrypt messages until an admin adds them to the MLS group.
+    /// 
+    /// If the Space is already known, the highest epoch is kept. The owner
+    /// comes from its `CreateSpace` op when we have one; between DHT records
+    /// alone, the earliest `created_at` (then lowest owner ID) wins, so
+    /// replicas agree whatever order they learn of a Space in.
     pub fn add_space_from_dht(&mut self, space: Space) {
         let space_id = space.id;
+        let has_origin = self.origin_op(&space_id).is_some();
         
-        // Only add if it doesn't exist
-        if !self.spaces.contains_key(&space_id) {
-            self.spaces.insert(space_id, space);
-            // Note: No MLS group yet - will be initialized when we receive Welcome message
+        match self.spaces.get_mut(&space_id) {
+            None => {
+                self.spaces.insert(space_id, space);
+                // Note: No MLS group yet - will be initialized when we receive Welcome message
+            }
+            Some(existing) => {
+                existing.epoch = existing.epoch.max(space.epoch);
+                if !has_origin && (space.created_at, space.owner.0) < (existing.created_at, existing.owner.0) {
+                    existing.reassign_owner(space.owner);
+                    existing.created_at = space.created_at;
+                }
+            }
         }
     }
     
+    /// The `CreateSpace` op that fixes a Space's owner: the lowest HLC, with
+    /// the op ID breaking ties
+    fn origin_op(&self, space_id: &SpaceId) -> Option<&CrdtOp> {
+        self.operations.values()
+            .filter(|op| op.space_id == *space_id && matches!(op.op_type, OpType::CreateSpace(_)))
+            .min_by_key(|op| (op.hlc, op.op_id.0))
+    }
+    
     /// Get all Spaces
     pub fn list_spaces(&self) -> Vec<&Space> {
         self.spaces.values().collect()
@@ -903,6 +1480,16 @@ impl SpaceManager {
         self.mls_groups.insert(space_id, mls_group);
     }
     
+    /// Forget a Space locally: its state, operations and MLS group
+    pub fn remove_space(&mut self, space_id: &SpaceId, provider: &DescordProvider) -> Result<Option<Space>> {
+        let space = self.spaces.remove(space_id);
+        self.operations.retain(|_, op| op.space_id != *space_id);
+        if let Some(group) = self.mls_groups.remove(space_id) {
+            group.delete(provider)?;
+        }
+        Ok(space)
+    }
+    
     /// Get mutable iterator over all MLS groups (for processing Commits)
     pub fn mls_groups_mut(&mut self) -> impl Iterator<Item = (&SpaceId, &mut MlsGroup)> {
         self.mls_groups.iter_mut()
@@ -927,42 +1514,53 @@ impl SpaceManager {
         &mut self,
         space_id: SpaceId,
         creator: UserId,
-        creator_keypair: &crate::crypto::signing::Keypair,
+        creator_keypair: &dyn crate::crypto::signing::Signer,
         max_uses: Option<u32>,
         max_age_hours: Option<u32>,
+        role: Option<RoleId>,
+        custom_code: Option<String>,
     ) -> Result<CrdtOp> {
-        println!("🎫 [CREATE_INVITE] START");
-        println!("   Space: {}", hex::encode(&space_id.0[..8]));
-        println!("   Creator: {}", hex::encode(&creator.as_bytes()[..8]));
-        
         let space = self.spaces.get(&space_id)
-            .ok_or_else(|| {
-                println!("✗ [CREATE_INVITE] Space not found: {}", hex::encode(&space_id.0[..8]));
-                Error::NotFound(format!("Space {:?} not found", space_id))
-            })?;
-        
-        println!("✓ [CREATE_INVITE] Space found: {}", space.name);
+            .ok_or_else(|| Error::NotFound(format!("Space {:?} not found", space_id)))?;
         
         // Check permissions
         let creator_role = space.get_role(&creator)
-            .ok_or_else(|| {
-                println!("✗ [CREATE_INVITE] User not a member of space");
-                Error::Rejected("Not a member of the space".to_string())
-            })?;
-        
-        println!("✓ [CREATE_INVITE] User role: {:?}", creator_role);
-        
-        let can_create = Invite::can_create(*creator_role, &space.invite_permissions);
-        println!("   Permission check: can_create={}", can_create);
+            .ok_or_else(|| Error::Rejected("Not a member of the space".to_string()))?;
         
-        if !can_create {
-            println!("✗ [CREATE_INVITE] Permission denied");
+        if !Invite::can_create(creator_role, &space.invite_permissions) {
             return Err(Error::Rejected(
                 "Insufficient permissions to create invites".to_string()
             ));
         }
         
-        println!("✓ [CREATE_INVITE] Permission granted");
+        // A role-scoped invite may only grant roles below the creator's own
+        if let Some(role_id) = &role {
+            if !space.roles.contains_key(role_id) {
+                return Err(Error::NotFound(format!("Role {:?} not found", role_id)));
+            }
+            if !space.can_assign_role(&creator, role_id) {
+                return Err(Error::Rejected(
+                    "Cannot create an invite for a role at or above your own".to_string()
+                ));
+            }
+        }
+        
+        // Custom codes must be well-formed and not shadow another active invite
+        let code = match custom_code {
+            Some(code) => {
+                if !Invite::is_valid_code(&code) {
+                    return Err(Error::InvalidOperation(format!(
+                        "Invalid invite code '{}': use 1-{} letters, digits, '-' or '_'",
+                        code, MAX_INVITE_CODE_LEN
+                    )));
+                }
+                if space.active_invite_with_code(&code).is_some() {
+                    return Err(Error::AlreadyExists(format!("Invite code '{}' is already in use", code)));
+                }
+                code
+            }
+            None => Self::generate_invite_code(),
+        };
         
         // Create invite
         let current_time = std::time::SystemTime::now()
@@ -978,17 +1576,20 @@ impl SpaceManager {
             id: InviteId(uuid::Uuid::new_v4()),
             space_id,
             creator,
-            code: Self::generate_invite_code(),
+            code,
             max_uses,
             expires_at,
             uses: 0,
             created_at: current_time,
             revoked: false,
+            role,
+            used_by: Vec::new(),
+            relay_hints: Vec::new(),
         };
         
         // Create CRDT operation
         let mut op = CrdtOp {
-            op_id: OpId(uuid::Uuid::new_v4()),
+            op_id: OpId(uuid::Uuid::nil()), // set by sign()
             space_id,
             channel_id: None,
             thread_id: None,
@@ -1004,8 +1605,8 @@ impl SpaceManager {
         };
         
         // Sign the operation
-        let signing_bytes = op.signing_bytes();
-        op.signature = Signature(creator_keypair.sign(&signing_bytes).0);
+        op.sign(creator_keypair)?;
+        self.validator.check_local(&op)?;
         
         // Apply locally
         let space = self.spaces.get_mut(&space_id).unwrap();
@@ -1013,9 +1614,7 @@ impl SpaceManager {
         self.operations.insert(op.op_id, op.clone());
         self.validator.apply_op(&op);
         
-        println!("✓ [CREATE_INVITE] Invite created successfully");
-        println!("   Invite code: {}", invite.code);
-        println!("   Invite ID: {}", invite.id.0);
+        tracing::debug!(space_id = %space_id.short(), invite_id = %invite.id.0, "Created invite");
         
         Ok(op)
     }
@@ -1026,7 +1625,7 @@ impl SpaceManager {
         space_id: SpaceId,
         invite_id: InviteId,
         revoker: UserId,
-        revoker_keypair: &crate::crypto::signing::Keypair,
+        revoker_keypair: &dyn crate::crypto::signing::Signer,
     ) -> Result<CrdtOp> {
         let space = self.spaces.get(&space_id)
             .ok_or_else(|| Error::NotFound(format!("Space {:?} not found", space_id)))?;
@@ -1051,7 +1650,7 @@ impl SpaceManager {
             .as_secs();
         
         let mut op = CrdtOp {
-            op_id: OpId(uuid::Uuid::new_v4()),
+            op_id: OpId(uuid::Uuid::nil()), // set by sign()
             space_id,
             channel_id: None,
             thread_id: None,
@@ -1067,8 +1666,8 @@ impl SpaceManager {
         };
         
         // Sign the operation
-        let signing_bytes = op.signing_bytes();
-        op.signature = Signature(revoker_keypair.sign(&signing_bytes).0);
+        op.sign(revoker_keypair)?;
+        self.validator.check_local(&op)?;
         
         // Apply locally
         let space = self.spaces.get_mut(&space_id).unwrap();
@@ -1087,15 +1686,16 @@ impl SpaceManager {
         space_id: SpaceId,
         code: String,
         joiner: UserId,
-        joiner_keypair: &crate::crypto::signing::Keypair,
+        joiner_keypair: &dyn crate::crypto::signing::Signer,
     ) -> Result<CrdtOp> {
         let space = self.spaces.get(&space_id)
-            .ok_or_else(|| Error::NotFound(format!("Space {:?} not found", space_id)))?;
+            .ok_or(JoinError::SpaceNotFound(space_id))?;
         
-        // Find invite by code
-        let invite = space.invites.values()
-            .find(|inv| inv.code == code && inv.space_id == space_id)
-            .ok_or_else(|| Error::NotFound("Invalid invite code".to_string()))?;
+        // Find invite by code, preferring the active one over revoked namesakes
+        let invite = space.active_invite_with_code(&code)
+            .or_else(|| space.invites.values().find(|inv| inv.code == code))
+            .filter(|inv| inv.space_id == space_id)
+            .ok_or(JoinError::InvalidCode)?;
         
         // Validate invite
         let current_time = std::time::SystemTime::now()
@@ -1104,25 +1704,27 @@ impl SpaceManager {
             .as_secs();
         
         if !invite.is_valid(current_time) {
-            return Err(Error::Rejected("Invite is no longer valid".to_string()));
+            return Err(JoinError::InviteNoLongerValid.into());
         }
         
         // Check if already a member
         if space.is_member(&joiner) {
-            return Err(Error::AlreadyExists("Already a member of this space".to_string()));
+            return Err(JoinError::AlreadyMember.into());
         }
         
         let invite_id = invite.id;
+        let role = space.invite_role(invite);
         
         // Create CRDT operation for using the invite
         let mut op = CrdtOp {
-            op_id: OpId(uuid::Uuid::new_v4()),
+            op_id: OpId(uuid::Uuid::nil()), // set by sign()
             space_id,
             channel_id: None,
             thread_id: None,
             op_type: OpType::UseInvite(OpPayload::UseInvite {
                 invite_id,
                 code: code.clone(),
+                role,
             }),
             prev_ops: vec![],
             author: joiner,
@@ -1133,17 +1735,21 @@ impl SpaceManager {
         };
         
         // Sign the operation
-        let signing_bytes = op.signing_bytes();
-        op.signature = Signature(joiner_keypair.sign(&signing_bytes).0);
+        op.sign(joiner_keypair)?;
+        self.validator.check_local(&op)?;
         
         // Apply locally
         let space = self.spaces.get_mut(&space_id).unwrap();
-        // Increment invite use count
+        // Increment invite use count and record who joined
         if let Some(invite) = space.invites.get_mut(&invite_id) {
             invite.uses += 1;
+            invite.record_use(joiner, current_time);
         }
-        // Add member with default role
+        // Add member with default role, or the role the invite grants
         space.add_member(joiner, Role::Member);
+        if let Some(role_id) = role {
+            space.assign_role(joiner, role_id)?;
+        }
         self.operations.insert(op.op_id, op.clone());
         self.validator.apply_op(&op);
         
@@ -1165,9 +1771,69 @@ impl SpaceManager {
             .and_then(|space| space.invites.get(invite_id))
     }
     
+    /// Who joined through an invite, for admins and the invite's creator
+    pub fn invite_usage(
+        &self,
+        space_id: &SpaceId,
+        invite_id: &InviteId,
+        requester: &UserId,
+    ) -> Result<Vec<(UserId, u64)>> {
+        let space = self.spaces.get(space_id)
+            .ok_or_else(|| Error::NotFound(format!("Space {:?} not found", space_id)))?;
+        let invite = space.invites.get(invite_id)
+            .ok_or_else(|| Error::NotFound(format!("Invite {:?} not found", invite_id)))?;
+        
+        let is_admin = space.get_role(requester).is_some_and(|role| role.is_admin());
+        if !is_admin && invite.creator != *requester {
+            return Err(Error::Permission(
+                "Only admins or the invite creator can view invite usage".to_string()
+            ));
+        }
+        
+        Ok(invite.used_by.clone())
+    }
+    
+    /// HLC and op ID of the op that created an invite, used to order duplicates
+    fn invite_order(&self, invite_id: &InviteId) -> Option<(Hlc, uuid::Uuid)> {
+        self.operations.values().find_map(|op| match &op.op_type {
+            OpType::CreateInvite(OpPayload::CreateInvite { invite }) if invite.id == *invite_id => {
+                Some((op.hlc, op.op_id.0))
+            }
+            _ => None,
+        })
+    }
+    
     /// Process a remote CreateInvite operation
+    /// 
+    /// Invite codes are unique among active invites. When two replicas create
+    /// the same code concurrently, the invite created first by HLC (op ID as
+    /// tiebreak) wins on every replica and the later one is rejected.
     pub fn process_create_invite(&mut self, op: &CrdtOp) -> Result<()> {
         if let OpType::CreateInvite(OpPayload::CreateInvite { invite }) = &op.op_type {
+            if !Invite::is_valid_code(&invite.code) {
+                return Err(Error::Rejected(format!("Malformed invite code '{}'", invite.code)));
+            }
+            
+            // Resolve a clash with an existing invite using the same code
+            let clashing = self.spaces.get(&op.space_id)
+                .and_then(|space| space.active_invite_with_code(&invite.code))
+                .filter(|existing| existing.id != invite.id)
+                .map(|existing| existing.id);
+            if let Some(existing_id) = clashing {
+                match self.invite_order(&existing_id) {
+                    Some(existing_order) if existing_order < (op.hlc, op.op_id.0) => {
+                        return Err(Error::AlreadyExists(format!(
+                            "Invite code '{}' is already in use", invite.code
+                        )));
+                    }
+                    _ => {
+                        if let Some(space) = self.spaces.get_mut(&op.space_id) {
+                            space.invites.remove(&existing_id);
+                        }
+                    }
+                }
+            }
+            
             // Validate the operation
             match self.validator.validate(op, &self.operations) {
                 ValidationResult::Accept => {
@@ -1223,18 +1889,28 @@ impl SpaceManager {
     
     /// Process a remote UseInvite operation
     pub fn process_use_invite(&mut self, op: &CrdtOp) -> Result<()> {
-        if let OpType::UseInvite(OpPayload::UseInvite { invite_id, .. }) = &op.op_type {
+        if let OpType::UseInvite(OpPayload::UseInvite { invite_id, role, .. }) = &op.op_type {
             // Validate the operation
             match self.validator.validate(op, &self.operations) {
                 ValidationResult::Accept => {
                     // Apply the operation
                     if let Some(space) = self.spaces.get_mut(&op.space_id) {
-                        // Increment invite use count
+                        // Only honour a role the invite itself grants, checked
+                        // against the creator's hierarchy on this replica
+                        let granted_role = space.invites.get(invite_id)
+                            .and_then(|invite| space.invite_role(invite))
+                            .filter(|granted| Some(*granted) == *role);
+                        
+                        // Increment invite use count and record who joined
                         if let Some(invite) = space.invites.get_mut(invite_id) {
                             invite.uses += 1;
+                            invite.record_use(op.author, op.timestamp);
                         }
                         // Add member
                         space.add_member(op.author, Role::Member);
+                        if let Some(role_id) = granted_role {
+                            space.assign_role(op.author, role_id)?;
+                        }
                         self.operations.insert(op.op_id, op.clone());
                         self.validator.apply_op(op);
                     }
@@ -1319,7 +1995,65 @@ mod tests {
         assert_eq!(space.name, "Test Space");
         assert_eq!(space.owner, creator);
         assert!(space.is_member(&creator));
-        assert_eq!(space.get_role(&creator), Some(&Role::Admin));
+        assert_eq!(space.get_role(&creator), Some(Role::Admin));
+    }
+    
+    #[test]
+    fn test_conflicting_space_origins_converge() {
+        let provider = create_provider();
+        let space_id = SpaceId::new();
+        let alice_keypair = crate::crypto::signing::Keypair::generate();
+        let bob_keypair = crate::crypto::signing::Keypair::generate();
+        let mallory = crate::crypto::signing::Keypair::generate().user_id();
+        
+        // Two independent claims on the same Space ID
+        let alice_op = SpaceManager::new()
+            .create_space(space_id, "Alice's".to_string(), None, alice_keypair.user_id(), &alice_keypair, &provider)
+            .unwrap();
+        let bob_op = SpaceManager::new()
+            .create_space(space_id, "Bob's".to_string(), None, bob_keypair.user_id(), &bob_keypair, &provider)
+            .unwrap();
+        let winning_op = [&alice_op, &bob_op].into_iter()
+            .min_by_key(|op| (op.hlc, op.op_id.0))
+            .unwrap();
+        let winner = winning_op.author;
+        let loser = if winner == alice_op.author { bob_op.author } else { alice_op.author };
+        let winning_name = match &winning_op.op_type {
+            OpType::CreateSpace(OpPayload::CreateSpace { name, .. }) => name.clone(),
+            _ => unreachable!(),
+        };
+        
+        // A DHT record further along, claiming yet another owner
+        let mut record = Space::new(space_id, "From DHT".to_string(), None, mallory, 0);
+        record.epoch = EpochId(3);
+        
+        let mut replica_a = SpaceManager::new();
+        replica_a.add_space_from_dht(record.clone());
+        replica_a.process_create_space(&bob_op).unwrap();
+        replica_a.process_create_space(&alice_op).unwrap();
+        
+        let mut replica_b = SpaceManager::new();
+        replica_b.process_create_space(&alice_op).unwrap();
+        replica_b.process_create_space(&bob_op).unwrap();
+        replica_b.add_space_from_dht(record);
+        
+        let a = replica_a.get_space(&space_id).unwrap();
+        let b = replica_b.get_space(&space_id).unwrap();
+        assert_eq!(a.owner, winner);
+        assert_eq!(b.owner, winner);
+        assert_eq!(a.epoch, EpochId(3));
+        assert_eq!(b.epoch, EpochId(3));
+        for space in [a, b] {
+            assert_eq!(space.name, winning_name);
+            assert_eq!(space.get_role(&winner), Some(Role::Admin));
+            assert_ne!(space.get_role(&loser), Some(Role::Admin));
+            assert_ne!(space.get_role(&mallory), Some(Role::Admin));
+        }
+        for replica in [&replica_a, &replica_b] {
+            assert_eq!(replica.validator.member_role(&space_id, &winner), Some(Role::Admin));
+            assert_eq!(replica.validator.member_role(&space_id, &loser), None);
+            assert_eq!(replica.validator.member_role(&space_id, &mallory), None);
+        }
     }
     
     #[test]
@@ -1353,7 +2087,7 @@ mod tests {
         
         let space = manager.get_space(&space_id).unwrap();
         assert!(space.is_member(&new_member));
-        assert_eq!(space.get_role(&new_member), Some(&Role::Member));
+        assert_eq!(space.get_role(&new_member), Some(Role::Member));
     }
     
     #[test]
@@ -56,31 +56,39 @@ pub struct SpaceMetadata {
     /// Ed25519 signature (owner signs the metadata)
     #[n(9)]
     pub signature: Signature,
+    
+    /// Topic tags (normalized)
+    #[n(10)]
+    pub tags: Vec<String>,
+    
+    /// Optional category
+    #[n(11)]
+    pub category: Option<String>,
 }
 
 impl SpaceMetadata {
     /// Create metadata from a Space
-    pub fn from_space(space: &crate::forum::space::Space, keypair: &ed25519_dalek::SigningKey) -> Self {
+    pub fn from_space(space: &crate::forum::space::Space, signer: &dyn crate::crypto::signing::Signer) -> Result<Self> {
         let mut metadata = Self {
             id: space.id,
             name: space.name.clone(),
             description: space.description.clone(),
             owner: space.owner,
             visibility: space.visibility,
-            initial_members: space.members.clone(),
+            initial_members: space.members(),
             invite_permissions: space.invite_permissions.clone(),
             epoch: space.epoch,
             created_at: space.created_at,
             signature: Signature([0u8; 64]), // Temporary
+            tags: space.tags.clone(),
+            category: space.category.clone(),
         };
         
         // Sign the metadata
         let signing_bytes = metadata.signing_bytes();
-        use ed25519_dalek::Signer;
-        let sig = keypair.sign(&signing_bytes);
-        metadata.signature = Signature(sig.to_bytes());
+        metadata.signature = signer.sign(&signing_bytes)?;
         
-        metadata
+        Ok(metadata)
     }
     
     /// Get bytes to sign (all fields except signature)
@@ -95,6 +103,13 @@ impl SpaceMetadata {
         buf.extend_from_slice(&[self.visibility as u8]);
         buf.extend_from_slice(&self.epoch.0.to_le_bytes());
         buf.extend_from_slice(&self.created_at.to_le_bytes());
+        for tag in &self.tags {
+            buf.extend_from_slice(tag.as_bytes());
+            buf.push(0);
+        }
+        if let Some(category) = &self.category {
+            buf.extend_from_slice(category.as_bytes());
+        }
         buf
     }
     
@@ -143,19 +158,46 @@ pub struct EncryptedSpaceMetadata {
     /// Visibility (plaintext to determine if decryption key should be shared)
     #[n(3)]
     pub visibility: SpaceVisibility,
+    
+    /// MLS epoch whose key sealed the metadata, or `None` for the Space ID key
+    #[n(4)]
+    pub key_epoch: Option<EpochId>,
 }
 
 impl EncryptedSpaceMetadata {
+    /// Seal Space metadata for the Space's members under its current epoch
+    /// 
+    /// Lightweight Spaces (`SpaceKeys::Public`) use the Space ID key.
+    pub fn seal(metadata: &SpaceMetadata, keys: &crate::mls::SpaceKeys) -> Result<Self> {
+        match keys.sealing()? {
+            Some((epoch, key)) => {
+                let mut sealed = Self::encrypt_with_key(metadata, &key)?;
+                sealed.key_epoch = Some(epoch);
+                Ok(sealed)
+            }
+            None => Self::encrypt(metadata),
+        }
+    }
+    
+    /// Open metadata with the key of the epoch it names
+    pub fn open(&self, keys: &crate::mls::SpaceKeys) -> Result<SpaceMetadata> {
+        match self.key_epoch {
+            Some(epoch) => self.decrypt_with_key(&keys.opening(epoch)?),
+            None => self.decrypt(),
+        }
+    }
+    
     /// Encrypt Space metadata for DHT storage
     /// 
-    /// Uses a key derived from the Space ID for Public/Private spaces.
-    /// For Hidden spaces, uses a key that must be shared out-of-band.
+    /// Uses a key derived from the Space ID, so anyone who knows the Space ID
+    /// can decrypt it; [`Self::seal`] keeps MLS Spaces' metadata to members.
     pub fn encrypt(metadata: &SpaceMetadata) -> Result<Self> {
-        // Derive encryption key from Space ID
-        // For Public/Private: Anyone with the Space ID can decrypt
-        // For Hidden: The Space ID itself should be kept secret
-        let key_bytes = Self::derive_key(&metadata.id);
-        let key = aes_gcm::Key::<Aes256Gcm>::from_slice(&key_bytes);
+        Self::encrypt_with_key(metadata, &Self::derive_key(&metadata.id))
+    }
+    
+    /// Encrypt Space metadata under a caller-supplied key
+    fn encrypt_with_key(metadata: &SpaceMetadata, key_bytes: &[u8; 32]) -> Result<Self> {
+        let key = aes_gcm::Key::<Aes256Gcm>::from_slice(key_bytes);
         let cipher = Aes256Gcm::new(key);
         
         // Generate random nonce
@@ -174,14 +216,19 @@ impl EncryptedSpaceMetadata {
             nonce: nonce_bytes,
             ciphertext,
             visibility: metadata.visibility,
+            key_epoch: None,
         })
     }
     
     /// Decrypt Space metadata
     pub fn decrypt(&self) -> Result<SpaceMetadata> {
         // Derive decryption key from Space ID
-        let key_bytes = Self::derive_key(&self.space_id);
-        let key = aes_gcm::Key::<Aes256Gcm>::from_slice(&key_bytes);
+        self.decrypt_with_key(&Self::derive_key(&self.space_id))
+    }
+    
+    /// Decrypt Space metadata sealed under `key_bytes`
+    fn decrypt_with_key(&self, key_bytes: &[u8; 32]) -> Result<SpaceMetadata> {
+        let key = aes_gcm::Key::<Aes256Gcm>::from_slice(key_bytes);
         let cipher = Aes256Gcm::new(key);
         
         let nonce = Nonce::from_slice(&self.nonce);
@@ -250,6 +297,8 @@ mod tests {
             epoch: EpochId(0),
             created_at: 1234567890,
             signature: Signature([0u8; 64]),
+            tags: vec![],
+            category: None,
         };
         
         // Sign
@@ -288,6 +337,8 @@ mod tests {
             epoch: EpochId(0),
             created_at: 1234567890,
             signature: Signature([0u8; 64]),
+            tags: vec![],
+            category: None,
         };
         
         // Sign
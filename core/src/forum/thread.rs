@@ -4,9 +4,13 @@
 //! Threads contain Messages and support replies.
 
 use crate::types::*;
-use crate::crdt::{CrdtOp, OpType, OpPayload, Hlc, HoldbackQueue, OpValidator, ValidationResult};
+use crate::crdt::{CrdtOp, OpType, OpPayload, Hlc, HoldbackQueue, OpLimits, OpValidator, StuckOp, ValidationResult};
+use crate::forum::link_preview::LinkPreview;
+use crate::forum::Space;
 use crate::{Error, Result};
-use std::collections::HashMap;
+use minicbor::{Decode, Encode};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
 
 /// A Thread (multi-message discussion)
 #[derive(Debug, Clone)]
@@ -35,8 +39,14 @@ pub struct Thread {
     /// Whether the thread is resolved/closed
     pub resolved: bool,
     
-    /// Number of messages (cached)
+    /// Number of non-deleted messages (cached)
     pub message_count: u64,
+    
+    /// Timestamp of the most recent non-deleted message (cached)
+    pub last_activity: u64,
+    
+    /// Whether the thread was deleted with its channel (only seen when listing tombstones)
+    pub deleted: bool,
 }
 
 impl Thread {
@@ -60,6 +70,8 @@ impl Thread {
             created_at,
             resolved: false,
             message_count: 1, // Includes first message
+            last_activity: created_at,
+            deleted: false,
         }
     }
     
@@ -77,11 +89,6 @@ impl Thread {
     pub fn set_title(&mut self, title: Option<String>) {
         self.title = title;
     }
-    
-    /// Increment message count
-    pub fn add_message(&mut self) {
-        self.message_count += 1;
-    }
 }
 
 /// A Message within a Thread
@@ -107,6 +114,60 @@ pub struct Message {
     
     /// Whether the message is deleted
     pub deleted: bool,
+    
+    /// Preview for the first link in the message, once attached
+    pub link_preview: Option<LinkPreview>,
+    
+    /// Original message, if this one was forwarded
+    pub forwarded_from: Option<MessageId>,
+    
+    /// Author and thread of the original, if this one was forwarded
+    pub forward_source: Option<ForwardSource>,
+    
+    /// Whether the content was redacted (purged, not just hidden)
+    pub redacted: bool,
+    
+    /// Message quoted inline, as it read when this one was sent
+    pub quote: Option<QuotedMessage>,
+    
+    /// Users who reacted, by reaction
+    pub reactions: HashMap<String, HashSet<UserId>>,
+}
+
+/// Where a forwarded Message came from
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ForwardSource {
+    /// Author of the original message
+    pub author: UserId,
+    /// Thread the original message was posted in
+    pub thread_id: ThreadId,
+}
+
+/// An inline quote of another Message
+/// 
+/// The snippet is copied at send time, so it survives later edits or
+/// deletion of the original.
+#[derive(Debug, Clone, PartialEq, Eq, Encode, Decode, Serialize, Deserialize)]
+pub struct QuotedMessage {
+    /// Message being quoted
+    #[n(0)]
+    pub message_id: MessageId,
+    /// Start of the quoted message's content
+    #[n(1)]
+    pub snippet: String,
+}
+
+impl QuotedMessage {
+    /// Longest snippet kept, in characters
+    pub const MAX_SNIPPET_CHARS: usize = 280;
+    
+    /// Quote `message`, keeping up to [`Self::MAX_SNIPPET_CHARS`] of its content
+    pub fn of(message: &Message) -> Self {
+        Self {
+            message_id: message.id,
+            snippet: message.content.chars().take(Self::MAX_SNIPPET_CHARS).collect(),
+        }
+    }
 }
 
 impl Message {
@@ -126,6 +187,12 @@ impl Message {
             created_at,
             edited_at: None,
             deleted: false,
+            link_preview: None,
+            forwarded_from: None,
+            forward_source: None,
+            redacted: false,
+            quote: None,
+            reactions: HashMap::new(),
         }
     }
     
@@ -139,6 +206,25 @@ impl Message {
     pub fn delete(&mut self) {
         self.deleted = true;
     }
+    
+    /// Drop the content and preview, leaving a deleted tombstone
+    pub fn redact(&mut self) {
+        self.content.clear();
+        self.link_preview = None;
+        self.quote = None;
+        self.deleted = true;
+        self.redacted = true;
+    }
+    
+    /// Whether the content mentions `user_id` as `@<short id>` (case-insensitive)
+    pub fn mentions(&self, user_id: &UserId) -> bool {
+        content_mentions(&self.content, user_id)
+    }
+}
+
+/// Whether `content` mentions `user_id` as `@<short id>` (case-insensitive)
+pub fn content_mentions(content: &str, user_id: &UserId) -> bool {
+    content.to_lowercase().contains(&format!("@{}", user_id.short()))
 }
 
 /// Manages Thread and Message state and operations
@@ -161,11 +247,20 @@ pub struct ThreadManager {
     /// Holdback queue for out-of-order operations
     holdback: HoldbackQueue,
     
+    /// Held-back operations applied since the last `take_released`
+    released: Vec<CrdtOp>,
+    
     /// HLC generator
     hlc: Hlc,
     
     /// All operations (for persistence)
     operations: HashMap<OpId, CrdtOp>,
+    
+    /// Deleted channels; ops that would add threads or messages to them are dropped
+    deleted_channels: HashSet<ChannelId>,
+    
+    /// Last known state of threads dropped with their channel, for audit listings
+    deleted_threads: HashMap<ThreadId, Thread>,
 }
 
 impl ThreadManager {
@@ -177,8 +272,29 @@ impl ThreadManager {
             thread_messages: HashMap::new(),
             validator: OpValidator::new(),
             holdback: HoldbackQueue::new(),
+            released: Vec::new(),
             hlc: Hlc::now(),
             operations: HashMap::new(),
+            deleted_channels: HashSet::new(),
+            deleted_threads: HashMap::new(),
+        }
+    }
+
+    /// Set the size limits applied to local and received operations
+    pub fn set_op_limits(&mut self, limits: OpLimits) {
+        self.validator.set_limits(limits);
+    }
+    
+    /// Restrict the reactions accepted in a Space from an accepted
+    /// `UpdateSpaceReactions` op on
+    ///
+    /// Our clock moves past the op, so reactions we add later are checked
+    /// against it.
+    pub fn set_allowed_reactions(&mut self, op: &CrdtOp) {
+        if let OpType::UpdateSpaceReactions(OpPayload::UpdateSpaceReactions { allowed }) = &op.op_type {
+            let allowed = allowed.as_ref().map(|reactions| Space::normalize_reactions(reactions));
+            self.validator.set_allowed_reactions(op, allowed);
+            self.hlc.update(op.hlc);
         }
     }
     
@@ -191,13 +307,16 @@ impl ThreadManager {
         title: Option<String>,
         first_message_content: String,
         creator: UserId,
-        creator_keypair: &crate::crypto::signing::Keypair,
+        creator_keypair: &dyn crate::crypto::signing::Signer,
         epoch: EpochId,
     ) -> Result<CrdtOp> {
         // Check if thread already exists
         if self.threads.contains_key(&thread_id) {
             return Err(Error::AlreadyExists(format!("Thread {:?} already exists", thread_id)));
         }
+        if self.deleted_channels.contains(&channel_id) {
+            return Err(Error::Rejected(format!("Channel {:?} was deleted", channel_id)));
+        }
         
         let current_time = std::time::SystemTime::now()
             .duration_since(std::time::UNIX_EPOCH)
@@ -241,7 +360,7 @@ impl ThreadManager {
         
         // Create CRDT operation
         let mut op = CrdtOp {
-            op_id: OpId(uuid::Uuid::new_v4()),
+            op_id: OpId(uuid::Uuid::nil()), // set by sign()
             space_id,
             channel_id: Some(channel_id),
             thread_id: Some(thread_id),
@@ -259,8 +378,8 @@ impl ThreadManager {
         };
         
         // Sign the operation
-        let signing_bytes = op.signing_bytes();
-        op.signature = Signature(creator_keypair.sign(&signing_bytes).0);
+        op.sign(creator_keypair)?;
+        self.validator.check_local(&op)?;
         
         // Apply locally
         self.threads.insert(thread_id, thread);
@@ -283,6 +402,7 @@ impl ThreadManager {
     
     /// Process an incoming CreateThread operation
     pub fn process_create_thread(&mut self, op: &CrdtOp) -> Result<()> {
+        self.check_channel_not_deleted(op)?;
         match self.validator.validate(op, &self.operations) {
             ValidationResult::Accept => {
                 if let OpType::CreateThread(OpPayload::CreateThread { title, first_message, first_message_id }) = &op.op_type {
@@ -322,10 +442,12 @@ impl ThreadManager {
                         .entry(thread_id)
                         .or_insert_with(Vec::new)
                         .push(first_message_id);
+                    self.refresh_summary(&thread_id);
                     
                     self.operations.insert(op.op_id, op.clone());
                     self.validator.apply_op(op);
                     self.hlc.update(op.hlc);
+                    self.release_held(op.op_id);
                     
                     Ok(())
                 } else {
@@ -345,19 +467,21 @@ impl ThreadManager {
     
     /// Process an incoming PostMessage operation
     pub fn process_post_message(&mut self, op: &CrdtOp) -> Result<()> {
+        self.check_channel_not_deleted(op)?;
         match self.validator.validate(op, &self.operations) {
             ValidationResult::Accept => {
-                if let OpType::PostMessage(OpPayload::PostMessage { message_id, content }) = &op.op_type {
+                if let OpType::PostMessage(OpPayload::PostMessage { message_id, content, quote }) = &op.op_type {
                     let thread_id = op.thread_id
                         .ok_or_else(|| Error::InvalidOperation("Missing thread_id".to_string()))?;
                     
-                    let message = Message::new(
+                    let mut message = Message::new(
                         *message_id,
                         thread_id,
                         content.clone(),
                         op.author,
                         op.timestamp,
                     );
+                    message.quote = quote.clone();
                     
                     self.messages.insert(*message_id, message);
                     self.thread_messages
@@ -365,9 +489,7 @@ impl ThreadManager {
                         .or_insert_with(Vec::new)
                         .push(*message_id);
                     
-                    if let Some(thread) = self.threads.get_mut(&thread_id) {
-                        thread.add_message();
-                    }
+                    self.refresh_summary(&thread_id);
                     
                     self.operations.insert(op.op_id, op.clone());
                     self.validator.apply_op(op);
@@ -424,18 +546,166 @@ impl ThreadManager {
         }
     }
     
-    /// Post a message to a Thread
+    /// Process an incoming ForwardMessage operation
+    pub fn process_forward_message(&mut self, op: &CrdtOp) -> Result<()> {
+        self.check_channel_not_deleted(op)?;
+        match self.validator.validate(op, &self.operations) {
+            ValidationResult::Accept => {
+                if let OpType::ForwardMessage(OpPayload::ForwardMessage {
+                    message_id, content, forwarded_from, original_author, source_thread,
+                }) = &op.op_type {
+                    let thread_id = op.thread_id
+                        .ok_or_else(|| Error::InvalidOperation("Missing thread_id".to_string()))?;
+                    
+                    let mut message = Message::new(
+                        *message_id,
+                        thread_id,
+                        content.clone(),
+                        op.author,
+                        op.timestamp,
+                    );
+                    message.forwarded_from = Some(*forwarded_from);
+                    message.forward_source = Some(ForwardSource {
+                        author: *original_author,
+                        thread_id: *source_thread,
+                    });
+                    
+                    self.messages.insert(*message_id, message);
+                    self.thread_messages
+                        .entry(thread_id)
+                        .or_insert_with(Vec::new)
+                        .push(*message_id);
+                    
+                    self.refresh_summary(&thread_id);
+                    
+                    self.operations.insert(op.op_id, op.clone());
+                    self.validator.apply_op(op);
+                    self.hlc.update(op.hlc);
+                    
+                    Ok(())
+                } else {
+                    Err(Error::InvalidOperation("Expected ForwardMessage operation".to_string()))
+                }
+            }
+            ValidationResult::Buffered(deps) => {
+                self.holdback.buffer(op.clone(), deps, op.timestamp)
+                    .map_err(|e| Error::Storage(e))?;
+                Ok(())
+            }
+            ValidationResult::Reject(reason) => {
+                Err(Error::InvalidOperation(format!("Operation rejected: {:?}", reason)))
+            }
+        }
+    }
+    
+    /// Process an incoming RedactMessage operation
+    pub fn process_redact_message(&mut self, op: &CrdtOp) -> Result<()> {
+        match self.validator.validate(op, &self.operations) {
+            ValidationResult::Accept => {
+                if let OpType::RedactMessage(OpPayload::RedactMessage { message_id }) = &op.op_type {
+                    let message = self.messages.get(message_id)
+                        .ok_or_else(|| Error::NotFound(format!("Message {:?} not found", message_id)))?;
+                    // Only the author may redact
+                    if message.author != op.author {
+                        return Err(Error::Permission("Only author can redact message".to_string()));
+                    }
+                    self.purge_content(message_id);
+                    
+                    self.operations.insert(op.op_id, op.clone());
+                    self.validator.apply_op(op);
+                    self.hlc.update(op.hlc);
+                    
+                    Ok(())
+                } else {
+                    Err(Error::InvalidOperation("Expected RedactMessage operation".to_string()))
+                }
+            }
+            ValidationResult::Buffered(deps) => {
+                self.holdback.buffer(op.clone(), deps, op.timestamp)
+                    .map_err(|e| Error::Storage(e))?;
+                Ok(())
+            }
+            ValidationResult::Reject(reason) => {
+                Err(Error::InvalidOperation(format!("Operation rejected: {:?}", reason)))
+            }
+        }
+    }
+    
+    /// Process an incoming AddReaction operation
+    pub fn process_add_reaction(&mut self, op: &CrdtOp) -> Result<()> {
+        match self.validator.validate(op, &self.operations) {
+            ValidationResult::Accept => {
+                if let OpType::AddReaction(OpPayload::AddReaction { message_id, emoji }) = &op.op_type {
+                    let message = self.messages.get_mut(message_id)
+                        .ok_or_else(|| Error::NotFound(format!("Message {:?} not found", message_id)))?;
+                    message.reactions.entry(emoji.clone()).or_default().insert(op.author);
+                    
+                    self.operations.insert(op.op_id, op.clone());
+                    self.validator.apply_op(op);
+                    self.hlc.update(op.hlc);
+                    
+                    Ok(())
+                } else {
+                    Err(Error::InvalidOperation("Expected AddReaction operation".to_string()))
+                }
+            }
+            ValidationResult::Buffered(deps) => {
+                self.holdback.buffer(op.clone(), deps, op.timestamp)
+                    .map_err(|e| Error::Storage(e))?;
+                Ok(())
+            }
+            ValidationResult::Reject(reason) => {
+                Err(Error::InvalidOperation(format!("Operation rejected: {:?}", reason)))
+            }
+        }
+    }
+    
+    /// Process an incoming AttachLinkPreview operation
+    pub fn process_attach_link_preview(&mut self, op: &CrdtOp) -> Result<()> {
+        match self.validator.validate(op, &self.operations) {
+            ValidationResult::Accept => {
+                if let OpType::AttachLinkPreview(OpPayload::AttachLinkPreview { message_id, preview }) = &op.op_type {
+                    let message = self.messages.get_mut(message_id)
+                        .ok_or_else(|| Error::NotFound(format!("Message {:?} not found", message_id)))?;
+                    // Only the author's client may attach a preview
+                    if message.author != op.author {
+                        return Err(Error::Permission("Only author can attach a link preview".to_string()));
+                    }
+                    message.link_preview = Some(preview.clone());
+                    
+                    self.operations.insert(op.op_id, op.clone());
+                    self.validator.apply_op(op);
+                    self.hlc.update(op.hlc);
+                    
+                    Ok(())
+                } else {
+                    Err(Error::InvalidOperation("Expected AttachLinkPreview operation".to_string()))
+                }
+            }
+            ValidationResult::Buffered(deps) => {
+                self.holdback.buffer(op.clone(), deps, op.timestamp)
+                    .map_err(|e| Error::Storage(e))?;
+                Ok(())
+            }
+            ValidationResult::Reject(reason) => {
+                Err(Error::InvalidOperation(format!("Operation rejected: {:?}", reason)))
+            }
+        }
+    }
+    
+    /// Post a message to a Thread, optionally quoting another
     pub fn post_message(
         &mut self,
         message_id: MessageId,
         thread_id: ThreadId,
         content: String,
+        quote: Option<QuotedMessage>,
         author: UserId,
-        author_keypair: &crate::crypto::signing::Keypair,
+        author_keypair: &dyn crate::crypto::signing::Signer,
         epoch: EpochId,
     ) -> Result<CrdtOp> {
         // Check thread exists
-        let thread = self.threads.get_mut(&thread_id)
+        let thread = self.threads.get(&thread_id)
             .ok_or_else(|| Error::NotFound(format!("Thread {:?} not found", thread_id)))?;
         
         let space_id = thread.space_id;
@@ -447,25 +717,28 @@ impl ThreadManager {
             .as_secs();
         
         // Create Message
-        let message = Message::new(
+        let mut message = Message::new(
             message_id,
             thread_id,
             content.clone(),
             author,
             current_time,
         );
+        message.quote = quote.clone();
         
         // Create CRDT operation
         let mut op = CrdtOp {
-            op_id: OpId(uuid::Uuid::new_v4()),
+            op_id: OpId(uuid::Uuid::nil()), // set by sign()
             space_id,
             channel_id: Some(channel_id),
             thread_id: Some(thread_id),
             op_type: OpType::PostMessage(OpPayload::PostMessage {
                 message_id,
                 content,
+                quote,
             }),
-            prev_ops: vec![],
+            // Peers hold the message back until they have its thread
+            prev_ops: self.thread_op(&thread_id).into_iter().collect(),
             author,
             epoch,
             hlc: self.hlc.tick(),
@@ -474,8 +747,8 @@ impl ThreadManager {
         };
         
         // Sign the operation
-        let signing_bytes = op.signing_bytes();
-        op.signature = Signature(author_keypair.sign(&signing_bytes).0);
+        op.sign(author_keypair)?;
+        self.validator.check_local(&op)?;
         
         // Apply locally
         self.messages.insert(message_id, message);
@@ -483,7 +756,7 @@ impl ThreadManager {
             .entry(thread_id)
             .or_insert_with(Vec::new)
             .push(message_id);
-        thread.add_message();
+        self.refresh_summary(&thread_id);
         
         self.operations.insert(op.op_id, op.clone());
         self.validator.apply_op(&op);
@@ -491,13 +764,354 @@ impl ThreadManager {
         Ok(op)
     }
     
+    /// The op that created a Thread
+    fn thread_op(&self, thread_id: &ThreadId) -> Option<OpId> {
+        self.operations.values()
+            .find(|op| op.thread_id == Some(*thread_id) && matches!(op.op_type, OpType::CreateThread(_)))
+            .map(|op| op.op_id)
+    }
+    
+    /// Apply messages that were held back waiting for a thread's op
+    fn release_held(&mut self, op_id: OpId) {
+        for op in self.holdback.on_op_accepted(op_id) {
+            let result = match &op.op_type {
+                OpType::PostMessage(_) => self.process_post_message(&op),
+                _ => continue,
+            };
+            match result {
+                Ok(()) => self.released.push(op),
+                Err(e) => tracing::debug!(op_id = ?op.op_id, error = %e, "Dropped held-back operation"),
+            }
+        }
+    }
+    
+    /// Held-back operations applied since the last call, in the order applied
+    pub fn take_released(&mut self) -> Vec<CrdtOp> {
+        std::mem::take(&mut self.released)
+    }
+    
+    /// Operations held back waiting on ops we don't have yet
+    pub fn stuck_ops(&self, current_time: u64) -> Vec<StuckOp> {
+        self.holdback.stuck_ops(current_time)
+    }
+    
+    /// Forward an existing Message into another Thread
+    /// 
+    /// The new message copies the original's content and records its ID,
+    /// author and thread, so attribution survives the move.
+    pub fn forward_message(
+        &mut self,
+        message_id: MessageId,
+        source_message_id: MessageId,
+        target_thread_id: ThreadId,
+        author: UserId,
+        author_keypair: &dyn crate::crypto::signing::Signer,
+        epoch: EpochId,
+    ) -> Result<CrdtOp> {
+        let original = self.messages.get(&source_message_id)
+            .ok_or_else(|| Error::NotFound(format!("Message {:?} not found", source_message_id)))?;
+        if original.deleted {
+            return Err(Error::InvalidOperation("Cannot forward a deleted message".to_string()));
+        }
+        let content = original.content.clone();
+        let source = ForwardSource {
+            author: original.author,
+            thread_id: original.thread_id,
+        };
+        
+        let thread = self.threads.get(&target_thread_id)
+            .ok_or_else(|| Error::NotFound(format!("Thread {:?} not found", target_thread_id)))?;
+        
+        let current_time = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_secs();
+        
+        let mut message = Message::new(
+            message_id,
+            target_thread_id,
+            content.clone(),
+            author,
+            current_time,
+        );
+        message.forwarded_from = Some(source_message_id);
+        message.forward_source = Some(source);
+        
+        let mut op = CrdtOp {
+            op_id: OpId(uuid::Uuid::nil()), // set by sign()
+            space_id: thread.space_id,
+            channel_id: Some(thread.channel_id),
+            thread_id: Some(target_thread_id),
+            op_type: OpType::ForwardMessage(OpPayload::ForwardMessage {
+                message_id,
+                content,
+                forwarded_from: source_message_id,
+                original_author: source.author,
+                source_thread: source.thread_id,
+            }),
+            prev_ops: vec![],
+            author,
+            epoch,
+            hlc: self.hlc.tick(),
+            timestamp: current_time,
+            signature: Signature([0u8; 64]),
+        };
+        
+        op.sign(author_keypair)?;
+        self.validator.check_local(&op)?;
+        
+        self.messages.insert(message_id, message);
+        self.thread_messages
+            .entry(target_thread_id)
+            .or_insert_with(Vec::new)
+            .push(message_id);
+        self.refresh_summary(&target_thread_id);
+        
+        self.operations.insert(op.op_id, op.clone());
+        self.validator.apply_op(&op);
+        
+        Ok(op)
+    }
+    
+    /// Redact one of the author's Messages
+    /// 
+    /// Unlike a delete, the content is purged: the message keeps only its
+    /// tombstone, and the ops that carried its text are scrubbed in place
+    /// (their signatures no longer verify, so they are never re-synced).
+    pub fn redact_message(
+        &mut self,
+        message_id: MessageId,
+        author: UserId,
+        author_keypair: &dyn crate::crypto::signing::Signer,
+        epoch: EpochId,
+    ) -> Result<CrdtOp> {
+        let message = self.messages.get(&message_id)
+            .ok_or_else(|| Error::NotFound(format!("Message {:?} not found", message_id)))?;
+        
+        if message.author != author {
+            return Err(Error::Permission("Only author can redact message".to_string()));
+        }
+        
+        let thread = self.threads.get(&message.thread_id)
+            .ok_or_else(|| Error::NotFound(format!("Thread {:?} not found", message.thread_id)))?;
+        
+        let current_time = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_secs();
+        
+        let mut op = CrdtOp {
+            op_id: OpId(uuid::Uuid::nil()), // set by sign()
+            space_id: thread.space_id,
+            channel_id: Some(thread.channel_id),
+            thread_id: Some(message.thread_id),
+            op_type: OpType::RedactMessage(OpPayload::RedactMessage { message_id }),
+            prev_ops: vec![],
+            author,
+            epoch,
+            hlc: self.hlc.tick(),
+            timestamp: current_time,
+            signature: Signature([0u8; 64]),
+        };
+        
+        op.sign(author_keypair)?;
+        self.validator.check_local(&op)?;
+        
+        self.purge_content(&message_id);
+        self.operations.insert(op.op_id, op.clone());
+        self.validator.apply_op(&op);
+        
+        Ok(op)
+    }
+    
+    /// Ops that carried a Message's content (post, edits, forward, preview)
+    pub fn message_ops(&self, message_id: &MessageId) -> Vec<&CrdtOp> {
+        self.operations.values()
+            .filter(|op| match &op.op_type {
+                OpType::CreateThread(OpPayload::CreateThread { first_message_id: id, .. })
+                | OpType::PostMessage(OpPayload::PostMessage { message_id: id, .. })
+                | OpType::EditMessage(OpPayload::EditMessage { message_id: id, .. })
+                | OpType::ForwardMessage(OpPayload::ForwardMessage { message_id: id, .. })
+                | OpType::AttachLinkPreview(OpPayload::AttachLinkPreview { message_id: id, .. }) => id == message_id,
+                _ => false,
+            })
+            .collect()
+    }
+    
+    /// Recompute a Thread's cached message count and last activity
+    /// 
+    /// Derived from the distinct non-deleted messages rather than counted
+    /// per op, so replicas that applied the same messages in any order (or
+    /// saw a message before its thread) agree.
+    fn refresh_summary(&mut self, thread_id: &ThreadId) {
+        let Some(thread) = self.threads.get_mut(thread_id) else {
+            return;
+        };
+        let message_ids: HashSet<&MessageId> = self.thread_messages.get(thread_id)
+            .map(|ids| ids.iter().collect())
+            .unwrap_or_default();
+        let live: Vec<&Message> = message_ids.into_iter()
+            .filter_map(|id| self.messages.get(id))
+            .filter(|message| !message.deleted)
+            .collect();
+        
+        thread.message_count = live.len() as u64;
+        thread.last_activity = live.iter()
+            .map(|message| message.created_at)
+            .max()
+            .unwrap_or(thread.created_at);
+    }
+    
+    /// Redact a Message and blank its content in every op that carried it
+    fn purge_content(&mut self, message_id: &MessageId) {
+        if let Some(message) = self.messages.get_mut(message_id) {
+            message.redact();
+            let thread_id = message.thread_id;
+            self.refresh_summary(&thread_id);
+        }
+        
+        let op_ids: Vec<OpId> = self.message_ops(message_id).iter().map(|op| op.op_id).collect();
+        for op_id in op_ids {
+            let Some(op) = self.operations.get_mut(&op_id) else {
+                continue;
+            };
+            match &mut op.op_type {
+                OpType::PostMessage(OpPayload::PostMessage { content, quote, .. }) => {
+                    content.clear();
+                    if let Some(quote) = quote {
+                        quote.snippet.clear();
+                    }
+                }
+                OpType::CreateThread(OpPayload::CreateThread { first_message: content, .. })
+                | OpType::EditMessage(OpPayload::EditMessage { new_content: content, .. })
+                | OpType::ForwardMessage(OpPayload::ForwardMessage { content, .. }) => content.clear(),
+                OpType::AttachLinkPreview(OpPayload::AttachLinkPreview { preview, .. }) => {
+                    *preview = LinkPreview {
+                        url: String::new(),
+                        title: None,
+                        description: None,
+                        image_blob: None,
+                    };
+                }
+                _ => {}
+            }
+        }
+    }
+    
+    /// Attach a link preview to one of the author's messages
+    pub fn attach_link_preview(
+        &mut self,
+        message_id: MessageId,
+        preview: LinkPreview,
+        author: UserId,
+        author_keypair: &dyn crate::crypto::signing::Signer,
+        epoch: EpochId,
+    ) -> Result<CrdtOp> {
+        let message = self.messages.get_mut(&message_id)
+            .ok_or_else(|| Error::NotFound(format!("Message {:?} not found", message_id)))?;
+        
+        if message.author != author {
+            return Err(Error::Permission("Only author can attach a link preview".to_string()));
+        }
+        
+        let thread = self.threads.get(&message.thread_id)
+            .ok_or_else(|| Error::NotFound(format!("Thread {:?} not found", message.thread_id)))?;
+        
+        let current_time = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_secs();
+        
+        let mut op = CrdtOp {
+            op_id: OpId(uuid::Uuid::nil()), // set by sign()
+            space_id: thread.space_id,
+            channel_id: Some(thread.channel_id),
+            thread_id: Some(message.thread_id),
+            op_type: OpType::AttachLinkPreview(OpPayload::AttachLinkPreview {
+                message_id,
+                preview: preview.clone(),
+            }),
+            prev_ops: vec![],
+            author,
+            epoch,
+            hlc: self.hlc.tick(),
+            timestamp: current_time,
+            signature: Signature([0u8; 64]),
+        };
+        
+        op.sign(author_keypair)?;
+        self.validator.check_local(&op)?;
+        
+        message.link_preview = Some(preview);
+        self.operations.insert(op.op_id, op.clone());
+        self.validator.apply_op(&op);
+        
+        Ok(op)
+    }
+    
+    /// React to a Message
+    /// 
+    /// Reacting twice with the same reaction has no further effect.
+    pub fn add_reaction(
+        &mut self,
+        message_id: MessageId,
+        emoji: String,
+        author: UserId,
+        author_keypair: &dyn crate::crypto::signing::Signer,
+        epoch: EpochId,
+    ) -> Result<CrdtOp> {
+        let emoji = emoji.trim().to_string();
+        if emoji.is_empty() {
+            return Err(Error::InvalidOperation("Reaction cannot be empty".to_string()));
+        }
+        let message = self.messages.get_mut(&message_id)
+            .ok_or_else(|| Error::NotFound(format!("Message {:?} not found", message_id)))?;
+        if message.deleted {
+            return Err(Error::InvalidOperation("Cannot react to a deleted message".to_string()));
+        }
+        
+        let thread = self.threads.get(&message.thread_id)
+            .ok_or_else(|| Error::NotFound(format!("Thread {:?} not found", message.thread_id)))?;
+        
+        let current_time = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_secs();
+        
+        let mut op = CrdtOp {
+            op_id: OpId(uuid::Uuid::nil()), // set by sign()
+            space_id: thread.space_id,
+            channel_id: Some(thread.channel_id),
+            thread_id: Some(message.thread_id),
+            op_type: OpType::AddReaction(OpPayload::AddReaction {
+                message_id,
+                emoji: emoji.clone(),
+            }),
+            prev_ops: vec![],
+            author,
+            epoch,
+            hlc: self.hlc.tick(),
+            timestamp: current_time,
+            signature: Signature([0u8; 64]),
+        };
+        
+        op.sign(author_keypair)?;
+        self.validator.check_local(&op)?;
+        
+        message.reactions.entry(emoji).or_default().insert(author);
+        self.operations.insert(op.op_id, op.clone());
+        self.validator.apply_op(&op);
+        
+        Ok(op)
+    }
+    
     /// Edit a message
     pub fn edit_message(
         &mut self,
         message_id: MessageId,
         new_content: String,
         author: UserId,
-        author_keypair: &crate::crypto::signing::Keypair,
+        author_keypair: &dyn crate::crypto::signing::Signer,
         epoch: EpochId,
     ) -> Result<CrdtOp> {
         let message = self.messages.get_mut(&message_id)
@@ -517,7 +1131,7 @@ impl ThreadManager {
             .as_secs();
         
         let mut op = CrdtOp {
-            op_id: OpId(uuid::Uuid::new_v4()),
+            op_id: OpId(uuid::Uuid::nil()), // set by sign()
             space_id: thread.space_id,
             channel_id: Some(thread.channel_id),
             thread_id: Some(message.thread_id),
@@ -533,8 +1147,8 @@ impl ThreadManager {
             signature: Signature([0u8; 64]),
         };
         
-        let signing_bytes = op.signing_bytes();
-        op.signature = Signature(author_keypair.sign(&signing_bytes).0);
+        op.sign(author_keypair)?;
+        self.validator.check_local(&op)?;
         
         message.edit(new_content, current_time);
         self.operations.insert(op.op_id, op.clone());
@@ -556,6 +1170,76 @@ impl ThreadManager {
             .unwrap_or_default()
     }
     
+    /// Get all Threads in a Channel, with deleted ones (flagged `deleted`) if asked
+    pub fn list_threads_with_deleted(&self, channel_id: &ChannelId, include_deleted: bool) -> Vec<&Thread> {
+        let mut threads = self.list_threads(channel_id);
+        if include_deleted {
+            threads.extend(self.deleted_threads.values().filter(|thread| thread.channel_id == *channel_id));
+        }
+        threads
+    }
+    
+    /// Forget every Thread and Message in a deleted Channel, returning the removed IDs
+    /// 
+    /// The Channel is tombstoned: later ops creating threads or posting
+    /// messages in it are refused.
+    pub fn remove_channel(&mut self, channel_id: &ChannelId) -> (Vec<ThreadId>, Vec<MessageId>) {
+        self.deleted_channels.insert(*channel_id);
+        
+        let thread_ids = self.channel_threads.remove(channel_id).unwrap_or_default();
+        let mut message_ids = Vec::new();
+        for thread_id in &thread_ids {
+            if let Some(mut thread) = self.threads.remove(thread_id) {
+                thread.deleted = true;
+                self.deleted_threads.insert(*thread_id, thread);
+            }
+            for message_id in self.thread_messages.remove(thread_id).unwrap_or_default() {
+                self.messages.remove(&message_id);
+                message_ids.push(message_id);
+            }
+        }
+        self.operations.retain(|_, op| op.channel_id != Some(*channel_id));
+        
+        (thread_ids, message_ids)
+    }
+    
+    fn check_channel_not_deleted(&self, op: &CrdtOp) -> Result<()> {
+        match op.channel_id {
+            Some(channel_id) if self.deleted_channels.contains(&channel_id) => {
+                Err(Error::Rejected(format!("Channel {:?} was deleted", channel_id)))
+            }
+            _ => Ok(()),
+        }
+    }
+    
+    /// Forget every Thread and Message in a Space, returning the removed IDs
+    pub fn remove_space(&mut self, space_id: &SpaceId) -> (Vec<ThreadId>, Vec<MessageId>) {
+        self.deleted_threads.retain(|_, thread| thread.space_id != *space_id);
+        let thread_ids: Vec<ThreadId> = self.threads.values()
+            .filter(|thread| thread.space_id == *space_id)
+            .map(|thread| thread.id)
+            .collect();
+        
+        let mut message_ids = Vec::new();
+        for thread_id in &thread_ids {
+            if let Some(thread) = self.threads.remove(thread_id) {
+                if let Some(ids) = self.channel_threads.get_mut(&thread.channel_id) {
+                    ids.retain(|id| id != thread_id);
+                    if ids.is_empty() {
+                        self.channel_threads.remove(&thread.channel_id);
+                    }
+                }
+            }
+            for message_id in self.thread_messages.remove(thread_id).unwrap_or_default() {
+                self.messages.remove(&message_id);
+                message_ids.push(message_id);
+            }
+        }
+        self.operations.retain(|_, op| op.space_id != *space_id);
+        
+        (thread_ids, message_ids)
+    }
+    
     /// Get a Message by ID
     pub fn get_message(&self, message_id: &MessageId) -> Option<&Message> {
         self.messages.get(message_id)
@@ -640,6 +1324,7 @@ mod tests {
             message_id,
             thread_id,
             "Second message".to_string(),
+            None,
             creator,
             &creator_keypair,
             EpochId(0),
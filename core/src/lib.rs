@@ -5,13 +5,17 @@
 //! Discord-like applications with E2E encryption, CRDT-based conflict resolution,
 //! and MLS group key management.
 
+pub mod bot;
 pub mod client;
 pub mod crdt;
 pub mod crypto;
 pub mod dashboard;
+pub mod export;
 pub mod forum;
+pub mod metrics;
 pub mod mls;
 pub mod network;
+pub mod notification;
 pub mod permissions;
 pub mod storage;
 
@@ -20,8 +24,12 @@ pub mod storage;
 pub mod smoothtest;
 pub mod types;
 pub mod version;
+pub mod webhook;
 
-pub use client::{Client, ClientConfig};
+pub use bot::{BotClient, BotContext};
+pub use client::{Client, ClientBuilder, ClientConfig, ClientEvent, DecryptFailReason};
+pub use metrics::ClientMetrics;
+pub use notification::{Notification, NotificationKind};
 pub use permissions::{Permissions, PermissionResult};
 pub use types::*;
 pub use version::{VERSION, version_string, PROTOCOL_VERSION};
@@ -74,6 +82,40 @@ pub enum Error {
     #[error("Invalid operation: {0}")]
     InvalidOperation(String),
 
+    #[error("Could not join Space: {0}")]
+    Join(#[from] JoinError),
+
+    #[error("Timed out: {0}")]
+    TimedOut(String),
+
     #[error(transparent)]
     Other(#[from] anyhow::Error),
 }
+
+/// Why joining a Space failed, so a UI can say what to do about it
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum JoinError {
+    /// No invite in the Space has this code
+    #[error("invalid invite code")]
+    InvalidCode,
+
+    /// The invite was revoked, has expired or is used up
+    #[error("invite is no longer valid")]
+    InviteNoLongerValid,
+
+    /// The user is already a member
+    #[error("already a member of this Space")]
+    AlreadyMember,
+
+    /// Neither this client, the DHT nor connected peers know the Space
+    #[error("Space {0} not found")]
+    SpaceNotFound(types::SpaceId),
+
+    /// The DHT answered with metadata for a different Space
+    #[error("Space ID mismatch: expected {expected}, got {actual}")]
+    SpaceIdMismatch { expected: types::SpaceId, actual: types::SpaceId },
+
+    /// No peer that could provide the Space is reachable
+    #[error("network unreachable: {0}")]
+    Unreachable(String),
+}
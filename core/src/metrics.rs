@@ -0,0 +1,221 @@
+//! Client health metrics
+//!
+//! Counters are updated on the op hot path and read back as a
+//! [`ClientMetrics`] snapshot, which can be rendered in the Prometheus text
+//! exposition format. With the `metrics-server` feature, [`serve`] exposes
+//! the snapshot on a small embedded `/metrics` HTTP endpoint, optionally
+//! behind a bearer token with [`serve_with_token`].
+
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+/// Live counters owned by a `Client`
+#[derive(Debug, Default)]
+pub(crate) struct ClientCounters {
+    ops_sent: AtomicU64,
+    ops_received: AtomicU64,
+    decrypt_failures: AtomicU64,
+}
+
+impl ClientCounters {
+    /// Record an operation broadcast by this client
+    pub(crate) fn record_op_sent(&self) {
+        self.ops_sent.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Record a new (non-duplicate) operation received from the network
+    pub(crate) fn record_op_received(&self) {
+        self.ops_received.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Record an MLS message that could not be decrypted
+    pub(crate) fn record_decrypt_failure(&self) {
+        self.decrypt_failures.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub(crate) fn ops_sent(&self) -> u64 {
+        self.ops_sent.load(Ordering::Relaxed)
+    }
+
+    pub(crate) fn ops_received(&self) -> u64 {
+        self.ops_received.load(Ordering::Relaxed)
+    }
+
+    pub(crate) fn decrypt_failures(&self) -> u64 {
+        self.decrypt_failures.load(Ordering::Relaxed)
+    }
+}
+
+/// Point-in-time view of a client's health
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct ClientMetrics {
+    /// Operations broadcast by this client
+    pub ops_sent: u64,
+    /// New operations received from peers (duplicates excluded)
+    pub ops_received: u64,
+    /// MLS messages that failed to decrypt (epoch mismatches are queued, not counted)
+    pub decrypt_failures: u64,
+    /// MLS messages waiting for an epoch update
+    pub pending_mls_messages: usize,
+    /// DHT put requests issued
+    pub dht_puts: u64,
+    /// DHT get requests issued
+    pub dht_gets: u64,
+    /// Currently connected peers
+    pub connected_peers: usize,
+    /// Current MLS epoch per Space (hex Space ID), for Spaces with an MLS group
+    pub space_epochs: BTreeMap<String, u64>,
+}
+
+impl ClientMetrics {
+    /// Render in the Prometheus text exposition format
+    pub fn to_prometheus(&self) -> String {
+        let mut out = String::new();
+
+        let counters = [
+            ("spaceway_ops_sent_total", "Operations broadcast by this client", self.ops_sent),
+            ("spaceway_ops_received_total", "New operations received from peers", self.ops_received),
+            ("spaceway_decrypt_failures_total", "MLS messages that failed to decrypt", self.decrypt_failures),
+            ("spaceway_dht_puts_total", "DHT put requests issued", self.dht_puts),
+            ("spaceway_dht_gets_total", "DHT get requests issued", self.dht_gets),
+        ];
+        for (name, help, value) in counters {
+            metric_header(&mut out, name, help, "counter");
+            let _ = writeln!(out, "{} {}", name, value);
+        }
+
+        let gauges = [
+            ("spaceway_pending_mls_messages", "MLS messages waiting for an epoch update", self.pending_mls_messages),
+            ("spaceway_connected_peers", "Currently connected peers", self.connected_peers),
+        ];
+        for (name, help, value) in gauges {
+            metric_header(&mut out, name, help, "gauge");
+            let _ = writeln!(out, "{} {}", name, value);
+        }
+
+        metric_header(&mut out, "spaceway_space_epoch", "Current MLS epoch per Space", "gauge");
+        for (space, epoch) in &self.space_epochs {
+            let _ = writeln!(out, "spaceway_space_epoch{{space=\"{}\"}} {}", space, epoch);
+        }
+
+        out
+    }
+}
+
+fn metric_header(out: &mut String, name: &str, help: &str, kind: &str) {
+    let _ = writeln!(out, "# HELP {} {}", name, help);
+    let _ = writeln!(out, "# TYPE {} {}", name, kind);
+}
+
+/// Serve `GET /metrics` for a client until the listener fails
+///
+/// Each connection gets a fresh snapshot; any other path returns 404.
+#[cfg(feature = "metrics-server")]
+pub async fn serve(
+    listener: tokio::net::TcpListener,
+    client: std::sync::Arc<tokio::sync::RwLock<crate::Client>>,
+) -> crate::Result<()> {
+    serve_with_token(listener, client, None).await
+}
+
+/// Serve `GET /metrics`, requiring `Authorization: Bearer <token>` if a
+/// token is given
+///
+/// Requests without the right token get 401. The endpoint speaks plain HTTP
+/// only; put it behind a TLS-terminating proxy when it leaves the host.
+#[cfg(feature = "metrics-server")]
+pub async fn serve_with_token(
+    listener: tokio::net::TcpListener,
+    client: std::sync::Arc<tokio::sync::RwLock<crate::Client>>,
+    bearer_token: Option<String>,
+) -> crate::Result<()> {
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+    let bearer_token = std::sync::Arc::new(bearer_token);
+    loop {
+        let (mut stream, _) = listener.accept().await
+            .map_err(|e| crate::Error::Network(format!("Metrics listener failed: {}", e)))?;
+        let client = std::sync::Arc::clone(&client);
+        let bearer_token = std::sync::Arc::clone(&bearer_token);
+
+        tokio::spawn(async move {
+            let mut buf = [0u8; 1024];
+            let n = match stream.read(&mut buf).await {
+                Ok(n) => n,
+                Err(_) => return,
+            };
+            let request = String::from_utf8_lossy(&buf[..n]);
+
+            let response = if !request.starts_with("GET /metrics ") {
+                "HTTP/1.1 404 Not Found\r\nContent-Length: 0\r\nConnection: close\r\n\r\n".to_string()
+            } else if !is_authorized(&request, bearer_token.as_deref()) {
+                "HTTP/1.1 401 Unauthorized\r\nWWW-Authenticate: Bearer\r\nContent-Length: 0\r\nConnection: close\r\n\r\n".to_string()
+            } else {
+                let body = client.read().await.metrics_snapshot().await.to_prometheus();
+                format!(
+                    "HTTP/1.1 200 OK\r\nContent-Type: text/plain; version=0.0.4\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                    body.len(),
+                    body
+                )
+            };
+
+            let _ = stream.write_all(response.as_bytes()).await;
+            let _ = stream.shutdown().await;
+        });
+    }
+}
+
+/// Whether a raw request carries the expected bearer token (always, if none is set)
+#[cfg(feature = "metrics-server")]
+fn is_authorized(request: &str, bearer_token: Option<&str>) -> bool {
+    let Some(expected) = bearer_token else { return true };
+
+    request.lines()
+        .skip(1)
+        .take_while(|line| !line.is_empty())
+        .filter_map(|line| line.split_once(':'))
+        .filter(|(name, _)| name.trim().eq_ignore_ascii_case("authorization"))
+        .filter_map(|(_, value)| value.trim().strip_prefix("Bearer "))
+        .any(|token| {
+            // Compare every byte so the time taken doesn't reveal the prefix matched
+            token.len() == expected.len()
+                && token.bytes().zip(expected.bytes()).fold(0u8, |acc, (a, b)| acc | (a ^ b)) == 0
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_counters_feed_snapshot() {
+        let counters = ClientCounters::default();
+        counters.record_op_sent();
+        counters.record_op_sent();
+        counters.record_op_received();
+        counters.record_decrypt_failure();
+
+        assert_eq!(counters.ops_sent(), 2);
+        assert_eq!(counters.ops_received(), 1);
+        assert_eq!(counters.decrypt_failures(), 1);
+    }
+
+    #[test]
+    fn test_prometheus_rendering() {
+        let mut metrics = ClientMetrics {
+            ops_sent: 3,
+            dht_puts: 2,
+            connected_peers: 1,
+            ..Default::default()
+        };
+        metrics.space_epochs.insert("abcd".to_string(), 4);
+
+        let text = metrics.to_prometheus();
+        assert!(text.contains("# TYPE spaceway_ops_sent_total counter\nspaceway_ops_sent_total 3\n"));
+        assert!(text.contains("spaceway_dht_puts_total 2\n"));
+        assert!(text.contains("# TYPE spaceway_connected_peers gauge\nspaceway_connected_peers 1\n"));
+        assert!(text.contains("spaceway_space_epoch{space=\"abcd\"} 4\n"));
+    }
+}
@@ -0,0 +1,153 @@
+//! Keys that seal a Space's DHT records for its members
+//!
+//! MLS Spaces seal operation batches, metadata and blobs under exporter keys
+//! (see [`crate::mls::key_labels`]), tagging each record with the epoch it was
+//! sealed in. A member who joins later has no keys for the epochs before
+//! their Welcome, so past keys are handed on in a [`SealedKeyHistory`],
+//! itself sealed under the epoch it was written in.
+
+use crate::mls::{key_labels, MlsGroup};
+use crate::types::{EpochId, SpaceId};
+use crate::{Error, Result};
+use aes_gcm::{
+    aead::{Aead, KeyInit, OsRng},
+    Aes256Gcm, Nonce,
+};
+use minicbor::{Decode, Encode};
+use sha2::{Digest, Sha256};
+use std::collections::{BTreeMap, HashMap};
+
+/// A Space's keys for one label
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum SpaceKeys {
+    /// Lightweight Space: no MLS group, records are keyed by the public Space ID
+    Public,
+    /// Exporter keys by epoch; the last one is the current epoch's
+    Epochs(BTreeMap<EpochId, [u8; 32]>),
+}
+
+impl SpaceKeys {
+    /// The epoch and key new records are sealed under
+    ///
+    /// `None` for a Lightweight Space.
+    pub fn sealing(&self) -> Result<Option<(EpochId, [u8; 32])>> {
+        match self {
+            SpaceKeys::Public => Ok(None),
+            SpaceKeys::Epochs(keys) => keys.last_key_value()
+                .map(|(epoch, key)| Some((*epoch, *key)))
+                .ok_or_else(|| Error::Crypto("No MLS epoch key to seal with".to_string())),
+        }
+    }
+
+    /// The key a record sealed at `epoch` opens with
+    pub fn opening(&self, epoch: EpochId) -> Result<[u8; 32]> {
+        match self {
+            SpaceKeys::Epochs(keys) => keys.get(&epoch).copied(),
+            SpaceKeys::Public => None,
+        }
+        .ok_or_else(|| Error::Crypto(format!("No key for MLS epoch {}", epoch.0)))
+    }
+}
+
+/// One label's key at one epoch
+#[derive(Clone, Debug, Encode, Decode)]
+struct EpochKey {
+    #[n(0)]
+    epoch: EpochId,
+    #[n(1)]
+    label: String,
+    #[n(2)]
+    key: [u8; 32],
+}
+
+/// Past epochs' keys, sealed for the members of the epoch it was written in
+///
+/// Stored under one DHT key per Space and replaced by whoever commits next,
+/// so a removed member can't open the copy written after their removal.
+#[derive(Clone, Debug, Encode, Decode)]
+pub struct SealedKeyHistory {
+    /// Space the keys belong to
+    #[n(0)]
+    pub space_id: SpaceId,
+
+    /// Epoch whose `KEY_HISTORY` key seals it
+    #[n(1)]
+    pub epoch: EpochId,
+
+    /// AES-GCM nonce (96 bits)
+    #[n(2)]
+    pub nonce: [u8; 12],
+
+    /// Encrypted CBOR list of keys
+    #[n(3)]
+    pub ciphertext: Vec<u8>,
+}
+
+impl SealedKeyHistory {
+    /// Seal every epoch key `group` knows under its current epoch
+    pub fn seal(space_id: SpaceId, group: &MlsGroup) -> Result<Self> {
+        let epoch = group.current_epoch();
+        let key = group.epoch_key(epoch, key_labels::KEY_HISTORY)
+            .ok_or_else(|| Error::Crypto(format!("No key history key for epoch {}", epoch.0)))?;
+
+        let entries: Vec<EpochKey> = group.known_epoch_keys().iter()
+            .flat_map(|(epoch, labels)| labels.iter().map(|(label, key)| EpochKey {
+                epoch: *epoch,
+                label: label.clone(),
+                key: *key,
+            }))
+            .collect();
+        let plaintext = minicbor::to_vec(&entries)
+            .map_err(|e| Error::Serialization(format!("Failed to encode key history: {}", e)))?;
+
+        let mut nonce_bytes = [0u8; 12];
+        use rand::RngCore;
+        OsRng.fill_bytes(&mut nonce_bytes);
+        let cipher = Aes256Gcm::new_from_slice(&key)
+            .map_err(|e| Error::Crypto(format!("Failed to create cipher: {}", e)))?;
+        let ciphertext = cipher.encrypt(Nonce::from_slice(&nonce_bytes), plaintext.as_ref())
+            .map_err(|e| Error::Crypto(format!("Failed to encrypt key history: {}", e)))?;
+
+        Ok(Self { space_id, epoch, nonce: nonce_bytes, ciphertext })
+    }
+
+    /// Open the history with the key `group` holds for its epoch
+    pub fn open(&self, group: &MlsGroup) -> Result<BTreeMap<EpochId, HashMap<String, [u8; 32]>>> {
+        let key = group.epoch_key(self.epoch, key_labels::KEY_HISTORY)
+            .ok_or_else(|| Error::Crypto(format!("No key history key for epoch {}", self.epoch.0)))?;
+        let cipher = Aes256Gcm::new_from_slice(&key)
+            .map_err(|e| Error::Crypto(format!("Failed to create cipher: {}", e)))?;
+        let plaintext = cipher.decrypt(Nonce::from_slice(&self.nonce), self.ciphertext.as_ref())
+            .map_err(|e| Error::Crypto(format!("Failed to decrypt key history: {}", e)))?;
+        let entries: Vec<EpochKey> = minicbor::decode(&plaintext)
+            .map_err(|e| Error::Serialization(format!("Failed to decode key history: {}", e)))?;
+
+        let mut keys: BTreeMap<EpochId, HashMap<String, [u8; 32]>> = BTreeMap::new();
+        for entry in entries {
+            keys.entry(entry.epoch).or_default().insert(entry.label, entry.key);
+        }
+        Ok(keys)
+    }
+
+    /// DHT key for a Space's key history
+    ///
+    /// Format: SHA-256(b"DESCORD_KEY_HISTORY:" + space_id)
+    pub fn dht_key(space_id: &SpaceId) -> Vec<u8> {
+        let mut hasher = Sha256::new();
+        hasher.update(b"DESCORD_KEY_HISTORY:");
+        hasher.update(space_id.as_bytes());
+        hasher.finalize().to_vec()
+    }
+
+    /// Serialize to CBOR bytes for DHT storage
+    pub fn to_bytes(&self) -> Result<Vec<u8>> {
+        minicbor::to_vec(self)
+            .map_err(|e| Error::Serialization(format!("Failed to encode SealedKeyHistory: {}", e)))
+    }
+
+    /// Deserialize from CBOR bytes
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self> {
+        minicbor::decode(bytes)
+            .map_err(|e| Error::Serialization(format!("Failed to decode SealedKeyHistory: {}", e)))
+    }
+}
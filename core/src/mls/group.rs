@@ -13,7 +13,7 @@ use crate::{Error, Result};
 
 use openmls::prelude::*;
 use openmls_basic_credential::SignatureKeyPair;
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::sync::Arc;
 
 /// Configuration for MLS group creation
@@ -48,6 +48,12 @@ pub struct MlsGroup {
     /// Member roles (UserId -> Role mapping)
     /// Stored locally and synced via MLS application messages
     member_roles: HashMap<UserId, Role>,
+    
+    /// Exporter keys of every epoch this member has been in, by label
+    ///
+    /// The exporter only yields the current epoch's keys, so each epoch's are
+    /// kept as it ends; records sealed under an older epoch still open.
+    epoch_keys: BTreeMap<EpochId, HashMap<String, [u8; 32]>>,
 }
 
 impl MlsGroup {
@@ -85,13 +91,16 @@ impl MlsGroup {
         let mut member_roles = HashMap::new();
         member_roles.insert(creator_id, Role::Admin);
 
-        Ok(Self {
+        let mut group = Self {
             group,
             space_id,
             current_epoch: EpochId(0),
             signer,
             member_roles,
-        })
+            epoch_keys: BTreeMap::new(),
+        };
+        group.remember_epoch_keys(provider)?;
+        Ok(group)
     }
 
     /// Get current epoch
@@ -186,11 +195,12 @@ impl MlsGroup {
         
         // Increment epoch
         self.current_epoch = EpochId(self.current_epoch.0 + 1);
+        self.remember_epoch_keys(provider)?;
 
         // Add to local role mapping
         self.member_roles.insert(user_id, role);
         
-        println!("✓ Added member {} to MLS group (epoch {})", user_id, self.current_epoch.0);
+        tracing::info!("Added member {} to MLS group (epoch {})", user_id, self.current_epoch.0);
         
         // Return the commit message and welcome message
         // Note: welcome_msg might be an MlsMessageOut, need to extract Welcome
@@ -255,11 +265,12 @@ impl MlsGroup {
 
         // Increment epoch
         self.current_epoch = EpochId(self.group.epoch().as_u64());
+        self.remember_epoch_keys(provider)?;
 
         // Remove from local role mapping
         self.member_roles.remove(user_id);
         
-        println!("✓ Removed member {} from MLS group (epoch {})", user_id, self.current_epoch.0);
+        tracing::info!("Removed member {} from MLS group (epoch {})", user_id, self.current_epoch.0);
         
         // Return the Commit message that must be broadcast to remaining members
         Ok(mls_message)
@@ -312,13 +323,79 @@ impl MlsGroup {
         let mut member_roles = HashMap::new();
         member_roles.insert(user_id, Role::Member);
         
-        Ok(Self {
+        let mut group = Self {
             group: mls_group,
             space_id,
             current_epoch,
             signer,
             member_roles,
-        })
+            epoch_keys: BTreeMap::new(),
+        };
+        group.remember_epoch_keys(provider)?;
+        Ok(group)
+    }
+
+    /// Export a GroupInfo (with ratchet tree) that lets outsiders join by external commit
+    pub fn export_group_info(&self, provider: &DescordProvider) -> Result<Vec<u8>> {
+        self.group
+            .export_group_info(OpenMlsProvider::crypto(provider), &*self.signer, true)
+            .map_err(|e| Error::Crypto(format!("Failed to export GroupInfo: {:?}", e)))?
+            .to_bytes()
+            .map_err(|e| Error::Serialization(format!("Failed to serialize GroupInfo: {:?}", e)))
+    }
+
+    /// Join a group without a Welcome, from a GroupInfo exported by a member
+    ///
+    /// # Returns
+    /// The joined group, and the external Commit that existing members must
+    /// process to include us
+    pub fn join_by_external_commit(
+        group_info_bytes: &[u8],
+        space_id: SpaceId,
+        user_id: UserId,
+        signer: Arc<SignatureKeyPair>,
+        provider: &DescordProvider,
+    ) -> Result<(Self, Vec<u8>)> {
+        use tls_codec::Deserialize;
+        let mls_message_in = openmls::framing::MlsMessageIn::tls_deserialize(&mut &group_info_bytes[..])
+            .map_err(|e| Error::Serialization(format!("Failed to deserialize GroupInfo: {:?}", e)))?;
+        let group_info = match mls_message_in.extract() {
+            openmls::framing::MlsMessageBodyIn::GroupInfo(group_info) => group_info,
+            _ => return Err(Error::Serialization("Expected GroupInfo message, got something else".to_string())),
+        };
+
+        let credential = BasicCredential::new(user_id.0.to_vec());
+        let (group, bundle) = openmls::group::MlsGroup::external_commit_builder()
+            .with_config(MlsGroupJoinConfig::default())
+            .build_group(provider, group_info, CredentialWithKey {
+                credential: credential.into(),
+                signature_key: signer.public().into(),
+            })
+            .map_err(|e| Error::Crypto(format!("Failed to join group by external commit: {:?}", e)))?
+            .load_psks(OpenMlsProvider::storage(provider))
+            .map_err(|e| Error::Crypto(format!("Failed to load PSKs for external commit: {:?}", e)))?
+            .build(OpenMlsProvider::rand(provider), OpenMlsProvider::crypto(provider), &*signer, |_| true)
+            .map_err(|e| Error::Crypto(format!("Failed to build external commit: {:?}", e)))?
+            .finalize(provider)
+            .map_err(|e| Error::Crypto(format!("Failed to finalize external commit: {:?}", e)))?;
+
+        let commit_bytes = bundle.into_contents().0.to_bytes()
+            .map_err(|e| Error::Serialization(format!("Failed to serialize Commit: {:?}", e)))?;
+
+        let current_epoch = EpochId(group.epoch().as_u64());
+        let mut member_roles = HashMap::new();
+        member_roles.insert(user_id, Role::Member);
+
+        let mut group = Self {
+            group,
+            space_id,
+            current_epoch,
+            signer,
+            member_roles,
+            epoch_keys: BTreeMap::new(),
+        };
+        group.remember_epoch_keys(provider)?;
+        Ok((group, commit_bytes))
     }
 
     /// Encrypt application message data using MLS
@@ -354,6 +431,20 @@ impl MlsGroup {
         encrypted_bytes: &[u8],
         provider: &DescordProvider,
     ) -> Result<Vec<u8>> {
+        self.decrypt_application_message_with_sender(encrypted_bytes, provider)
+            .map(|(plaintext, _)| plaintext)
+    }
+
+    /// Decrypt an application message and identify who sent it
+    ///
+    /// The sender is the user whose identity key signed the message, or
+    /// `None` when the sender's leaf isn't bound to a user ID (see
+    /// [`crate::mls::bound_user`]).
+    pub fn decrypt_application_message_with_sender(
+        &mut self,
+        encrypted_bytes: &[u8],
+        provider: &DescordProvider,
+    ) -> Result<(Vec<u8>, Option<UserId>)> {
         use tls_codec::Deserialize;
         
         // Deserialize the MlsMessageIn
@@ -368,17 +459,25 @@ impl MlsGroup {
         let processed_message = self.group
             .process_message(provider, protocol_message)
             .map_err(|e| {
-                eprintln!("  🔍 MLS DECRYPTION DEBUG:");
-                eprintln!("     Current epoch: {}", self.current_epoch.0);
-                eprintln!("     Group members: {}", self.member_roles.len());
-                eprintln!("     Error details: {:?}", e);
+                tracing::debug!(
+                    epoch = self.current_epoch.0,
+                    members = self.member_roles.len(),
+                    error = ?e,
+                    "Failed to process MLS message"
+                );
                 Error::Crypto(format!("Failed to process MLS message: {:?}", e))
             })?;
         
+        let sender = match processed_message.sender() {
+            Sender::Member(index) => self.group.member_at(*index)
+                .and_then(|member| crate::mls::bound_user(&member.credential, &member.signature_key)),
+            _ => None,
+        };
+
         // Extract the application message
         match processed_message.into_content() {
             ProcessedMessageContent::ApplicationMessage(app_msg) => {
-                Ok(app_msg.into_bytes())
+                Ok((app_msg.into_bytes(), sender))
             }
             ProcessedMessageContent::ProposalMessage(_) => {
                 Err(Error::Crypto("Received proposal instead of application message".to_string()))
@@ -427,8 +526,9 @@ impl MlsGroup {
                 
                 // Update our local epoch
                 self.current_epoch = EpochId(self.group.epoch().as_u64());
+                self.remember_epoch_keys(provider)?;
                 
-                println!("  ✓ Processed Commit - updated to epoch {}", self.current_epoch.0);
+                tracing::debug!("Processed Commit - updated to epoch {}", self.current_epoch.0);
                 Ok(())
             }
             _ => {
@@ -445,11 +545,77 @@ impl MlsGroup {
         self.member_roles.remove(user_id);
     }
 
+    /// Whether this member is still in the group (false once a Commit
+    /// removing them has been merged)
+    pub fn is_active(&self) -> bool {
+        self.group.is_active()
+    }
+
     /// Get the current epoch of the MLS group
     pub fn current_epoch(&self) -> EpochId {
         self.current_epoch
     }
 
+    /// Derive a 256-bit symmetric key from the current epoch's exporter secret
+    ///
+    /// Only members at this epoch can derive it, and it changes with every
+    /// Commit, so a removed member can't derive the keys of later epochs.
+    /// `label` keeps keys for different uses apart (see [`crate::mls::key_labels`]).
+    pub fn export_key(&self, label: &str, provider: &DescordProvider) -> Result<[u8; 32]> {
+        let secret = self.group
+            .export_secret(OpenMlsProvider::crypto(provider), label, &[], 32)
+            .map_err(|e| Error::Crypto(format!("Failed to export MLS secret: {:?}", e)))?;
+        secret.try_into()
+            .map_err(|_| Error::Crypto("MLS exporter returned a key of the wrong length".to_string()))
+    }
+
+    /// Derive and keep every label's key for the current epoch
+    fn remember_epoch_keys(&mut self, provider: &DescordProvider) -> Result<()> {
+        let mut keys = HashMap::new();
+        for label in crate::mls::key_labels::ALL {
+            keys.insert(label.to_string(), self.export_key(label, provider)?);
+        }
+        self.epoch_keys.insert(self.current_epoch, keys);
+        Ok(())
+    }
+
+    /// The key for `label` at `epoch`, if this member has held that epoch
+    pub fn epoch_key(&self, epoch: EpochId, label: &str) -> Option<[u8; 32]> {
+        self.epoch_keys.get(&epoch)?.get(label).copied()
+    }
+
+    /// Every known epoch's key for `label`
+    pub fn space_keys(&self, label: &str) -> crate::mls::SpaceKeys {
+        crate::mls::SpaceKeys::Epochs(
+            self.epoch_keys.iter()
+                .filter_map(|(epoch, keys)| Some((*epoch, *keys.get(label)?)))
+                .collect()
+        )
+    }
+
+    /// Keys of every epoch this member knows, by label
+    pub fn known_epoch_keys(&self) -> &BTreeMap<EpochId, HashMap<String, [u8; 32]>> {
+        &self.epoch_keys
+    }
+
+    /// Whether some epoch before the current one has no keys here (we
+    /// joined after it, or were out of the group during it)
+    pub fn is_missing_epochs(&self) -> bool {
+        (self.epoch_keys.len() as u64) <= self.current_epoch.0
+    }
+
+    /// Take past epochs' keys handed on by another member
+    ///
+    /// Only epochs before the current one that we have no keys for are
+    /// added; keys we derived ourselves are never replaced.
+    pub fn import_epoch_keys(&mut self, keys: BTreeMap<EpochId, HashMap<String, [u8; 32]>>) {
+        for (epoch, labels) in keys {
+            if epoch < self.current_epoch {
+                self.epoch_keys.entry(epoch).or_insert(labels);
+            }
+        }
+    }
+
     /// Check if user has permission to perform an action
     pub fn check_permission<F>(&self, user_id: &UserId, check: F) -> Result<()>
     where
@@ -469,6 +635,12 @@ impl MlsGroup {
     pub fn members_with_roles(&self) -> &HashMap<UserId, Role> {
         &self.member_roles
     }
+
+    /// Delete this group's persisted state (tree, epoch secrets) from the provider
+    pub fn delete(mut self, provider: &DescordProvider) -> Result<()> {
+        self.group.delete(provider.storage())
+            .map_err(|e| Error::Mls(format!("Failed to delete group state: {:?}", e)))
+    }
 }
 
 #[cfg(test)]
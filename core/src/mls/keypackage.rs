@@ -51,6 +51,9 @@ pub struct KeyPackageStore {
     /// Generated OpenMLS KeyPackageBundles waiting to be used
     /// These bundles maintain the connection to private keys in the provider
     available_bundles: Vec<openmls::prelude::KeyPackageBundle>,
+    
+    /// KeyPackages handed out so far (each is used at most once)
+    consumed: usize,
 }
 
 impl KeyPackageStore {
@@ -65,7 +68,18 @@ impl KeyPackageStore {
             signer,
             ciphersuite,
             available_bundles: Vec::new(),
+            consumed: 0,
+        }
+    }
+
+    /// Generate KeyPackages until at least `min` are unused, returning how
+    /// many were generated
+    pub fn ensure_available(&mut self, min: usize, provider: &DescordProvider) -> Result<usize> {
+        let missing = min.saturating_sub(self.available_bundles.len());
+        if missing > 0 {
+            self.generate_key_packages(missing, provider)?;
         }
+        Ok(missing)
     }
 
     /// Generate a batch of KeyPackages
@@ -109,7 +123,7 @@ impl KeyPackageStore {
             // Get the KeyPackage hash for debugging
             let kp_hash = key_package.hash_ref(provider.crypto())
                 .map_err(|e| Error::Crypto(format!("Failed to compute KeyPackage hash: {:?}", e)))?;
-            println!("✓ Generated KeyPackage with hash: {:?}", hex::encode(kp_hash.as_slice()));
+            tracing::debug!("Generated KeyPackage with hash: {:?}", hex::encode(kp_hash.as_slice()));
             
             // Serialize the KeyPackage using TLS codec (required by OpenMLS)
             use tls_codec::Serialize;
@@ -178,7 +192,7 @@ impl KeyPackageStore {
         // Get the KeyPackage hash for debugging
         let kp_hash = key_package.hash_ref(provider.crypto())
             .map_err(|e| Error::Crypto(format!("Failed to compute KeyPackage hash after deserialization: {:?}", e)))?;
-        println!("✓ Deserialized and validated KeyPackage with hash: {:?}", hex::encode(kp_hash.as_slice()));
+        tracing::debug!("Deserialized and validated KeyPackage with hash: {:?}", hex::encode(kp_hash.as_slice()));
         
         Ok(key_package)
     }
@@ -188,6 +202,11 @@ impl KeyPackageStore {
         self.available_bundles.len()
     }
     
+    /// Number of KeyPackages handed out since this store was created
+    pub fn consumed_count(&self) -> usize {
+        self.consumed
+    }
+    
     /// Get a clone of the signer Arc (for Welcome message processing)
     pub fn signer(&self) -> Arc<SignatureKeyPair> {
         Arc::clone(&self.signer)
@@ -195,13 +214,13 @@ impl KeyPackageStore {
 
     /// Consume a KeyPackage (removes it from available pool)
     /// Returns the KeyPackage extracted from the bundle
-    /// NOTE: Keeps a clone of the bundle to maintain private key references
+    /// 
+    /// KeyPackages are one-time: once handed out, a package is never offered
+    /// again. Its private keys stay in the provider until a Welcome uses them.
     pub fn consume_key_package(&mut self) -> Option<KeyPackage> {
-        if let Some(bundle) = self.available_bundles.last() {
-            Some(bundle.key_package().clone())
-        } else {
-            None
-        }
+        let bundle = self.available_bundles.pop()?;
+        self.consumed += 1;
+        Some(bundle.key_package().clone())
     }
     
     /// Get a KeyPackage bundle (consuming one KeyPackage from the pool)
@@ -209,7 +228,24 @@ impl KeyPackageStore {
     pub fn get_key_package_bundle(&mut self) -> Result<KeyPackageBundle> {
         let key_package = self.consume_key_package()
             .ok_or_else(|| Error::NotFound("No KeyPackages available".to_string()))?;
-        
+        self.to_bundle(&key_package)
+    }
+    
+    /// Consume `count` KeyPackages from the pool as serialized bundles
+    /// (fewer if the pool runs out)
+    pub fn take_key_package_bundles(&mut self, count: usize) -> Result<Vec<KeyPackageBundle>> {
+        let mut bundles = Vec::with_capacity(count);
+        while bundles.len() < count {
+            let Some(key_package) = self.consume_key_package() else {
+                break;
+            };
+            bundles.push(self.to_bundle(&key_package)?);
+        }
+        Ok(bundles)
+    }
+    
+    /// Wrap a KeyPackage for transmission
+    fn to_bundle(&self, key_package: &KeyPackage) -> Result<KeyPackageBundle> {
         // Serialize the KeyPackage using TLS codec
         use tls_codec::Serialize;
         let key_package_bytes = key_package.tls_serialize_detached()
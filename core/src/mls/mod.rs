@@ -6,7 +6,54 @@
 pub mod group;
 pub mod provider;
 pub mod keypackage;
+pub mod epoch_keys;
 
 pub use group::{MlsGroup, MlsGroupConfig};
-pub use provider::DescordProvider;
+pub use provider::{DescordProvider, MlsBackend};
 pub use keypackage::{KeyPackageBundle, KeyPackageStore};
+pub use epoch_keys::{SealedKeyHistory, SpaceKeys};
+
+/// Exporter labels for keys derived with [`MlsGroup::export_key`]
+pub mod key_labels {
+    /// Space metadata shared with members only
+    pub const SPACE_METADATA: &str = "spaceway space metadata";
+    /// Operation batches sealed for members only
+    pub const OP_BATCH: &str = "spaceway op batch";
+    /// Blobs sealed for members only
+    pub const BLOB: &str = "spaceway blob";
+    /// Past epochs' keys, handed on to later members
+    pub const KEY_HISTORY: &str = "spaceway key history";
+
+    /// Every label, derived and kept at each epoch change
+    pub const ALL: [&str; 4] = [SPACE_METADATA, OP_BATCH, BLOB, KEY_HISTORY];
+}
+
+use crate::crypto::signing::Signer;
+use crate::types::UserId;
+use crate::{Error, Result};
+use openmls::prelude::{BasicCredential, Credential};
+use openmls_basic_credential::SignatureKeyPair;
+use std::sync::Arc;
+
+/// MLS signature key for a user's groups and KeyPackages
+///
+/// Uses the identity key itself so the leaf is bound to the user ID (see
+/// [`bound_user`]). Signers that can't export their secret, such as a
+/// hardware token, can't sign for MLS: an unbound leaf would let its holder
+/// send ops under any author, so peers reject what it sends.
+pub fn identity_signer(identity: &dyn Signer) -> Result<Arc<SignatureKeyPair>> {
+    identity.mls_signature_key()
+        .map(Arc::new)
+        .ok_or_else(|| Error::Crypto("Identity signer can't export an MLS signature key".to_string()))
+}
+
+/// The user an MLS leaf provably belongs to
+///
+/// A leaf is bound when its credential identity is the signature key it
+/// signs with; since a user ID is the Ed25519 public key, only the holder
+/// of that identity key can produce it. Returns `None` for unbound leaves.
+pub fn bound_user(credential: &Credential, signature_key: &[u8]) -> Option<UserId> {
+    let credential = BasicCredential::try_from(credential.clone()).ok()?;
+    let identity: [u8; 32] = credential.identity().try_into().ok()?;
+    (identity.as_slice() == signature_key).then_some(UserId(identity))
+}
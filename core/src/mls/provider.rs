@@ -1,15 +1,245 @@
 //! OpenMLS crypto provider implementation
 //!
-//! Provides the cryptographic backend for OpenMLS using rust-crypto
+//! Provides the cryptographic backend for OpenMLS. Crypto primitives and
+//! randomness come from an [`MlsBackend`] (rust-crypto by default); MLS group
+//! state lives in an in-memory key store.
 
-use openmls_rust_crypto::OpenMlsRustCrypto;
+use openmls::prelude::tls_codec::SecretVLBytes;
+use openmls_rust_crypto::{MemoryStorage, RustCrypto};
+use openmls_traits::crypto::OpenMlsCrypto;
+use openmls_traits::random::OpenMlsRand;
+use openmls_traits::types::{
+    AeadType, Ciphersuite, CryptoError, ExporterSecret, HashType, HpkeCiphertext, HpkeConfig,
+    HpkeKeyPair, KemOutput, SignatureScheme,
+};
+use openmls_traits::OpenMlsProvider;
+use std::sync::Arc;
 
-/// Descord's OpenMLS crypto provider
+/// Crypto and randomness source for MLS
 ///
-/// Wraps OpenMlsRustCrypto which provides both crypto and storage
-pub type DescordProvider = OpenMlsRustCrypto;
+/// Implement this to run MLS on something other than rust-crypto, e.g. keys
+/// held in an HSM or WebCrypto under WASM, and inject it with
+/// [`crate::ClientBuilder::mls_backend`].
+pub trait MlsBackend: Send + Sync {
+    /// The crypto primitives
+    fn crypto(&self) -> &dyn OpenMlsCrypto;
+
+    /// Fill `buf` with cryptographically secure random bytes
+    fn fill_random(&self, buf: &mut [u8]) -> Result<(), CryptoError>;
+}
+
+impl MlsBackend for RustCrypto {
+    fn crypto(&self) -> &dyn OpenMlsCrypto {
+        self
+    }
+
+    fn fill_random(&self, buf: &mut [u8]) -> Result<(), CryptoError> {
+        let bytes = self.random_vec(buf.len())
+            .map_err(|_| CryptoError::InsufficientRandomness)?;
+        buf.copy_from_slice(&bytes);
+        Ok(())
+    }
+}
+
+/// Descord's OpenMLS provider
+///
+/// Pairs an [`MlsBackend`] with the key store for MLS group state. The
+/// default backend is rust-crypto.
+pub struct DescordProvider {
+    backend: SharedBackend,
+    key_store: MemoryStorage,
+}
+
+impl DescordProvider {
+    /// Create a provider on the given backend
+    pub fn with_backend(backend: Arc<dyn MlsBackend>) -> Self {
+        Self {
+            backend: SharedBackend(backend),
+            key_store: MemoryStorage::default(),
+        }
+    }
+}
+
+impl Default for DescordProvider {
+    fn default() -> Self {
+        Self::with_backend(Arc::new(RustCrypto::default()))
+    }
+}
+
+impl OpenMlsProvider for DescordProvider {
+    type CryptoProvider = SharedBackend;
+    type RandProvider = SharedBackend;
+    type StorageProvider = MemoryStorage;
+
+    fn storage(&self) -> &Self::StorageProvider {
+        &self.key_store
+    }
+
+    fn crypto(&self) -> &Self::CryptoProvider {
+        &self.backend
+    }
+
+    fn rand(&self) -> &Self::RandProvider {
+        &self.backend
+    }
+}
+
+/// An [`MlsBackend`] as OpenMLS's crypto and randomness provider
+#[derive(Clone)]
+pub struct SharedBackend(Arc<dyn MlsBackend>);
+
+impl OpenMlsCrypto for SharedBackend {
+    fn supports(&self, ciphersuite: Ciphersuite) -> Result<(), CryptoError> {
+        self.0.crypto().supports(ciphersuite)
+    }
+
+    fn supported_ciphersuites(&self) -> Vec<Ciphersuite> {
+        self.0.crypto().supported_ciphersuites()
+    }
+
+    fn hkdf_extract(
+        &self,
+        hash_type: HashType,
+        salt: &[u8],
+        ikm: &[u8],
+    ) -> Result<SecretVLBytes, CryptoError> {
+        self.0.crypto().hkdf_extract(hash_type, salt, ikm)
+    }
+
+    fn hmac(
+        &self,
+        hash_type: HashType,
+        key: &[u8],
+        message: &[u8],
+    ) -> Result<SecretVLBytes, CryptoError> {
+        self.0.crypto().hmac(hash_type, key, message)
+    }
+
+    fn hkdf_expand(
+        &self,
+        hash_type: HashType,
+        prk: &[u8],
+        info: &[u8],
+        okm_len: usize,
+    ) -> Result<SecretVLBytes, CryptoError> {
+        self.0.crypto().hkdf_expand(hash_type, prk, info, okm_len)
+    }
+
+    fn hash(&self, hash_type: HashType, data: &[u8]) -> Result<Vec<u8>, CryptoError> {
+        self.0.crypto().hash(hash_type, data)
+    }
+
+    fn aead_encrypt(
+        &self,
+        alg: AeadType,
+        key: &[u8],
+        data: &[u8],
+        nonce: &[u8],
+        aad: &[u8],
+    ) -> Result<Vec<u8>, CryptoError> {
+        self.0.crypto().aead_encrypt(alg, key, data, nonce, aad)
+    }
+
+    fn aead_decrypt(
+        &self,
+        alg: AeadType,
+        key: &[u8],
+        ct_tag: &[u8],
+        nonce: &[u8],
+        aad: &[u8],
+    ) -> Result<Vec<u8>, CryptoError> {
+        self.0.crypto().aead_decrypt(alg, key, ct_tag, nonce, aad)
+    }
+
+    fn signature_key_gen(&self, alg: SignatureScheme) -> Result<(Vec<u8>, Vec<u8>), CryptoError> {
+        self.0.crypto().signature_key_gen(alg)
+    }
+
+    fn verify_signature(
+        &self,
+        alg: SignatureScheme,
+        data: &[u8],
+        pk: &[u8],
+        signature: &[u8],
+    ) -> Result<(), CryptoError> {
+        self.0.crypto().verify_signature(alg, data, pk, signature)
+    }
+
+    fn sign(&self, alg: SignatureScheme, data: &[u8], key: &[u8]) -> Result<Vec<u8>, CryptoError> {
+        self.0.crypto().sign(alg, data, key)
+    }
+
+    fn hpke_seal(
+        &self,
+        config: HpkeConfig,
+        pk_r: &[u8],
+        info: &[u8],
+        aad: &[u8],
+        ptxt: &[u8],
+    ) -> Result<HpkeCiphertext, CryptoError> {
+        self.0.crypto().hpke_seal(config, pk_r, info, aad, ptxt)
+    }
+
+    fn hpke_open(
+        &self,
+        config: HpkeConfig,
+        input: &HpkeCiphertext,
+        sk_r: &[u8],
+        info: &[u8],
+        aad: &[u8],
+    ) -> Result<Vec<u8>, CryptoError> {
+        self.0.crypto().hpke_open(config, input, sk_r, info, aad)
+    }
+
+    fn hpke_setup_sender_and_export(
+        &self,
+        config: HpkeConfig,
+        pk_r: &[u8],
+        info: &[u8],
+        exporter_context: &[u8],
+        exporter_length: usize,
+    ) -> Result<(KemOutput, ExporterSecret), CryptoError> {
+        self.0.crypto().hpke_setup_sender_and_export(config, pk_r, info, exporter_context, exporter_length)
+    }
+
+    fn hpke_setup_receiver_and_export(
+        &self,
+        config: HpkeConfig,
+        enc: &[u8],
+        sk_r: &[u8],
+        info: &[u8],
+        exporter_context: &[u8],
+        exporter_length: usize,
+    ) -> Result<ExporterSecret, CryptoError> {
+        self.0.crypto().hpke_setup_receiver_and_export(config, enc, sk_r, info, exporter_context, exporter_length)
+    }
+
+    fn derive_hpke_keypair(
+        &self,
+        config: HpkeConfig,
+        ikm: &[u8],
+    ) -> Result<HpkeKeyPair, CryptoError> {
+        self.0.crypto().derive_hpke_keypair(config, ikm)
+    }
+}
+
+impl OpenMlsRand for SharedBackend {
+    type Error = CryptoError;
+
+    fn random_array<const N: usize>(&self) -> Result<[u8; N], Self::Error> {
+        let mut out = [0u8; N];
+        self.0.fill_random(&mut out)?;
+        Ok(out)
+    }
+
+    fn random_vec(&self, len: usize) -> Result<Vec<u8>, Self::Error> {
+        let mut out = vec![0u8; len];
+        self.0.fill_random(&mut out)?;
+        Ok(out)
+    }
+}
 
 /// Create a new crypto provider instance
 pub fn create_provider() -> DescordProvider {
-    OpenMlsRustCrypto::default()
+    DescordProvider::default()
 }
@@ -0,0 +1,163 @@
+//! Delivery acknowledgements
+//!
+//! Receivers that opt in confirm new operations with a signed [`Ack`] on the
+//! Space's ack topic. Acks are ephemeral: they are never stored as CRDT
+//! operations. To avoid amplification, a receiver batches the op IDs it has
+//! seen and publishes at most one ack per Space per flush interval, and a
+//! sender only records acks for operations it broadcast itself. Acks carry a
+//! per-receiver stamp, so a replayed one is dropped.
+
+use crate::crypto::signing::{PublicKey, Signer};
+use crate::network::replay::ReplayGuard;
+use crate::types::*;
+use crate::{Error, Result};
+use minicbor::{Decode, Encode};
+use std::collections::{HashMap, VecDeque};
+use std::time::Duration;
+
+/// How often a receiver publishes its batched acks
+pub const ACK_FLUSH_INTERVAL: Duration = Duration::from_millis(500);
+
+/// Most op IDs carried by one ack
+pub const MAX_ACK_BATCH: usize = 64;
+
+/// Most sent operations whose delivery is tracked at once
+pub const MAX_TRACKED_OPS: usize = 1024;
+
+/// Topic a Space's acks are published on
+pub fn ack_topic(space_id: &SpaceId) -> String {
+    format!("space/{}/acks", space_id.short())
+}
+
+/// Signed confirmation that `from` received the listed operations
+#[derive(Debug, Clone, PartialEq, Eq, Encode, Decode)]
+pub struct Ack {
+    /// Space the operations belong to
+    #[n(0)]
+    pub space_id: SpaceId,
+    /// Acknowledged operations
+    #[n(1)]
+    pub op_ids: Vec<OpId>,
+    /// Receiver sending the ack
+    #[n(2)]
+    pub from: UserId,
+    /// Receiver's signature over the Space, op IDs and stamp
+    #[n(3)]
+    pub signature: Signature,
+    /// Receiver's stamp in milliseconds; higher than any ack it sent before
+    #[n(4)]
+    pub sent_at: u64,
+}
+
+impl Ack {
+    /// Create and sign an ack for a batch of operations
+    pub fn new(signer: &dyn Signer, space_id: SpaceId, op_ids: Vec<OpId>, sent_at: u64) -> Result<Self> {
+        let signature = signer.sign(&Self::signing_bytes(&space_id, &op_ids, sent_at))?;
+        Ok(Self { space_id, op_ids, from: signer.user_id(), signature, sent_at })
+    }
+
+    fn signing_bytes(space_id: &SpaceId, op_ids: &[OpId], sent_at: u64) -> Vec<u8> {
+        let mut bytes = b"spaceway-ack-v2".to_vec();
+        bytes.extend_from_slice(&space_id.0);
+        for op_id in op_ids {
+            bytes.extend_from_slice(op_id.0.as_bytes());
+        }
+        bytes.extend_from_slice(&sent_at.to_be_bytes());
+        bytes
+    }
+
+    /// Check the receiver's signature
+    pub fn verify(&self) -> bool {
+        PublicKey::from_bytes(&self.from.0)
+            .and_then(|key| key.verify(&Self::signing_bytes(&self.space_id, &self.op_ids, self.sent_at), &self.signature))
+            .is_ok()
+    }
+
+    /// Serialize to CBOR bytes for publishing
+    pub fn to_bytes(&self) -> Result<Vec<u8>> {
+        minicbor::to_vec(self)
+            .map_err(|e| Error::Serialization(format!("Failed to encode Ack: {}", e)))
+    }
+
+    /// Deserialize from CBOR bytes
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self> {
+        minicbor::decode(bytes)
+            .map_err(|e| Error::Serialization(format!("Failed to decode Ack: {}", e)))
+    }
+}
+
+/// Receiver side: op IDs waiting to be acknowledged, per Space
+#[derive(Debug, Default)]
+pub struct AckBatcher {
+    pending: HashMap<SpaceId, Vec<OpId>>,
+}
+
+impl AckBatcher {
+    /// Queue an operation for the next ack of its Space
+    pub fn queue(&mut self, space_id: SpaceId, op_id: OpId) {
+        let pending = self.pending.entry(space_id).or_default();
+        if !pending.contains(&op_id) {
+            pending.push(op_id);
+        }
+    }
+
+    /// Take everything queued, split into batches of at most [`MAX_ACK_BATCH`]
+    pub fn drain(&mut self) -> Vec<(SpaceId, Vec<OpId>)> {
+        self.pending.drain()
+            .flat_map(|(space_id, op_ids)| {
+                op_ids.chunks(MAX_ACK_BATCH)
+                    .map(|chunk| (space_id, chunk.to_vec()))
+                    .collect::<Vec<_>>()
+            })
+            .collect()
+    }
+}
+
+/// Sender side: who has acknowledged each operation we broadcast
+#[derive(Debug, Default)]
+pub struct DeliveryTracker {
+    acks: HashMap<OpId, Vec<UserId>>,
+    /// Tracked ops, oldest first, so the oldest is dropped past [`MAX_TRACKED_OPS`]
+    order: VecDeque<OpId>,
+    /// Newest ack stamp accepted from each receiver, per Space
+    guard: ReplayGuard,
+}
+
+impl DeliveryTracker {
+    /// Start collecting acks for an operation we sent
+    pub fn track(&mut self, op_id: OpId) {
+        if self.acks.contains_key(&op_id) {
+            return;
+        }
+        self.acks.insert(op_id, Vec::new());
+        self.order.push_back(op_id);
+        while self.order.len() > MAX_TRACKED_OPS {
+            if let Some(oldest) = self.order.pop_front() {
+                self.acks.remove(&oldest);
+            }
+        }
+    }
+
+    /// Record a verified ack; op IDs we are not tracking are ignored
+    ///
+    /// Returns false for a replay: an ack whose stamp isn't newer than the
+    /// last one accepted from the same receiver in the same Space.
+    pub fn record(&mut self, ack: &Ack) -> bool {
+        if !self.guard.accept(ack.space_id, ack.from, ack.sent_at) {
+            return false;
+        }
+        for op_id in &ack.op_ids {
+            if let Some(receivers) = self.acks.get_mut(op_id) {
+                if !receivers.contains(&ack.from) {
+                    receivers.push(ack.from);
+                }
+            }
+        }
+        true
+    }
+
+    /// Users that acknowledged an operation, in arrival order
+    pub fn status(&self, op_id: &OpId) -> Vec<UserId> {
+        self.acks.get(op_id).cloned().unwrap_or_default()
+    }
+}
@@ -0,0 +1,222 @@
+//! Anti-entropy
+//!
+//! Gossip only reaches peers that are connected when an op is published, so
+//! two replicas that were partitioned for a moment can each miss the other's
+//! ops for good. Every `ClientConfig::anti_entropy_interval`, a client sends
+//! a [`SyncDigest`] for each subscribed Space to one random connected peer.
+//! The digest carries a per-author clock of the ops it holds. The named peer
+//! answers by re-publishing only the ops that clock shows to be missing, so a
+//! healed partition converges without an explicit `request_space_sync`.
+//!
+//! An explicit `request_space_sync` carries the same clock in a
+//! [`CatchUpRequest`], also naming one connected peer to answer. That peer
+//! only answers a request it received straight from the requester, and sends
+//! the missing ops to the requester's own [`catch_up_topic`] rather than the
+//! Space topic; otherwise each join would re-send the whole Space to everyone.
+//! The answer ends with a [`ResponderClock`], so the requester can tell when
+//! it holds everything that peer had.
+
+use crate::crdt::{CrdtOp, Hlc};
+use crate::types::*;
+use crate::{Error, Result};
+use minicbor::{Decode, Encode};
+use std::collections::BTreeMap;
+use std::time::Duration;
+
+/// Default time between digests (`ClientConfig::anti_entropy_interval`)
+pub const ANTI_ENTROPY_INTERVAL: Duration = Duration::from_secs(30);
+
+/// Topic a Space's sync digests are published on
+pub fn sync_topic(space_id: &SpaceId) -> String {
+    format!("space/{}/sync", space_id.short())
+}
+
+/// Marks a `CatchUpRequest` published on a Space topic
+pub const SYNC_REQUEST_PREFIX: &[u8] = b"SYNC_REQUEST:";
+
+/// Marks a `ResponderClock` published on a catch-up topic
+pub const SYNC_CLOCK_PREFIX: &[u8] = b"SYNC_CLOCK:";
+
+/// Topic only `peer_id` subscribes to, carrying the ops it asked for
+pub fn catch_up_topic(peer_id: &str) -> String {
+    format!("peer/{}/catch-up", peer_id)
+}
+
+/// How many ops a replica holds from one author, and the newest of them
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Encode, Decode)]
+pub struct ClockEntry {
+    /// Author of the ops
+    #[n(0)]
+    pub author: UserId,
+    /// Number of the author's ops held
+    #[n(1)]
+    pub count: u64,
+    /// HLC of the author's newest op held
+    #[n(2)]
+    pub latest: Hlc,
+}
+
+/// Per-author summary of the ops a replica holds for a Space
+#[derive(Debug, Clone, Default, PartialEq, Eq, Encode, Decode)]
+pub struct SpaceClock {
+    /// One entry per author, sorted by author
+    #[n(0)]
+    pub entries: Vec<ClockEntry>,
+}
+
+impl SpaceClock {
+    /// Summarize a Space's ops
+    pub fn of(ops: &[CrdtOp]) -> Self {
+        let mut by_author: BTreeMap<UserId, ClockEntry> = BTreeMap::new();
+        for op in ops {
+            let entry = by_author.entry(op.author).or_insert(ClockEntry {
+                author: op.author,
+                count: 0,
+                latest: op.hlc,
+            });
+            entry.count += 1;
+            entry.latest = entry.latest.max(op.hlc);
+        }
+        Self { entries: by_author.into_values().collect() }
+    }
+
+    /// Op counts per author as a [`VectorClock`](crate::storage::VectorClock)
+    pub fn to_vector_clock(&self) -> crate::storage::VectorClock {
+        crate::storage::VectorClock {
+            clocks: self.entries.iter()
+                .map(|entry| (entry.author.to_string(), entry.count))
+                .collect(),
+        }
+    }
+
+    /// The entry for an author, if any of their ops are held
+    pub fn get(&self, author: &UserId) -> Option<&ClockEntry> {
+        self.entries
+            .binary_search_by(|entry| entry.author.cmp(author))
+            .ok()
+            .map(|index| &self.entries[index])
+    }
+
+    /// Ops in `ours` that the replica summarized by this clock lacks
+    ///
+    /// Normally that is each author's ops newer than the clock's latest. If
+    /// we hold more of an author's older ops than the clock counts, the other
+    /// replica has gaps, and all of that author's ops are returned. The
+    /// result is in HLC order, so parents tend to arrive before children.
+    pub fn missing_ops(&self, ours: &[CrdtOp]) -> Vec<CrdtOp> {
+        let mut by_author: BTreeMap<UserId, Vec<&CrdtOp>> = BTreeMap::new();
+        for op in ours {
+            by_author.entry(op.author).or_default().push(op);
+        }
+
+        let mut missing = Vec::new();
+        for (author, ops) in by_author {
+            let Some(theirs) = self.get(&author) else {
+                missing.extend(ops);
+                continue;
+            };
+            let newer: Vec<&CrdtOp> = ops.iter().copied().filter(|op| op.hlc > theirs.latest).collect();
+            if (ops.len() - newer.len()) as u64 > theirs.count {
+                missing.extend(ops);
+            } else {
+                missing.extend(newer);
+            }
+        }
+
+        missing.sort_by_key(|op| op.hlc);
+        missing.into_iter().cloned().collect()
+    }
+}
+
+/// A replica's clock for a Space, addressed to the peer that should answer
+#[derive(Debug, Clone, PartialEq, Eq, Encode, Decode)]
+pub struct SyncDigest {
+    /// Space the clock covers
+    #[n(0)]
+    pub space_id: SpaceId,
+    /// libp2p peer ID of the one peer that should answer
+    #[n(1)]
+    pub responder: String,
+    /// Ops the sender holds
+    #[n(2)]
+    pub clock: SpaceClock,
+}
+
+impl SyncDigest {
+    /// Serialize to CBOR bytes for publishing
+    pub fn to_bytes(&self) -> Result<Vec<u8>> {
+        minicbor::to_vec(self)
+            .map_err(|e| Error::Serialization(format!("Failed to encode SyncDigest: {}", e)))
+    }
+
+    /// Deserialize from CBOR bytes
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self> {
+        minicbor::decode(bytes)
+            .map_err(|e| Error::Serialization(format!("Failed to decode SyncDigest: {}", e)))
+    }
+}
+
+/// A request for the ops of a Space that the requester's clock lacks
+#[derive(Debug, Clone, PartialEq, Eq, Encode, Decode)]
+pub struct CatchUpRequest {
+    /// Space to catch up on
+    #[n(0)]
+    pub space_id: SpaceId,
+    /// libp2p peer ID of the requester, whose catch-up topic gets the ops
+    #[n(1)]
+    pub requester: String,
+    /// Ops the requester holds
+    #[n(2)]
+    pub clock: SpaceClock,
+    /// libp2p peer ID of the one peer that should answer
+    #[n(3)]
+    pub responder: String,
+}
+
+impl CatchUpRequest {
+    /// Serialize for publishing, behind `SYNC_REQUEST_PREFIX`
+    pub fn to_bytes(&self) -> Result<Vec<u8>> {
+        let body = minicbor::to_vec(self)
+            .map_err(|e| Error::Serialization(format!("Failed to encode CatchUpRequest: {}", e)))?;
+        Ok([SYNC_REQUEST_PREFIX, &body].concat())
+    }
+
+    /// Deserialize a published request, prefix included
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self> {
+        let body = bytes.strip_prefix(SYNC_REQUEST_PREFIX)
+            .ok_or_else(|| Error::Serialization("Missing SYNC_REQUEST prefix".to_string()))?;
+        minicbor::decode(body)
+            .map_err(|e| Error::Serialization(format!("Failed to decode CatchUpRequest: {}", e)))
+    }
+}
+
+/// The clock of a peer that answered a [`CatchUpRequest`], sent after the ops
+#[derive(Debug, Clone, PartialEq, Eq, Encode, Decode)]
+pub struct ResponderClock {
+    /// Space the clock covers
+    #[n(0)]
+    pub space_id: SpaceId,
+    /// libp2p peer ID of the responder
+    #[n(1)]
+    pub responder: String,
+    /// Ops the responder holds
+    #[n(2)]
+    pub clock: SpaceClock,
+}
+
+impl ResponderClock {
+    /// Serialize for publishing, behind `SYNC_CLOCK_PREFIX`
+    pub fn to_bytes(&self) -> Result<Vec<u8>> {
+        let body = minicbor::to_vec(self)
+            .map_err(|e| Error::Serialization(format!("Failed to encode ResponderClock: {}", e)))?;
+        Ok([SYNC_CLOCK_PREFIX, &body].concat())
+    }
+
+    /// Deserialize a published clock, prefix included
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self> {
+        let body = bytes.strip_prefix(SYNC_CLOCK_PREFIX)
+            .ok_or_else(|| Error::Serialization("Missing SYNC_CLOCK prefix".to_string()))?;
+        minicbor::decode(body)
+            .map_err(|e| Error::Serialization(format!("Failed to decode ResponderClock: {}", e)))
+    }
+}
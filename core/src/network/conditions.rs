@@ -0,0 +1,107 @@
+//! Simulated network conditions
+//!
+//! Lets tests degrade the link between peers by delaying and dropping
+//! inbound GossipSub messages, so reordering and retry paths get exercised
+//! on a single machine. Only built for tests and the `test-utils` feature,
+//! so a release node never drops or delays messages on purpose.
+
+use rand::Rng;
+use std::time::Duration;
+
+/// Latency, jitter and loss applied to inbound GossipSub messages
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct NetworkConditions {
+    /// Fixed delay added to every delivered message
+    pub latency: Duration,
+
+    /// Upper bound of the random extra delay added on top of `latency`
+    pub jitter: Duration,
+
+    /// Probability (0.0 - 1.0) that a message is silently dropped
+    pub loss_rate: f64,
+}
+
+impl Default for NetworkConditions {
+    fn default() -> Self {
+        Self::perfect()
+    }
+}
+
+impl NetworkConditions {
+    /// No delay and no loss (the default)
+    pub fn perfect() -> Self {
+        Self {
+            latency: Duration::ZERO,
+            jitter: Duration::ZERO,
+            loss_rate: 0.0,
+        }
+    }
+
+    /// Drop messages with the given probability, without added delay
+    pub fn lossy(loss_rate: f64) -> Self {
+        Self {
+            loss_rate: loss_rate.clamp(0.0, 1.0),
+            ..Self::perfect()
+        }
+    }
+
+    /// Delay messages by `latency` plus up to `jitter`, without loss
+    pub fn delayed(latency: Duration, jitter: Duration) -> Self {
+        Self {
+            latency,
+            jitter,
+            ..Self::perfect()
+        }
+    }
+
+    /// Check if these conditions leave messages untouched
+    pub fn is_perfect(&self) -> bool {
+        self.latency.is_zero() && self.jitter.is_zero() && self.loss_rate <= 0.0
+    }
+
+    /// Roll whether the next message should be dropped
+    pub fn should_drop(&self) -> bool {
+        self.loss_rate > 0.0 && rand::thread_rng().gen_bool(self.loss_rate.min(1.0))
+    }
+
+    /// Sample the delay for the next delivered message
+    pub fn sample_delay(&self) -> Duration {
+        if self.jitter.is_zero() {
+            return self.latency;
+        }
+        let jitter_ms = rand::thread_rng().gen_range(0..=self.jitter.as_millis() as u64);
+        self.latency + Duration::from_millis(jitter_ms)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_perfect_conditions() {
+        let conditions = NetworkConditions::default();
+        assert!(conditions.is_perfect());
+        assert!(!conditions.should_drop());
+        assert_eq!(conditions.sample_delay(), Duration::ZERO);
+    }
+
+    #[test]
+    fn test_total_loss_always_drops() {
+        let conditions = NetworkConditions::lossy(1.0);
+        assert!((0..100).all(|_| conditions.should_drop()));
+    }
+
+    #[test]
+    fn test_delay_within_jitter_bounds() {
+        let conditions = NetworkConditions::delayed(
+            Duration::from_millis(50),
+            Duration::from_millis(20),
+        );
+        for _ in 0..100 {
+            let delay = conditions.sample_delay();
+            assert!(delay >= Duration::from_millis(50));
+            assert!(delay <= Duration::from_millis(70));
+        }
+    }
+}
@@ -0,0 +1,67 @@
+//! Connection lifetime tuning
+//!
+//! NAT bindings for an idle TCP connection are often dropped after a minute
+//! or two, and the connection then dies without either side noticing. A
+//! libp2p `ping` on every connection keeps the binding fresh and reveals a
+//! dead link, which is then closed so the usual reconnect paths take over.
+
+use libp2p::{ping, swarm};
+use std::time::Duration;
+
+use crate::{Error, Result};
+
+/// Idle timeout and keepalive applied when building the swarm
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ConnectionConfig {
+    /// How long a connection without active streams stays open
+    pub idle_connection_timeout: Duration,
+
+    /// Time between keepalive pings on each connection (`None` disables them)
+    pub keepalive_interval: Option<Duration>,
+
+    /// How long a ping may go unanswered before the connection is closed
+    pub keepalive_timeout: Duration,
+}
+
+impl Default for ConnectionConfig {
+    /// The 60s idle timeout the node always used, plus a ping every 15s
+    /// (well inside common NAT binding timeouts) that fails after 20s
+    fn default() -> Self {
+        Self {
+            idle_connection_timeout: Duration::from_secs(60),
+            keepalive_interval: Some(Duration::from_secs(15)),
+            keepalive_timeout: Duration::from_secs(20),
+        }
+    }
+}
+
+impl ConnectionConfig {
+    /// Check that the timeouts are usable
+    pub fn validate(&self) -> Result<()> {
+        if self.idle_connection_timeout.is_zero() {
+            return Err(Error::Network("Idle connection timeout must be positive".to_string()));
+        }
+        if self.keepalive_interval.is_some_and(|interval| interval.is_zero()) {
+            return Err(Error::Network("Keepalive interval must be positive".to_string()));
+        }
+        if self.keepalive_timeout.is_zero() {
+            return Err(Error::Network("Keepalive timeout must be positive".to_string()));
+        }
+        Ok(())
+    }
+
+    /// Build the swarm config with the idle timeout applied
+    pub fn swarm_config(&self) -> swarm::Config {
+        swarm::Config::with_tokio_executor()
+            .with_idle_connection_timeout(self.idle_connection_timeout)
+    }
+
+    /// Build the ping config, or `None` when keepalive is disabled
+    pub fn ping_config(&self) -> Option<ping::Config> {
+        self.keepalive_interval.map(|interval| {
+            ping::Config::new()
+                .with_interval(interval)
+                .with_timeout(self.keepalive_timeout)
+        })
+    }
+}
@@ -0,0 +1,124 @@
+//! Recently seen operations
+//!
+//! GossipSub hands the same operation over more than once (re-publishes,
+//! anti-entropy, several peers forwarding it), and each copy used to cost a
+//! RocksDB lookup before it could be dropped. [`DedupCache`] keeps the IDs of
+//! the most recently seen operations in memory with exact LRU eviction, so
+//! repeated copies are dropped without touching the store. Only IDs of
+//! operations that are actually in the store are cached, which keeps the
+//! cache from hiding an operation that was dropped before being stored.
+
+use crate::types::OpId;
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap};
+
+/// Operation IDs remembered by default
+pub const DEFAULT_DEDUP_CACHE_CAPACITY: usize = 4096;
+
+/// Hit and miss counts of a [`DedupCache`]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+pub struct DedupCacheStats {
+    /// Operation IDs the cache holds at most
+    pub capacity: usize,
+    /// Operation IDs it holds now
+    pub len: usize,
+    /// Duplicates recognised without a store lookup
+    pub hits: u64,
+    /// Lookups that fell through to the store
+    pub misses: u64,
+    /// IDs dropped to make room for newer ones
+    pub evictions: u64,
+}
+
+/// Bounded LRU set of operation IDs known to be stored
+#[derive(Debug)]
+pub struct DedupCache {
+    capacity: usize,
+    /// Recency stamp of each cached ID
+    entries: HashMap<OpId, u64>,
+    /// Cached IDs by recency stamp, least recently used first
+    recency: BTreeMap<u64, OpId>,
+    next_stamp: u64,
+    hits: u64,
+    misses: u64,
+    evictions: u64,
+}
+
+impl Default for DedupCache {
+    fn default() -> Self {
+        Self::new(DEFAULT_DEDUP_CACHE_CAPACITY)
+    }
+}
+
+impl DedupCache {
+    /// Cache holding up to `capacity` IDs (0 caches nothing)
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            entries: HashMap::new(),
+            recency: BTreeMap::new(),
+            next_stamp: 0,
+            hits: 0,
+            misses: 0,
+            evictions: 0,
+        }
+    }
+
+    /// Whether `op_id` was already seen, asking `in_store` only on a miss
+    ///
+    /// A hit refreshes the ID's recency; an ID the store knows is cached.
+    pub fn is_duplicate(&mut self, op_id: &OpId, in_store: impl FnOnce() -> bool) -> bool {
+        if self.entries.contains_key(op_id) {
+            self.hits += 1;
+            self.touch(*op_id);
+            return true;
+        }
+
+        self.misses += 1;
+        let stored = in_store();
+        if stored {
+            self.touch(*op_id);
+        }
+        stored
+    }
+
+    /// Remember an operation that was just stored
+    pub fn insert(&mut self, op_id: OpId) {
+        self.touch(op_id);
+    }
+
+    /// Whether `op_id` is cached, without counting or refreshing it
+    pub fn contains(&self, op_id: &OpId) -> bool {
+        self.entries.contains_key(op_id)
+    }
+
+    /// Current size and hit/miss counts
+    pub fn stats(&self) -> DedupCacheStats {
+        DedupCacheStats {
+            capacity: self.capacity,
+            len: self.entries.len(),
+            hits: self.hits,
+            misses: self.misses,
+            evictions: self.evictions,
+        }
+    }
+
+    fn touch(&mut self, op_id: OpId) {
+        if self.capacity == 0 {
+            return;
+        }
+
+        let stamp = self.next_stamp;
+        self.next_stamp += 1;
+        if let Some(previous) = self.entries.insert(op_id, stamp) {
+            self.recency.remove(&previous);
+        }
+        self.recency.insert(stamp, op_id);
+
+        while self.entries.len() > self.capacity {
+            let Some((_, oldest)) = self.recency.pop_first() else { break };
+            self.entries.remove(&oldest);
+            self.evictions += 1;
+        }
+    }
+}
@@ -0,0 +1,81 @@
+//! GossipSub tuning
+//!
+//! Chat traffic is many small messages spread over many topics (one per
+//! Space, plus welcome and ack topics), so the defaults favour a fast
+//! heartbeat and a small mesh that still works with only two peers.
+
+use libp2p::gossipsub;
+use std::time::Duration;
+
+use crate::{Error, Result};
+
+/// Heartbeat and mesh parameters applied when building the GossipSub behaviour
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct GossipConfig {
+    /// Time between heartbeats (mesh maintenance and gossip emission)
+    pub heartbeat_interval: Duration,
+
+    /// Target number of peers in each topic mesh
+    pub mesh_n: usize,
+
+    /// Below this many mesh peers, more are grafted
+    pub mesh_n_low: usize,
+
+    /// Above this many mesh peers, some are pruned
+    pub mesh_n_high: usize,
+
+    /// Heartbeats a published message stays in the cache for late joiners
+    pub history_length: usize,
+
+    /// How long seen message IDs are kept for deduplication
+    pub duplicate_cache_time: Duration,
+}
+
+impl Default for GossipConfig {
+    /// Chat defaults: a 1s heartbeat so messages propagate quickly, a mesh
+    /// of 2 (down to 1, so two-peer networks form a mesh) capped at 12, 10
+    /// heartbeats of history and 5 minutes of deduplication
+    fn default() -> Self {
+        Self {
+            heartbeat_interval: Duration::from_secs(1),
+            mesh_n: 2,
+            mesh_n_low: 1,
+            mesh_n_high: 12,
+            history_length: 10,
+            duplicate_cache_time: Duration::from_secs(300),
+        }
+    }
+}
+
+impl GossipConfig {
+    /// Build the libp2p GossipSub config
+    ///
+    /// Settings that aren't tunable here (strict validation, 1MB messages,
+    /// no flood publishing) are fixed for privacy and spam resistance.
+    pub fn build(&self) -> Result<gossipsub::Config> {
+        if !(self.mesh_n_low <= self.mesh_n && self.mesh_n <= self.mesh_n_high) {
+            return Err(Error::Network(format!(
+                "GossipSub mesh must satisfy mesh_n_low <= mesh_n <= mesh_n_high (got {} / {} / {})",
+                self.mesh_n_low, self.mesh_n, self.mesh_n_high
+            )));
+        }
+
+        gossipsub::ConfigBuilder::default()
+            .heartbeat_interval(self.heartbeat_interval)
+            // Strict validation - reject unsigned/invalid messages
+            .validation_mode(gossipsub::ValidationMode::Strict)
+            .duplicate_cache_time(self.duplicate_cache_time)
+            // Limit message size to prevent spam (1MB max)
+            .max_transmit_size(1024 * 1024)
+            // Privacy: only send to mesh peers (reduces metadata leakage)
+            .flood_publish(false)
+            .mesh_n(self.mesh_n)
+            .mesh_n_low(self.mesh_n_low)
+            .mesh_n_high(self.mesh_n_high)
+            .history_length(self.history_length)
+            // Gossip about at most 5 cached heartbeats
+            .history_gossip(self.history_length.min(5))
+            .build()
+            .map_err(|e| Error::Network(format!("GossipSub config error: {}", e)))
+    }
+}
@@ -5,6 +5,28 @@
 pub mod node;
 pub mod relay;
 pub mod gossip_metrics;
+#[cfg(any(test, feature = "test-utils"))]
+pub mod conditions;
+pub mod ack;
+pub mod gossip_config;
+pub mod connection_config;
+pub mod peer_exchange;
+pub mod anti_entropy;
+pub mod dedup;
+pub mod replay;
+pub mod presence;
+pub mod throttle;
 
-pub use node::{NetworkNode, NetworkEvent, create_relay_server};
+pub use node::{NetworkNode, NetworkEvent, ConnectedPeer, ConnectionType, PeerDetail, create_relay_server, create_relay_server_with_config};
 pub use gossip_metrics::GossipMetrics;
+#[cfg(any(test, feature = "test-utils"))]
+pub use conditions::NetworkConditions;
+pub use ack::{Ack, AckBatcher, DeliveryTracker};
+pub use gossip_config::GossipConfig;
+pub use connection_config::ConnectionConfig;
+pub use peer_exchange::{PeerBook, PeerExchange, PeerRecord};
+pub use anti_entropy::{CatchUpRequest, ResponderClock, SpaceClock, SyncDigest};
+pub use dedup::{DedupCache, DedupCacheStats};
+pub use replay::{ReplayGuard, Stamper};
+pub use presence::{Presence, PresenceBook};
+pub use throttle::OpThrottle;
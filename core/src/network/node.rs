@@ -8,8 +8,8 @@
 
 use libp2p::{
     gossipsub, identity, kad,
-    noise, relay,
-    swarm::{NetworkBehaviour, SwarmEvent},
+    noise, ping, relay,
+    swarm::{behaviour::toggle::Toggle, ConnectionId, NetworkBehaviour, SwarmEvent},
     tcp, yamux, Multiaddr, PeerId, Swarm,
     futures::StreamExt,
     core::{
@@ -19,7 +19,7 @@ use libp2p::{
     },
     Transport,
 };
-use std::time::{Duration, Instant};
+use std::time::{Duration, Instant, SystemTime};
 use std::collections::HashMap;
 use tokio::sync::{mpsc, oneshot};
 
@@ -37,12 +37,20 @@ pub enum NetworkCommand {
         target_peer_id: PeerId,
         response: oneshot::Sender<Result<()>> 
     },
+    /// Reserve a slot on a relay and listen for circuits through it
+    ListenViaRelay { relay_addr: Multiaddr, response: oneshot::Sender<Result<()>> },
     /// Subscribe to a topic
     Subscribe { topic: String, response: oneshot::Sender<Result<()>> },
     /// Publish to a topic
     Publish { topic: String, data: Vec<u8>, response: oneshot::Sender<Result<()>> },
     /// Get listening addresses
     GetListeners { response: oneshot::Sender<Vec<Multiaddr>> },
+    /// Get the GossipSub topics we are subscribed to
+    GetTopics { response: oneshot::Sender<Vec<String>> },
+    /// Get currently connected peers
+    GetConnectedPeers { response: oneshot::Sender<Vec<ConnectedPeer>> },
+    /// Get connected peers with all their connection addresses
+    GetPeerDetails { response: oneshot::Sender<Vec<PeerDetail>> },
     /// Advertise as relay server on DHT
     AdvertiseRelay { 
         info: crate::network::relay::RelayAdvertisement,
@@ -63,10 +71,66 @@ pub enum NetworkCommand {
         key: Vec<u8>,
         response: oneshot::Sender<Result<Vec<Vec<u8>>>>
     },
+    /// Set simulated latency/loss for inbound messages
+    #[cfg(any(test, feature = "test-utils"))]
+    SetConditions {
+        conditions: crate::network::conditions::NetworkConditions,
+        response: oneshot::Sender<()>
+    },
     /// Shutdown the network
     Shutdown,
 }
 
+/// A peer with at least one open connection
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConnectedPeer {
+    /// Remote peer ID
+    pub peer_id: PeerId,
+    /// Remote address of the most recent connection
+    pub address: Multiaddr,
+    /// Whether the connection goes through a circuit relay
+    pub relayed: bool,
+}
+
+/// How a connected peer is reached
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConnectionType {
+    /// At least one connection goes straight to the peer
+    Direct,
+    /// Every connection goes through a circuit relay
+    Relayed,
+}
+
+/// A connected peer with all of its open connections
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PeerDetail {
+    /// Remote peer ID
+    pub peer_id: PeerId,
+    /// Remote addresses of the open connections, oldest first
+    pub addrs: Vec<Multiaddr>,
+    /// Direct if any open connection is direct
+    pub connection_type: ConnectionType,
+    /// When the peer became connected (kept across overlapping connections)
+    pub since: SystemTime,
+}
+
+impl PeerDetail {
+    /// How long the peer has been connected
+    pub fn uptime(&self) -> Duration {
+        self.since.elapsed().unwrap_or_default()
+    }
+}
+
+/// Open connections to one peer, tracked by the network worker
+struct PeerConnections {
+    since: SystemTime,
+    addrs: Vec<(ConnectionId, Multiaddr)>,
+}
+
+fn is_relayed(addr: &Multiaddr) -> bool {
+    addr.iter().any(|p| matches!(p, libp2p::multiaddr::Protocol::P2pCircuit))
+}
+
 /// Network event from the P2P layer
 #[derive(Debug, Clone)]
 pub enum NetworkEvent {
@@ -104,6 +168,9 @@ pub struct DescordBehaviour {
     
     /// Relay client for connecting via relays (IP privacy)
     pub relay_client: relay::client::Behaviour,
+    
+    /// Keepalive pings (disabled when `ConnectionConfig::keepalive_interval` is `None`)
+    pub ping: Toggle<ping::Behaviour>,
 }
 
 /// P2P network node with message-passing interface
@@ -116,6 +183,15 @@ pub struct NetworkNode {
     
     /// Event receiver from network thread
     event_rx: mpsc::UnboundedReceiver<NetworkEvent>,
+    
+    /// DHT put requests issued (for client metrics)
+    dht_puts: u64,
+    
+    /// DHT get requests issued (for client metrics)
+    dht_gets: u64,
+    
+    /// Idle timeout and keepalive the swarm was built with
+    connection: crate::network::ConnectionConfig,
 }
 
 /// Internal network worker that owns the Swarm
@@ -137,6 +213,13 @@ struct NetworkWorker {
     
     /// Last time we checked for DHT peers and possibly triggered bootstrap
     last_bootstrap_check: Instant,
+    
+    /// Simulated network conditions applied to inbound GossipSub messages
+    #[cfg(any(test, feature = "test-utils"))]
+    conditions: crate::network::conditions::NetworkConditions,
+    
+    /// Open connections to each connected peer
+    peer_connections: HashMap<PeerId, PeerConnections>,
 }
 
 impl NetworkNode {
@@ -147,11 +230,32 @@ impl NetworkNode {
     
     /// Create a new network node with bootstrap peers and listen addresses
     pub fn new_with_config(bootstrap_peers: Vec<String>, listen_addrs: Vec<String>) -> Result<(Self, mpsc::UnboundedReceiver<NetworkEvent>)> {
+        Self::new_with_gossip_config(bootstrap_peers, listen_addrs, crate::network::GossipConfig::default())
+    }
+    
+    /// Create a new network node with custom GossipSub heartbeat and mesh parameters
+    pub fn new_with_gossip_config(
+        bootstrap_peers: Vec<String>,
+        listen_addrs: Vec<String>,
+        gossip: crate::network::GossipConfig,
+    ) -> Result<(Self, mpsc::UnboundedReceiver<NetworkEvent>)> {
+        Self::new_with_network_config(bootstrap_peers, listen_addrs, gossip, crate::network::ConnectionConfig::default())
+    }
+    
+    /// Create a new network node with custom GossipSub and connection lifetime parameters
+    pub fn new_with_network_config(
+        bootstrap_peers: Vec<String>,
+        listen_addrs: Vec<String>,
+        gossip: crate::network::GossipConfig,
+        connection: crate::network::ConnectionConfig,
+    ) -> Result<(Self, mpsc::UnboundedReceiver<NetworkEvent>)> {
+        connection.validate()?;
+        
         // Generate identity
         let local_key = identity::Keypair::generate_ed25519();
         let local_peer_id = PeerId::from(local_key.public());
         
-        println!("Local peer ID: {}", local_peer_id);
+        tracing::info!("Local peer ID: {}", local_peer_id);
         
         // Create Kademlia DHT
         let store = kad::store::MemoryStore::new(local_peer_id);
@@ -161,34 +265,7 @@ impl NetworkNode {
         kademlia.set_mode(Some(kad::Mode::Server));
         
         // Create GossipSub with privacy-preserving configuration
-        let gossipsub_config = gossipsub::ConfigBuilder::default()
-            // Faster propagation for real-time messaging
-            .heartbeat_interval(Duration::from_secs(1))
-            
-            // Strict validation - reject unsigned/invalid messages
-            .validation_mode(gossipsub::ValidationMode::Strict)
-            
-            // Message deduplication (keep seen messages for 5 minutes)
-            .duplicate_cache_time(Duration::from_secs(300))
-            
-            // Limit message size to prevent spam (1MB max)
-            .max_transmit_size(1024 * 1024)
-            
-            // Privacy: Don't flood-publish to all peers
-            // Only send to mesh peers (reduces metadata leakage)
-            .flood_publish(false)
-            
-            // Mesh configuration for small networks (2+ peers)
-            .mesh_n(2)        // Target 2 peers in mesh (works with 2 peers)
-            .mesh_n_low(1)    // Min 1 peer (allows 2-peer networks)
-            .mesh_n_high(12)  // Max 12 peers
-            
-            // Message caching for late joiners
-            .history_length(10)   // Keep last 10 messages
-            .history_gossip(5)    // Gossip about 5 cached messages
-            
-            .build()
-            .map_err(|e| Error::Network(format!("GossipSub config error: {}", e)))?;
+        let gossipsub_config = gossip.build()?;
         
         let mut gossipsub = gossipsub::Behaviour::new(
             gossipsub::MessageAuthenticity::Signed(local_key.clone()),
@@ -208,11 +285,12 @@ impl NetworkNode {
         // Create relay client behavior
         let (relay_transport, relay_client) = relay::client::new(local_peer_id);
         
-        // Create behavior with relay client
+        // Create behavior with relay client and keepalive pings
         let behaviour = DescordBehaviour {
             kademlia,
             gossipsub,
             relay_client,
+            ping: connection.ping_config().map(ping::Behaviour::new).into(),
         };
         
         // Build transport: TCP with relay support
@@ -232,8 +310,7 @@ impl NetworkNode {
             transport,
             behaviour,
             local_peer_id,
-            libp2p::swarm::Config::with_tokio_executor()
-                .with_idle_connection_timeout(Duration::from_secs(60))
+            connection.swarm_config(),
         );
         
         // Create channels
@@ -249,6 +326,9 @@ impl NetworkNode {
             pending_get_queries: HashMap::new(),
             pending_put_queries: HashMap::new(),
             last_bootstrap_check: Instant::now(),
+            #[cfg(any(test, feature = "test-utils"))]
+            conditions: Default::default(),
+            peer_connections: HashMap::new(),
         };
         
         // Listen on configured addresses or default
@@ -258,7 +338,7 @@ impl NetworkNode {
         } else {
             for addr_str in &listen_addrs {
                 if let Ok(addr) = addr_str.parse::<Multiaddr>() {
-                    println!("📡 Configuring listener on: {}", addr);
+                    tracing::debug!("Configuring listener on: {}", addr);
                     worker.swarm.listen_on(addr).unwrap();
                 }
             }
@@ -271,16 +351,16 @@ impl NetworkNode {
                     // Extract peer ID from multiaddr if present
                     if let Some(libp2p::multiaddr::Protocol::P2p(peer_id)) = addr.iter().last() {
                         worker.swarm.behaviour_mut().kademlia.add_address(&peer_id, addr.clone());
-                        println!("Added bootstrap peer: {} at {}", peer_id, addr);
+                        tracing::debug!("Added bootstrap peer: {} at {}", peer_id, addr);
                     }
                 }
             }
             
             // Start DHT bootstrap
             if let Err(e) = worker.swarm.behaviour_mut().kademlia.bootstrap() {
-                println!("Warning: DHT bootstrap failed: {:?}", e);
+                tracing::warn!("DHT bootstrap failed: {:?}", e);
             } else {
-                println!("✓ DHT bootstrap initiated with {} peers", bootstrap_peers.len());
+                tracing::info!("DHT bootstrap initiated with {} peers", bootstrap_peers.len());
             }
         }
         
@@ -294,6 +374,9 @@ impl NetworkNode {
                 peer_id: local_peer_id,
                 command_tx,
                 event_rx,
+                dht_puts: 0,
+                dht_gets: 0,
+                connection,
             },
             user_event_rx,
         ))
@@ -311,14 +394,33 @@ impl NetworkNode {
         rx.await.unwrap_or_default()
     }
     
+    /// Get the GossipSub topics we are subscribed to
+    pub async fn subscribed_topics(&self) -> Vec<String> {
+        let (tx, rx) = oneshot::channel();
+        let _ = self.command_tx.send(NetworkCommand::GetTopics { response: tx });
+        rx.await.unwrap_or_default()
+    }
+    
     /// Get list of connected peer IDs
-    /// 
-    /// Returns empty list for now - will be implemented when we add the command
-    /// to query swarm.connected_peers()
     pub async fn connected_peers(&self) -> Vec<PeerId> {
-        // TODO: Add NetworkCommand::GetConnectedPeers
-        // For now, return empty list
-        Vec::new()
+        self.connected_peer_info().await
+            .into_iter()
+            .map(|peer| peer.peer_id)
+            .collect()
+    }
+    
+    /// Get connected peers with their address and connection type
+    pub async fn connected_peer_info(&self) -> Vec<ConnectedPeer> {
+        let (tx, rx) = oneshot::channel();
+        let _ = self.command_tx.send(NetworkCommand::GetConnectedPeers { response: tx });
+        rx.await.unwrap_or_default()
+    }
+    
+    /// Get connected peers with every connection address, type and uptime
+    pub async fn peer_details(&self) -> Vec<PeerDetail> {
+        let (tx, rx) = oneshot::channel();
+        let _ = self.command_tx.send(NetworkCommand::GetPeerDetails { response: tx });
+        rx.await.unwrap_or_default()
     }
     
     /// Start listening on an address
@@ -356,6 +458,19 @@ impl NetworkNode {
             .map_err(|_| Error::Network("Response channel closed".to_string()))?
     }
     
+    /// Reserve a slot on a relay so peers can reach us through it
+    /// 
+    /// `relay_addr` must end in `/p2p/{relay_peer_id}`. Once the relay
+    /// accepts the reservation we are reachable at
+    /// `{relay_addr}/p2p-circuit/p2p/{our_peer_id}`.
+    pub async fn listen_via_relay(&self, relay_addr: Multiaddr) -> Result<()> {
+        let (tx, rx) = oneshot::channel();
+        self.command_tx.send(NetworkCommand::ListenViaRelay { relay_addr, response: tx })
+            .map_err(|_| Error::Network("Network thread died".to_string()))?;
+        rx.await
+            .map_err(|_| Error::Network("Response channel closed".to_string()))?
+    }
+    
     /// Advertise this node as a relay server on DHT
     /// Allows other users to discover and use this node as relay
     pub async fn advertise_as_relay(
@@ -363,6 +478,20 @@ impl NetworkNode {
         mode: crate::network::relay::RelayMode,
         addresses: Vec<Multiaddr>,
         capacity: u32,
+    ) -> Result<()> {
+        self.advertise_as_relay_with_load(mode, addresses, capacity, 0).await
+    }
+    
+    /// Advertise this node as a relay, including how many circuits are in use
+    /// 
+    /// Re-advertise as load changes so clients can steer away from a relay
+    /// that is close to capacity.
+    pub async fn advertise_as_relay_with_load(
+        &self,
+        mode: crate::network::relay::RelayMode,
+        addresses: Vec<Multiaddr>,
+        capacity: u32,
+        active_circuits: u32,
     ) -> Result<()> {
         use crate::network::relay::RelayAdvertisement;
         
@@ -370,6 +499,7 @@ impl NetworkNode {
             peer_id: self.peer_id,
             addresses,
             capacity,
+            active_circuits,
             mode,
             timestamp: std::time::SystemTime::now()
                 .duration_since(std::time::UNIX_EPOCH)
@@ -413,10 +543,9 @@ impl NetworkNode {
     
     /// Publish to a GossipSub topic
     pub async fn publish(&mut self, topic: &str, data: Vec<u8>) -> Result<()> {
-        eprintln!("🟢 [publish] START: topic={}, data_size={} bytes", topic, data.len());
+        tracing::trace!(topic, bytes = data.len(), "Publishing");
         
         let (tx, rx) = oneshot::channel();
-        eprintln!("🟢 [publish] Sending Publish command to network thread...");
         self.command_tx.send(NetworkCommand::Publish { 
             topic: topic.to_string(), 
             data,
@@ -424,25 +553,43 @@ impl NetworkNode {
         })
             .map_err(|_| Error::Network("Network thread died".to_string()))?;
         
-        eprintln!("🟢 [publish] Command sent, awaiting response...");
         let result = rx.await;
         
         match &result {
-            Ok(Ok(_)) => eprintln!("🟢 [publish] END: ✓ Success"),
-            Ok(Err(e)) => eprintln!("🟢 [publish] END: ✗ Error: {}", e),
-            Err(_) => eprintln!("🟢 [publish] END: ✗ Response channel closed"),
+            Ok(Ok(_)) => tracing::trace!(topic, "Published"),
+            Ok(Err(e)) => tracing::debug!(topic, error = %e, "Publish failed"),
+            Err(_) => tracing::debug!(topic, "Publish failed: response channel closed"),
         }
         
         result.map_err(|_| Error::Network("Response channel closed".to_string()))?
     }
     
+    /// Apply simulated network conditions to inbound GossipSub messages
+    #[cfg(any(test, feature = "test-utils"))]
+    pub async fn set_conditions(&mut self, conditions: crate::network::conditions::NetworkConditions) -> Result<()> {
+        let (tx, rx) = oneshot::channel();
+        self.command_tx.send(NetworkCommand::SetConditions { conditions, response: tx })
+            .map_err(|_| Error::Network("Network thread died".to_string()))?;
+        rx.await.map_err(|_| Error::Network("Response channel closed".to_string()))
+    }
+    
+    /// Idle timeout and keepalive in effect for this node's connections
+    pub fn connection_config(&self) -> crate::network::ConnectionConfig {
+        self.connection
+    }
+    
+    /// Number of DHT (put, get) requests issued so far
+    pub fn dht_request_counts(&self) -> (u64, u64) {
+        (self.dht_puts, self.dht_gets)
+    }
+    
     /// Put a value in the DHT
     pub async fn dht_put(&mut self, key: Vec<u8>, value: Vec<u8>) -> Result<()> {
-        eprintln!("🔶 [dht_put] START: key={}, value_size={} bytes", 
-                 hex::encode(&key[..std::cmp::min(8, key.len())]), value.len());
+        let key_prefix = hex::encode(&key[..std::cmp::min(8, key.len())]);
+        tracing::debug!(key = %key_prefix, bytes = value.len(), "DHT put");
         
+        self.dht_puts += 1;
         let (tx, rx) = oneshot::channel();
-        eprintln!("🔶 [dht_put] Sending DhtPut command to network thread...");
         self.command_tx.send(NetworkCommand::DhtPut {
             key: key.clone(),
             value,
@@ -450,8 +597,6 @@ impl NetworkNode {
         })
             .map_err(|_| Error::Network("Network thread died".to_string()))?;
         
-        eprintln!("🔶 [dht_put] Command sent, awaiting response with 12s timeout...");
-        
         // Add timeout wrapper to ensure we don't wait forever
         let result = tokio::time::timeout(
             std::time::Duration::from_secs(12), // Slightly longer than query timeout
@@ -460,10 +605,10 @@ impl NetworkNode {
         .await;
         
         match &result {
-            Ok(Ok(Ok(_))) => eprintln!("🔶 [dht_put] END: ✓ Success"),
-            Ok(Ok(Err(e))) => eprintln!("🔶 [dht_put] END: ✗ Network error: {}", e),
-            Ok(Err(_)) => eprintln!("🔶 [dht_put] END: ✗ Response channel closed"),
-            Err(_) => eprintln!("🔶 [dht_put] END: ✗ TIMEOUT after 12 seconds"),
+            Ok(Ok(Ok(_))) => tracing::debug!(key = %key_prefix, "DHT put succeeded"),
+            Ok(Ok(Err(e))) => tracing::debug!(key = %key_prefix, error = %e, "DHT put failed"),
+            Ok(Err(_)) => tracing::debug!(key = %key_prefix, "DHT put failed: response channel closed"),
+            Err(_) => tracing::debug!(key = %key_prefix, "DHT put timed out"),
         }
         
         result
@@ -473,19 +618,17 @@ impl NetworkNode {
     
     /// Get values from the DHT
     pub async fn dht_get(&mut self, key: Vec<u8>) -> Result<Vec<Vec<u8>>> {
-        eprintln!("🔷 [dht_get] START: key={}", 
-                 hex::encode(&key[..std::cmp::min(8, key.len())]));
+        let key_prefix = hex::encode(&key[..std::cmp::min(8, key.len())]);
+        tracing::debug!(key = %key_prefix, "DHT get");
         
+        self.dht_gets += 1;
         let (tx, rx) = oneshot::channel();
-        eprintln!("🔷 [dht_get] Sending DhtGet command to network thread...");
         self.command_tx.send(NetworkCommand::DhtGet {
             key: key.clone(),
             response: tx
         })
             .map_err(|_| Error::Network("Network thread died".to_string()))?;
         
-        eprintln!("🔷 [dht_get] Command sent, awaiting response with 12s timeout...");
-        
         // Add timeout wrapper to ensure we don't wait forever
         let result = tokio::time::timeout(
             std::time::Duration::from_secs(12), // Slightly longer than query timeout
@@ -494,10 +637,10 @@ impl NetworkNode {
         .await;
         
         match &result {
-            Ok(Ok(Ok(values))) => eprintln!("🔷 [dht_get] END: ✓ Success ({} values)", values.len()),
-            Ok(Ok(Err(e))) => eprintln!("🔷 [dht_get] END: ✗ Network error: {}", e),
-            Ok(Err(_)) => eprintln!("🔷 [dht_get] END: ✗ Response channel closed"),
-            Err(_) => eprintln!("🔷 [dht_get] END: ✗ TIMEOUT after 12 seconds"),
+            Ok(Ok(Ok(values))) => tracing::debug!(key = %key_prefix, values = values.len(), "DHT get succeeded"),
+            Ok(Ok(Err(e))) => tracing::debug!(key = %key_prefix, error = %e, "DHT get failed"),
+            Ok(Err(_)) => tracing::debug!(key = %key_prefix, "DHT get failed: response channel closed"),
+            Err(_) => tracing::debug!(key = %key_prefix, "DHT get timed out"),
         }
         
         result
@@ -549,20 +692,63 @@ impl NetworkWorker {
                             let _ = response.send(result);
                         }
                         NetworkCommand::Publish { topic, data, response } => {
-                            eprintln!("🟣 [NetworkWorker] Received Publish command for topic: {}, size: {} bytes", topic, data.len());
                             let topic = gossipsub::IdentTopic::new(topic);
-                            eprintln!("🟣 [NetworkWorker] Calling gossipsub.publish...");
                             let result = self.swarm.behaviour_mut().gossipsub.publish(topic, data)
                                 .map(|_| ())
                                 .map_err(|e| Error::Network(format!("Publish failed: {}", e)));
-                            eprintln!("🟣 [NetworkWorker] Publish result: {:?}, sending response...", result.is_ok());
+                            tracing::trace!(ok = result.is_ok(), "Worker published");
+                            let _ = response.send(result);
+                        }
+                        NetworkCommand::ListenViaRelay { relay_addr, response } => {
+                            let circuit_addr = relay_addr.with(libp2p::multiaddr::Protocol::P2pCircuit);
+                            let result = self.swarm.listen_on(circuit_addr)
+                                .map(|_| ())
+                                .map_err(|e| Error::Network(format!("Relay reservation failed: {}", e)));
                             let _ = response.send(result);
-                            eprintln!("🟣 [NetworkWorker] Response sent");
                         }
                         NetworkCommand::GetListeners { response } => {
                             let listeners: Vec<Multiaddr> = self.swarm.listeners().cloned().collect();
                             let _ = response.send(listeners);
                         }
+                        NetworkCommand::GetTopics { response } => {
+                            let topics: Vec<String> = self.swarm.behaviour().gossipsub.topics()
+                                .map(|topic| topic.as_str().to_string())
+                                .collect();
+                            let _ = response.send(topics);
+                        }
+                        NetworkCommand::GetConnectedPeers { response } => {
+                            let peers = self.swarm.connected_peers()
+                                .map(|peer_id| {
+                                    let address = self.peer_connections.get(peer_id)
+                                        .and_then(|conns| conns.addrs.last())
+                                        .map(|(_, addr)| addr.clone())
+                                        .unwrap_or_else(Multiaddr::empty);
+                                    let relayed = is_relayed(&address);
+                                    ConnectedPeer { peer_id: *peer_id, address, relayed }
+                                })
+                                .collect();
+                            let _ = response.send(peers);
+                        }
+                        NetworkCommand::GetPeerDetails { response } => {
+                            let peers = self.swarm.connected_peers()
+                                .map(|peer_id| {
+                                    let (addrs, since) = match self.peer_connections.get(peer_id) {
+                                        Some(conns) => (
+                                            conns.addrs.iter().map(|(_, addr)| addr.clone()).collect::<Vec<_>>(),
+                                            conns.since,
+                                        ),
+                                        None => (Vec::new(), SystemTime::now()),
+                                    };
+                                    let connection_type = if !addrs.is_empty() && addrs.iter().all(is_relayed) {
+                                        ConnectionType::Relayed
+                                    } else {
+                                        ConnectionType::Direct
+                                    };
+                                    PeerDetail { peer_id: *peer_id, addrs, connection_type, since }
+                                })
+                                .collect();
+                            let _ = response.send(peers);
+                        }
                         NetworkCommand::AdvertiseRelay { info, response } => {
                             use crate::network::relay::RELAY_DHT_KEY;
                             
@@ -583,7 +769,7 @@ impl NetworkWorker {
                                 .map(|_| ())
                                 .map_err(|e| Error::Network(format!("DHT put failed: {:?}", e)));
                             
-                            println!("✓ Advertised relay on DHT");
+                            tracing::info!("Advertised relay on DHT");
                             let _ = response.send(result);
                         }
                         NetworkCommand::DiscoverRelays { response } => {
@@ -596,12 +782,18 @@ impl NetworkWorker {
                             // Also try to get stored relay records
                             let _ = self.swarm.behaviour_mut().kademlia.get_record(key);
                             
-                            // For now, return empty list (DHT discovery is async)
-                            // In production, we'd wait for DHT responses or maintain a cache
-                            // For MVP, we'll rely on bootstrap relays as fallback
-                            let relays = Vec::new();
+                            // Answer from the advertisements already replicated to our
+                            // record store; the queries above refresh it for next time
+                            use libp2p::kad::store::RecordStore;
+                            let prefix = format!("{}/", RELAY_DHT_KEY);
+                            let relays: Vec<RelayInfo> = self.swarm.behaviour_mut().kademlia.store_mut()
+                                .records()
+                                .filter(|record| record.key.as_ref().starts_with(prefix.as_bytes()))
+                                .filter_map(|record| RelayAdvertisement::from_bytes(&record.value).ok())
+                                .map(RelayInfo::from_advertisement)
+                                .collect();
                             
-                            println!("✓ Discovering relays from DHT...");
+                            tracing::info!("Discovering relays from DHT ({} known)", relays.len());
                             let _ = response.send(Ok(relays));
                         }
                         NetworkCommand::DhtPut { key, value, response } => {
@@ -611,12 +803,12 @@ impl NetworkWorker {
                                 .map(|bucket| bucket.iter().count())
                                 .sum();
                             
-                            eprintln!("🔍 DHT PUT: {} peers in routing table", peer_count);
+                            tracing::trace!(peer_count, "DHT put: peers in routing table");
                             
                             if peer_count == 0 {
-                                eprintln!("⚠️  No DHT peers available, triggering bootstrap...");
+                                tracing::debug!("No DHT peers available, triggering bootstrap");
                                 if let Err(e) = self.swarm.behaviour_mut().kademlia.bootstrap() {
-                                    eprintln!("⚠️  Bootstrap failed: {:?}", e);
+                                    tracing::debug!(error = ?e, "DHT bootstrap failed");
                                 }
                             }
                             
@@ -632,12 +824,12 @@ impl NetworkWorker {
                             match self.swarm.behaviour_mut().kademlia
                                 .put_record(record, libp2p::kad::Quorum::One) {
                                 Ok(query_id) => {
-                                    eprintln!("🔍 DHT PUT query started: {:?}", query_id);
+                                    tracing::trace!(?query_id, "DHT put query started");
                                     // Track pending query
                                     self.pending_put_queries.insert(query_id, (response, Instant::now()));
                                 }
                                 Err(e) => {
-                                    eprintln!("❌ DHT PUT failed immediately: {:?}", e);
+                                    tracing::debug!(error = ?e, "DHT put failed to start");
                                     let _ = response.send(Err(Error::Network(format!("DHT put failed: {:?}", e))));
                                 }
                             }
@@ -650,6 +842,12 @@ impl NetworkWorker {
                             // Track pending query - will be resolved when GetRecord event arrives
                             self.pending_get_queries.insert(query_id, (response, Instant::now()));
                         }
+                        #[cfg(any(test, feature = "test-utils"))]
+                        NetworkCommand::SetConditions { conditions, response } => {
+                            tracing::debug!("Network conditions set: {:?}", conditions);
+                            self.conditions = conditions;
+                            let _ = response.send(());
+                        }
                         NetworkCommand::Shutdown => {
                             break;
                         }
@@ -683,9 +881,10 @@ impl NetworkWorker {
             .sum();
         
         if peer_count == 0 {
-            eprintln!("⚠️  No DHT peers in routing table, triggering bootstrap...");
+            tracing::debug!("No DHT peers in routing table, triggering bootstrap");
             if let Err(e) = self.swarm.behaviour_mut().kademlia.bootstrap() {
-                eprintln!("   Bootstrap failed: {:?} (this is normal if no bootstrap peers configured)", e);
+                // Expected when no bootstrap peers are configured
+                tracing::trace!(error = ?e, "DHT bootstrap failed");
             }
         }
     }
@@ -711,7 +910,7 @@ impl NetworkWorker {
         for query_id in timed_out_gets.iter() {
             if let Some((response, start_time)) = self.pending_get_queries.remove(&query_id) {
                 let elapsed = now.duration_since(start_time);
-                eprintln!("⏱️  DHT GET query timed out after {:?}: {:?}", elapsed, query_id);
+                tracing::debug!(?query_id, ?elapsed, "DHT get query timed out");
                 let _ = response.send(Err(Error::Network("DHT GET query timed out".to_string())));
             }
         }
@@ -732,16 +931,20 @@ impl NetworkWorker {
         for query_id in timed_out_puts.iter() {
             if let Some((response, start_time)) = self.pending_put_queries.remove(&query_id) {
                 let elapsed = now.duration_since(start_time);
-                eprintln!("⏱️  DHT PUT query timed out after {:?}: {:?}", elapsed, query_id);
+                tracing::debug!(?query_id, ?elapsed, "DHT put query timed out");
                 let _ = response.send(Err(Error::Network("DHT PUT query timed out".to_string())));
             }
         }
         
         // Report how many queries are being checked
         if !timed_out_gets.is_empty() || !timed_out_puts.is_empty() {
-            eprintln!("🕐 Timeout check: {} GET, {} PUT queries timed out (tracking {} GET, {} PUT total)", 
-                     timed_out_gets.len(), timed_out_puts.len(),
-                     self.pending_get_queries.len(), self.pending_put_queries.len());
+            tracing::debug!(
+                timed_out_gets = timed_out_gets.len(),
+                timed_out_puts = timed_out_puts.len(),
+                pending_gets = self.pending_get_queries.len(),
+                pending_puts = self.pending_put_queries.len(),
+                "DHT queries timed out"
+            );
         }
     }
     
@@ -749,21 +952,29 @@ impl NetworkWorker {
     async fn handle_swarm_event(&mut self, event: SwarmEvent<DescordBehaviourEvent>) {
         match event {
             SwarmEvent::NewListenAddr { address, .. } => {
-                println!("Listening on {}", address);
+                tracing::info!("Listening on {}", address);
             }
             SwarmEvent::Behaviour(behaviour_event) => {
                 self.handle_behaviour_event(behaviour_event).await;
             }
-            SwarmEvent::ConnectionEstablished { peer_id, endpoint, .. } => {
-                println!("✅ Connection established with peer: {}", peer_id);
+            SwarmEvent::ConnectionEstablished { peer_id, connection_id, endpoint, .. } => {
+                tracing::info!("Connection established with peer: {}", peer_id);
                 // Add peer as explicit GossipSub peer for small networks
                 self.swarm.behaviour_mut().gossipsub.add_explicit_peer(&peer_id);
                 // Add peer to Kademlia routing table so DHT operations can find it
                 self.swarm.behaviour_mut().kademlia.add_address(&peer_id, endpoint.get_remote_address().clone());
+                self.peer_connections.entry(peer_id)
+                    .or_insert_with(|| PeerConnections { since: SystemTime::now(), addrs: Vec::new() })
+                    .addrs.push((connection_id, endpoint.get_remote_address().clone()));
                 let _ = self.event_tx.send(NetworkEvent::PeerConnected(peer_id));
             }
-            SwarmEvent::ConnectionClosed { peer_id, .. } => {
-                println!("❌ Connection closed with peer: {}", peer_id);
+            SwarmEvent::ConnectionClosed { peer_id, connection_id, num_established, .. } => {
+                if num_established == 0 {
+                    self.peer_connections.remove(&peer_id);
+                } else if let Some(conns) = self.peer_connections.get_mut(&peer_id) {
+                    conns.addrs.retain(|(id, _)| *id != connection_id);
+                }
+                tracing::debug!("Connection closed with peer: {}", peer_id);
                 self.swarm.behaviour_mut().gossipsub.remove_explicit_peer(&peer_id);
                 let _ = self.event_tx.send(NetworkEvent::PeerDisconnected(peer_id));
             }
@@ -783,6 +994,13 @@ impl NetworkWorker {
             DescordBehaviourEvent::RelayClient(relay_event) => {
                 self.handle_relay_client_event(relay_event).await;
             }
+            DescordBehaviourEvent::Ping(ping::Event { peer, connection, result }) => {
+                // A missed pong means the link (or its NAT binding) is gone
+                if let Err(e) = result {
+                    tracing::debug!(%peer, error = %e, "Keepalive ping failed, closing connection");
+                    self.swarm.close_connection(connection);
+                }
+            }
         }
     }
     
@@ -793,12 +1011,12 @@ impl NetworkWorker {
                 match result {
                     kad::QueryResult::GetClosestPeers(Ok(ok)) => {
                         for peer in ok.peers {
-                            println!("Discovered peer: {:?}", peer);
+                            tracing::debug!("Discovered peer: {:?}", peer);
                         }
                         let _ = self.event_tx.send(NetworkEvent::DhtQueryComplete);
                     }
                     kad::QueryResult::Bootstrap(Ok(_)) => {
-                        println!("DHT bootstrap complete");
+                        tracing::debug!("DHT bootstrap complete");
                         let _ = self.event_tx.send(NetworkEvent::DhtQueryComplete);
                     }
                     kad::QueryResult::GetRecord(Ok(ok)) => {
@@ -808,11 +1026,11 @@ impl NetworkWorker {
                             
                             let values: Vec<Vec<u8>> = match ok {
                                 GetRecordOk::FoundRecord(peer_record) => {
-                                    println!("✓ DHT GET: Found 1 record");
+                                    tracing::info!("DHT GET: Found 1 record");
                                     vec![peer_record.record.value]
                                 }
                                 GetRecordOk::FinishedWithNoAdditionalRecord { .. } => {
-                                    println!("⚠️  DHT GET: Query finished, no additional records");
+                                    tracing::debug!("DHT GET: Query finished, no additional records");
                                     Vec::new()
                                 }
                             };
@@ -823,7 +1041,7 @@ impl NetworkWorker {
                     kad::QueryResult::GetRecord(Err(e)) => {
                         // DHT GET query failed
                         if let Some((response, _start_time)) = self.pending_get_queries.remove(&id) {
-                            println!("✗ DHT GET failed: {:?}", e);
+                            tracing::warn!("DHT GET failed: {:?}", e);
                             let _ = response.send(Err(Error::Network(format!("DHT GET failed: {:?}", e))));
                         }
                     }
@@ -831,20 +1049,20 @@ impl NetworkWorker {
                         // DHT PUT query completed successfully
                         if let Some((response, start_time)) = self.pending_put_queries.remove(&id) {
                             let elapsed = start_time.elapsed();
-                            eprintln!("✓ DHT PUT: Record stored successfully in {:?}, query_id: {:?}", elapsed, id);
+                            tracing::debug!(query_id = ?id, ?elapsed, "DHT put stored record");
                             let _ = response.send(Ok(()));
                         } else {
-                            eprintln!("⚠️  DHT PUT completed but query not tracked: {:?}", id);
+                            tracing::trace!(query_id = ?id, "DHT put completed for an untracked query");
                         }
                     }
                     kad::QueryResult::PutRecord(Err(e)) => {
                         // DHT PUT query failed
                         if let Some((response, start_time)) = self.pending_put_queries.remove(&id) {
                             let elapsed = start_time.elapsed();
-                            eprintln!("✗ DHT PUT failed after {:?}: {:?}, query_id: {:?}", elapsed, e, id);
+                            tracing::debug!(query_id = ?id, ?elapsed, error = ?e, "DHT put failed");
                             let _ = response.send(Err(Error::Network(format!("DHT PUT failed: {:?}", e))));
                         } else {
-                            eprintln!("⚠️  DHT PUT failed but query not tracked: {:?}, error: {:?}", id, e);
+                            tracing::trace!(query_id = ?id, error = ?e, "DHT put failed for an untracked query");
                         }
                     }
                     _ => {}
@@ -869,18 +1087,40 @@ impl NetworkWorker {
                 ..
             } => {
                 let topic = message.topic.to_string();
-                println!("🎯 NetworkWorker received GossipSub message on topic: {}", topic);
-                let _ = self.event_tx.send(NetworkEvent::MessageReceived {
+                tracing::debug!("NetworkWorker received GossipSub message on topic: {}", topic);
+                
+                #[cfg(any(test, feature = "test-utils"))]
+                if self.conditions.should_drop() {
+                    tracing::debug!("Simulated loss: dropping message on topic: {}", topic);
+                    return;
+                }
+                
+                let event = NetworkEvent::MessageReceived {
                     topic,
                     data: message.data,
                     source: propagation_source,
-                });
+                };
+                
+                #[cfg(any(test, feature = "test-utils"))]
+                {
+                    let delay = self.conditions.sample_delay();
+                    if !delay.is_zero() {
+                        // Deliver later without blocking the swarm loop
+                        let event_tx = self.event_tx.clone();
+                        tokio::spawn(async move {
+                            tokio::time::sleep(delay).await;
+                            let _ = event_tx.send(event);
+                        });
+                        return;
+                    }
+                }
+                let _ = self.event_tx.send(event);
             }
             gossipsub::Event::Subscribed { peer_id, topic } => {
-                println!("🔔 Peer {} subscribed to topic: {}", peer_id, topic);
+                tracing::debug!("Peer {} subscribed to topic: {}", peer_id, topic);
             }
             gossipsub::Event::Unsubscribed { peer_id, topic } => {
-                println!("🔕 Peer {} unsubscribed from topic: {}", peer_id, topic);
+                tracing::debug!("Peer {} unsubscribed from topic: {}", peer_id, topic);
             }
             _ => {}
         }
@@ -890,19 +1130,19 @@ impl NetworkWorker {
     async fn handle_relay_client_event(&mut self, event: relay::client::Event) {
         match event {
             relay::client::Event::ReservationReqAccepted { relay_peer_id, .. } => {
-                println!("✓ Relay reservation accepted by {:?}", relay_peer_id);
+                tracing::info!("Relay reservation accepted by {:?}", relay_peer_id);
             }
             relay::client::Event::OutboundCircuitEstablished { relay_peer_id, limit } => {
-                println!("✓ Circuit established via relay {:?} (IP hidden)", relay_peer_id);
+                tracing::info!("Circuit established via relay {:?} (IP hidden)", relay_peer_id);
                 // Note: The actual destination peer will be added to Kademlia via ConnectionEstablished event
             }
             relay::client::Event::InboundCircuitEstablished { src_peer_id, limit } => {
-                println!("✓ Inbound circuit from {:?} (their IP hidden)", src_peer_id);
+                tracing::info!("Inbound circuit from {:?} (their IP hidden)", src_peer_id);
                 // Note: The src_peer will be added to Kademlia via ConnectionEstablished event
             }
             _ => {
                 // Log all other events for debugging
-                println!("Relay event: {:?}", event);
+                tracing::debug!("Relay event: {:?}", event);
             }
         }
     }
@@ -911,14 +1151,19 @@ impl NetworkWorker {
 /// Create a relay server node (for future relay deployment)
 #[allow(dead_code)]
 pub fn create_relay_server() -> Result<Swarm<libp2p::relay::Behaviour>> {
+    create_relay_server_with_config(&super::relay::RelayConfig::default())
+}
+
+/// Create a relay server node with the given limits
+pub fn create_relay_server_with_config(config: &super::relay::RelayConfig) -> Result<Swarm<libp2p::relay::Behaviour>> {
     use libp2p::relay;
     
     let local_key = identity::Keypair::generate_ed25519();
     let local_peer_id = PeerId::from(local_key.public());
     
-    println!("Relay server peer ID: {}", local_peer_id);
+    tracing::info!(peer_id = %local_peer_id, "Relay server peer ID");
     
-    let behaviour = relay::Behaviour::new(local_peer_id, Default::default());
+    let behaviour = relay::Behaviour::new(local_peer_id, config.behaviour_config());
     
     let swarm = libp2p::SwarmBuilder::with_existing_identity(local_key)
         .with_tokio()
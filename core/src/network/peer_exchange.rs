@@ -0,0 +1,206 @@
+//! Peer exchange
+//!
+//! Clients that opt in periodically announce, on each Space's peer topic,
+//! their own listen addresses and the peers they have verified in that
+//! Space. Receivers dial a few of the peers they learn about. To limit
+//! amplification and poisoning, announcements carry a bounded list, each
+//! Space's book is capped, a peer is only dialed once, and a peer only counts
+//! as known (and is only passed on) after a connection to it succeeded.
+//! Dials include the peer ID, so the transport handshake rejects an address
+//! that belongs to someone else.
+
+use crate::types::*;
+use crate::{Error, Result};
+use libp2p::{Multiaddr, PeerId};
+use minicbor::{Decode, Encode};
+use std::collections::{HashMap, HashSet};
+use std::time::Duration;
+
+/// How often pending announcements are published
+pub const PEX_INTERVAL: Duration = Duration::from_secs(2);
+
+/// Most peers carried by one announcement (besides the sender)
+pub const MAX_EXCHANGED_PEERS: usize = 8;
+
+/// Most addresses accepted per peer
+pub const MAX_PEER_ADDRS: usize = 4;
+
+/// Most peers remembered per Space
+pub const MAX_KNOWN_PEERS: usize = 64;
+
+/// Most peers dialed in response to one announcement
+pub const MAX_AUTO_DIALS: usize = 3;
+
+/// Topic a Space's peer announcements are published on
+pub fn peer_exchange_topic(space_id: &SpaceId) -> String {
+    format!("space/{}/peers", space_id.short())
+}
+
+/// A peer and the addresses it can be dialed on
+#[derive(Debug, Clone, PartialEq, Eq, Encode, Decode)]
+pub struct PeerRecord {
+    /// libp2p peer ID
+    #[n(0)]
+    pub peer_id: String,
+    /// Dialable multiaddrs, without the `/p2p/` suffix
+    #[n(1)]
+    pub addrs: Vec<String>,
+}
+
+/// Announcement of a peer's addresses and the peers it knows in a Space
+#[derive(Debug, Clone, PartialEq, Eq, Encode, Decode)]
+pub struct PeerExchange {
+    /// Space the peers are members of
+    #[n(0)]
+    pub space_id: SpaceId,
+    /// The announcing peer itself
+    #[n(1)]
+    pub sender: PeerRecord,
+    /// Peers the sender has connected to in this Space
+    #[n(2)]
+    pub peers: Vec<PeerRecord>,
+}
+
+impl PeerExchange {
+    /// Serialize to CBOR bytes for publishing
+    pub fn to_bytes(&self) -> Result<Vec<u8>> {
+        minicbor::to_vec(self)
+            .map_err(|e| Error::Serialization(format!("Failed to encode PeerExchange: {}", e)))
+    }
+
+    /// Deserialize from CBOR bytes
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self> {
+        minicbor::decode(bytes)
+            .map_err(|e| Error::Serialization(format!("Failed to decode PeerExchange: {}", e)))
+    }
+}
+
+#[derive(Debug, Clone)]
+struct PeerEntry {
+    addrs: Vec<Multiaddr>,
+    /// A connection to this peer succeeded
+    verified: bool,
+    /// We already dialed this peer because of an announcement
+    dialed: bool,
+}
+
+/// Peers learned through exchange, per Space
+#[derive(Debug, Default)]
+pub struct PeerBook {
+    connected: HashSet<PeerId>,
+    spaces: HashMap<SpaceId, HashMap<PeerId, PeerEntry>>,
+}
+
+impl PeerBook {
+    /// Record a connection, verifying the peer wherever it was learned
+    pub fn connected(&mut self, peer_id: PeerId) {
+        self.connected.insert(peer_id);
+        for entries in self.spaces.values_mut() {
+            if let Some(entry) = entries.get_mut(&peer_id) {
+                entry.verified = true;
+            }
+        }
+    }
+
+    /// Record that the last connection to a peer closed
+    pub fn disconnected(&mut self, peer_id: &PeerId) {
+        self.connected.remove(peer_id);
+    }
+
+    /// Take in an announcement, returning the addresses to dial
+    ///
+    /// Only the first [`MAX_EXCHANGED_PEERS`] records are read, records that
+    /// don't parse are dropped, and the addresses of a verified peer are
+    /// never replaced by what someone else claims.
+    pub fn learn(&mut self, local: &PeerId, exchange: &PeerExchange) -> Vec<Multiaddr> {
+        let entries = self.spaces.entry(exchange.space_id).or_default();
+        let records = std::iter::once(&exchange.sender)
+            .chain(exchange.peers.iter().take(MAX_EXCHANGED_PEERS));
+
+        for record in records {
+            let Ok(peer_id) = record.peer_id.parse::<PeerId>() else { continue };
+            if peer_id == *local {
+                continue;
+            }
+            let addrs: Vec<Multiaddr> = record.addrs.iter()
+                .take(MAX_PEER_ADDRS)
+                .filter_map(|addr| addr.parse().ok())
+                .collect();
+
+            if !entries.contains_key(&peer_id) && entries.len() >= MAX_KNOWN_PEERS {
+                continue;
+            }
+            let entry = entries.entry(peer_id).or_insert_with(|| PeerEntry {
+                addrs: Vec::new(),
+                verified: false,
+                dialed: false,
+            });
+            if !entry.verified && !addrs.is_empty() {
+                entry.addrs = addrs;
+            }
+            if self.connected.contains(&peer_id) {
+                entry.verified = true;
+            }
+        }
+
+        let mut dials = Vec::new();
+        for (peer_id, entry) in entries.iter_mut() {
+            if dials.len() >= MAX_AUTO_DIALS {
+                break;
+            }
+            if entry.verified || entry.dialed || self.connected.contains(peer_id) {
+                continue;
+            }
+            if let Some(addr) = entry.addrs.first() {
+                entry.dialed = true;
+                dials.push(addr.clone().with(libp2p::multiaddr::Protocol::P2p(*peer_id)));
+            }
+        }
+        dials
+    }
+
+    /// Verified peers in a Space
+    pub fn known_peers(&self, space_id: &SpaceId) -> Vec<PeerRecord> {
+        let mut peers: Vec<PeerRecord> = self.spaces.get(space_id)
+            .into_iter()
+            .flatten()
+            .filter(|(_, entry)| entry.verified)
+            .map(|(peer_id, entry)| PeerRecord {
+                peer_id: peer_id.to_string(),
+                addrs: entry.addrs.iter().map(|addr| addr.to_string()).collect(),
+            })
+            .collect();
+        peers.sort_by(|a, b| a.peer_id.cmp(&b.peer_id));
+        peers
+    }
+
+    /// Announcement of ourselves and up to [`MAX_EXCHANGED_PEERS`] verified peers
+    pub fn announcement(&self, space_id: SpaceId, local: &PeerId, listen_addrs: &[Multiaddr]) -> PeerExchange {
+        let mut peers = self.known_peers(&space_id);
+        peers.truncate(MAX_EXCHANGED_PEERS);
+        PeerExchange {
+            space_id,
+            sender: PeerRecord {
+                peer_id: local.to_string(),
+                addrs: dialable(listen_addrs).into_iter().map(|addr| addr.to_string()).collect(),
+            },
+            peers,
+        }
+    }
+}
+
+/// Listen addresses others can dial (drops unspecified and circuit addresses)
+fn dialable(addrs: &[Multiaddr]) -> Vec<Multiaddr> {
+    use libp2p::multiaddr::Protocol;
+
+    addrs.iter()
+        .filter(|addr| addr.iter().all(|protocol| match protocol {
+            Protocol::Ip4(ip) => !ip.is_unspecified(),
+            Protocol::Ip6(ip) => !ip.is_unspecified(),
+            Protocol::P2pCircuit => false,
+            _ => true,
+        }))
+        .take(MAX_PEER_ADDRS)
+        .cloned()
+        .collect()
+}
@@ -0,0 +1,100 @@
+//! Presence heartbeats
+//!
+//! Clients that opt in publish a signed [`Presence`] on each Space's presence
+//! topic every [`PRESENCE_INTERVAL`]. Like acks, heartbeats are ephemeral and
+//! never stored as CRDT operations. Each carries a stamp from the sender's
+//! [`Stamper`](super::replay::Stamper), so a replayed heartbeat can't make a
+//! user look online after they left.
+
+use crate::crypto::signing::{PublicKey, Signer};
+use crate::network::replay::ReplayGuard;
+use crate::types::*;
+use crate::{Error, Result};
+use minicbor::{Decode, Encode};
+use std::collections::HashMap;
+use std::time::Duration;
+
+/// How often a client announces itself in each Space
+pub const PRESENCE_INTERVAL: Duration = Duration::from_secs(30);
+
+/// Topic a Space's presence heartbeats are published on
+pub fn presence_topic(space_id: &SpaceId) -> String {
+    format!("space/{}/presence", space_id.short())
+}
+
+/// Signed claim that `from` is online in a Space
+#[derive(Debug, Clone, PartialEq, Eq, Encode, Decode)]
+pub struct Presence {
+    /// Space the user is present in
+    #[n(0)]
+    pub space_id: SpaceId,
+    /// User announcing itself
+    #[n(1)]
+    pub from: UserId,
+    /// Sender's stamp in milliseconds; higher than any it sent before
+    #[n(2)]
+    pub sent_at: u64,
+    /// Sender's signature over the Space and stamp
+    #[n(3)]
+    pub signature: Signature,
+}
+
+impl Presence {
+    /// Create and sign a heartbeat
+    pub fn new(signer: &dyn Signer, space_id: SpaceId, sent_at: u64) -> Result<Self> {
+        let signature = signer.sign(&Self::signing_bytes(&space_id, sent_at))?;
+        Ok(Self { space_id, from: signer.user_id(), sent_at, signature })
+    }
+
+    fn signing_bytes(space_id: &SpaceId, sent_at: u64) -> Vec<u8> {
+        let mut bytes = b"spaceway-presence-v1".to_vec();
+        bytes.extend_from_slice(&space_id.0);
+        bytes.extend_from_slice(&sent_at.to_be_bytes());
+        bytes
+    }
+
+    /// Check the sender's signature
+    pub fn verify(&self) -> bool {
+        PublicKey::from_bytes(&self.from.0)
+            .and_then(|key| key.verify(&Self::signing_bytes(&self.space_id, self.sent_at), &self.signature))
+            .is_ok()
+    }
+
+    /// Serialize to CBOR bytes for publishing
+    pub fn to_bytes(&self) -> Result<Vec<u8>> {
+        minicbor::to_vec(self)
+            .map_err(|e| Error::Serialization(format!("Failed to encode Presence: {}", e)))
+    }
+
+    /// Deserialize from CBOR bytes
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self> {
+        minicbor::decode(bytes)
+            .map_err(|e| Error::Serialization(format!("Failed to decode Presence: {}", e)))
+    }
+}
+
+/// When each user was last seen, per Space
+#[derive(Debug, Default)]
+pub struct PresenceBook {
+    guard: ReplayGuard,
+    last_seen: HashMap<SpaceId, HashMap<UserId, u64>>,
+}
+
+impl PresenceBook {
+    /// Record a heartbeat, returning whether it was accepted
+    ///
+    /// Heartbeats with a bad signature, or a stamp not newer than the last
+    /// one accepted from the same user in the same Space, are ignored.
+    pub fn record(&mut self, presence: &Presence) -> bool {
+        if !presence.verify() || !self.guard.accept(presence.space_id, presence.from, presence.sent_at) {
+            return false;
+        }
+        self.last_seen.entry(presence.space_id).or_default().insert(presence.from, presence.sent_at);
+        true
+    }
+
+    /// Last accepted stamp of each user seen in a Space
+    pub fn last_seen(&self, space_id: &SpaceId) -> HashMap<UserId, u64> {
+        self.last_seen.get(space_id).cloned().unwrap_or_default()
+    }
+}
@@ -16,7 +16,8 @@ use libp2p::{
     PeerId,
 };
 use serde::{Deserialize, Serialize};
-use std::time::Duration;
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
 
 /// Relay configuration
 #[derive(Clone, Debug)]
@@ -29,6 +30,10 @@ pub struct RelayConfig {
     pub max_circuit_duration: Duration,
     /// Maximum circuit bytes
     pub max_circuit_bytes: u64,
+    /// Reservation and circuit requests each peer may make per second
+    pub requests_per_sec: f64,
+    /// Requests a peer may make back to back before the rate applies
+    pub request_burst: u32,
 }
 
 impl Default for RelayConfig {
@@ -38,10 +43,174 @@ impl Default for RelayConfig {
             max_circuits_per_peer: 5,
             max_circuit_duration: Duration::from_secs(3600), // 1 hour
             max_circuit_bytes: 100 * 1024 * 1024, // 100 MB
+            requests_per_sec: 1.0,
+            request_burst: 10,
         }
     }
 }
 
+impl RelayConfig {
+    /// libp2p relay server configuration with these limits
+    ///
+    /// Requests over a peer's rate are refused by the relay behaviour and
+    /// surface as `ReservationReqDenied` / `CircuitReqDenied` events.
+    pub fn behaviour_config(&self) -> relay::Config {
+        let mut config = relay::Config {
+            max_reservations_per_peer: self.max_reservations_per_peer,
+            max_circuits_per_peer: self.max_circuits_per_peer,
+            max_circuit_duration: self.max_circuit_duration,
+            max_circuit_bytes: self.max_circuit_bytes,
+            ..Default::default()
+        };
+        config.reservation_rate_limiters.push(Box::new(
+            PeerRateLimiter::new(self.requests_per_sec, self.request_burst),
+        ));
+        config.circuit_src_rate_limiters.push(Box::new(
+            PeerRateLimiter::new(self.requests_per_sec, self.request_burst),
+        ));
+        config
+    }
+}
+
+/// Buckets kept before full ones are dropped; a full bucket is the same as none
+const RATE_LIMITER_PRUNE_THRESHOLD: usize = 1024;
+
+/// Token bucket per peer for relay requests
+///
+/// Each peer starts with `burst` tokens, spends one per request and regains
+/// `rate` tokens per second up to `burst`. A request without a token is denied.
+#[derive(Debug)]
+pub struct PeerRateLimiter {
+    rate: f64,
+    burst: f64,
+    buckets: HashMap<PeerId, (f64, Instant)>,
+}
+
+impl PeerRateLimiter {
+    /// Limiter allowing `rate` requests per second after a burst of `burst`
+    pub fn new(rate: f64, burst: u32) -> Self {
+        Self {
+            rate: rate.max(0.0),
+            burst: f64::from(burst.max(1)),
+            buckets: HashMap::new(),
+        }
+    }
+
+    /// Whether `peer` may make a request at `now`, spending a token if so
+    pub fn allow(&mut self, peer: PeerId, now: Instant) -> bool {
+        if self.buckets.len() > RATE_LIMITER_PRUNE_THRESHOLD {
+            let (rate, burst) = (self.rate, self.burst);
+            self.buckets.retain(|_, (tokens, last)| {
+                *tokens + now.saturating_duration_since(*last).as_secs_f64() * rate < burst
+            });
+        }
+
+        let (tokens, last) = self.buckets.entry(peer).or_insert((self.burst, now));
+        let elapsed = now.saturating_duration_since(*last).as_secs_f64();
+        *tokens = (*tokens + elapsed * self.rate).min(self.burst);
+        *last = now;
+
+        if *tokens >= 1.0 {
+            *tokens -= 1.0;
+            true
+        } else {
+            false
+        }
+    }
+}
+
+impl relay::RateLimiter for PeerRateLimiter {
+    fn try_next(&mut self, peer: PeerId, _addr: &Multiaddr, now: Instant) -> bool {
+        self.allow(peer, now)
+    }
+}
+
+/// A peer holding a reservation on this relay
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct ReservationStats {
+    pub peer_id: String,
+    /// Seconds since the reservation was first accepted
+    pub age_secs: u64,
+    /// Times the peer renewed it
+    pub renewals: u32,
+}
+
+/// A circuit this relay is carrying
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct CircuitStats {
+    pub src_peer_id: String,
+    pub dst_peer_id: String,
+    /// Seconds since the circuit was accepted
+    pub age_secs: u64,
+}
+
+/// Reservations and circuits a relay server currently holds
+///
+/// The libp2p relay behaviour keeps these to itself, so the relay's event
+/// loop hands each [`relay::Event`] to [`RelayActivity::observe`].
+#[derive(Debug, Default)]
+pub struct RelayActivity {
+    reservations: HashMap<PeerId, (Instant, u32)>,
+    circuits: Vec<(PeerId, PeerId, Instant)>,
+}
+
+impl RelayActivity {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Update from an event of the relay server behaviour
+    pub fn observe(&mut self, event: &relay::Event) {
+        match event {
+            relay::Event::ReservationReqAccepted { src_peer_id, .. } => {
+                self.reservations.entry(*src_peer_id)
+                    .and_modify(|(_, renewals)| *renewals += 1)
+                    .or_insert((Instant::now(), 0));
+            }
+            relay::Event::ReservationClosed { src_peer_id }
+            | relay::Event::ReservationTimedOut { src_peer_id } => {
+                self.reservations.remove(src_peer_id);
+            }
+            relay::Event::CircuitReqAccepted { src_peer_id, dst_peer_id } => {
+                self.circuits.push((*src_peer_id, *dst_peer_id, Instant::now()));
+            }
+            relay::Event::CircuitClosed { src_peer_id, dst_peer_id, .. } => {
+                // Circuits between the same pair are indistinguishable; drop the oldest
+                if let Some(index) = self.circuits.iter()
+                    .position(|(src, dst, _)| src == src_peer_id && dst == dst_peer_id)
+                {
+                    self.circuits.remove(index);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Peers holding a reservation, longest-held first
+    pub fn reservations(&self) -> Vec<ReservationStats> {
+        let mut reservations: Vec<_> = self.reservations.iter()
+            .map(|(peer_id, (since, renewals))| ReservationStats {
+                peer_id: peer_id.to_string(),
+                age_secs: since.elapsed().as_secs(),
+                renewals: *renewals,
+            })
+            .collect();
+        reservations.sort_by(|a, b| b.age_secs.cmp(&a.age_secs).then(a.peer_id.cmp(&b.peer_id)));
+        reservations
+    }
+
+    /// Active circuits, oldest first
+    pub fn circuits(&self) -> Vec<CircuitStats> {
+        self.circuits.iter()
+            .map(|(src, dst, since)| CircuitStats {
+                src_peer_id: src.to_string(),
+                dst_peer_id: dst.to_string(),
+                age_secs: since.elapsed().as_secs(),
+            })
+            .collect()
+    }
+}
+
 /// Relay mode - how this node participates in relay network
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
 pub enum RelayMode {
@@ -73,6 +242,8 @@ pub struct RelayInfo {
     pub addresses: Vec<Multiaddr>,
     /// Relay capacity (max concurrent circuits)
     pub capacity: u32,
+    /// Circuits the relay reported in use when it last advertised
+    pub active_circuits: u32,
     /// Reputation score (0-100)
     pub reputation: u32,
     /// Estimated latency in milliseconds
@@ -92,25 +263,133 @@ pub struct RelayAdvertisement {
     pub addresses: Vec<Multiaddr>,
     /// Max concurrent circuits relay can handle
     pub capacity: u32,
+    /// Circuits in use when this advertisement was published
+    pub active_circuits: u32,
     /// Relay mode
     pub mode: RelayMode,
     /// Timestamp of advertisement
     pub timestamp: u64,
 }
 
+/// Reputation given to a relay known only from its advertisement
+pub const DEFAULT_RELAY_REPUTATION: u32 = 50;
+
+impl RelayInfo {
+    /// Build from a relay's advertisement, before any reputation is earned
+    pub fn from_advertisement(advertisement: RelayAdvertisement) -> Self {
+        Self {
+            peer_id: advertisement.peer_id,
+            addresses: advertisement.addresses,
+            capacity: advertisement.capacity,
+            active_circuits: advertisement.active_circuits,
+            reputation: DEFAULT_RELAY_REPUTATION,
+            latency_ms: None,
+            last_seen: advertisement.timestamp,
+            mode: advertisement.mode,
+        }
+    }
+    
+    /// Circuits the relay can carry at once: its advertised capacity, capped
+    /// by the circuit limit of a cooperative relay
+    pub fn max_circuits(&self) -> u32 {
+        match self.mode {
+            RelayMode::Cooperative { max_concurrent_circuits, .. } => {
+                self.capacity.min(u32::try_from(max_concurrent_circuits).unwrap_or(u32::MAX))
+            }
+            RelayMode::ClientOnly | RelayMode::DedicatedServer => self.capacity,
+        }
+    }
+    
+    /// Circuits still free as of the last advertisement
+    pub fn free_circuits(&self) -> u32 {
+        self.max_circuits().saturating_sub(self.active_circuits)
+    }
+    
+    /// Whether the relay is likely to refuse a new reservation
+    pub fn is_saturated(&self) -> bool {
+        self.free_circuits() == 0
+    }
+    
+    /// First address with the relay's peer ID appended, as needed to reserve
+    /// a slot on it
+    pub fn dial_address(&self) -> Option<Multiaddr> {
+        let addr = self.addresses.first()?.clone();
+        match addr.iter().last() {
+            Some(libp2p::multiaddr::Protocol::P2p(_)) => Some(addr),
+            _ => Some(addr.with(libp2p::multiaddr::Protocol::P2p(self.peer_id))),
+        }
+    }
+    
+    /// Reputation scaled by the share of circuits still free
+    pub fn selection_score(&self) -> f64 {
+        let max_circuits = self.max_circuits();
+        if max_circuits == 0 {
+            return 0.0;
+        }
+        f64::from(self.reputation) * f64::from(self.free_circuits()) / f64::from(max_circuits)
+    }
+}
+
+/// Order relays best first for a new reservation
+/// 
+/// Saturated relays go last, so they are only tried when nothing else is
+/// left. Ties on score go to the relay with more free circuits.
+pub fn rank_relays(mut relays: Vec<RelayInfo>) -> Vec<RelayInfo> {
+    relays.sort_by(|a, b| {
+        a.is_saturated().cmp(&b.is_saturated())
+            .then_with(|| b.selection_score().total_cmp(&a.selection_score()))
+            .then_with(|| b.free_circuits().cmp(&a.free_circuits()))
+    });
+    relays
+}
+
 // Manual serialization helpers for types that don't impl Serialize
 impl RelayAdvertisement {
     pub fn to_bytes(&self) -> Vec<u8> {
         let data = format!(
-            "{{\"peer_id\":\"{}\",\"addresses\":[{}],\"capacity\":{},\"mode\":{},\"timestamp\":{}}}",
+            "{{\"peer_id\":\"{}\",\"addresses\":[{}],\"capacity\":{},\"active_circuits\":{},\"mode\":{},\"timestamp\":{}}}",
             self.peer_id,
             self.addresses.iter().map(|a| format!("\"{}\"", a)).collect::<Vec<_>>().join(","),
             self.capacity,
+            self.active_circuits,
             serde_json::to_string(&self.mode).unwrap(),
             self.timestamp
         );
         data.into_bytes()
     }
+    
+    /// Parse an advertisement written by `to_bytes`
+    /// 
+    /// Advertisements from older relays carry no `active_circuits` and are
+    /// read as idle. Unparseable addresses are skipped.
+    pub fn from_bytes(bytes: &[u8]) -> crate::Result<Self> {
+        use crate::Error;
+        
+        #[derive(Deserialize)]
+        struct Raw {
+            peer_id: String,
+            addresses: Vec<String>,
+            capacity: u32,
+            #[serde(default)]
+            active_circuits: u32,
+            mode: RelayMode,
+            timestamp: u64,
+        }
+        
+        let raw: Raw = serde_json::from_slice(bytes)
+            .map_err(|e| Error::Serialization(format!("Invalid relay advertisement: {}", e)))?;
+        let peer_id = raw.peer_id.parse()
+            .map_err(|e| Error::Serialization(format!("Invalid relay peer ID: {}", e)))?;
+        
+        Ok(Self {
+            peer_id,
+            addresses: raw.addresses.iter().filter_map(|addr| addr.parse().ok()).collect(),
+            capacity: raw.capacity,
+            active_circuits: raw.active_circuits,
+            mode: raw.mode,
+            timestamp: raw.timestamp,
+        })
+    }
 }
 
 /// DHT key for relay advertisements
@@ -164,6 +443,31 @@ mod tests {
         assert_eq!(config.max_circuits_per_peer, 5);
     }
 
+    #[test]
+    fn test_peer_rate_limiter_burst_then_steady() {
+        let mut limiter = PeerRateLimiter::new(2.0, 5);
+        let peer = PeerId::random();
+        let other = PeerId::random();
+        let start = Instant::now();
+
+        // A burst is served up to its size, then denied
+        let allowed = (0..8).filter(|_| limiter.allow(peer, start)).count();
+        assert_eq!(allowed, 5);
+        assert!(limiter.allow(other, start), "peers have their own buckets");
+
+        // Steady load at the configured rate keeps being served
+        for step in 1..=10 {
+            assert!(limiter.allow(peer, start + Duration::from_millis(500 * step)));
+        }
+
+        // Faster than the rate, only every other request gets through
+        let base = start + Duration::from_secs(5);
+        let allowed = (1..=10)
+            .filter(|step| limiter.allow(peer, base + Duration::from_millis(250 * step)))
+            .count();
+        assert_eq!(allowed, 5);
+    }
+
     #[test]
     fn test_default_relay_addresses() {
         let addrs = default_relay_addresses();
@@ -0,0 +1,52 @@
+//! Replay protection for ephemeral messages
+//!
+//! Acks and presence heartbeats are signed but never stored, so a captured
+//! one could be published again later, e.g. to make a user look online. Each
+//! carries a per-sender stamp that only goes up; receivers drop any message
+//! whose stamp isn't newer than the last they accepted from that sender in
+//! the same Space. Stamps are compared per Space because each Space's topic
+//! is delivered independently, so a sender's messages to two Spaces can
+//! arrive in either order.
+
+use crate::types::*;
+use std::collections::HashMap;
+
+/// Sender side: strictly increasing stamps, in milliseconds since the epoch
+///
+/// Stamps follow the wall clock, so they keep increasing across restarts,
+/// but never repeat when several messages go out in the same millisecond.
+#[derive(Debug, Default)]
+pub struct Stamper {
+    last: u64,
+}
+
+impl Stamper {
+    /// The stamp for the next message
+    pub fn next(&mut self) -> u64 {
+        let now = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_millis() as u64;
+        self.last = now.max(self.last + 1);
+        self.last
+    }
+}
+
+/// Receiver side: the newest stamp accepted from each sender, per Space
+#[derive(Debug, Default)]
+pub struct ReplayGuard {
+    last_seen: HashMap<(SpaceId, UserId), u64>,
+}
+
+impl ReplayGuard {
+    /// Accept a message if its stamp is newer than any seen from `from` in `space_id`
+    pub fn accept(&mut self, space_id: SpaceId, from: UserId, stamp: u64) -> bool {
+        match self.last_seen.get(&(space_id, from)) {
+            Some(last) if stamp <= *last => false,
+            _ => {
+                self.last_seen.insert((space_id, from), stamp);
+                true
+            }
+        }
+    }
+}
@@ -0,0 +1,52 @@
+//! Per-author throttle on operations received over gossip
+//!
+//! A peer flooding the network with validly signed operations could
+//! otherwise keep every receiver busy storing and applying them. The
+//! throttle counts each author's operations by when *we* received them, not
+//! by their HLC, which the author chooses. An operation dropped here is not
+//! stored, so anti-entropy can still fetch it once the author calms down;
+//! replicas never disagree on what the CRDT accepted.
+
+use crate::crdt::RateLimit;
+use crate::types::UserId;
+use std::collections::{HashMap, VecDeque};
+use std::time::{Duration, Instant};
+
+/// Sliding-window count of received operations per author
+#[derive(Debug)]
+pub struct OpThrottle {
+    limit: Option<RateLimit>,
+    /// Receive times per author, oldest first
+    received: HashMap<UserId, VecDeque<Instant>>,
+}
+
+impl OpThrottle {
+    /// Throttle enforcing `limit` (None lets everything through)
+    pub fn new(limit: Option<RateLimit>) -> Self {
+        Self { limit, received: HashMap::new() }
+    }
+
+    /// Whether an operation from `author` received at `now` may be processed
+    ///
+    /// Admitted operations count towards the author's window; dropped ones
+    /// don't, so a flood doesn't keep the author throttled once it stops.
+    pub fn admit(&mut self, author: UserId, now: Instant) -> bool {
+        let Some(limit) = self.limit else {
+            return true;
+        };
+        let window = Duration::from_millis(limit.window_ms);
+
+        let received = self.received.entry(author).or_default();
+        while received.front().is_some_and(|&time| now.saturating_duration_since(time) >= window) {
+            received.pop_front();
+        }
+        if received.len() >= limit.max_ops {
+            return false;
+        }
+        received.push_back(now);
+
+        // Forget authors who went quiet
+        self.received.retain(|_, times| times.back().is_some_and(|&time| now.saturating_duration_since(time) < window));
+        true
+    }
+}
@@ -0,0 +1,124 @@
+//! Notification inbox
+//!
+//! Notifications are not ops of their own: they are derived from the stored
+//! op stream each time they are listed, minus the messages the user marked
+//! read. Both the ops and the read markers are persisted, so the inbox
+//! survives a restart. A message that is later edited to drop the mention,
+//! deleted or redacted no longer notifies. There are no direct messages yet,
+//! so mentions and replies are the only kinds.
+
+use crate::crdt::{CrdtOp, Hlc, OpPayload, OpType};
+use crate::forum::thread::content_mentions;
+use crate::types::*;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+
+/// Why a message notifies the user
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum NotificationKind {
+    /// The message mentions the user as `@<short id>`
+    Mention,
+    /// The message quotes one of the user's messages
+    Reply,
+}
+
+/// An unread message that concerns the user
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Notification {
+    /// Message that notifies; also identifies the notification
+    pub message_id: MessageId,
+    /// Space the message was posted in
+    pub space_id: SpaceId,
+    /// Thread the message was posted in
+    pub thread_id: ThreadId,
+    /// Who posted the message
+    pub author: UserId,
+    /// Why it notifies (a reply that also mentions counts as a reply)
+    pub kind: NotificationKind,
+    /// Current content of the message
+    pub content: String,
+    /// When the message was posted (milliseconds since epoch)
+    pub timestamp: u64,
+}
+
+struct TrackedMessage {
+    space_id: SpaceId,
+    thread_id: ThreadId,
+    author: UserId,
+    content: String,
+    quoted: Option<MessageId>,
+    hlc: Hlc,
+    timestamp: u64,
+    deleted: bool,
+}
+
+/// Unread notifications for `user_id` in `ops`, newest first
+pub fn derive_notifications(ops: &[CrdtOp], user_id: &UserId, read: &HashSet<MessageId>) -> Vec<Notification> {
+    let mut ops: Vec<&CrdtOp> = ops.iter().collect();
+    ops.sort_by_key(|op| op.hlc);
+
+    let mut messages: HashMap<MessageId, TrackedMessage> = HashMap::new();
+    for op in ops {
+        let (message_id, content, quoted) = match &op.op_type {
+            OpType::PostMessage(OpPayload::PostMessage { message_id, content, quote }) => {
+                (*message_id, content, quote.as_ref().map(|quote| quote.message_id))
+            }
+            OpType::CreateThread(OpPayload::CreateThread { first_message, first_message_id, .. }) => {
+                (*first_message_id, first_message, None)
+            }
+            OpType::EditMessage(OpPayload::EditMessage { message_id, new_content }) => {
+                if let Some(message) = messages.get_mut(message_id) {
+                    message.content = new_content.clone();
+                }
+                continue;
+            }
+            OpType::DeleteMessage(OpPayload::DeleteMessage { message_id, .. })
+            | OpType::RedactMessage(OpPayload::RedactMessage { message_id }) => {
+                if let Some(message) = messages.get_mut(message_id) {
+                    message.deleted = true;
+                }
+                continue;
+            }
+            _ => continue,
+        };
+        let Some(thread_id) = op.thread_id else { continue };
+        messages.insert(message_id, TrackedMessage {
+            space_id: op.space_id,
+            thread_id,
+            author: op.author,
+            content: content.clone(),
+            quoted,
+            hlc: op.hlc,
+            timestamp: op.timestamp,
+            deleted: false,
+        });
+    }
+
+    let mut notifications: Vec<(Hlc, Notification)> = messages.iter()
+        .filter(|(id, message)| message.author != *user_id && !message.deleted && !read.contains(id))
+        .filter_map(|(id, message)| {
+            let replies_to_user = message.quoted
+                .and_then(|quoted| messages.get(&quoted))
+                .is_some_and(|quoted| quoted.author == *user_id);
+            let kind = if replies_to_user {
+                NotificationKind::Reply
+            } else if content_mentions(&message.content, user_id) {
+                NotificationKind::Mention
+            } else {
+                return None;
+            };
+            Some((message.hlc, Notification {
+                message_id: *id,
+                space_id: message.space_id,
+                thread_id: message.thread_id,
+                author: message.author,
+                kind,
+                content: message.content.clone(),
+                timestamp: message.timestamp,
+            }))
+        })
+        .collect();
+
+    notifications.sort_by(|a, b| b.0.cmp(&a.0));
+    notifications.into_iter().map(|(_, notification)| notification).collect()
+}
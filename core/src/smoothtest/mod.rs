@@ -8,6 +8,7 @@
 //! - `SmoothClient`: Single test client with isolated storage
 //! - `SmoothClientBatch`: Collection of clients that can communicate
 //! - Utilities for awaiting DHT consistency, peer discovery, etc.
+//! - `NetworkConditions`: injectable latency, jitter and message loss
 //!
 //! # Example
 //!
@@ -36,3 +37,4 @@ mod consistency;
 pub use smooth_client::SmoothClient;
 pub use smooth_batch::SmoothClientBatch;
 pub use consistency::await_dht_consistency;
+pub use crate::network::NetworkConditions;
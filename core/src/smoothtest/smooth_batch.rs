@@ -2,6 +2,7 @@
 
 use super::SmoothClient;
 use crate::client::ClientConfig;
+use crate::network::NetworkConditions;
 use anyhow::Result;
 use std::ops::{Index, IndexMut};
 
@@ -50,6 +51,36 @@ impl SmoothClientBatch {
         Ok(Self { clients })
     }
 
+    /// Create a batch of N clients with reproducible identities
+    ///
+    /// Each client's seed is derived from `seed` and its index, so the
+    /// same batch seed always yields the same identities in the same order.
+    pub fn with_seed(count: usize, seed: [u8; 32]) -> Result<Self> {
+        let mut clients = Vec::with_capacity(count);
+        for index in 0..count {
+            clients.push(SmoothClient::with_seed(Self::client_seed(&seed, index))?);
+        }
+
+        Ok(Self { clients })
+    }
+
+    /// Derive the seed for the client at `index` from the batch seed
+    fn client_seed(seed: &[u8; 32], index: usize) -> [u8; 32] {
+        let mut hasher = blake3::Hasher::new();
+        hasher.update(b"SMOOTHTEST_SEED_V1:");
+        hasher.update(seed);
+        hasher.update(&(index as u64).to_le_bytes());
+        *hasher.finalize().as_bytes()
+    }
+
+    /// Apply the same simulated network conditions to every client
+    pub async fn set_network_conditions(&self, conditions: NetworkConditions) -> Result<()> {
+        for client in &self.clients {
+            client.set_network_conditions(conditions).await?;
+        }
+        Ok(())
+    }
+
     /// Get the number of clients in this batch
     pub fn len(&self) -> usize {
         self.clients.len()
@@ -128,5 +159,14 @@ mod tests {
         let _bob = &batch[1];
         let _carol = &batch[2];
     }
-}
 
+    #[tokio::test]
+    async fn test_batch_with_seed_is_reproducible() {
+        let batch1 = SmoothClientBatch::with_seed(3, [1u8; 32]).unwrap();
+        let batch2 = SmoothClientBatch::with_seed(3, [1u8; 32]).unwrap();
+        for i in 0..3 {
+            assert_eq!(batch1[i].keypair().user_id(), batch2[i].keypair().user_id());
+        }
+        assert_ne!(batch1[0].keypair().user_id(), batch1[1].keypair().user_id());
+    }
+}
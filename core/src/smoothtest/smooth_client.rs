@@ -3,7 +3,8 @@
 use crate::client::{Client, ClientConfig};
 use crate::crypto::signing::Keypair;
 use crate::forum::Space;
-use crate::types::SpaceId;
+use crate::network::NetworkConditions;
+use crate::types::{Role, SpaceId};
 use std::sync::Arc;
 use tokio::sync::RwLock;
 use anyhow::Result;
@@ -28,15 +29,31 @@ impl SmoothClient {
     }
 
     /// Create a new test client with custom configuration
-    pub fn with_config(mut config: ClientConfig) -> Result<Self> {
+    pub fn with_config(config: ClientConfig) -> Result<Self> {
+        // Create random keypair for test
+        Self::with_keypair(Keypair::generate(), config)
+    }
+
+    /// Create a test client whose identity is derived from `seed`
+    ///
+    /// The same seed always produces the same `UserId`, so test runs that
+    /// depend on identity ordering are reproducible.
+    pub fn with_seed(seed: [u8; 32]) -> Result<Self> {
+        Self::with_seed_and_config(seed, ClientConfig::default())
+    }
+
+    /// Create a seeded test client with custom configuration
+    pub fn with_seed_and_config(seed: [u8; 32], config: ClientConfig) -> Result<Self> {
+        Self::with_keypair(Keypair::generate_seeded(seed), config)
+    }
+
+    /// Create a test client with the given identity and isolated storage
+    fn with_keypair(keypair: Keypair, mut config: ClientConfig) -> Result<Self> {
         let data_dir = tempfile::tempdir()?;
         
         // Override storage path to use temp directory
         config.storage_path = data_dir.path().to_path_buf();
         
-        // Create random keypair for test
-        let keypair = Keypair::generate();
-        
         // Initialize client with isolated data directory
         let client = Client::new(keypair.clone(), config)?;
 
@@ -83,6 +100,41 @@ impl SmoothClient {
         Ok(())
     }
 
+    /// Apply simulated latency/loss to messages this client receives
+    pub async fn set_network_conditions(&self, conditions: NetworkConditions) -> Result<()> {
+        let client = self.client.read().await;
+        client.set_network_conditions(conditions).await?;
+        Ok(())
+    }
+
+    /// Ask a connected peer to re-send the operations of a space we lack
+    pub async fn request_sync(&self, space_id: SpaceId) -> Result<()> {
+        let client = self.client.read().await;
+        client.request_space_sync(&space_id).await?;
+        Ok(())
+    }
+
+    /// Add `member` to a space's MLS group and wait until its Welcome is processed
+    /// 
+    /// `member` must already know the space, e.g. from its CreateSpace op,
+    /// and be connected to this client.
+    pub async fn admit(&self, member: &SmoothClient, space_id: SpaceId, role: Role) -> Result<()> {
+        let member_client = member.client.read().await;
+        let bundle = member_client.get_key_package_bundle().await?;
+        self.client.read().await
+            .add_member_with_key_package_bundle(space_id, member_client.user_id(), role, bundle)
+            .await?;
+        
+        let deadline = tokio::time::Instant::now() + std::time::Duration::from_secs(10);
+        while member_client.space_epoch(&space_id).await.is_none() {
+            if tokio::time::Instant::now() >= deadline {
+                anyhow::bail!("Welcome for space {} did not arrive", space_id.short());
+            }
+            tokio::time::sleep(std::time::Duration::from_millis(200)).await;
+        }
+        Ok(())
+    }
+
     /// Get the number of spaces this client knows about
     pub async fn space_count(&self) -> usize {
         let client = self.client.read().await;
@@ -131,5 +183,13 @@ mod tests {
         let _space = client.create_space("test-space", Some("A test space")).await.unwrap();
         assert_eq!(client.space_count().await, 1);
     }
+
+    #[tokio::test]
+    async fn test_smooth_client_with_seed() {
+        let client1 = SmoothClient::with_seed([42u8; 32]).unwrap();
+        let client2 = SmoothClient::with_seed([42u8; 32]).unwrap();
+        assert_eq!(client1.keypair().user_id(), client2.keypair().user_id());
+        assert_ne!(client1.data_path(), client2.data_path());
+    }
 }
 
@@ -0,0 +1,98 @@
+//! Blob content-type validation
+//!
+//! When `StorageConfig::allowed_mime_types` is set, uploads must declare one
+//! of the allowed types and their leading bytes must not contradict it: a
+//! blob declared as `image/png` has to start with the PNG signature, and a
+//! blob declared as text must not sniff as a known binary format.
+
+use super::Storage;
+use crate::{Error, Result};
+
+/// Magic-number signatures: (mime type, byte offset, signature)
+const SIGNATURES: &[(&str, usize, &[u8])] = &[
+    ("image/png", 0, b"\x89PNG\r\n\x1a\n"),
+    ("image/jpeg", 0, b"\xff\xd8\xff"),
+    ("image/gif", 0, b"GIF87a"),
+    ("image/gif", 0, b"GIF89a"),
+    ("image/webp", 8, b"WEBP"),
+    ("audio/wav", 8, b"WAVE"),
+    ("audio/ogg", 0, b"OggS"),
+    ("audio/mpeg", 0, b"ID3"),
+    ("video/mp4", 4, b"ftyp"),
+    ("application/pdf", 0, b"%PDF-"),
+    ("application/zip", 0, b"PK\x03\x04"),
+    ("application/gzip", 0, b"\x1f\x8b"),
+];
+
+/// Identify a blob's type from its leading bytes, if it has a known signature
+pub fn sniff_mime_type(data: &[u8]) -> Option<&'static str> {
+    SIGNATURES.iter()
+        .find(|(_, offset, magic)| data.get(*offset..*offset + magic.len()) == Some(*magic))
+        .map(|(mime, _, _)| *mime)
+}
+
+/// Lowercase a mime type and drop parameters such as `; charset=utf-8`
+fn normalize(mime_type: &str) -> String {
+    mime_type.split(';').next().unwrap_or_default().trim().to_lowercase()
+}
+
+/// Whether `mime_type` matches an allowlist entry (`image/png` or `image/*`)
+fn is_allowed(allowed: &[String], mime_type: &str) -> bool {
+    allowed.iter().map(|entry| normalize(entry)).any(|entry| {
+        match entry.strip_suffix("/*") {
+            Some(family) => mime_type.split('/').next() == Some(family),
+            None => entry == mime_type,
+        }
+    })
+}
+
+impl Storage {
+    /// Check a blob's declared mime type against the allowlist and its bytes
+    ///
+    /// Does nothing unless `allowed_mime_types` is configured.
+    pub fn check_content_type(&self, data: &[u8], mime_type: Option<&str>) -> Result<()> {
+        let Some(allowed) = &self.config.allowed_mime_types else {
+            return Ok(());
+        };
+
+        let declared = mime_type.map(normalize)
+            .ok_or_else(|| Error::InvalidOperation("Blob has no declared content type".to_string()))?;
+        if !is_allowed(allowed, &declared) {
+            return Err(Error::InvalidOperation(format!("Content type {} is not allowed", declared)));
+        }
+
+        let declared_has_signature = SIGNATURES.iter().any(|(mime, _, _)| *mime == declared);
+        match sniff_mime_type(data) {
+            Some(sniffed) if sniffed != declared => Err(Error::InvalidOperation(format!(
+                "Blob declared as {} but its contents look like {}",
+                declared, sniffed
+            ))),
+            None if declared_has_signature => Err(Error::InvalidOperation(format!(
+                "Blob declared as {} does not start with a {} signature",
+                declared, declared
+            ))),
+            _ => Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sniff_known_signatures() {
+        assert_eq!(sniff_mime_type(b"\x89PNG\r\n\x1a\n...."), Some("image/png"));
+        assert_eq!(sniff_mime_type(b"RIFF\0\0\0\0WEBPVP8 "), Some("image/webp"));
+        assert_eq!(sniff_mime_type(b"hello world"), None);
+        assert_eq!(sniff_mime_type(b""), None);
+    }
+
+    #[test]
+    fn test_allowlist_wildcards_and_parameters() {
+        let allowed = vec!["image/*".to_string(), "Text/Plain".to_string()];
+        assert!(is_allowed(&allowed, "image/png"));
+        assert!(is_allowed(&allowed, &normalize("text/plain; charset=utf-8")));
+        assert!(!is_allowed(&allowed, "application/pdf"));
+    }
+}
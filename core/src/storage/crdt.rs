@@ -65,6 +65,21 @@ impl VectorClock {
         !self.happens_before(other) && !other.happens_before(self)
     }
 
+    /// Users whose counters differ, as `(user, ours, theirs)`, sorted by user
+    pub fn diff(&self, other: &VectorClock) -> Vec<(String, u64, u64)> {
+        let users: std::collections::BTreeSet<&String> = self.clocks.keys()
+            .chain(other.clocks.keys())
+            .collect();
+        users.into_iter()
+            .map(|user| (
+                user.clone(),
+                self.clocks.get(user).copied().unwrap_or(0),
+                other.clocks.get(user).copied().unwrap_or(0),
+            ))
+            .filter(|(_, ours, theirs)| ours != theirs)
+            .collect()
+    }
+
     /// Merge two vector clocks (take max of each counter)
     pub fn merge(&mut self, other: &VectorClock) {
         for (user, &count) in &other.clocks {
@@ -228,6 +243,20 @@ mod tests {
         assert!(!clock2.happens_before(&clock1));
     }
 
+    #[test]
+    fn test_vector_clock_diff() {
+        let alice = UserId([1u8; 32]);
+        let bob = UserId([2u8; 32]);
+        let mut ours = VectorClock::new();
+        let mut theirs = VectorClock::new();
+        ours.increment(&alice);
+        theirs.increment(&alice);
+        theirs.increment(&bob);
+
+        assert_eq!(ours.diff(&theirs), vec![(bob.to_string(), 0, 1)]);
+        assert!(ours.diff(&ours).is_empty());
+    }
+
     #[test]
     fn test_vector_clock_concurrent() {
         let mut clock1 = VectorClock::new();
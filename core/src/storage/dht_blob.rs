@@ -4,7 +4,8 @@
 //! so users can fetch them even when the original author is offline.
 
 use crate::storage::{BlobHash, EncryptedBlob};
-use crate::types::SpaceId;
+use crate::mls::SpaceKeys;
+use crate::types::{EpochId, SpaceId};
 use crate::{Error, Result};
 use aes_gcm::{Aes256Gcm, Nonce, aead::{Aead, KeyInit}};
 use rand::rngs::OsRng;
@@ -16,8 +17,9 @@ use minicbor::{Encode, Decode};
 /// Encrypted blob for DHT storage
 /// 
 /// Blobs are already encrypted once (for local storage), but we encrypt
-/// them again with a Space-derived key for DHT storage. This allows
-/// Space members to discover and decrypt blobs without knowing the
+/// them again for DHT storage: under the epoch's MLS exporter key for MLS
+/// Spaces ([`Self::seal`]), or a Space ID key for Lightweight Spaces. This
+/// allows Space members to discover and decrypt blobs without knowing the
 /// original encryption key.
 #[derive(Clone, Debug, Serialize, Deserialize, Encode, Decode)]
 pub struct DhtBlob {
@@ -36,27 +38,56 @@ pub struct DhtBlob {
     /// Encrypted blob data (contains the locally-encrypted blob)
     #[b(3)]
     pub ciphertext: Vec<u8>,
+    
+    /// MLS epoch whose key sealed the blob, or `None` for the Space ID key
+    #[n(4)]
+    pub key_epoch: Option<EpochId>,
 }
 
 impl DhtBlob {
+    /// Seal a locally-encrypted blob for a Space's members under its current epoch
+    /// 
+    /// Lightweight Spaces (`SpaceKeys::Public`) use the Space ID key.
+    pub fn seal(space_id: &SpaceId, blob_hash: &BlobHash, local_blob: &EncryptedBlob, keys: &SpaceKeys) -> Result<Self> {
+        match keys.sealing()? {
+            Some((epoch, key)) => {
+                let mut sealed = Self::encrypt_with_key(space_id, blob_hash, local_blob, &key)?;
+                sealed.key_epoch = Some(epoch);
+                Ok(sealed)
+            }
+            None => Self::encrypt(space_id, blob_hash, local_blob),
+        }
+    }
+    
+    /// Open a DHT blob with the key of the epoch it names
+    pub fn open(&self, keys: &SpaceKeys) -> Result<EncryptedBlob> {
+        match self.key_epoch {
+            Some(epoch) => self.decrypt_with_key(&keys.opening(epoch)?),
+            None => self.decrypt(),
+        }
+    }
+    
     /// Encrypt a locally-encrypted blob for DHT storage
     /// 
     /// Takes the EncryptedBlob (already encrypted for local storage)
     /// and encrypts it again with the Space-derived key.
     pub fn encrypt(space_id: &SpaceId, blob_hash: &BlobHash, local_blob: &EncryptedBlob) -> Result<Self> {
+        // Derive encryption key from Space ID
+        Self::encrypt_with_key(space_id, blob_hash, local_blob, &Self::derive_key(space_id))
+    }
+    
+    /// Encrypt a locally-encrypted blob under a caller-supplied key
+    fn encrypt_with_key(space_id: &SpaceId, blob_hash: &BlobHash, local_blob: &EncryptedBlob, key: &[u8; 32]) -> Result<Self> {
         // Serialize the locally-encrypted blob
         let plaintext = local_blob.to_bytes()?;
         
-        // Derive encryption key from Space ID
-        let key = Self::derive_key(space_id);
-        
         // Generate random nonce
         let mut nonce_bytes = [0u8; 12];
         OsRng.fill_bytes(&mut nonce_bytes);
         let nonce = Nonce::from_slice(&nonce_bytes);
         
         // Encrypt
-        let cipher = Aes256Gcm::new_from_slice(&key)
+        let cipher = Aes256Gcm::new_from_slice(key)
             .map_err(|e| Error::Crypto(format!("Failed to create cipher: {}", e)))?;
         
         let ciphertext = cipher.encrypt(nonce, plaintext.as_ref())
@@ -67,16 +98,20 @@ impl DhtBlob {
             content_hash: *blob_hash,
             nonce: nonce_bytes,
             ciphertext,
+            key_epoch: None,
         })
     }
     
     /// Decrypt a DHT blob to get the locally-encrypted blob
     pub fn decrypt(&self) -> Result<EncryptedBlob> {
         // Derive decryption key from Space ID
-        let key = Self::derive_key(&self.space_id);
-        
+        self.decrypt_with_key(&Self::derive_key(&self.space_id))
+    }
+    
+    /// Decrypt a DHT blob sealed under `key`
+    fn decrypt_with_key(&self, key: &[u8; 32]) -> Result<EncryptedBlob> {
         // Decrypt
-        let cipher = Aes256Gcm::new_from_slice(&key)
+        let cipher = Aes256Gcm::new_from_slice(key)
             .map_err(|e| Error::Crypto(format!("Failed to create cipher: {}", e)))?;
         
         let nonce = Nonce::from_slice(&self.nonce);
@@ -131,6 +166,23 @@ impl DhtBlob {
     }
 }
 
+/// Where a blob can be loaded from, checked without fetching its bytes
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct BlobAvailability {
+    /// The encrypted blob is on local disk
+    pub local: bool,
+    
+    /// The Space's DHT blob index lists it
+    pub dht: bool,
+}
+
+impl BlobAvailability {
+    /// Whether the blob can be loaded at all
+    pub fn is_available(&self) -> bool {
+        self.local || self.dht
+    }
+}
+
 /// Index of blobs available in the DHT for a Space
 /// 
 /// This allows efficient discovery of all blobs without scanning.
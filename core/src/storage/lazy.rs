@@ -153,9 +153,11 @@ impl Storage {
     
     /// Get recent messages from a user (with pagination)
     pub fn get_user_messages_page(&self, user_id: &UserId, page_size: usize, offset: usize) -> Result<Vec<(MessageId, BlobHash, u64)>> {
-        // TODO: Implement get_user_messages method
-        // For now, return empty vec as placeholder
-        Ok(Vec::new())
+        let messages = self.get_user_messages(user_id, offset.saturating_add(page_size), None)?;
+        Ok(messages.into_iter()
+            .skip(offset)
+            .map(|index| (index.message_id, index.blob_hash, index.timestamp))
+            .collect())
     }
 }
 
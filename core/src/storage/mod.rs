@@ -17,25 +17,34 @@ pub mod lazy;
 pub mod relay_cache;
 pub mod compression;
 pub mod dht_blob;
+pub mod stats;
+pub mod quota;
+pub mod schema;
+pub mod content_type;
 
 use anyhow::{Context, Result, anyhow};
 use rocksdb::{DB, Options, ColumnFamilyDescriptor};
 use sha2::{Sha256, Digest};
 use hkdf::Hkdf;
+use std::collections::HashMap;
 use std::path::{Path, PathBuf};
 use std::fs;
-use crate::types::{ThreadId, MessageId};
+use crate::types::{ThreadId, MessageId, SpaceId, UserId};
 use serde::{Serialize, Deserialize};
 use zeroize::Zeroizing;
 
 pub use blob::EncryptedBlob;
-pub use dht_blob::{DhtBlob, BlobIndex};
+pub use dht_blob::{BlobAvailability, DhtBlob, BlobIndex};
 pub use indices::{BlobMetadata, MessageIndex};
 pub use crdt::{VectorClock, TombstoneSet};
 pub use store::Store;
 pub use sync::{SyncRequest, SyncResponse, SyncMessage};
 pub use lazy::{ThreadPreview, MessageCursor, MessagePage};
 pub use relay_cache::RelayStats;
+pub use stats::StorageStats;
+pub use quota::EvictionPolicy;
+pub use schema::{Migration, SCHEMA_VERSION};
+pub use content_type::sniff_mime_type;
 
 /// Content-addressed blob hash (SHA256)
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, minicbor::Encode, minicbor::Decode)]
@@ -80,12 +89,30 @@ impl BlobHash {
     }
 }
 
+/// Storage layout and limits
+#[derive(Debug, Clone, Default)]
+pub struct StorageConfig {
+    /// Per-Space blob directories that override the default `blobs/spaces/<id>`
+    pub space_blob_dirs: HashMap<SpaceId, PathBuf>,
+    /// Upper bound on blob bytes on disk; cached DHT blobs are evicted to stay under it
+    pub max_bytes: Option<u64>,
+    /// Which cached blobs to evict first when over `max_bytes`
+    pub eviction: EvictionPolicy,
+    /// Mime types accepted for uploaded blobs (`image/*` wildcards allowed);
+    /// when set, declared types are also checked against the blob's magic number
+    pub allowed_mime_types: Option<Vec<String>>,
+}
+
 /// Storage manager
 pub struct Storage {
     /// RocksDB instance
     db: DB,
     /// Blob storage directory
     blob_dir: PathBuf,
+    /// Layout and limits
+    config: StorageConfig,
+    /// Last access stamp handed out, so stamps are strictly increasing
+    access_clock: std::sync::atomic::AtomicU64,
 }
 
 impl Storage {
@@ -98,9 +125,50 @@ impl Storage {
     const CF_VECTOR_CLOCKS: &'static str = "vector_clocks";
     const CF_TOMBSTONES: &'static str = "tombstones";
     const CF_RELAYS: &'static str = "relays";
+    const CF_MESSAGE_ORIGINS: &'static str = "message_origins";
+    const CF_BLOB_SPACES: &'static str = "blob_spaces";
+    const CF_BLOB_ACCESS: &'static str = "blob_access";
+    const CF_META: &'static str = "meta";
+    const CF_PENDING_DHT_UPLOADS: &'static str = "pending_dht_uploads";
+    const CF_UNSENT_OPS: &'static str = "unsent_ops";
+
+    /// Every column family this version knows
+    const COLUMN_FAMILIES: [&'static str; 14] = [
+        Self::CF_THREAD_MESSAGES,
+        Self::CF_USER_MESSAGES,
+        Self::CF_BLOB_METADATA,
+        Self::CF_MESSAGES,
+        Self::CF_MESSAGE_REFS,
+        Self::CF_VECTOR_CLOCKS,
+        Self::CF_TOMBSTONES,
+        Self::CF_RELAYS,
+        Self::CF_MESSAGE_ORIGINS,
+        Self::CF_BLOB_SPACES,
+        Self::CF_BLOB_ACCESS,
+        Self::CF_META,
+        Self::CF_PENDING_DHT_UPLOADS,
+        Self::CF_UNSENT_OPS,
+    ];
 
     /// Open storage at the given path
     pub fn open(path: impl AsRef<Path>) -> Result<Self> {
+        Self::open_with_config(path, StorageConfig::default())
+    }
+
+    /// Open storage with a custom layout and limits
+    pub fn open_with_config(path: impl AsRef<Path>, config: StorageConfig) -> Result<Self> {
+        Self::open_with_migrations(path, config, &schema::builtin_migrations())
+    }
+
+    /// Open storage, upgrading an older schema with the given migrations
+    ///
+    /// `migrations` replaces the built-in list; fails if the store was written
+    /// by a newer schema or a step has no migration.
+    pub fn open_with_migrations(
+        path: impl AsRef<Path>,
+        config: StorageConfig,
+        migrations: &[Box<dyn Migration>],
+    ) -> Result<Self> {
         let path = path.as_ref();
         
         // Create directory structure
@@ -118,24 +186,37 @@ impl Storage {
         opts.create_missing_column_families(true);
 
         // Define column families
-        let cfs = vec![
-            ColumnFamilyDescriptor::new(Self::CF_THREAD_MESSAGES, Options::default()),
-            ColumnFamilyDescriptor::new(Self::CF_USER_MESSAGES, Options::default()),
-            ColumnFamilyDescriptor::new(Self::CF_BLOB_METADATA, Options::default()),
-            ColumnFamilyDescriptor::new(Self::CF_MESSAGES, Options::default()),
-            ColumnFamilyDescriptor::new(Self::CF_MESSAGE_REFS, Options::default()),
-            ColumnFamilyDescriptor::new(Self::CF_VECTOR_CLOCKS, Options::default()),
-            ColumnFamilyDescriptor::new(Self::CF_TOMBSTONES, Options::default()),
-            ColumnFamilyDescriptor::new(Self::CF_RELAYS, Options::default()),
-        ];
+        let mut cf_names: Vec<String> = Self::COLUMN_FAMILIES.iter().map(|name| name.to_string()).collect();
+
+        // A newer schema may have column families we don't know; open them
+        // anyway so the version check below can report the mismatch
+        for name in DB::list_cf(&opts, &db_path).unwrap_or_default() {
+            if !cf_names.contains(&name) {
+                cf_names.push(name);
+            }
+        }
+
+        let cfs = cf_names.iter()
+            .map(|name| ColumnFamilyDescriptor::new(name, Options::default()));
 
         // Open database
         let db = DB::open_cf_descriptors(&opts, &db_path, cfs)
             .context("Failed to open RocksDB")?;
 
+        // Data without a version stamp means a store from before versioning
+        let existing = db.iterator(rocksdb::IteratorMode::Start).next().is_some()
+            || cf_names.iter().any(|name| {
+                db.cf_handle(name)
+                    .is_some_and(|cf| db.iterator_cf(&cf, rocksdb::IteratorMode::Start).next().is_some())
+            });
+
+        schema::migrate(&db, Self::CF_META, existing, migrations)?;
+
         Ok(Self {
             db,
             blob_dir,
+            config,
+            access_clock: std::sync::atomic::AtomicU64::new(0),
         })
     }
 
@@ -148,16 +229,143 @@ impl Storage {
         // Write to file in blob directory
         let blob_path = self.blob_dir.join(hash.to_hex());
         encrypted.write_to_file(&blob_path)?;
+        self.enforce_quota()?;
         
         Ok(hash)
     }
     
+    /// Store an encrypted blob in a Space's blob directory and return its hash
+    pub fn store_space_blob(&self, space_id: &SpaceId, data: &[u8], key: &[u8; 32]) -> Result<BlobHash> {
+        let encrypted = EncryptedBlob::encrypt(data, key)?;
+        let hash = BlobHash::hash(data);
+        self.put_space_blob(space_id, &hash, &encrypted)?;
+        self.enforce_quota()?;
+        Ok(hash)
+    }
+
+    /// Write an already-encrypted blob into a Space's blob directory
+    pub fn put_space_blob(&self, space_id: &SpaceId, hash: &BlobHash, blob: &EncryptedBlob) -> Result<()> {
+        let dir = self.space_blob_dir(space_id);
+        fs::create_dir_all(&dir)
+            .context("Failed to create Space blob directory")?;
+        blob.write_to_file(&dir.join(hash.to_hex()))?;
+
+        let cf = self.db.cf_handle(Self::CF_BLOB_SPACES)
+            .ok_or_else(|| anyhow!("CF_BLOB_SPACES not found"))?;
+        self.db.put_cf(&cf, hash.to_hex().as_bytes(), space_id.0)?;
+        Ok(())
+    }
+
     /// Load and decrypt a blob by hash
     pub fn load_blob(&self, hash: &BlobHash, key: &[u8; 32]) -> Result<Vec<u8>> {
-        let blob_path = self.blob_dir.join(hash.to_hex());
-        let encrypted = EncryptedBlob::read_from_file(&blob_path)?;
+        let encrypted = EncryptedBlob::read_from_file(&self.blob_path(hash)?)?;
+        self.touch_cached_blob(hash)?;
         encrypted.decrypt(key)
     }
+
+    /// Whether a blob's encrypted file is on disk
+    pub fn has_blob(&self, hash: &BlobHash) -> Result<bool> {
+        Ok(self.blob_path(hash)?.is_file())
+    }
+
+    /// Path of a blob's file: its Space's directory if it has one, else the shared directory
+    pub fn blob_path(&self, hash: &BlobHash) -> Result<PathBuf> {
+        let cf = self.db.cf_handle(Self::CF_BLOB_SPACES)
+            .ok_or_else(|| anyhow!("CF_BLOB_SPACES not found"))?;
+
+        let dir = match self.db.get_cf(&cf, hash.to_hex().as_bytes())? {
+            Some(bytes) => {
+                let space_id: [u8; 32] = bytes.as_slice().try_into()
+                    .map_err(|_| anyhow!("Invalid Space ID in blob index"))?;
+                self.space_blob_dir(&SpaceId(space_id))
+            }
+            None => self.blob_dir.clone(),
+        };
+        Ok(dir.join(hash.to_hex()))
+    }
+
+    /// Blob directory for a Space (configured path, or `blobs/spaces/<space id>`)
+    pub fn space_blob_dir(&self, space_id: &SpaceId) -> PathBuf {
+        self.config.space_blob_dirs.get(space_id)
+            .cloned()
+            .unwrap_or_else(|| self.blob_dir.join("spaces").join(hex::encode(space_id.0)))
+    }
+
+    /// Delete everything stored for one Space
+    ///
+    /// Removes the Space's blobs and their metadata, plus the message indices
+    /// and origins of the given threads and messages. Blobs in the shared
+    /// directory and other Spaces' data are left alone.
+    pub fn purge_space(&self, space_id: &SpaceId, thread_ids: &[ThreadId], message_ids: &[MessageId]) -> Result<()> {
+        let blob_spaces_cf = self.db.cf_handle(Self::CF_BLOB_SPACES)
+            .ok_or_else(|| anyhow!("CF_BLOB_SPACES not found"))?;
+        let metadata_cf = self.db.cf_handle(Self::CF_BLOB_METADATA)
+            .ok_or_else(|| anyhow!("CF_BLOB_METADATA not found"))?;
+        let thread_cf = self.db.cf_handle(Self::CF_THREAD_MESSAGES)
+            .ok_or_else(|| anyhow!("CF_THREAD_MESSAGES not found"))?;
+        let user_cf = self.db.cf_handle(Self::CF_USER_MESSAGES)
+            .ok_or_else(|| anyhow!("CF_USER_MESSAGES not found"))?;
+        let msg_cf = self.db.cf_handle(Self::CF_MESSAGES)
+            .ok_or_else(|| anyhow!("CF_MESSAGES not found"))?;
+        let origin_cf = self.db.cf_handle(Self::CF_MESSAGE_ORIGINS)
+            .ok_or_else(|| anyhow!("CF_MESSAGE_ORIGINS not found"))?;
+        let access_cf = self.db.cf_handle(Self::CF_BLOB_ACCESS)
+            .ok_or_else(|| anyhow!("CF_BLOB_ACCESS not found"))?;
+
+        let mut batch = rocksdb::WriteBatch::default();
+
+        // Blobs owned by this Space
+        let dir = self.space_blob_dir(space_id);
+        for item in self.db.iterator_cf(&blob_spaces_cf, rocksdb::IteratorMode::Start) {
+            let (key, value) = item?;
+            if value.as_ref() != space_id.0 {
+                continue;
+            }
+            let path = dir.join(String::from_utf8_lossy(&key).as_ref());
+            if path.exists() {
+                fs::remove_file(&path).context("Failed to remove Space blob")?;
+            }
+            batch.delete_cf(&blob_spaces_cf, &key);
+            batch.delete_cf(&metadata_cf, &key);
+            batch.delete_cf(&access_cf, &key);
+        }
+
+        // Thread and user message indices
+        for thread_id in thread_ids {
+            let prefix = thread_id.as_bytes();
+            for item in self.db.prefix_iterator_cf(&thread_cf, prefix) {
+                let (key, value) = item?;
+                if !key.starts_with(prefix) {
+                    break;
+                }
+                let index: MessageIndex = bincode::deserialize(&value)?;
+                let mut user_key = Vec::new();
+                user_key.extend_from_slice(index.author.as_bytes());
+                user_key.extend_from_slice(&index.timestamp.to_be_bytes());
+                user_key.extend_from_slice(index.message_id.as_bytes());
+                batch.delete_cf(&user_cf, user_key);
+                batch.delete_cf(&msg_cf, index.message_id.as_bytes());
+                batch.delete_cf(&thread_cf, &key);
+            }
+        }
+
+        for message_id in message_ids {
+            batch.delete_cf(&msg_cf, message_id.as_bytes());
+            batch.delete_cf(&origin_cf, message_id.as_bytes());
+        }
+
+        self.db.write(batch)?;
+
+        // The default directory belongs to this Space alone; a configured one
+        // may be shared with other files, so only drop it if it is now empty
+        if self.config.space_blob_dirs.contains_key(space_id) {
+            let _ = fs::remove_dir(&dir);
+        } else if dir.exists() {
+            fs::remove_dir_all(&dir).context("Failed to remove Space blob directory")?;
+        }
+
+        Ok(())
+    }
     
     /// Store metadata for a blob
     pub fn store_blob_metadata(&self, hash: &BlobHash, metadata: &BlobMetadata) -> Result<()> {
@@ -199,6 +407,149 @@ impl Storage {
         }
     }
     
+    /// Overwrite a blob's file with zeros, remove it and forget its metadata
+    pub fn purge_blob(&self, hash: &BlobHash) -> Result<()> {
+        let path = self.blob_path(hash)?;
+        if path.exists() {
+            let len = fs::metadata(&path)?.len() as usize;
+            fs::write(&path, vec![0u8; len]).context("Failed to overwrite blob")?;
+            fs::remove_file(&path).context("Failed to remove blob")?;
+        }
+
+        let key = hash.to_hex();
+        for name in [Self::CF_BLOB_SPACES, Self::CF_BLOB_METADATA, Self::CF_BLOB_ACCESS] {
+            let cf = self.db.cf_handle(name)
+                .ok_or_else(|| anyhow!("{} not found", name))?;
+            self.db.delete_cf(&cf, key.as_bytes())?;
+        }
+        Ok(())
+    }
+
+    /// Remove a redacted message's blob, indices and origin
+    pub fn purge_message(&self, thread_id: &ThreadId, message_id: &MessageId) -> Result<()> {
+        if let Some(hash) = self.get_message_blob(message_id)? {
+            self.purge_blob(&hash)?;
+        }
+
+        let thread_cf = self.db.cf_handle(Self::CF_THREAD_MESSAGES)
+            .ok_or_else(|| anyhow!("CF_THREAD_MESSAGES not found"))?;
+        let user_cf = self.db.cf_handle(Self::CF_USER_MESSAGES)
+            .ok_or_else(|| anyhow!("CF_USER_MESSAGES not found"))?;
+        let msg_cf = self.db.cf_handle(Self::CF_MESSAGES)
+            .ok_or_else(|| anyhow!("CF_MESSAGES not found"))?;
+        let origin_cf = self.db.cf_handle(Self::CF_MESSAGE_ORIGINS)
+            .ok_or_else(|| anyhow!("CF_MESSAGE_ORIGINS not found"))?;
+
+        let mut batch = rocksdb::WriteBatch::default();
+        let prefix = thread_id.as_bytes();
+        for item in self.db.prefix_iterator_cf(&thread_cf, prefix) {
+            let (key, value) = item?;
+            if !key.starts_with(prefix) {
+                break;
+            }
+            if !key.ends_with(message_id.as_bytes()) {
+                continue;
+            }
+            let index: MessageIndex = bincode::deserialize(&value)?;
+            let mut user_key = Vec::new();
+            user_key.extend_from_slice(index.author.as_bytes());
+            user_key.extend_from_slice(&index.timestamp.to_be_bytes());
+            user_key.extend_from_slice(index.message_id.as_bytes());
+            batch.delete_cf(&user_cf, user_key);
+            batch.delete_cf(&thread_cf, &key);
+        }
+        batch.delete_cf(&msg_cf, message_id.as_bytes());
+        batch.delete_cf(&origin_cf, message_id.as_bytes());
+        self.db.write(batch)?;
+
+        Ok(())
+    }
+    
+    /// Record the original attribution of an imported message
+    pub fn store_message_origin(&self, message_id: &MessageId, origin: &crate::export::MessageOrigin) -> Result<()> {
+        let cf = self.db.cf_handle(Self::CF_MESSAGE_ORIGINS)
+            .ok_or_else(|| anyhow::anyhow!("CF_MESSAGE_ORIGINS not found"))?;
+        
+        let value = bincode::serialize(origin)?;
+        self.db.put_cf(&cf, message_id.as_bytes(), &value)?;
+        Ok(())
+    }
+    
+    /// Get the original attribution of an imported message
+    pub fn get_message_origin(&self, message_id: &MessageId) -> Result<Option<crate::export::MessageOrigin>> {
+        let cf = self.db.cf_handle(Self::CF_MESSAGE_ORIGINS)
+            .ok_or_else(|| anyhow::anyhow!("CF_MESSAGE_ORIGINS not found"))?;
+        
+        match self.db.get_cf(&cf, message_id.as_bytes())? {
+            Some(bytes) => Ok(Some(bincode::deserialize(&bytes)?)),
+            None => Ok(None),
+        }
+    }
+    
+    /// Queue an operation whose DHT upload failed, to be retried later
+    pub fn queue_pending_upload(&self, op: &crate::crdt::CrdtOp) -> Result<()> {
+        self.queue_op(Self::CF_PENDING_DHT_UPLOADS, op)
+    }
+    
+    /// Drop an operation from the upload queue once it reached the DHT
+    pub fn remove_pending_upload(&self, op_id: &crate::types::OpId) -> Result<()> {
+        self.dequeue_op(Self::CF_PENDING_DHT_UPLOADS, op_id)
+    }
+    
+    /// Operations still waiting for a DHT upload, oldest (by HLC) first
+    pub fn pending_uploads(&self) -> Result<Vec<crate::crdt::CrdtOp>> {
+        self.queued_ops(Self::CF_PENDING_DHT_UPLOADS)
+    }
+    
+    /// Queue an operation that could not be published (no peers), to be resent
+    pub fn queue_unsent_op(&self, op: &crate::crdt::CrdtOp) -> Result<()> {
+        self.queue_op(Self::CF_UNSENT_OPS, op)
+    }
+    
+    /// Drop an operation from the send queue once it was published
+    pub fn remove_unsent_op(&self, op_id: &crate::types::OpId) -> Result<()> {
+        self.dequeue_op(Self::CF_UNSENT_OPS, op_id)
+    }
+    
+    /// Operations still waiting to be published, oldest (by HLC) first
+    pub fn unsent_ops(&self) -> Result<Vec<crate::crdt::CrdtOp>> {
+        self.queued_ops(Self::CF_UNSENT_OPS)
+    }
+    
+    /// Store an operation in an op queue, keyed by op ID
+    fn queue_op(&self, cf_name: &str, op: &crate::crdt::CrdtOp) -> Result<()> {
+        let cf = self.db.cf_handle(cf_name)
+            .ok_or_else(|| anyhow!("{} not found", cf_name))?;
+        
+        let value = minicbor::to_vec(op)
+            .map_err(|e| anyhow!("Failed to encode operation: {}", e))?;
+        self.db.put_cf(&cf, op.op_id.0.as_bytes(), &value)?;
+        Ok(())
+    }
+    
+    fn dequeue_op(&self, cf_name: &str, op_id: &crate::types::OpId) -> Result<()> {
+        let cf = self.db.cf_handle(cf_name)
+            .ok_or_else(|| anyhow!("{} not found", cf_name))?;
+        
+        self.db.delete_cf(&cf, op_id.0.as_bytes())?;
+        Ok(())
+    }
+    
+    fn queued_ops(&self, cf_name: &str) -> Result<Vec<crate::crdt::CrdtOp>> {
+        let cf = self.db.cf_handle(cf_name)
+            .ok_or_else(|| anyhow!("{} not found", cf_name))?;
+        
+        let mut ops = Vec::new();
+        for item in self.db.iterator_cf(&cf, rocksdb::IteratorMode::Start) {
+            let (_, value) = item?;
+            let op: crate::crdt::CrdtOp = minicbor::decode(&value)
+                .map_err(|e| anyhow!("Failed to decode queued operation: {}", e))?;
+            ops.push(op);
+        }
+        ops.sort_by_key(|op| op.hlc);
+        Ok(ops)
+    }
+    
     /// Index a message in thread and user message indices
     pub fn index_message(&self, index: &MessageIndex) -> Result<()> {
         // Store in thread messages index
@@ -254,6 +605,70 @@ impl Storage {
         Ok(messages)
     }
 
+    /// Get a user's messages across all threads, newest first
+    ///
+    /// With `before`, only messages with an earlier timestamp are returned,
+    /// so passing the oldest timestamp of one page fetches the next.
+    pub fn get_user_messages(&self, user_id: &UserId, limit: usize, before: Option<u64>) -> Result<Vec<MessageIndex>> {
+        let cf = self.db.cf_handle(Self::CF_USER_MESSAGES)
+            .ok_or_else(|| anyhow::anyhow!("CF_USER_MESSAGES not found"))?;
+
+        // Key: user_id || timestamp || message_id; seek to the last key below
+        // the bound and walk backwards
+        let prefix = user_id.as_bytes();
+        let mut start = prefix.to_vec();
+        match before {
+            Some(timestamp) => start.extend_from_slice(&timestamp.to_be_bytes()),
+            None => start.extend_from_slice(&[0xff; 8 + 32]),
+        }
+
+        let iter = self.db.iterator_cf(&cf, rocksdb::IteratorMode::From(&start, rocksdb::Direction::Reverse));
+        let mut messages = Vec::new();
+        for item in iter {
+            if messages.len() >= limit {
+                break;
+            }
+            let (key, value) = item?;
+            if !key.starts_with(prefix) {
+                break;
+            }
+            messages.push(bincode::deserialize(&value)?);
+        }
+
+        Ok(messages)
+    }
+
+    /// Write memtables to disk and sync the WAL
+    ///
+    /// Writes are durable once they reach the WAL anyway; flushing makes them
+    /// land in SST files, so a reopened store doesn't have to replay the log.
+    pub fn flush(&self) -> Result<()> {
+        self.db.flush().context("Failed to flush default column family")?;
+        for name in Self::COLUMN_FAMILIES {
+            let cf = self.db.cf_handle(name)
+                .ok_or_else(|| anyhow!("{} not found", name))?;
+            self.db.flush_cf(&cf).with_context(|| format!("Failed to flush {}", name))?;
+        }
+        self.db.flush_wal(true).context("Failed to sync WAL")?;
+        Ok(())
+    }
+
+    /// Compact every column family, dropping deleted and overwritten entries
+    pub fn compact(&self) -> Result<()> {
+        self.db.compact_range(None::<&[u8]>, None::<&[u8]>);
+        for name in Self::COLUMN_FAMILIES {
+            let cf = self.db.cf_handle(name)
+                .ok_or_else(|| anyhow!("{} not found", name))?;
+            self.db.compact_range_cf(&cf, None::<&[u8]>, None::<&[u8]>);
+        }
+        Ok(())
+    }
+
+    /// Schema version of the open database
+    pub fn schema_version(&self) -> Result<u32> {
+        Ok(schema::read_version(&self.db, Self::CF_META)?.unwrap_or(SCHEMA_VERSION))
+    }
+
     /// Get the blob directory path
     pub fn blob_dir(&self) -> &Path {
         &self.blob_dir
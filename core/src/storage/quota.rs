@@ -0,0 +1,145 @@
+//! Storage quota for cached blobs
+//!
+//! Blobs fetched from the DHT are a local cache: they are recorded in the
+//! `blob_access` column family with when they were cached and last read, and
+//! evicted by [`EvictionPolicy`] once blob files exceed
+//! [`StorageConfig::max_bytes`](super::StorageConfig::max_bytes). Blobs this
+//! client authored are never in that column family, so they are never evicted.
+
+use super::{BlobHash, EncryptedBlob, Storage};
+use crate::types::SpaceId;
+use anyhow::{Context, Result, anyhow};
+use serde::{Serialize, Deserialize};
+use std::fs;
+use std::sync::atomic::Ordering;
+
+/// Order in which cached blobs are evicted when over quota
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum EvictionPolicy {
+    /// Evict the blob read least recently
+    #[default]
+    LeastRecentlyUsed,
+    /// Evict the blob cached earliest, regardless of reads
+    OldestFirst,
+}
+
+/// Access record for a cached blob
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+struct BlobAccess {
+    /// Stamp when the blob was cached
+    cached_at: u64,
+    /// Stamp of the last read
+    last_access: u64,
+}
+
+impl Storage {
+    /// Cache a blob fetched from the DHT in a Space's directory, evicting
+    /// older cached blobs if this puts storage over quota
+    pub fn cache_space_blob(&self, space_id: &SpaceId, hash: &BlobHash, blob: &EncryptedBlob) -> Result<()> {
+        self.put_space_blob(space_id, hash, blob)?;
+
+        let stamp = self.next_access_stamp();
+        self.put_access(hash, &BlobAccess { cached_at: stamp, last_access: stamp })?;
+
+        self.enforce_quota()?;
+        Ok(())
+    }
+
+    /// Whether a blob is an evictable cache entry (rather than authored content)
+    pub fn is_cached_blob(&self, hash: &BlobHash) -> Result<bool> {
+        Ok(self.get_access(hash)?.is_some())
+    }
+
+    /// Evict cached blobs until blob files fit in `max_bytes`, returning the evicted hashes
+    pub fn enforce_quota(&self) -> Result<Vec<BlobHash>> {
+        let Some(max_bytes) = self.config.max_bytes else {
+            return Ok(Vec::new());
+        };
+
+        let mut used = self.stats()?.total_blob_bytes;
+        if used <= max_bytes {
+            return Ok(Vec::new());
+        }
+
+        let cf = self.db.cf_handle(Self::CF_BLOB_ACCESS)
+            .ok_or_else(|| anyhow!("CF_BLOB_ACCESS not found"))?;
+
+        let mut candidates = Vec::new();
+        for item in self.db.iterator_cf(&cf, rocksdb::IteratorMode::Start) {
+            let (key, value) = item?;
+            let hash = BlobHash::from_hex(&String::from_utf8_lossy(&key))?;
+            let access: BlobAccess = bincode::deserialize(&value)
+                .context("Failed to deserialize blob access")?;
+            let order = match self.config.eviction {
+                EvictionPolicy::LeastRecentlyUsed => access.last_access,
+                EvictionPolicy::OldestFirst => access.cached_at,
+            };
+            candidates.push((order, hash));
+        }
+        candidates.sort_by_key(|(order, _)| *order);
+
+        let mut evicted = Vec::new();
+        for (_, hash) in candidates {
+            if used <= max_bytes {
+                break;
+            }
+
+            let path = self.blob_path(&hash)?;
+            let size = fs::metadata(&path).map(|m| m.len()).unwrap_or(0);
+            if path.exists() {
+                fs::remove_file(&path).context("Failed to evict cached blob")?;
+            }
+
+            let spaces_cf = self.db.cf_handle(Self::CF_BLOB_SPACES)
+                .ok_or_else(|| anyhow!("CF_BLOB_SPACES not found"))?;
+            self.db.delete_cf(&spaces_cf, hash.to_hex().as_bytes())?;
+            self.db.delete_cf(&cf, hash.to_hex().as_bytes())?;
+
+            tracing::debug!(hash = %hash.to_hex(), size, "Evicted cached blob");
+            used = used.saturating_sub(size);
+            evicted.push(hash);
+        }
+
+        Ok(evicted)
+    }
+
+    /// Record a read of a cached blob (no-op for authored blobs)
+    pub(crate) fn touch_cached_blob(&self, hash: &BlobHash) -> Result<()> {
+        if let Some(mut access) = self.get_access(hash)? {
+            access.last_access = self.next_access_stamp();
+            self.put_access(hash, &access)?;
+        }
+        Ok(())
+    }
+
+    /// Strictly increasing stamp based on wall-clock nanoseconds
+    fn next_access_stamp(&self) -> u64 {
+        let now = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_nanos() as u64;
+        let previous = self.access_clock
+            .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |last| Some(now.max(last + 1)))
+            .unwrap_or(0);
+        now.max(previous + 1)
+    }
+
+    fn get_access(&self, hash: &BlobHash) -> Result<Option<BlobAccess>> {
+        let cf = self.db.cf_handle(Self::CF_BLOB_ACCESS)
+            .ok_or_else(|| anyhow!("CF_BLOB_ACCESS not found"))?;
+
+        match self.db.get_cf(&cf, hash.to_hex().as_bytes())? {
+            Some(bytes) => Ok(Some(bincode::deserialize(&bytes)
+                .context("Failed to deserialize blob access")?)),
+            None => Ok(None),
+        }
+    }
+
+    fn put_access(&self, hash: &BlobHash, access: &BlobAccess) -> Result<()> {
+        let cf = self.db.cf_handle(Self::CF_BLOB_ACCESS)
+            .ok_or_else(|| anyhow!("CF_BLOB_ACCESS not found"))?;
+
+        self.db.put_cf(&cf, hash.to_hex().as_bytes(), bincode::serialize(access)?)?;
+        Ok(())
+    }
+}
@@ -0,0 +1,110 @@
+//! Schema versioning and migrations
+//!
+//! The storage database records its schema version in the `meta` column
+//! family. On open, older databases are upgraded one version at a time by
+//! the registered [`Migration`]s, and databases written by a newer release
+//! are refused rather than misread.
+
+use anyhow::{Result, anyhow, bail};
+use rocksdb::DB;
+
+/// Schema version written by this release
+pub const SCHEMA_VERSION: u32 = 1;
+
+/// Version assumed for databases created before versioning existed
+pub const UNVERSIONED: u32 = 0;
+
+/// Key of the schema version in the `meta` column family
+const VERSION_KEY: &[u8] = b"schema_version";
+
+/// One upgrade step, from `from_version()` to `from_version() + 1`
+pub trait Migration: Send + Sync {
+    /// Schema version this migration upgrades from
+    fn from_version(&self) -> u32;
+
+    /// Short description for logs
+    fn description(&self) -> &str;
+
+    /// Rewrite the database in place (all column families are open)
+    fn migrate(&self, db: &DB) -> Result<()>;
+}
+
+/// Unversioned stores have the same layout as version 1; column families
+/// added since are created on open, so only the version stamp is missing
+struct StampUnversioned;
+
+impl Migration for StampUnversioned {
+    fn from_version(&self) -> u32 {
+        UNVERSIONED
+    }
+
+    fn description(&self) -> &str {
+        "stamp unversioned store"
+    }
+
+    fn migrate(&self, _db: &DB) -> Result<()> {
+        Ok(())
+    }
+}
+
+/// Migrations shipped with this release, in any order
+pub fn builtin_migrations() -> Vec<Box<dyn Migration>> {
+    vec![Box::new(StampUnversioned)]
+}
+
+/// Read the stored schema version, if any
+pub(crate) fn read_version(db: &DB, meta_cf: &str) -> Result<Option<u32>> {
+    let cf = db.cf_handle(meta_cf)
+        .ok_or_else(|| anyhow!("Missing {} column family", meta_cf))?;
+
+    match db.get_cf(&cf, VERSION_KEY)? {
+        Some(bytes) => {
+            let bytes: [u8; 4] = bytes.as_slice().try_into()
+                .map_err(|_| anyhow!("Invalid schema version record"))?;
+            Ok(Some(u32::from_be_bytes(bytes)))
+        }
+        None => Ok(None),
+    }
+}
+
+fn write_version(db: &DB, meta_cf: &str, version: u32) -> Result<()> {
+    let cf = db.cf_handle(meta_cf)
+        .ok_or_else(|| anyhow!("Missing {} column family", meta_cf))?;
+    db.put_cf(&cf, VERSION_KEY, version.to_be_bytes())?;
+    Ok(())
+}
+
+/// Bring the database to [`SCHEMA_VERSION`]
+///
+/// `existing` tells a brand-new database (stamped directly) from one created
+/// before versioning (treated as [`UNVERSIONED`]). The version is stamped
+/// after each step, so an interrupted upgrade resumes where it stopped.
+pub(crate) fn migrate(db: &DB, meta_cf: &str, existing: bool, migrations: &[Box<dyn Migration>]) -> Result<()> {
+    let mut version = match read_version(db, meta_cf)? {
+        Some(version) => version,
+        None if existing => UNVERSIONED,
+        None => SCHEMA_VERSION,
+    };
+
+    if version > SCHEMA_VERSION {
+        bail!(
+            "Storage schema version {} is newer than the supported version {}; upgrade to open this store",
+            version,
+            SCHEMA_VERSION
+        );
+    }
+
+    while version < SCHEMA_VERSION {
+        let migration = migrations.iter()
+            .find(|m| m.from_version() == version)
+            .ok_or_else(|| anyhow!("No migration registered from storage schema version {}", version))?;
+
+        migration.migrate(db)?;
+        version += 1;
+        write_version(db, meta_cf, version)?;
+
+        tracing::info!(version, migration = migration.description(), "Migrated storage schema");
+    }
+
+    write_version(db, meta_cf, version)
+}
@@ -0,0 +1,81 @@
+//! Storage usage statistics
+//! 
+//! Sizes blobs from the files on disk and counts index entries by iterating
+//! column families, for quota UIs and garbage collection.
+
+use super::{BlobHash, Storage, TombstoneSet};
+use crate::types::SpaceId;
+use anyhow::{Context, Result};
+use std::collections::HashMap;
+use std::fs;
+
+/// Disk usage of a client's local storage
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct StorageStats {
+    /// Bytes of encrypted blob files, across the shared and per-Space directories
+    pub total_blob_bytes: u64,
+    /// Number of blob files
+    pub blob_count: u64,
+    /// Number of stored CRDT operations
+    pub op_count: u64,
+    /// Number of deleted-message tombstones
+    pub tombstone_count: u64,
+    /// Bytes attributable to each Space (its blobs and stored operations)
+    pub per_space_bytes: HashMap<SpaceId, u64>,
+}
+
+impl StorageStats {
+    /// Account for operations stored for a Space
+    pub fn add_ops(&mut self, space_id: SpaceId, count: u64, bytes: u64) {
+        self.op_count += count;
+        *self.per_space_bytes.entry(space_id).or_default() += bytes;
+    }
+}
+
+impl Storage {
+    /// Compute blob and tombstone usage
+    /// 
+    /// Operations live in the [`Store`](super::Store); see
+    /// [`Store::space_op_usage`](super::Store::space_op_usage) for those.
+    pub fn stats(&self) -> Result<StorageStats> {
+        let mut stats = StorageStats::default();
+
+        // Blobs in the shared directory (per-Space blobs live in subdirectories)
+        for entry in fs::read_dir(&self.blob_dir).context("Failed to read blob directory")? {
+            let metadata = entry?.metadata()?;
+            if metadata.is_file() {
+                stats.total_blob_bytes += metadata.len();
+                stats.blob_count += 1;
+            }
+        }
+
+        // Blobs owned by a Space, wherever their directory is
+        let blob_spaces_cf = self.db.cf_handle(Self::CF_BLOB_SPACES)
+            .context("Missing blob_spaces column family")?;
+        for item in self.db.iterator_cf(&blob_spaces_cf, rocksdb::IteratorMode::Start) {
+            let (key, value) = item?;
+            let space_id: [u8; 32] = value.as_ref().try_into()
+                .context("Invalid Space ID in blob index")?;
+            let space_id = SpaceId(space_id);
+            let hash = BlobHash::from_hex(&String::from_utf8_lossy(&key))?;
+
+            let Ok(metadata) = fs::metadata(self.space_blob_dir(&space_id).join(hash.to_hex())) else {
+                continue;
+            };
+            stats.total_blob_bytes += metadata.len();
+            stats.blob_count += 1;
+            *stats.per_space_bytes.entry(space_id).or_default() += metadata.len();
+        }
+
+        let tombstones_cf = self.db.cf_handle(Self::CF_TOMBSTONES)
+            .context("Missing tombstones column family")?;
+        for item in self.db.iterator_cf(&tombstones_cf, rocksdb::IteratorMode::Start) {
+            let (_, value) = item?;
+            let tombstones: TombstoneSet = bincode::deserialize(&value)
+                .context("Failed to deserialize tombstones")?;
+            stats.tombstone_count += tombstones.tombstones.len() as u64;
+        }
+
+        Ok(stats)
+    }
+}
@@ -4,7 +4,12 @@ use crate::{Error, Result};
 use crate::types::*;
 use crate::crdt::CrdtOp;
 use rocksdb::{DB, Options, IteratorMode};
+use std::collections::{HashMap, HashSet};
 use std::path::Path;
+use uuid::Uuid;
+
+/// Marks a database whose canonical order index covers every stored op
+const ORDER_INDEX_MARKER: &[u8] = b"meta:order_index";
 
 /// Main storage interface
 pub struct Store {
@@ -21,7 +26,21 @@ impl Store {
         let db = DB::open(&opts, path)
             .map_err(|e| Error::Storage(format!("Failed to open database: {}", e)))?;
         
-        Ok(Self { db })
+        let store = Self { db };
+        store.backfill_order_index()?;
+        Ok(store)
+    }
+
+    /// Write memtables to disk and sync the WAL
+    pub fn flush(&self) -> Result<()> {
+        self.db.flush()
+            .and_then(|_| self.db.flush_wal(true))
+            .map_err(|e| Error::Storage(format!("Failed to flush database: {}", e)))
+    }
+
+    /// Compact the whole database, dropping deleted and overwritten entries
+    pub fn compact(&self) {
+        self.db.compact_range(None::<&[u8]>, None::<&[u8]>);
     }
 
     /// Store a CRDT operation
@@ -42,6 +61,11 @@ impl Store {
             .put(&space_key, &value)
             .map_err(|e| Error::Storage(format!("Failed to store op by space: {}", e)))?;
         
+        // And in canonical order; the op itself is looked up by id
+        self.db
+            .put(self.order_key(op), [])
+            .map_err(|e| Error::Storage(format!("Failed to index op order: {}", e)))?;
+        
         Ok(())
     }
 
@@ -84,6 +108,189 @@ impl Store {
         Ok(ops)
     }
 
+    /// Iterate a space's operations in canonical order: by HLC, then op ID
+    /// 
+    /// Every replica that holds the same ops yields them in the same order,
+    /// which is what replay, export and reconciliation rely on. Ops are read
+    /// lazily from the index as the iterator advances.
+    pub fn iter_space_ops_ordered(&self, space_id: &SpaceId) -> impl Iterator<Item = Result<CrdtOp>> + '_ {
+        let prefix = self.order_prefix(space_id);
+        
+        self.db
+            .iterator(IteratorMode::From(&prefix, rocksdb::Direction::Forward))
+            .map(|item| item.map_err(|e| Error::Storage(format!("Iterator error: {}", e))))
+            .take_while(move |item| match item {
+                Ok((key, _)) => key.starts_with(&prefix),
+                Err(_) => true,
+            })
+            .map(|item| {
+                let (key, _) = item?;
+                let op_id = Uuid::from_slice(&key[key.len() - 16..])
+                    .map_err(|e| Error::Storage(format!("Corrupt order index key: {}", e)))?;
+                self.get_op(&OpId(op_id))?
+                    .ok_or_else(|| Error::NotFound(format!("Indexed op {} is missing", op_id)))
+            })
+    }
+
+    /// The latest `limit` operations of each space, newest first within a space
+    /// 
+    /// Walks the order index backwards, so only `limit` ops per space are
+    /// read however many are stored.
+    pub fn latest_ops_per_space(&self, limit: usize) -> Result<Vec<CrdtOp>> {
+        // ';' follows ':', so this seeks to the last key of the order index
+        const ORDER_END: &[u8] = b"order;";
+        let prefix_len = self.order_prefix(&SpaceId([0; 32])).len();
+        
+        let mut ops = Vec::new();
+        let mut iter = self.db.raw_iterator();
+        iter.seek_for_prev(ORDER_END);
+        while let Some(key) = iter.key() {
+            if !key.starts_with(b"order:") || key.len() < prefix_len + 16 {
+                break;
+            }
+            let space_prefix = key[..prefix_len].to_vec();
+            
+            let mut taken = 0;
+            while taken < limit {
+                let Some(key) = iter.key().filter(|key| key.starts_with(&space_prefix)) else {
+                    break;
+                };
+                let op_id = Uuid::from_slice(&key[key.len() - 16..])
+                    .map_err(|e| Error::Storage(format!("Corrupt order index key: {}", e)))?;
+                ops.push(self.get_op(&OpId(op_id))?
+                    .ok_or_else(|| Error::NotFound(format!("Indexed op {} is missing", op_id)))?);
+                taken += 1;
+                iter.prev();
+            }
+            
+            // Every key of this space sorts after its bare prefix
+            iter.seek_for_prev(&space_prefix);
+        }
+        iter.status().map_err(|e| Error::Storage(format!("Iterator error: {}", e)))?;
+        
+        Ok(ops)
+    }
+
+    /// Get every stored operation, across all spaces
+    pub fn get_all_ops(&self) -> Result<Vec<CrdtOp>> {
+        let prefix = b"op:".to_vec();
+        let mut ops = Vec::new();
+        
+        let iter = self.db.iterator(IteratorMode::From(&prefix, rocksdb::Direction::Forward));
+        
+        for item in iter {
+            let (key, value) = item
+                .map_err(|e| Error::Storage(format!("Iterator error: {}", e)))?;
+            
+            if !key.starts_with(&prefix) {
+                break;
+            }
+            
+            let op: CrdtOp = minicbor::decode(&value)
+                .map_err(|e| Error::Serialization(format!("Failed to decode op: {}", e)))?;
+            ops.push(op);
+        }
+        
+        Ok(ops)
+    }
+
+    /// Operation count and encoded bytes per space
+    pub fn space_op_usage(&self) -> Result<HashMap<SpaceId, (u64, u64)>> {
+        let prefix = b"space:".to_vec();
+        let mut usage: HashMap<SpaceId, (u64, u64)> = HashMap::new();
+        
+        let iter = self.db.iterator(IteratorMode::From(&prefix, rocksdb::Direction::Forward));
+        
+        for item in iter {
+            let (key, value) = item
+                .map_err(|e| Error::Storage(format!("Iterator error: {}", e)))?;
+            
+            if !key.starts_with(&prefix) {
+                break;
+            }
+            
+            // Key: "space:" || space_id (32 bytes) || ':' || op_id
+            let Some(space_bytes) = key.get(prefix.len()..prefix.len() + 32) else {
+                continue;
+            };
+            let mut space_id = [0u8; 32];
+            space_id.copy_from_slice(space_bytes);
+            
+            let entry = usage.entry(SpaceId(space_id)).or_default();
+            entry.0 += 1;
+            entry.1 += value.len() as u64;
+        }
+        
+        Ok(usage)
+    }
+
+    /// Delete every operation stored for a space, returning how many were removed
+    pub fn purge_space(&self, space_id: &SpaceId) -> Result<usize> {
+        let prefix = self.space_prefix(space_id);
+        let mut batch = rocksdb::WriteBatch::default();
+        let mut removed = 0;
+        
+        let iter = self.db.iterator(IteratorMode::From(&prefix, rocksdb::Direction::Forward));
+        
+        for item in iter {
+            let (key, value) = item
+                .map_err(|e| Error::Storage(format!("Iterator error: {}", e)))?;
+            
+            if !key.starts_with(&prefix) {
+                break;
+            }
+            
+            let op: CrdtOp = minicbor::decode(&value)
+                .map_err(|e| Error::Serialization(format!("Failed to decode op: {}", e)))?;
+            batch.delete(self.op_key(&op.op_id));
+            batch.delete(self.order_key(&op));
+            batch.delete(&key);
+            removed += 1;
+        }
+        
+        self.db
+            .write(batch)
+            .map_err(|e| Error::Storage(format!("Failed to purge space: {}", e)))?;
+        
+        Ok(removed)
+    }
+
+    /// Remember that the notification for a message was read
+    pub fn put_read_marker(&self, message_id: &MessageId) -> Result<()> {
+        let mut key = b"read:".to_vec();
+        key.extend_from_slice(&message_id.0);
+        
+        self.db
+            .put(&key, [])
+            .map_err(|e| Error::Storage(format!("Failed to store read marker: {}", e)))?;
+        
+        Ok(())
+    }
+
+    /// Messages whose notifications were read
+    pub fn read_markers(&self) -> Result<HashSet<MessageId>> {
+        let prefix = b"read:".to_vec();
+        let mut read = HashSet::new();
+        
+        let iter = self.db.iterator(IteratorMode::From(&prefix, rocksdb::Direction::Forward));
+        
+        for item in iter {
+            let (key, _) = item
+                .map_err(|e| Error::Storage(format!("Iterator error: {}", e)))?;
+            
+            if !key.starts_with(&prefix) {
+                break;
+            }
+            
+            let Ok(id) = <[u8; 32]>::try_from(&key[prefix.len()..]) else {
+                continue;
+            };
+            read.insert(MessageId(id));
+        }
+        
+        Ok(read)
+    }
+
     /// Store a content blob
     pub fn put_blob(&self, hash: &ContentHash, data: &[u8]) -> Result<()> {
         let key = self.blob_key(hash);
@@ -123,13 +330,49 @@ impl Store {
         key.extend_from_slice(&hash.0);
         key
     }
+
+    fn order_prefix(&self, space_id: &SpaceId) -> Vec<u8> {
+        let mut prefix = b"order:".to_vec();
+        prefix.extend_from_slice(&space_id.0);
+        prefix.push(b':');
+        prefix
+    }
+
+    /// "order:" || space_id || ':' || wall_time || logical || op_id, with the
+    /// integers big-endian so byte order matches canonical order
+    fn order_key(&self, op: &CrdtOp) -> Vec<u8> {
+        let mut key = self.order_prefix(&op.space_id);
+        key.extend_from_slice(&op.hlc.wall_time.to_be_bytes());
+        key.extend_from_slice(&op.hlc.logical.to_be_bytes());
+        key.extend_from_slice(op.op_id.0.as_bytes());
+        key
+    }
+
+    /// Index ops stored before the order index existed
+    fn backfill_order_index(&self) -> Result<()> {
+        let indexed = self.db
+            .get(ORDER_INDEX_MARKER)
+            .map_err(|e| Error::Storage(format!("Failed to read order index marker: {}", e)))?;
+        if indexed.is_some() {
+            return Ok(());
+        }
+        
+        let mut batch = rocksdb::WriteBatch::default();
+        for op in self.get_all_ops()? {
+            batch.put(self.order_key(&op), []);
+        }
+        batch.put(ORDER_INDEX_MARKER, []);
+        
+        self.db
+            .write(batch)
+            .map_err(|e| Error::Storage(format!("Failed to backfill order index: {}", e)))
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::crdt::{OpType, OpPayload, Hlc};
-    use uuid::Uuid;
     use tempfile::TempDir;
 
     #[test]
@@ -160,6 +403,74 @@ mod tests {
         assert_eq!(Some(op), retrieved);
     }
 
+    fn op_at(space_id: SpaceId, wall_time: u64, logical: u64) -> CrdtOp {
+        CrdtOp {
+            op_id: OpId(Uuid::new_v4()),
+            space_id,
+            channel_id: None,
+            thread_id: None,
+            op_type: OpType::ArchiveSpace,
+            prev_ops: vec![],
+            author: UserId([0u8; 32]),
+            epoch: EpochId(0),
+            hlc: Hlc { wall_time, logical },
+            timestamp: wall_time,
+            signature: Signature([0u8; 64]),
+        }
+    }
+
+    #[test]
+    fn test_space_ops_iterate_in_canonical_order() {
+        let temp_dir = TempDir::new().unwrap();
+        let store = Store::open(temp_dir.path()).unwrap();
+        let space_id = SpaceId::new();
+        
+        let mut tied = [op_at(space_id, 2000, 0), op_at(space_id, 2000, 0)];
+        tied.sort_by_key(|op| op.op_id.0);
+        let expected = vec![
+            op_at(space_id, 255, 0),
+            op_at(space_id, 256, 0),
+            op_at(space_id, 1000, 3),
+            tied[0].clone(),
+            tied[1].clone(),
+            op_at(space_id, 3000, 1),
+        ];
+        for index in [5, 3, 1, 0, 4, 2] {
+            store.put_op(&expected[index]).unwrap();
+        }
+        store.put_op(&op_at(SpaceId::new(), 1500, 0)).unwrap();
+        
+        let ordered: Vec<CrdtOp> = store.iter_space_ops_ordered(&space_id)
+            .collect::<Result<_>>()
+            .unwrap();
+        assert_eq!(ordered, expected);
+        
+        store.purge_space(&space_id).unwrap();
+        assert_eq!(store.iter_space_ops_ordered(&space_id).count(), 0);
+    }
+
+    #[test]
+    fn test_latest_ops_per_space_reads_the_tail_of_each_space() {
+        let temp_dir = TempDir::new().unwrap();
+        let store = Store::open(temp_dir.path()).unwrap();
+        let (first, second) = (SpaceId::new(), SpaceId::new());
+        
+        for wall_time in [300, 100, 200] {
+            store.put_op(&op_at(first, wall_time, 0)).unwrap();
+        }
+        store.put_op(&op_at(second, 50, 0)).unwrap();
+        
+        let mut latest: Vec<([u8; 32], u64)> = store.latest_ops_per_space(2).unwrap()
+            .iter()
+            .map(|op| (op.space_id.0, op.hlc.wall_time))
+            .collect();
+        latest.sort();
+        let mut expected = vec![(first.0, 200), (first.0, 300), (second.0, 50)];
+        expected.sort();
+        assert_eq!(latest, expected);
+        assert!(store.latest_ops_per_space(0).unwrap().is_empty());
+    }
+
     #[test]
     fn test_store_and_retrieve_blob() {
         let temp_dir = TempDir::new().unwrap();
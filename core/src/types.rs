@@ -10,8 +10,86 @@ use std::fmt;
 use uuid::Uuid;
 use anyhow::Result;
 
+/// Error parsing an identifier from its hex form
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum ParseIdError {
+    #[error("Invalid {kind} hex: {reason}")]
+    InvalidHex { kind: &'static str, reason: String },
+
+    #[error("Invalid {kind} length: expected {expected} bytes ({} hex chars), got {actual}", .expected * 2)]
+    WrongLength { kind: &'static str, expected: usize, actual: usize },
+}
+
+/// Implement full-hex `Display`, `from_hex`, `FromStr`, `TryFrom<&str>` and
+/// serde for a fixed-size id
+///
+/// Human-readable formats (JSON) carry the id as a full hex string; binary
+/// formats (bincode) keep the raw bytes, so stored records are unaffected.
+macro_rules! impl_hex_id {
+    ($id:ident, $kind:literal, $len:literal, $build:expr) => {
+        impl $id {
+            /// Parse from the full hex encoding
+            pub fn from_hex(s: &str) -> std::result::Result<Self, ParseIdError> {
+                let bytes = ::hex::decode(s)
+                    .map_err(|e| ParseIdError::InvalidHex { kind: $kind, reason: e.to_string() })?;
+                let array: [u8; $len] = bytes.as_slice().try_into()
+                    .map_err(|_| ParseIdError::WrongLength { kind: $kind, expected: $len, actual: bytes.len() })?;
+                Ok($build(array))
+            }
+
+            /// First 8 bytes in hex, for compact display where an
+            /// ambiguous prefix is acceptable (and for topic names)
+            pub fn short(&self) -> String {
+                ::hex::encode(&self.as_bytes()[..8])
+            }
+        }
+
+        impl fmt::Display for $id {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                write!(f, "{}", ::hex::encode(self.as_bytes()))
+            }
+        }
+
+        impl Serialize for $id {
+            fn serialize<S: serde::Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+                if serializer.is_human_readable() {
+                    serializer.serialize_str(&self.to_string())
+                } else {
+                    serializer.serialize_newtype_struct(stringify!($id), &self.0)
+                }
+            }
+        }
+
+        impl<'de> Deserialize<'de> for $id {
+            fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> std::result::Result<Self, D::Error> {
+                if deserializer.is_human_readable() {
+                    String::deserialize(deserializer)?.parse().map_err(serde::de::Error::custom)
+                } else {
+                    Ok(Self(Deserialize::deserialize(deserializer)?))
+                }
+            }
+        }
+
+        impl std::str::FromStr for $id {
+            type Err = ParseIdError;
+
+            fn from_str(s: &str) -> std::result::Result<Self, ParseIdError> {
+                Self::from_hex(s)
+            }
+        }
+
+        impl TryFrom<&str> for $id {
+            type Error = ParseIdError;
+
+            fn try_from(s: &str) -> std::result::Result<Self, ParseIdError> {
+                Self::from_hex(s)
+            }
+        }
+    };
+}
+
 /// User identity (Ed25519 public key)
-#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Encode, Decode, Serialize, Deserialize)]
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Encode, Decode)]
 #[cbor(transparent)]
 pub struct UserId(#[b(0)] pub [u8; 32]);
 
@@ -40,19 +118,13 @@ impl fmt::Debug for UserId {
     }
 }
 
-impl fmt::Display for UserId {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "{}", hex::encode(&self.0[..8]))
-    }
-}
-
 /// Device identifier
 #[derive(Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, Debug)]
 pub struct DeviceId(pub Uuid);
 
 /// Space identifier (community/server)
 /// Content-addressed: Hash(creator_pubkey || space_name || creation_timestamp)
-#[derive(Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, Debug)]
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
 pub struct SpaceId(pub [u8; 32]);
 
 impl SpaceId {
@@ -91,15 +163,9 @@ impl SpaceId {
     }
 }
 
-impl fmt::Display for SpaceId {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "space_{}", hex::encode(&self.0[..8]))
-    }
-}
-
 /// Channel identifier
 /// Content-addressed: Hash(space_id || channel_name || creator_pubkey)
-#[derive(Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, Debug)]
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
 pub struct ChannelId(pub [u8; 32]);
 
 impl ChannelId {
@@ -138,15 +204,9 @@ impl ChannelId {
     }
 }
 
-impl fmt::Display for ChannelId {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "channel_{}", hex::encode(&self.0[..8]))
-    }
-}
-
 /// Thread identifier
 /// Content-addressed: Hash(channel_id || creator_pubkey || first_message_hash || timestamp)
-#[derive(Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, Debug)]
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
 pub struct ThreadId(pub [u8; 32]);
 
 impl ThreadId {
@@ -201,12 +261,6 @@ impl ThreadId {
     }
 }
 
-impl fmt::Display for ThreadId {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "thread_{}", hex::encode(&self.0[..8]))
-    }
-}
-
 /// Post identifier (same as MessageId, but for top-level posts)
 /// Content-addressed: Hash(author || thread_id || content_hash || timestamp)
 #[derive(Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, Debug)]
@@ -269,7 +323,7 @@ impl fmt::Display for PostId {
 /// Message identifier
 /// Content-addressed: Hash(author || thread_id || content_hash || timestamp || parent_id)
 /// This ensures messages are unforgeable and self-verifying in the DHT
-#[derive(Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, Debug)]
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
 pub struct MessageId(pub [u8; 32]);
 
 impl MessageId {
@@ -323,18 +377,6 @@ impl MessageId {
         &self.0
     }
 
-    /// Parse from hex string (for DHT keys)
-    pub fn from_hex(s: &str) -> Result<Self, anyhow::Error> {
-        let bytes = ::hex::decode(s)
-            .map_err(|e| anyhow::anyhow!("Invalid hex: {}", e))?;
-        if bytes.len() != 32 {
-            return Err(anyhow::anyhow!("MessageId must be 32 bytes, got {}", bytes.len()));
-        }
-        let mut array = [0u8; 32];
-        array.copy_from_slice(&bytes);
-        Ok(Self(array))
-    }
-
     /// Convert to hex string (for DHT keys)
     pub fn to_hex(&self) -> String {
         ::hex::encode(&self.0)
@@ -353,13 +395,7 @@ impl MessageId {
     /// For backward compatibility - parse from hex string (deprecated)
     #[deprecated(note = "Use from_hex or from_content instead")]
     pub fn from_string(s: &str) -> Result<Self, anyhow::Error> {
-        Self::from_hex(s)
-    }
-}
-
-impl fmt::Display for MessageId {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "msg_{}", hex::encode(&self.0[..8]))
+        Ok(Self::from_hex(s)?)
     }
 }
 
@@ -842,6 +878,19 @@ impl RoleId {
     pub fn new() -> Self {
         Self(Uuid::new_v4())
     }
+
+    /// Deterministic ID for a built-in role, so every replica of a Space agrees on it
+    pub fn derived(space_id: &SpaceId, name: &str) -> Self {
+        let mut hasher = Sha256::new();
+        hasher.update(b"ROLE_V1:");
+        hasher.update(&space_id.0);
+        hasher.update(name.as_bytes());
+
+        let hash = hasher.finalize();
+        let mut bytes = [0u8; 16];
+        bytes.copy_from_slice(&hash[..16]);
+        Self(Uuid::from_bytes(bytes))
+    }
 }
 
 impl Default for RoleId {
@@ -865,6 +914,8 @@ pub struct SpaceRole {
     pub position: u32,
     /// Optional color for UI (RGB)
     pub color: Option<u32>,
+    /// Whether members with this role are listed separately in the member list
+    pub hoisted: bool,
 }
 
 impl SpaceRole {
@@ -876,6 +927,7 @@ impl SpaceRole {
             permissions,
             position,
             color: None,
+            hoisted: false,
         }
     }
     
@@ -887,6 +939,7 @@ impl SpaceRole {
             permissions: SpacePermissions::admin(),
             position: 100,
             color: Some(0xFF0000), // Red
+            hoisted: false,
         }
     }
     
@@ -898,6 +951,7 @@ impl SpaceRole {
             permissions: SpacePermissions::moderator(),
             position: 50,
             color: Some(0x00FF00), // Green
+            hoisted: false,
         }
     }
     
@@ -909,14 +963,43 @@ impl SpaceRole {
             permissions: SpacePermissions::member(),
             position: 0,
             color: None,
+            hoisted: false,
+        }
+    }
+
+    /// Legacy `Role` this role's permissions correspond to
+    pub fn legacy_role(&self) -> Role {
+        if self.permissions.is_admin() {
+            Role::Admin
+        } else if self.permissions.has(SpacePermissions::KICK_MEMBERS) {
+            Role::Moderator
+        } else {
+            Role::Member
         }
     }
 }
 
 /// Invite identifier
-#[derive(Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, Debug)]
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
 pub struct InviteId(pub Uuid);
 
+impl InviteId {
+    pub fn as_bytes(&self) -> &[u8; 16] {
+        self.0.as_bytes()
+    }
+
+    pub fn to_hex(&self) -> String {
+        ::hex::encode(self.as_bytes())
+    }
+}
+
+impl_hex_id!(UserId, "user ID", 32, UserId);
+impl_hex_id!(SpaceId, "space ID", 32, SpaceId);
+impl_hex_id!(ChannelId, "channel ID", 32, ChannelId);
+impl_hex_id!(ThreadId, "thread ID", 32, ThreadId);
+impl_hex_id!(MessageId, "message ID", 32, MessageId);
+impl_hex_id!(InviteId, "invite ID", 16, |bytes| InviteId(Uuid::from_bytes(bytes)));
+
 /// Invite to join a space
 #[derive(Clone, PartialEq, Eq, Serialize, Deserialize, Debug, Encode, Decode)]
 pub struct Invite {
@@ -947,9 +1030,35 @@ pub struct Invite {
     /// Whether this invite is revoked
     #[n(8)]
     pub revoked: bool,
+    /// Role assigned to whoever joins with this invite (None = default role)
+    #[n(9)]
+    pub role: Option<RoleId>,
+    /// Who joined through this invite and when (unix seconds), sorted
+    #[n(10)]
+    pub used_by: Vec<(UserId, u64)>,
+    /// Relay circuit addresses the creator can be reached at
+    /// (`/.../p2p/{relay}/p2p-circuit/p2p/{creator}`)
+    /// 
+    /// Filled in by the creator's client from its current relay reservation
+    /// when invites are listed, so it follows relay rotation; replicated
+    /// copies leave it empty.
+    #[n(11)]
+    pub relay_hints: Vec<String>,
 }
 
 impl Invite {
+    /// Record that `user` joined through this invite at `joined_at`
+    /// 
+    /// Usage is a set, so replaying the same join is a no-op and replicas
+    /// converge regardless of the order joins arrive in.
+    pub fn record_use(&mut self, user: UserId, joined_at: u64) {
+        let entry = (user, joined_at);
+        if let Err(pos) = self.used_by.binary_search(&entry) {
+            self.used_by.insert(pos, entry);
+        }
+    }
+    
+
     /// Check if this invite is valid (not expired, not exceeded uses, not revoked)
     pub fn is_valid(&self, current_time: u64) -> bool {
         // Check if revoked
@@ -982,6 +1091,109 @@ impl Invite {
             InviteCreatorRole::Everyone => true,
         }
     }
+
+    /// Shareable link for this invite: `descord://join/{space_id_hex}?code={code}`
+    /// 
+    /// The creator's relay hints are appended as `&hint=` parameters.
+    pub fn to_uri(&self) -> String {
+        self.uri(None).to_string()
+    }
+
+    /// Shareable link that also carries a relay address to connect through
+    pub fn to_uri_with_relay(&self, relay: &str) -> String {
+        self.uri(Some(relay.to_string())).to_string()
+    }
+
+    fn uri(&self, relay: Option<String>) -> InviteUri {
+        InviteUri {
+            hints: self.relay_hints.clone(),
+            ..InviteUri::new(self.space_id, self.code.clone(), relay)
+        }
+    }
+
+    /// Whether `code` is a well-formed invite code (1-32 of `[A-Za-z0-9_-]`)
+    pub fn is_valid_code(code: &str) -> bool {
+        !code.is_empty()
+            && code.len() <= MAX_INVITE_CODE_LEN
+            && code.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+    }
+}
+
+/// Longest accepted invite code
+pub const MAX_INVITE_CODE_LEN: usize = 32;
+
+/// Prefix of every invite link
+pub const INVITE_URI_PREFIX: &str = "descord://join/";
+
+/// Parsed invite link: `descord://join/{space_id_hex}?code={code}[&relay={multiaddr}][&hint={multiaddr}]...`
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct InviteUri {
+    /// Space to join
+    pub space_id: SpaceId,
+    /// Invite code
+    pub code: String,
+    /// Optional relay address to connect through before joining
+    pub relay: Option<String>,
+    /// Relay circuit addresses of the invite's creator, dialed before joining
+    pub hints: Vec<String>,
+}
+
+impl InviteUri {
+    pub fn new(space_id: SpaceId, code: String, relay: Option<String>) -> Self {
+        Self { space_id, code, relay, hints: Vec::new() }
+    }
+
+    /// Parse and validate an invite link
+    pub fn parse(uri: &str) -> crate::Result<Self> {
+        let invalid = |reason: &str| crate::Error::InvalidOperation(format!("Invalid invite link: {}", reason));
+
+        let rest = uri.trim().strip_prefix(INVITE_URI_PREFIX)
+            .ok_or_else(|| invalid("expected descord://join/..."))?;
+        let (space_hex, query) = rest.split_once('?')
+            .ok_or_else(|| invalid("missing invite code"))?;
+
+        let space_id = SpaceId::from_hex(space_hex).map_err(|e| invalid(&e.to_string()))?;
+
+        let mut code = None;
+        let mut relay = None;
+        let mut hints = Vec::new();
+        for pair in query.split('&') {
+            match pair.split_once('=') {
+                Some(("code", value)) => code = Some(value.to_string()),
+                Some(("relay", value)) if !value.is_empty() => relay = Some(value.to_string()),
+                Some(("hint", value)) if !value.is_empty() => hints.push(value.to_string()),
+                _ => return Err(invalid(&format!("unexpected parameter '{}'", pair))),
+            }
+        }
+
+        let code = code.ok_or_else(|| invalid("missing invite code"))?;
+        if !Invite::is_valid_code(&code) {
+            return Err(invalid("malformed invite code"));
+        }
+
+        Ok(Self { space_id, code, relay, hints })
+    }
+}
+
+impl fmt::Display for InviteUri {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}{}?code={}", INVITE_URI_PREFIX, ::hex::encode(self.space_id.0), self.code)?;
+        if let Some(relay) = &self.relay {
+            write!(f, "&relay={}", relay)?;
+        }
+        for hint in &self.hints {
+            write!(f, "&hint={}", hint)?;
+        }
+        Ok(())
+    }
+}
+
+impl std::str::FromStr for InviteUri {
+    type Err = crate::Error;
+
+    fn from_str(s: &str) -> crate::Result<Self> {
+        Self::parse(s)
+    }
 }
 
 /// Who can create invites in a space
@@ -1204,6 +1416,21 @@ impl<'b, C> Decode<'b, C> for InviteId {
     }
 }
 
+impl<C> Encode<C> for RoleId {
+    fn encode<W: minicbor::encode::Write>(&self, e: &mut minicbor::Encoder<W>, _ctx: &mut C) -> Result<(), minicbor::encode::Error<W::Error>> {
+        e.bytes(self.0.as_bytes())?;
+        Ok(())
+    }
+}
+
+impl<'b, C> Decode<'b, C> for RoleId {
+    fn decode(d: &mut minicbor::Decoder<'b>, _ctx: &mut C) -> Result<Self, minicbor::decode::Error> {
+        let bytes = d.bytes()?;
+        let uuid = Uuid::from_slice(bytes).map_err(|_| minicbor::decode::Error::message("invalid UUID"))?;
+        Ok(RoleId(uuid))
+    }
+}
+
 impl<C> Encode<C> for Signature {
     fn encode<W: minicbor::encode::Write>(&self, e: &mut minicbor::Encoder<W>, _ctx: &mut C) -> Result<(), minicbor::encode::Error<W::Error>> {
         e.bytes(&self.0)?;
@@ -1229,3 +1456,158 @@ mod hex {
         bytes.iter().map(|b| format!("{:02x}", b)).collect()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::version::PROTOCOL_VERSION;
+
+    /// Protocol version the vectors below were frozen at
+    ///
+    /// IDs are content addresses shared between peers, so changing how any of
+    /// them is derived is a wire format change. If a vector stops matching,
+    /// bump `PROTOCOL_VERSION`, record the new vectors and update this too.
+    const VECTORS_PROTOCOL_VERSION: u32 = 2;
+
+    const CREATOR: UserId = UserId([1u8; 32]);
+    const AUTHOR: UserId = UserId([2u8; 32]);
+    const TIMESTAMP: u64 = 1_700_000_000_000;
+
+    fn assert_vector(kind: &str, actual: &[u8], expected: &str) {
+        assert_eq!(
+            VECTORS_PROTOCOL_VERSION, PROTOCOL_VERSION,
+            "PROTOCOL_VERSION changed: re-freeze the id vectors for the new version"
+        );
+        assert_eq!(
+            hex::encode(actual), expected,
+            "{} derivation changed: this breaks compatibility and needs a PROTOCOL_VERSION bump",
+            kind
+        );
+    }
+
+    #[test]
+    fn test_space_id_vector() {
+        let id = SpaceId::from_content(&CREATOR, "general", TIMESTAMP);
+        assert_vector("SpaceId", &id.0, "acbddba535e77c97b9db40b9b614746767596346e551d140e536d3554a15f906");
+    }
+
+    #[test]
+    fn test_channel_id_vector() {
+        let space_id = SpaceId::from_content(&CREATOR, "general", TIMESTAMP);
+        let id = ChannelId::from_content(&space_id, "announcements", &CREATOR);
+        assert_vector("ChannelId", &id.0, "e199c16b0c0f6a5de92acacda9c62e75745c803b6039fe2b0277cb17ef0c112e");
+    }
+
+    #[test]
+    fn test_thread_id_vector() {
+        let space_id = SpaceId::from_content(&CREATOR, "general", TIMESTAMP);
+        let channel_id = ChannelId::from_content(&space_id, "announcements", &CREATOR);
+        let id = ThreadId::from_content(&channel_id, &CREATOR, &[3u8; 32], TIMESTAMP + 1);
+        assert_vector("ThreadId", &id.0, "3907f6fa4444587981e00f53d701ee6a7fbe23d53335572ba37f98af9c7a03c2");
+    }
+
+    #[test]
+    fn test_message_id_vectors() {
+        let space_id = SpaceId::from_content(&CREATOR, "general", TIMESTAMP);
+        let channel_id = ChannelId::from_content(&space_id, "announcements", &CREATOR);
+        let thread_id = ThreadId::from_content(&channel_id, &CREATOR, &[3u8; 32], TIMESTAMP + 1);
+
+        let id = MessageId::from_content(&AUTHOR, &thread_id, &[4u8; 32], TIMESTAMP + 2, None);
+        assert_vector("MessageId", &id.0, "b6bcf5d40fc60626dca9f30fc4d85f13131c08705d8992d3005799e19ea600f4");
+
+        let reply = MessageId::from_content(&AUTHOR, &thread_id, &[4u8; 32], TIMESTAMP + 3, Some(&id));
+        assert_vector("MessageId (reply)", &reply.0, "678429428cef91e3f6d0033137860f23fa8a060c00a6579e3c9e63ece6961886");
+    }
+
+    #[test]
+    fn test_op_id_vector() {
+        use crate::crdt::{CrdtOp, Hlc, OpType};
+
+        let space_id = SpaceId::from_content(&CREATOR, "general", TIMESTAMP);
+        let channel_id = ChannelId::from_content(&space_id, "announcements", &CREATOR);
+        let op = CrdtOp {
+            op_id: OpId(Uuid::nil()),
+            space_id,
+            channel_id: Some(channel_id),
+            thread_id: None,
+            op_type: OpType::ArchiveChannel,
+            prev_ops: vec![OpId(Uuid::from_bytes([5u8; 16]))],
+            author: AUTHOR,
+            epoch: EpochId(3),
+            hlc: Hlc { wall_time: TIMESTAMP, logical: 1 },
+            timestamp: TIMESTAMP,
+            signature: Signature([0u8; 64]),
+        };
+        assert_vector("OpId", op.content_id().0.as_bytes(), "83597ef0a87729a4bf1ca0439dcdc812");
+    }
+
+    fn assert_parses<T>(hex_id: &str, len: usize)
+    where
+        T: std::str::FromStr<Err = ParseIdError> + for<'a> TryFrom<&'a str, Error = ParseIdError> + PartialEq + fmt::Debug,
+    {
+        let parsed: T = hex_id.parse().unwrap();
+        assert_eq!(T::try_from(hex_id).unwrap(), parsed);
+
+        let short = &hex_id[..hex_id.len() - 2];
+        assert!(matches!(
+            short.parse::<T>(),
+            Err(ParseIdError::WrongLength { expected, actual, .. }) if expected == len && actual == len - 1
+        ));
+        let non_hex = format!("zz{}", &hex_id[2..]);
+        assert!(matches!(non_hex.parse::<T>(), Err(ParseIdError::InvalidHex { .. })));
+        assert!(matches!(T::try_from(""), Err(ParseIdError::WrongLength { actual: 0, .. })));
+    }
+
+    #[test]
+    fn test_parse_ids_from_hex() {
+        let bytes = [0xabu8; 32];
+        let hex_id = hex::encode(bytes);
+
+        assert_parses::<UserId>(&hex_id, 32);
+        assert_parses::<SpaceId>(&hex_id, 32);
+        assert_parses::<ChannelId>(&hex_id, 32);
+        assert_parses::<ThreadId>(&hex_id, 32);
+        assert_parses::<MessageId>(&hex_id, 32);
+        assert_eq!(SpaceId::from_hex(&hex_id).unwrap(), SpaceId(bytes));
+        assert_eq!(ThreadId::from_hex(&ThreadId(bytes).to_hex()).unwrap(), ThreadId(bytes));
+
+        let invite = InviteId(Uuid::from_bytes([7u8; 16]));
+        assert_parses::<InviteId>(&invite.to_hex(), 16);
+        assert_eq!(InviteId::from_hex(&invite.to_hex()).unwrap(), invite);
+        assert!(matches!(SpaceId::from_hex(&invite.to_hex()), Err(ParseIdError::WrongLength { expected: 32, actual: 16, .. })));
+    }
+
+    fn assert_round_trips<T>(id: T, hex_id: &str)
+    where
+        T: fmt::Display + std::str::FromStr<Err = ParseIdError> + Serialize + for<'de> Deserialize<'de> + PartialEq + fmt::Debug,
+    {
+        assert_eq!(id.to_string(), hex_id);
+        assert_eq!(id.to_string().parse::<T>().unwrap(), id);
+
+        let json = serde_json::to_string(&id).unwrap();
+        assert_eq!(json, format!("\"{}\"", hex_id));
+        assert_eq!(serde_json::from_str::<T>(&json).unwrap(), id);
+
+        let stored = bincode::serialize(&id).unwrap();
+        assert_eq!(bincode::deserialize::<T>(&stored).unwrap(), id);
+    }
+
+    #[test]
+    fn test_ids_round_trip_through_display_and_serde() {
+        let bytes: [u8; 32] = std::array::from_fn(|i| i as u8);
+        let hex_id = hex::encode(bytes);
+
+        assert_round_trips(UserId(bytes), &hex_id);
+        assert_round_trips(SpaceId(bytes), &hex_id);
+        assert_round_trips(ChannelId(bytes), &hex_id);
+        assert_round_trips(ThreadId(bytes), &hex_id);
+        assert_round_trips(MessageId(bytes), &hex_id);
+
+        let invite = InviteId(Uuid::from_bytes([7u8; 16]));
+        assert_round_trips(invite, &invite.to_hex());
+
+        // Binary storage keeps the raw bytes, as before
+        assert_eq!(bincode::serialize(&SpaceId(bytes)).unwrap(), bytes.to_vec());
+        assert_eq!(SpaceId(bytes).short(), hex_id[..16]);
+    }
+}
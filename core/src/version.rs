@@ -14,8 +14,9 @@ pub const VERSION_MINOR: u32 = 1;
 /// Patch version (bug fixes)
 pub const VERSION_PATCH: u32 = 1;
 
-/// Protocol version (incremented on wire format changes)
-pub const PROTOCOL_VERSION: u32 = 1;
+/// Protocol version (incremented on wire format changes, including how
+/// content-addressed IDs are derived - see the vectors in `types`)
+pub const PROTOCOL_VERSION: u32 = 2;
 
 /// Build timestamp (Unix epoch)
 pub const BUILD_TIMESTAMP: u64 = 1732233600; // Nov 21, 2025 20:00:00 UTC
@@ -0,0 +1,214 @@
+//! Channel webhooks for external integrations
+//!
+//! A [`WebhookToken`] is issued by `Client::create_webhook` for one channel
+//! and signed with the client's key, so forged tokens are rejected. The
+//! client also remembers which tokens are live, and a token stops working
+//! once `Client::revoke_webhook` forgets it. With the `webhook-server` feature, [`serve`]
+//! accepts `POST /webhook/<token>` with a JSON [`WebhookPost`] body and posts
+//! it through the client.
+
+use crate::types::*;
+use crate::{Error, Result};
+use serde::Deserialize;
+use std::fmt;
+use std::str::FromStr;
+
+/// Capability to post into one channel through the webhook endpoint
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WebhookToken {
+    /// Random token ID, used for revocation
+    pub id: [u8; 16],
+    /// Channel the token may post into
+    pub channel_id: ChannelId,
+    /// Issuing client's signature over the ID and channel
+    pub signature: Signature,
+}
+
+impl WebhookToken {
+    /// Bytes covered by the signature
+    pub fn signing_bytes(id: &[u8; 16], channel_id: &ChannelId) -> Vec<u8> {
+        let mut bytes = b"spaceway-webhook-v1".to_vec();
+        bytes.extend_from_slice(id);
+        bytes.extend_from_slice(&channel_id.0);
+        bytes
+    }
+}
+
+/// Rendered as `<id>.<channel>.<signature>` in hex, for use in URLs
+impl fmt::Display for WebhookToken {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}.{}.{}",
+            hex::encode(self.id),
+            hex::encode(self.channel_id.0),
+            hex::encode(self.signature.0)
+        )
+    }
+}
+
+impl FromStr for WebhookToken {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let malformed = || Error::InvalidOperation("Malformed webhook token".to_string());
+        let mut parts = s.split('.');
+        let (Some(id), Some(channel), Some(signature), None) =
+            (parts.next(), parts.next(), parts.next(), parts.next())
+        else {
+            return Err(malformed());
+        };
+
+        let decode = |part: &str| hex::decode(part).map_err(|_| malformed());
+        Ok(Self {
+            id: decode(id)?.try_into().map_err(|_| malformed())?,
+            channel_id: ChannelId(decode(channel)?.try_into().map_err(|_| malformed())?),
+            signature: Signature(decode(signature)?.try_into().map_err(|_| malformed())?),
+        })
+    }
+}
+
+/// JSON body accepted by the webhook endpoint
+#[derive(Debug, Clone, Deserialize)]
+pub struct WebhookPost {
+    /// Message content
+    pub content: String,
+    /// Hex ID of a thread in the channel to post into; a new thread is started if omitted
+    #[serde(default)]
+    pub thread_id: Option<String>,
+    /// Title for a newly started thread
+    #[serde(default)]
+    pub title: Option<String>,
+}
+
+impl WebhookPost {
+    /// The target thread, if one was given
+    pub fn parse_thread_id(&self) -> Result<Option<ThreadId>> {
+        self.thread_id.as_deref()
+            .map(|hex_id| ThreadId::from_hex(hex_id).map_err(|e| Error::InvalidOperation(e.to_string())))
+            .transpose()
+    }
+}
+
+/// Largest request (headers and body) the endpoint reads
+#[cfg(feature = "webhook-server")]
+const MAX_REQUEST_BYTES: usize = 64 * 1024;
+
+/// Serve `POST /webhook/<token>` for a client until the listener fails
+///
+/// Replies 200 with the posted message ID, 400 for a bad body, 401 for an
+/// invalid or revoked token, 413 for oversized requests and 404 otherwise.
+#[cfg(feature = "webhook-server")]
+pub async fn serve(
+    listener: tokio::net::TcpListener,
+    client: std::sync::Arc<tokio::sync::RwLock<crate::Client>>,
+) -> Result<()> {
+    use tokio::io::AsyncWriteExt;
+
+    loop {
+        let (mut stream, _) = listener.accept().await
+            .map_err(|e| Error::Network(format!("Webhook listener failed: {}", e)))?;
+        let client = std::sync::Arc::clone(&client);
+
+        tokio::spawn(async move {
+            let (status, body) = match read_request(&mut stream).await {
+                Some((path, body)) => handle(&client, &path, &body).await,
+                None => ("413 Payload Too Large", String::new()),
+            };
+            let response = format!(
+                "HTTP/1.1 {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                status,
+                body.len(),
+                body
+            );
+            let _ = stream.write_all(response.as_bytes()).await;
+            let _ = stream.shutdown().await;
+        });
+    }
+}
+
+/// Read a `POST` request, returning its path and body
+#[cfg(feature = "webhook-server")]
+async fn read_request(stream: &mut tokio::net::TcpStream) -> Option<(String, Vec<u8>)> {
+    use tokio::io::AsyncReadExt;
+
+    let mut buf = Vec::new();
+    let mut chunk = [0u8; 4096];
+    loop {
+        if let Some(header_end) = buf.windows(4).position(|w| w == b"\r\n\r\n") {
+            let head = String::from_utf8_lossy(&buf[..header_end]).to_string();
+            let path = head.strip_prefix("POST ")
+                .and_then(|rest| rest.split_whitespace().next())
+                .unwrap_or_default()
+                .to_string();
+            let content_length = head.lines()
+                .filter_map(|line| line.split_once(':'))
+                .find(|(name, _)| name.trim().eq_ignore_ascii_case("content-length"))
+                .and_then(|(_, value)| value.trim().parse::<usize>().ok())
+                .unwrap_or(0);
+
+            let body_start = header_end + 4;
+            if body_start + content_length > MAX_REQUEST_BYTES {
+                return None;
+            }
+            while buf.len() < body_start + content_length {
+                let n = stream.read(&mut chunk).await.ok()?;
+                if n == 0 {
+                    break;
+                }
+                buf.extend_from_slice(&chunk[..n]);
+            }
+            let body_end = buf.len().min(body_start + content_length);
+            return Some((path, buf[body_start..body_end].to_vec()));
+        }
+
+        if buf.len() > MAX_REQUEST_BYTES {
+            return None;
+        }
+        let n = stream.read(&mut chunk).await.ok()?;
+        if n == 0 {
+            return Some((String::new(), Vec::new()));
+        }
+        buf.extend_from_slice(&chunk[..n]);
+    }
+}
+
+#[cfg(feature = "webhook-server")]
+async fn handle(
+    client: &tokio::sync::RwLock<crate::Client>,
+    path: &str,
+    body: &[u8],
+) -> (&'static str, String) {
+    let Some(token) = path.strip_prefix("/webhook/") else {
+        return ("404 Not Found", String::new());
+    };
+    let Ok(token) = token.parse::<WebhookToken>() else {
+        return ("401 Unauthorized", String::new());
+    };
+    let post: WebhookPost = match serde_json::from_slice(body) {
+        Ok(post) => post,
+        Err(e) => return ("400 Bad Request", serde_json::json!({ "error": e.to_string() }).to_string()),
+    };
+
+    match client.read().await.post_via_webhook(&token, post).await {
+        Ok(message) => ("200 OK", serde_json::json!({ "message_id": hex::encode(message.id.0) }).to_string()),
+        Err(Error::Permission(e)) => ("401 Unauthorized", serde_json::json!({ "error": e }).to_string()),
+        Err(e) => ("400 Bad Request", serde_json::json!({ "error": e.to_string() }).to_string()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_token_round_trips_through_string() {
+        let token = WebhookToken {
+            id: [1u8; 16],
+            channel_id: ChannelId([2u8; 32]),
+            signature: Signature([3u8; 64]),
+        };
+        assert_eq!(token.to_string().parse::<WebhookToken>().unwrap(), token);
+        assert!("abc.def".parse::<WebhookToken>().is_err());
+    }
+}
@@ -0,0 +1,61 @@
+//! Anti-entropy clocks pick out exactly the ops a peer is missing
+
+use spaceway_core::crdt::{CrdtOp, Hlc, OpPayload, OpType};
+use spaceway_core::network::{SpaceClock, SyncDigest};
+use spaceway_core::types::*;
+
+fn op(author: UserId, wall_time: u64) -> CrdtOp {
+    CrdtOp {
+        op_id: OpId(uuid::Uuid::new_v4()),
+        space_id: SpaceId([1u8; 32]),
+        channel_id: None,
+        thread_id: None,
+        op_type: OpType::PostMessage(OpPayload::PostMessage {
+            message_id: MessageId([wall_time as u8; 32]),
+            content: wall_time.to_string(),
+            quote: None,
+        }),
+        prev_ops: vec![],
+        author,
+        epoch: EpochId(0),
+        hlc: Hlc { wall_time, logical: 0 },
+        timestamp: wall_time,
+        signature: Signature([0u8; 64]),
+    }
+}
+
+#[test]
+fn test_only_newer_ops_are_missing() {
+    let alice = UserId([1u8; 32]);
+    let bob = UserId([2u8; 32]);
+    let ours = vec![op(alice, 1), op(alice, 2), op(bob, 3), op(alice, 4)];
+    let theirs = SpaceClock::of(&ours[..2]);
+
+    let missing = theirs.missing_ops(&ours);
+    let times: Vec<u64> = missing.iter().map(|op| op.hlc.wall_time).collect();
+    assert_eq!(times, vec![3, 4]);
+    assert!(SpaceClock::of(&ours).missing_ops(&ours).is_empty());
+}
+
+#[test]
+fn test_gap_resends_all_of_author() {
+    let alice = UserId([1u8; 32]);
+    let ours = vec![op(alice, 1), op(alice, 2), op(alice, 3)];
+
+    // They received 1 and 3 but lost 2
+    let theirs = SpaceClock::of(&[ours[0].clone(), ours[2].clone()]);
+    assert_eq!(theirs.missing_ops(&ours).len(), 3);
+}
+
+#[test]
+fn test_digest_round_trip() {
+    let alice = UserId([1u8; 32]);
+    let digest = SyncDigest {
+        space_id: SpaceId([1u8; 32]),
+        responder: "peer".to_string(),
+        clock: SpaceClock::of(&[op(alice, 1), op(alice, 5)]),
+    };
+    let decoded = SyncDigest::from_bytes(&digest.to_bytes().unwrap()).unwrap();
+    assert_eq!(decoded, digest);
+    assert_eq!(decoded.clock.get(&alice).unwrap().count, 2);
+}
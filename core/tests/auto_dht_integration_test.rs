@@ -22,6 +22,7 @@ async fn test_auto_operation_storage() -> anyhow::Result<()> {
         storage_path: format!("./test-data/auto-ops-alice-{}", uuid::Uuid::new_v4()).into(),
         listen_addrs: vec!["/ip4/127.0.0.1/tcp/0".to_string()],
         bootstrap_peers: vec![],
+        ..Default::default()
     };
     
     let alice = Client::new(alice_keypair, alice_config)?;
@@ -67,6 +68,7 @@ async fn test_auto_blob_storage_and_retrieval() -> anyhow::Result<()> {
         storage_path: format!("./test-data/auto-blobs-{}", uuid::Uuid::new_v4()).into(),
         listen_addrs: vec!["/ip4/127.0.0.1/tcp/0".to_string()],
         bootstrap_peers: vec![],
+        ..Default::default()
     };
     
     let client = Client::new(keypair, config)?;
@@ -95,7 +97,7 @@ async fn test_auto_blob_storage_and_retrieval() -> anyhow::Result<()> {
     println!("  Size: {} bytes", metadata.size);
     
     // Verify blob exists locally
-    let retrieved = client.retrieve_blob(&metadata.hash).await?;
+    let retrieved = client.retrieve_blob_for_space(&space_id, &metadata.hash).await?;
     assert_eq!(&retrieved[..], test_data);
     println!("✓ Retrieved blob from local storage");
     
@@ -119,6 +121,7 @@ async fn test_auto_fetch_on_join() -> anyhow::Result<()> {
         storage_path: format!("./test-data/auto-join-alice-{}", uuid::Uuid::new_v4()).into(),
         listen_addrs: vec!["/ip4/127.0.0.1/tcp/0".to_string()],
         bootstrap_peers: vec![],
+        ..Default::default()
     };
     
     let alice = Client::new(alice_keypair, alice_config)?;
@@ -170,6 +173,7 @@ async fn test_blob_fetch_fallback() -> anyhow::Result<()> {
         storage_path: format!("./test-data/blob-fallback-{}", uuid::Uuid::new_v4()).into(),
         listen_addrs: vec!["/ip4/127.0.0.1/tcp/0".to_string()],
         bootstrap_peers: vec![],
+        ..Default::default()
     };
     
     let client = Client::new(keypair, config)?;
@@ -224,6 +228,7 @@ async fn test_full_auto_integration_workflow() -> anyhow::Result<()> {
         storage_path: format!("./test-data/full-auto-{}", uuid::Uuid::new_v4()).into(),
         listen_addrs: vec!["/ip4/127.0.0.1/tcp/0".to_string()],
         bootstrap_peers: vec![],
+        ..Default::default()
     };
     
     let alice = Client::new(alice_keypair, alice_config)?;
@@ -260,8 +265,8 @@ async fn test_full_auto_integration_workflow() -> anyhow::Result<()> {
     println!("✓ Step 3: Blob uploaded (auto-stored in DHT)");
     println!("  Blob hash: {}", blob_metadata.hash.to_hex());
     
-    // 4. Retrieve blob locally
-    let retrieved = alice.retrieve_blob(&blob_metadata.hash).await?;
+    // 4. Retrieve blob locally (Space blobs are sealed with the Space key)
+    let retrieved = alice.retrieve_blob_for_space(&space_id, &blob_metadata.hash).await?;
     assert_eq!(&retrieved[..], content);
     println!("✓ Step 4: Blob retrieved from local storage");
     
@@ -366,6 +366,7 @@ async fn create_user(name: &str) -> (Client, String) {
         storage_path: temp.path().to_path_buf(),
         listen_addrs: vec![],
         bootstrap_peers: vec![],
+        ..Default::default()
     };
     
     let client = Client::new(keypair, config).expect(&format!("{} should initialize", name));
@@ -0,0 +1,57 @@
+//! Checking where a blob can be loaded from without fetching it
+
+use spaceway_core::crypto::signing::Keypair;
+use spaceway_core::storage::{BlobHash, EncryptedBlob};
+use spaceway_core::{Client, ClientConfig};
+use tempfile::TempDir;
+use tokio::time::{sleep, Duration};
+
+fn create_client(temp_dir: &TempDir) -> Client {
+    let config = ClientConfig {
+        storage_path: temp_dir.path().to_path_buf(),
+        listen_addrs: vec!["/ip4/127.0.0.1/tcp/0".to_string()],
+        bootstrap_peers: vec![],
+        ..Default::default()
+    };
+    Client::new(Keypair::generate(), config).unwrap()
+}
+
+#[tokio::test]
+async fn test_blob_available_locally_then_in_dht() {
+    let alice_dir = TempDir::new().unwrap();
+    let bob_dir = TempDir::new().unwrap();
+    let alice = create_client(&alice_dir);
+    let bob = create_client(&bob_dir);
+    alice.start().await.unwrap();
+    bob.start().await.unwrap();
+    sleep(Duration::from_millis(500)).await;
+
+    let alice_addr = alice.listening_addrs().await
+        .into_iter()
+        .find(|addr| addr.to_string().contains("127.0.0.1"))
+        .expect("Alice should listen on loopback");
+    bob.network_dial(&format!("{}/p2p/{}", alice_addr, alice.peer_id().await)).await.unwrap();
+    sleep(Duration::from_secs(1)).await;
+
+    let (space, _, _) = alice.create_space("Attachments".to_string(), None).await.unwrap();
+
+    // Nobody has a blob that was never stored
+    let missing = BlobHash::hash(b"never stored");
+    let availability = alice.blob_available(&space.id, &missing).await.unwrap();
+    assert!(!availability.is_available());
+
+    let metadata = alice.store_blob_for_space(&space.id, b"local attachment", None, None).await.unwrap();
+    assert!(alice.blob_available(&space.id, &metadata.hash).await.unwrap().local);
+    assert!(!bob.blob_available(&space.id, &metadata.hash).await.unwrap().local);
+
+    // Bob only learns of this one through the DHT index
+    let plaintext = b"replicated attachment";
+    let hash = BlobHash::hash(plaintext);
+    let blob = EncryptedBlob::encrypt(plaintext, &[7u8; 32]).unwrap();
+    alice.dht_put_blob(&space.id, &hash, &blob).await.unwrap();
+    sleep(Duration::from_secs(1)).await;
+
+    let availability = bob.blob_available(&space.id, &hash).await.unwrap();
+    assert!(!availability.local);
+    assert!(availability.dht);
+}
@@ -0,0 +1,70 @@
+//! Integration tests for the blob content-type allowlist
+
+use spaceway_core::crypto::signing::Keypair;
+use spaceway_core::storage::StorageConfig;
+use spaceway_core::{Client, ClientConfig, Error};
+use tempfile::TempDir;
+
+const PNG: &[u8] = b"\x89PNG\r\n\x1a\n\0\0\0\rIHDR";
+const JPEG: &[u8] = b"\xff\xd8\xff\xe0\0\x10JFIF\0";
+
+fn create_client(temp_dir: &TempDir, allowed: Option<&[&str]>) -> Client {
+    let config = ClientConfig {
+        storage_path: temp_dir.path().to_path_buf(),
+        listen_addrs: vec!["/ip4/127.0.0.1/tcp/0".to_string()],
+        bootstrap_peers: vec![],
+        storage: StorageConfig {
+            allowed_mime_types: allowed.map(|types| types.iter().map(|t| t.to_string()).collect()),
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+    Client::new(Keypair::generate(), config).unwrap()
+}
+
+#[tokio::test]
+async fn test_allowed_type_is_stored() {
+    let temp_dir = TempDir::new().unwrap();
+    let client = create_client(&temp_dir, Some(&["image/*", "text/plain"]));
+
+    let image = client.store_blob(PNG, Some("image/png".to_string()), None).await.unwrap();
+    assert_eq!(client.retrieve_blob(&image.hash).await.unwrap(), PNG);
+
+    let text = b"just some notes";
+    client.store_blob(text, Some("text/plain; charset=utf-8".to_string()), None).await.unwrap();
+}
+
+#[tokio::test]
+async fn test_disallowed_type_is_rejected() {
+    let temp_dir = TempDir::new().unwrap();
+    let client = create_client(&temp_dir, Some(&["image/*"]));
+
+    let result = client.store_blob(b"%PDF-1.7 ...", Some("application/pdf".to_string()), None).await;
+    assert!(matches!(result, Err(Error::InvalidOperation(_))));
+
+    let result = client.store_blob(PNG, None, None).await;
+    assert!(matches!(result, Err(Error::InvalidOperation(_))));
+}
+
+#[tokio::test]
+async fn test_magic_number_mismatch_is_rejected() {
+    let temp_dir = TempDir::new().unwrap();
+    let client = create_client(&temp_dir, Some(&["image/*", "text/plain"]));
+
+    // JPEG bytes labelled as PNG
+    let result = client.store_blob(JPEG, Some("image/png".to_string()), None).await;
+    assert!(matches!(result, Err(Error::InvalidOperation(_))));
+
+    // A zip archive passed off as text
+    let result = client.store_blob(b"PK\x03\x04payload", Some("text/plain".to_string()), None).await;
+    assert!(matches!(result, Err(Error::InvalidOperation(_))));
+}
+
+#[tokio::test]
+async fn test_validation_is_opt_in() {
+    let temp_dir = TempDir::new().unwrap();
+    let client = create_client(&temp_dir, None);
+
+    client.store_blob(JPEG, Some("image/png".to_string()), None).await.unwrap();
+    client.store_blob(b"anything", None, None).await.unwrap();
+}
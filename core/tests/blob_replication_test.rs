@@ -23,6 +23,7 @@ async fn test_blob_storage_code_path() -> anyhow::Result<()> {
         storage_path: format!("./test-data/blob-storage-alice-{}", uuid::Uuid::new_v4()).into(),
         listen_addrs: vec!["/ip4/127.0.0.1/tcp/0".to_string()],
         bootstrap_peers: vec![],
+        ..Default::default()
     };
     
     let alice = Client::new(alice_keypair, alice_config)?;
@@ -77,6 +78,7 @@ async fn test_blob_retrieval_code_path() -> anyhow::Result<()> {
         storage_path: format!("./test-data/blob-retrieval-{}", uuid::Uuid::new_v4()).into(),
         listen_addrs: vec!["/ip4/127.0.0.1/tcp/0".to_string()],
         bootstrap_peers: vec![],
+        ..Default::default()
     };
     
     let client = Client::new(keypair, config)?;
@@ -120,6 +122,7 @@ async fn test_blob_index_listing() -> anyhow::Result<()> {
         storage_path: format!("./test-data/blob-listing-{}", uuid::Uuid::new_v4()).into(),
         listen_addrs: vec!["/ip4/127.0.0.1/tcp/0".to_string()],
         bootstrap_peers: vec![],
+        ..Default::default()
     };
     
     let client = Client::new(keypair, config)?;
@@ -162,6 +165,7 @@ async fn test_full_blob_replication() -> anyhow::Result<()> {
         listen_addrs: vec!["/ip4/127.0.0.1/tcp/0".to_string()],
         // In production: bootstrap_peers: vec!["<multiaddr>".to_string()],
         bootstrap_peers: vec![],
+        ..Default::default()
     };
     
     let alice = Client::new(alice_keypair, alice_config)?;
@@ -195,6 +199,7 @@ async fn test_full_blob_replication() -> anyhow::Result<()> {
         listen_addrs: vec!["/ip4/127.0.0.1/tcp/0".to_string()],
         // In production: same bootstrap peers
         bootstrap_peers: vec![],
+        ..Default::default()
     };
     
     let bob = Client::new(bob_keypair, bob_config)?;
@@ -0,0 +1,74 @@
+//! Integration test: a welcome bot that replies when mentioned
+
+use spaceway_core::crypto::signing::Keypair;
+use spaceway_core::{BotClient, Client, ClientConfig, Role};
+use std::sync::Arc;
+use std::time::Duration;
+use tempfile::TempDir;
+
+fn create_client(temp_dir: &TempDir) -> Client {
+    let config = ClientConfig {
+        storage_path: temp_dir.path().to_path_buf(),
+        listen_addrs: vec!["/ip4/127.0.0.1/tcp/0".to_string()],
+        bootstrap_peers: vec![],
+        ..Default::default()
+    };
+    Client::new(Keypair::generate(), config).unwrap()
+}
+
+#[tokio::test]
+async fn test_bot_auto_replies_to_mention() {
+    let alice_dir = TempDir::new().unwrap();
+    let bot_dir = TempDir::new().unwrap();
+    let alice = create_client(&alice_dir);
+    let bot_client = Arc::new(create_client(&bot_dir));
+
+    let mut bot = BotClient::new(Arc::clone(&bot_client));
+    let mention = bot.mention();
+    bot.on_mention(|ctx, message| async move {
+        ctx.reply(&message, "Welcome aboard!").await?;
+        Ok(())
+    });
+    let handle = bot.run();
+
+    // Alice's ops reach the bot as they would over the network
+    let (space, space_op, _) = alice.create_space("Lobby".to_string(), None).await.unwrap();
+    let (channel, channel_op) = alice.create_channel(space.id, "general".to_string(), None).await.unwrap();
+    let (thread, thread_op) = alice.create_thread(space.id, channel.id, None, "Hi all".to_string()).await.unwrap();
+    let member_op = alice.add_member(space.id, bot_client.user_id(), Role::Member).await.unwrap();
+    for op in [space_op, channel_op, thread_op, member_op] {
+        bot_client.handle_incoming_op(op).await.unwrap();
+    }
+    let group_info = alice.channel_group_info(&channel.id).await.unwrap();
+    bot_client.join_channel_with_group_info(&channel.id, &group_info).await.unwrap();
+    
+    let (_, plain_op) = alice.post_message(space.id, thread.id, "No bots needed here".to_string()).await.unwrap();
+    let (asked, asked_op) = alice.post_message(space.id, thread.id, format!("{} hello?", mention)).await.unwrap();
+    for op in [plain_op, asked_op] {
+        bot_client.handle_incoming_op(op).await.unwrap();
+    }
+
+    let replies = tokio::time::timeout(Duration::from_secs(5), async {
+        loop {
+            let replies: Vec<_> = bot_client.list_messages(&thread.id).await.into_iter()
+                .filter(|message| message.author == bot_client.user_id())
+                .collect();
+            if !replies.is_empty() {
+                break replies;
+            }
+            tokio::time::sleep(Duration::from_millis(20)).await;
+        }
+    }).await.expect("bot should reply to the mention");
+
+    // Exactly one reply: the unmentioned messages and the bot's own reply are ignored
+    tokio::time::sleep(Duration::from_millis(200)).await;
+    let replies_after: Vec<_> = bot_client.list_messages(&thread.id).await.into_iter()
+        .filter(|message| message.author == bot_client.user_id())
+        .collect();
+    assert_eq!(replies.len(), 1);
+    assert_eq!(replies_after.len(), 1);
+    assert_eq!(replies[0].content, "Welcome aboard!");
+    assert_eq!(replies[0].thread_id, asked.thread_id);
+
+    handle.abort();
+}
@@ -0,0 +1,50 @@
+//! Deleting a Channel removes it everywhere and late ops can't bring it back
+
+use spaceway_core::crypto::signing::Keypair;
+use spaceway_core::{Client, ClientConfig};
+use tempfile::TempDir;
+
+fn create_client(temp_dir: &TempDir) -> Client {
+    let config = ClientConfig {
+        storage_path: temp_dir.path().to_path_buf(),
+        listen_addrs: vec!["/ip4/127.0.0.1/tcp/0".to_string()],
+        bootstrap_peers: vec![],
+        ..Default::default()
+    };
+    Client::new(Keypair::generate(), config).unwrap()
+}
+
+#[tokio::test]
+async fn test_deleted_channel_is_gone_on_second_replica() {
+    let alice_dir = TempDir::new().unwrap();
+    let bob_dir = TempDir::new().unwrap();
+    let alice = create_client(&alice_dir);
+    let bob = create_client(&bob_dir);
+
+    let (space, space_op, _) = alice.create_space("Deletions".to_string(), None).await.unwrap();
+    let (channel, channel_op) = alice.create_channel(space.id, "old".to_string(), None).await.unwrap();
+    let (thread, thread_op) = alice.create_thread(space.id, channel.id, None, "Hello".to_string()).await.unwrap();
+    for op in [space_op, channel_op, thread_op] {
+        bob.handle_incoming_op(op).await.unwrap();
+    }
+    assert!(bob.get_channel(&channel.id).await.is_some());
+
+    // Bob holds no DELETE_CHANNELS
+    assert!(bob.delete_channel(channel.id).await.is_err());
+
+    // Created before the deletion, but delivered after it
+    let (straggler, straggler_op) = alice.create_thread(space.id, channel.id, None, "Late".to_string()).await.unwrap();
+
+    let delete_op = alice.delete_channel(channel.id).await.unwrap();
+    assert!(alice.get_channel(&channel.id).await.is_none());
+    assert!(alice.get_thread(&thread.id).await.is_none());
+    assert!(alice.create_thread(space.id, channel.id, None, "Again".to_string()).await.is_err());
+
+    bob.handle_incoming_op(delete_op).await.unwrap();
+    assert!(bob.get_channel(&channel.id).await.is_none());
+    assert!(bob.list_channels(&space.id).await.iter().all(|c| c.id != channel.id));
+    assert!(bob.get_thread(&thread.id).await.is_none());
+
+    assert!(bob.handle_incoming_op(straggler_op).await.is_err());
+    assert!(bob.get_thread(&straggler.id).await.is_none());
+}
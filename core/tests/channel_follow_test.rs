@@ -0,0 +1,99 @@
+//! Integration tests for announcement channel follows
+
+use spaceway_core::crypto::signing::Keypair;
+use spaceway_core::forum::Message;
+use spaceway_core::{Client, ClientConfig, Error, ThreadId};
+use std::time::Duration;
+use tempfile::TempDir;
+
+fn create_client(temp_dir: &TempDir) -> Client {
+    let config = ClientConfig {
+        storage_path: temp_dir.path().to_path_buf(),
+        listen_addrs: vec!["/ip4/127.0.0.1/tcp/0".to_string()],
+        bootstrap_peers: vec![],
+        ..Default::default()
+    };
+    Client::new(Keypair::generate(), config).unwrap()
+}
+
+/// Forwarded copies in `thread_id`
+async fn forwards(client: &Client, thread_id: &ThreadId) -> Vec<Message> {
+    client.list_messages(thread_id).await.into_iter()
+        .filter(|message| message.forwarded_from.is_some())
+        .collect()
+}
+
+/// Wait until the relay has forwarded `count` messages into `thread_id`
+async fn wait_for_forwards(client: &Client, thread_id: &ThreadId, count: usize) -> Vec<Message> {
+    tokio::time::timeout(Duration::from_secs(5), async {
+        loop {
+            let forwarded = forwards(client, thread_id).await;
+            if forwarded.len() >= count {
+                break forwarded;
+            }
+            tokio::time::sleep(Duration::from_millis(20)).await;
+        }
+    }).await.expect("announcements should be relayed without polling")
+}
+
+#[tokio::test]
+async fn test_followed_channel_messages_are_announced() {
+    let alice_dir = TempDir::new().unwrap();
+    let bob_dir = TempDir::new().unwrap();
+    let alice = create_client(&alice_dir);
+    let bob = create_client(&bob_dir);
+
+    let (project, _, _) = alice.create_space("Project".to_string(), None).await.unwrap();
+    let (releases, _) = alice.create_channel(project.id, "releases".to_string(), None).await.unwrap();
+
+    let (community, community_op, _) = alice.create_space("Community".to_string(), None).await.unwrap();
+    let (news, news_op) = alice.create_channel(community.id, "news".to_string(), None).await.unwrap();
+    let (follow, follow_op) = alice.follow_channel(releases.id, news.id).await.unwrap();
+    assert!(matches!(alice.follow_channel(releases.id, news.id).await, Err(Error::AlreadyExists(_))));
+
+    let (source_thread, _) = alice.create_thread(project.id, releases.id, None, "Releases".to_string()).await.unwrap();
+    let (posted, _) = alice.post_message(project.id, source_thread.id, "v1.2 is out".to_string()).await.unwrap();
+    let announced = wait_for_forwards(&alice, &follow.target_thread, 2).await;
+    assert!(announced.iter().any(|m| m.forwarded_from == Some(posted.id) && m.content == "v1.2 is out"));
+
+    // The forwarded copies are not announced again
+    tokio::time::sleep(Duration::from_millis(200)).await;
+    assert_eq!(forwards(&alice, &follow.target_thread).await.len(), 2);
+
+    // Members of the target Space see the follow
+    for op in [community_op, news_op, follow_op] {
+        bob.handle_incoming_op(op).await.unwrap();
+    }
+    assert_eq!(bob.list_channel_follows(&news.id).await, vec![follow]);
+
+    // After unfollowing, new messages stay in the source
+    alice.unfollow_channel(releases.id, news.id).await.unwrap();
+    alice.post_message(project.id, source_thread.id, "v1.3 is out".to_string()).await.unwrap();
+    tokio::time::sleep(Duration::from_millis(200)).await;
+    assert_eq!(forwards(&alice, &follow.target_thread).await.len(), 2);
+    assert!(alice.list_channel_follows(&news.id).await.is_empty());
+}
+
+#[tokio::test]
+async fn test_mutual_follows_do_not_loop() {
+    let temp_dir = TempDir::new().unwrap();
+    let client = create_client(&temp_dir);
+
+    let (space, _, _) = client.create_space("Loop".to_string(), None).await.unwrap();
+    let (a, _) = client.create_channel(space.id, "a".to_string(), None).await.unwrap();
+    let (b, _) = client.create_channel(space.id, "b".to_string(), None).await.unwrap();
+    let (into_b, _) = client.follow_channel(a.id, b.id).await.unwrap();
+    let (into_a, _) = client.follow_channel(b.id, a.id).await.unwrap();
+
+    // Following b started a thread in a, which is announced in b
+    wait_for_forwards(&client, &into_b.target_thread, 1).await;
+
+    let (thread, _) = client.create_thread(space.id, a.id, None, "ping".to_string()).await.unwrap();
+    let (_, _) = client.post_message(space.id, thread.id, "hello".to_string()).await.unwrap();
+
+    // "ping" and "hello" reach b once each; their copies are not bounced back
+    wait_for_forwards(&client, &into_b.target_thread, 3).await;
+    tokio::time::sleep(Duration::from_millis(200)).await;
+    assert_eq!(forwards(&client, &into_b.target_thread).await.len(), 3);
+    assert!(forwards(&client, &into_a.target_thread).await.is_empty());
+}
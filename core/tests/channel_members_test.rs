@@ -0,0 +1,60 @@
+//! Listing a Channel's members and their channel roles
+
+use spaceway_core::crypto::signing::Keypair;
+use spaceway_core::types::Role;
+use spaceway_core::{Client, ClientConfig};
+use std::time::Duration;
+use tempfile::TempDir;
+use tokio::time::{sleep, Instant};
+
+fn create_client(temp_dir: &TempDir) -> Client {
+    let config = ClientConfig {
+        storage_path: temp_dir.path().to_path_buf(),
+        listen_addrs: vec!["/ip4/127.0.0.1/tcp/0".to_string()],
+        bootstrap_peers: vec![],
+        ..Default::default()
+    };
+    Client::new(Keypair::generate(), config).unwrap()
+}
+
+#[tokio::test]
+async fn test_added_user_appears_in_channel_members() {
+    let alice_dir = TempDir::new().unwrap();
+    let bob_dir = TempDir::new().unwrap();
+    let alice = create_client(&alice_dir);
+    let bob = create_client(&bob_dir);
+    alice.start().await.unwrap();
+    bob.start().await.unwrap();
+
+    let alice_addr = alice.listening_addrs().await[0].clone();
+    bob.network_dial(&format!("{}/p2p/{}", alice_addr, alice.peer_id().await)).await.unwrap();
+    sleep(Duration::from_secs(2)).await;
+    bob.publish_key_packages_to_dht().await.unwrap();
+
+    let (space, _, _) = alice.create_space("Members".to_string(), None).await.unwrap();
+    let (channel, _) = alice.create_channel(space.id, "staff".to_string(), None).await.unwrap();
+    assert_eq!(alice.list_channel_members(&channel.id).await, vec![(alice.user_id(), Role::Admin)]);
+
+    // Bob's KeyPackages take a moment to become fetchable
+    let deadline = Instant::now() + Duration::from_secs(30);
+    while let Err(e) = alice.add_to_channel(&channel.id, bob.user_id(), Role::Moderator).await {
+        assert!(Instant::now() < deadline, "could not add Bob to the channel: {}", e);
+        sleep(Duration::from_millis(500)).await;
+    }
+
+    let members = alice.list_channel_members(&channel.id).await;
+    assert_eq!(members.len(), 2);
+    assert!(members.contains(&(alice.user_id(), Role::Admin)));
+    assert!(members.contains(&(bob.user_id(), Role::Moderator)));
+
+    let snapshot = alice.get_dashboard_snapshot("alice").await;
+    let channel_snapshot = snapshot.spaces.iter()
+        .flat_map(|space| &space.channels)
+        .find(|snapshot| snapshot.id == channel.id.to_string())
+        .unwrap();
+    let bob_hex = bob.user_id().to_string();
+    let bob_info = channel_snapshot.members.iter().find(|member| member.user_id == bob_hex).unwrap();
+    assert_eq!(bob_info.role, "Moderator");
+
+    assert!(alice.list_channel_members(&spaceway_core::ChannelId([9u8; 32])).await.is_empty());
+}
@@ -0,0 +1,63 @@
+//! Stress test: concurrent broadcasts must not deadlock with the receive path
+
+use spaceway_core::crypto::signing::Keypair;
+use spaceway_core::types::Role;
+use spaceway_core::{Client, ClientConfig};
+use std::sync::Arc;
+use std::time::Duration;
+use tempfile::TempDir;
+
+fn create_client(temp_dir: &TempDir) -> Client {
+    let config = ClientConfig {
+        storage_path: temp_dir.path().to_path_buf(),
+        listen_addrs: vec!["/ip4/127.0.0.1/tcp/0".to_string()],
+        bootstrap_peers: vec![],
+        ..Default::default()
+    };
+    Client::new(Keypair::generate(), config).unwrap()
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn test_concurrent_broadcasts_do_not_deadlock() {
+    const TASKS: usize = 8;
+    const POSTS: usize = 10;
+
+    let alice_dir = TempDir::new().unwrap();
+    let bob_dir = TempDir::new().unwrap();
+    let alice = Arc::new(create_client(&alice_dir));
+    let bob = Arc::new(create_client(&bob_dir));
+
+    let (space, space_op, _) = alice.create_space("Busy".to_string(), None).await.unwrap();
+    let (channel, channel_op) = alice.create_channel(space.id, "general".to_string(), None).await.unwrap();
+    let (thread, thread_op) = alice.create_thread(space.id, channel.id, None, "Load".to_string()).await.unwrap();
+    let member_op = alice.add_member(space.id, bob.user_id(), Role::Member).await.unwrap();
+    for op in [space_op, channel_op, thread_op, member_op] {
+        bob.handle_incoming_op(op).await.unwrap();
+    }
+    let group_info = alice.channel_group_info(&channel.id).await.unwrap();
+    bob.join_channel_with_group_info(&channel.id, &group_info).await.unwrap();
+
+    let run = async {
+        let mut tasks = Vec::new();
+        for task in 0..TASKS {
+            let alice = Arc::clone(&alice);
+            let bob = Arc::clone(&bob);
+            tasks.push(tokio::spawn(async move {
+                for n in 0..POSTS {
+                    let (_, op) = alice.post_message(space.id, thread.id, format!("{}-{}", task, n)).await.unwrap();
+                    // Bob's receive path takes the same managers while he broadcasts too
+                    bob.handle_incoming_op(op).await.unwrap();
+                    bob.post_message(space.id, thread.id, format!("ack {}-{}", task, n)).await.unwrap();
+                }
+            }));
+        }
+        for task in tasks {
+            task.await.unwrap();
+        }
+    };
+    tokio::time::timeout(Duration::from_secs(120), run).await.expect("broadcasts deadlocked");
+
+    // Thread opener plus every post
+    assert_eq!(alice.list_messages(&thread.id).await.len(), 1 + TASKS * POSTS);
+    assert_eq!(bob.list_messages(&thread.id).await.len(), 1 + 2 * TASKS * POSTS);
+}
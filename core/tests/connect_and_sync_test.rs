@@ -0,0 +1,80 @@
+//! A fresh client catches up to a Space from one peer in a single call
+
+use spaceway_core::crypto::signing::Keypair;
+use spaceway_core::types::Role;
+use spaceway_core::{Client, ClientConfig, Error};
+use tempfile::TempDir;
+use tokio::time::{sleep, Duration};
+
+fn create_client(temp_dir: &TempDir) -> Client {
+    let config = ClientConfig {
+        storage_path: temp_dir.path().to_path_buf(),
+        listen_addrs: vec!["/ip4/127.0.0.1/tcp/0".to_string()],
+        bootstrap_peers: vec![],
+        ..Default::default()
+    };
+    Client::new(Keypair::generate(), config).unwrap()
+}
+
+async fn loopback_addr(client: &Client) -> String {
+    let addr = client.listening_addrs().await
+        .into_iter()
+        .find(|addr| addr.to_string().contains("127.0.0.1"))
+        .expect("Client should listen on loopback");
+    format!("{}/p2p/{}", addr, client.peer_id().await)
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_fresh_client_catches_up_in_one_call() {
+    let alice_dir = TempDir::new().unwrap();
+    let bob_dir = TempDir::new().unwrap();
+    let alice = create_client(&alice_dir);
+    let bob = create_client(&bob_dir);
+    alice.start().await.unwrap();
+    bob.start().await.unwrap();
+    sleep(Duration::from_millis(500)).await;
+
+    let (space, space_op, _) = alice.create_space("Book club".to_string(), None).await.unwrap();
+    alice.update_space_tags(space.id, vec!["books".to_string()], None).await.unwrap();
+    alice.update_space_tags(space.id, vec!["books".to_string(), "reading".to_string()], None).await.unwrap();
+
+    // Answers are sealed for the Space's MLS group, so Bob is admitted first
+    bob.handle_incoming_op(space_op).await.unwrap();
+    bob.network_dial(&loopback_addr(&alice).await).await.unwrap();
+    sleep(Duration::from_secs(1)).await;
+    let bundle = bob.get_key_package_bundle().await.unwrap();
+    alice.add_member_with_key_package_bundle(space.id, bob.user_id(), Role::Member, bundle).await.unwrap();
+    let deadline = tokio::time::Instant::now() + Duration::from_secs(10);
+    while bob.space_epoch(&space.id).await.is_none() {
+        assert!(tokio::time::Instant::now() < deadline, "Bob should have joined the MLS group");
+        sleep(Duration::from_millis(200)).await;
+    }
+
+    bob.connect_and_sync(&loopback_addr(&alice).await, &space.id, Duration::from_secs(20)).await.unwrap();
+
+    let synced = bob.get_space(&space.id).await.unwrap();
+    assert_eq!(synced.name, "Book club");
+    assert_eq!(synced.tags, vec!["books".to_string(), "reading".to_string()]);
+    assert_eq!(bob.space_vector_clock(&space.id).unwrap(), alice.space_vector_clock(&space.id).unwrap());
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_connect_and_sync_times_out_without_an_answer() {
+    let alice_dir = TempDir::new().unwrap();
+    let bob_dir = TempDir::new().unwrap();
+    let alice = create_client(&alice_dir);
+    let bob = create_client(&bob_dir);
+    alice.start().await.unwrap();
+    bob.start().await.unwrap();
+    sleep(Duration::from_millis(500)).await;
+
+    // Alice is reachable but never subscribed to this Space, so nobody answers
+    let (space, _, _) = bob.create_space("Empty room".to_string(), None).await.unwrap();
+    let result = bob.connect_and_sync(&loopback_addr(&alice).await, &space.id, Duration::from_secs(3)).await;
+    assert!(matches!(result, Err(Error::TimedOut(_))));
+
+    // Without a peer ID there's no answer to wait for
+
+    let result = bob.connect_and_sync("/ip4/127.0.0.1/tcp/1", &space.id, Duration::from_secs(3)).await;
+    assert!(matches!(result, Err(Error::Network(_))));
+}
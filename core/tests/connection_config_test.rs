@@ -0,0 +1,50 @@
+//! Idle connection timeout and keepalive from `ClientConfig`
+
+use spaceway_core::network::{ConnectionConfig, GossipConfig, NetworkNode};
+use spaceway_core::{Client, ClientConfig};
+use spaceway_core::crypto::signing::Keypair;
+use std::time::Duration;
+use tempfile::TempDir;
+
+fn custom() -> ConnectionConfig {
+    ConnectionConfig {
+        idle_connection_timeout: Duration::from_secs(300),
+        keepalive_interval: Some(Duration::from_secs(5)),
+        keepalive_timeout: Duration::from_secs(10),
+    }
+}
+
+#[tokio::test]
+async fn test_custom_idle_timeout_is_applied_to_swarm() {
+    let (node, _rx) = NetworkNode::new_with_network_config(vec![], vec![], GossipConfig::default(), custom()).unwrap();
+    assert_eq!(node.connection_config(), custom());
+    assert!(custom().ping_config().is_some());
+
+    // Defaults match what the node used before the settings were exposed
+    let (node, _rx) = NetworkNode::new().unwrap();
+    assert_eq!(node.connection_config().idle_connection_timeout, Duration::from_secs(60));
+
+    let temp_dir = TempDir::new().unwrap();
+    let config = ClientConfig {
+        storage_path: temp_dir.path().to_path_buf(),
+        listen_addrs: vec!["/ip4/127.0.0.1/tcp/0".to_string()],
+        connection: custom(),
+        ..Default::default()
+    };
+    let client = Client::new(Keypair::generate(), config).unwrap();
+    assert_eq!(client.connection_config().await, custom());
+}
+
+#[tokio::test]
+async fn test_keepalive_can_be_disabled_but_not_zeroed() {
+    let disabled = ConnectionConfig { keepalive_interval: None, ..custom() };
+    assert!(disabled.ping_config().is_none());
+    assert!(NetworkNode::new_with_network_config(vec![], vec![], GossipConfig::default(), disabled).is_ok());
+
+    let zero = ConnectionConfig { keepalive_interval: Some(Duration::ZERO), ..custom() };
+    assert!(zero.validate().is_err());
+    assert!(NetworkNode::new_with_network_config(vec![], vec![], GossipConfig::default(), zero).is_err());
+
+    let no_idle = ConnectionConfig { idle_connection_timeout: Duration::ZERO, ..custom() };
+    assert!(no_idle.validate().is_err());
+}
@@ -28,6 +28,7 @@ async fn test_operation_storage_code_path() -> anyhow::Result<()> {
         storage_path: format!("./test-data/crdt-storage-alice-{}", uuid::Uuid::new_v4()).into(),
         listen_addrs: vec!["/ip4/127.0.0.1/tcp/0".to_string()],
         bootstrap_peers: vec![],
+        ..Default::default()
     };
     
     let alice = Client::new(alice_keypair, alice_config)?;
@@ -75,6 +76,7 @@ async fn test_operation_retrieval_code_path() -> anyhow::Result<()> {
         storage_path: format!("./test-data/crdt-retrieval-{}", uuid::Uuid::new_v4()).into(),
         listen_addrs: vec!["/ip4/127.0.0.1/tcp/0".to_string()],
         bootstrap_peers: vec![],
+        ..Default::default()
     };
     
     let client = Client::new(keypair, config)?;
@@ -120,16 +122,19 @@ async fn test_full_operation_replication() -> anyhow::Result<()> {
         listen_addrs: vec!["/ip4/127.0.0.1/tcp/0".to_string()],
         // In production: bootstrap_peers: vec!["<multiaddr>".to_string()],
         bootstrap_peers: vec![],
+        ..Default::default()
     };
     
     let alice = Client::new(alice_keypair, alice_config)?;
     alice.start().await?;
     
-    // Alice creates a Space
-    let (space, _, _) = alice.create_space_with_visibility(
+    // Alice creates a Lightweight Space, whose ops anyone with its ID can read;
+    // an MLS Space's are sealed for members only
+    let (space, _, _) = alice.create_space_with_mode(
         "Test Space".to_string(),
         Some("A space for testing".to_string()),
         SpaceVisibility::Public,
+        SpaceMembershipMode::Lightweight,
     ).await?;
     
     let space_id = space.id;
@@ -152,6 +157,7 @@ async fn test_full_operation_replication() -> anyhow::Result<()> {
         listen_addrs: vec!["/ip4/127.0.0.1/tcp/0".to_string()],
         // In production: same bootstrap peers
         bootstrap_peers: vec![],
+        ..Default::default()
     };
     
     let bob = Client::new(bob_keypair, bob_config)?;
@@ -0,0 +1,60 @@
+//! Integration tests for the dashboard snapshot API
+
+use spaceway_core::{Client, ClientConfig};
+use spaceway_core::crypto::signing::Keypair;
+use tempfile::TempDir;
+
+fn create_client(temp_dir: &TempDir) -> Client {
+    let config = ClientConfig {
+        storage_path: temp_dir.path().to_path_buf(),
+        listen_addrs: vec!["/ip4/127.0.0.1/tcp/0".to_string()],
+        bootstrap_peers: vec![],
+        ..Default::default()
+    };
+    Client::new(Keypair::generate(), config).unwrap()
+}
+
+#[tokio::test]
+async fn test_create_space_produces_timeline_entry() {
+    let temp_dir = TempDir::new().unwrap();
+    let client = create_client(&temp_dir);
+
+    assert!(client.recent_ops(10).unwrap().is_empty());
+
+    let (space, _, _) = client.create_space("Timeline".to_string(), None).await.unwrap();
+
+    let timeline = client.recent_ops(10).unwrap();
+    let entry = timeline.iter()
+        .find(|op| op.op_type == "CreateSpace")
+        .expect("CreateSpace op should appear in the timeline");
+    assert_eq!(entry.space_id, hex::encode(space.id.0));
+    assert_eq!(entry.author, hex::encode(client.user_id().0));
+
+    // Timeline is ordered by HLC
+    assert!(timeline.windows(2).all(|pair| pair[0].hlc <= pair[1].hlc));
+}
+
+#[tokio::test]
+async fn test_dht_storage_snapshot_shows_space_entries() {
+    let temp_dir = TempDir::new().unwrap();
+    let client = create_client(&temp_dir);
+
+    let (space, _, _) = client.create_space("Inspect".to_string(), None).await.unwrap();
+
+    let entries = client.dht_storage_snapshot(&space.id).await;
+    let metadata = entries.iter()
+        .find(|entry| entry.value_type == "space_metadata")
+        .expect("space metadata should be listed");
+    assert!(metadata.size_bytes > 0);
+    assert!(entries.iter().any(|entry| entry.value_type.starts_with("operation_batch")));
+
+    // Entries for other spaces are not included
+    let (other, _, _) = client.create_space("Other".to_string(), None).await.unwrap();
+    let entries = client.dht_storage_snapshot(&space.id).await;
+    assert_eq!(entries.iter().filter(|entry| entry.value_type == "space_metadata").count(), 1);
+    assert!(client.dht_storage_snapshot(&other.id).await.len() >= 2);
+
+    // The dashboard snapshot lists everything this client wrote
+    let snapshot = client.get_dashboard_snapshot("Tester").await;
+    assert_eq!(snapshot.dht_storage.iter().filter(|entry| entry.value_type == "space_metadata").count(), 2);
+}
@@ -0,0 +1,70 @@
+//! A removed member is told it can no longer decrypt, rather than silently
+//! missing messages
+
+use spaceway_core::crypto::signing::Keypair;
+use spaceway_core::{Client, ClientConfig, ClientEvent, DecryptFailReason, Role};
+use tempfile::TempDir;
+use tokio::time::{sleep, timeout, Duration};
+
+fn create_client(temp_dir: &TempDir) -> Client {
+    let config = ClientConfig {
+        storage_path: temp_dir.path().to_path_buf(),
+        listen_addrs: vec!["/ip4/127.0.0.1/tcp/0".to_string()],
+        bootstrap_peers: vec![],
+        ..Default::default()
+    };
+    Client::new(Keypair::generate(), config).unwrap()
+}
+
+#[test]
+fn test_only_epoch_mismatches_are_transient() {
+    assert!(DecryptFailReason::WrongEpoch.is_transient());
+    assert!(!DecryptFailReason::NotAMember.is_transient());
+    assert!(!DecryptFailReason::Corrupt.is_transient());
+    assert!(DecryptFailReason::NotAMember.suggestion().unwrap().contains("sync_space_from_dht"));
+    assert!(DecryptFailReason::Corrupt.suggestion().is_none());
+}
+
+#[tokio::test]
+async fn test_removed_member_reports_not_a_member() {
+    let alice_dir = TempDir::new().unwrap();
+    let bob_dir = TempDir::new().unwrap();
+    let alice = create_client(&alice_dir);
+    let bob = create_client(&bob_dir);
+    alice.start().await.unwrap();
+    bob.start().await.unwrap();
+    sleep(Duration::from_secs(1)).await;
+
+    let alice_addr = alice.listening_addrs().await
+        .into_iter()
+        .find(|addr| addr.to_string().contains("127.0.0.1"))
+        .expect("Alice should listen on loopback");
+    bob.network_dial(&format!("{}/p2p/{}", alice_addr, alice.peer_id().await)).await.unwrap();
+
+    let (space, _, _) = alice.create_space("Members only".to_string(), None).await.unwrap();
+    let (channel, _) = alice.create_channel(space.id, "general".to_string(), None).await.unwrap();
+    let (thread, _) = alice.create_thread(space.id, channel.id, None, "Hello".to_string()).await.unwrap();
+    bob.subscribe_to_space(&space.id).await.unwrap();
+    sleep(Duration::from_secs(2)).await;
+
+    let bob_keypackage = bob.get_key_package_bundle().await.unwrap();
+    alice.add_member_with_key_package_bundle(space.id, bob.user_id(), Role::Member, bob_keypackage).await.unwrap();
+    sleep(Duration::from_secs(3)).await;
+
+    let mut events = bob.subscribe_events();
+    alice.remove_member(space.id, bob.user_id()).await.unwrap();
+    sleep(Duration::from_secs(2)).await;
+    alice.post_message(space.id, thread.id, "after the kick".to_string()).await.unwrap();
+
+    // Epoch mismatches seen before the removal Commit are only transient
+    let space_id = timeout(Duration::from_secs(10), async {
+        loop {
+            if let ClientEvent::DecryptionFailed { space_id, reason: DecryptFailReason::NotAMember } = events.recv().await.unwrap() {
+                break space_id;
+            }
+        }
+    })
+    .await
+    .expect("Bob should learn he can no longer decrypt the Space");
+    assert_eq!(space_id, space.id);
+}
@@ -0,0 +1,101 @@
+//! Received duplicates are dropped from memory before the store is asked
+
+use spaceway_core::crypto::signing::Keypair;
+use spaceway_core::network::{DedupCache, NetworkNode};
+use spaceway_core::types::OpId;
+use spaceway_core::{Client, ClientConfig};
+use std::cell::Cell;
+use std::time::Duration;
+use tempfile::TempDir;
+use uuid::Uuid;
+
+fn create_client(temp_dir: &TempDir) -> Client {
+    let config = ClientConfig {
+        storage_path: temp_dir.path().to_path_buf(),
+        listen_addrs: vec!["/ip4/127.0.0.1/tcp/0".to_string()],
+        bootstrap_peers: vec![],
+        ..Default::default()
+    };
+    Client::new(Keypair::generate(), config).unwrap()
+}
+
+#[test]
+fn test_dedup_cache_evicts_least_recently_seen() {
+    let [a, b, c] = [OpId(Uuid::new_v4()), OpId(Uuid::new_v4()), OpId(Uuid::new_v4())];
+    let lookups = Cell::new(0);
+    let store_has = |known: bool| {
+        lookups.set(lookups.get() + 1);
+        known
+    };
+
+    let mut cache = DedupCache::new(2);
+    assert!(!cache.is_duplicate(&a, || store_has(false)));
+    assert!(!cache.contains(&a), "ops the store doesn't know are not cached");
+    cache.insert(a);
+    cache.insert(b);
+
+    // Seeing `a` again makes `b` the oldest, so `c` pushes `b` out
+    assert!(cache.is_duplicate(&a, || store_has(true)));
+    cache.insert(c);
+    assert!(cache.contains(&a) && cache.contains(&c) && !cache.contains(&b));
+    assert_eq!(lookups.get(), 1);
+
+    // An evicted op is still recognised, at the cost of a store lookup
+    assert!(cache.is_duplicate(&b, || store_has(true)));
+    assert_eq!(lookups.get(), 2);
+
+    let stats = cache.stats();
+    assert_eq!((stats.capacity, stats.len), (2, 2));
+    assert_eq!((stats.hits, stats.misses, stats.evictions), (1, 2, 2));
+
+    // Capacity 0 always falls through to the store
+    let mut disabled = DedupCache::new(0);
+    disabled.insert(a);
+    assert!(disabled.is_duplicate(&a, || store_has(true)));
+    assert_eq!(disabled.stats().hits, 0);
+    assert_eq!(lookups.get(), 3);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_repeated_duplicates_hit_the_cache() {
+    let alice_dir = TempDir::new().unwrap();
+    let bob_dir = TempDir::new().unwrap();
+    let alice = create_client(&alice_dir);
+    let bob = create_client(&bob_dir);
+    bob.start().await.unwrap();
+
+    let (space, space_op, _) = alice.create_space("Echoes".to_string(), None).await.unwrap();
+    bob.handle_incoming_op(space_op.clone()).await.unwrap();
+    bob.subscribe_to_space(&space.id).await.unwrap();
+    assert_eq!(bob.dedup_cache_stats().await.len, 1);
+
+    // A bare node replays the same op to Bob over and over
+    let (mut replayer, _events) = NetworkNode::new().unwrap();
+    let bob_addr = bob.listening_addrs().await
+        .into_iter()
+        .find(|addr| addr.to_string().contains("127.0.0.1"))
+        .expect("Bob should listen on loopback");
+    replayer.dial(format!("{}/p2p/{}", bob_addr, bob.peer_id().await).parse().unwrap()).await.unwrap();
+    let topic = format!("space/{}", space.id.short());
+    replayer.subscribe(&topic).await.unwrap();
+
+    const REPLAYS: u64 = 5;
+    let deadline = tokio::time::Instant::now() + Duration::from_secs(10);
+    while replayer.publish(&topic, space_op.to_canonical_bytes()).await.is_err() {
+        assert!(tokio::time::Instant::now() < deadline, "Bob never joined the topic");
+        tokio::time::sleep(Duration::from_millis(200)).await;
+    }
+    for _ in 1..REPLAYS {
+        replayer.publish(&topic, space_op.to_canonical_bytes()).await.unwrap();
+    }
+
+    while bob.dedup_cache_stats().await.hits < REPLAYS {
+        assert!(tokio::time::Instant::now() < deadline, "replays did not reach Bob");
+        tokio::time::sleep(Duration::from_millis(100)).await;
+    }
+
+    let stats = bob.dedup_cache_stats().await;
+    assert_eq!(stats.hits, REPLAYS);
+    assert_eq!(stats.misses, 0, "no replay should have needed a store lookup");
+    assert_eq!(stats.len, 1);
+}
@@ -0,0 +1,122 @@
+//! Integration tests for delivery acknowledgements
+
+use spaceway_core::crypto::signing::Keypair;
+use spaceway_core::network::ack::{ack_topic, MAX_ACK_BATCH};
+use spaceway_core::network::{Ack, AckBatcher, DeliveryTracker, Stamper};
+use spaceway_core::types::*;
+use spaceway_core::{Client, ClientConfig};
+use tempfile::TempDir;
+
+fn op_id() -> OpId {
+    OpId(uuid::Uuid::new_v4())
+}
+
+#[test]
+fn test_two_receivers_ack_and_sender_sees_both() {
+    let space_id = SpaceId([5u8; 32]);
+    let sent = op_id();
+    let bob = Keypair::generate();
+    let carol = Keypair::generate();
+
+    let mut sender = DeliveryTracker::default();
+    sender.track(sent);
+
+    // Each receiver batches what it saw and publishes one signed ack
+    for receiver in [&bob, &carol] {
+        let mut batcher = AckBatcher::default();
+        batcher.queue(space_id, sent);
+        batcher.queue(space_id, sent);
+        for (space, op_ids) in batcher.drain() {
+            let bytes = Ack::new(receiver, space, op_ids, Stamper::default().next()).unwrap().to_bytes().unwrap();
+            let ack = Ack::from_bytes(&bytes).unwrap();
+            assert!(ack.verify());
+            assert!(sender.record(&ack));
+            assert!(!sender.record(&ack));
+        }
+    }
+
+    assert_eq!(sender.status(&sent), vec![bob.user_id(), carol.user_id()]);
+    assert_eq!(ack_topic(&space_id), format!("space/{}/acks", hex::encode(&space_id.0[..8])));
+}
+
+#[test]
+fn test_forged_and_untracked_acks_are_ignored() {
+    let space_id = SpaceId([6u8; 32]);
+    let sent = op_id();
+    let mut sender = DeliveryTracker::default();
+    sender.track(sent);
+
+    // Claiming to be someone else breaks the signature
+    let mut forged = Ack::new(&Keypair::generate(), space_id, vec![sent], 1).unwrap();
+    forged.from = Keypair::generate().user_id();
+    assert!(!forged.verify());
+
+    // So does bumping the stamp to get past replay protection
+    let mut restamped = Ack::new(&Keypair::generate(), space_id, vec![sent], 1).unwrap();
+    restamped.sent_at = 2;
+    assert!(!restamped.verify());
+
+    // Acks for ops we never sent are not recorded
+    let unknown = op_id();
+    sender.record(&Ack::new(&Keypair::generate(), space_id, vec![unknown], 1).unwrap());
+    assert!(sender.status(&unknown).is_empty());
+    assert!(sender.status(&sent).is_empty());
+}
+
+#[test]
+fn test_acks_from_two_spaces_arrive_out_of_order() {
+    let first_space = SpaceId([12u8; 32]);
+    let second_space = SpaceId([13u8; 32]);
+    let (in_first, in_second) = (op_id(), op_id());
+    let bob = Keypair::generate();
+    let mut stamper = Stamper::default();
+
+    let mut sender = DeliveryTracker::default();
+    sender.track(in_first);
+    sender.track(in_second);
+
+    // Bob acks the first Space, then the second; the acks cross on the way
+    let first_ack = Ack::new(&bob, first_space, vec![in_first], stamper.next()).unwrap();
+    let second_ack = Ack::new(&bob, second_space, vec![in_second], stamper.next()).unwrap();
+    assert!(sender.record(&second_ack));
+    assert!(sender.record(&first_ack));
+    assert_eq!(sender.status(&in_first), vec![bob.user_id()]);
+    assert_eq!(sender.status(&in_second), vec![bob.user_id()]);
+
+    assert!(!sender.record(&first_ack));
+    assert!(!sender.record(&second_ack));
+}
+
+#[test]
+fn test_batches_are_capped() {
+    let space_id = SpaceId([7u8; 32]);
+    let mut batcher = AckBatcher::default();
+    for _ in 0..MAX_ACK_BATCH + 1 {
+        batcher.queue(space_id, op_id());
+    }
+
+    let mut sizes: Vec<usize> = batcher.drain().into_iter().map(|(_, op_ids)| op_ids.len()).collect();
+    sizes.sort();
+    assert_eq!(sizes, vec![1, MAX_ACK_BATCH]);
+    assert!(batcher.drain().is_empty());
+}
+
+#[tokio::test]
+async fn test_sent_op_starts_with_no_acks() {
+    let temp_dir = TempDir::new().unwrap();
+    let config = ClientConfig {
+        storage_path: temp_dir.path().to_path_buf(),
+        listen_addrs: vec!["/ip4/127.0.0.1/tcp/0".to_string()],
+        bootstrap_peers: vec![],
+        delivery_acks: true,
+        ..Default::default()
+    };
+    let client = Client::new(Keypair::generate(), config).unwrap();
+
+    let (space, _, _) = client.create_space("Acked".to_string(), None).await.unwrap();
+    let (channel, _) = client.create_channel(space.id, "general".to_string(), None).await.unwrap();
+    let (thread, _) = client.create_thread(space.id, channel.id, None, "First".to_string()).await.unwrap();
+    let (_, op) = client.post_message(space.id, thread.id, "anyone there?".to_string()).await.unwrap();
+
+    assert!(client.delivery_status(&op.op_id).await.is_empty());
+}
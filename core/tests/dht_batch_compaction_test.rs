@@ -0,0 +1,134 @@
+//! DHT operation log: tail merging and compaction of bloated indices
+//!
+//! A map stands in for the DHT, holding the same encrypted records the client
+//! writes, so batch fetches can be counted.
+
+use spaceway_core::crdt::dht_storage::{BATCH_TARGET_OPS, MAX_INDEX_BATCHES};
+use spaceway_core::crdt::{
+    CrdtOp, EncryptedOperationBatch, Hlc, OpPayload, OpType, OperationBatch, OperationBatchIndex,
+};
+use spaceway_core::types::*;
+use std::collections::HashMap;
+
+fn op(space_id: SpaceId, n: u64) -> CrdtOp {
+    CrdtOp {
+        op_id: OpId(uuid::Uuid::new_v4()),
+        space_id,
+        channel_id: None,
+        thread_id: None,
+        op_type: OpType::PostMessage(OpPayload::PostMessage {
+            message_id: MessageId([n as u8; 32]),
+            content: format!("message {}", n),
+            quote: None,
+        }),
+        prev_ops: vec![],
+        author: UserId([0u8; 32]),
+        epoch: EpochId(0),
+        hlc: Hlc { wall_time: n, logical: 0 },
+        timestamp: n,
+        signature: Signature([0u8; 64]),
+    }
+}
+
+#[derive(Default)]
+struct FakeDht {
+    records: HashMap<Vec<u8>, Vec<u8>>,
+    batch_gets: usize,
+}
+
+impl FakeDht {
+    fn put_batch(&mut self, batch: &OperationBatch) {
+        let encrypted = EncryptedOperationBatch::encrypt(batch).unwrap();
+        self.records.insert(encrypted.dht_key(), encrypted.to_bytes().unwrap());
+    }
+
+    fn get_batch(&mut self, space_id: &SpaceId, sequence: u32) -> Option<OperationBatch> {
+        self.batch_gets += 1;
+        let bytes = self.records.get(&EncryptedOperationBatch::compute_dht_key(space_id, sequence))?;
+        Some(EncryptedOperationBatch::from_bytes(bytes).unwrap().decrypt().unwrap())
+    }
+
+    /// What `Client::dht_put_operations` does for one put
+    fn put_operations(&mut self, index: &mut OperationBatchIndex, ops: Vec<CrdtOp>) {
+        let space_id = index.space_id;
+        let tail = index.batch_sequences.last().and_then(|seq| self.get_batch(&space_id, *seq));
+        let batch = index.append(tail, ops);
+        self.put_batch(&batch);
+
+        if index.needs_compaction() {
+            let batches: Vec<_> = index.batch_sequences.clone().into_iter()
+                .filter_map(|seq| self.get_batch(&space_id, seq))
+                .collect();
+            for batch in index.compact(batches) {
+                self.put_batch(&batch);
+            }
+        }
+    }
+
+    /// What `Client::dht_get_operations` does
+    fn get_operations(&mut self, index: &OperationBatchIndex) -> Vec<CrdtOp> {
+        index.sequences_in(0..usize::MAX).iter()
+            .filter_map(|seq| self.get_batch(&index.space_id, *seq))
+            .flat_map(|batch| batch.operations)
+            .collect()
+    }
+}
+
+fn timestamps(ops: &[CrdtOp]) -> Vec<u64> {
+    ops.iter().map(|op| op.timestamp).collect()
+}
+
+#[test]
+fn test_many_small_puts_fill_few_batches() {
+    let space_id = SpaceId::new();
+    let mut dht = FakeDht::default();
+    let mut index = OperationBatchIndex::new(space_id);
+
+    let total = 500;
+    for n in 0..total {
+        dht.put_operations(&mut index, vec![op(space_id, n)]);
+    }
+    let expected_batches = (total as usize).div_ceil(BATCH_TARGET_OPS);
+    assert_eq!(index.batch_sequences.len(), expected_batches);
+    assert_eq!(index.total_operations, total);
+
+    dht.batch_gets = 0;
+    let ops = dht.get_operations(&index);
+    assert_eq!(timestamps(&ops), (0..total).collect::<Vec<_>>());
+    assert_eq!(dht.batch_gets, expected_batches);
+
+    // A reader can page through the log instead of fetching all of it
+    dht.batch_gets = 0;
+    let first: Vec<_> = index.sequences_in(0..1).iter()
+        .filter_map(|seq| dht.get_batch(&space_id, *seq))
+        .flat_map(|batch| batch.operations)
+        .collect();
+    assert_eq!(timestamps(&first), (0..BATCH_TARGET_OPS as u64).collect::<Vec<_>>());
+    assert_eq!(dht.batch_gets, 1);
+}
+
+#[test]
+fn test_bloated_index_is_compacted_on_next_put() {
+    let space_id = SpaceId::new();
+    let mut dht = FakeDht::default();
+    let mut index = OperationBatchIndex::new(space_id);
+
+    // An index written by the old one-batch-per-op path
+    let legacy = MAX_INDEX_BATCHES as u64 * 4;
+    for n in 0..legacy {
+        let batch = OperationBatch::new(space_id, vec![op(space_id, n)], n as u32 + 1);
+        dht.put_batch(&batch);
+        index.add_batch(batch.sequence, 1);
+    }
+    assert!(index.needs_compaction());
+
+    dht.put_operations(&mut index, vec![op(space_id, legacy)]);
+    assert!(!index.needs_compaction());
+    assert!(index.batch_sequences.len() <= (legacy as usize + 1).div_ceil(BATCH_TARGET_OPS));
+    assert!(index.batch_sequences[0] > legacy as u32, "compacted batches use fresh sequences");
+
+    dht.batch_gets = 0;
+    let ops = dht.get_operations(&index);
+    assert_eq!(timestamps(&ops), (0..=legacy).collect::<Vec<_>>());
+    assert_eq!(dht.batch_gets, index.batch_sequences.len());
+}
@@ -1,6 +1,6 @@
 use spaceway_core::client::{Client, ClientConfig};
 use spaceway_core::crypto::Keypair;
-use spaceway_core::{SpaceVisibility, InvitePermissions, Signature, SpaceId, EpochId};
+use spaceway_core::{SpaceMembershipMode, SpaceVisibility, InvitePermissions, Signature, SpaceId, EpochId};
 use anyhow::Result;
 use tokio;
 use std::path::PathBuf;
@@ -14,6 +14,7 @@ async fn test_store_and_retrieve_space_metadata() -> Result<()> {
         storage_path: PathBuf::from("./test-data/alice-dht-space-1"),
         listen_addrs: vec!["/ip4/127.0.0.1/tcp/0".to_string()],
         bootstrap_peers: vec![],
+        ..Default::default()
     };
     let alice = Client::new(alice_keypair.clone(), alice_config)?;
     
@@ -22,6 +23,7 @@ async fn test_store_and_retrieve_space_metadata() -> Result<()> {
         storage_path: PathBuf::from("./test-data/bob-dht-space-1"),
         listen_addrs: vec!["/ip4/127.0.0.1/tcp/0".to_string()],
         bootstrap_peers: vec![],
+        ..Default::default()
     };
     let bob = Client::new(bob_keypair.clone(), bob_config)?;
     
@@ -32,11 +34,12 @@ async fn test_store_and_retrieve_space_metadata() -> Result<()> {
     // Wait for network initialization
     tokio::time::sleep(tokio::time::Duration::from_millis(500)).await;
     
-    // Alice creates a Space
-    let (space, _, _) = alice.create_space_with_visibility(
+    // Alice creates a Lightweight Space; an MLS Space's metadata is sealed for members
+    let (space, _, _) = alice.create_space_with_mode(
         "Test Space".to_string(),
         Some("A test space for DHT metadata".to_string()),
         SpaceVisibility::Public,
+        SpaceMembershipMode::Lightweight,
     ).await?;
     
     let space_id = space.id.clone();
@@ -67,6 +70,7 @@ async fn test_offline_space_joining() -> Result<()> {
         storage_path: PathBuf::from("./test-data/alice-dht-space-2"),
         listen_addrs: vec!["/ip4/127.0.0.1/tcp/0".to_string()],
         bootstrap_peers: vec![],
+        ..Default::default()
     };
     let alice = Client::new(alice_keypair.clone(), alice_config)?;
     
@@ -75,6 +79,7 @@ async fn test_offline_space_joining() -> Result<()> {
         storage_path: PathBuf::from("./test-data/bob-dht-space-2"),
         listen_addrs: vec!["/ip4/127.0.0.1/tcp/0".to_string()],
         bootstrap_peers: vec![],
+        ..Default::default()
     };
     let bob = Client::new(bob_keypair.clone(), bob_config)?;
     
@@ -85,11 +90,12 @@ async fn test_offline_space_joining() -> Result<()> {
     // Wait for network initialization
     tokio::time::sleep(tokio::time::Duration::from_millis(500)).await;
     
-    // Alice creates a Public Space
-    let (space, _, _) = alice.create_space_with_visibility(
+    // Alice creates a Public, Lightweight Space that anyone with its ID can load
+    let (space, _, _) = alice.create_space_with_mode(
         "Public Space".to_string(),
         Some("Anyone can join".to_string()),
         SpaceVisibility::Public,
+        SpaceMembershipMode::Lightweight,
     ).await?;
     
     let space_id = space.id.clone();
@@ -122,7 +128,6 @@ async fn test_offline_space_joining() -> Result<()> {
 async fn test_space_metadata_signature_verification() -> Result<()> {
     use spaceway_core::forum::{SpaceMetadata, Space};
     use std::collections::HashMap;
-    use ed25519_dalek::SigningKey;
     
     // Create a keypair
     let keypair = Keypair::generate();
@@ -144,8 +149,7 @@ async fn test_space_metadata_signature_verification() -> Result<()> {
     };
     
     // Create metadata with valid signature
-    let signing_key = SigningKey::from_bytes(&keypair.to_bytes());
-    let metadata = SpaceMetadata::from_space(&space, &signing_key);
+    let metadata = SpaceMetadata::from_space(&space, &keypair)?;
     
     // Verify signature is valid
     assert!(metadata.verify_signature());
@@ -159,7 +163,6 @@ async fn test_space_metadata_signature_verification() -> Result<()> {
 async fn test_encrypted_metadata_round_trip() -> Result<()> {
     use spaceway_core::forum::{SpaceMetadata, EncryptedSpaceMetadata, Space};
     use std::collections::HashMap;
-    use ed25519_dalek::SigningKey;
     
     // Create a keypair
     let keypair = Keypair::generate();
@@ -181,8 +184,7 @@ async fn test_encrypted_metadata_round_trip() -> Result<()> {
     };
     
     // Create metadata with signature
-    let signing_key = SigningKey::from_bytes(&keypair.to_bytes());
-    let metadata = SpaceMetadata::from_space(&space, &signing_key);
+    let metadata = SpaceMetadata::from_space(&space, &keypair)?;
     
     // Encrypt
     let encrypted = EncryptedSpaceMetadata::encrypt(&metadata)?;
@@ -0,0 +1,110 @@
+//! Integration tests for Space transcript export
+
+use spaceway_core::{Client, ClientConfig};
+use spaceway_core::crypto::signing::Keypair;
+use spaceway_core::export::{ExportFormat, SpaceTranscript};
+use tempfile::TempDir;
+
+fn create_client(temp_dir: &TempDir) -> Client {
+    let config = ClientConfig {
+        storage_path: temp_dir.path().to_path_buf(),
+        listen_addrs: vec!["/ip4/127.0.0.1/tcp/0".to_string()],
+        bootstrap_peers: vec![],
+        ..Default::default()
+    };
+    Client::new(Keypair::generate(), config).unwrap()
+}
+
+#[tokio::test]
+async fn test_export_space_contains_messages() {
+    let temp_dir = TempDir::new().unwrap();
+    let client = create_client(&temp_dir);
+
+    let (space, _, _) = client.create_space("Book Club".to_string(), None).await.unwrap();
+    let (channel, _) = client.create_channel(space.id, "general".to_string(), None).await.unwrap();
+    let (thread, _) = client.create_thread(
+        space.id,
+        channel.id,
+        Some("Dune".to_string()),
+        "Who has finished it?".to_string(),
+    ).await.unwrap();
+    client.post_message(space.id, thread.id, "The ending was great".to_string()).await.unwrap();
+
+    let json = client.export_space(&space.id, ExportFormat::Json).await.unwrap();
+    let transcript: SpaceTranscript = serde_json::from_slice(&json).unwrap();
+    assert_eq!(transcript.name, "Book Club");
+    assert_eq!(transcript.channels.len(), 1);
+    assert_eq!(transcript.channels[0].name, "general");
+    let thread_export = &transcript.channels[0].threads[0];
+    assert_eq!(thread_export.title.as_deref(), Some("Dune"));
+    assert!(thread_export.messages.iter().any(|m| m.content == "The ending was great"));
+    assert!(thread_export.messages.iter().all(|m| m.author == hex::encode(client.user_id().0)));
+
+    // Exporting unchanged state is byte-for-byte stable
+    assert_eq!(json, client.export_space(&space.id, ExportFormat::Json).await.unwrap());
+
+    let markdown = client.export_space(&space.id, ExportFormat::Markdown).await.unwrap();
+    let markdown = String::from_utf8(markdown).unwrap();
+    assert!(markdown.contains("# Book Club"));
+    assert!(markdown.contains("### Dune"));
+    assert!(markdown.contains("The ending was great"));
+}
+
+#[tokio::test]
+async fn test_import_round_trip() {
+    let alice_dir = TempDir::new().unwrap();
+    let bob_dir = TempDir::new().unwrap();
+    let alice = create_client(&alice_dir);
+    let bob = create_client(&bob_dir);
+
+    let (space, _, _) = alice.create_space("Migrating".to_string(), Some("Old home".to_string())).await.unwrap();
+    let (channel, _) = alice.create_channel(space.id, "general".to_string(), None).await.unwrap();
+    let (thread, _) = alice.create_thread(
+        space.id,
+        channel.id,
+        Some("Plans".to_string()),
+        "Moving to a new identity".to_string(),
+    ).await.unwrap();
+    for text in ["First reply", "Second reply"] {
+        alice.post_message(space.id, thread.id, text.to_string()).await.unwrap();
+    }
+    let exported = alice.export_space(&space.id, ExportFormat::Json).await.unwrap();
+
+    let imported_id = bob.import_transcript(&exported).await.unwrap();
+    assert_ne!(imported_id, space.id);
+
+    let original: SpaceTranscript = serde_json::from_slice(&exported).unwrap();
+    let reimported: SpaceTranscript = serde_json::from_slice(
+        &bob.export_space(&imported_id, ExportFormat::Json).await.unwrap()
+    ).unwrap();
+
+    assert_eq!(reimported.name, "Migrating");
+    assert_eq!(reimported.description.as_deref(), Some("Old home"));
+    let contents = |t: &SpaceTranscript| -> Vec<String> {
+        t.channels.iter()
+            .flat_map(|c| &c.threads)
+            .flat_map(|t| &t.messages)
+            .map(|m| m.content.clone())
+            .collect()
+    };
+    assert_eq!(contents(&reimported), contents(&original));
+
+    // Re-authored by Bob, with Alice kept as the original author
+    let messages = &reimported.channels[0].threads[0].messages;
+    assert!(messages.iter().all(|m| m.author == hex::encode(bob.user_id().0)));
+    assert!(messages.iter().all(|m| {
+        m.origin.as_ref().map(|o| o.author.as_str()) == Some(hex::encode(alice.user_id().0).as_str())
+    }));
+}
+
+#[tokio::test]
+async fn test_import_rejects_malformed_transcript() {
+    let temp_dir = TempDir::new().unwrap();
+    let client = create_client(&temp_dir);
+
+    assert!(client.import_transcript(b"not json").await.is_err());
+    assert!(client.import_transcript(br#"{"version": 1, "name": "Partial"}"#).await.is_err());
+
+    // Nothing is created for a rejected import
+    assert!(client.list_spaces().await.is_empty());
+}
@@ -36,6 +36,7 @@ async fn test_full_relay_based_messaging() {
         storage_path: alice_temp.path().to_path_buf(),
         listen_addrs: vec![],  // No listening = no IP exposure
         bootstrap_peers: vec![],
+        ..Default::default()
     };
     
     let alice = Client::new(alice_keypair, alice_config).unwrap();
@@ -52,6 +53,7 @@ async fn test_full_relay_based_messaging() {
         storage_path: bob_temp.path().to_path_buf(),
         listen_addrs: vec![],  // No listening = no IP exposure
         bootstrap_peers: vec![],
+        ..Default::default()
     };
     
     let bob = Client::new(bob_keypair, bob_config).unwrap();
@@ -329,6 +331,7 @@ async fn test_relay_privacy_guarantees() {
         storage_path: temp.path().to_path_buf(),
         listen_addrs: vec![],
         bootstrap_peers: vec![],
+        ..Default::default()
     };
     
     let client = Client::new(keypair, config).unwrap();
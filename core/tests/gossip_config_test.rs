@@ -0,0 +1,61 @@
+//! GossipSub heartbeat and mesh parameters from `ClientConfig`
+
+use spaceway_core::network::{GossipConfig, NetworkNode};
+use spaceway_core::{Client, ClientConfig};
+use spaceway_core::crypto::signing::Keypair;
+use std::time::Duration;
+use tempfile::TempDir;
+
+fn custom() -> GossipConfig {
+    GossipConfig {
+        heartbeat_interval: Duration::from_millis(250),
+        mesh_n: 4,
+        mesh_n_low: 3,
+        mesh_n_high: 8,
+        history_length: 3,
+        duplicate_cache_time: Duration::from_secs(60),
+    }
+}
+
+#[test]
+fn test_custom_parameters_are_applied() {
+    let config = custom().build().unwrap();
+    assert_eq!(config.heartbeat_interval(), Duration::from_millis(250));
+    assert_eq!(config.mesh_n(), 4);
+    assert_eq!(config.mesh_n_low(), 3);
+    assert_eq!(config.mesh_n_high(), 8);
+    assert_eq!(config.history_length(), 3);
+    assert_eq!(config.duplicate_cache_time(), Duration::from_secs(60));
+
+    // Defaults match what the node used before the settings were exposed
+    let defaults = GossipConfig::default().build().unwrap();
+    assert_eq!(defaults.heartbeat_interval(), Duration::from_secs(1));
+    assert_eq!(defaults.mesh_n(), 2);
+}
+
+#[test]
+fn test_inconsistent_mesh_is_rejected() {
+    let gossip = GossipConfig { mesh_n_low: 6, ..custom() };
+    assert!(gossip.build().is_err());
+    assert!(NetworkNode::new_with_gossip_config(vec![], vec![], gossip).is_err());
+}
+
+#[tokio::test]
+async fn test_client_builds_with_custom_gossip() {
+    let temp_dir = TempDir::new().unwrap();
+    let config = ClientConfig {
+        storage_path: temp_dir.path().to_path_buf(),
+        listen_addrs: vec!["/ip4/127.0.0.1/tcp/0".to_string()],
+        gossip: custom(),
+        ..Default::default()
+    };
+    assert!(Client::new(Keypair::generate(), config).is_ok());
+
+    let bad_dir = TempDir::new().unwrap();
+    let bad = ClientConfig {
+        storage_path: bad_dir.path().to_path_buf(),
+        gossip: GossipConfig { mesh_n_high: 1, ..custom() },
+        ..Default::default()
+    };
+    assert!(Client::new(Keypair::generate(), bad).is_err());
+}
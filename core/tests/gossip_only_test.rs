@@ -0,0 +1,72 @@
+//! With the DHT disabled, Spaces sync over gossip and peer sync alone
+
+use spaceway_core::crypto::signing::Keypair;
+use spaceway_core::types::Role;
+use spaceway_core::{Client, ClientConfig, Error};
+use tempfile::TempDir;
+use tokio::time::{sleep, Duration, Instant};
+
+fn create_client(temp_dir: &TempDir, keypair: Keypair) -> Client {
+    let config = ClientConfig {
+        storage_path: temp_dir.path().to_path_buf(),
+        listen_addrs: vec!["/ip4/127.0.0.1/tcp/0".to_string()],
+        bootstrap_peers: vec![],
+        dht_enabled: false,
+        ..Default::default()
+    };
+    Client::new(keypair, config).unwrap()
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_clients_converge_without_dht() {
+    let alice_dir = TempDir::new().unwrap();
+    let bob_dir = TempDir::new().unwrap();
+    let bob_keypair = Keypair::generate();
+    let alice = create_client(&alice_dir, Keypair::generate());
+    let bob = create_client(&bob_dir, bob_keypair.clone());
+    alice.start().await.unwrap();
+    bob.start().await.unwrap();
+    sleep(Duration::from_millis(500)).await;
+
+    let alice_addr = alice.listening_addrs().await
+        .into_iter()
+        .find(|addr| addr.to_string().contains("127.0.0.1"))
+        .expect("Alice should listen on loopback");
+    bob.network_dial(&format!("{}/p2p/{}", alice_addr, alice.peer_id().await)).await.unwrap();
+    sleep(Duration::from_secs(1)).await;
+
+    let (space, _, _) = alice.create_space("LAN party".to_string(), None).await.unwrap();
+    let (general, _) = alice.create_channel(space.id, "general".to_string(), None).await.unwrap();
+    assert!(matches!(alice.dht_get_space(&space.id).await, Err(Error::Network(_))));
+
+    // Bob catches up from Alice rather than the DHT
+    bob.subscribe_to_space(&space.id).await.unwrap();
+    sleep(Duration::from_secs(2)).await;
+    bob.sync_space_from_dht(space.id).await.unwrap();
+    let deadline = Instant::now() + Duration::from_secs(15);
+    while bob.get_channel(&general.id).await.is_none() {
+        assert!(Instant::now() < deadline, "Bob did not catch up from Alice");
+        sleep(Duration::from_millis(200)).await;
+    }
+
+    // KeyPackages are handed over directly, then new ops arrive live
+    let bundle = bob.get_key_package_bundle().await.unwrap();
+    alice.add_member_with_key_package_bundle(space.id, bob_keypair.user_id(), Role::Member, bundle).await.unwrap();
+    sleep(Duration::from_secs(3)).await;
+    let (random, _) = alice.create_channel(space.id, "random".to_string(), None).await.unwrap();
+    let deadline = Instant::now() + Duration::from_secs(15);
+    while bob.get_channel(&random.id).await.is_none() {
+        assert!(Instant::now() < deadline, "Bob did not receive the new channel");
+        sleep(Duration::from_millis(200)).await;
+    }
+
+    let alice_space = alice.get_space(&space.id).await.unwrap();
+    let bob_space = bob.get_space(&space.id).await.unwrap();
+    assert_eq!(bob_space.name, alice_space.name);
+    assert_eq!(bob_space.owner, alice_space.owner);
+    assert!(bob_space.is_member(&bob_keypair.user_id()));
+
+    // Nothing was written to the DHT along the way
+    assert!(alice.dht_storage_snapshot(&space.id).await.is_empty());
+    assert!(bob.dht_storage_snapshot(&space.id).await.is_empty());
+}
@@ -17,6 +17,9 @@ async fn create_test_client(name: &str) -> (Client, TempDir) {
         storage_path: temp_dir.path().to_path_buf(),
         listen_addrs: vec!["/ip4/127.0.0.1/tcp/0".to_string()],
         bootstrap_peers: vec![],
+        // Peers follow the Space they see announced
+        auto_subscribe_discovered: true,
+        ..Default::default()
     };
     
     let client = Client::new(keypair, config).unwrap();
@@ -81,7 +84,7 @@ async fn test_gossipsub_message_propagation() {
     println!("\nPhase 4: Waiting for GossipSub mesh formation...");
     sleep(Duration::from_secs(3)).await;
     
-    // Bob and Charlie should auto-subscribe via discovery topic
+    // Bob and Charlie auto-subscribe via the discovery topic
     println!("\nPhase 5: Creating channel and thread...");
     
     let (channel, _op) = alice.create_channel(
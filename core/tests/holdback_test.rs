@@ -0,0 +1,59 @@
+//! Ops that arrive before their dependencies are held back and reported
+
+use spaceway_core::crypto::signing::Keypair;
+use spaceway_core::{Client, ClientConfig, ClientEvent};
+use tempfile::TempDir;
+use tokio::time::{timeout, Duration};
+
+fn create_client(temp_dir: &TempDir) -> Client {
+    let config = ClientConfig {
+        storage_path: temp_dir.path().to_path_buf(),
+        listen_addrs: vec!["/ip4/127.0.0.1/tcp/0".to_string()],
+        bootstrap_peers: vec![],
+        op_stuck_after: Duration::ZERO,
+        ..Default::default()
+    };
+    Client::new(Keypair::generate(), config).unwrap()
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_message_before_thread_is_held_back_until_thread_arrives() {
+    let alice_dir = TempDir::new().unwrap();
+    let bob_dir = TempDir::new().unwrap();
+    let alice = create_client(&alice_dir);
+    let bob = create_client(&bob_dir);
+    bob.start().await.unwrap();
+    let mut events = bob.subscribe_events();
+
+    let (space, space_op, _) = alice.create_space("Club".to_string(), None).await.unwrap();
+    let (channel, channel_op) = alice.create_channel(space.id, "general".to_string(), None).await.unwrap();
+    let (thread, thread_op) = alice.create_thread(space.id, channel.id, None, "Hello".to_string()).await.unwrap();
+    let (message, message_op) = alice.post_message(space.id, thread.id, "Anyone?".to_string()).await.unwrap();
+    for op in [space_op, channel_op] {
+        bob.handle_incoming_op(op).await.unwrap();
+    }
+
+    // The message shows up before the thread it belongs to
+    bob.handle_incoming_op(message_op.clone()).await.unwrap();
+    assert!(bob.list_messages(&thread.id).await.is_empty());
+
+    let status = bob.holdback_status().await;
+    assert_eq!(status.len(), 1);
+    assert_eq!(status[0].op_id, message_op.op_id);
+    assert_eq!(status[0].space_id, space.id);
+    assert_eq!(status[0].waiting_on, vec![thread_op.op_id]);
+
+    let stuck = timeout(Duration::from_secs(15), async {
+        loop {
+            if let Ok(ClientEvent::OpStuck(stuck)) = events.recv().await {
+                return stuck;
+            }
+        }
+    }).await.expect("Bob should report the message as stuck");
+    assert_eq!(stuck.op_id, message_op.op_id);
+
+    // Once the thread arrives, the message is applied and leaves the queue
+    bob.handle_incoming_op(thread_op).await.unwrap();
+    assert!(bob.list_messages(&thread.id).await.iter().any(|m| m.id == message.id));
+    assert!(bob.holdback_status().await.is_empty());
+}
@@ -6,6 +6,7 @@ mod integration;
 
 use anyhow::Result;
 use spaceway_core::{Client, ClientConfig, crypto::Keypair};
+use spaceway_core::types::{ChannelId, Role, SpaceId};
 use tokio::time::{sleep, Duration};
 
 /// Helper to create a test client
@@ -16,11 +17,21 @@ async fn create_test_client(_name: &str) -> Result<Client> {
         storage_path: temp_dir.path().to_path_buf(),
         listen_addrs: vec!["/ip4/127.0.0.1/tcp/0".to_string()],
         bootstrap_peers: vec![],
+        ..Default::default()
     };
     
     Ok(Client::new(keypair, config)?)
 }
 
+/// Make `member` a member of the Space who has joined the channel's MLS group
+async fn admit(owner: &Client, member: &Client, space_id: SpaceId, channel_id: &ChannelId) -> Result<()> {
+    let member_op = owner.add_member(space_id, member.user_id(), Role::Member).await?;
+    member.apply_remote_op(&member_op).await?;
+    let group_info = owner.channel_group_info(channel_id).await?;
+    member.join_channel_with_group_info(channel_id, &group_info).await?;
+    Ok(())
+}
+
 #[tokio::test]
 async fn test_single_client_basic_operations() -> Result<()> {
     let name = "test_single_client";
@@ -189,6 +200,7 @@ async fn test_multi_client_sync() -> Result<()> {
     // Bob receives thread creation
     bob.apply_remote_op(&thread_op).await?;
     sleep(Duration::from_millis(100)).await;
+    admit(&alice, &bob, space.id, &channel.id).await?;
     
     // Bob posts a message
     let (bob_msg, bob_msg_op) = bob.post_message(
@@ -252,6 +264,7 @@ async fn test_concurrent_operations() -> Result<()> {
     bob.apply_remote_op(&space_op).await?;
     bob.apply_remote_op(&channel_op).await?;
     bob.apply_remote_op(&thread_op).await?;
+    admit(&alice, &bob, space.id, &channel.id).await?;
     
     // Both post messages concurrently
     let alice_msg_future = alice.post_message(
@@ -323,6 +336,7 @@ async fn test_crdt_commutativity() -> Result<()> {
     client2.apply_remote_op(&space_op).await?;
     client2.apply_remote_op(&channel_op).await?;
     client2.apply_remote_op(&thread_op).await?;
+    admit(&client1, &client2, space.id, &channel.id).await?;
     
     // Create two operations
     let (_msg1, op1) = client1.post_message(space.id, thread.id, "Message 1".to_string()).await?;
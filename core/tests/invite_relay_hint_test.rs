@@ -0,0 +1,99 @@
+//! Invite links carry the creator's relay circuit, so a joiner with nothing
+//! but the link can reach the creator
+
+use spaceway_core::crypto::signing::Keypair;
+use spaceway_core::network::create_relay_server;
+use spaceway_core::types::InviteUri;
+use spaceway_core::{Client, ClientConfig};
+use libp2p::futures::StreamExt;
+use libp2p::swarm::SwarmEvent;
+use std::time::Duration;
+use tempfile::TempDir;
+
+fn create_client(temp_dir: &TempDir) -> Client {
+    let config = ClientConfig {
+        storage_path: temp_dir.path().to_path_buf(),
+        listen_addrs: vec!["/ip4/127.0.0.1/tcp/0".to_string()],
+        bootstrap_peers: vec![],
+        ..Default::default()
+    };
+    Client::new(Keypair::generate(), config).unwrap()
+}
+
+/// Run a relay on loopback and return its `/ip4/.../p2p/{relay}` address
+async fn start_relay() -> String {
+    let mut relay = create_relay_server().unwrap();
+    let relay_peer_id = *relay.local_peer_id();
+    relay.listen_on("/ip4/127.0.0.1/tcp/0".parse().unwrap()).unwrap();
+    let addr = loop {
+        if let SwarmEvent::NewListenAddr { address, .. } = relay.select_next_some().await {
+            break address;
+        }
+    };
+    // Reservations are only granted by a relay that knows its own address
+    relay.add_external_address(addr.clone());
+    tokio::spawn(async move {
+        loop {
+            relay.select_next_some().await;
+        }
+    });
+    format!("{}/p2p/{}", addr, relay_peer_id)
+}
+
+/// Connect to the relay and wait until the reservation is listening
+async fn reserve(client: &Client, relay_addr: &str) {
+    client.connect_to_relay(relay_addr).await.unwrap();
+    let deadline = tokio::time::Instant::now() + Duration::from_secs(10);
+    loop {
+        let listening = client.listening_addrs().await;
+        if listening.iter().any(|addr| addr.to_string().starts_with(relay_addr)) {
+            return;
+        }
+        assert!(tokio::time::Instant::now() < deadline, "relay reservation was not accepted");
+        tokio::time::sleep(Duration::from_millis(100)).await;
+    }
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_joiner_reaches_creator_through_invite_relay_hint() {
+    let alice_dir = TempDir::new().unwrap();
+    let bob_dir = TempDir::new().unwrap();
+    let alice = create_client(&alice_dir);
+    let bob = create_client(&bob_dir);
+    alice.start().await.unwrap();
+    bob.start().await.unwrap();
+
+    let relay_addr = start_relay().await;
+    reserve(&alice, &relay_addr).await;
+    let alice_peer_id = alice.peer_id().await;
+    let circuit = format!("{}/p2p-circuit/p2p/{}", relay_addr, alice_peer_id);
+    assert_eq!(alice.relay_addresses().await, vec![circuit.clone()]);
+
+    let (space, _, _) = alice.create_space("Hinted".to_string(), None).await.unwrap();
+    alice.create_invite(space.id, None, None, None, None).await.unwrap();
+    let invite = alice.list_invites(&space.id).await.remove(0);
+    assert_eq!(invite.relay_hints, vec![circuit.clone()]);
+    let uri = invite.to_uri();
+    assert_eq!(InviteUri::parse(&uri).unwrap().hints, vec![circuit]);
+
+    // Bob has never been connected to Alice; the link is all he has
+    assert!(bob.peer_details().await.is_empty());
+    bob.join_with_uri(&uri).await.unwrap();
+    assert!(bob.peer_details().await.iter().any(|peer| peer.peer_id == alice_peer_id));
+    assert!(bob.get_space(&space.id).await.is_some());
+
+    let deadline = tokio::time::Instant::now() + Duration::from_secs(15);
+    while !alice.list_members(&space.id).await.iter().any(|(user, _)| *user == bob.user_id()) {
+        assert!(tokio::time::Instant::now() < deadline, "Alice never saw Bob's join");
+        tokio::time::sleep(Duration::from_millis(200)).await;
+    }
+
+    // After moving to another relay, new links point there
+    let next_relay = start_relay().await;
+    reserve(&alice, &next_relay).await;
+    let refreshed = alice.list_invites(&space.id).await.remove(0);
+    assert_eq!(
+        refreshed.relay_hints,
+        vec![format!("{}/p2p-circuit/p2p/{}", next_relay, alice_peer_id)]
+    );
+}
@@ -10,6 +10,7 @@ fn create_test_client(_name: &str) -> Result<Client> {
         storage_path: temp_dir.path().to_path_buf(),
         listen_addrs: vec!["/ip4/127.0.0.1/tcp/0".to_string()],
         bootstrap_peers: vec![],
+        ..Default::default()
     };
     
     Ok(Client::new(keypair, config)?)
@@ -28,6 +29,8 @@ async fn test_create_invite() -> Result<()> {
         space.id,
         Some(10),  // max 10 uses
         Some(24),  // expires in 24 hours
+        None,      // default role
+        None,      // random code
     ).await?;
     
     assert!(invite_op.op_id.0.as_bytes().len() > 0);
@@ -60,6 +63,8 @@ async fn test_create_unlimited_invite() -> Result<()> {
         space.id,
         None,  // unlimited uses
         None,  // never expires
+        None,  // default role
+        None,  // random code
     ).await?;
     
     let invites = admin.list_invites(&space.id).await;
@@ -78,7 +83,7 @@ async fn test_revoke_invite() -> Result<()> {
     let (space, _, _) = admin.create_space("Test Space".to_string(), None).await?;
     
     // Create invite
-    admin.create_invite(space.id, Some(5), Some(24)).await?;
+    admin.create_invite(space.id, Some(5), Some(24), None, None).await?;
     
     let invites = admin.list_invites(&space.id).await;
     let invite_id = invites[0].id;
@@ -110,7 +115,7 @@ async fn test_join_with_invite() -> Result<()> {
     joiner.handle_incoming_op(space_op).await?;
     
     // Admin creates invite
-    let invite_op = admin.create_invite(space.id, Some(1), Some(24)).await?;
+    let invite_op = admin.create_invite(space.id, Some(1), Some(24), None, None).await?;
     
     // Joiner receives the invite creation operation
     joiner.handle_incoming_op(invite_op).await?;
@@ -146,7 +151,7 @@ async fn test_invite_max_uses() -> Result<()> {
     joiner2.handle_incoming_op(space_op).await?;
     
     // Create invite with max 1 use
-    let invite_op = admin.create_invite(space.id, Some(1), None).await?;
+    let invite_op = admin.create_invite(space.id, Some(1), None, None, None).await?;
     
     // Both joiners receive the invite
     joiner1.handle_incoming_op(invite_op.clone()).await?;
@@ -179,7 +184,7 @@ async fn test_revoked_invite_cannot_be_used() -> Result<()> {
     let (space, _, _) = admin.create_space("Test Space".to_string(), None).await?;
     
     // Create and immediately revoke invite
-    admin.create_invite(space.id, Some(5), None).await?;
+    admin.create_invite(space.id, Some(5), None, None, None).await?;
     let invites = admin.list_invites(&space.id).await;
     let invite_id = invites[0].id;
     let invite_code = invites[0].code.clone();
@@ -214,7 +219,7 @@ async fn test_already_member_cannot_use_invite() -> Result<()> {
     let (space, _, _) = admin.create_space("Test Space".to_string(), None).await?;
     
     // Admin creates invite
-    admin.create_invite(space.id, Some(5), None).await?;
+    admin.create_invite(space.id, Some(5), None, None, None).await?;
     let invites = admin.list_invites(&space.id).await;
     let invite_code = invites[0].code.clone();
     
@@ -225,15 +230,201 @@ async fn test_already_member_cannot_use_invite() -> Result<()> {
     Ok(())
 }
 
+#[tokio::test]
+async fn test_role_scoped_invite() -> Result<()> {
+    let admin = create_test_client("test_role_invite_admin")?;
+    let joiner = create_test_client("test_role_invite_user")?;
+    
+    let (space, space_op, _) = admin.create_space("Test Space".to_string(), None).await?;
+    joiner.handle_incoming_op(space_op).await?;
+    
+    let roles = admin.get_space(&space.id).await.unwrap().roles;
+    let role_named = |name: &str| roles.values().find(|r| r.name == name).unwrap().id;
+    let moderator = role_named("Moderator");
+    let admin_role = role_named("Admin");
+    
+    // Admin creates an invite that grants Moderator
+    let invite_op = admin.create_invite(space.id, Some(1), None, Some(moderator), None).await?;
+    joiner.handle_incoming_op(invite_op).await?;
+    
+    let invite_code = joiner.list_invites(&space.id).await[0].code.clone();
+    let join_op = joiner.join_with_invite(space.id, invite_code).await?;
+    
+    // The admin's replica applies the join and lands the joiner in Moderator
+    admin.handle_incoming_op(join_op).await?;
+    let admin_view = admin.get_space(&space.id).await.unwrap();
+    assert_eq!(admin_view.member_roles.get(&joiner.user_id()), Some(&moderator));
+    
+    let joiner_view = joiner.get_space(&space.id).await.unwrap();
+    assert_eq!(joiner_view.member_roles.get(&joiner.user_id()), Some(&moderator));
+    
+    // A moderator cannot hand out a role above their own
+    let result = joiner.create_invite(space.id, None, None, Some(admin_role), None).await;
+    assert!(result.is_err());
+    
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_custom_invite_code() -> Result<()> {
+    let admin = create_test_client("test_custom_code_admin")?;
+    let joiner = create_test_client("test_custom_code_user")?;
+    
+    let (space, space_op, _) = admin.create_space("Test Space".to_string(), None).await?;
+    joiner.handle_incoming_op(space_op).await?;
+    
+    let invite_op = admin.create_invite(space.id, None, None, None, Some("welcome".to_string())).await?;
+    joiner.handle_incoming_op(invite_op).await?;
+    assert_eq!(admin.list_invites(&space.id).await[0].code, "welcome");
+    
+    // Codes must be well-formed and unique among active invites
+    assert!(admin.create_invite(space.id, None, None, None, Some("not ok".to_string())).await.is_err());
+    assert!(admin.create_invite(space.id, None, None, None, Some("welcome".to_string())).await.is_err());
+    
+    joiner.join_with_invite(space.id, "welcome".to_string()).await?;
+    assert_eq!(joiner.list_invites(&space.id).await[0].uses, 1);
+    
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_concurrent_custom_codes_converge() -> Result<()> {
+    let admin = create_test_client("test_code_race_admin")?;
+    let moderator = create_test_client("test_code_race_mod")?;
+    
+    let (space, space_op, _) = admin.create_space("Test Space".to_string(), None).await?;
+    moderator.handle_incoming_op(space_op).await?;
+    
+    let roles = admin.get_space(&space.id).await.unwrap().roles;
+    let moderator_role = roles.values().find(|r| r.name == "Moderator").unwrap().id;
+    let invite_op = admin.create_invite(space.id, Some(1), None, Some(moderator_role), None).await?;
+    moderator.handle_incoming_op(invite_op).await?;
+    let code = moderator.list_invites(&space.id).await[0].code.clone();
+    let join_op = moderator.join_with_invite(space.id, code).await?;
+    admin.handle_incoming_op(join_op).await?;
+    
+    // Both claim the same vanity code before seeing each other's invite
+    let admin_op = admin.create_invite(space.id, None, None, None, Some("welcome".to_string())).await?;
+    let moderator_op = moderator.create_invite(space.id, None, None, None, Some("welcome".to_string())).await?;
+    
+    let admin_result = admin.handle_incoming_op(moderator_op).await;
+    let moderator_result = moderator.handle_incoming_op(admin_op).await;
+    assert!(admin_result.is_err() != moderator_result.is_err());
+    
+    // Both replicas keep the same single winner
+    let active = |invites: Vec<spaceway_core::types::Invite>| -> Vec<_> {
+        invites.into_iter().filter(|i| i.code == "welcome" && !i.revoked).map(|i| i.id).collect()
+    };
+    let admin_view = active(admin.list_invites(&space.id).await);
+    let moderator_view = active(moderator.list_invites(&space.id).await);
+    assert_eq!(admin_view.len(), 1);
+    assert_eq!(admin_view, moderator_view);
+    
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_invite_usage_lists_joiners() -> Result<()> {
+    let admin = create_test_client("test_usage_admin")?;
+    let first = create_test_client("test_usage_first")?;
+    let second = create_test_client("test_usage_second")?;
+    
+    let (space, space_op, _) = admin.create_space("Test Space".to_string(), None).await?;
+    let invite_op = admin.create_invite(space.id, None, None, None, None).await?;
+    for joiner in [&first, &second] {
+        joiner.handle_incoming_op(space_op.clone()).await?;
+        joiner.handle_incoming_op(invite_op.clone()).await?;
+    }
+    
+    let invite = admin.list_invites(&space.id).await.remove(0);
+    let first_join = first.join_with_invite(space.id, invite.code.clone()).await?;
+    let second_join = second.join_with_invite(space.id, invite.code.clone()).await?;
+    
+    // Joins arrive in different orders on different replicas
+    admin.handle_incoming_op(second_join.clone()).await?;
+    admin.handle_incoming_op(first_join.clone()).await?;
+    second.handle_incoming_op(first_join).await?;
+    
+    let usage = admin.invite_usage(space.id, invite.id).await?;
+    let users: Vec<_> = usage.iter().map(|(user, _)| *user).collect();
+    assert_eq!(usage.len(), 2);
+    assert!(users.contains(&first.user_id()));
+    assert!(users.contains(&second.user_id()));
+    
+    let replica = second.get_space(&space.id).await.unwrap().invites[&invite.id].used_by.clone();
+    assert_eq!(replica, usage);
+    
+    // Plain members cannot see who joined
+    assert!(second.invite_usage(space.id, invite.id).await.is_err());
+    
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_invite_uri_round_trip() -> Result<()> {
+    use spaceway_core::types::InviteUri;
+    
+    let admin = create_test_client("test_uri_admin")?;
+    let joiner = create_test_client("test_uri_user")?;
+    
+    let (space, space_op, _) = admin.create_space("Test Space".to_string(), None).await?;
+    joiner.handle_incoming_op(space_op).await?;
+    let invite_op = admin.create_invite(space.id, None, None, None, None).await?;
+    joiner.handle_incoming_op(invite_op).await?;
+    
+    let invite = admin.list_invites(&space.id).await.remove(0);
+    let uri = invite.to_uri();
+    assert_eq!(uri, format!("descord://join/{}?code={}", hex::encode(space.id.0), invite.code));
+    
+    let parsed = InviteUri::parse(&uri)?;
+    assert_eq!(parsed, InviteUri::new(space.id, invite.code.clone(), None));
+    
+    let relay = "/ip4/127.0.0.1/tcp/9000";
+    let with_relay = InviteUri::parse(&invite.to_uri_with_relay(relay))?;
+    assert_eq!(with_relay.relay.as_deref(), Some(relay));
+    assert_eq!(with_relay.to_string(), invite.to_uri_with_relay(relay));
+    
+    // The joiner can use the link directly
+    joiner.join_with_uri(&uri).await?;
+    assert_eq!(joiner.list_invites(&space.id).await[0].uses, 1);
+    
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_malformed_invite_uri_rejected() -> Result<()> {
+    use spaceway_core::types::InviteUri;
+    
+    let space_hex = hex::encode([7u8; 32]);
+    let malformed = [
+        format!("https://join/{}?code=ABCD1234", space_hex),
+        format!("descord://join/{}?code=ABCD1234", &space_hex[..16]),
+        format!("descord://join/{}?code=ABCD1234", "zz".repeat(32)),
+        format!("descord://join/{}", space_hex),
+        format!("descord://join/{}?code=", space_hex),
+        format!("descord://join/{}?code=bad%20code", space_hex),
+        format!("descord://join/{}?code=ABCD1234&extra=1", space_hex),
+    ];
+    
+    for uri in &malformed {
+        assert!(InviteUri::parse(uri).is_err(), "accepted malformed link {}", uri);
+    }
+    
+    let joiner = create_test_client("test_bad_uri_user")?;
+    assert!(joiner.join_with_uri(&malformed[1]).await.is_err());
+    
+    Ok(())
+}
+
 #[tokio::test]
 async fn test_multiple_invites() -> Result<()> {
     let admin = create_test_client("test_multiple_invites")?;
     let (space, _, _) = admin.create_space("Test Space".to_string(), None).await?;
     
     // Create multiple invites
-    admin.create_invite(space.id, Some(1), Some(24)).await?;
-    admin.create_invite(space.id, Some(5), Some(48)).await?;
-    admin.create_invite(space.id, None, None).await?;
+    admin.create_invite(space.id, Some(1), Some(24), None, None).await?;
+    admin.create_invite(space.id, Some(5), Some(48), None, None).await?;
+    admin.create_invite(space.id, None, None, None, None).await?;
     
     let invites = admin.list_invites(&space.id).await;
     assert_eq!(invites.len(), 3);
@@ -273,6 +464,9 @@ async fn test_invite_validation() -> Result<()> {
         uses: 0,
         created_at: current_time,
         revoked: false,
+        role: None,
+        used_by: vec![],
+        relay_hints: vec![],
     };
     
     assert!(invite.is_valid(current_time));
@@ -0,0 +1,106 @@
+//! Posting in a channel requires joining its MLS group first
+
+use spaceway_core::crypto::signing::Keypair;
+use spaceway_core::{Client, ClientConfig, Error, Role};
+use tempfile::TempDir;
+use tokio::time::{sleep, timeout, Duration};
+
+fn create_client(temp_dir: &TempDir) -> Client {
+    let config = ClientConfig {
+        storage_path: temp_dir.path().to_path_buf(),
+        listen_addrs: vec!["/ip4/127.0.0.1/tcp/0".to_string()],
+        bootstrap_peers: vec![],
+        ..Default::default()
+    };
+    Client::new(Keypair::generate(), config).unwrap()
+}
+
+#[tokio::test]
+async fn test_posting_requires_joining_the_channel() {
+    let alice_dir = TempDir::new().unwrap();
+    let bob_dir = TempDir::new().unwrap();
+    let alice = create_client(&alice_dir);
+    let bob = create_client(&bob_dir);
+
+    let (space, space_op, _) = alice.create_space("Club".to_string(), None).await.unwrap();
+    let (channel, channel_op) = alice.create_channel(space.id, "general".to_string(), None).await.unwrap();
+    let (thread, thread_op) = alice.create_thread(space.id, channel.id, None, "Hello".to_string()).await.unwrap();
+    let member_op = alice.add_member(space.id, bob.user_id(), Role::Member).await.unwrap();
+    for op in [space_op, channel_op, thread_op, member_op] {
+        bob.handle_incoming_op(op).await.unwrap();
+    }
+
+    // Nothing joins Bob behind his back
+    let result = bob.post_message(space.id, thread.id, "hi".to_string()).await;
+    assert!(matches!(result, Err(Error::Permission(ref e)) if e.contains("join_channel")));
+    assert_eq!(bob.list_messages(&thread.id).await.len(), 1);
+
+    let group_info = alice.channel_group_info(&channel.id).await.unwrap();
+    bob.join_channel_with_group_info(&channel.id, &group_info).await.unwrap();
+    assert!(bob.get_channel(&channel.id).await.unwrap().is_member(&bob.user_id()));
+
+    let (message, op) = bob.post_message(space.id, thread.id, "hi".to_string()).await.unwrap();
+    alice.handle_incoming_op(op).await.unwrap();
+    assert!(alice.list_messages(&thread.id).await.iter().any(|m| m.id == message.id));
+
+    // Joining again is a no-op
+    bob.join_channel_with_group_info(&channel.id, &group_info).await.unwrap();
+}
+
+#[tokio::test]
+async fn test_only_space_members_can_join() {
+    let alice_dir = TempDir::new().unwrap();
+    let eve_dir = TempDir::new().unwrap();
+    let alice = create_client(&alice_dir);
+    let eve = create_client(&eve_dir);
+
+    let (space, space_op, _) = alice.create_space("Club".to_string(), None).await.unwrap();
+    let (channel, channel_op) = alice.create_channel(space.id, "general".to_string(), None).await.unwrap();
+    for op in [space_op, channel_op] {
+        eve.handle_incoming_op(op).await.unwrap();
+    }
+
+    let group_info = alice.channel_group_info(&channel.id).await.unwrap();
+    let result = eve.join_channel_with_group_info(&channel.id, &group_info).await;
+    assert!(matches!(result, Err(Error::Permission(_))));
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_join_channel_from_dht_and_post() {
+    let alice_dir = TempDir::new().unwrap();
+    let bob_dir = TempDir::new().unwrap();
+    let alice = create_client(&alice_dir);
+    let bob = create_client(&bob_dir);
+    alice.start().await.unwrap();
+    bob.start().await.unwrap();
+    sleep(Duration::from_millis(500)).await;
+
+    let alice_addr = alice.listening_addrs().await
+        .into_iter()
+        .find(|addr| addr.to_string().contains("127.0.0.1"))
+        .expect("Alice should listen on loopback");
+    bob.network_dial(&format!("{}/p2p/{}", alice_addr, alice.peer_id().await)).await.unwrap();
+    sleep(Duration::from_secs(1)).await;
+
+    // Creating the channel publishes its GroupInfo
+    let (space, space_op, _) = alice.create_space("Club".to_string(), None).await.unwrap();
+    let (channel, channel_op) = alice.create_channel(space.id, "general".to_string(), None).await.unwrap();
+    let (thread, thread_op) = alice.create_thread(space.id, channel.id, None, "Hello".to_string()).await.unwrap();
+    let member_op = alice.add_member(space.id, bob.user_id(), Role::Member).await.unwrap();
+    for op in [space_op, channel_op, thread_op, member_op] {
+        bob.handle_incoming_op(op).await.unwrap();
+    }
+    bob.subscribe_to_space(&space.id).await.unwrap();
+    sleep(Duration::from_secs(2)).await;
+
+    bob.join_channel(&channel.id).await.unwrap();
+    sleep(Duration::from_secs(2)).await;
+
+    // Alice merged Bob's external Commit, so she can read what he seals
+    let (message, _) = bob.post_message(space.id, thread.id, "made it".to_string()).await.unwrap();
+    timeout(Duration::from_secs(10), async {
+        while !alice.list_messages(&thread.id).await.iter().any(|m| m.id == message.id) {
+            sleep(Duration::from_millis(200)).await;
+        }
+    }).await.expect("Alice should receive Bob's message");
+}
@@ -0,0 +1,78 @@
+//! Join failures come back as distinct `JoinError` variants
+
+use spaceway_core::crypto::signing::Keypair;
+use spaceway_core::{Client, ClientConfig, Error, JoinError, SpaceId};
+use tempfile::TempDir;
+
+fn create_client(temp_dir: &TempDir) -> Client {
+    let config = ClientConfig {
+        storage_path: temp_dir.path().to_path_buf(),
+        listen_addrs: vec!["/ip4/127.0.0.1/tcp/0".to_string()],
+        bootstrap_peers: vec![],
+        ..Default::default()
+    };
+    Client::new(Keypair::generate(), config).unwrap()
+}
+
+fn join_error<T: std::fmt::Debug>(result: spaceway_core::Result<T>) -> JoinError {
+    match result {
+        Err(Error::Join(e)) => e,
+        other => panic!("expected a join error, got {:?}", other),
+    }
+}
+
+#[tokio::test]
+async fn test_bad_code_and_unusable_invites() {
+    let admin_dir = TempDir::new().unwrap();
+    let joiner_dir = TempDir::new().unwrap();
+    let admin = create_client(&admin_dir);
+    let joiner = create_client(&joiner_dir);
+
+    let (space, space_op, _) = admin.create_space("Gated".to_string(), None).await.unwrap();
+    joiner.handle_incoming_op(space_op.clone()).await.unwrap();
+    let invite_op = admin.create_invite(space.id, None, None, None, Some("open".to_string())).await.unwrap();
+    joiner.handle_incoming_op(invite_op.clone()).await.unwrap();
+
+    let bad_code = joiner.join_with_invite(space.id, "not-a-code".to_string()).await;
+    assert_eq!(join_error(bad_code), JoinError::InvalidCode);
+
+    joiner.join_with_invite(space.id, "open".to_string()).await.unwrap();
+    let again = joiner.join_with_invite(space.id, "open".to_string()).await;
+    assert_eq!(join_error(again), JoinError::AlreadyMember);
+
+    let invite_id = admin.list_invites(&space.id).await[0].id;
+    let revoke_op = admin.revoke_invite(space.id, invite_id).await.unwrap();
+    let late_dir = TempDir::new().unwrap();
+    let late = create_client(&late_dir);
+    for op in [space_op, invite_op, revoke_op] {
+        late.handle_incoming_op(op).await.unwrap();
+    }
+    let revoked = late.join_with_invite(space.id, "open".to_string()).await;
+    assert_eq!(join_error(revoked), JoinError::InviteNoLongerValid);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_missing_space_vs_no_network() {
+    let alice_dir = TempDir::new().unwrap();
+    let bob_dir = TempDir::new().unwrap();
+    let alice = create_client(&alice_dir);
+    let bob = create_client(&bob_dir);
+    alice.start().await.unwrap();
+    bob.start().await.unwrap();
+    let unknown = SpaceId([9; 32]);
+
+    // Nobody to ask: the network is the problem, not the Space
+    let offline = bob.join_with_invite(unknown, "open".to_string()).await;
+    assert!(matches!(join_error(offline), JoinError::Unreachable(_)));
+
+    let alice_addr = alice.listening_addrs().await
+        .into_iter()
+        .find(|addr| addr.to_string().contains("127.0.0.1"))
+        .expect("Alice should listen on loopback");
+    bob.network_dial(&format!("{}/p2p/{}", alice_addr, alice.peer_id().await)).await.unwrap();
+    tokio::time::sleep(std::time::Duration::from_secs(1)).await;
+
+    // Alice is reachable but has never heard of the Space
+    let missing = bob.join_with_invite(unknown, "open".to_string()).await;
+    assert_eq!(join_error(missing), JoinError::SpaceNotFound(unknown));
+}
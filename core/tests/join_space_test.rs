@@ -0,0 +1,78 @@
+//! Integration test: joining a Space with `Client::join_space`
+//!
+//! Bob only knows the Space ID and an invite code. Everything else (Space
+//! state, membership on Alice's side, the MLS Welcome) has to arrive over
+//! the network.
+
+use spaceway_core::{Client, ClientConfig};
+use spaceway_core::crypto::signing::Keypair;
+use std::future::Future;
+use std::time::Duration;
+use tempfile::TempDir;
+use tokio::time::{sleep, Instant};
+
+fn create_client(temp_dir: &TempDir) -> Client {
+    let config = ClientConfig {
+        storage_path: temp_dir.path().to_path_buf(),
+        listen_addrs: vec!["/ip4/127.0.0.1/tcp/0".to_string()],
+        bootstrap_peers: vec![],
+        ..Default::default()
+    };
+    Client::new(Keypair::generate(), config).unwrap()
+}
+
+/// Poll `check` until it holds or `timeout` passes
+async fn wait_until<F, Fut>(timeout: Duration, mut check: F) -> bool
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = bool>,
+{
+    let deadline = Instant::now() + timeout;
+    while Instant::now() < deadline {
+        if check().await {
+            return true;
+        }
+        sleep(Duration::from_millis(250)).await;
+    }
+    false
+}
+
+#[tokio::test]
+async fn test_join_space_over_network() {
+    let alice_dir = TempDir::new().unwrap();
+    let bob_dir = TempDir::new().unwrap();
+    let alice = create_client(&alice_dir);
+    let bob = create_client(&bob_dir);
+    alice.start().await.unwrap();
+    bob.start().await.unwrap();
+
+    let alice_addr = alice.listening_addrs().await[0].clone();
+    bob.network_dial(&format!("{}/p2p/{}", alice_addr, alice.peer_id().await)).await.unwrap();
+    sleep(Duration::from_secs(2)).await;
+
+    let (space, _, _) = alice.create_space("Garden".to_string(), None).await.unwrap();
+    alice.create_channel(space.id, "general".to_string(), None).await.unwrap();
+    alice.create_invite(space.id, Some(1), None, None, None).await.unwrap();
+    let code = alice.list_invites(&space.id).await[0].code.clone();
+
+    let joined = bob.join_space(space.id, &code).await.unwrap();
+    assert_eq!(joined.name, "Garden");
+    let synced = wait_until(Duration::from_secs(10), || async {
+        bob.list_channels(&space.id).await.len() == 1
+    }).await;
+    assert!(synced, "Bob never caught up on the Space's channels");
+
+    // Alice sees Bob through the UseInvite op alone
+    let bob_id = bob.user_id();
+    let listed = wait_until(Duration::from_secs(10), || async {
+        alice.list_members(&space.id).await.iter().any(|(user_id, _)| *user_id == bob_id)
+    }).await;
+    assert!(listed, "Alice never saw Bob join");
+
+    // Alice admits Bob to MLS without being asked, and Bob applies the Welcome
+    let admitted = wait_until(Duration::from_secs(30), || async {
+        bob.space_epoch(&space.id).await.is_some()
+    }).await;
+    assert!(admitted, "Bob never received the MLS Welcome");
+    assert!(alice.mls_members(&space.id).await.contains(&bob_id));
+}
@@ -0,0 +1,56 @@
+//! KeyPackage pool size, refill and one-time use
+
+use spaceway_core::crypto::signing::Keypair;
+use spaceway_core::{Client, ClientConfig};
+use std::collections::HashSet;
+use tempfile::TempDir;
+
+fn create_client(temp_dir: &TempDir, key_package_pool: usize) -> Client {
+    let config = ClientConfig {
+        storage_path: temp_dir.path().to_path_buf(),
+        listen_addrs: vec!["/ip4/127.0.0.1/tcp/0".to_string()],
+        bootstrap_peers: vec![],
+        key_package_pool,
+        ..Default::default()
+    };
+    Client::new(Keypair::generate(), config).unwrap()
+}
+
+#[tokio::test]
+async fn test_pool_refills_after_packages_are_consumed() {
+    let temp_dir = TempDir::new().unwrap();
+    let client = create_client(&temp_dir, 4);
+    assert_eq!(client.available_keypackages().await, 4);
+
+    let mut handed_out = HashSet::new();
+    for remaining in [3, 2] {
+        let bundle = client.get_key_package_bundle().await.unwrap();
+        assert!(handed_out.insert(bundle.key_package_bytes));
+        assert_eq!(client.available_keypackages().await, remaining);
+    }
+
+    // Dropping below half the pool tops it back up
+    let bundle = client.get_key_package_bundle().await.unwrap();
+    assert!(handed_out.insert(bundle.key_package_bytes));
+    assert_eq!(client.available_keypackages().await, 4);
+
+    // None of the refilled packages repeats one already handed out
+    for _ in 0..4 {
+        let bundle = client.get_key_package_bundle().await.unwrap();
+        assert!(handed_out.insert(bundle.key_package_bytes), "KeyPackage handed out twice");
+    }
+}
+
+#[tokio::test]
+async fn test_empty_pool_generates_on_demand() {
+    let temp_dir = TempDir::new().unwrap();
+    let client = create_client(&temp_dir, 0);
+    assert_eq!(client.available_keypackages().await, 0);
+
+    assert!(client.get_key_package_bundle().await.is_ok());
+    assert_eq!(client.available_keypackages().await, 0);
+
+    assert_eq!(client.ensure_keypackages(3).await.unwrap(), 3);
+    assert_eq!(client.ensure_keypackages(2).await.unwrap(), 0);
+    assert_eq!(client.available_keypackages().await, 3);
+}
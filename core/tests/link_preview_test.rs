@@ -0,0 +1,106 @@
+//! Integration tests for pluggable link previews
+
+use spaceway_core::{Client, ClientConfig};
+use spaceway_core::crypto::signing::Keypair;
+use spaceway_core::forum::{LinkPreview, LinkPreviewProvider, PreviewFuture};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::Duration;
+use tempfile::TempDir;
+
+/// Provider that answers every URL with a fixed title
+#[derive(Default)]
+struct MockProvider {
+    calls: AtomicUsize,
+}
+
+impl LinkPreviewProvider for MockProvider {
+    fn preview<'a>(&'a self, url: &'a str) -> PreviewFuture<'a> {
+        self.calls.fetch_add(1, Ordering::SeqCst);
+        Box::pin(async move {
+            Some(LinkPreview {
+                url: url.to_string(),
+                title: Some("Example Domain".to_string()),
+                description: Some("For use in examples".to_string()),
+                image_blob: None,
+            })
+        })
+    }
+}
+
+fn create_client(temp_dir: &TempDir) -> Client {
+    let config = ClientConfig {
+        storage_path: temp_dir.path().to_path_buf(),
+        listen_addrs: vec!["/ip4/127.0.0.1/tcp/0".to_string()],
+        bootstrap_peers: vec![],
+        ..Default::default()
+    };
+    Client::new(Keypair::generate(), config).unwrap()
+}
+
+#[tokio::test]
+async fn test_mock_provider_attaches_preview() {
+    let author_dir = TempDir::new().unwrap();
+    let replica_dir = TempDir::new().unwrap();
+    let author = create_client(&author_dir);
+    let replica = create_client(&replica_dir);
+
+    let provider = Arc::new(MockProvider::default());
+    author.set_link_preview_provider(provider.clone()).await;
+
+    let (space, space_op, _) = author.create_space("Links".to_string(), None).await.unwrap();
+    let (channel, channel_op) = author.create_channel(space.id, "general".to_string(), None).await.unwrap();
+    let (thread, thread_op) = author.create_thread(space.id, channel.id, None, "No links yet".to_string()).await.unwrap();
+    let (plain, plain_op) = author.post_message(space.id, thread.id, "Still no links".to_string()).await.unwrap();
+    let (linked, linked_op) = author.post_message(space.id, thread.id, "Look at https://example.com/page.".to_string()).await.unwrap();
+    assert!(linked.link_preview.is_none());
+
+    // The fetch runs in the background; wait for it to land
+    let mut attached = Vec::new();
+    for _ in 0..50 {
+        attached.extend(author.attach_pending_link_previews().await.unwrap());
+        if !attached.is_empty() {
+            break;
+        }
+        tokio::time::sleep(Duration::from_millis(20)).await;
+    }
+    assert_eq!(attached.len(), 1);
+    assert_eq!(provider.calls.load(Ordering::SeqCst), 1);
+
+    for op in [space_op, channel_op, thread_op, plain_op, linked_op, attached.remove(0)] {
+        replica.handle_incoming_op(op).await.unwrap();
+    }
+
+    for client in [&author, &replica] {
+        let messages = client.list_messages(&thread.id).await;
+        let linked = messages.iter().find(|m| m.id == linked.id).unwrap();
+        let preview = linked.link_preview.as_ref().expect("preview should be attached");
+        assert_eq!(preview.url, "https://example.com/page");
+        assert_eq!(preview.title.as_deref(), Some("Example Domain"));
+        assert!(messages.iter().find(|m| m.id == plain.id).unwrap().link_preview.is_none());
+    }
+}
+
+#[tokio::test]
+async fn test_only_author_can_attach_preview() {
+    let author_dir = TempDir::new().unwrap();
+    let other_dir = TempDir::new().unwrap();
+    let author = create_client(&author_dir);
+    let other = create_client(&other_dir);
+
+    let (space, space_op, _) = author.create_space("Links".to_string(), None).await.unwrap();
+    let (channel, channel_op) = author.create_channel(space.id, "general".to_string(), None).await.unwrap();
+    let (thread, thread_op) = author.create_thread(space.id, channel.id, None, "https://example.com".to_string()).await.unwrap();
+    for op in [space_op, channel_op, thread_op] {
+        other.handle_incoming_op(op).await.unwrap();
+    }
+
+    let message = other.list_messages(&thread.id).await.remove(0);
+    let preview = LinkPreview {
+        url: "https://example.com".to_string(),
+        title: Some("Spoofed".to_string()),
+        description: None,
+        image_blob: None,
+    };
+    assert!(other.attach_link_preview(space.id, message.id, preview).await.is_err());
+}
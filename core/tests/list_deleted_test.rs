@@ -0,0 +1,62 @@
+//! Deleted items are hidden from listings unless tombstones are asked for
+
+use spaceway_core::crypto::signing::Keypair;
+use spaceway_core::{Client, ClientConfig};
+use tempfile::TempDir;
+
+fn create_client(temp_dir: &TempDir) -> Client {
+    let config = ClientConfig {
+        storage_path: temp_dir.path().to_path_buf(),
+        listen_addrs: vec!["/ip4/127.0.0.1/tcp/0".to_string()],
+        bootstrap_peers: vec![],
+        ..Default::default()
+    };
+    Client::new(Keypair::generate(), config).unwrap()
+}
+
+#[tokio::test]
+async fn test_deleted_message_only_listed_with_flag() {
+    let temp_dir = TempDir::new().unwrap();
+    let client = create_client(&temp_dir);
+
+    let (space, _, _) = client.create_space("Audit".to_string(), None).await.unwrap();
+    let (channel, _) = client.create_channel(space.id, "general".to_string(), None).await.unwrap();
+    let (thread, _) = client.create_thread(space.id, channel.id, None, "Hello".to_string()).await.unwrap();
+    let (kept, _) = client.post_message(space.id, thread.id, "stays".to_string()).await.unwrap();
+    let (gone, _) = client.post_message(space.id, thread.id, "goes".to_string()).await.unwrap();
+    client.redact_message(space.id, gone.id).await.unwrap();
+
+    let visible = client.list_messages(&thread.id).await;
+    assert!(visible.iter().any(|m| m.id == kept.id));
+    assert!(visible.iter().all(|m| m.id != gone.id));
+
+    let audit = client.list_messages_with_deleted(&thread.id, true).await;
+    assert_eq!(audit.len(), visible.len() + 1);
+    assert!(audit.iter().any(|m| m.id == gone.id && m.deleted));
+    assert_eq!(client.list_messages_with_deleted(&thread.id, false).await.len(), visible.len());
+}
+
+#[tokio::test]
+async fn test_deleted_channel_and_its_threads_listed_with_flag() {
+    let temp_dir = TempDir::new().unwrap();
+    let client = create_client(&temp_dir);
+
+    let (space, _, _) = client.create_space("Audit".to_string(), None).await.unwrap();
+    let (kept, _) = client.create_channel(space.id, "general".to_string(), None).await.unwrap();
+    let (old, _) = client.create_channel(space.id, "old".to_string(), None).await.unwrap();
+    let (thread, _) = client.create_thread(space.id, old.id, None, "Archive me".to_string()).await.unwrap();
+    client.delete_channel(old.id).await.unwrap();
+
+    let channels = client.list_channels(&space.id).await;
+    assert_eq!(channels.iter().map(|c| c.id).collect::<Vec<_>>(), vec![kept.id]);
+
+    let audit = client.list_channels_with_deleted(&space.id, true).await;
+    assert_eq!(audit.len(), 2);
+    assert!(audit.iter().any(|c| c.id == old.id && c.deleted && c.name == "old"));
+    assert!(audit.iter().any(|c| c.id == kept.id && !c.deleted));
+
+    assert!(client.list_threads(&old.id).await.is_empty());
+    let threads = client.list_threads_with_deleted(&old.id, true).await;
+    assert_eq!(threads.len(), 1);
+    assert!(threads[0].id == thread.id && threads[0].deleted);
+}
@@ -0,0 +1,97 @@
+//! Membership is kept in `member_roles`; `list_members` and the legacy
+//! `Role` API are views over it
+
+use spaceway_core::{Client, ClientConfig, crypto::Keypair};
+use spaceway_core::types::Role;
+use anyhow::Result;
+
+fn create_test_client() -> Result<Client> {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let config = ClientConfig {
+        storage_path: temp_dir.path().to_path_buf(),
+        listen_addrs: vec!["/ip4/127.0.0.1/tcp/0".to_string()],
+        bootstrap_peers: vec![],
+        ..Default::default()
+    };
+    Ok(Client::new(Keypair::generate(), config)?)
+}
+
+#[tokio::test]
+async fn test_role_assigned_member_is_listed() -> Result<()> {
+    let admin = create_test_client()?;
+    let joiner = create_test_client()?;
+
+    let (space, space_op, _) = admin.create_space("Test Space".to_string(), None).await?;
+    joiner.handle_incoming_op(space_op).await?;
+
+    let roles = admin.get_space(&space.id).await.unwrap().roles;
+    let moderator = roles.values().find(|r| r.name == "Moderator").unwrap().id;
+
+    // Joining through a role-scoped invite assigns the role directly
+    let invite_op = admin.create_invite(space.id, Some(1), None, Some(moderator), None).await?;
+    joiner.handle_incoming_op(invite_op).await?;
+    let invite_code = joiner.list_invites(&space.id).await[0].code.clone();
+    let join_op = joiner.join_with_invite(space.id, invite_code).await?;
+    admin.handle_incoming_op(join_op).await?;
+
+    let members = admin.list_members(&space.id).await;
+    assert!(members.contains(&(joiner.user_id(), Role::Moderator)));
+    assert!(members.contains(&(admin.user_id(), Role::Admin)));
+
+    let admin_view = admin.get_space(&space.id).await.unwrap();
+    assert!(admin_view.is_member(&joiner.user_id()));
+    assert!(admin_view.can_kick_members(&joiner.user_id()));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_legacy_added_member_has_role_permissions() -> Result<()> {
+    let admin = create_test_client()?;
+    let (space, _, _) = admin.create_space("Test Space".to_string(), None).await?;
+
+    let moderator = Keypair::generate().user_id();
+    let member = Keypair::generate().user_id();
+    admin.add_member(space.id, moderator, Role::Moderator).await?;
+    admin.add_member(space.id, member, Role::Member).await?;
+
+    let space = admin.get_space(&space.id).await.unwrap();
+    let moderator_role = space.roles.values().find(|r| r.name == "Moderator").unwrap().id;
+    assert_eq!(space.member_roles.get(&moderator), Some(&moderator_role));
+    assert_eq!(space.member_roles.get(&member), Some(&space.default_role));
+    assert!(space.can_kick_members(&moderator));
+    assert!(!space.can_kick_members(&member));
+
+    let members = admin.list_members(&space.id).await;
+    assert_eq!(members.len(), 3);
+    assert!(members.contains(&(member, Role::Member)));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_member_count_tracks_adds_and_removes() -> Result<()> {
+    let admin = create_test_client()?;
+    let (space, _, _) = admin.create_space("Test Space".to_string(), None).await?;
+    assert_eq!(admin.member_count(&space.id).await, 1);
+
+    let first = Keypair::generate().user_id();
+    let second = Keypair::generate().user_id();
+    admin.add_member(space.id, first, Role::Member).await?;
+    admin.add_member(space.id, second, Role::Moderator).await?;
+    assert_eq!(admin.member_count(&space.id).await, 3);
+
+    // Re-adding an existing member doesn't count twice
+    admin.add_member(space.id, first, Role::Member).await?;
+    assert_eq!(admin.member_count(&space.id).await, 3);
+
+    admin.remove_member(space.id, first).await?;
+    assert_eq!(admin.member_count(&space.id).await, 2);
+
+    let snapshot = admin.get_space_snapshot(space.id).await.unwrap();
+    assert_eq!(snapshot.member_count, 2);
+
+    assert_eq!(admin.member_count(&spaceway_core::SpaceId([9u8; 32])).await, 0);
+
+    Ok(())
+}
@@ -0,0 +1,100 @@
+//! Integration tests for epoch-aware membership validation
+
+use spaceway_core::crdt::{CrdtOp, Hlc, OpPayload, OpType, OpValidator, RejectionReason, ValidationResult};
+use spaceway_core::crypto::signing::Keypair;
+use spaceway_core::types::*;
+use std::collections::HashMap;
+
+fn signed_op(keypair: &Keypair, space_id: SpaceId, epoch: u64, wall_time: u64, op_type: OpType) -> CrdtOp {
+    let mut op = CrdtOp {
+        op_id: OpId(uuid::Uuid::new_v4()),
+        space_id,
+        channel_id: None,
+        thread_id: None,
+        op_type,
+        prev_ops: vec![],
+        author: keypair.user_id(),
+        epoch: EpochId(epoch),
+        hlc: Hlc { wall_time, logical: 0 },
+        timestamp: wall_time,
+        signature: Signature([0u8; 64]),
+    };
+    op.sign(keypair).unwrap();
+    op
+}
+
+fn message(keypair: &Keypair, space_id: SpaceId, epoch: u64, wall_time: u64) -> CrdtOp {
+    signed_op(keypair, space_id, epoch, wall_time, OpType::PostMessage(OpPayload::PostMessage {
+        message_id: MessageId([wall_time as u8; 32]),
+        content: "hello".to_string(),
+        quote: None,
+    }))
+}
+
+fn assign(admin: &Keypair, space_id: SpaceId, user_id: UserId, epoch: u64, wall_time: u64) -> CrdtOp {
+    signed_op(admin, space_id, epoch, wall_time, OpType::AssignRole(OpPayload::AssignRole {
+        user_id,
+        role: Role::Member,
+        channel_id: None,
+    }))
+}
+
+fn remove(admin: &Keypair, space_id: SpaceId, user_id: UserId, epoch: u64, wall_time: u64) -> CrdtOp {
+    signed_op(admin, space_id, epoch, wall_time, OpType::RemoveMember(OpPayload::RemoveMember {
+        user_id,
+        reason: None,
+    }))
+}
+
+/// Validator for a Space where `member` joined at epoch 0 and was removed at
+/// wall time 200, with the removal commit advancing the Space to epoch 1
+fn validator_after_removal(admin: &Keypair, member: &Keypair, space_id: SpaceId) -> OpValidator {
+    let mut validator = OpValidator::new();
+    validator.apply_op(&signed_op(admin, space_id, 0, 10, OpType::CreateSpace(OpPayload::CreateSpace {
+        name: "History".to_string(),
+        description: None,
+    })));
+    validator.apply_op(&assign(admin, space_id, member.user_id(), 0, 20));
+    validator.apply_op(&remove(admin, space_id, member.user_id(), 0, 200));
+    validator.update_epoch(space_id, EpochId(1));
+    validator
+}
+
+#[test]
+fn test_message_from_before_removal_is_accepted_on_replay() {
+    let admin = Keypair::generate();
+    let member = Keypair::generate();
+    let space_id = SpaceId([7u8; 32]);
+    let validator = validator_after_removal(&admin, &member, space_id);
+
+    // Written at the removal's epoch but before it: valid history
+    let earlier = message(&member, space_id, 0, 100);
+    assert_eq!(validator.validate(&earlier, &HashMap::new()), ValidationResult::Accept);
+
+    // Written after the removal, in the same epoch or the next
+    for later in [message(&member, space_id, 0, 300), message(&member, space_id, 1, 300)] {
+        assert_eq!(
+            validator.validate(&later, &HashMap::new()),
+            ValidationResult::Reject(RejectionReason::AuthorRemoved)
+        );
+    }
+}
+
+#[test]
+fn test_rejoined_member_keeps_history() {
+    let admin = Keypair::generate();
+    let member = Keypair::generate();
+    let space_id = SpaceId([7u8; 32]);
+    let mut validator = validator_after_removal(&admin, &member, space_id);
+
+    validator.update_epoch(space_id, EpochId(2));
+    validator.apply_op(&assign(&admin, space_id, member.user_id(), 2, 400));
+
+    // First membership, the gap between, and the second membership
+    assert_eq!(validator.validate(&message(&member, space_id, 0, 100), &HashMap::new()), ValidationResult::Accept);
+    assert_eq!(
+        validator.validate(&message(&member, space_id, 1, 300), &HashMap::new()),
+        ValidationResult::Reject(RejectionReason::AuthorRemoved)
+    );
+    assert_eq!(validator.validate(&message(&member, space_id, 2, 500), &HashMap::new()), ValidationResult::Accept);
+}
@@ -0,0 +1,76 @@
+//! Integration tests for forwarding messages between threads
+
+use spaceway_core::crypto::signing::Keypair;
+use spaceway_core::forum::ForwardSource;
+use spaceway_core::{Client, ClientConfig, Error};
+use tempfile::TempDir;
+
+fn create_client(temp_dir: &TempDir) -> Client {
+    let config = ClientConfig {
+        storage_path: temp_dir.path().to_path_buf(),
+        listen_addrs: vec!["/ip4/127.0.0.1/tcp/0".to_string()],
+        bootstrap_peers: vec![],
+        ..Default::default()
+    };
+    Client::new(Keypair::generate(), config).unwrap()
+}
+
+#[tokio::test]
+async fn test_forward_into_another_space_keeps_attribution() {
+    let alice_dir = TempDir::new().unwrap();
+    let bob_dir = TempDir::new().unwrap();
+    let alice = create_client(&alice_dir);
+    let bob = create_client(&bob_dir);
+
+    let (space, space_op, _) = alice.create_space("Team".to_string(), None).await.unwrap();
+    let (channel, channel_op) = alice.create_channel(space.id, "general".to_string(), None).await.unwrap();
+    let (source, source_op) = alice.create_thread(space.id, channel.id, None, "Plans".to_string()).await.unwrap();
+    let (original, post_op) = alice.post_message(space.id, source.id, "Launch is on Friday".to_string()).await.unwrap();
+
+    let (other, other_op, _) = alice.create_space("Friends".to_string(), None).await.unwrap();
+    let (news, news_op) = alice.create_channel(other.id, "news".to_string(), None).await.unwrap();
+    let (target, target_op) = alice.create_thread(other.id, news.id, None, "Updates".to_string()).await.unwrap();
+
+    let (forwarded, forward_op) = alice.forward_message(original.id, target.id).await.unwrap();
+    assert_ne!(forwarded.id, original.id);
+    assert_eq!(forwarded.thread_id, target.id);
+    assert_eq!(forwarded.content, "Launch is on Friday");
+    assert_eq!(forwarded.forwarded_from, Some(original.id));
+    assert_eq!(
+        forwarded.forward_source,
+        Some(ForwardSource { author: alice.user_id(), thread_id: source.id })
+    );
+    assert!(alice.list_messages(&target.id).await.iter().any(|m| m.id == forwarded.id));
+
+    // A peer applying the op sees the same attribution
+    for op in [space_op, channel_op, source_op, post_op, other_op, news_op, target_op, forward_op] {
+        bob.handle_incoming_op(op).await.unwrap();
+    }
+    let received = bob.get_message(&forwarded.id).await.expect("peer should apply the forward");
+    assert_eq!(received.forwarded_from, Some(original.id));
+    assert_eq!(received.forward_source, forwarded.forward_source);
+    assert_eq!(received.author, alice.user_id());
+}
+
+#[tokio::test]
+async fn test_forward_requires_membership_in_source_space() {
+    let alice_dir = TempDir::new().unwrap();
+    let bob_dir = TempDir::new().unwrap();
+    let alice = create_client(&alice_dir);
+    let bob = create_client(&bob_dir);
+
+    let (space, space_op, _) = alice.create_space("Private".to_string(), None).await.unwrap();
+    let (channel, channel_op) = alice.create_channel(space.id, "general".to_string(), None).await.unwrap();
+    let (thread, thread_op) = alice.create_thread(space.id, channel.id, None, "Secrets".to_string()).await.unwrap();
+    let (original, post_op) = alice.post_message(space.id, thread.id, "Members only".to_string()).await.unwrap();
+    for op in [space_op, channel_op, thread_op, post_op] {
+        bob.handle_incoming_op(op).await.unwrap();
+    }
+
+    let (own, _, _) = bob.create_space("Bob's".to_string(), None).await.unwrap();
+    let (own_channel, _) = bob.create_channel(own.id, "general".to_string(), None).await.unwrap();
+    let (own_thread, _) = bob.create_thread(own.id, own_channel.id, None, "Loot".to_string()).await.unwrap();
+
+    let result = bob.forward_message(original.id, own_thread.id).await;
+    assert!(matches!(result, Err(Error::Permission(_))));
+}
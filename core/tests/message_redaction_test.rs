@@ -0,0 +1,69 @@
+//! Integration tests for message redaction
+
+use spaceway_core::crypto::signing::Keypair;
+use spaceway_core::export::ExportFormat;
+use spaceway_core::{Client, ClientConfig, Error};
+use tempfile::TempDir;
+
+fn create_client(temp_dir: &TempDir) -> Client {
+    let config = ClientConfig {
+        storage_path: temp_dir.path().to_path_buf(),
+        listen_addrs: vec!["/ip4/127.0.0.1/tcp/0".to_string()],
+        bootstrap_peers: vec![],
+        ..Default::default()
+    };
+    Client::new(Keypair::generate(), config).unwrap()
+}
+
+#[tokio::test]
+async fn test_redacted_content_is_purged_locally_and_on_peers() {
+    let alice_dir = TempDir::new().unwrap();
+    let bob_dir = TempDir::new().unwrap();
+    let alice = create_client(&alice_dir);
+    let bob = create_client(&bob_dir);
+
+    let (space, space_op, _) = alice.create_space("Private".to_string(), None).await.unwrap();
+    let (channel, channel_op) = alice.create_channel(space.id, "general".to_string(), None).await.unwrap();
+    let (thread, thread_op) = alice.create_thread(space.id, channel.id, None, "Hello".to_string()).await.unwrap();
+    let (message, post_op) = alice.post_message(space.id, thread.id, "my phone is 555-0100".to_string()).await.unwrap();
+    let edit_op = alice.edit_message(space.id, message.id, "my phone is 555-0199".to_string()).await.unwrap();
+    for op in [space_op, channel_op, thread_op, post_op, edit_op] {
+        bob.handle_incoming_op(op).await.unwrap();
+    }
+
+    let redact_op = alice.redact_message(space.id, message.id).await.unwrap();
+
+    let redacted = alice.get_message(&message.id).await.unwrap();
+    assert!(redacted.redacted && redacted.deleted);
+    assert!(redacted.content.is_empty());
+    let export = alice.export_space(&space.id, ExportFormat::Json).await.unwrap();
+    assert!(!String::from_utf8_lossy(&export).contains("555-01"));
+
+    // Replicas purge the content rather than just hiding it
+    bob.handle_incoming_op(redact_op).await.unwrap();
+    let on_bob = bob.get_message(&message.id).await.unwrap();
+    assert!(on_bob.redacted);
+    assert!(on_bob.content.is_empty());
+    let export = bob.export_space(&space.id, ExportFormat::Json).await.unwrap();
+    assert!(!String::from_utf8_lossy(&export).contains("555-01"));
+}
+
+#[tokio::test]
+async fn test_only_author_can_redact() {
+    let alice_dir = TempDir::new().unwrap();
+    let bob_dir = TempDir::new().unwrap();
+    let alice = create_client(&alice_dir);
+    let bob = create_client(&bob_dir);
+
+    let (space, space_op, _) = alice.create_space("Shared".to_string(), None).await.unwrap();
+    let (channel, channel_op) = alice.create_channel(space.id, "general".to_string(), None).await.unwrap();
+    let (thread, thread_op) = alice.create_thread(space.id, channel.id, None, "Hello".to_string()).await.unwrap();
+    let (message, post_op) = alice.post_message(space.id, thread.id, "keep me".to_string()).await.unwrap();
+    for op in [space_op, channel_op, thread_op, post_op] {
+        bob.handle_incoming_op(op).await.unwrap();
+    }
+
+    let result = bob.redact_message(space.id, message.id).await;
+    assert!(matches!(result, Err(Error::Permission(_))));
+    assert_eq!(bob.get_message(&message.id).await.unwrap().content, "keep me");
+}
@@ -0,0 +1,100 @@
+//! Integration tests for Client health metrics
+
+use spaceway_core::{Client, ClientConfig};
+use spaceway_core::crypto::signing::Keypair;
+use tempfile::TempDir;
+
+fn create_client(temp_dir: &TempDir) -> Client {
+    let config = ClientConfig {
+        storage_path: temp_dir.path().to_path_buf(),
+        listen_addrs: vec!["/ip4/127.0.0.1/tcp/0".to_string()],
+        bootstrap_peers: vec![],
+        ..Default::default()
+    };
+    Client::new(Keypair::generate(), config).unwrap()
+}
+
+#[tokio::test]
+async fn test_snapshot_counts_space_and_message() {
+    let temp_dir = TempDir::new().unwrap();
+    let client = create_client(&temp_dir);
+
+    let before = client.metrics_snapshot().await;
+    assert_eq!(before.ops_sent, 0);
+    assert!(before.space_epochs.is_empty());
+
+    let (space, _, _) = client.create_space("Observed".to_string(), None).await.unwrap();
+    let (channel, _) = client.create_channel(space.id, "general".to_string(), None).await.unwrap();
+    let (thread, _) = client.create_thread(space.id, channel.id, None, "First".to_string()).await.unwrap();
+    let after_setup = client.metrics_snapshot().await;
+
+    client.post_message(space.id, thread.id, "Second".to_string()).await.unwrap();
+    let after_post = client.metrics_snapshot().await;
+
+    assert!(after_setup.ops_sent >= 3);
+    assert_eq!(after_post.ops_sent, after_setup.ops_sent + 1);
+    assert!(after_post.dht_puts > after_setup.dht_puts);
+    assert_eq!(after_post.ops_received, 0);
+    assert_eq!(after_post.decrypt_failures, 0);
+    assert_eq!(after_post.connected_peers, 0);
+    assert_eq!(after_post.space_epochs.get(&hex::encode(space.id.0)), Some(&0));
+
+    let text = after_post.to_prometheus();
+    assert!(text.contains(&format!("spaceway_ops_sent_total {}\n", after_post.ops_sent)));
+}
+
+#[cfg(feature = "metrics-server")]
+#[tokio::test]
+async fn test_metrics_endpoint_serves_snapshot() {
+    use std::sync::Arc;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::sync::RwLock;
+
+    let temp_dir = TempDir::new().unwrap();
+    let client = Arc::new(RwLock::new(create_client(&temp_dir)));
+    client.read().await.create_space("Scraped".to_string(), None).await.unwrap();
+
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(spaceway_core::metrics::serve(listener, client));
+
+    let mut stream = tokio::net::TcpStream::connect(addr).await.unwrap();
+    stream.write_all(b"GET /metrics HTTP/1.1\r\nHost: localhost\r\n\r\n").await.unwrap();
+    let mut response = String::new();
+    stream.read_to_string(&mut response).await.unwrap();
+
+    assert!(response.starts_with("HTTP/1.1 200 OK"));
+    assert!(response.contains("spaceway_ops_sent_total"));
+}
+
+#[cfg(feature = "metrics-server")]
+#[tokio::test]
+async fn test_metrics_endpoint_requires_bearer_token() {
+    use std::sync::Arc;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::sync::RwLock;
+
+    let temp_dir = TempDir::new().unwrap();
+    let client = Arc::new(RwLock::new(create_client(&temp_dir)));
+
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(spaceway_core::metrics::serve_with_token(listener, client, Some("s3cret".to_string())));
+
+    let get = |request: &'static str| async move {
+        let mut stream = tokio::net::TcpStream::connect(addr).await.unwrap();
+        stream.write_all(request.as_bytes()).await.unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).await.unwrap();
+        response
+    };
+
+    let anonymous = get("GET /metrics HTTP/1.1\r\nHost: localhost\r\n\r\n").await;
+    assert!(anonymous.starts_with("HTTP/1.1 401 Unauthorized"));
+    let wrong = get("GET /metrics HTTP/1.1\r\nAuthorization: Bearer guess\r\n\r\n").await;
+    assert!(wrong.starts_with("HTTP/1.1 401 Unauthorized"));
+
+    let authorized = get("GET /metrics HTTP/1.1\r\nHost: localhost\r\nauthorization: Bearer s3cret\r\n\r\n").await;
+    assert!(authorized.starts_with("HTTP/1.1 200 OK"));
+    assert!(authorized.contains("spaceway_ops_sent_total"));
+}
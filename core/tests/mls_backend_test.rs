@@ -0,0 +1,55 @@
+//! A client runs MLS on an injected crypto backend
+
+use openmls_rust_crypto::RustCrypto;
+use openmls_traits::crypto::OpenMlsCrypto;
+use openmls_traits::types::CryptoError;
+use spaceway_core::crypto::signing::Keypair;
+use spaceway_core::mls::MlsBackend;
+use spaceway_core::{Client, ClientConfig};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use tempfile::TempDir;
+
+/// Forwards to rust-crypto, counting the randomness it hands out
+#[derive(Default)]
+struct CountingBackend {
+    inner: RustCrypto,
+    random_fills: AtomicUsize,
+}
+
+impl MlsBackend for CountingBackend {
+    fn crypto(&self) -> &dyn OpenMlsCrypto {
+        &self.inner
+    }
+
+    fn fill_random(&self, buf: &mut [u8]) -> Result<(), CryptoError> {
+        self.random_fills.fetch_add(1, Ordering::SeqCst);
+        self.inner.fill_random(buf)
+    }
+}
+
+#[tokio::test]
+async fn test_client_uses_injected_mls_backend() {
+    let temp_dir = TempDir::new().unwrap();
+    let config = ClientConfig {
+        storage_path: temp_dir.path().to_path_buf(),
+        listen_addrs: vec!["/ip4/127.0.0.1/tcp/0".to_string()],
+        bootstrap_peers: vec![],
+        ..Default::default()
+    };
+
+    let backend = Arc::new(CountingBackend::default());
+    let client = Client::builder(Keypair::generate(), config)
+        .mls_backend(backend.clone())
+        .build()
+        .unwrap();
+
+    // KeyPackages are generated on construction
+    let after_build = backend.random_fills.load(Ordering::SeqCst);
+    assert!(after_build > 0);
+
+    // Creating a Space creates its MLS group on the same backend
+    let (space, _, _) = client.create_space("Pluggable".to_string(), None).await.unwrap();
+    assert!(backend.random_fills.load(Ordering::SeqCst) > after_build);
+    assert_eq!(client.mls_members(&space.id).await, vec![client.user_id()]);
+}
@@ -0,0 +1,153 @@
+//! Members-only keys derived from the MLS exporter rotate away from removed members
+
+use openmls::prelude::Ciphersuite;
+use spaceway_core::crdt::{EncryptedOperationBatch, OperationBatch};
+use spaceway_core::crypto::signing::{Keypair, Signer};
+use spaceway_core::mls::provider::{create_provider, DescordProvider};
+use spaceway_core::mls::{self, key_labels, KeyPackageStore, MlsGroup, MlsGroupConfig};
+use spaceway_core::types::{Role, SpaceId};
+use spaceway_core::{Client, ClientConfig};
+use tempfile::TempDir;
+use tokio::time::{sleep, Duration, Instant};
+
+/// Add `member` to `group`, returning the member's view of the group and the Commit
+fn add(group: &mut MlsGroup, admin: &Keypair, member: &Keypair, provider: &DescordProvider) -> (MlsGroup, DescordProvider, Vec<u8>) {
+    let member_provider = create_provider();
+    let mut store = KeyPackageStore::new(
+        member.user_id(),
+        mls::identity_signer(member).unwrap(),
+        Ciphersuite::MLS_128_DHKEMX25519_AES128GCM_SHA256_Ed25519,
+    );
+    let bundles = store.generate_key_packages(1, &member_provider).unwrap();
+    let key_package = KeyPackageStore::deserialize_key_package(&bundles[0], provider).unwrap();
+    let (commit, welcome) = group.add_member_with_key_package(
+        member.user_id(),
+        Role::Member,
+        key_package,
+        &admin.user_id(),
+        provider,
+    ).unwrap();
+    let member_group = MlsGroup::from_welcome(
+        welcome.to_bytes().unwrap(),
+        member.user_id(),
+        store.signer(),
+        &member_provider,
+    ).unwrap();
+    (member_group, member_provider, commit.to_bytes().unwrap())
+}
+
+#[test]
+fn test_removed_member_cannot_derive_new_space_key() {
+    let (alice, bob, charlie) = (Keypair::generate(), Keypair::generate(), Keypair::generate());
+    let alice_provider = create_provider();
+    let mut alice_group = MlsGroup::create(
+        SpaceId([8; 32]),
+        alice.user_id(),
+        mls::identity_signer(&alice).unwrap(),
+        MlsGroupConfig::default(),
+        &alice_provider,
+    ).unwrap();
+
+    let (mut bob_group, bob_provider, _) = add(&mut alice_group, &alice, &bob, &alice_provider);
+    let (mut charlie_group, charlie_provider, commit) = add(&mut alice_group, &alice, &charlie, &alice_provider);
+    bob_group.process_commit_message(&commit, &bob_provider).unwrap();
+
+    // Everyone in the epoch derives the same key, distinct per label
+    let old_key = alice_group.export_key(key_labels::OP_BATCH, &alice_provider).unwrap();
+    assert_eq!(bob_group.export_key(key_labels::OP_BATCH, &bob_provider).unwrap(), old_key);
+    assert_eq!(charlie_group.export_key(key_labels::OP_BATCH, &charlie_provider).unwrap(), old_key);
+    assert_ne!(alice_group.export_key(key_labels::BLOB, &alice_provider).unwrap(), old_key);
+
+    let removal = alice_group.remove_member_with_key_rotation(&charlie.user_id(), &alice.user_id(), &alice_provider)
+        .unwrap().to_bytes().unwrap();
+    bob_group.process_commit_message(&removal, &bob_provider).unwrap();
+
+    let new_key = alice_group.export_key(key_labels::OP_BATCH, &alice_provider).unwrap();
+    assert_eq!(bob_group.export_key(key_labels::OP_BATCH, &bob_provider).unwrap(), new_key);
+    assert_ne!(new_key, old_key);
+
+    // Charlie still holds only the old epoch's key
+    assert_eq!(charlie_group.export_key(key_labels::OP_BATCH, &charlie_provider).unwrap(), old_key);
+    let batch = OperationBatch::new(SpaceId([8; 32]), Vec::new(), 0);
+    let sealed = EncryptedOperationBatch::encrypt_with_key(&batch, &new_key).unwrap();
+    assert!(sealed.decrypt_with_key(&bob_group.export_key(key_labels::OP_BATCH, &bob_provider).unwrap()).is_ok());
+    assert!(sealed.decrypt_with_key(&old_key).is_err());
+    assert!(sealed.decrypt().is_err(), "the Space ID alone must not open it");
+}
+
+fn create_client(temp_dir: &TempDir) -> Client {
+    let config = ClientConfig {
+        storage_path: temp_dir.path().to_path_buf(),
+        listen_addrs: vec!["/ip4/127.0.0.1/tcp/0".to_string()],
+        bootstrap_peers: vec![],
+        ..Default::default()
+    };
+    Client::new(Keypair::generate(), config).unwrap()
+}
+
+/// Wait until `member` holds the same op batch key as `admin`
+async fn wait_for_epoch(admin: &Client, member: &Client, space_id: &SpaceId) {
+    let key = admin.space_key(space_id, key_labels::OP_BATCH).await.unwrap();
+    let deadline = Instant::now() + Duration::from_secs(15);
+    while member.space_key(space_id, key_labels::OP_BATCH).await.ok() != Some(key) {
+        assert!(Instant::now() < deadline, "member never reached the admin's epoch");
+        sleep(Duration::from_millis(200)).await;
+    }
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_removed_member_cannot_read_later_dht_records() {
+    let dirs: Vec<TempDir> = (0..4).map(|_| TempDir::new().unwrap()).collect();
+    let [alice, bob, charlie, dave] = [0, 1, 2, 3].map(|i| create_client(&dirs[i]));
+    for client in [&alice, &bob, &charlie, &dave] {
+        client.start().await.unwrap();
+    }
+    sleep(Duration::from_millis(500)).await;
+
+    let alice_addr = alice.listening_addrs().await
+        .into_iter()
+        .find(|addr| addr.to_string().contains("127.0.0.1"))
+        .expect("Alice should listen on loopback");
+    let alice_multiaddr = format!("{}/p2p/{}", alice_addr, alice.peer_id().await);
+    for client in [&bob, &charlie, &dave] {
+        client.network_dial(&alice_multiaddr).await.unwrap();
+    }
+    sleep(Duration::from_secs(1)).await;
+
+    let (space, space_op, _) = alice.create_space("Sealed".to_string(), None).await.unwrap();
+    for client in [&bob, &charlie, &dave] {
+        client.handle_incoming_op(space_op.clone()).await.unwrap();
+    }
+    for member in [&bob, &charlie] {
+        let bundle = member.get_key_package_bundle().await.unwrap();
+        alice.add_member_with_key_package_bundle(space.id, member.user_id(), Role::Member, bundle).await.unwrap();
+        wait_for_epoch(&alice, member, &space.id).await;
+    }
+    wait_for_epoch(&alice, &bob, &space.id).await;
+
+    alice.remove_member(space.id, charlie.user_id()).await.unwrap();
+    wait_for_epoch(&alice, &bob, &space.id).await;
+    let (secret, secret_op) = alice.create_channel(space.id, "after-charlie".to_string(), None).await.unwrap();
+    let blob = alice.store_blob_for_space(&space.id, b"not for charlie", None, None).await.unwrap();
+    sleep(Duration::from_secs(2)).await;
+
+    // Bob, still a member, opens everything sealed in the new epoch
+    let ops = bob.dht_get_operations(&space.id).await.unwrap();
+    assert!(ops.iter().any(|op| op.op_id == secret_op.op_id));
+    assert_eq!(bob.retrieve_blob_for_space(&space.id, &blob.hash).await.unwrap(), b"not for charlie");
+
+    // Charlie's keys stop at the epoch Charlie was removed in
+    let charlie_ops = charlie.dht_get_operations(&space.id).await;
+    assert!(charlie_ops.map_or(true, |ops| ops.iter().all(|op| op.op_id != secret_op.op_id)));
+    assert!(charlie.get_channel(&secret.id).await.is_none());
+    assert!(charlie.retrieve_blob_for_space(&space.id, &blob.hash).await.is_err());
+
+    // Dave joins later and opens records sealed before his Welcome through the key history
+    let bundle = dave.get_key_package_bundle().await.unwrap();
+    alice.add_member_with_key_package_bundle(space.id, dave.user_id(), Role::Member, bundle).await.unwrap();
+    wait_for_epoch(&alice, &dave, &space.id).await;
+    sleep(Duration::from_secs(1)).await;
+    assert_eq!(dave.dht_get_space(&space.id).await.unwrap().name, "Sealed");
+    assert!(dave.dht_get_operations(&space.id).await.unwrap().iter().any(|op| op.op_id == secret_op.op_id));
+    assert_eq!(dave.retrieve_blob_for_space(&space.id, &blob.hash).await.unwrap(), b"not for charlie");
+}
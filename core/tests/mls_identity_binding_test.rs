@@ -0,0 +1,92 @@
+//! The MLS sender of a message and the author of the op inside it are the same key
+
+use openmls::prelude::Ciphersuite;
+use spaceway_core::crypto::signing::{ExternalSigner, Keypair, Signer};
+use spaceway_core::mls::provider::create_provider;
+use spaceway_core::mls::{self, KeyPackageStore, MlsGroup, MlsGroupConfig};
+use spaceway_core::types::{Role, SpaceId};
+use spaceway_core::crdt::CrdtOp;
+use spaceway_core::{Client, ClientConfig};
+use tempfile::TempDir;
+
+fn create_client(temp_dir: &TempDir, keypair: Keypair) -> Client {
+    let config = ClientConfig {
+        storage_path: temp_dir.path().to_path_buf(),
+        listen_addrs: vec!["/ip4/127.0.0.1/tcp/0".to_string()],
+        bootstrap_peers: vec![],
+        ..Default::default()
+    };
+    Client::new(keypair, config).unwrap()
+}
+
+#[tokio::test]
+async fn test_op_author_is_the_mls_sender() {
+    let alice = Keypair::generate();
+    let bob = Keypair::generate();
+    let alice_provider = create_provider();
+    let bob_provider = create_provider();
+
+    let alice_signer = mls::identity_signer(&alice).unwrap();
+    assert_eq!(alice_signer.public(), alice.user_id().0.as_slice());
+
+    let space_id = SpaceId([5; 32]);
+    let mut alice_group = MlsGroup::create(
+        space_id,
+        alice.user_id(),
+        alice_signer,
+        MlsGroupConfig::default(),
+        &alice_provider,
+    ).unwrap();
+
+    let mut bob_store = KeyPackageStore::new(
+        bob.user_id(),
+        mls::identity_signer(&bob).unwrap(),
+        Ciphersuite::MLS_128_DHKEMX25519_AES128GCM_SHA256_Ed25519,
+    );
+    let bundles = bob_store.generate_key_packages(1, &bob_provider).unwrap();
+    let key_package = KeyPackageStore::deserialize_key_package(&bundles[0], &alice_provider).unwrap();
+    let (_commit, welcome) = alice_group.add_member_with_key_package(
+        bob.user_id(),
+        Role::Member,
+        key_package,
+        &alice.user_id(),
+        &alice_provider,
+    ).unwrap();
+    let mut bob_group = MlsGroup::from_welcome(
+        welcome.to_bytes().unwrap(),
+        bob.user_id(),
+        bob_store.signer(),
+        &bob_provider,
+    ).unwrap();
+
+    // Bob seals an op he signed; Alice learns both the author and the sender
+    let bob_dir = TempDir::new().unwrap();
+    let (_, bob_op, _) = create_client(&bob_dir, bob.clone())
+        .create_space("Bob's".to_string(), None).await.unwrap();
+    let sealed = bob_group.encrypt_application_message(&bob_op.to_canonical_bytes(), &bob_provider)
+        .unwrap().to_bytes().unwrap();
+    let (plaintext, sender) = alice_group.decrypt_application_message_with_sender(&sealed, &alice_provider).unwrap();
+    let op = CrdtOp::from_canonical_bytes(&plaintext).unwrap();
+    assert!(op.verify_signature());
+    assert_eq!(sender, Some(op.author));
+    assert_eq!(sender, Some(bob.user_id()));
+
+    // An op Alice signed but Bob relays is caught: the sender isn't the author
+    let alice_dir = TempDir::new().unwrap();
+    let (_, alice_op, _) = create_client(&alice_dir, alice.clone())
+        .create_space("Alice's".to_string(), None).await.unwrap();
+    let relayed = bob_group.encrypt_application_message(&alice_op.to_canonical_bytes(), &bob_provider)
+        .unwrap().to_bytes().unwrap();
+    let (plaintext, sender) = alice_group.decrypt_application_message_with_sender(&relayed, &alice_provider).unwrap();
+    let op = CrdtOp::from_canonical_bytes(&plaintext).unwrap();
+    assert_ne!(sender, Some(op.author));
+}
+
+#[test]
+fn test_signer_that_cannot_export_has_no_mls_key() {
+    let key = Keypair::generate();
+    let token = ExternalSigner::new(key.public_key(), move |message| Ok(key.sign(message).0));
+
+    // A fresh key would give a leaf bound to no one
+    assert!(mls::identity_signer(&token).is_err());
+}
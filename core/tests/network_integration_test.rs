@@ -22,6 +22,7 @@ async fn create_test_client(_name: &str, port: u16) -> Result<(Client, TempDir)>
         storage_path: temp_dir.path().to_path_buf(),
         listen_addrs: vec![format!("/ip4/127.0.0.1/tcp/{}", port)],
         bootstrap_peers: vec![],
+        ..Default::default()
     };
     
     let client = Client::new(keypair, config)?;
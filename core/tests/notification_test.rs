@@ -0,0 +1,64 @@
+//! Mentions and replies land in the notification inbox until marked read
+
+use spaceway_core::crypto::signing::Keypair;
+use spaceway_core::{Client, ClientConfig, NotificationKind, Role};
+use tempfile::TempDir;
+
+fn create_client(temp_dir: &TempDir, keypair: Keypair) -> Client {
+    let config = ClientConfig {
+        storage_path: temp_dir.path().to_path_buf(),
+        listen_addrs: vec!["/ip4/127.0.0.1/tcp/0".to_string()],
+        bootstrap_peers: vec![],
+        ..Default::default()
+    };
+    Client::new(keypair, config).unwrap()
+}
+
+#[tokio::test]
+async fn test_mention_notifies_until_marked_read() {
+    let alice_dir = TempDir::new().unwrap();
+    let bob_dir = TempDir::new().unwrap();
+    let alice_key = Keypair::generate();
+    let alice = create_client(&alice_dir, alice_key.clone());
+    let bob = create_client(&bob_dir, Keypair::generate());
+
+    let (space, space_op, _) = alice.create_space("Inbox".to_string(), None).await.unwrap();
+    let (channel, channel_op) = alice.create_channel(space.id, "general".to_string(), None).await.unwrap();
+    let (thread, thread_op) = alice.create_thread(space.id, channel.id, None, "Hello".to_string()).await.unwrap();
+    let member_op = alice.add_member(space.id, bob.user_id(), Role::Member).await.unwrap();
+    let (own, own_op) = alice.post_message(space.id, thread.id, format!("my own @{}", alice.user_id().short())).await.unwrap();
+    for op in [space_op, channel_op, thread_op, member_op, own_op] {
+        bob.handle_incoming_op(op).await.unwrap();
+    }
+    let group_info = alice.channel_group_info(&channel.id).await.unwrap();
+    bob.join_channel_with_group_info(&channel.id, &group_info).await.unwrap();
+
+    let mention = format!("ping @{}", alice.user_id().short());
+    let (mentioning, mention_op) = bob.post_message(space.id, thread.id, mention.clone()).await.unwrap();
+    let (reply, reply_op) = bob.quote_reply(thread.id, own.id, "agreed".to_string()).await.unwrap();
+    let (_, unrelated_op) = bob.post_message(space.id, thread.id, "nothing to see".to_string()).await.unwrap();
+    for op in [mention_op, reply_op, unrelated_op] {
+        alice.handle_incoming_op(op).await.unwrap();
+    }
+
+    // Newest first; her own mention and the unrelated message don't notify
+    let notifications = alice.notifications().unwrap();
+    assert_eq!(notifications.len(), 2);
+    assert_eq!(notifications[0].message_id, reply.id);
+    assert_eq!(notifications[0].kind, NotificationKind::Reply);
+    assert_eq!(notifications[1].message_id, mentioning.id);
+    assert_eq!(notifications[1].kind, NotificationKind::Mention);
+    assert_eq!(notifications[1].content, mention);
+    assert!(bob.notifications().unwrap().is_empty());
+
+    alice.mark_notification_read(&mentioning.id).unwrap();
+    let remaining = alice.notifications().unwrap();
+    assert_eq!(remaining.len(), 1);
+    assert_eq!(remaining[0].message_id, reply.id);
+
+    // Both the inbox and the read marker survive a restart
+    drop(alice);
+    let alice = create_client(&alice_dir, alice_key);
+    let after_restart = alice.notifications().unwrap();
+    assert_eq!(after_restart, remaining);
+}
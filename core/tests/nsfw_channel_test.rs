@@ -0,0 +1,49 @@
+//! Integration tests for NSFW / age-restricted channels
+
+use spaceway_core::{Client, ClientConfig};
+use spaceway_core::crypto::signing::Keypair;
+use tempfile::TempDir;
+
+fn create_client(temp_dir: &TempDir, show_nsfw: bool) -> Client {
+    let config = ClientConfig {
+        storage_path: temp_dir.path().to_path_buf(),
+        listen_addrs: vec!["/ip4/127.0.0.1/tcp/0".to_string()],
+        bootstrap_peers: vec![],
+        show_nsfw,
+        ..Default::default()
+    };
+    Client::new(Keypair::generate(), config).unwrap()
+}
+
+#[tokio::test]
+async fn test_nsfw_flag_replicates_and_is_listed() {
+    let owner_dir = TempDir::new().unwrap();
+    let replica_dir = TempDir::new().unwrap();
+    let owner = create_client(&owner_dir, true);
+    let replica = create_client(&replica_dir, false);
+
+    let (space, space_op, _) = owner.create_space("Gallery".to_string(), None).await.unwrap();
+    let (channel, channel_op) = owner.create_channel(space.id, "after-dark".to_string(), None).await.unwrap();
+    let (thread, thread_op) = owner.create_thread(space.id, channel.id, None, "Hello".to_string()).await.unwrap();
+    assert!(!channel.nsfw);
+
+    let flag_op = owner.set_channel_nsfw(channel.id, true).await.unwrap();
+    for op in [space_op, channel_op, thread_op, flag_op] {
+        replica.handle_incoming_op(op).await.unwrap();
+    }
+
+    for client in [&owner, &replica] {
+        let channels = client.list_channels(&space.id).await;
+        assert_eq!(channels.len(), 1);
+        assert!(channels[0].nsfw);
+    }
+
+    let snapshot = spaceway_core::dashboard::ChannelSnapshot::from_channel(&replica.get_channel(&channel.id).await.unwrap());
+    assert!(snapshot.nsfw);
+
+    // The owner opted in; the replica did not
+    assert!(owner.post_message(space.id, thread.id, "Still here".to_string()).await.is_ok());
+    assert!(!owner.list_messages(&thread.id).await.is_empty());
+    assert!(replica.list_messages(&thread.id).await.is_empty());
+    assert!(replica.post_message(space.id, thread.id, "Hi".to_string()).await.is_err());
+}
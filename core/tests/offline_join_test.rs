@@ -22,6 +22,7 @@ async fn test_offline_join_workflow() -> Result<()> {
         storage_path: PathBuf::from("./test-data/alice-offline-join"),
         listen_addrs: vec!["/ip4/127.0.0.1/tcp/0".to_string()],
         bootstrap_peers: vec![],
+        ..Default::default()
     };
     let alice = Client::new(alice_keypair.clone(), alice_config)?;
     alice.start().await?;
@@ -40,7 +41,7 @@ async fn test_offline_join_workflow() -> Result<()> {
     println!("✓ Alice created Space: {}", space.name);
     
     // Alice creates an invite
-    let invite_op = alice.create_invite(space_id, None, None).await?;
+    let invite_op = alice.create_invite(space_id, None, None, None, None).await?;
     
     // Extract invite code from the operation
     let invite_code = if let OpType::CreateInvite(OpPayload::CreateInvite { invite }) = &invite_op.op_type {
@@ -67,6 +68,7 @@ async fn test_offline_join_workflow() -> Result<()> {
         storage_path: PathBuf::from("./test-data/bob-offline-join"),
         listen_addrs: vec!["/ip4/127.0.0.1/tcp/0".to_string()],
         bootstrap_peers: vec![],
+        ..Default::default()
     };
     let bob = Client::new(bob_keypair.clone(), bob_config)?;
     bob.start().await?;
@@ -113,6 +115,7 @@ async fn test_join_checks_dht_on_missing_space() -> Result<()> {
         storage_path: PathBuf::from("./test-data/test-dht-check"),
         listen_addrs: vec!["/ip4/127.0.0.1/tcp/0".to_string()],
         bootstrap_peers: vec![],
+        ..Default::default()
     };
     let client = Client::new(keypair.clone(), config)?;
     client.start().await?;
@@ -0,0 +1,150 @@
+//! Integration tests for op size limits and the per-author receive throttle
+
+use spaceway_core::crdt::{CrdtOp, Hlc, OpLimits, OpPayload, OpType, OpValidator, RateLimit, RejectionReason, ValidationResult};
+use spaceway_core::crypto::signing::Keypair;
+use spaceway_core::network::OpThrottle;
+use spaceway_core::types::*;
+use spaceway_core::{Client, ClientConfig, Error};
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+use tempfile::TempDir;
+
+fn message(keypair: &Keypair, space_id: SpaceId, wall_time: u64, content: String) -> CrdtOp {
+    let mut op = CrdtOp {
+        op_id: OpId(uuid::Uuid::new_v4()),
+        space_id,
+        channel_id: None,
+        thread_id: None,
+        op_type: OpType::PostMessage(OpPayload::PostMessage {
+            message_id: MessageId([wall_time as u8; 32]),
+            content,
+            quote: None,
+        }),
+        prev_ops: vec![],
+        author: keypair.user_id(),
+        epoch: EpochId(0),
+        hlc: Hlc { wall_time, logical: 0 },
+        timestamp: wall_time,
+        signature: Signature([0u8; 64]),
+    };
+    op.sign(keypair).unwrap();
+    op
+}
+
+#[test]
+fn test_oversized_op_is_rejected() {
+    let author = Keypair::generate();
+    let space_id = SpaceId([3u8; 32]);
+    let validator = OpValidator::with_limits(OpLimits { max_op_bytes: 1024, rate_limit: None, ..OpLimits::default() });
+
+    let small = message(&author, space_id, 100, "hi".to_string());
+    assert_eq!(validator.validate(&small, &HashMap::new()), ValidationResult::Accept);
+
+    let large = message(&author, space_id, 101, "x".repeat(2048));
+    assert_eq!(
+        validator.validate(&large, &HashMap::new()),
+        ValidationResult::Reject(RejectionReason::TooLarge)
+    );
+}
+
+#[test]
+fn test_burst_beyond_rate_limit_is_throttled() {
+    let author = Keypair::generate().user_id();
+    let other = Keypair::generate().user_id();
+    let mut throttle = OpThrottle::new(Some(RateLimit { max_ops: 3, window_ms: 1000 }));
+    let start = Instant::now();
+    let at = |ms| start + Duration::from_millis(ms);
+
+    for ms in [100, 200, 300] {
+        assert!(throttle.admit(author, at(ms)));
+    }
+
+    // A fourth op received inside the window is dropped
+    assert!(!throttle.admit(author, at(400)));
+
+    // Other authors have their own window
+    assert!(throttle.admit(other, at(400)));
+
+    // Once the earliest op slides out of the window, the author gets through again
+    assert!(throttle.admit(author, at(1150)));
+}
+
+#[test]
+fn test_rate_limit_does_not_decide_validity() {
+    // However an author stamps their ops, the validator accepts each one:
+    // replicas must agree on the CRDT whatever order ops arrive in
+    let author = Keypair::generate();
+    let space_id = SpaceId([4u8; 32]);
+    let mut validator = OpValidator::with_limits(OpLimits {
+        rate_limit: Some(RateLimit { max_ops: 1, window_ms: 1000 }),
+        ..OpLimits::default()
+    });
+
+    for wall_time in [100, 100, 101] {
+        let op = message(&author, space_id, wall_time, format!("burst {}", wall_time));
+        assert_eq!(validator.validate(&op, &HashMap::new()), ValidationResult::Accept);
+        validator.apply_op(&op);
+    }
+}
+
+#[tokio::test]
+async fn test_local_op_over_limit_is_refused() {
+    let temp_dir = TempDir::new().unwrap();
+    let config = ClientConfig {
+        storage_path: temp_dir.path().to_path_buf(),
+        listen_addrs: vec!["/ip4/127.0.0.1/tcp/0".to_string()],
+        bootstrap_peers: vec![],
+        op_limits: OpLimits { max_op_bytes: 4096, rate_limit: None, ..OpLimits::default() },
+        ..Default::default()
+    };
+    let client = Client::new(Keypair::generate(), config).unwrap();
+
+    let (space, _, _) = client.create_space("Limited".to_string(), None).await.unwrap();
+    let (channel, _) = client.create_channel(space.id, "general".to_string(), None).await.unwrap();
+    let (thread, _) = client.create_thread(space.id, channel.id, None, "First".to_string()).await.unwrap();
+
+    let before = client.list_messages(&thread.id).await.len();
+
+    assert!(client.post_message(space.id, thread.id, "x".repeat(8192)).await.is_err());
+    assert_eq!(client.list_messages(&thread.id).await.len(), before);
+}
+
+#[tokio::test]
+async fn test_oversized_message_is_refused_locally_and_remotely() {
+    let alice_dir = TempDir::new().unwrap();
+    let bob_dir = TempDir::new().unwrap();
+    let config = |dir: &TempDir, max_message_bytes| ClientConfig {
+        storage_path: dir.path().to_path_buf(),
+        listen_addrs: vec!["/ip4/127.0.0.1/tcp/0".to_string()],
+        bootstrap_peers: vec![],
+        max_message_bytes,
+        ..Default::default()
+    };
+    // Alice allows big messages, Bob keeps the default limit
+    let alice = Client::new(Keypair::generate(), config(&alice_dir, 64 * 1024)).unwrap();
+    let bob = Client::new(Keypair::generate(), config(&bob_dir, ClientConfig::default().max_message_bytes)).unwrap();
+
+    let (space, space_op, _) = bob.create_space("Limited".to_string(), None).await.unwrap();
+    let (channel, channel_op) = bob.create_channel(space.id, "general".to_string(), None).await.unwrap();
+    let (thread, thread_op) = bob.create_thread(space.id, channel.id, None, "First".to_string()).await.unwrap();
+    let member_op = bob.add_member(space.id, alice.user_id(), Role::Member).await.unwrap();
+    for op in [space_op, channel_op, thread_op, member_op] {
+        alice.handle_incoming_op(op).await.unwrap();
+    }
+    let group_info = bob.channel_group_info(&channel.id).await.unwrap();
+    alice.join_channel_with_group_info(&channel.id, &group_info).await.unwrap();
+
+    // A normal message is fine
+    bob.post_message(space.id, thread.id, "hello".to_string()).await.unwrap();
+
+    // Locally, the sender is pointed at attachments
+    let result = bob.post_message(space.id, thread.id, "x".repeat(20 * 1024)).await;
+    assert!(matches!(result, Err(Error::InvalidOperation(ref e)) if e.contains("attachment")));
+
+    // Remotely, a receiver with a smaller limit rejects the op
+    let (_, small_op) = alice.post_message(space.id, thread.id, "hi bob".to_string()).await.unwrap();
+    bob.handle_incoming_op(small_op).await.unwrap();
+    let (_, big_op) = alice.post_message(space.id, thread.id, "x".repeat(20 * 1024)).await.unwrap();
+    assert!(bob.handle_incoming_op(big_op).await.is_err());
+    assert!(bob.list_messages(&thread.id).await.iter().all(|m| m.content.len() < 1024));
+}
@@ -0,0 +1,53 @@
+//! Raw op subscription for indexers
+
+use spaceway_core::crdt::{OpPayload, OpType};
+use spaceway_core::crypto::signing::Keypair;
+use spaceway_core::{Client, ClientConfig, Role};
+use tempfile::TempDir;
+
+fn create_client(temp_dir: &TempDir) -> Client {
+    let config = ClientConfig {
+        storage_path: temp_dir.path().to_path_buf(),
+        listen_addrs: vec!["/ip4/127.0.0.1/tcp/0".to_string()],
+        bootstrap_peers: vec![],
+        ..Default::default()
+    };
+    Client::new(Keypair::generate(), config).unwrap()
+}
+
+#[tokio::test]
+async fn test_posted_message_surfaces_as_post_message_op() {
+    let alice_dir = TempDir::new().unwrap();
+    let bob_dir = TempDir::new().unwrap();
+    let alice = create_client(&alice_dir);
+    let bob = create_client(&bob_dir);
+
+    let (space, space_op, _) = alice.create_space("Indexed".to_string(), None).await.unwrap();
+    let (channel, channel_op) = alice.create_channel(space.id, "general".to_string(), None).await.unwrap();
+    let (thread, thread_op) = alice.create_thread(space.id, channel.id, None, "Hello".to_string()).await.unwrap();
+    let member_op = alice.add_member(space.id, bob.user_id(), Role::Member).await.unwrap();
+    for op in [space_op, channel_op, thread_op, member_op] {
+        bob.handle_incoming_op(op).await.unwrap();
+    }
+
+    // Only ops applied after subscribing are delivered
+    let mut alice_ops = alice.subscribe_ops();
+    let mut bob_ops = bob.subscribe_ops();
+    let (message, post_op) = alice.post_message(space.id, thread.id, "index me".to_string()).await.unwrap();
+    bob.handle_incoming_op(post_op.clone()).await.unwrap();
+
+    let (op, space_id) = bob_ops.try_recv().unwrap();
+    assert_eq!(space_id, space.id);
+    assert_eq!(op.op_id, post_op.op_id);
+    match op.op_type {
+        OpType::PostMessage(OpPayload::PostMessage { message_id, content, .. }) => {
+            assert_eq!(message_id, message.id);
+            assert_eq!(content, "index me");
+        }
+        other => panic!("expected PostMessage, got {:?}", other),
+    }
+    assert!(bob_ops.try_recv().is_err());
+
+    // Local ops are not echoed to the author
+    assert!(alice_ops.try_recv().is_err());
+}
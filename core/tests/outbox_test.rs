@@ -0,0 +1,64 @@
+//! Ops created while offline are queued and sent once a peer connects
+
+use spaceway_core::crypto::signing::Keypair;
+use spaceway_core::{Client, ClientConfig, SpaceMembershipMode, SpaceVisibility};
+use std::time::Duration;
+use tempfile::TempDir;
+use tokio::time::{sleep, Instant};
+
+fn config(temp_dir: &TempDir) -> ClientConfig {
+    ClientConfig {
+        storage_path: temp_dir.path().to_path_buf(),
+        listen_addrs: vec!["/ip4/127.0.0.1/tcp/0".to_string()],
+        bootstrap_peers: vec![],
+        ..Default::default()
+    }
+}
+
+#[tokio::test]
+async fn test_offline_op_is_sent_when_peer_connects() {
+    let alice_dir = TempDir::new().unwrap();
+    let alice = Client::new(Keypair::generate(), config(&alice_dir)).unwrap();
+    alice.start().await.unwrap();
+
+    // Lightweight, so the op goes out in plaintext and Bob can apply it
+    let (space, space_op, _) = alice.create_space_with_mode(
+        "Offline first".to_string(),
+        None,
+        SpaceVisibility::default(),
+        SpaceMembershipMode::Lightweight,
+    ).await.unwrap();
+    let unsent = alice.unsent_ops().await.unwrap();
+    assert_eq!(unsent.iter().map(|op| op.op_id).collect::<Vec<_>>(), vec![space_op.op_id]);
+
+    let bob_dir = TempDir::new().unwrap();
+    let bob = Client::new(Keypair::generate(), config(&bob_dir)).unwrap();
+    bob.start().await.unwrap();
+    bob.subscribe_to_space(&space.id).await.unwrap();
+
+    let bob_addr = bob.listening_addrs().await[0].clone();
+    alice.network_dial(&format!("{}/p2p/{}", bob_addr, bob.peer_id().await)).await.unwrap();
+
+    let deadline = Instant::now() + Duration::from_secs(30);
+    while bob.get_space(&space.id).await.is_none() {
+        assert!(Instant::now() < deadline, "Bob never received the queued op");
+        sleep(Duration::from_millis(250)).await;
+    }
+    assert!(alice.unsent_ops().await.unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn test_unsent_ops_survive_restart() {
+    let temp_dir = TempDir::new().unwrap();
+    let keypair = Keypair::generate();
+
+    let op_id = {
+        let client = Client::new(keypair.clone(), config(&temp_dir)).unwrap();
+        let (_, op, _) = client.create_space("Queued".to_string(), None).await.unwrap();
+        op.op_id
+    };
+
+    let client = Client::new(keypair, config(&temp_dir)).unwrap();
+    let unsent = client.unsent_ops().await.unwrap();
+    assert!(unsent.iter().any(|op| op.op_id == op_id));
+}
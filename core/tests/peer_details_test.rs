@@ -0,0 +1,55 @@
+//! Connected-peer details: addresses, connection type and uptime
+
+use spaceway_core::crypto::signing::Keypair;
+use spaceway_core::network::ConnectionType;
+use spaceway_core::{Client, ClientConfig};
+use std::time::{Duration, SystemTime};
+use tempfile::TempDir;
+use tokio::time::{sleep, Instant};
+
+fn create_client(temp_dir: &TempDir) -> Client {
+    let config = ClientConfig {
+        storage_path: temp_dir.path().to_path_buf(),
+        listen_addrs: vec!["/ip4/127.0.0.1/tcp/0".to_string()],
+        bootstrap_peers: vec![],
+        ..Default::default()
+    };
+    Client::new(Keypair::generate(), config).unwrap()
+}
+
+#[tokio::test]
+async fn test_connected_peer_has_addresses() {
+    let alice_dir = TempDir::new().unwrap();
+    let bob_dir = TempDir::new().unwrap();
+    let alice = create_client(&alice_dir);
+    let bob = create_client(&bob_dir);
+    alice.start().await.unwrap();
+    bob.start().await.unwrap();
+    sleep(Duration::from_millis(500)).await;
+
+    let before_dial = SystemTime::now();
+    let alice_addr = alice.listening_addrs().await[0].clone();
+    let alice_peer = alice.peer_id().await;
+    bob.network_dial(&format!("{}/p2p/{}", alice_addr, alice_peer)).await.unwrap();
+
+    let deadline = Instant::now() + Duration::from_secs(10);
+    let detail = loop {
+        if let Some(detail) = bob.peer_details().await.into_iter().find(|peer| peer.peer_id == alice_peer) {
+            break detail;
+        }
+        assert!(Instant::now() < deadline, "Alice never showed up in Bob's peer details");
+        sleep(Duration::from_millis(100)).await;
+    };
+
+    assert!(!detail.addrs.is_empty());
+    assert!(detail.addrs.iter().any(|addr| addr.to_string().starts_with("/ip4/127.0.0.1/tcp/")));
+    assert_eq!(detail.connection_type, ConnectionType::Direct);
+    assert!(detail.since >= before_dial - Duration::from_secs(1));
+    assert!(detail.uptime() < Duration::from_secs(60));
+
+    // The dashboard snapshot carries the same peer
+    let snapshot = bob.get_dashboard_snapshot("bob").await;
+    let peer = snapshot.peers.iter().find(|peer| peer.peer_id == alice_peer.to_string()).unwrap();
+    assert_eq!(peer.connection_type, "direct");
+    assert!(!peer.addrs.is_empty());
+}
@@ -0,0 +1,135 @@
+//! Peer exchange: bounded announcements, verified-only trust, and a third
+//! client discovering a peer it was never told about directly
+
+use libp2p::{Multiaddr, PeerId};
+use spaceway_core::crypto::signing::Keypair;
+use spaceway_core::network::peer_exchange::{MAX_AUTO_DIALS, MAX_EXCHANGED_PEERS};
+use spaceway_core::network::{PeerBook, PeerExchange, PeerRecord};
+use spaceway_core::types::SpaceId;
+use spaceway_core::{Client, ClientConfig};
+use std::time::Duration;
+use tempfile::TempDir;
+use tokio::time::{sleep, Instant};
+
+fn record(peer_id: &PeerId, port: u16) -> PeerRecord {
+    PeerRecord {
+        peer_id: peer_id.to_string(),
+        addrs: vec![format!("/ip4/127.0.0.1/tcp/{}", port)],
+    }
+}
+
+#[test]
+fn test_announcements_are_capped_and_only_verified_peers_are_known() {
+    let space_id = SpaceId([1u8; 32]);
+    let local = PeerId::random();
+    let sender = PeerId::random();
+    let mut book = PeerBook::default();
+
+    let listed: Vec<PeerId> = (0..MAX_EXCHANGED_PEERS * 2).map(|_| PeerId::random()).collect();
+    let mut peers: Vec<PeerRecord> = listed.iter().enumerate()
+        .map(|(i, peer_id)| record(peer_id, 5000 + i as u16))
+        .collect();
+    peers.push(record(&local, 4000));
+    peers.push(PeerRecord { peer_id: "not a peer id".to_string(), addrs: vec![] });
+    let exchange = PeerExchange { space_id, sender: record(&sender, 4001), peers };
+
+    // A bounded number of dials, each pinned to the peer ID, never to ourselves
+    let dials = book.learn(&local, &exchange);
+    assert_eq!(dials.len(), MAX_AUTO_DIALS);
+    assert!(dials.iter().all(|addr| addr.to_string().contains("/p2p/")));
+    assert!(!dials.iter().any(|addr| addr.to_string().contains(&local.to_string())));
+
+    // Repeating the announcement doesn't re-dial the same peers
+    let again = book.learn(&local, &exchange);
+    assert!(again.iter().all(|addr| !dials.contains(addr)));
+
+    // Nothing is known until a connection succeeds
+    assert!(book.known_peers(&space_id).is_empty());
+    book.connected(sender);
+    book.connected(listed[MAX_EXCHANGED_PEERS]);
+    let known: Vec<String> = book.known_peers(&space_id).into_iter().map(|r| r.peer_id).collect();
+    assert_eq!(known, vec![sender.to_string()], "peers past the cap are never recorded");
+}
+
+#[test]
+fn test_verified_addresses_are_not_overwritten() {
+    let space_id = SpaceId([2u8; 32]);
+    let local = PeerId::random();
+    let peer = PeerId::random();
+    let mut book = PeerBook::default();
+
+    book.learn(&local, &PeerExchange { space_id, sender: record(&peer, 6000), peers: vec![] });
+    book.connected(peer);
+
+    // Someone else claims the peer moved
+    let liar = PeerId::random();
+    let dials = book.learn(&local, &PeerExchange { space_id, sender: record(&liar, 6001), peers: vec![record(&peer, 6666)] });
+    assert!(!dials.iter().any(|addr| addr.to_string().contains(&peer.to_string())));
+
+    let known = book.known_peers(&space_id);
+    assert_eq!(known[0].addrs, vec!["/ip4/127.0.0.1/tcp/6000".to_string()]);
+
+    // Our own announcement passes on only what we verified
+    let listen: Vec<Multiaddr> = vec!["/ip4/0.0.0.0/tcp/7000".parse().unwrap(), "/ip4/127.0.0.1/tcp/7000".parse().unwrap()];
+    let announcement = book.announcement(space_id, &local, &listen);
+    assert_eq!(announcement.sender.addrs, vec!["/ip4/127.0.0.1/tcp/7000".to_string()]);
+    assert_eq!(announcement.peers.len(), 1);
+    assert_eq!(PeerExchange::from_bytes(&announcement.to_bytes().unwrap()).unwrap(), announcement);
+}
+
+fn create_client(temp_dir: &TempDir) -> Client {
+    let config = ClientConfig {
+        storage_path: temp_dir.path().to_path_buf(),
+        listen_addrs: vec!["/ip4/127.0.0.1/tcp/0".to_string()],
+        bootstrap_peers: vec![],
+        peer_exchange: true,
+        ..Default::default()
+    };
+    Client::new(Keypair::generate(), config).unwrap()
+}
+
+async fn connect(from: &Client, to: &Client) {
+    let addr = to.listening_addrs().await[0].clone();
+    from.network_dial(&format!("{}/p2p/{}", addr, to.peer_id().await)).await.unwrap();
+}
+
+#[tokio::test]
+async fn test_third_client_discovers_peer_through_exchange() {
+    let dirs: Vec<TempDir> = (0..3).map(|_| TempDir::new().unwrap()).collect();
+    let alice = create_client(&dirs[0]);
+    let bob = create_client(&dirs[1]);
+    let carol = create_client(&dirs[2]);
+    for client in [&alice, &bob, &carol] {
+        client.start().await.unwrap();
+    }
+
+    let (space, space_op, _) = alice.create_space("Meshy".to_string(), None).await.unwrap();
+    for client in [&bob, &carol] {
+        client.handle_incoming_op(space_op.clone()).await.unwrap();
+    }
+    for client in [&alice, &bob, &carol] {
+        client.subscribe_to_space(&space.id).await.unwrap();
+    }
+
+    // Alice and Bob know each other; Carol only knows Bob
+    connect(&bob, &alice).await;
+    sleep(Duration::from_secs(3)).await;
+    connect(&carol, &bob).await;
+
+    let alice_peer = alice.peer_id().await.to_string();
+    let deadline = Instant::now() + Duration::from_secs(30);
+    let mut discovered = false;
+    while Instant::now() < deadline {
+        if carol.known_peers(&space.id).await.iter().any(|peer| peer.peer_id == alice_peer) {
+            discovered = true;
+            break;
+        }
+        sleep(Duration::from_millis(250)).await;
+    }
+    assert!(discovered, "Carol never learned about Alice through Bob");
+
+    let carol_connections: Vec<String> = carol.connected_peer_info().await.iter()
+        .map(|peer| peer.peer_id.to_string())
+        .collect();
+    assert!(carol_connections.contains(&alice_peer));
+}
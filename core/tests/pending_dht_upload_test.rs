@@ -0,0 +1,51 @@
+//! Ops whose DHT upload fails are queued and uploaded once a peer connects
+
+use spaceway_core::crypto::signing::Keypair;
+use spaceway_core::{Client, ClientConfig, SpaceMembershipMode, SpaceVisibility};
+use std::time::Duration;
+use tempfile::TempDir;
+use tokio::time::{sleep, Instant};
+
+fn create_client(temp_dir: &TempDir) -> Client {
+    let config = ClientConfig {
+        storage_path: temp_dir.path().to_path_buf(),
+        listen_addrs: vec!["/ip4/127.0.0.1/tcp/0".to_string()],
+        bootstrap_peers: vec![],
+        ..Default::default()
+    };
+    Client::new(Keypair::generate(), config).unwrap()
+}
+
+#[tokio::test]
+async fn test_offline_op_is_uploaded_after_reconnection() {
+    let alice_dir = TempDir::new().unwrap();
+    let alice = create_client(&alice_dir);
+    alice.start().await.unwrap();
+
+    // No peers: the DHT store fails and the op is queued. Lightweight, so
+    // Bob can open the batch without being in an MLS group
+    let (space, space_op, _) = alice.create_space_with_mode(
+        "Offline".to_string(),
+        None,
+        SpaceVisibility::default(),
+        SpaceMembershipMode::Lightweight,
+    ).await.unwrap();
+    let pending = alice.pending_dht_uploads().await.unwrap();
+    assert!(pending.iter().any(|op| op.op_id == space_op.op_id));
+
+    // A peer connects; the retrier flushes the queue
+    let bob_dir = TempDir::new().unwrap();
+    let bob = create_client(&bob_dir);
+    bob.start().await.unwrap();
+    let bob_addr = bob.listening_addrs().await[0].clone();
+    alice.network_dial(&format!("{}/p2p/{}", bob_addr, bob.peer_id().await)).await.unwrap();
+
+    let deadline = Instant::now() + Duration::from_secs(90);
+    while !alice.pending_dht_uploads().await.unwrap().is_empty() {
+        assert!(Instant::now() < deadline, "queued ops were never uploaded");
+        sleep(Duration::from_millis(500)).await;
+    }
+
+    let ops = bob.dht_get_operations(&space.id).await.unwrap();
+    assert!(ops.iter().any(|op| op.op_id == space_op.op_id));
+}
@@ -148,6 +148,7 @@ fn demo_3_custom_role_creation() {
         permissions: content_creator_permissions,
         position: 50, // Between Member (0) and Moderator (100)
         color: Some("#FF6B6B".to_string()), // Nice red color
+        hoisted: false,
     };
     
     let role_id = content_creator_role.id;
@@ -238,6 +239,7 @@ fn demo_5_permission_combinations() {
         permissions: support_permissions,
         position: 75,
         color: Some("#4ECDC4".to_string()),
+        hoisted: false,
     };
     
     println!("Support role permissions (bitfield):");
@@ -324,6 +326,7 @@ fn demo_7_realistic_scenario() {
             | SpacePermissions::USE_VOICE,
         position: 25,
         color: Some("#FFD700".to_string()), // Gold
+        hoisted: false,
     };
     let vip_role_id = vip_role.id;
     space.roles.insert(vip_role_id, vip_role);
@@ -339,6 +342,7 @@ fn demo_7_realistic_scenario() {
             | SpacePermissions::MENTION_ALL,
         position: 60,
         color: Some("#9B59B6".to_string()), // Purple
+        hoisted: false,
     };
     let organizer_role_id = organizer_role.id;
     space.roles.insert(organizer_role_id, organizer_role);
@@ -352,6 +356,7 @@ fn demo_7_realistic_scenario() {
             | SpacePermissions::SEND_MESSAGES,
         position: 80,
         color: Some("#3498DB".to_string()), // Blue
+        hoisted: false,
     };
     let helper_role_id = helper_role.id;
     space.roles.insert(helper_role_id, helper_role);
@@ -155,6 +155,7 @@ fn demo_4_custom_role_creation() {
         permissions: support_perms,
         position: 75, // Between Moderator (50) and Admin (100)
         color: Some(0x3498DB), // Blue
+        hoisted: false,
     };
     
     println!("Created custom 'Support' role:");
@@ -376,6 +377,7 @@ fn demo_10_realistic_gaming_server() {
         },
         position: 25,
         color: Some(0xFFD700), // Gold
+        hoisted: false,
     };
     
     // Create Event Organizer role
@@ -389,6 +391,7 @@ fn demo_10_realistic_gaming_server() {
         },
         position: 60,
         color: Some(0x9B59B6), // Purple
+        hoisted: false,
     };
     
     let vip_id = vip_role.id;
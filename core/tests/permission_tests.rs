@@ -365,10 +365,9 @@ fn test_backward_compatibility_with_old_role_enum() {
         1000,
     );
     
-    // Old API still works (deprecated members HashMap)
-    #[allow(deprecated)]
-    let old_role = space.members.get(&owner);
-    assert_eq!(old_role, Some(&Role::Admin));
+    // Old API still works (members view computed from member_roles)
+    let members = space.members();
+    assert_eq!(members.get(&owner), Some(&Role::Admin));
     
     // Old Role enum methods still work
     assert!(Role::Admin.is_admin());
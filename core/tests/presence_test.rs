@@ -0,0 +1,76 @@
+//! Presence heartbeats are signed and can't be replayed
+
+use spaceway_core::crypto::signing::Keypair;
+use spaceway_core::network::presence::presence_topic;
+use spaceway_core::network::{Presence, PresenceBook, Stamper};
+use spaceway_core::types::*;
+
+#[test]
+fn test_replayed_presence_is_ignored() {
+    let space_id = SpaceId([8u8; 32]);
+    let alice = Keypair::generate();
+    let mut stamper = Stamper::default();
+    let mut book = PresenceBook::default();
+
+    let first = Presence::new(&alice, space_id, stamper.next()).unwrap().to_bytes().unwrap();
+    let second = Presence::new(&alice, space_id, stamper.next()).unwrap().to_bytes().unwrap();
+    assert!(book.record(&Presence::from_bytes(&first).unwrap()));
+    assert!(book.record(&Presence::from_bytes(&second).unwrap()));
+    let seen = book.last_seen(&space_id)[&alice.user_id()];
+
+    // Re-publishing either heartbeat later changes nothing
+    assert!(!book.record(&Presence::from_bytes(&second).unwrap()));
+    assert!(!book.record(&Presence::from_bytes(&first).unwrap()));
+    assert_eq!(book.last_seen(&space_id)[&alice.user_id()], seen);
+
+    assert!(book.record(&Presence::new(&alice, space_id, stamper.next()).unwrap()));
+    assert!(book.last_seen(&space_id)[&alice.user_id()] > seen);
+    assert_eq!(presence_topic(&space_id), format!("space/{}/presence", hex::encode(&space_id.0[..8])));
+}
+
+#[test]
+fn test_forged_presence_is_ignored() {
+    let space_id = SpaceId([9u8; 32]);
+    let alice = Keypair::generate();
+    let mut book = PresenceBook::default();
+
+    // A replay can't be refreshed with a newer stamp without Alice's key
+    let mut restamped = Presence::new(&alice, space_id, 1).unwrap();
+    restamped.sent_at = u64::MAX;
+    assert!(!restamped.verify());
+    assert!(!book.record(&restamped));
+
+    // Nor can someone else speak for her
+    let mut impersonated = Presence::new(&Keypair::generate(), space_id, 1).unwrap();
+    impersonated.from = alice.user_id();
+    assert!(!book.record(&impersonated));
+    assert!(book.last_seen(&space_id).is_empty());
+}
+
+#[test]
+fn test_heartbeats_to_two_spaces_arrive_out_of_order() {
+    let first_space = SpaceId([10u8; 32]);
+    let second_space = SpaceId([11u8; 32]);
+    let alice = Keypair::generate();
+    let mut stamper = Stamper::default();
+    let mut book = PresenceBook::default();
+
+    // Sent to the first Space, then the second, but delivered the other way round
+    let to_first = Presence::new(&alice, first_space, stamper.next()).unwrap();
+    let to_second = Presence::new(&alice, second_space, stamper.next()).unwrap();
+    assert!(book.record(&to_second));
+    assert!(book.record(&to_first));
+    assert_eq!(book.last_seen(&first_space)[&alice.user_id()], to_first.sent_at);
+    assert_eq!(book.last_seen(&second_space)[&alice.user_id()], to_second.sent_at);
+
+    // Replays are still dropped in each Space
+    assert!(!book.record(&to_first));
+    assert!(!book.record(&to_second));
+}
+
+#[test]
+fn test_stamps_increase_within_a_millisecond() {
+    let mut stamper = Stamper::default();
+    let stamps: Vec<u64> = (0..100).map(|_| stamper.next()).collect();
+    assert!(stamps.windows(2).all(|pair| pair[0] < pair[1]));
+}
@@ -10,6 +10,7 @@ async fn test_public_space_privacy_info() -> Result<()> {
         storage_path: temp_dir.path().to_path_buf(),
         listen_addrs: vec!["/ip4/127.0.0.1/tcp/0".to_string()],
         bootstrap_peers: vec![],
+        ..Default::default()
     };
     
     let client = Client::new(keypair, config)?;
@@ -52,6 +53,7 @@ async fn test_private_space_privacy_info() -> Result<()> {
         storage_path: temp_dir.path().to_path_buf(),
         listen_addrs: vec!["/ip4/127.0.0.1/tcp/0".to_string()],
         bootstrap_peers: vec![],
+        ..Default::default()
     };
     
     let client = Client::new(keypair, config)?;
@@ -90,6 +92,7 @@ async fn test_hidden_space_privacy_info() -> Result<()> {
         storage_path: temp_dir.path().to_path_buf(),
         listen_addrs: vec!["/ip4/127.0.0.1/tcp/0".to_string()],
         bootstrap_peers: vec![],
+        ..Default::default()
     };
     
     let client = Client::new(keypair, config)?;
@@ -128,6 +131,7 @@ async fn test_default_space_uses_private() -> Result<()> {
         storage_path: temp_dir.path().to_path_buf(),
         listen_addrs: vec!["/ip4/127.0.0.1/tcp/0".to_string()],
         bootstrap_peers: vec![],
+        ..Default::default()
     };
     
     let client = Client::new(keypair, config)?;
@@ -175,6 +179,7 @@ async fn test_get_join_privacy_info() -> Result<()> {
         storage_path: temp_dir.path().to_path_buf(),
         listen_addrs: vec!["/ip4/127.0.0.1/tcp/0".to_string()],
         bootstrap_peers: vec![],
+        ..Default::default()
     };
     
     let client = Client::new(keypair, config)?;
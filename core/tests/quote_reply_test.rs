@@ -0,0 +1,55 @@
+//! Quote replies keep the snippet captured at send time
+
+use spaceway_core::crypto::signing::Keypair;
+use spaceway_core::{Client, ClientConfig};
+use tempfile::TempDir;
+
+fn create_client(temp_dir: &TempDir) -> Client {
+    let config = ClientConfig {
+        storage_path: temp_dir.path().to_path_buf(),
+        listen_addrs: vec!["/ip4/127.0.0.1/tcp/0".to_string()],
+        bootstrap_peers: vec![],
+        ..Default::default()
+    };
+    Client::new(Keypair::generate(), config).unwrap()
+}
+
+#[tokio::test]
+async fn test_quoted_snippet_survives_edit_of_original() {
+    let alice_dir = TempDir::new().unwrap();
+    let bob_dir = TempDir::new().unwrap();
+    let alice = create_client(&alice_dir);
+    let bob = create_client(&bob_dir);
+
+    let (space, space_op, _) = alice.create_space("Quotes".to_string(), None).await.unwrap();
+    let (channel, channel_op) = alice.create_channel(space.id, "general".to_string(), None).await.unwrap();
+    let (thread, thread_op) = alice.create_thread(space.id, channel.id, None, "Hello".to_string()).await.unwrap();
+    let (original, original_op) = alice.post_message(space.id, thread.id, "the meeting is at noon".to_string()).await.unwrap();
+
+    let (reply, reply_op) = alice.quote_reply(thread.id, original.id, "see you there".to_string()).await.unwrap();
+    let quote = reply.quote.clone().unwrap();
+    assert_eq!(quote.message_id, original.id);
+    assert_eq!(quote.snippet, "the meeting is at noon");
+
+    let edit_op = alice.edit_message(space.id, original.id, "the meeting is cancelled".to_string()).await.unwrap();
+    for op in [space_op, channel_op, thread_op, original_op, reply_op, edit_op] {
+        bob.handle_incoming_op(op).await.unwrap();
+    }
+
+    for client in [&alice, &bob] {
+        assert_eq!(client.get_message(&original.id).await.unwrap().content, "the meeting is cancelled");
+        let reply = client.get_message(&reply.id).await.unwrap();
+        assert_eq!(reply.quote.as_ref(), Some(&quote));
+    }
+
+    let snapshot = bob.get_dashboard_snapshot("bob").await;
+    let reply_snapshot = snapshot.spaces.iter()
+        .flat_map(|space| &space.channels)
+        .flat_map(|channel| &channel.threads)
+        .flat_map(|thread| &thread.messages)
+        .find(|message| message.id == reply.id.to_string())
+        .unwrap();
+    let quote_snapshot = reply_snapshot.quote.as_ref().unwrap();
+    assert_eq!(quote_snapshot.message_id, original.id.to_string());
+    assert_eq!(quote_snapshot.snippet, "the meeting is at noon");
+}
@@ -0,0 +1,97 @@
+//! A Space's reaction allowlist is enforced locally and on receive
+
+use spaceway_core::crypto::signing::Keypair;
+use spaceway_core::{Client, ClientConfig, Error};
+use tempfile::TempDir;
+
+fn create_client(temp_dir: &TempDir) -> Client {
+    let config = ClientConfig {
+        storage_path: temp_dir.path().to_path_buf(),
+        listen_addrs: vec!["/ip4/127.0.0.1/tcp/0".to_string()],
+        bootstrap_peers: vec![],
+        ..Default::default()
+    };
+    Client::new(Keypair::generate(), config).unwrap()
+}
+
+#[tokio::test]
+async fn test_disallowed_reaction_is_rejected() {
+    let alice_dir = TempDir::new().unwrap();
+    let bob_dir = TempDir::new().unwrap();
+    let carol_dir = TempDir::new().unwrap();
+    let alice = create_client(&alice_dir);
+    let bob = create_client(&bob_dir);
+    let carol = create_client(&carol_dir);
+
+    let (space, space_op, _) = alice.create_space("Quiet".to_string(), None).await.unwrap();
+    let (channel, channel_op) = alice.create_channel(space.id, "general".to_string(), None).await.unwrap();
+    let (thread, thread_op) = alice.create_thread(space.id, channel.id, None, "Hello".to_string()).await.unwrap();
+    let (message, message_op) = alice.post_message(space.id, thread.id, "React to me".to_string()).await.unwrap();
+    let base_ops = [space_op, channel_op, thread_op, message_op];
+
+    // Unrestricted by default
+    assert!(alice.get_space(&space.id).await.unwrap().allowed_reactions.is_none());
+    alice.add_reaction(space.id, message.id, "🎉".to_string()).await.unwrap();
+
+    let allowlist_op = alice.set_allowed_reactions(
+        space.id,
+        Some(vec!["👍".to_string(), ":party_parrot:".to_string()]),
+    ).await.unwrap();
+    // Reactions below come strictly after the allowlist, even in the same millisecond
+    tokio::time::sleep(std::time::Duration::from_millis(5)).await;
+
+    // Bob knows the allowlist, so his client refuses the reaction up front
+    for op in base_ops.iter().cloned().chain([allowlist_op]) {
+        bob.handle_incoming_op(op).await.unwrap();
+    }
+    let result = bob.add_reaction(space.id, message.id, "🎉".to_string()).await;
+    assert!(matches!(result, Err(Error::Rejected(_))));
+    let custom = bob.add_reaction(space.id, message.id, ":party_parrot:".to_string()).await.unwrap();
+
+    // Carol never got the allowlist; Alice refuses her reaction on receive
+    for op in base_ops.iter().cloned() {
+        carol.handle_incoming_op(op).await.unwrap();
+    }
+    let rogue = carol.add_reaction(space.id, message.id, "💩".to_string()).await.unwrap();
+    assert!(alice.handle_incoming_op(rogue).await.is_err());
+    alice.handle_incoming_op(custom).await.unwrap();
+
+    let reactions = alice.get_message(&message.id).await.unwrap().reactions;
+    assert!(reactions[":party_parrot:"].contains(&bob.user_id()));
+    assert!(!reactions.contains_key("💩"));
+
+    // Lifting the allowlist lets any reaction through again
+    alice.set_allowed_reactions(space.id, None).await.unwrap();
+    alice.add_reaction(space.id, message.id, "💩".to_string()).await.unwrap();
+}
+
+#[tokio::test]
+async fn test_reaction_is_checked_against_the_allowlist_it_was_added_under() {
+    let alice_dir = TempDir::new().unwrap();
+    let carol_dir = TempDir::new().unwrap();
+    let alice = create_client(&alice_dir);
+    let carol = create_client(&carol_dir);
+
+    let (space, space_op, _) = alice.create_space("Quiet".to_string(), None).await.unwrap();
+    let (channel, channel_op) = alice.create_channel(space.id, "general".to_string(), None).await.unwrap();
+    let (thread, thread_op) = alice.create_thread(space.id, channel.id, None, "Hello".to_string()).await.unwrap();
+    let (message, message_op) = alice.post_message(space.id, thread.id, "React to me".to_string()).await.unwrap();
+    for op in [space_op, channel_op, thread_op, message_op] {
+        carol.handle_incoming_op(op).await.unwrap();
+    }
+
+    // Carol reacts while anything goes; Alice restricts reactions before it reaches her
+    let early = carol.add_reaction(space.id, message.id, "🎉".to_string()).await.unwrap();
+    tokio::time::sleep(std::time::Duration::from_millis(5)).await;
+    alice.set_allowed_reactions(space.id, Some(vec!["👍".to_string()])).await.unwrap();
+    tokio::time::sleep(std::time::Duration::from_millis(5)).await;
+    let late = carol.add_reaction(space.id, message.id, "🔥".to_string()).await.unwrap();
+
+    // Arrival order doesn't matter: only the reaction added after the restriction is refused
+    assert!(alice.handle_incoming_op(late).await.is_err());
+    alice.handle_incoming_op(early).await.unwrap();
+
+    let reactions = alice.get_message(&message.id).await.unwrap().reactions;
+    assert!(reactions["🎉"].contains(&carol.user_id()));
+    assert!(!reactions.contains_key("🔥"));
+}
@@ -0,0 +1,80 @@
+//! A member removed and re-added is told it rejoined and catches up on what it may read
+
+use spaceway_core::crypto::signing::Keypair;
+use spaceway_core::types::Role;
+use spaceway_core::{Client, ClientConfig, ClientEvent};
+use tempfile::TempDir;
+use tokio::time::{sleep, timeout, Duration};
+
+fn create_client(temp_dir: &TempDir, keypair: Keypair) -> Client {
+    let config = ClientConfig {
+        storage_path: temp_dir.path().to_path_buf(),
+        listen_addrs: vec!["/ip4/127.0.0.1/tcp/0".to_string()],
+        bootstrap_peers: vec![],
+        ..Default::default()
+    };
+    Client::new(keypair, config).unwrap()
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_readded_member_sees_new_messages_but_not_those_from_while_out() {
+    let alice_dir = TempDir::new().unwrap();
+    let bob_dir = TempDir::new().unwrap();
+    let bob_keypair = Keypair::generate();
+    let alice = create_client(&alice_dir, Keypair::generate());
+    let bob = create_client(&bob_dir, bob_keypair.clone());
+    alice.start().await.unwrap();
+    bob.start().await.unwrap();
+    sleep(Duration::from_millis(500)).await;
+
+    let alice_addr = alice.listening_addrs().await
+        .into_iter()
+        .find(|addr| addr.to_string().contains("127.0.0.1"))
+        .expect("Alice should listen on loopback");
+    bob.network_dial(&format!("{}/p2p/{}", alice_addr, alice.peer_id().await)).await.unwrap();
+    sleep(Duration::from_secs(1)).await;
+
+    let (space, space_op, _) = alice.create_space("Club".to_string(), None).await.unwrap();
+    let (channel, channel_op) = alice.create_channel(space.id, "general".to_string(), None).await.unwrap();
+    let (thread, thread_op) = alice.create_thread(space.id, channel.id, None, "Hello".to_string()).await.unwrap();
+    for op in [space_op, channel_op, thread_op] {
+        bob.handle_incoming_op(op).await.unwrap();
+    }
+    bob.subscribe_to_space(&space.id).await.unwrap();
+    sleep(Duration::from_secs(2)).await;
+
+    let bundle = bob.get_key_package_bundle().await.unwrap();
+    alice.add_member_with_key_package_bundle(space.id, bob_keypair.user_id(), Role::Member, bundle).await.unwrap();
+    sleep(Duration::from_secs(3)).await;
+    assert!(bob.space_epoch(&space.id).await.is_some(), "Bob should have joined the MLS group");
+
+    alice.remove_member(space.id, bob_keypair.user_id()).await.unwrap();
+    sleep(Duration::from_secs(3)).await;
+
+    // Posted while Bob is out: a later sync must not hand it to him
+    let (_, while_out_op) = alice.post_message(space.id, thread.id, "while you were out".to_string()).await.unwrap();
+    sleep(Duration::from_secs(2)).await;
+
+    let mut events = bob.subscribe_events();
+    let bundle = bob.get_key_package_bundle().await.unwrap();
+    alice.add_member_with_key_package_bundle(space.id, bob_keypair.user_id(), Role::Member, bundle).await.unwrap();
+
+    let rejoined = timeout(Duration::from_secs(10), async {
+        loop {
+            if let Ok(ClientEvent::Rejoined { space_id, previous_epoch, epoch }) = events.recv().await {
+                return (space_id, previous_epoch, epoch);
+            }
+        }
+    }).await.expect("Bob should be told he rejoined");
+    assert_eq!(rejoined.0, space.id);
+    assert!(rejoined.2 > rejoined.1, "the Welcome is for a later epoch");
+
+    let (_, after_op) = alice.post_message(space.id, thread.id, "welcome back".to_string()).await.unwrap();
+
+    // Whichever way the ops reach Bob, only the post-rejoin one is readable
+    assert!(bob.handle_incoming_op(while_out_op).await.is_err());
+    bob.handle_incoming_op(after_op).await.unwrap();
+    let contents: Vec<String> = bob.list_messages(&thread.id).await.into_iter().map(|m| m.content).collect();
+    assert!(contents.contains(&"welcome back".to_string()));
+    assert!(!contents.contains(&"while you were out".to_string()));
+}
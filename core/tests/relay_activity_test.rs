@@ -0,0 +1,79 @@
+//! A relay can list the reservations and circuits it is holding
+
+use spaceway_core::crypto::signing::Keypair;
+use spaceway_core::network::create_relay_server;
+use spaceway_core::network::relay::RelayActivity;
+use spaceway_core::{Client, ClientConfig};
+use libp2p::futures::StreamExt;
+use libp2p::swarm::SwarmEvent;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tempfile::TempDir;
+
+fn create_client(temp_dir: &TempDir) -> Client {
+    let config = ClientConfig {
+        storage_path: temp_dir.path().to_path_buf(),
+        listen_addrs: vec!["/ip4/127.0.0.1/tcp/0".to_string()],
+        bootstrap_peers: vec![],
+        ..Default::default()
+    };
+    Client::new(Keypair::generate(), config).unwrap()
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_active_circuit_is_listed() {
+    let mut relay = create_relay_server().unwrap();
+    let relay_peer_id = *relay.local_peer_id();
+    relay.listen_on("/ip4/127.0.0.1/tcp/0".parse().unwrap()).unwrap();
+    let addr = loop {
+        if let SwarmEvent::NewListenAddr { address, .. } = relay.select_next_some().await {
+            break address;
+        }
+    };
+    relay.add_external_address(addr.clone());
+    let relay_addr = format!("{}/p2p/{}", addr, relay_peer_id);
+
+    let activity = Arc::new(Mutex::new(RelayActivity::new()));
+    let observed = Arc::clone(&activity);
+    tokio::spawn(async move {
+        loop {
+            if let SwarmEvent::Behaviour(event) = relay.select_next_some().await {
+                observed.lock().unwrap().observe(&event);
+            }
+        }
+    });
+
+    let alice_dir = TempDir::new().unwrap();
+    let bob_dir = TempDir::new().unwrap();
+    let alice = create_client(&alice_dir);
+    let bob = create_client(&bob_dir);
+    alice.start().await.unwrap();
+    bob.start().await.unwrap();
+    let alice_peer_id = alice.peer_id().await;
+    let bob_peer_id = bob.peer_id().await;
+
+    alice.connect_to_relay(&relay_addr).await.unwrap();
+    let deadline = tokio::time::Instant::now() + Duration::from_secs(10);
+    while activity.lock().unwrap().reservations().is_empty() {
+        assert!(tokio::time::Instant::now() < deadline, "relay reservation was not accepted");
+        tokio::time::sleep(Duration::from_millis(100)).await;
+    }
+    let reservations = activity.lock().unwrap().reservations();
+    assert_eq!(reservations.len(), 1);
+    assert_eq!(reservations[0].peer_id, alice_peer_id.to_string());
+    assert!(activity.lock().unwrap().circuits().is_empty());
+
+    bob.network_dial(&format!("{}/p2p-circuit/p2p/{}", relay_addr, alice_peer_id)).await.unwrap();
+    while activity.lock().unwrap().circuits().is_empty() {
+        assert!(tokio::time::Instant::now() < deadline, "no circuit through the relay");
+        tokio::time::sleep(Duration::from_millis(100)).await;
+    }
+
+    let circuits = activity.lock().unwrap().circuits();
+    assert_eq!(circuits.len(), 1);
+    assert_eq!(circuits[0].src_peer_id, bob_peer_id.to_string());
+    assert_eq!(circuits[0].dst_peer_id, alice_peer_id.to_string());
+
+    let json = serde_json::to_string(&circuits).unwrap();
+    assert!(json.contains(&format!("\"dst_peer_id\":\"{}\"", alice_peer_id)));
+}
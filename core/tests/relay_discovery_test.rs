@@ -191,3 +191,65 @@ async fn test_relay_mode_serialization() -> Result<()> {
     
     Ok(())
 }
+
+fn relay_with_load(capacity: u32, active_circuits: u32) -> RelayInfo {
+    RelayInfo {
+        peer_id: libp2p::PeerId::random(),
+        addresses: vec!["/ip4/127.0.0.1/tcp/4001".parse().unwrap()],
+        capacity,
+        active_circuits,
+        reputation: 80,
+        latency_ms: None,
+        last_seen: 0,
+        mode: RelayMode::DedicatedServer,
+    }
+}
+
+/// Test: Free capacity decides between equally reputable relays
+#[test]
+fn test_relay_with_more_free_capacity_is_preferred() {
+    use spaceway_core::network::relay::rank_relays;
+    
+    let busy = relay_with_load(10, 9);
+    let idle = relay_with_load(10, 2);
+    let ranked = rank_relays(vec![busy.clone(), idle.clone()]);
+    assert_eq!(ranked[0].peer_id, idle.peer_id);
+    
+    // A full relay loses even to a less reputable one
+    let full = relay_with_load(100, 100);
+    let mut modest = relay_with_load(5, 4);
+    modest.reputation = 10;
+    let ranked = rank_relays(vec![full.clone(), modest.clone()]);
+    assert!(full.is_saturated());
+    assert_eq!(ranked[0].peer_id, modest.peer_id);
+    
+    // A cooperative relay's own circuit limit caps its advertised capacity
+    let mut cooperative = relay_with_load(50, 8);
+    cooperative.mode = RelayMode::Cooperative { max_bandwidth_mb_hour: 500, max_concurrent_circuits: 10 };
+    assert_eq!(cooperative.free_circuits(), 2);
+}
+
+/// Test: Discovery reports the load a relay advertised
+#[tokio::test]
+async fn test_discovered_relay_carries_advertised_load() -> Result<()> {
+    let (relay_node, _rx) = NetworkNode::new()?;
+    sleep(Duration::from_millis(500)).await;
+    
+    relay_node.advertise_as_relay_with_load(
+        RelayMode::DedicatedServer,
+        vec!["/ip4/127.0.0.1/tcp/14003".parse()?],
+        20,
+        15,
+    ).await?;
+    
+    // The advertisement is kept in the node's own record store
+    let discovered = relay_node.discover_relays().await?;
+    let relay = discovered.iter()
+        .find(|relay| relay.peer_id == *relay_node.local_peer_id())
+        .expect("Own advertisement should be discoverable");
+    assert_eq!(relay.capacity, 20);
+    assert_eq!(relay.active_circuits, 15);
+    assert_eq!(relay.free_circuits(), 5);
+    
+    Ok(())
+}
@@ -25,6 +25,7 @@ async fn test_relay_only_communication() {
         storage_path: alice_temp.path().to_path_buf(),
         listen_addrs: vec![],
         bootstrap_peers: vec![],
+        ..Default::default()
     };
     
     let alice = Client::new(alice_keypair, alice_config).unwrap();
@@ -37,6 +38,7 @@ async fn test_relay_only_communication() {
         storage_path: bob_temp.path().to_path_buf(),
         listen_addrs: vec![],
         bootstrap_peers: vec![],
+        ..Default::default()
     };
     
     let bob = Client::new(bob_keypair, bob_config).unwrap();
@@ -116,6 +118,7 @@ async fn test_relay_discovery_simulation() {
         storage_path: temp.path().to_path_buf(),
         listen_addrs: vec![],
         bootstrap_peers: vec![],
+        ..Default::default()
     };
     
     let client = Client::new(keypair, config).unwrap();
@@ -139,6 +142,7 @@ async fn test_relay_only_vs_direct_mode() {
         storage_path: temp.path().to_path_buf(),
         listen_addrs: vec![],
         bootstrap_peers: vec![],
+        ..Default::default()
     };
     
     let client = Client::new(keypair, config).unwrap();
@@ -180,6 +184,7 @@ async fn test_relay_rotation() {
         storage_path: temp.path().to_path_buf(),
         listen_addrs: vec![],
         bootstrap_peers: vec![],
+        ..Default::default()
     };
     
     let client = Client::new(keypair, config).unwrap();
@@ -0,0 +1,47 @@
+//! Integration tests for role colors and hoisting
+
+use spaceway_core::{Client, ClientConfig};
+use spaceway_core::crypto::signing::Keypair;
+use spaceway_core::dashboard::SpaceSnapshot;
+use tempfile::TempDir;
+
+fn create_client(temp_dir: &TempDir) -> Client {
+    let config = ClientConfig {
+        storage_path: temp_dir.path().to_path_buf(),
+        listen_addrs: vec!["/ip4/127.0.0.1/tcp/0".to_string()],
+        bootstrap_peers: vec![],
+        ..Default::default()
+    };
+    Client::new(Keypair::generate(), config).unwrap()
+}
+
+#[tokio::test]
+async fn test_role_color_replicates() {
+    let owner_dir = TempDir::new().unwrap();
+    let replica_dir = TempDir::new().unwrap();
+    let owner = create_client(&owner_dir);
+    let replica = create_client(&replica_dir);
+
+    let (space, space_op, _) = owner.create_space("Painters".to_string(), None).await.unwrap();
+    replica.handle_incoming_op(space_op).await.unwrap();
+
+    let moderator = space.roles.values().find(|r| r.name == "Moderator").unwrap().id;
+    let update_op = owner.update_role(space.id, moderator, Some(0x3498DB), true).await.unwrap();
+    replica.handle_incoming_op(update_op).await.unwrap();
+
+    let replica_space = replica.get_space(&space.id).await.unwrap();
+    let role = &replica_space.roles[&moderator];
+    assert_eq!(role.color, Some(0x3498DB));
+    assert!(role.hoisted);
+
+    // Snapshot lists roles highest first, with display settings
+    let snapshot = SpaceSnapshot::from_space(&replica_space);
+    let positions: Vec<u32> = snapshot.roles.iter().map(|r| r.position).collect();
+    assert_eq!(positions, vec![100, 50, 0]);
+    assert_eq!(snapshot.roles[1].color, Some(0x3498DB));
+    assert!(snapshot.roles[1].hoisted);
+
+    // Members cannot restyle roles
+    let admin = space.roles.values().find(|r| r.name == "Admin").unwrap().id;
+    assert!(replica.update_role(space.id, admin, None, false).await.is_err());
+}
@@ -0,0 +1,103 @@
+//! Clients sign through a `Signer`, so the identity key need not be in memory
+
+use spaceway_core::crypto::signing::{ExternalSigner, Keypair, PublicKey, Signer};
+use spaceway_core::{Client, ClientBuilder, ClientConfig, Error, Result, Signature};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use tempfile::TempDir;
+
+fn config(temp_dir: &TempDir) -> ClientConfig {
+    ClientConfig {
+        storage_path: temp_dir.path().to_path_buf(),
+        listen_addrs: vec!["/ip4/127.0.0.1/tcp/0".to_string()],
+        bootstrap_peers: vec![],
+        ..Default::default()
+    }
+}
+
+/// Stands in for a hardware token, counting the signatures it produces
+struct MockToken {
+    key: Keypair,
+    signatures: AtomicUsize,
+}
+
+impl Signer for MockToken {
+    fn public_key(&self) -> PublicKey {
+        self.key.public_key()
+    }
+
+    fn sign(&self, message: &[u8]) -> Result<Signature> {
+        self.signatures.fetch_add(1, Ordering::SeqCst);
+        Ok(self.key.sign(message))
+    }
+
+    // A token that lets the key out for MLS, as a software keystore can
+    fn mls_signature_key(&self) -> Option<openmls_basic_credential::SignatureKeyPair> {
+        self.key.mls_signature_key()
+    }
+}
+
+#[tokio::test]
+async fn test_ops_signed_by_mock_signer_are_accepted() {
+    let alice_dir = TempDir::new().unwrap();
+    let bob_dir = TempDir::new().unwrap();
+    let token = Arc::new(MockToken { key: Keypair::generate(), signatures: AtomicUsize::new(0) });
+    let alice = ClientBuilder::with_signer(token.clone(), config(&alice_dir)).build().unwrap();
+    let bob = Client::new(Keypair::generate(), config(&bob_dir)).unwrap();
+    assert_eq!(alice.user_id(), token.user_id());
+
+    let (space, space_op, _) = alice.create_space("Hardware".to_string(), None).await.unwrap();
+    let (channel, channel_op) = alice.create_channel(space.id, "general".to_string(), None).await.unwrap();
+    let (thread, thread_op) = alice.create_thread(space.id, channel.id, None, "Hello".to_string()).await.unwrap();
+    let (message, message_op) = alice.post_message(space.id, thread.id, "signed on a token".to_string()).await.unwrap();
+    assert!(token.signatures.load(Ordering::SeqCst) >= 4);
+
+    for op in [space_op, channel_op, thread_op, message_op] {
+        assert!(op.verify_signature());
+        bob.handle_incoming_op(op).await.unwrap();
+    }
+    assert_eq!(bob.get_message(&message.id).await.unwrap().content, "signed on a token");
+}
+
+#[tokio::test]
+async fn test_external_signer_with_wrong_key_fails_locally() {
+    let temp_dir = TempDir::new().unwrap();
+    let claimed = Keypair::generate();
+    let on_device = Keypair::generate();
+    let signer = ExternalSigner::new(claimed.public_key(), move |message| Ok(on_device.sign(message).0));
+    let client = ClientBuilder::with_signer(Arc::new(signer), config(&temp_dir)).build().unwrap();
+
+    assert!(client.create_space("Mismatch".to_string(), None).await.is_err());
+}
+
+#[tokio::test]
+async fn test_signer_that_cannot_export_cannot_create_mls_space() {
+    let temp_dir = TempDir::new().unwrap();
+    let key = Keypair::generate();
+    let signer = ExternalSigner::new(key.public_key(), move |message| Ok(key.sign(message).0));
+    let client = ClientBuilder::with_signer(Arc::new(signer), config(&temp_dir)).build().unwrap();
+
+    assert_eq!(client.available_keypackages().await, 0);
+    assert!(client.create_space("Hardware".to_string(), None).await.is_err());
+}
+
+#[tokio::test]
+async fn test_op_claiming_another_author_is_rejected() {
+    let mallory_dir = TempDir::new().unwrap();
+    let bob_dir = TempDir::new().unwrap();
+    let mallory_key = Keypair::generate();
+    let alice_key = Keypair::generate();
+    let mallory = Client::new(mallory_key.clone(), config(&mallory_dir)).unwrap();
+    let bob = Client::new(Keypair::generate(), config(&bob_dir)).unwrap();
+
+    // Mallory signs with their own key but claims to be Alice
+    let (space, mut forged, _) = mallory.create_space("Not Alice's".to_string(), None).await.unwrap();
+    forged.author = alice_key.user_id();
+    assert!(forged.sign(&mallory_key).is_err());
+    forged.op_id = forged.content_id();
+    forged.signature = mallory_key.sign(&forged.signing_bytes());
+    assert!(!forged.verify_signature());
+
+    assert!(matches!(bob.handle_incoming_op(forged).await, Err(Error::InvalidSignature)));
+    assert!(bob.get_space(&space.id).await.is_none());
+}
@@ -26,7 +26,8 @@ async fn create_test_user(name: &str, port: u16) -> (Client, String) {
     let config = ClientConfig {
         storage_path: data_dir.path().to_path_buf(),
         listen_addrs: vec![format!("/ip4/0.0.0.0/tcp/{}", port)],
-        bootstrap_peers: vec![], // Will connect manually
+        bootstrap_peers: vec![], // Will connect manually,
+        ..Default::default()
     };
     
     let client = Client::new(keypair, config)
@@ -161,7 +162,7 @@ async fn simple_local_3peer_test() {
     println!();
     
     println!("🎟️  Alice generating invite code...");
-    let _op = alice.create_invite(space_id, None, None).await
+    let _op = alice.create_invite(space_id, None, None, None, None).await
         .expect("Alice failed to create invite");
     
     // Get the invite code
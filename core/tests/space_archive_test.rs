@@ -0,0 +1,73 @@
+//! Archived Spaces are read-only everywhere; deleted Spaces stay deleted
+
+use spaceway_core::crypto::signing::Keypair;
+use spaceway_core::{Client, ClientConfig};
+use tempfile::TempDir;
+
+fn create_client(temp_dir: &TempDir) -> Client {
+    let config = ClientConfig {
+        storage_path: temp_dir.path().to_path_buf(),
+        listen_addrs: vec!["/ip4/127.0.0.1/tcp/0".to_string()],
+        bootstrap_peers: vec![],
+        ..Default::default()
+    };
+    Client::new(Keypair::generate(), config).unwrap()
+}
+
+#[tokio::test]
+async fn test_posting_to_archived_space_is_rejected_on_all_replicas() {
+    let alice_dir = TempDir::new().unwrap();
+    let bob_dir = TempDir::new().unwrap();
+    let alice = create_client(&alice_dir);
+    let bob = create_client(&bob_dir);
+
+    let (space, space_op, _) = alice.create_space("Archive".to_string(), None).await.unwrap();
+    let (channel, channel_op) = alice.create_channel(space.id, "general".to_string(), None).await.unwrap();
+    let (thread, thread_op) = alice.create_thread(space.id, channel.id, None, "Hello".to_string()).await.unwrap();
+    for op in [space_op, channel_op, thread_op] {
+        bob.handle_incoming_op(op).await.unwrap();
+    }
+
+    // Only the owner can archive
+    assert!(bob.archive_space(space.id).await.is_err());
+
+    // Posted before the archive, delivered after it
+    let (late, late_op) = alice.post_message(space.id, thread.id, "late".to_string()).await.unwrap();
+
+    let archive_op = alice.archive_space(space.id).await.unwrap();
+    bob.handle_incoming_op(archive_op).await.unwrap();
+    assert!(alice.get_space(&space.id).await.unwrap().archived);
+    assert!(bob.get_space(&space.id).await.unwrap().archived);
+
+    assert!(alice.post_message(space.id, thread.id, "after".to_string()).await.is_err());
+    assert!(bob.post_message(space.id, thread.id, "after".to_string()).await.is_err());
+    assert!(bob.handle_incoming_op(late_op).await.is_err());
+    assert!(bob.get_message(&late.id).await.is_none());
+
+    // History stays readable
+    assert!(bob.get_thread(&thread.id).await.is_some());
+}
+
+#[tokio::test]
+async fn test_deleted_space_is_not_recreated_by_replayed_create() {
+    let alice_dir = TempDir::new().unwrap();
+    let bob_dir = TempDir::new().unwrap();
+    let alice = create_client(&alice_dir);
+    let bob = create_client(&bob_dir);
+
+    let (space, space_op, _) = alice.create_space("Doomed".to_string(), None).await.unwrap();
+    bob.handle_incoming_op(space_op.clone()).await.unwrap();
+
+    assert!(bob.delete_space(space.id).await.is_err());
+
+    let delete_op = alice.delete_space(space.id).await.unwrap();
+    assert!(alice.get_space(&space.id).await.is_none());
+
+    bob.handle_incoming_op(delete_op).await.unwrap();
+    assert!(bob.get_space(&space.id).await.is_none());
+
+    for client in [&alice, &bob] {
+        assert!(client.handle_incoming_op(space_op.clone()).await.is_err());
+        assert!(client.get_space(&space.id).await.is_none());
+    }
+}
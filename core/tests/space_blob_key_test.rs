@@ -0,0 +1,76 @@
+//! Blobs stored for a Space are readable by its other members
+
+use spaceway_core::crypto::signing::Keypair;
+use spaceway_core::mls::key_labels;
+use spaceway_core::types::Role;
+use spaceway_core::{Client, ClientConfig};
+use tempfile::TempDir;
+use tokio::time::{sleep, Duration, Instant};
+
+fn create_client(temp_dir: &TempDir) -> Client {
+    let config = ClientConfig {
+        storage_path: temp_dir.path().to_path_buf(),
+        listen_addrs: vec!["/ip4/127.0.0.1/tcp/0".to_string()],
+        bootstrap_peers: vec![],
+        ..Default::default()
+    };
+    Client::new(Keypair::generate(), config).unwrap()
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_member_decrypts_blob_another_member_stored() {
+    let alice_dir = TempDir::new().unwrap();
+    let bob_dir = TempDir::new().unwrap();
+    let charlie_dir = TempDir::new().unwrap();
+    let alice = create_client(&alice_dir);
+    let bob = create_client(&bob_dir);
+    let charlie = create_client(&charlie_dir);
+    alice.start().await.unwrap();
+    bob.start().await.unwrap();
+    charlie.start().await.unwrap();
+    sleep(Duration::from_millis(500)).await;
+
+    let alice_addr = alice.listening_addrs().await
+        .into_iter()
+        .find(|addr| addr.to_string().contains("127.0.0.1"))
+        .expect("Alice should listen on loopback");
+    let alice_multiaddr = format!("{}/p2p/{}", alice_addr, alice.peer_id().await);
+    bob.network_dial(&alice_multiaddr).await.unwrap();
+    charlie.network_dial(&alice_multiaddr).await.unwrap();
+    sleep(Duration::from_secs(1)).await;
+
+    let (space, space_op, _) = alice.create_space("Shared".to_string(), None).await.unwrap();
+    bob.handle_incoming_op(space_op.clone()).await.unwrap();
+    charlie.handle_incoming_op(space_op).await.unwrap();
+
+    // Only Bob is let into the MLS group
+    let bundle = bob.get_key_package_bundle().await.unwrap();
+    alice.add_member_with_key_package_bundle(space.id, bob.user_id(), Role::Member, bundle).await.unwrap();
+    let alice_key = alice.space_key(&space.id, key_labels::BLOB).await.unwrap();
+    let deadline = Instant::now() + Duration::from_secs(15);
+    while bob.space_key(&space.id, key_labels::BLOB).await.ok() != Some(alice_key) {
+        assert!(Instant::now() < deadline, "Bob never reached Alice's epoch");
+        sleep(Duration::from_millis(200)).await;
+    }
+
+    let plaintext = b"shared attachment";
+    let metadata = alice.store_blob_for_space(&space.id, plaintext, None, Some("notes.txt".to_string()))
+        .await.unwrap();
+    sleep(Duration::from_secs(1)).await;
+
+    // Bob has no copy and a different user key, yet the Space key opens it
+    let retrieved = bob.retrieve_blob_for_space(&space.id, &metadata.hash).await.unwrap();
+    assert_eq!(retrieved, plaintext);
+
+    // Now cached locally, it still opens without the DHT
+    assert_eq!(bob.retrieve_blob_for_space(&space.id, &metadata.hash).await.unwrap(), plaintext);
+
+    // Knowing the Space ID is not enough without the group's exporter secret
+    assert!(charlie.space_key(&space.id, key_labels::BLOB).await.is_err());
+    assert!(charlie.retrieve_blob_for_space(&space.id, &metadata.hash).await.is_err());
+
+    // Private blobs stay on the user key
+    let private = alice.store_blob(b"just mine", None, None).await.unwrap();
+    assert_eq!(alice.retrieve_blob(&private.hash).await.unwrap(), b"just mine");
+    assert!(alice.retrieve_blob(&metadata.hash).await.is_err());
+}
@@ -0,0 +1,78 @@
+//! Integration tests for the public Space directory
+
+use spaceway_core::{Client, ClientConfig, SpaceVisibility};
+use spaceway_core::crypto::signing::Keypair;
+use tempfile::TempDir;
+
+fn create_client(temp_dir: &TempDir) -> Client {
+    let config = ClientConfig {
+        storage_path: temp_dir.path().to_path_buf(),
+        listen_addrs: vec!["/ip4/127.0.0.1/tcp/0".to_string()],
+        bootstrap_peers: vec![],
+        ..Default::default()
+    };
+    Client::new(Keypair::generate(), config).unwrap()
+}
+
+#[tokio::test]
+async fn test_public_space_discoverable_private_not() {
+    let temp_dir = TempDir::new().unwrap();
+    let client = create_client(&temp_dir);
+
+    let (public, _, _) = client.create_space_with_visibility(
+        "Rust Gardeners".to_string(),
+        Some("Growing crates".to_string()),
+        SpaceVisibility::Public,
+    ).await.unwrap();
+    let (private, _, _) = client.create_space_with_visibility(
+        "Secret Garden".to_string(),
+        None,
+        SpaceVisibility::Private,
+    ).await.unwrap();
+
+    let results = client.search_public_spaces("garden", None).await;
+    assert_eq!(results.len(), 1);
+    assert_eq!(results[0].space_id, public.id);
+    assert_eq!(results[0].name, "Rust Gardeners");
+    assert_eq!(results[0].description.as_deref(), Some("Growing crates"));
+    assert_eq!(results[0].member_count, 1);
+
+    assert!(client.search_public_spaces("secret", None).await.is_empty());
+    assert!(client.dht_put_directory_entry(&private.id).await.is_err());
+}
+
+#[tokio::test]
+async fn test_directory_filters_by_normalized_tag() {
+    let owner_dir = TempDir::new().unwrap();
+    let replica_dir = TempDir::new().unwrap();
+    let owner = create_client(&owner_dir);
+    let replica = create_client(&replica_dir);
+
+    let (tagged, space_op, _) = owner.create_space_with_visibility(
+        "Rust Gardeners".to_string(),
+        None,
+        SpaceVisibility::Public,
+    ).await.unwrap();
+    owner.create_space_with_visibility(
+        "Garden Party".to_string(),
+        None,
+        SpaceVisibility::Public,
+    ).await.unwrap();
+
+    let tags = vec![" Rust ".to_string(), "gardening".to_string(), "RUST".to_string()];
+    let tag_op = owner.update_space_tags(tagged.id, tags, Some("Hobbies".to_string())).await.unwrap();
+
+    let results = owner.search_public_spaces("", Some("rust")).await;
+    assert_eq!(results.len(), 1);
+    assert_eq!(results[0].space_id, tagged.id);
+    assert_eq!(results[0].tags, vec!["gardening".to_string(), "rust".to_string()]);
+    assert_eq!(results[0].category.as_deref(), Some("hobbies"));
+    assert_eq!(owner.search_public_spaces("garden", None).await.len(), 2);
+
+    // Replicas normalize the same op to the same tags
+    replica.handle_incoming_op(space_op).await.unwrap();
+    replica.handle_incoming_op(tag_op).await.unwrap();
+    let replica_space = replica.get_space(&tagged.id).await.unwrap();
+    assert_eq!(replica_space.tags, owner.get_space(&tagged.id).await.unwrap().tags);
+    assert_eq!(replica_space.category.as_deref(), Some("hobbies"));
+}
@@ -0,0 +1,68 @@
+//! Spaces announced on the discovery topic are only followed when configured to
+
+use spaceway_core::crypto::signing::Keypair;
+use spaceway_core::{Client, ClientConfig, ClientEvent};
+use tempfile::TempDir;
+use tokio::time::{sleep, timeout, Duration};
+
+fn create_client(temp_dir: &TempDir, auto_subscribe_discovered: bool) -> Client {
+    let config = ClientConfig {
+        storage_path: temp_dir.path().to_path_buf(),
+        listen_addrs: vec!["/ip4/127.0.0.1/tcp/0".to_string()],
+        bootstrap_peers: vec![],
+        auto_subscribe_discovered,
+        ..Default::default()
+    };
+    Client::new(Keypair::generate(), config).unwrap()
+}
+
+/// Have Alice announce a Space to a connected Bob, returning its topic once Bob saw it
+async fn announce(alice: &Client, bob: &Client) -> String {
+    alice.start().await.unwrap();
+    bob.start().await.unwrap();
+    sleep(Duration::from_millis(500)).await;
+
+    let alice_addr = alice.listening_addrs().await
+        .into_iter()
+        .find(|addr| addr.to_string().contains("127.0.0.1"))
+        .expect("Alice should listen on loopback");
+    bob.network_dial(&format!("{}/p2p/{}", alice_addr, alice.peer_id().await)).await.unwrap();
+    sleep(Duration::from_secs(2)).await;
+
+    let mut events = bob.subscribe_events();
+    let (space, _, _) = alice.create_space("Announced".to_string(), None).await.unwrap();
+    let (space_id, name) = timeout(Duration::from_secs(10), async {
+        loop {
+            if let Ok(ClientEvent::SpaceDiscovered { space_id, name }) = events.recv().await {
+                return (space_id, name);
+            }
+        }
+    }).await.expect("Bob should hear about the Space");
+    assert_eq!(space_id, space.id);
+    assert_eq!(name, "Announced");
+
+    format!("space/{}", space.id.short())
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_discovered_space_not_subscribed_by_default() {
+    let alice_dir = TempDir::new().unwrap();
+    let bob_dir = TempDir::new().unwrap();
+    let alice = create_client(&alice_dir, false);
+    let bob = create_client(&bob_dir, false);
+    assert!(!ClientConfig::default().auto_subscribe_discovered);
+
+    let topic = announce(&alice, &bob).await;
+    assert!(!bob.subscribed_topics().await.contains(&topic));
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_discovered_space_subscribed_when_enabled() {
+    let alice_dir = TempDir::new().unwrap();
+    let bob_dir = TempDir::new().unwrap();
+    let alice = create_client(&alice_dir, false);
+    let bob = create_client(&bob_dir, true);
+
+    let topic = announce(&alice, &bob).await;
+    assert!(bob.subscribed_topics().await.contains(&topic));
+}
@@ -0,0 +1,88 @@
+//! Integration tests for per-space storage and `Client::purge_space`
+
+use spaceway_core::{Client, ClientConfig};
+use spaceway_core::crypto::signing::Keypair;
+use spaceway_core::storage::{Storage, StorageConfig};
+use spaceway_core::types::SpaceId;
+use std::collections::HashMap;
+use tempfile::TempDir;
+
+#[tokio::test]
+async fn test_purge_space_leaves_other_space_intact() {
+    let temp_dir = TempDir::new().unwrap();
+    let config = ClientConfig {
+        storage_path: temp_dir.path().to_path_buf(),
+        listen_addrs: vec!["/ip4/127.0.0.1/tcp/0".to_string()],
+        bootstrap_peers: vec![],
+        ..Default::default()
+    };
+    let client = Client::new(Keypair::generate(), config).unwrap();
+
+    let (purged, _, _) = client.create_space("Purged".to_string(), None).await.unwrap();
+    let (kept, _, _) = client.create_space("Kept".to_string(), None).await.unwrap();
+
+    let mut threads = Vec::new();
+    let mut blobs = Vec::new();
+    for space in [&purged, &kept] {
+        let (channel, _) = client.create_channel(space.id, "general".to_string(), None).await.unwrap();
+        let (thread, _) = client.create_thread(space.id, channel.id, None, "First".to_string()).await.unwrap();
+        client.post_message(space.id, thread.id, "Second".to_string()).await.unwrap();
+        let blob = client.store_blob_for_space(&space.id, space.name.as_bytes(), None, None).await.unwrap();
+        threads.push(thread.id);
+        blobs.push(blob.hash);
+    }
+
+    // Each Space's blobs live in their own directory
+    let purged_dir = temp_dir.path().join("blobs").join("spaces").join(hex::encode(purged.id.0));
+    assert!(purged_dir.join(blobs[0].to_hex()).exists());
+
+    client.purge_space(purged.id).await.unwrap();
+
+    // The purged Space is gone: state, ops, messages and blobs
+    assert!(client.get_space(&purged.id).await.is_none());
+    assert!(client.space_epoch(&purged.id).await.is_none());
+    assert!(client.list_channels(&purged.id).await.is_empty());
+    assert!(client.list_messages(&threads[0]).await.is_empty());
+    assert!(client.retrieve_blob(&blobs[0]).await.is_err());
+    assert!(!purged_dir.exists());
+    let ops = client.recent_ops(usize::MAX).unwrap();
+    assert!(ops.iter().all(|op| op.space_id != hex::encode(purged.id.0)));
+
+    // The other Space is untouched
+    assert!(client.get_space(&kept.id).await.is_some());
+    assert!(client.space_epoch(&kept.id).await.is_some());
+    assert_eq!(client.list_channels(&kept.id).await.len(), 1);
+    assert_eq!(client.list_messages(&threads[1]).await.len(), 2);
+    assert_eq!(client.retrieve_blob_for_space(&kept.id, &blobs[1]).await.unwrap(), b"Kept");
+    assert!(ops.iter().filter(|op| op.space_id == hex::encode(kept.id.0)).count() >= 4);
+}
+
+#[test]
+fn test_configured_space_blob_dir() {
+    let temp_dir = TempDir::new().unwrap();
+    let other_disk = TempDir::new().unwrap();
+    let moved = SpaceId([1u8; 32]);
+    let local = SpaceId([2u8; 32]);
+    let key = [7u8; 32];
+
+    let config = StorageConfig {
+        space_blob_dirs: HashMap::from([(moved, other_disk.path().to_path_buf())]),
+        ..Default::default()
+    };
+    let storage = Storage::open_with_config(temp_dir.path(), config).unwrap();
+
+    let moved_hash = storage.store_space_blob(&moved, b"moved", &key).unwrap();
+    let local_hash = storage.store_space_blob(&local, b"local", &key).unwrap();
+    let unrelated = other_disk.path().join("unrelated.txt");
+    std::fs::write(&unrelated, b"not a blob").unwrap();
+
+    assert_eq!(storage.blob_path(&moved_hash).unwrap(), other_disk.path().join(moved_hash.to_hex()));
+    assert_eq!(storage.load_blob(&moved_hash, &key).unwrap(), b"moved");
+
+    storage.purge_space(&moved, &[], &[]).unwrap();
+
+    // Only the Space's own blobs are removed from a configured directory
+    assert!(storage.load_blob(&moved_hash, &key).is_err());
+    assert!(unrelated.exists());
+    assert_eq!(storage.load_blob(&local_hash, &key).unwrap(), b"local");
+}
@@ -0,0 +1,38 @@
+//! The local causal frontier of a Space, for sync diagnostics
+
+use spaceway_core::crypto::signing::Keypair;
+use spaceway_core::{Client, ClientConfig};
+use tempfile::TempDir;
+
+#[tokio::test]
+async fn test_posting_advances_own_clock_entry() {
+    let temp_dir = TempDir::new().unwrap();
+    let config = ClientConfig {
+        storage_path: temp_dir.path().to_path_buf(),
+        listen_addrs: vec!["/ip4/127.0.0.1/tcp/0".to_string()],
+        bootstrap_peers: vec![],
+        ..Default::default()
+    };
+    let client = Client::new(Keypair::generate(), config).unwrap();
+    let me = client.user_id();
+
+    let (space, _, _) = client.create_space("Clocked".to_string(), None).await.unwrap();
+    let (channel, _) = client.create_channel(space.id, "general".to_string(), None).await.unwrap();
+    let (thread, _) = client.create_thread(space.id, channel.id, None, "First".to_string()).await.unwrap();
+
+    let before = client.space_vector_clock(&space.id).unwrap();
+    assert!(before.get(&me) >= 3, "space, channel and thread ops are counted");
+    assert_eq!(before.clocks.len(), 1);
+
+    client.post_message(space.id, thread.id, "Tick".to_string()).await.unwrap();
+    let after = client.space_vector_clock(&space.id).unwrap();
+    assert_eq!(after.get(&me), before.get(&me) + 1);
+    assert!(before.happens_before(&after));
+    assert_eq!(before.diff(&after), vec![(me.to_string(), before.get(&me), after.get(&me))]);
+
+    // Serializable for diagnostics output
+    let json = serde_json::to_value(&after).unwrap();
+    assert_eq!(json["clocks"][me.to_string()], after.get(&me));
+
+    assert!(client.peer_space_clocks(&space.id).await.is_empty());
+}
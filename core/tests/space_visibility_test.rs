@@ -10,6 +10,7 @@ fn create_test_client(_name: &str) -> Result<Client> {
         storage_path: temp_dir.path().to_path_buf(),
         listen_addrs: vec!["/ip4/127.0.0.1/tcp/0".to_string()],
         bootstrap_peers: vec![],
+        ..Default::default()
     };
     
     Ok(Client::new(keypair, config)?)
@@ -0,0 +1,53 @@
+//! Integration tests for Space state hashes
+
+use spaceway_core::crypto::signing::Keypair;
+use spaceway_core::types::SpaceId;
+use spaceway_core::{Client, ClientConfig};
+use tempfile::TempDir;
+
+fn create_client(temp_dir: &TempDir) -> Client {
+    let config = ClientConfig {
+        storage_path: temp_dir.path().to_path_buf(),
+        listen_addrs: vec!["/ip4/127.0.0.1/tcp/0".to_string()],
+        bootstrap_peers: vec![],
+        ..Default::default()
+    };
+    Client::new(Keypair::generate(), config).unwrap()
+}
+
+#[tokio::test]
+async fn test_same_ops_same_hash_dropped_op_diverges() {
+    let alice_dir = TempDir::new().unwrap();
+    let bob_dir = TempDir::new().unwrap();
+    let carol_dir = TempDir::new().unwrap();
+    let alice = create_client(&alice_dir);
+    let bob = create_client(&bob_dir);
+    let carol = create_client(&carol_dir);
+
+    let (space, space_op, _) = alice.create_space("Mirror".to_string(), None).await.unwrap();
+    let (channel, channel_op) = alice.create_channel(space.id, "general".to_string(), None).await.unwrap();
+    let (thread, thread_op) = alice.create_thread(space.id, channel.id, None, "Hello".to_string()).await.unwrap();
+    let (_, first_op) = alice.post_message(space.id, thread.id, "first".to_string()).await.unwrap();
+    let (_, second_op) = alice.post_message(space.id, thread.id, "second".to_string()).await.unwrap();
+
+    let ops = vec![space_op, channel_op, thread_op, first_op, second_op];
+    for op in &ops {
+        bob.handle_incoming_op(op.clone()).await.unwrap();
+    }
+    // Carol misses the last message
+    for op in &ops[..ops.len() - 1] {
+        carol.handle_incoming_op(op.clone()).await.unwrap();
+    }
+
+    let expected = alice.space_state_hash(&space.id).await;
+    assert_eq!(bob.space_state_hash(&space.id).await, expected);
+    assert_ne!(carol.space_state_hash(&space.id).await, expected);
+
+    // Catching up restores convergence
+    carol.handle_incoming_op(ops[ops.len() - 1].clone()).await.unwrap();
+    assert_eq!(carol.space_state_hash(&space.id).await, expected);
+
+    // Spaces the client doesn't know still hash deterministically
+    let unknown = SpaceId([9u8; 32]);
+    assert_eq!(bob.space_state_hash(&unknown).await, carol.space_state_hash(&unknown).await);
+}
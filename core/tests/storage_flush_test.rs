@@ -0,0 +1,44 @@
+//! Explicit flush and compaction of the on-disk stores
+
+use spaceway_core::crypto::signing::Keypair;
+use spaceway_core::storage::{Storage, Store};
+use spaceway_core::{Client, ClientConfig};
+use tempfile::TempDir;
+
+#[test]
+fn test_flushed_storage_is_seen_after_reopen() {
+    let temp_dir = TempDir::new().unwrap();
+    let key = [7u8; 32];
+
+    let hash = {
+        let storage = Storage::open(temp_dir.path()).unwrap();
+        let hash = storage.store_blob(b"survives a reopen", &key).unwrap();
+        storage.flush().unwrap();
+        storage.compact().unwrap();
+        hash
+    };
+
+    let storage = Storage::open(temp_dir.path()).unwrap();
+    assert_eq!(storage.load_blob(&hash, &key).unwrap(), b"survives a reopen");
+}
+
+#[tokio::test]
+async fn test_client_flush_persists_ops() {
+    let temp_dir = TempDir::new().unwrap();
+    let config = ClientConfig {
+        storage_path: temp_dir.path().to_path_buf(),
+        listen_addrs: vec!["/ip4/127.0.0.1/tcp/0".to_string()],
+        ..Default::default()
+    };
+
+    let op_id = {
+        let client = Client::new(Keypair::generate(), config).unwrap();
+        let (_, op, _) = client.create_space("Flushed".to_string(), None).await.unwrap();
+        client.flush_storage().unwrap();
+        client.compact_storage().unwrap();
+        op.op_id
+    };
+
+    let store = Store::open(temp_dir.path()).unwrap();
+    assert!(store.get_op(&op_id).unwrap().is_some());
+}
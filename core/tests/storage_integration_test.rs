@@ -23,6 +23,7 @@ async fn create_client_with_storage(_name: &str) -> Result<(Client, TempDir)> {
         storage_path: temp_dir.path().to_path_buf(),
         listen_addrs: vec![],
         bootstrap_peers: vec![],
+        ..Default::default()
     };
     
     let client = Client::new(keypair, config)?;
@@ -35,6 +36,7 @@ async fn create_client_at_path(path: &PathBuf, keypair: Keypair) -> Result<Clien
         storage_path: path.clone(),
         listen_addrs: vec![],
         bootstrap_peers: vec![],
+        ..Default::default()
     };
     
     Ok(Client::new(keypair, config)?)
@@ -0,0 +1,69 @@
+//! Integration tests for the blob storage quota
+
+use spaceway_core::storage::{EncryptedBlob, EvictionPolicy, Storage, StorageConfig};
+use spaceway_core::types::SpaceId;
+use tempfile::TempDir;
+
+const KEY: [u8; 32] = [9u8; 32];
+
+fn blob(fill: u8) -> (spaceway_core::storage::BlobHash, EncryptedBlob) {
+    let data = vec![fill; 1000];
+    (spaceway_core::storage::BlobHash::hash(&data), EncryptedBlob::encrypt(&data, &KEY).unwrap())
+}
+
+/// Storage whose quota fits one authored blob plus two cached ones
+fn storage_with_quota(temp_dir: &TempDir, eviction: EvictionPolicy) -> Storage {
+    let config = StorageConfig {
+        max_bytes: Some(3500),
+        eviction,
+        ..Default::default()
+    };
+    Storage::open_with_config(temp_dir.path(), config).unwrap()
+}
+
+#[test]
+fn test_quota_evicts_least_recently_used_cached_blob() {
+    let temp_dir = TempDir::new().unwrap();
+    let storage = storage_with_quota(&temp_dir, EvictionPolicy::LeastRecentlyUsed);
+    let space = SpaceId([1u8; 32]);
+
+    let authored = storage.store_space_blob(&space, &[0u8; 1000], &KEY).unwrap();
+    let (first, first_blob) = blob(1);
+    let (second, second_blob) = blob(2);
+    storage.cache_space_blob(&space, &first, &first_blob).unwrap();
+    storage.cache_space_blob(&space, &second, &second_blob).unwrap();
+    assert!(storage.is_cached_blob(&first).unwrap());
+    assert!(!storage.is_cached_blob(&authored).unwrap());
+
+    // Reading the first blob makes the second the least recently used
+    storage.load_blob(&first, &KEY).unwrap();
+
+    let (third, third_blob) = blob(3);
+    storage.cache_space_blob(&space, &third, &third_blob).unwrap();
+
+    assert!(storage.load_blob(&second, &KEY).is_err());
+    assert!(storage.load_blob(&first, &KEY).is_ok());
+    assert!(storage.load_blob(&third, &KEY).is_ok());
+    assert_eq!(storage.load_blob(&authored, &KEY).unwrap(), vec![0u8; 1000]);
+    assert!(storage.stats().unwrap().total_blob_bytes <= 3500);
+}
+
+#[test]
+fn test_quota_oldest_first_ignores_reads() {
+    let temp_dir = TempDir::new().unwrap();
+    let storage = storage_with_quota(&temp_dir, EvictionPolicy::OldestFirst);
+    let space = SpaceId([1u8; 32]);
+
+    let authored = storage.store_space_blob(&space, &[0u8; 1000], &KEY).unwrap();
+    let (first, first_blob) = blob(1);
+    let (second, second_blob) = blob(2);
+    let (third, third_blob) = blob(3);
+    storage.cache_space_blob(&space, &first, &first_blob).unwrap();
+    storage.cache_space_blob(&space, &second, &second_blob).unwrap();
+    storage.load_blob(&first, &KEY).unwrap();
+    storage.cache_space_blob(&space, &third, &third_blob).unwrap();
+
+    assert!(storage.load_blob(&first, &KEY).is_err());
+    assert!(storage.load_blob(&second, &KEY).is_ok());
+    assert!(storage.load_blob(&authored, &KEY).is_ok());
+}
@@ -0,0 +1,97 @@
+//! Integration tests for storage schema versioning and migrations
+
+use spaceway_core::storage::{Migration, Storage, StorageConfig, SCHEMA_VERSION};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU32, Ordering};
+use tempfile::TempDir;
+
+/// Migration from an unversioned store that records it ran and writes a marker
+struct MarkerMigration {
+    runs: Arc<AtomicU32>,
+}
+
+impl Migration for MarkerMigration {
+    fn from_version(&self) -> u32 {
+        0
+    }
+
+    fn description(&self) -> &str {
+        "write marker"
+    }
+
+    fn migrate(&self, db: &rocksdb::DB) -> anyhow::Result<()> {
+        self.runs.fetch_add(1, Ordering::SeqCst);
+        db.put(b"migrated", b"yes")?;
+        Ok(())
+    }
+}
+
+/// Create a database as it looked before schema versioning existed
+fn create_unversioned_store(temp_dir: &TempDir) {
+    let mut opts = rocksdb::Options::default();
+    opts.create_if_missing(true);
+    opts.create_missing_column_families(true);
+    let db = rocksdb::DB::open_cf(&opts, temp_dir.path().join("db"), ["thread_messages", "messages"]).unwrap();
+    db.put(b"legacy", b"data").unwrap();
+}
+
+#[test]
+fn test_new_store_is_stamped_current() {
+    let temp_dir = TempDir::new().unwrap();
+    let storage = Storage::open(temp_dir.path()).unwrap();
+    assert_eq!(storage.schema_version().unwrap(), SCHEMA_VERSION);
+}
+
+#[test]
+fn test_old_store_runs_registered_migration() {
+    let temp_dir = TempDir::new().unwrap();
+    create_unversioned_store(&temp_dir);
+
+    let runs = Arc::new(AtomicU32::new(0));
+    let migrations: Vec<Box<dyn Migration>> = vec![Box::new(MarkerMigration { runs: runs.clone() })];
+
+    let storage = Storage::open_with_migrations(temp_dir.path(), StorageConfig::default(), &migrations).unwrap();
+    assert_eq!(storage.schema_version().unwrap(), SCHEMA_VERSION);
+    assert_eq!(runs.load(Ordering::SeqCst), 1);
+    storage.close().unwrap();
+
+    // Already current: reopening does not migrate again
+    let storage = Storage::open_with_migrations(temp_dir.path(), StorageConfig::default(), &migrations).unwrap();
+    assert_eq!(runs.load(Ordering::SeqCst), 1);
+    storage.close().unwrap();
+
+    let db = rocksdb::DB::open_cf(&rocksdb::Options::default(), temp_dir.path().join("db"),
+        rocksdb::DB::list_cf(&rocksdb::Options::default(), temp_dir.path().join("db")).unwrap()).unwrap();
+    assert_eq!(db.get(b"migrated").unwrap().as_deref(), Some(&b"yes"[..]));
+    assert_eq!(db.get(b"legacy").unwrap().as_deref(), Some(&b"data"[..]));
+}
+
+#[test]
+fn test_missing_migration_fails() {
+    let temp_dir = TempDir::new().unwrap();
+    create_unversioned_store(&temp_dir);
+
+    let error = Storage::open_with_migrations(temp_dir.path(), StorageConfig::default(), &[]).err().unwrap();
+    assert!(error.to_string().contains("No migration registered from storage schema version 0"));
+}
+
+#[test]
+fn test_newer_store_is_refused() {
+    let temp_dir = TempDir::new().unwrap();
+    Storage::open(temp_dir.path()).unwrap().close().unwrap();
+
+    {
+        let path = temp_dir.path().join("db");
+        let cfs = rocksdb::DB::list_cf(&rocksdb::Options::default(), &path).unwrap();
+        let mut opts = rocksdb::Options::default();
+        opts.create_missing_column_families(true);
+        let mut cfs = cfs;
+        cfs.push("from_the_future".to_string());
+        let db = rocksdb::DB::open_cf(&opts, &path, cfs).unwrap();
+        let meta = db.cf_handle("meta").unwrap();
+        db.put_cf(&meta, b"schema_version", (SCHEMA_VERSION + 1).to_be_bytes()).unwrap();
+    }
+
+    let error = Storage::open(temp_dir.path()).err().unwrap();
+    assert!(error.to_string().contains("newer than the supported version"), "{}", error);
+}
@@ -0,0 +1,57 @@
+//! Integration tests for storage usage statistics
+
+use spaceway_core::{Client, ClientConfig};
+use spaceway_core::crypto::signing::Keypair;
+use spaceway_core::storage::Storage;
+use spaceway_core::types::{MessageId, ThreadId};
+use tempfile::TempDir;
+
+#[tokio::test]
+async fn test_stats_grow_with_blobs_and_ops() {
+    let temp_dir = TempDir::new().unwrap();
+    let config = ClientConfig {
+        storage_path: temp_dir.path().to_path_buf(),
+        listen_addrs: vec!["/ip4/127.0.0.1/tcp/0".to_string()],
+        bootstrap_peers: vec![],
+        ..Default::default()
+    };
+    let client = Client::new(Keypair::generate(), config).unwrap();
+
+    let empty = client.storage_stats().unwrap();
+    assert_eq!(empty.blob_count, 0);
+    assert_eq!(empty.op_count, 0);
+    assert!(empty.per_space_bytes.is_empty());
+
+    let (space, _, _) = client.create_space("Measured".to_string(), None).await.unwrap();
+    let (channel, _) = client.create_channel(space.id, "general".to_string(), None).await.unwrap();
+    let (thread, _) = client.create_thread(space.id, channel.id, None, "First".to_string()).await.unwrap();
+    let after_ops = client.storage_stats().unwrap();
+    assert_eq!(after_ops.op_count, 3);
+    let space_op_bytes = after_ops.per_space_bytes[&space.id];
+    assert!(space_op_bytes > 0);
+
+    client.post_message(space.id, thread.id, "Second".to_string()).await.unwrap();
+    client.store_blob(&[1u8; 100], None, None).await.unwrap();
+    client.store_blob_for_space(&space.id, &[2u8; 200], None, None).await.unwrap();
+
+    let after_blobs = client.storage_stats().unwrap();
+    assert_eq!(after_blobs.op_count, 4);
+    assert_eq!(after_blobs.blob_count, 2);
+    assert!(after_blobs.total_blob_bytes > 300);
+    // The Space's share grew by its message op and its blob; the user blob is unattributed
+    assert!(after_blobs.per_space_bytes[&space.id] > space_op_bytes + 200);
+    assert_eq!(after_blobs.per_space_bytes.len(), 1);
+}
+
+#[test]
+fn test_stats_count_tombstones() {
+    let temp_dir = TempDir::new().unwrap();
+    let storage = Storage::open(temp_dir.path()).unwrap();
+    let thread_id = ThreadId([1u8; 32]);
+
+    assert_eq!(storage.stats().unwrap().tombstone_count, 0);
+    storage.add_tombstone(&thread_id, &MessageId([2u8; 32])).unwrap();
+    storage.add_tombstone(&thread_id, &MessageId([3u8; 32])).unwrap();
+    storage.add_tombstone(&ThreadId([4u8; 32]), &MessageId([5u8; 32])).unwrap();
+    assert_eq!(storage.stats().unwrap().tombstone_count, 3);
+}
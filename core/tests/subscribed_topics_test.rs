@@ -0,0 +1,30 @@
+//! The gossip topics a client is subscribed to can be inspected
+
+use spaceway_core::crypto::signing::Keypair;
+use spaceway_core::{Client, ClientConfig};
+use tempfile::TempDir;
+
+#[tokio::test]
+async fn test_created_space_topic_is_listed() {
+    let temp_dir = TempDir::new().unwrap();
+    let config = ClientConfig {
+        storage_path: temp_dir.path().to_path_buf(),
+        listen_addrs: vec!["/ip4/127.0.0.1/tcp/0".to_string()],
+        bootstrap_peers: vec![],
+        ..Default::default()
+    };
+    let client = Client::new(Keypair::generate(), config).unwrap();
+    client.start().await.unwrap();
+
+    let topics = client.subscribed_topics().await;
+    assert!(topics.contains(&"descord/space-discovery".to_string()));
+    assert!(topics.contains(&format!("user/{}/welcome", client.user_id().short())));
+
+    let (space, _, _) = client.create_space("Listed".to_string(), None).await.unwrap();
+    let space_topic = format!("space/{}", space.id.short());
+    assert!(!topics.contains(&space_topic));
+
+    let topics = client.subscribed_topics().await;
+    assert!(topics.contains(&space_topic), "{:?} should include {}", topics, space_topic);
+    assert!(topics.windows(2).all(|pair| pair[0] <= pair[1]), "topics are sorted");
+}
@@ -0,0 +1,143 @@
+//! Sync requests are answered to the requester only
+
+use spaceway_core::crypto::signing::Keypair;
+use spaceway_core::types::{Role, SpaceId};
+use spaceway_core::{Client, ClientConfig};
+use tempfile::TempDir;
+use tokio::time::{sleep, Duration, Instant};
+
+fn create_client(temp_dir: &TempDir) -> Client {
+    let config = ClientConfig {
+        storage_path: temp_dir.path().to_path_buf(),
+        listen_addrs: vec!["/ip4/127.0.0.1/tcp/0".to_string()],
+        bootstrap_peers: vec![],
+        anti_entropy_interval: None,
+        ..Default::default()
+    };
+    Client::new(Keypair::generate(), config).unwrap()
+}
+
+async fn dial(from: &Client, to: &Client) {
+    let addr = to.listening_addrs().await
+        .into_iter()
+        .find(|addr| addr.to_string().contains("127.0.0.1"))
+        .expect("Client should listen on loopback");
+    from.network_dial(&format!("{}/p2p/{}", addr, to.peer_id().await)).await.unwrap();
+}
+
+/// Add `member` to the Space's MLS group and wait for its Welcome
+async fn admit(admin: &Client, member: &Client, space_id: SpaceId, role: Role) {
+    let bundle = member.get_key_package_bundle().await.unwrap();
+    admin.add_member_with_key_package_bundle(space_id, member.user_id(), role, bundle).await.unwrap();
+    let deadline = Instant::now() + Duration::from_secs(10);
+    while member.space_epoch(&space_id).await.is_none() {
+        assert!(Instant::now() < deadline, "Welcome did not arrive");
+        sleep(Duration::from_millis(200)).await;
+    }
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_joining_peer_sync_does_not_reach_uninvolved_peer() {
+    let dirs: Vec<TempDir> = (0..3).map(|_| TempDir::new().unwrap()).collect();
+    let alice = create_client(&dirs[0]);
+    let bob = create_client(&dirs[1]);
+    let carol = create_client(&dirs[2]);
+    for client in [&alice, &bob, &carol] {
+        client.start().await.unwrap();
+    }
+    sleep(Duration::from_millis(500)).await;
+
+    // Carol already holds everything Alice has
+    let (space, space_op, _) = alice.create_space("Busy".to_string(), None).await.unwrap();
+    let tags_op = alice.update_space_tags(space.id, vec!["busy".to_string()], None).await.unwrap();
+    for op in [space_op.clone(), tags_op] {
+        carol.handle_incoming_op(op).await.unwrap();
+    }
+    dial(&carol, &alice).await;
+    carol.subscribe_to_space(&space.id).await.unwrap();
+
+    // Bob joins late and asks for what he is missing
+    bob.handle_incoming_op(space_op).await.unwrap();
+    dial(&bob, &alice).await;
+    bob.subscribe_to_space(&space.id).await.unwrap();
+    sleep(Duration::from_secs(2)).await;
+    admit(&alice, &bob, space.id, Role::Member).await;
+    bob.request_space_sync(&space.id).await.unwrap();
+
+    let deadline = Instant::now() + Duration::from_secs(15);
+    while bob.get_space(&space.id).await.is_none_or(|space| space.tags.is_empty()) {
+        assert!(Instant::now() < deadline, "Bob did not catch up");
+        sleep(Duration::from_millis(200)).await;
+    }
+    sleep(Duration::from_secs(1)).await;
+
+    // The answers went to Bob alone, so Carol saw no re-sent ops
+    let space_topic = format!("space/{}", space.id.short());
+    let duplicates = carol.gossip_metrics()
+        .get_topic_metrics(&space_topic)
+        .await
+        .map_or(0, |metrics| metrics.duplicates_received);
+    assert_eq!(duplicates, 0);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_sync_answers_stay_sealed_for_the_space_group() {
+    let dirs: Vec<TempDir> = (0..2).map(|_| TempDir::new().unwrap()).collect();
+    let alice = create_client(&dirs[0]);
+    let bob = create_client(&dirs[1]);
+    for client in [&alice, &bob] {
+        client.start().await.unwrap();
+    }
+    sleep(Duration::from_millis(500)).await;
+
+    // Bob isn't in the MLS group, so Alice's answer is nothing he can open
+    let (space, _, _) = alice.create_space("Private".to_string(), None).await.unwrap();
+    dial(&bob, &alice).await;
+    bob.subscribe_to_space(&space.id).await.unwrap();
+    sleep(Duration::from_secs(2)).await;
+    bob.request_space_sync(&space.id).await.unwrap();
+
+    sleep(Duration::from_secs(3)).await;
+    assert!(bob.get_space(&space.id).await.is_none());
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_member_catches_up_on_ops_relayed_by_another_member() {
+    let dirs: Vec<TempDir> = (0..3).map(|_| TempDir::new().unwrap()).collect();
+    let alice = create_client(&dirs[0]);
+    let bob = create_client(&dirs[1]);
+    let carol = create_client(&dirs[2]);
+    for client in [&alice, &bob, &carol] {
+        client.start().await.unwrap();
+    }
+    sleep(Duration::from_millis(500)).await;
+
+    // Bob is in the MLS group and receives Alice's ops as she sends them
+    let (space, space_op, _) = alice.create_space("Relay".to_string(), None).await.unwrap();
+    bob.handle_incoming_op(space_op.clone()).await.unwrap();
+    dial(&bob, &alice).await;
+    bob.subscribe_to_space(&space.id).await.unwrap();
+    sleep(Duration::from_secs(2)).await;
+    admit(&alice, &bob, space.id, Role::Admin).await;
+    alice.update_space_tags(space.id, vec!["relayed".to_string()], None).await.unwrap();
+    let deadline = Instant::now() + Duration::from_secs(10);
+    while bob.get_space(&space.id).await.is_none_or(|space| space.tags.is_empty()) {
+        assert!(Instant::now() < deadline, "Bob did not receive Alice's op");
+        sleep(Duration::from_millis(200)).await;
+    }
+
+    // Carol only ever talks to Bob, who seals Alice's ops under his own leaf
+    carol.handle_incoming_op(space_op).await.unwrap();
+    dial(&carol, &bob).await;
+    carol.subscribe_to_space(&space.id).await.unwrap();
+    sleep(Duration::from_secs(2)).await;
+    admit(&bob, &carol, space.id, Role::Member).await;
+    carol.request_space_sync(&space.id).await.unwrap();
+
+    let deadline = Instant::now() + Duration::from_secs(15);
+    while carol.get_space(&space.id).await.is_none_or(|space| space.tags.is_empty()) {
+        assert!(Instant::now() < deadline, "Carol did not catch up on Alice's op from Bob");
+        sleep(Duration::from_millis(200)).await;
+    }
+    assert_eq!(carol.get_space(&space.id).await.unwrap().tags, vec!["relayed".to_string()]);
+}
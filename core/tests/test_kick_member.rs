@@ -34,6 +34,7 @@ async fn test_kick_member_mls_security() {
         storage_path: PathBuf::from("test-alice-kick-data"),
         listen_addrs: vec!["/ip4/127.0.0.1/tcp/9100".to_string()],
         bootstrap_peers: vec![],
+        ..Default::default()
     };
     let alice = Client::new(alice_keypair.clone(), alice_config).unwrap();
     alice.start().await.unwrap();
@@ -80,6 +81,7 @@ async fn test_kick_member_mls_security() {
         storage_path: PathBuf::from("test-bob-kick-data"),
         listen_addrs: vec![],
         bootstrap_peers: vec![],
+        ..Default::default()
     };
     let bob = Client::new(bob_keypair.clone(), bob_config).unwrap();
     bob.start().await.unwrap();
@@ -47,6 +47,7 @@ async fn test_mls_add_member_with_keypackages() -> Result<()> {
         storage_path: alice_dir.clone(),
         bootstrap_peers: vec![],
         listen_addrs: vec!["/ip4/127.0.0.1/tcp/0".parse().unwrap()],
+        ..Default::default()
     };
     
     let alice = Client::new(alice_keypair, alice_config)?;
@@ -65,6 +66,7 @@ async fn test_mls_add_member_with_keypackages() -> Result<()> {
         storage_path: bob_dir.clone(),
         bootstrap_peers: vec![],
         listen_addrs: vec!["/ip4/127.0.0.1/tcp/0".parse().unwrap()],
+        ..Default::default()
     };
     
     let bob = Client::new(bob_keypair, bob_config)?;
@@ -182,6 +184,7 @@ async fn test_mls_keypackage_retrieval_failure() -> Result<()> {
         storage_path: alice_dir.clone(),
         bootstrap_peers: vec![],
         listen_addrs: vec!["/ip4/127.0.0.1/tcp/0".parse().unwrap()],
+        ..Default::default()
     };
     
     let alice = Client::new(alice_keypair, alice_config)?;
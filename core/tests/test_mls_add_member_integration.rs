@@ -31,6 +31,7 @@ async fn test_mls_member_addition_with_connected_peers() {
         storage_path: PathBuf::from("test-alice-mls"),
         listen_addrs: vec!["/ip4/127.0.0.1/tcp/9877".to_string()],
         bootstrap_peers: vec![],
+        ..Default::default()
     };
     let alice = Client::new(alice_keypair.clone(), alice_config).unwrap();
     let alice_id = alice.user_id();
@@ -64,6 +65,7 @@ async fn test_mls_member_addition_with_connected_peers() {
         storage_path: PathBuf::from("test-bob-mls"),
         listen_addrs: vec![],
         bootstrap_peers: vec![],
+        ..Default::default()
     };
     let bob = Client::new(bob_keypair.clone(), bob_config).unwrap();
     let bob_id = bob.user_id();
@@ -188,6 +190,7 @@ async fn test_mls_keypackage_generation_on_startup() {
         storage_path: PathBuf::from("test-kp-gen"),
         listen_addrs: vec![],
         bootstrap_peers: vec![],
+        ..Default::default()
     };
     
     let client = Client::new(keypair, config).unwrap();
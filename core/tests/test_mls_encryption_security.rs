@@ -17,6 +17,7 @@ async fn test_kicked_member_cannot_decrypt_messages() -> Result<()> {
         storage_path: PathBuf::from("test-alice-mls-security"),
         listen_addrs: vec![],
         bootstrap_peers: vec![],
+        ..Default::default()
     };
     let alice = Client::new(alice_keypair.clone(), alice_config)?;
     let alice_id = alice.user_id();
@@ -31,6 +32,7 @@ async fn test_kicked_member_cannot_decrypt_messages() -> Result<()> {
         storage_path: PathBuf::from("test-bob-mls-security"),
         listen_addrs: vec![],
         bootstrap_peers: vec![],
+        ..Default::default()
     };
     let bob = Client::new(bob_keypair.clone(), bob_config)?;
     let bob_id = bob.user_id();
@@ -0,0 +1,77 @@
+//! SmoothTest for anti-entropy
+//!
+//! Partitions Bob from Alice while she tags a Space they share, heals the
+//! link, and checks that Bob catches up on his own: nobody calls
+//! `request_sync`.
+
+#![cfg(feature = "test-utils")]
+
+use spaceway_core::smoothtest::*;
+use spaceway_core::types::Role;
+use spaceway_core::ClientConfig;
+use std::time::Duration;
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_transient_partition_heals_via_anti_entropy() {
+    println!("\nTEST: Partition heals through anti-entropy...");
+
+    let config = ClientConfig {
+        anti_entropy_interval: Some(Duration::from_secs(1)),
+        ..Default::default()
+    };
+    let batch = SmoothClientBatch::with_config(2, config).unwrap();
+    let alice = &batch[0];
+    let bob = &batch[1];
+
+    for client in batch.iter() {
+        client.client().read().await.start().await.unwrap();
+    }
+    tokio::time::sleep(Duration::from_secs(1)).await;
+
+    // Bob dials Alice directly
+    let alice_peer_id = alice.client().read().await.peer_id().await;
+    let alice_addr = alice.client().read().await.listening_addrs().await
+        .into_iter()
+        .find(|addr| addr.to_string().contains("127.0.0.1"))
+        .expect("Alice should listen on loopback");
+    bob.client().read().await
+        .network_dial(&format!("{}/p2p/{}", alice_addr, alice_peer_id))
+        .await
+        .unwrap();
+    println!("✓ Bob dialed Alice at {}", alice_addr);
+
+    // Bob joins Alice's Space and its MLS group
+    let (space, space_op, _) = alice.client().read().await
+        .create_space("partitioned".to_string(), None)
+        .await
+        .unwrap();
+    bob.client().read().await.handle_incoming_op(space_op).await.unwrap();
+    bob.client().read().await.subscribe_to_space(&space.id).await.unwrap();
+    tokio::time::sleep(Duration::from_secs(1)).await;
+    alice.admit(bob, space.id, Role::Member).await.unwrap();
+    println!("✓ Bob joined the Space");
+
+    // Partition: Bob drops everything he receives
+    bob.set_network_conditions(NetworkConditions::lossy(1.0)).await.unwrap();
+    alice.client().read().await
+        .update_space_tags(space.id, vec!["healed".to_string()], None)
+        .await
+        .unwrap();
+    tokio::time::sleep(Duration::from_secs(2)).await;
+    assert!(bob.client().read().await.get_space(&space.id).await.unwrap().tags.is_empty());
+    println!("✓ Bob missed the Space's tags during the partition");
+
+    // Heal, then wait without requesting sync
+    bob.set_network_conditions(NetworkConditions::perfect()).await.unwrap();
+    let deadline = tokio::time::Instant::now() + Duration::from_secs(30);
+    while bob.client().read().await.get_space(&space.id).await.unwrap().tags.is_empty() {
+        assert!(
+            tokio::time::Instant::now() < deadline,
+            "Bob did not catch up within 30s of the partition healing"
+        );
+        tokio::time::sleep(Duration::from_millis(500)).await;
+    }
+
+    assert_eq!(bob.space_count().await, 1);
+    println!("✓ TEST PASSED: Bob caught up through anti-entropy");
+}
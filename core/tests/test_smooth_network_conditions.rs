@@ -0,0 +1,78 @@
+//! SmoothTest under degraded network conditions
+//!
+//! Runs two clients over a lossy, jittery link and checks that Space state
+//! still converges once the joiner keeps requesting sync (anti-entropy).
+
+#![cfg(feature = "test-utils")]
+
+use spaceway_core::smoothtest::*;
+use spaceway_core::types::Role;
+use std::time::Duration;
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_space_converges_under_20_percent_loss() {
+    println!("\nTEST: Space convergence under 20% message loss...");
+
+    let batch = SmoothClientBatch::with_seed(2, [3u8; 32]).unwrap();
+    let alice = &batch[0];
+    let bob = &batch[1];
+
+    for client in batch.iter() {
+        client.client().read().await.start().await.unwrap();
+    }
+    tokio::time::sleep(Duration::from_secs(1)).await;
+
+    // Bob dials Alice directly
+    let alice_peer_id = alice.client().read().await.peer_id().await;
+    let alice_addr = alice.client().read().await.listening_addrs().await
+        .into_iter()
+        .find(|addr| addr.to_string().contains("127.0.0.1"))
+        .expect("Alice should listen on loopback");
+    bob.client().read().await
+        .network_dial(&format!("{}/p2p/{}", alice_addr, alice_peer_id))
+        .await
+        .unwrap();
+    println!("✓ Bob dialed Alice at {}", alice_addr);
+
+    let (space, space_op, _) = alice.client().read().await
+        .create_space("lossy-space".to_string(), Some("Created over a bad link".to_string()))
+        .await
+        .unwrap();
+    bob.client().read().await.handle_incoming_op(space_op).await.unwrap();
+    bob.client().read().await.subscribe_to_space(&space.id).await.unwrap();
+    tokio::time::sleep(Duration::from_secs(1)).await;
+    alice.admit(bob, space.id, Role::Member).await.unwrap();
+    println!("✓ Alice created space {:?}, Bob joined", space.id);
+
+    // Degrade every link: 20% loss plus 20-50ms delay
+    batch.set_network_conditions(NetworkConditions {
+        latency: Duration::from_millis(20),
+        jitter: Duration::from_millis(30),
+        loss_rate: 0.2,
+    }).await.unwrap();
+    println!("✓ Network conditions applied");
+
+    let tags: Vec<String> = ["lossy", "jittery", "slow"].iter().map(|tag| tag.to_string()).collect();
+    for count in 1..=tags.len() {
+        alice.client().read().await
+            .update_space_tags(space.id, tags[..count].to_vec(), None)
+            .await
+            .unwrap();
+    }
+
+    // Keep requesting sync until Bob has every update
+    let deadline = tokio::time::Instant::now() + Duration::from_secs(30);
+    while bob.client().read().await.get_space(&space.id).await.unwrap().tags != tags {
+        assert!(
+            tokio::time::Instant::now() < deadline,
+            "Bob did not converge within 30s under 20% loss"
+        );
+        let _ = bob.request_sync(space.id).await;
+        tokio::time::sleep(Duration::from_secs(1)).await;
+    }
+
+    let bob_spaces = bob.list_spaces().await;
+    assert_eq!(bob_spaces.len(), 1);
+    assert_eq!(bob_spaces[0].id, space.id);
+    println!("✓ TEST PASSED: Bob converged on Alice's updates");
+}
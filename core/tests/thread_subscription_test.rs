@@ -0,0 +1,59 @@
+//! Following one Thread live through `subscribe_thread`
+
+use spaceway_core::crypto::signing::Keypair;
+use spaceway_core::{Client, ClientConfig, Role};
+use tempfile::TempDir;
+use tokio::time::{timeout, Duration};
+
+fn create_client(temp_dir: &TempDir) -> Client {
+    let config = ClientConfig {
+        storage_path: temp_dir.path().to_path_buf(),
+        listen_addrs: vec!["/ip4/127.0.0.1/tcp/0".to_string()],
+        bootstrap_peers: vec![],
+        ..Default::default()
+    };
+    Client::new(Keypair::generate(), config).unwrap()
+}
+
+#[tokio::test]
+async fn test_subscribed_thread_emits_posts_and_edits() {
+    let alice_dir = TempDir::new().unwrap();
+    let bob_dir = TempDir::new().unwrap();
+    let alice = create_client(&alice_dir);
+    let bob = create_client(&bob_dir);
+
+    let (space, space_op, _) = alice.create_space("Live".to_string(), None).await.unwrap();
+    let (channel, channel_op) = alice.create_channel(space.id, "general".to_string(), None).await.unwrap();
+    let (thread, thread_op) = alice.create_thread(space.id, channel.id, None, "Watched".to_string()).await.unwrap();
+    let (other, other_op) = alice.create_thread(space.id, channel.id, None, "Elsewhere".to_string()).await.unwrap();
+    let member_op = alice.add_member(space.id, bob.user_id(), Role::Member).await.unwrap();
+    for op in [space_op, channel_op, thread_op, other_op, member_op] {
+        bob.handle_incoming_op(op).await.unwrap();
+    }
+    let mut alice_tail = alice.subscribe_thread(thread.id);
+    let mut bob_tail = bob.subscribe_thread(thread.id);
+
+    // Messages in other threads are filtered out
+    let (_, elsewhere_op) = alice.post_message(space.id, other.id, "Not here".to_string()).await.unwrap();
+    let (message, message_op) = alice.post_message(space.id, thread.id, "First!".to_string()).await.unwrap();
+    let posted = timeout(Duration::from_secs(5), alice_tail.recv()).await.unwrap().unwrap();
+    assert_eq!(posted.id, message.id);
+    assert_eq!(posted.content, "First!");
+
+    // Ops from peers show up the same way
+    bob.handle_incoming_op(elsewhere_op).await.unwrap();
+    bob.handle_incoming_op(message_op).await.unwrap();
+    let received = timeout(Duration::from_secs(5), bob_tail.recv()).await.unwrap().unwrap();
+    assert_eq!(received.id, message.id);
+
+    // Edits resend the message as it now reads
+    let edit_op = alice.edit_message(space.id, message.id, "First! (edited)".to_string()).await.unwrap();
+    let edited = timeout(Duration::from_secs(5), alice_tail.recv()).await.unwrap().unwrap();
+    assert_eq!(edited.id, message.id);
+    assert_eq!(edited.content, "First! (edited)");
+    assert!(edited.edited_at.is_some());
+
+    bob.handle_incoming_op(edit_op).await.unwrap();
+    let edited = timeout(Duration::from_secs(5), bob_tail.recv()).await.unwrap().unwrap();
+    assert_eq!(edited.content, "First! (edited)");
+}
@@ -0,0 +1,68 @@
+//! Per-thread message count and last activity converge across replicas
+
+use spaceway_core::crypto::signing::Keypair;
+use spaceway_core::{Client, ClientConfig};
+use std::time::Duration;
+use tempfile::TempDir;
+
+fn create_client(temp_dir: &TempDir) -> Client {
+    let config = ClientConfig {
+        storage_path: temp_dir.path().to_path_buf(),
+        listen_addrs: vec!["/ip4/127.0.0.1/tcp/0".to_string()],
+        bootstrap_peers: vec![],
+        ..Default::default()
+    };
+    Client::new(Keypair::generate(), config).unwrap()
+}
+
+#[tokio::test]
+async fn test_posting_updates_summary_on_second_replica() {
+    let alice_dir = TempDir::new().unwrap();
+    let bob_dir = TempDir::new().unwrap();
+    let alice = create_client(&alice_dir);
+    let bob = create_client(&bob_dir);
+
+    let (space, space_op, _) = alice.create_space("Summaries".to_string(), None).await.unwrap();
+    let (channel, channel_op) = alice.create_channel(space.id, "general".to_string(), None).await.unwrap();
+    let (thread, thread_op) = alice.create_thread(space.id, channel.id, None, "Hello".to_string()).await.unwrap();
+    for op in [space_op, channel_op, thread_op] {
+        bob.handle_incoming_op(op).await.unwrap();
+    }
+
+    let on_bob = bob.get_thread(&thread.id).await.unwrap();
+    assert_eq!(on_bob.message_count, 1);
+    assert_eq!(on_bob.last_activity, on_bob.created_at);
+
+    // Timestamps have second resolution
+    tokio::time::sleep(Duration::from_millis(1100)).await;
+    let (first, first_op) = alice.post_message(space.id, thread.id, "one".to_string()).await.unwrap();
+    let (second, second_op) = alice.post_message(space.id, thread.id, "two".to_string()).await.unwrap();
+    for op in [first_op.clone(), second_op, first_op] {
+        let _ = bob.handle_incoming_op(op).await;
+    }
+
+    let on_alice = alice.get_thread(&thread.id).await.unwrap();
+    let on_bob = bob.get_thread(&thread.id).await.unwrap();
+    assert_eq!(on_bob.message_count, 3, "a re-delivered post is counted once");
+    assert_eq!(on_bob.last_activity, first.created_at.max(second.created_at));
+    assert!(on_bob.last_activity > on_bob.created_at);
+    assert_eq!(
+        (on_alice.message_count, on_alice.last_activity),
+        (on_bob.message_count, on_bob.last_activity)
+    );
+
+    // Deleted messages drop out of the count everywhere
+    let redact_op = alice.redact_message(space.id, first.id).await.unwrap();
+    bob.handle_incoming_op(redact_op).await.unwrap();
+    assert_eq!(alice.get_thread(&thread.id).await.unwrap().message_count, 2);
+    assert_eq!(bob.get_thread(&thread.id).await.unwrap().message_count, 2);
+
+    let snapshot = bob.get_dashboard_snapshot("bob").await;
+    let summary = snapshot.spaces.iter()
+        .flat_map(|space| &space.channels)
+        .flat_map(|channel| &channel.threads)
+        .find(|snapshot| snapshot.id == thread.id.to_string())
+        .unwrap();
+    assert_eq!(summary.message_count, 2);
+    assert_eq!(summary.last_activity, bob.get_thread(&thread.id).await.unwrap().last_activity);
+}
@@ -1,4 +1,4 @@
-use spaceway_core::{Client, ClientConfig, crypto::signing::Keypair};
+use spaceway_core::{Client, ClientConfig, crypto::signing::Keypair, Role};
 use tokio::time::{sleep, Duration};
 use anyhow::Result;
 
@@ -28,6 +28,7 @@ async fn test_three_person_interaction() -> Result<()> {
         storage_path: alice_dir.path().to_path_buf(),
         listen_addrs: vec!["/ip4/127.0.0.1/tcp/9001".to_string()],
         bootstrap_peers: vec![],
+        ..Default::default()
     };
 
     // Start Alice's client
@@ -74,6 +75,7 @@ async fn test_three_person_interaction() -> Result<()> {
         storage_path: bob_dir.path().to_path_buf(),
         listen_addrs: vec!["/ip4/127.0.0.1/tcp/9002".to_string()],
         bootstrap_peers: vec!["/ip4/127.0.0.1/tcp/9001".to_string()],
+        ..Default::default()
     };
 
     // Start Bob's client
@@ -88,6 +90,11 @@ async fn test_three_person_interaction() -> Result<()> {
     bob.apply_remote_op(&channel_op).await?;
     bob.apply_remote_op(&thread_op).await?;
     bob.apply_remote_op(&msg1_op).await?;
+    
+    // Bob becomes a member and joins the channel's MLS group to post in it
+    let bob_member_op = alice.add_member(alice_space.id, bob.user_id(), Role::Member).await?;
+    bob.apply_remote_op(&bob_member_op).await?;
+    bob.join_channel_with_group_info(&alice_channel.id, &alice.channel_group_info(&alice_channel.id).await?).await?;
 
     // Give Bob time to process operations
     sleep(Duration::from_millis(500)).await;
@@ -137,6 +144,7 @@ async fn test_three_person_interaction() -> Result<()> {
         storage_path: charlie_dir.path().to_path_buf(),
         listen_addrs: vec!["/ip4/127.0.0.1/tcp/9003".to_string()],
         bootstrap_peers: vec!["/ip4/127.0.0.1/tcp/9001".to_string()],
+        ..Default::default()
     };
 
     // Start Charlie's client
@@ -150,6 +158,9 @@ async fn test_three_person_interaction() -> Result<()> {
     charlie.apply_remote_op(&thread_op).await?;
     charlie.apply_remote_op(&msg1_op).await?;
     charlie.apply_remote_op(&bob_msg1_op).await?;
+    let charlie_member_op = alice.add_member(alice_space.id, charlie.user_id(), Role::Member).await?;
+    charlie.apply_remote_op(&charlie_member_op).await?;
+    charlie.join_channel_with_group_info(&alice_channel.id, &alice.channel_group_info(&alice_channel.id).await?).await?;
 
     // Give Charlie time to process
     sleep(Duration::from_millis(500)).await;
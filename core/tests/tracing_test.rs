@@ -0,0 +1,91 @@
+//! Integration tests for tracing spans along the op lifecycle
+
+use spaceway_core::{Client, ClientConfig};
+use spaceway_core::crypto::signing::Keypair;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use tempfile::TempDir;
+use tracing::field::{Field, Visit};
+use tracing::span::{Attributes, Id, Record};
+use tracing_subscriber::layer::{Context, SubscriberExt};
+use tracing_subscriber::Layer;
+
+/// A recorded span: its name and every field recorded on it
+#[derive(Debug, Clone)]
+struct CapturedSpan {
+    name: &'static str,
+    fields: HashMap<String, String>,
+}
+
+/// Layer that keeps every span it sees, in creation order
+#[derive(Clone, Default)]
+struct SpanCapture {
+    spans: Arc<Mutex<Vec<CapturedSpan>>>,
+    ids: Arc<Mutex<HashMap<Id, usize>>>,
+}
+
+struct FieldVisitor<'a>(&'a mut HashMap<String, String>);
+
+impl Visit for FieldVisitor<'_> {
+    fn record_str(&mut self, field: &Field, value: &str) {
+        self.0.insert(field.name().to_string(), value.to_string());
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn std::fmt::Debug) {
+        self.0.insert(field.name().to_string(), format!("{:?}", value));
+    }
+}
+
+impl<S: tracing::Subscriber> Layer<S> for SpanCapture {
+    fn on_new_span(&self, attrs: &Attributes<'_>, id: &Id, _ctx: Context<'_, S>) {
+        let mut fields = HashMap::new();
+        attrs.record(&mut FieldVisitor(&mut fields));
+        let mut spans = self.spans.lock().unwrap();
+        self.ids.lock().unwrap().insert(id.clone(), spans.len());
+        spans.push(CapturedSpan { name: attrs.metadata().name(), fields });
+    }
+
+    fn on_record(&self, id: &Id, values: &Record<'_>, _ctx: Context<'_, S>) {
+        if let Some(&index) = self.ids.lock().unwrap().get(id) {
+            values.record(&mut FieldVisitor(&mut self.spans.lock().unwrap()[index].fields));
+        }
+    }
+}
+
+#[tokio::test]
+async fn test_broadcast_op_span_carries_op_fields() {
+    let capture = SpanCapture::default();
+    let _guard = tracing::subscriber::set_default(
+        tracing_subscriber::registry().with(capture.clone())
+    );
+
+    let temp_dir = TempDir::new().unwrap();
+    let config = ClientConfig {
+        storage_path: temp_dir.path().to_path_buf(),
+        listen_addrs: vec!["/ip4/127.0.0.1/tcp/0".to_string()],
+        bootstrap_peers: vec![],
+        ..Default::default()
+    };
+    let client = Client::new(Keypair::generate(), config).unwrap();
+
+    let (space, op, _) = client.create_space("Traced".to_string(), None).await.unwrap();
+    let space_hex = hex::encode(&space.id.0[..8]);
+
+    let spans = capture.spans.lock().unwrap().clone();
+
+    let broadcast = spans.iter()
+        .find(|span| span.name == "broadcast_op")
+        .expect("broadcast_op span should be recorded");
+    assert_eq!(broadcast.fields["op_id"], op.op_id.0.to_string());
+    assert_eq!(broadcast.fields["space_id"], space_hex);
+    assert_eq!(broadcast.fields["topic"], format!("space/{}", space_hex));
+
+    let create = spans.iter()
+        .find(|span| span.name == "create_space")
+        .expect("create_space span should be recorded");
+    assert_eq!(create.fields["space_id"], space_hex);
+    assert_eq!(create.fields["op_id"], op.op_id.0.to_string());
+
+    assert!(spans.iter().any(|span| span.name == "dht_put_operations"
+        && span.fields.get("space_id") == Some(&space_hex)));
+}
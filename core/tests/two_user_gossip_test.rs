@@ -27,6 +27,7 @@ async fn test_two_users_gossipsub_sync() {
         storage_path: PathBuf::from("test-alice-data"),
         listen_addrs: vec!["/ip4/127.0.0.1/tcp/9876".to_string()],
         bootstrap_peers: vec![],
+        ..Default::default()
     };
     let alice = Client::new(alice_keypair.clone(), alice_config).unwrap();
     alice.start().await.unwrap();
@@ -79,7 +80,7 @@ async fn test_two_users_gossipsub_sync() {
 
     // Step 3: Alice creates an invite
     println!("\n📝 Step 3: Alice creates invite code...");
-    let invite_op = alice.create_invite(space.id, None, None).await.unwrap();
+    let invite_op = alice.create_invite(space.id, None, None, None, None).await.unwrap();
     
     // Get the actual invite from the list
     let invites = alice.list_invites(&space.id).await;
@@ -97,6 +98,7 @@ async fn test_two_users_gossipsub_sync() {
         storage_path: PathBuf::from("test-bob-data"),
         listen_addrs: vec![],
         bootstrap_peers: vec![],
+        ..Default::default()
     };
     let bob = Client::new(bob_keypair.clone(), bob_config).unwrap();
     bob.start().await.unwrap();
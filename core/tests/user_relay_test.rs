@@ -70,6 +70,7 @@ async fn test_client_discovers_user_relay() -> Result<()> {
         storage_path: temp_dir.path().to_path_buf(),
         listen_addrs: vec!["/ip4/127.0.0.1/tcp/0".to_string()],
         bootstrap_peers: vec![],
+        ..Default::default()
     };
     
     let _client = Client::new(keypair, config)?;
@@ -117,6 +118,7 @@ async fn test_relay_hides_ip_addresses() -> Result<()> {
         storage_path: alice_temp.path().to_path_buf(),
         listen_addrs: vec!["/ip4/127.0.0.1/tcp/0".to_string()],
         bootstrap_peers: vec![],
+        ..Default::default()
     };
     let alice = Client::new(alice_keypair, alice_config)?;
     
@@ -127,6 +129,7 @@ async fn test_relay_hides_ip_addresses() -> Result<()> {
         storage_path: bob_temp.path().to_path_buf(),
         listen_addrs: vec!["/ip4/127.0.0.1/tcp/0".to_string()],
         bootstrap_peers: vec![],
+        ..Default::default()
     };
     let _bob = Client::new(bob_keypair, bob_config)?;
     
@@ -161,6 +164,7 @@ async fn test_relay_bandwidth_limits() -> Result<()> {
         max_circuits_per_peer: 3,
         max_circuit_duration: Duration::from_secs(300),
         max_circuit_bytes: 10 * 1024 * 1024, // 10 MB
+        ..Default::default()
     };
     
     // Verify config is reasonable
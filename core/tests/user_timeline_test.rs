@@ -0,0 +1,56 @@
+//! A user's messages across threads, read back from the per-user index
+
+use spaceway_core::crypto::signing::Keypair;
+use spaceway_core::storage::{BlobHash, MessageIndex, Storage};
+use spaceway_core::types::{MessageId, ThreadId, UserId};
+use spaceway_core::{Client, ClientConfig};
+use tempfile::TempDir;
+
+fn index(storage: &Storage, author: UserId, thread: u8, timestamp: u64) -> MessageId {
+    let message_id = MessageId([timestamp as u8; 32]);
+    storage.index_message(&MessageIndex {
+        message_id,
+        blob_hash: BlobHash::hash(&timestamp.to_be_bytes()),
+        timestamp,
+        author,
+        thread_id: ThreadId([thread; 32]),
+    }).unwrap();
+    message_id
+}
+
+#[test]
+fn test_user_timeline_is_newest_first_and_excludes_others() {
+    let temp_dir = TempDir::new().unwrap();
+    let alice = UserId([1; 32]);
+    let bob = UserId([2; 32]);
+
+    let expected = {
+        let storage = Storage::open(temp_dir.path()).unwrap();
+        let first = index(&storage, alice, 1, 100);
+        index(&storage, bob, 1, 150);
+        let second = index(&storage, alice, 2, 200);
+        index(&storage, bob, 2, 250);
+        let third = index(&storage, alice, 1, 300);
+
+        let page = storage.get_user_messages(&alice, 2, None).unwrap();
+        assert_eq!(page.iter().map(|m| m.message_id).collect::<Vec<_>>(), vec![third, second]);
+
+        // The oldest timestamp of one page fetches the next
+        let next = storage.get_user_messages(&alice, 2, Some(page[1].timestamp)).unwrap();
+        assert_eq!(next.iter().map(|m| m.message_id).collect::<Vec<_>>(), vec![first]);
+        assert!(storage.get_user_messages(&UserId([3; 32]), 10, None).unwrap().is_empty());
+
+        vec![third, second, first]
+    };
+
+    let config = ClientConfig {
+        storage_path: temp_dir.path().to_path_buf(),
+        listen_addrs: vec!["/ip4/127.0.0.1/tcp/0".to_string()],
+        ..Default::default()
+    };
+    let client = Client::new(Keypair::generate(), config).unwrap();
+    let timeline = client.user_timeline(&alice).unwrap();
+    assert_eq!(timeline.iter().map(|m| m.message_id).collect::<Vec<_>>(), expected);
+    assert!(timeline.iter().all(|m| m.author == alice));
+    assert_eq!(client.user_timeline(&bob).unwrap().len(), 2);
+}
@@ -0,0 +1,99 @@
+//! Integration tests for channel webhooks
+
+use spaceway_core::crypto::signing::Keypair;
+use spaceway_core::webhook::{WebhookPost, WebhookToken};
+use spaceway_core::{Client, ClientConfig, Error};
+use tempfile::TempDir;
+
+fn create_client(temp_dir: &TempDir) -> Client {
+    let config = ClientConfig {
+        storage_path: temp_dir.path().to_path_buf(),
+        listen_addrs: vec!["/ip4/127.0.0.1/tcp/0".to_string()],
+        bootstrap_peers: vec![],
+        ..Default::default()
+    };
+    Client::new(Keypair::generate(), config).unwrap()
+}
+
+fn post(content: &str, thread_id: Option<String>) -> WebhookPost {
+    WebhookPost { content: content.to_string(), thread_id, title: None }
+}
+
+#[tokio::test]
+async fn test_webhook_posts_into_thread_until_revoked() {
+    let temp_dir = TempDir::new().unwrap();
+    let client = create_client(&temp_dir);
+
+    let (space, _, _) = client.create_space("Builds".to_string(), None).await.unwrap();
+    let (channel, _) = client.create_channel(space.id, "ci".to_string(), None).await.unwrap();
+    let (thread, _) = client.create_thread(space.id, channel.id, None, "Build log".to_string()).await.unwrap();
+    let token = client.create_webhook(channel.id).await.unwrap();
+
+    // The token survives a round trip through its URL form
+    let token: WebhookToken = token.to_string().parse().unwrap();
+    let message = client.post_via_webhook(&token, post("Build #42 passed", Some(hex::encode(thread.id.0))))
+        .await.unwrap();
+    let messages = client.list_messages(&thread.id).await;
+    assert!(messages.iter().any(|m| m.id == message.id && m.content == "Build #42 passed"));
+
+    // Without a thread, a new one is started in the token's channel
+    let started = client.post_via_webhook(&token, post("Deploy finished", None)).await.unwrap();
+    assert_eq!(client.get_thread(&started.thread_id).await.unwrap().channel_id, channel.id);
+
+    assert!(client.revoke_webhook(&token).await);
+    let result = client.post_via_webhook(&token, post("too late", None)).await;
+    assert!(matches!(result, Err(Error::Permission(_))));
+}
+
+#[tokio::test]
+async fn test_token_from_another_client_is_rejected() {
+    let temp_dir = TempDir::new().unwrap();
+    let other_dir = TempDir::new().unwrap();
+    let client = create_client(&temp_dir);
+    let other = create_client(&other_dir);
+
+    let (space, _, _) = other.create_space("Elsewhere".to_string(), None).await.unwrap();
+    let (channel, _) = other.create_channel(space.id, "ci".to_string(), None).await.unwrap();
+    let token = other.create_webhook(channel.id).await.unwrap();
+
+    let result = client.post_via_webhook(&token, post("spoofed", None)).await;
+    assert!(matches!(result, Err(Error::Permission(_))));
+}
+
+#[cfg(feature = "webhook-server")]
+#[tokio::test]
+async fn test_http_endpoint_posts_message() {
+    use std::sync::Arc;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::sync::RwLock;
+
+    let temp_dir = TempDir::new().unwrap();
+    let client = Arc::new(RwLock::new(create_client(&temp_dir)));
+    let (thread, token) = {
+        let client = client.read().await;
+        let (space, _, _) = client.create_space("Alerts".to_string(), None).await.unwrap();
+        let (channel, _) = client.create_channel(space.id, "pager".to_string(), None).await.unwrap();
+        let (thread, _) = client.create_thread(space.id, channel.id, None, "Incidents".to_string()).await.unwrap();
+        (thread, client.create_webhook(channel.id).await.unwrap())
+    };
+
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(spaceway_core::webhook::serve(listener, Arc::clone(&client)));
+
+    let body = serde_json::json!({ "content": "Disk almost full", "thread_id": hex::encode(thread.id.0) }).to_string();
+    let request = format!(
+        "POST /webhook/{} HTTP/1.1\r\nHost: localhost\r\nContent-Type: application/json\r\nContent-Length: {}\r\n\r\n{}",
+        token,
+        body.len(),
+        body
+    );
+    let mut stream = tokio::net::TcpStream::connect(addr).await.unwrap();
+    stream.write_all(request.as_bytes()).await.unwrap();
+    let mut response = String::new();
+    stream.read_to_string(&mut response).await.unwrap();
+
+    assert!(response.starts_with("HTTP/1.1 200 OK"), "{}", response);
+    let messages = client.read().await.list_messages(&thread.id).await;
+    assert!(messages.iter().any(|m| m.content == "Disk almost full"));
+}
@@ -1,6 +1,7 @@
 //! Dashboard Backend
 //!
-//! Manages 3 Discord-Lite clients (Alice, Bob, Charlie) and exposes their state via WebSocket API.
+//! Manages N Discord-Lite clients (Alice, Bob, Charlie, ... ; 3 by default) and exposes
+//! their state via WebSocket API.
 
 use axum::{
     extract::{
@@ -11,6 +12,7 @@ use axum::{
     routing::{get, post},
     Json, Router,
 };
+use clap::Parser;
 use serde::{Deserialize, Serialize};
 use spaceway_core::{Client, ClientConfig, dashboard::DashboardState};
 use spaceway_core::crypto::signing::Keypair;
@@ -20,24 +22,53 @@ use tower_http::cors::{Any, CorsLayer};
 use tracing::{info, error};
 use tempfile::TempDir;
 
-/// Shared state for all 3 clients
+/// Command-line arguments
+#[derive(Parser, Debug)]
+#[command(name = "dashboard-backend")]
+#[command(about = "Spaceway dashboard backend", long_about = None)]
+struct Args {
+    /// Number of clients to run (named Alice, Bob, Charlie, ...)
+    #[arg(long, default_value_t = 3)]
+    clients: usize,
+}
+
+/// Display names for the first clients; later ones are numbered
+const CLIENT_NAMES: [&str; 10] = [
+    "Alice", "Bob", "Charlie", "Dave", "Eve", "Frank", "Grace", "Heidi", "Ivan", "Judy",
+];
+
+/// Display name for the client at `index`
+fn client_name(index: usize) -> String {
+    CLIENT_NAMES.get(index)
+        .map(|name| name.to_string())
+        .unwrap_or_else(|| format!("Client{}", index + 1))
+}
+
+/// Shared state for all clients
 #[derive(Clone)]
 struct AppState {
-    alice: Arc<RwLock<Client>>,
-    bob: Arc<RwLock<Client>>,
-    charlie: Arc<RwLock<Client>>,
+    clients: Vec<(String, Arc<RwLock<Client>>)>,
     temp_dirs: Arc<Vec<TempDir>>, // Keep temp directories alive
 }
 
+impl AppState {
+    /// Look up a client by display name (case-insensitive)
+    fn client(&self, name: &str) -> Option<&Arc<RwLock<Client>>> {
+        self.clients.iter()
+            .find(|(client_name, _)| client_name.eq_ignore_ascii_case(name))
+            .map(|(_, client)| client)
+    }
+}
+
 /// Action request from frontend
 #[derive(Debug, Deserialize)]
 struct ActionRequest {
-    client: String, // "alice", "bob", or "charlie"
+    client: String, // Client display name, e.g. "alice" or "Bob"
     action: Action,
 }
 
 /// Action response
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, Deserialize)]
 struct ActionResponse {
     success: bool,
     message: String,
@@ -45,6 +76,17 @@ struct ActionResponse {
     data: Option<serde_json::Value>,
 }
 
+/// Non-state messages pushed over the WebSocket
+///
+/// State updates are sent as a bare `DashboardState`; everything else is
+/// tagged with a `type` field so the frontend can tell them apart.
+#[derive(Debug, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum SocketMessage {
+    /// Result of an action received over the socket
+    ActionResult(ActionResponse),
+}
+
 /// Actions that can be performed
 #[derive(Debug, Deserialize)]
 #[serde(tag = "type")]
@@ -89,67 +131,70 @@ async fn main() -> anyhow::Result<()> {
         .with_env_filter("dashboard_backend=debug,spaceway_core=info")
         .init();
 
+    let args = Args::parse();
+    anyhow::ensure!(args.clients > 0, "--clients must be at least 1");
+
     info!("🚀 Starting Dashboard Backend");
 
-    // Create temporary directories for client storage
-    let alice_dir = TempDir::new()?;
-    let bob_dir = TempDir::new()?;
-    let charlie_dir = TempDir::new()?;
-    
-    info!("📁 Alice storage: {:?}", alice_dir.path());
-    info!("📁 Bob storage: {:?}", bob_dir.path());
-    info!("📁 Charlie storage: {:?}", charlie_dir.path());
+    let state = create_app_state(args.clients)?;
+    let app = build_router(state);
+
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:3030")
+        .await?;
 
-    // Create 3 real clients
-    info!("👥 Creating clients...");
+    info!("🎯 Dashboard backend listening on http://127.0.0.1:3030");
+    info!("💡 Using {} REAL spaceway-core clients", args.clients);
+    info!("💡 WebSocket at ws://127.0.0.1:3030/ws");
+    info!("💡 REST API at http://127.0.0.1:3030/api/*");
     
-    let alice_keypair = Keypair::generate();
-    let alice_config = ClientConfig {
-        storage_path: alice_dir.path().to_path_buf(),
-        listen_addrs: vec!["/ip4/127.0.0.1/tcp/0".to_string()],
-        bootstrap_peers: vec![],
-    };
-    let alice = Client::new(alice_keypair, alice_config)?;
-    info!("✓ Alice created: {}", alice.user_id());
+    axum::serve(listener, app).await?;
     
-    let bob_keypair = Keypair::generate();
-    let bob_config = ClientConfig {
-        storage_path: bob_dir.path().to_path_buf(),
-        listen_addrs: vec!["/ip4/127.0.0.1/tcp/0".to_string()],
-        bootstrap_peers: vec![],
-    };
-    let bob = Client::new(bob_keypair, bob_config)?;
-    info!("✓ Bob created: {}", bob.user_id());
+    Ok(())
+}
+
+/// Create `count` clients and the shared application state
+fn create_app_state(count: usize) -> anyhow::Result<AppState> {
+    info!("👥 Creating {} clients...", count);
     
-    let charlie_keypair = Keypair::generate();
-    let charlie_config = ClientConfig {
-        storage_path: charlie_dir.path().to_path_buf(),
-        listen_addrs: vec!["/ip4/127.0.0.1/tcp/0".to_string()],
-        bootstrap_peers: vec![],
-    };
-    let charlie = Client::new(charlie_keypair, charlie_config)?;
-    info!("✓ Charlie created: {}", charlie.user_id());
-
-    // Start network event processing for all clients
-    info!("🌐 Starting network event loops...");
-    let alice_clone = Arc::new(RwLock::new(alice));
-    let bob_clone = Arc::new(RwLock::new(bob));
-    let charlie_clone = Arc::new(RwLock::new(charlie));
+    let mut clients = Vec::with_capacity(count);
+    let mut temp_dirs = Vec::with_capacity(count);
     
-    tokio::spawn(process_network_events(alice_clone.clone(), "Alice"));
-    tokio::spawn(process_network_events(bob_clone.clone(), "Bob"));
-    tokio::spawn(process_network_events(charlie_clone.clone(), "Charlie"));
+    for index in 0..count {
+        let name = client_name(index);
+        
+        // Create temporary directory for client storage
+        let dir = TempDir::new()?;
+        info!("📁 {} storage: {:?}", name, dir.path());
+        
+        let config = ClientConfig {
+            storage_path: dir.path().to_path_buf(),
+            listen_addrs: vec!["/ip4/127.0.0.1/tcp/0".to_string()],
+            bootstrap_peers: vec![],
+            // Demo clients follow every Space they see announced
+            auto_subscribe_discovered: true,
+            ..Default::default()
+        };
+        let client = Client::new(Keypair::generate(), config)?;
+        info!("✓ {} created: {}", name, client.user_id());
+        
+        // Start network event processing
+        let client = Arc::new(RwLock::new(client));
+        tokio::spawn(process_network_events(client.clone(), name.clone()));
+        
+        clients.push((name, client));
+        temp_dirs.push(dir);
+    }
 
     // Create application state
-    let state = AppState {
-        alice: alice_clone,
-        bob: bob_clone,
-        charlie: charlie_clone,
-        temp_dirs: Arc::new(vec![alice_dir, bob_dir, charlie_dir]),
-    };
+    Ok(AppState {
+        clients,
+        temp_dirs: Arc::new(temp_dirs),
+    })
+}
 
-    // Build router
-    let app = Router::new()
+/// Build the HTTP/WebSocket router
+fn build_router(state: AppState) -> Router {
+    Router::new()
         .route("/ws", get(websocket_handler))
         .route("/api/action", post(action_handler))
         .route("/api/state", get(get_state))
@@ -160,23 +205,11 @@ async fn main() -> anyhow::Result<()> {
                 .allow_methods(Any)
                 .allow_headers(Any),
         )
-        .with_state(state);
-
-    let listener = tokio::net::TcpListener::bind("127.0.0.1:3030")
-        .await?;
-
-    info!("🎯 Dashboard backend listening on http://127.0.0.1:3030");
-    info!("💡 Using REAL spaceway-core clients (Alice, Bob, Charlie)");
-    info!("💡 WebSocket at ws://127.0.0.1:3030/ws");
-    info!("💡 REST API at http://127.0.0.1:3030/api/*");
-    
-    axum::serve(listener, app).await?;
-    
-    Ok(())
+        .with_state(state)
 }
 
 /// Background task to process network events for a client
-async fn process_network_events(client: Arc<RwLock<Client>>, name: &str) {
+async fn process_network_events(client: Arc<RwLock<Client>>, name: String) {
     info!("🔄 Network event loop started for {}", name);
     
     loop {
@@ -202,34 +235,46 @@ async fn handle_socket(mut socket: WebSocket, state: AppState) {
     info!("🔌 WebSocket connection established");
 
     // Send initial state
-    match get_dashboard_state(&state).await {
-        Ok(dashboard_state) => {
-            let json = serde_json::to_string(&dashboard_state).unwrap();
-            if socket.send(Message::Text(json)).await.is_err() {
-                return;
-            }
-        }
-        Err(e) => {
-            error!("Failed to get initial state: {}", e);
-            return;
-        }
+    if let Err(e) = send_state(&mut socket, &state).await {
+        error!("Failed to send initial state: {}", e);
+        return;
     }
 
-    // Stream updates every 500ms
+    // Stream updates every 500ms, and execute actions sent by the frontend
     let mut interval = tokio::time::interval(tokio::time::Duration::from_millis(500));
     loop {
-        interval.tick().await;
-
-        match get_dashboard_state(&state).await {
-            Ok(dashboard_state) => {
-                let json = serde_json::to_string(&dashboard_state).unwrap();
-                if socket.send(Message::Text(json)).await.is_err() {
+        tokio::select! {
+            _ = interval.tick() => {
+                if let Err(e) = send_state(&mut socket, &state).await {
+                    error!("Failed to send state: {}", e);
                     break;
                 }
             }
-            Err(e) => {
-                error!("Failed to get state: {}", e);
-                break;
+            incoming = socket.recv() => {
+                match incoming {
+                    Some(Ok(Message::Text(text))) => {
+                        let response = match serde_json::from_str::<ActionRequest>(&text) {
+                            Ok(request) => run_action(&state, request).await,
+                            Err(e) => ActionResponse {
+                                success: false,
+                                message: format!("Invalid action request: {}", e),
+                                data: None,
+                            },
+                        };
+
+                        // Push the result, then the updated state right away
+                        let reply = serde_json::to_string(&SocketMessage::ActionResult(response)).unwrap();
+                        if socket.send(Message::Text(reply)).await.is_err() {
+                            break;
+                        }
+                        if let Err(e) = send_state(&mut socket, &state).await {
+                            error!("Failed to send state: {}", e);
+                            break;
+                        }
+                    }
+                    Some(Ok(Message::Close(_))) | Some(Err(_)) | None => break,
+                    Some(Ok(_)) => {} // Ignore binary/ping/pong frames
+                }
             }
         }
     }
@@ -237,40 +282,51 @@ async fn handle_socket(mut socket: WebSocket, state: AppState) {
     info!("🔌 WebSocket connection closed");
 }
 
+/// Send the current dashboard state over the socket
+async fn send_state(socket: &mut WebSocket, state: &AppState) -> anyhow::Result<()> {
+    let dashboard_state = get_dashboard_state(state).await?;
+    let json = serde_json::to_string(&dashboard_state)?;
+    socket.send(Message::Text(json)).await?;
+    Ok(())
+}
+
 async fn action_handler(
     State(state): State<AppState>,
     Json(request): Json<ActionRequest>,
 ) -> Json<ActionResponse> {
+    Json(run_action(&state, request).await)
+}
+
+/// Execute an action request from REST or WebSocket
+async fn run_action(state: &AppState, request: ActionRequest) -> ActionResponse {
     info!("📝 Action request: {:?}", request);
 
     // Get the appropriate client
-    let client = match request.client.as_str() {
-        "alice" | "Alice" => &state.alice,
-        "bob" | "Bob" => &state.bob,
-        "charlie" | "Charlie" => &state.charlie,
-        _ => {
-            return Json(ActionResponse {
+    let client = match state.client(&request.client) {
+        Some(client) => client,
+        None => {
+            return ActionResponse {
                 success: false,
                 message: format!("Unknown client: {}", request.client),
                 data: None,
-            });
+            };
         }
     };
 
     // Execute the action
-    let result = execute_action(&state, client, request.action).await;
+    let result = execute_action(state, client, request.action).await;
 
     match result {
-        Ok(message) => Json(ActionResponse {
+        Ok(message) => ActionResponse {
             success: true,
             message,
             data: None,
-        }),
-        Err(e) => Json(ActionResponse {
+        },
+        Err(e) => ActionResponse {
             success: false,
             message: format!("Action failed: {}", e),
             data: None,
-        }),
+        },
     }
 }
 
@@ -279,42 +335,18 @@ async fn execute_action(state: &AppState, client: &Arc<RwLock<Client>>, action:
         Action::CreateSpace { name } => {
             let client_guard = client.read().await;
             let (space, _, _) = client_guard.create_space(name.clone(), None).await?;
-            Ok(format!("Created space '{}' with ID: {}", name, hex::encode(&space.id.0[..8])))
+            Ok(format!("Created space '{}' with ID: {}", name, space.id.short()))
         }
         Action::CreateChannel { space_id, name } => {
-            // Parse space_id from hex
-            let space_id_bytes = hex::decode(&space_id)
-                .map_err(|e| anyhow::anyhow!("Invalid space_id hex: {}", e))?;
-            
-            if space_id_bytes.len() != 32 {
-                return Err(anyhow::anyhow!("Invalid space_id length, expected 32 bytes"));
-            }
-            
-            let mut id_bytes = [0u8; 32];
-            id_bytes.copy_from_slice(&space_id_bytes);
-            let space_id = spaceway_core::SpaceId(id_bytes);
+            let space_id: spaceway_core::SpaceId = space_id.parse()?;
             
             let client_guard = client.read().await;
             let (channel, _) = client_guard.create_channel(space_id, name.clone(), None).await?;
             Ok(format!("Created channel '{}' with ID: {}", name, hex::encode(&channel.id.0)))
         }
         Action::CreateThread { space_id, channel_id, title, first_message } => {
-            let space_id_bytes = hex::decode(&space_id)
-                .map_err(|e| anyhow::anyhow!("Invalid space_id hex: {}", e))?;
-            let channel_id_bytes = hex::decode(&channel_id)
-                .map_err(|e| anyhow::anyhow!("Invalid channel_id hex: {}", e))?;
-            
-            if space_id_bytes.len() != 32 || channel_id_bytes.len() != 32 {
-                return Err(anyhow::anyhow!("Invalid ID length, expected 32 bytes"));
-            }
-            
-            let mut space_id_arr = [0u8; 32];
-            space_id_arr.copy_from_slice(&space_id_bytes);
-            let space_id = spaceway_core::SpaceId(space_id_arr);
-            
-            let mut channel_id_arr = [0u8; 32];
-            channel_id_arr.copy_from_slice(&channel_id_bytes);
-            let channel_id = spaceway_core::ChannelId(channel_id_arr);
+            let space_id: spaceway_core::SpaceId = space_id.parse()?;
+            let channel_id: spaceway_core::ChannelId = channel_id.parse()?;
             
             let client_guard = client.read().await;
             let (thread, _) = client_guard.create_thread(
@@ -331,73 +363,33 @@ async fn execute_action(state: &AppState, client: &Arc<RwLock<Client>>, action:
             ))
         }
         Action::SendMessage { space_id, thread_id, content } => {
-            let space_id_bytes = hex::decode(&space_id)
-                .map_err(|e| anyhow::anyhow!("Invalid space_id hex: {}", e))?;
-            let thread_id_bytes = hex::decode(&thread_id)
-                .map_err(|e| anyhow::anyhow!("Invalid thread_id hex: {}", e))?;
-            
-            if space_id_bytes.len() != 32 || thread_id_bytes.len() != 32 {
-                return Err(anyhow::anyhow!("Invalid ID length, expected 32 bytes"));
-            }
-            
-            let mut space_id_arr = [0u8; 32];
-            space_id_arr.copy_from_slice(&space_id_bytes);
-            let space_id = spaceway_core::SpaceId(space_id_arr);
-            
-            let mut thread_id_arr = [0u8; 32];
-            thread_id_arr.copy_from_slice(&thread_id_bytes);
-            let thread_id = spaceway_core::ThreadId(thread_id_arr);
+            let space_id: spaceway_core::SpaceId = space_id.parse()?;
+            let thread_id: spaceway_core::ThreadId = thread_id.parse()?;
             
             let client_guard = client.read().await;
             let (message, _) = client_guard.post_message(space_id, thread_id, content.clone()).await?;
             
             Ok(format!("✓ Sent message: '{}' (ID: {})", 
                 &content[..content.len().min(50)],
-                hex::encode(&message.id.0[..8])
+                message.id.short()
             ))
         }
         Action::RemoveMember { space_id, user_id } => {
-            let space_id_bytes = hex::decode(&space_id)
-                .map_err(|e| anyhow::anyhow!("Invalid space_id hex: {}", e))?;
-            let user_id_bytes = hex::decode(&user_id)
-                .map_err(|e| anyhow::anyhow!("Invalid user_id hex: {}", e))?;
-            
-            if space_id_bytes.len() != 32 {
-                return Err(anyhow::anyhow!("Invalid space_id length, expected 32 bytes"));
-            }
-            if user_id_bytes.len() != 32 {
-                return Err(anyhow::anyhow!("Invalid user_id length, expected 32 bytes"));
-            }
-            
-            let mut space_id_arr = [0u8; 32];
-            space_id_arr.copy_from_slice(&space_id_bytes);
-            let space_id = spaceway_core::SpaceId(space_id_arr);
-            
-            let mut user_id_arr = [0u8; 32];
-            user_id_arr.copy_from_slice(&user_id_bytes);
-            let target_user_id = spaceway_core::UserId(user_id_arr);
+            let space_id: spaceway_core::SpaceId = space_id.parse()?;
+            let target_user_id: spaceway_core::UserId = user_id.parse()?;
             
             let client_guard = client.read().await;
             let _ = client_guard.remove_member(space_id, target_user_id).await?;
             
             Ok(format!("✓ Removed member {} from space. They can no longer decrypt new messages!", 
-                hex::encode(&target_user_id.0[..8])
+                target_user_id.short()
             ))
         }
         Action::CreateInvite { space_id } => {
-            let space_id_bytes = hex::decode(&space_id)
-                .map_err(|e| anyhow::anyhow!("Invalid space_id hex: {}", e))?;
-            
-            if space_id_bytes.len() != 32 {
-                return Err(anyhow::anyhow!("Invalid space_id length, expected 32 bytes"));
-            }
-            
-            let mut id_bytes = [0u8; 32];
-            id_bytes.copy_from_slice(&space_id_bytes);
-            let space_id = spaceway_core::SpaceId(id_bytes);
+            let space_id: spaceway_core::SpaceId = space_id.parse()?;
             
             let client_guard = client.read().await;
-            let invite_op = client_guard.create_invite(space_id, None, None).await?;
+            let invite_op = client_guard.create_invite(space_id, None, None, None, None).await?;
             
             // Wait a bit for the invite to be processed locally
             tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
@@ -405,203 +397,57 @@ async fn execute_action(state: &AppState, client: &Arc<RwLock<Client>>, action:
             // Retrieve the invite code
             let invites = client_guard.list_invites(&space_id).await;
             if let Some(invite) = invites.last() {
-                Ok(format!("Created invite! Code: {} (Space: {})", 
+                Ok(format!("Created invite! Code: {} (Space: {}) Link: {}", 
                     invite.code, 
-                    hex::encode(&space_id.0[..8])
+                    space_id.short(),
+                    invite.to_uri()
                 ))
             } else {
                 Ok(format!("Created invite operation with ID: {}", hex::encode(&invite_op.op_id.0.as_bytes()[..8])))
             }
         }
         Action::JoinSpace { space_id, invite_code } => {
-            // Parse space_id as hex SpaceId
-            let space_id_bytes = hex::decode(&space_id)
-                .map_err(|e| anyhow::anyhow!("Invalid space_id hex: {}", e))?;
-            
-            if space_id_bytes.len() != 32 {
-                return Err(anyhow::anyhow!("Invalid space_id length, expected 32 bytes (64 hex chars)"));
-            }
-            
-            let mut id_bytes = [0u8; 32];
-            id_bytes.copy_from_slice(&space_id_bytes);
-            let space_id = spaceway_core::SpaceId(id_bytes);
-            
-            // DASHBOARD WORKAROUND: Since nodes are isolated, look for space in other clients
-            info!("🔧 Dashboard workaround: Looking for space {} in other clients", hex::encode(&space_id.0[..8]));
+            let space_id: spaceway_core::SpaceId = space_id.parse()?;
             
-            // Check if space exists in Alice, Bob, or Charlie
-            let alice_guard = state.alice.read().await;
-            let alice_space = alice_guard.get_space(&space_id).await;
-            drop(alice_guard);
+            let invite_code = invite_code
+                .ok_or_else(|| anyhow::anyhow!("An invite code is required to join a space"))?;
             
-            let bob_guard = state.bob.read().await;
-            let bob_space = bob_guard.get_space(&space_id).await;
-            drop(bob_guard);
-            
-            let charlie_guard = state.charlie.read().await;
-            let charlie_space = charlie_guard.get_space(&space_id).await;
-            drop(charlie_guard);
+            let client_guard = client.read().await;
+            let space = client_guard.join_space(space_id, &invite_code).await?;
+            let channels = client_guard.list_channels(&space_id).await;
             
-            if let Some(space) = alice_space.or(bob_space).or(charlie_space) {
-                info!("✓ Found space '{}'", space.name);
-                let space_name = space.name.clone();
-                
-                // Verify invite if provided
-                if let Some(code) = &invite_code {
-                    let alice_guard = state.alice.read().await;
-                    let invites = alice_guard.list_invites(&space_id).await;
-                    drop(alice_guard);
-                    
-                    if !invites.iter().any(|inv| inv.code == *code) {
-                        return Err(anyhow::anyhow!("Invalid or expired invite code"));
-                    }
-                    info!("✓ Invite code validated");
-                }
-                
-                // Get the joining user's ID
-                let client_guard = client.read().await;
-                let new_member_id = client_guard.user_id();
-                info!("🔍 DEBUG: Joining user ID: {}", hex::encode(&new_member_id.0));
-                drop(client_guard);
-                
-                // Try to join via DHT (will likely fail due to isolation)
-                let client_guard = client.read().await;
-                let join_result = client_guard.join_space_from_dht(space_id).await;
-                drop(client_guard);
-                
-                // Find which client owns this space (has the channels)
-                let alice_guard = state.alice.read().await;
-                let alice_channels = alice_guard.list_channels(&space_id).await;
-                info!("🔍 DEBUG: Alice has {} channels in space", alice_channels.len());
-                for ch in &alice_channels {
-                    info!("🔍 DEBUG:   - Channel '{}' (ID: {})", ch.name, hex::encode(&ch.id.0));
-                }
-                drop(alice_guard);
-                
-                // ALWAYS add user to channels, regardless of DHT join success
-                if !alice_channels.is_empty() {
-                    info!("📢 Adding new member {} to {} channels in space '{}'", 
-                        hex::encode(&new_member_id.0[..8]), alice_channels.len(), space_name);
-                    
-                    // For each channel, add the new member
-                    for channel in &alice_channels {
-                        info!("🔍 DEBUG: Attempting to add member to channel '{}'...", channel.name);
-                        
-                        // The channel creator (Alice) needs to add the new member to the channel
-                        let alice_guard = state.alice.read().await;
-                        let alice_id = alice_guard.user_id();
-                        info!("🔍 DEBUG: Alice's user ID: {}", hex::encode(&alice_id.0));
-                        
-                        match alice_guard.add_to_channel(
-                            &channel.id,
-                            new_member_id,
-                            spaceway_core::Role::Member
-                        ).await {
-                            Ok(_) => {
-                                info!("  ✅ Successfully added member to channel '{}'", channel.name);
-                                
-                                // Verify the member was actually added
-                                tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
-                                drop(alice_guard);
-                                
-                                let client_guard = client.read().await;
-                                let bob_channels = client_guard.list_channels(&space_id).await;
-                                info!("🔍 DEBUG: After add, Bob now sees {} channels", bob_channels.len());
-                                for ch in &bob_channels {
-                                    info!("🔍 DEBUG:   - Bob sees channel '{}' (ID: {})", ch.name, hex::encode(&ch.id.0));
-                                }
-                                drop(client_guard);
-                            }
-                            Err(e) => {
-                                info!("  ❌ Could not add member to channel '{}': {}", channel.name, e);
-                                drop(alice_guard);
-                            }
-                        }
-                    }
-                    
-                    // Final verification
-                    info!("🔍 DEBUG: === FINAL VERIFICATION ===");
-                    let client_guard = client.read().await;
-                    let final_channels = client_guard.list_channels(&space_id).await;
-                    info!("🔍 DEBUG: Bob's final channel count: {}", final_channels.len());
-                    for ch in &final_channels {
-                        info!("🔍 DEBUG:   - Channel '{}' visible to Bob", ch.name);
-                    }
-                    drop(client_guard);
-                }
-                
-                match join_result {
-                    Ok(space) => {
-                        info!("✓ User successfully joined space '{}' via DHT", space.name);
-                        Ok(format!("✓ Joined space '{}' and added to {} channels", space.name, alice_channels.len()))
-                    }
-                    Err(e) => {
-                        // DHT failed, but we still added to channels
-                        info!("ℹ️  DHT join failed: {}, but user added to channels", e);
-                        Ok(format!("✓ Added to space '{}' and {} channels (DHT sync pending)", space_name, alice_channels.len()))
-                    }
-                }
-            } else {
-                Err(anyhow::anyhow!("Space not found in any client. Make sure the space was created first."))
-            }
+            Ok(format!("✓ Joined space '{}' ({} channels)", space.name, channels.len()))
         }
         Action::ConnectPeers => {
             info!("🔗 Connecting all peers together...");
             
             // Get peer addresses for all clients
-            let alice_guard = state.alice.read().await;
-            let alice_addrs = alice_guard.listening_addrs().await;
-            let alice_peer_id = alice_guard.peer_id().await;
-            drop(alice_guard);
-            
-            let bob_guard = state.bob.read().await;
-            let bob_addrs = bob_guard.listening_addrs().await;
-            let bob_peer_id = bob_guard.peer_id().await;
-            drop(bob_guard);
-            
-            let charlie_guard = state.charlie.read().await;
-            let charlie_addrs = charlie_guard.listening_addrs().await;
-            let charlie_peer_id = charlie_guard.peer_id().await;
-            drop(charlie_guard);
-            
-            info!("Alice: {} at {:?}", alice_peer_id, alice_addrs);
-            info!("Bob: {} at {:?}", bob_peer_id, bob_addrs);
-            info!("Charlie: {} at {:?}", charlie_peer_id, charlie_addrs);
-            
-            // Connect Bob to Alice
-            if let Some(alice_addr) = alice_addrs.first() {
-                let full_addr = format!("{}/p2p/{}", alice_addr, alice_peer_id);
-                info!("Connecting Bob → Alice: {}", full_addr);
-                let bob_guard = state.bob.read().await;
-                match bob_guard.network_dial(&full_addr).await {
-                    Ok(_) => info!("✓ Bob connected to Alice"),
-                    Err(e) => info!("✗ Bob → Alice failed: {}", e),
-                }
-                drop(bob_guard);
-            }
-            
-            // Connect Charlie to Alice
-            if let Some(alice_addr) = alice_addrs.first() {
-                let full_addr = format!("{}/p2p/{}", alice_addr, alice_peer_id);
-                info!("Connecting Charlie → Alice: {}", full_addr);
-                let charlie_guard = state.charlie.read().await;
-                match charlie_guard.network_dial(&full_addr).await {
-                    Ok(_) => info!("✓ Charlie connected to Alice"),
-                    Err(e) => info!("✗ Charlie → Alice failed: {}", e),
-                }
-                drop(charlie_guard);
+            let mut peers = Vec::with_capacity(state.clients.len());
+            for (name, other) in &state.clients {
+                let other_guard = other.read().await;
+                let addrs = other_guard.listening_addrs().await;
+                let peer_id = other_guard.peer_id().await;
+                drop(other_guard);
+                
+                info!("{}: {} at {:?}", name, peer_id, addrs);
+                peers.push((addrs, peer_id));
             }
             
-            // Connect Charlie to Bob
-            if let Some(bob_addr) = bob_addrs.first() {
-                let full_addr = format!("{}/p2p/{}", bob_addr, bob_peer_id);
-                info!("Connecting Charlie → Bob: {}", full_addr);
-                let charlie_guard = state.charlie.read().await;
-                match charlie_guard.network_dial(&full_addr).await {
-                    Ok(_) => info!("✓ Charlie connected to Bob"),
-                    Err(e) => info!("✗ Charlie → Bob failed: {}", e),
+            // Each client dials every client created before it (full mesh)
+            for (i, (name, dialer)) in state.clients.iter().enumerate() {
+                for (j, (target_name, _)) in state.clients.iter().enumerate().take(i) {
+                    let (target_addrs, target_peer_id) = &peers[j];
+                    if let Some(target_addr) = target_addrs.first() {
+                        let full_addr = format!("{}/p2p/{}", target_addr, target_peer_id);
+                        info!("Connecting {} → {}: {}", name, target_name, full_addr);
+                        let dialer_guard = dialer.read().await;
+                        match dialer_guard.network_dial(&full_addr).await {
+                            Ok(_) => info!("✓ {} connected to {}", name, target_name),
+                            Err(e) => info!("✗ {} → {} failed: {}", name, target_name, e),
+                        }
+                        drop(dialer_guard);
+                    }
                 }
-                drop(charlie_guard);
             }
             
             // Give connections time to establish
@@ -610,83 +456,58 @@ async fn execute_action(state: &AppState, client: &Arc<RwLock<Client>>, action:
             // Publish KeyPackages to DHT now that peers are connected
             info!("🔑 Publishing KeyPackages to DHT...");
             
-            let alice_guard = state.alice.read().await;
-            match alice_guard.publish_key_packages_to_dht().await {
-                Ok(_) => info!("✓ Alice KeyPackages published"),
-                Err(e) => info!("✗ Alice KeyPackages failed: {}", e),
-            }
-            drop(alice_guard);
-            
-            let bob_guard = state.bob.read().await;
-            match bob_guard.publish_key_packages_to_dht().await {
-                Ok(_) => info!("✓ Bob KeyPackages published"),
-                Err(e) => info!("✗ Bob KeyPackages failed: {}", e),
-            }
-            drop(bob_guard);
-            
-            let charlie_guard = state.charlie.read().await;
-            match charlie_guard.publish_key_packages_to_dht().await {
-                Ok(_) => info!("✓ Charlie KeyPackages published"),
-                Err(e) => info!("✗ Charlie KeyPackages failed: {}", e),
+            for (name, other) in &state.clients {
+                let other_guard = other.read().await;
+                match other_guard.publish_key_packages_to_dht().await {
+                    Ok(_) => info!("✓ {} KeyPackages published", name),
+                    Err(e) => info!("✗ {} KeyPackages failed: {}", name, e),
+                }
+                drop(other_guard);
             }
-            drop(charlie_guard);
             
             Ok("✓ Peer connections established and KeyPackages published to DHT!".to_string())
         }
     }
 }
 
+/// Maximum number of operations shown in the CRDT timeline
+const TIMELINE_LIMIT: usize = 200;
+
 /// Get current state snapshot from all clients
 async fn get_dashboard_state(state: &AppState) -> anyhow::Result<DashboardState> {
-    let alice_guard = state.alice.read().await;
-    let alice_snapshot = alice_guard.get_dashboard_snapshot("Alice").await;
-    drop(alice_guard);
+    let mut snapshots = Vec::with_capacity(state.clients.len());
+    let mut timelines = Vec::with_capacity(state.clients.len());
     
-    let bob_guard = state.bob.read().await;
-    let bob_snapshot = bob_guard.get_dashboard_snapshot("Bob").await;
-    drop(bob_guard);
-    
-    let charlie_guard = state.charlie.read().await;
-    let charlie_snapshot = charlie_guard.get_dashboard_snapshot("Charlie").await;
-    drop(charlie_guard);
+    for (name, client) in &state.clients {
+        let client_guard = client.read().await;
+        snapshots.push(client_guard.get_dashboard_snapshot(name).await);
+        timelines.push(client_guard.recent_ops(TIMELINE_LIMIT)?);
+        drop(client_guard);
+    }
     
     // Build network graph
     let mut network_graph = spaceway_core::dashboard::NetworkGraph::new();
     
     // Add client nodes
-    network_graph.add_client_node(&alice_snapshot.user_id, "Alice");
-    network_graph.add_client_node(&bob_snapshot.user_id, "Bob");
-    network_graph.add_client_node(&charlie_snapshot.user_id, "Charlie");
-    
-    // Add edges based on shared spaces
-    for alice_space in &alice_snapshot.spaces {
-        for bob_space in &bob_snapshot.spaces {
-            if alice_space.id == bob_space.id {
-                network_graph.add_gossipsub_edge(&alice_snapshot.user_id, &bob_snapshot.user_id);
-                break;
-            }
-        }
-        for charlie_space in &charlie_snapshot.spaces {
-            if alice_space.id == charlie_space.id {
-                network_graph.add_gossipsub_edge(&alice_snapshot.user_id, &charlie_snapshot.user_id);
-                break;
-            }
-        }
+    for snapshot in &snapshots {
+        network_graph.add_client_node(&snapshot.user_id, &snapshot.name);
     }
     
-    for bob_space in &bob_snapshot.spaces {
-        for charlie_space in &charlie_snapshot.spaces {
-            if bob_space.id == charlie_space.id {
-                network_graph.add_gossipsub_edge(&bob_snapshot.user_id, &charlie_snapshot.user_id);
-                break;
+    // Add an edge between every pair of clients that share a space
+    for (i, a) in snapshots.iter().enumerate() {
+        for b in &snapshots[i + 1..] {
+            let shares_space = a.spaces.iter()
+                .any(|a_space| b.spaces.iter().any(|b_space| a_space.id == b_space.id));
+            if shares_space {
+                network_graph.add_gossipsub_edge(&a.user_id, &b.user_id);
             }
         }
     }
     
     Ok(DashboardState {
-        clients: vec![alice_snapshot, bob_snapshot, charlie_snapshot],
+        clients: snapshots,
         network_graph,
-        crdt_timeline: vec![], // TODO: Collect CRDT operations
+        crdt_timeline: spaceway_core::dashboard::merge_timelines(timelines, TIMELINE_LIMIT),
     })
 }
 
@@ -769,3 +590,97 @@ async fn build_handler(Json(req): Json<BuildRequest>) -> Json<BuildResponse> {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures_util::{SinkExt, StreamExt};
+    use tokio_tungstenite::tungstenite::Message as WsMessage;
+
+    #[tokio::test]
+    async fn test_create_space_over_websocket() {
+        let state = create_app_state(3).unwrap();
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            axum::serve(listener, build_router(state)).await.unwrap();
+        });
+
+        let (mut ws, _) = tokio_tungstenite::connect_async(format!("ws://{}/ws", addr))
+            .await
+            .unwrap();
+
+        let request = serde_json::json!({
+            "client": "alice",
+            "action": { "type": "CreateSpace", "name": "WebSocket Space" }
+        });
+        ws.send(WsMessage::Text(request.to_string())).await.unwrap();
+
+        // Skip periodic state updates until the action result arrives
+        let mut got_result = false;
+        let deadline = tokio::time::Instant::now() + tokio::time::Duration::from_secs(10);
+        while tokio::time::Instant::now() < deadline {
+            let msg = ws.next().await.unwrap().unwrap();
+            let json: serde_json::Value = serde_json::from_str(msg.to_text().unwrap()).unwrap();
+
+            if json["type"] == "action_result" {
+                assert_eq!(json["success"], true, "action failed: {}", json["message"]);
+                got_result = true;
+                continue;
+            }
+
+            // First state update after the result must include the new space
+            if got_result {
+                let state: DashboardState = serde_json::from_value(json).unwrap();
+                let alice = state.clients.iter().find(|c| c.name == "Alice").unwrap();
+                assert!(alice.spaces.iter().any(|space| space.name == "WebSocket Space"));
+                return;
+            }
+        }
+        panic!("No action result received over the WebSocket");
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_dashboard_state_with_five_clients() {
+        let state = create_app_state(5).unwrap();
+        assert!(state.client("dave").is_some());
+        assert!(state.client("Frank").is_none());
+
+        // Eve syncs a Lightweight space straight from Alice; nobody else joins
+        let alice = state.client("Alice").unwrap().read().await;
+        let eve = state.client("Eve").unwrap().read().await;
+        alice.start().await.unwrap();
+        eve.start().await.unwrap();
+        let (space, _, _) = alice.create_space_with_mode(
+            "Mesh".to_string(),
+            None,
+            spaceway_core::SpaceVisibility::default(),
+            spaceway_core::SpaceMembershipMode::Lightweight,
+        ).await.unwrap();
+        let alice_addr = alice.listening_addrs().await
+            .into_iter()
+            .find(|addr| addr.to_string().contains("127.0.0.1"))
+            .expect("Alice should listen on loopback");
+        let alice_addr = format!("{}/p2p/{}", alice_addr, alice.peer_id().await);
+        eve.connect_and_sync(&alice_addr, &space.id, std::time::Duration::from_secs(20)).await.unwrap();
+        drop((alice, eve));
+
+        let dashboard = get_dashboard_state(&state).await.unwrap();
+        let names: Vec<&str> = dashboard.clients.iter().map(|c| c.name.as_str()).collect();
+        assert_eq!(names, vec!["Alice", "Bob", "Charlie", "Dave", "Eve"]);
+        assert_eq!(dashboard.network_graph.nodes.len(), 5);
+
+        let user_id = |name: &str| dashboard.clients.iter().find(|c| c.name == name).unwrap().user_id.clone();
+        let edges: Vec<(String, String, String)> = dashboard.network_graph.edges.iter()
+            .map(|edge| (edge.from.clone(), edge.to.clone(), edge.edge_type.clone()))
+            .collect();
+        assert_eq!(edges, vec![(user_id("Alice"), user_id("Eve"), "gossipsub".to_string())]);
+    }
+
+    #[test]
+    fn test_client_names_extend_past_defaults() {
+        assert_eq!(client_name(0), "Alice");
+        assert_eq!(client_name(2), "Charlie");
+        assert_eq!(client_name(10), "Client11");
+    }
+}
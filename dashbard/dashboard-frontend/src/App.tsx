@@ -119,6 +119,11 @@ function App() {
 
     ws.onmessage = (event) => {
       const data = JSON.parse(event.data);
+      // Action results are tagged; everything else is a state update
+      if (data.type === "action_result") {
+        console.log(data.success ? "✅" : "❌", data.message);
+        return;
+      }
       setState(data);
     };
 